@@ -49,6 +49,18 @@ price_model_core( uint64_t  cnt,
 
   int64_t * sort_quote = int64_sort_ascending_stable( quote, cnt, scratch );
 
+  price_model_percentiles( cnt, sort_quote, _p25, _p50, _p75 );
+
+  return sort_quote;
+}
+
+void
+price_model_percentiles( uint64_t        cnt,
+                         int64_t const * sort_quote,
+                         int64_t *       _p25,
+                         int64_t *       _p50,
+                         int64_t *       _p75 ) {
+
   /* Extract the p25
 
      There are many variants with subtle tradeoffs here.  One option is
@@ -107,6 +119,4 @@ price_model_core( uint64_t  cnt,
   uint64_t p75_idx = cnt - ((uint64_t)1) - p25_idx;
 
   *_p75 = sort_quote[p75_idx];
-
-  return sort_quote;
 }
@@ -94,6 +94,20 @@ price_model( uint64_t  cnt,
   return quote;
 }
 
+/* price_model_percentiles computes the loss model minimizing values of
+   price_model_core for quotes that are already sorted.  Assumes valid
+   inputs (cnt is at least 1, sort_quote[i] for i in [0,cnt) are the
+   quotes of interest in ascending order, p25, p50, p75 point to where
+   to write model outputs).  This is O(1) and lets callers that keep
+   their quotes sorted skip the sort. */
+
+void
+price_model_percentiles( uint64_t        cnt,          /* Assumes price_model_cnt_valid( cnt ) is true */
+                         int64_t const * sort_quote,   /* Assumes sort_quote[i] for i in [0,cnt) is sorted ascending */
+                         int64_t *       _p25,         /* Assumes *_p25 is safe to write to the p25 model output */
+                         int64_t *       _p50,         /* Assumes *_p50 " */
+                         int64_t *       _p75 );       /* Assumes *_p75 " */
+
 #ifdef __cplusplus
 }
 #endif
@@ -51,6 +51,12 @@ int test_price_model() {
     if( val[0]!=quote[ p25_idx ] ) { printf( "FAIL (p25)\n" ); return 1; }
     if( val[1]!=avg_2_int64( quote[ p50_idx-is_even ], quote[ p50_idx ] ) ) { printf( "FAIL (p50)\n" ); return 1; }
     if( val[2]!=quote[ p75_idx ] ) { printf( "FAIL (p75)\n" ); return 1; }
+
+    /* Already sorted quotes give the same results without the sort */
+
+    int64_t sorted_val[3];
+    price_model_percentiles( cnt, quote0, sorted_val+0, sorted_val+1, sorted_val+2 );
+    if( memcmp( sorted_val, val, sizeof(val) ) ) { printf( "FAIL (percentiles)\n" ); return 1; }
   }
 
 # undef N
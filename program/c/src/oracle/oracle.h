@@ -16,6 +16,8 @@ extern "C" {
 
 // max latency in slots between send and receive
 #define PC_MAX_SEND_LATENCY  25
+// quotes of NAV feeds stay valid for about a day
+#define PC_MAX_SEND_LATENCY_NAV  216000
 
 // various size constants
 #define PC_PUBKEY_SIZE       32
@@ -40,6 +42,9 @@ extern "C" {
 // price types
 #define PC_PTYPE_UNKNOWN      0
 #define PC_PTYPE_PRICE        1
+#define PC_PTYPE_FUNDING      2
+#define PC_PTYPE_NAV          3
+#define PC_PTYPE_INDEX        4
 
 // symbol status
 #define PC_STATUS_UNKNOWN     0
@@ -54,6 +59,19 @@ extern "C" {
 #define PC_ACCTYPE_PRICE      3
 #define PC_ACCTYPE_TEST       4
 #define PC_ACCTYPE_PERMISSIONS       5
+#define PC_ACCTYPE_SNAPSHOT   6
+#define PC_ACCTYPE_FEED_REGISTRY   7
+#define PC_ACCTYPE_PUBLISHER_REGISTRY   8
+#define PC_ACCTYPE_MULTISIG   9
+#define PC_ACCTYPE_PUBLISHER_LINKAGE   10
+#define PC_ACCTYPE_FEATURE_GATE   11
+#define PC_ACCTYPE_ACCESS_CONTROL   12
+#define PC_ACCTYPE_AUDIT_LOG   13
+#define PC_ACCTYPE_FEE_VAULT   14
+#define PC_ACCTYPE_REWARDS_LEDGER   15
+#define PC_ACCTYPE_CONSISTENCY_CHECK   16
+#define PC_ACCTYPE_MAPPING_V2   17
+#define PC_ACCTYPE_AGGREGATION_CONFIG   18
 
 
 // Compute budget requested per price update instruction
@@ -148,7 +166,7 @@ typedef struct pc_price_info
   int64_t         price_;            // price per ptype_
   uint64_t        conf_;             // price confidence interval
   uint32_t        status_;           // symbol status as of last update
-  uint32_t        corp_act_status_;  // corp action status as of last update
+  uint32_t        corp_act_status_;  // venue of a publisher quote, see cmd_upd_price_t
   uint64_t        pub_slot_;         // publish slot of price
 } pc_price_info_t;
 
@@ -424,13 +442,15 @@ typedef struct cmd_upd_price
   uint32_t     ver_;
   int32_t      cmd_;
   uint32_t     status_;
-  uint32_t     unused_;
+  uint8_t      venue_;
+  uint8_t      unused_[3];
   int64_t      price_;
   uint64_t     conf_;
   uint64_t     pub_slot_;
+  uint64_t     sequence_;
 } cmd_upd_price_t;
 
-static_assert( sizeof( cmd_upd_price_t ) == 40, "" );
+static_assert( sizeof( cmd_upd_price_t ) == 48, "" );
 
 // structure of clock sysvar account
 typedef struct sysvar_clock
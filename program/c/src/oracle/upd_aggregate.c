@@ -11,6 +11,10 @@ extern bool c_upd_aggregate_pythnet( pc_price_t *ptr, uint64_t slot, int64_t tim
   return upd_aggregate(ptr, slot, timestamp );
 }
 
+extern bool c_upd_aggregate_sorted( pc_price_t *ptr, uint64_t slot, int64_t timestamp, uint8_t const *order, uint32_t order_len ){
+  return upd_aggregate_sorted(ptr, slot, timestamp, order, order_len );
+}
+
 extern void c_upd_twap( pc_price_t *ptr, int64_t nslots ){
   upd_twap(ptr, nslots);
 }
@@ -131,8 +131,81 @@ static inline void upd_twap(
   upd_ema( &ptr->twac_, conf, conf, nslots, qs, ptr->expo_ );
 }
 
-// update aggregate price
-static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timestamp )
+// price of the valid component i used by the model, list 0 is price - conf, 1 is price and
+// 2 is price + conf
+static inline int64_t ordered_quote( pc_price_t const *ptr, uint32_t list, uint8_t i )
+{
+  int64_t price = ptr->comp_[i].agg_.price_;
+  int64_t conf  = ( int64_t )( ptr->comp_[i].agg_.conf_ );
+  return list == 0 ? price - conf : ( list == 1 ? price : price + conf );
+}
+
+// merge the prices of the numv valid components into sorted in ascending order, in O(num_)
+// instead of sorting them. order holds three lists of PC_NUM_COMP component indexes, the first
+// len of which are components sorted by price - conf, price and price + conf. returns false if
+// a list misses a valid component, holds one twice or isn't sorted, e.g. because it is stale,
+// in which case sorted must not be used
+static bool merge_ordered_quotes(
+    pc_price_t const *ptr, uint8_t const *order, uint32_t len, uint8_t const *valid,
+    uint32_t numv, int64_t *sorted )
+{
+  uint32_t num = ptr->num_;
+  if ( len > num ) {
+    return false;
+  }
+
+  // every list must hold distinct components
+  uint8_t seen[ PC_NUM_COMP ];
+  for ( uint32_t i = 0; i != num; ++i ) {
+    seen[ i ] = 0;
+  }
+  for ( uint32_t list = 0; list != 3; ++list ) {
+    for ( uint32_t j = 0; j != len; ++j ) {
+      uint8_t i = order[ list * PC_NUM_COMP + j ];
+      if ( i >= num || ( seen[ i ] & ( 1 << list ) ) ) {
+        return false;
+      }
+      seen[ i ] |= ( uint8_t )( 1 << list );
+    }
+  }
+
+  // 3-way merge of the valid components of the lists, checking that each list is sorted
+  uint32_t pos[ 3 ]  = { 0, 0, 0 };
+  int64_t  last[ 3 ] = { INT64_MIN, INT64_MIN, INT64_MIN };
+  uint32_t nsorted   = 0;
+  for ( ;; ) {
+    uint32_t best_list = 3;
+    int64_t  best      = 0;
+    for ( uint32_t list = 0; list != 3; ++list ) {
+      uint8_t const *lptr = &order[ list * PC_NUM_COMP ];
+      while ( pos[ list ] != len && !valid[ lptr[ pos[ list ] ] ] ) {
+        ++pos[ list ];
+      }
+      if ( pos[ list ] != len ) {
+        int64_t quote = ordered_quote( ptr, list, lptr[ pos[ list ] ] );
+        if ( best_list == 3 || quote < best ) {
+          best_list = list;
+          best      = quote;
+        }
+      }
+    }
+    if ( best_list == 3 ) {
+      // the lists hold distinct components, so every list holds every valid component
+      return nsorted == 3 * numv;
+    }
+    if ( best < last[ best_list ] ) {
+      return false;
+    }
+    last[ best_list ] = best;
+    sorted[ nsorted++ ] = best;
+    ++pos[ best_list ];
+  }
+}
+
+// update aggregate price. order is null or a hint for merge_ordered_quotes: the quotes are
+// sorted again if it can't be used, so both paths compute the same aggregate
+static inline bool upd_aggregate_core(
+    pc_price_t *ptr, uint64_t slot, int64_t timestamp, uint8_t const *order, uint32_t order_len )
 {
   // Update the value of the previous price, if it had TRADING status.
   if ( ptr->agg_.status_ == PC_STATUS_TRADING ) {
@@ -155,6 +228,7 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
     uint32_t numv  = 0;
     uint32_t nprcs = (uint32_t)0;
     int64_t  prcs[ PC_NUM_COMP * 3 ]; // ~0.75KiB for current PC_NUM_COMP (FIXME: DOUBLE CHECK THIS FITS INTO STACK FRAME LIMIT)
+    uint8_t  valid[ PC_NUM_COMP ];
     bool allow_zero_ci = (ptr->flags & 0x4) != 0;
 
     for ( uint32_t i = 0; i != ptr->num_; ++i ) {
@@ -165,7 +239,9 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
       int64_t slot_diff = ( int64_t )slot - ( int64_t )( iptr->agg_.pub_slot_ );
       int64_t price     = iptr->agg_.price_;
       int64_t conf      = ( int64_t )( iptr->agg_.conf_ );
-      int64_t max_latency = ptr->max_latency_ ? ptr->max_latency_ : PC_MAX_SEND_LATENCY;
+      int64_t max_latency = ptr->ptype_ == PC_PTYPE_NAV ? PC_MAX_SEND_LATENCY_NAV
+        : ptr->max_latency_ ? ptr->max_latency_ : PC_MAX_SEND_LATENCY;
+      valid[ i ] = 0;
       if ( iptr->agg_.status_ == PC_STATUS_TRADING &&
            // Only accept confidence of zero if the flag is set
            (allow_zero_ci || conf > 0) &&
@@ -174,6 +250,7 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
            // slot_diff is implicitly >= 0 due to the check in Rust code ensuring publishing_slot is always less than or equal to the current slot.
            slot_diff <= max_latency ) {
         numv += 1;
+        valid[ i ] = 1;
         prcs[ nprcs++ ] = price - conf;
         prcs[ nprcs++ ] = price;
         prcs[ nprcs++ ] = price + conf;
@@ -192,7 +269,11 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
     int64_t agg_p25;
     int64_t agg_p75;
     int64_t scratch[ PC_NUM_COMP * 3 ]; // ~0.75KiB for current PC_NUM_COMP (FIXME: DOUBLE CHECK THIS FITS INTO STACK FRAME LIMIT)
-    price_model_core( (uint64_t)nprcs, prcs, &agg_p25, &agg_price, &agg_p75, scratch );
+    if ( order && merge_ordered_quotes( ptr, order, order_len, valid, numv, scratch ) ) {
+      price_model_percentiles( (uint64_t)nprcs, scratch, &agg_p25, &agg_price, &agg_p75 );
+    } else {
+      price_model_core( (uint64_t)nprcs, prcs, &agg_p25, &agg_price, &agg_p75, scratch );
+    }
 
     // get the left and right confidences
     // note that as valid quotes have positive prices currently and
@@ -221,6 +302,19 @@ static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timest
   return true;
 }
 
+// update aggregate price, sorting the valid quotes
+static inline bool upd_aggregate( pc_price_t *ptr, uint64_t slot, int64_t timestamp )
+{
+  return upd_aggregate_core( ptr, slot, timestamp, 0, 0 );
+}
+
+// update aggregate price, merging the valid quotes along order (see merge_ordered_quotes)
+static inline bool upd_aggregate_sorted(
+    pc_price_t *ptr, uint64_t slot, int64_t timestamp, uint8_t const *order, uint32_t order_len )
+{
+  return upd_aggregate_core( ptr, slot, timestamp, order, order_len );
+}
+
 #ifdef __cplusplus
 }
 #endif
@@ -0,0 +1,428 @@
+//! End-to-end tests of the oracle program, see the `tests` directory.
+//!
+//! The tests of the program crate mostly call `process_instruction` directly. These ones deploy
+//! the compiled program (`target/deploy/pyth_oracle.so`, built by `scripts/build-bpf.sh`) in
+//! `solana-program-test` and send it real transactions, so that the runtime checks signatures,
+//! rent and sysvars the way a cluster would. Account states are read back with
+//! `pyth-oracle-interface`, the way consumers read them.
+
+use {
+    bytemuck::{
+        bytes_of,
+        Pod,
+    },
+    pyth_oracle::{
+        instruction::{
+            AddPriceArgs,
+            AddPublisherArgs,
+            CommandHeader,
+            OracleCommand,
+            SetMinPubArgs,
+            UpdPermissionsArgs,
+        },
+        OracleError,
+        PERMISSIONS_SEED,
+    },
+    pyth_oracle_interface::{
+        load_checked,
+        MappingAccount,
+        PriceAccount,
+        PythAccount,
+        PC_PROD_ACC_SIZE,
+    },
+    solana_program_test::{
+        read_file,
+        BanksClientError,
+        ProgramTest,
+        ProgramTestBanksClientExt,
+        ProgramTestContext,
+        ProgramTestError,
+    },
+    solana_sdk::{
+        account::Account,
+        bpf_loader_upgradeable::{
+            self,
+            UpgradeableLoaderState,
+        },
+        clock::Clock,
+        hash::Hash,
+        instruction::{
+            AccountMeta,
+            Instruction,
+            InstructionError,
+        },
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        system_instruction,
+        system_program,
+        transaction::{
+            Transaction,
+            TransactionError,
+        },
+    },
+    std::{
+        mem::size_of,
+        path::PathBuf,
+    },
+};
+
+/// Price type of the price accounts, `PC_PTYPE_PRICE` of the program
+pub const PC_PTYPE_PRICE: u32 = 1;
+
+/// The oracle program deployed as upgradeable in a fresh bank, with `authority` holding every
+/// permission.
+pub struct OracleTest {
+    pub program_id:        Pubkey,
+    pub programdata_id:    Pubkey,
+    pub upgrade_authority: Keypair,
+    /// Master, data curation and security authority. It doesn't pay for the transactions, so
+    /// that an instruction missing its signature is really unsigned.
+    pub authority:         Keypair,
+    context:               ProgramTestContext,
+    /// Hash of the last transaction, advanced for every transaction so that the same
+    /// instruction can be sent again in another state
+    last_blockhash:        Hash,
+}
+
+impl OracleTest {
+    pub async fn start() -> OracleTest {
+        let target_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target");
+        let mut program_data = read_file(PathBuf::from(target_dir).join("deploy/pyth_oracle.so"));
+
+        let program_id = Pubkey::new_unique();
+        let (programdata_id, _) =
+            Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id());
+        let upgrade_authority = Keypair::new();
+
+        let program = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address: programdata_id,
+        })
+        .unwrap();
+        let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot:                      1,
+            upgrade_authority_address: Some(upgrade_authority.pubkey()),
+        })
+        .unwrap();
+        programdata.append(&mut program_data);
+
+        let mut program_test = ProgramTest::default();
+        program_test.add_account(
+            program_id,
+            Account {
+                lamports:   Rent::default().minimum_balance(program.len()),
+                data:       program,
+                owner:      bpf_loader_upgradeable::id(),
+                executable: true,
+                rent_epoch: 0,
+            },
+        );
+        program_test.add_account(
+            programdata_id,
+            Account {
+                lamports:   Rent::default().minimum_balance(programdata.len()),
+                data:       programdata,
+                owner:      bpf_loader_upgradeable::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        );
+
+        let context = program_test.start_with_context().await;
+        let last_blockhash = context.last_blockhash;
+        let mut test = OracleTest {
+            program_id,
+            programdata_id,
+            upgrade_authority,
+            authority: Keypair::new(),
+            context,
+            last_blockhash,
+        };
+
+        let upgrade_authority = test.upgrade_authority.pubkey();
+        test.airdrop(&upgrade_authority, 10 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+        let authority = test.authority.pubkey();
+        test.airdrop(&authority, 100 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+        let instruction = test.upd_permissions(authority, authority, authority);
+        let upgrade_authority = copy_keypair(&test.upgrade_authority);
+        test.process(&[instruction], &[&upgrade_authority])
+            .await
+            .unwrap();
+
+        test
+    }
+
+    /// Instruction of the oracle program with `data` as its instruction data.
+    pub fn instruction<T: Pod>(&self, data: &T, accounts: Vec<AccountMeta>) -> Instruction {
+        Instruction::new_with_bytes(self.program_id, bytes_of(data), accounts)
+    }
+
+    /// `UpdPermissions` signed by the upgrade authority.
+    pub fn upd_permissions(
+        &self,
+        master_authority: Pubkey,
+        data_curation_authority: Pubkey,
+        security_authority: Pubkey,
+    ) -> Instruction {
+        let cmd = UpdPermissionsArgs {
+            header: OracleCommand::UpdPermissions.into(),
+            master_authority,
+            data_curation_authority,
+            security_authority,
+        };
+        self.instruction(
+            &cmd,
+            vec![
+                AccountMeta::new(self.upgrade_authority.pubkey(), true),
+                AccountMeta::new_readonly(self.programdata_id, false),
+                AccountMeta::new(self.permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(Clock::id(), false),
+            ],
+        )
+    }
+
+    pub fn permissions_pubkey(&self) -> Pubkey {
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.program_id).0
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.context.payer.pubkey()
+    }
+
+    /// Send a transaction with `instructions`, paid by the payer of the test. The keypairs of
+    /// `signers` only sign if an instruction requires their signature, so that a test can strip
+    /// a signature from the account metas and keep the same signers.
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let payer = copy_keypair(&self.context.payer);
+        let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+        let signer_keys: Vec<Pubkey> = transaction
+            .message
+            .signer_keys()
+            .into_iter()
+            .copied()
+            .collect();
+        let signers: Vec<&Keypair> = signers
+            .iter()
+            .copied()
+            .filter(|signer| signer_keys.contains(&signer.pubkey()))
+            .collect();
+
+        self.last_blockhash = self
+            .context
+            .banks_client
+            .get_new_latest_blockhash(&self.last_blockhash)
+            .await
+            .unwrap();
+        transaction.partial_sign(&[&payer], self.last_blockhash);
+        transaction.partial_sign(&signers, self.last_blockhash);
+        self.context
+            .banks_client
+            .process_transaction(transaction)
+            .await
+    }
+
+    /// Send `instruction` without the signature of each of `required_signers` in turn, checking
+    /// that the program rejects it and leaves its writable accounts unchanged, then with every
+    /// signature.
+    pub async fn process_requiring_signatures(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        required_signers: &[Pubkey],
+    ) -> Result<(), BanksClientError> {
+        let writable: Vec<Pubkey> = instruction
+            .accounts
+            .iter()
+            .filter(|meta| meta.is_writable)
+            .map(|meta| meta.pubkey)
+            .collect();
+        for key in required_signers {
+            let before = self.accounts(&writable).await;
+            let unsigned = without_signer(&instruction, key);
+            assert!(
+                self.process(&[unsigned], signers).await.is_err(),
+                "accepted without the signature of {key}"
+            );
+            assert_eq!(
+                self.accounts(&writable).await,
+                before,
+                "changed accounts without the signature of {key}"
+            );
+        }
+        self.process(&[instruction], signers).await
+    }
+
+    /// Data, lamports and owner of the accounts at `keys`, `None` for the ones that don't exist.
+    async fn accounts(&mut self, keys: &[Pubkey]) -> Vec<Option<(Vec<u8>, u64, Pubkey)>> {
+        let mut accounts = vec![];
+        for key in keys {
+            accounts.push(
+                self.account(*key)
+                    .await
+                    .map(|account| (account.data, account.lamports, account.owner)),
+            );
+        }
+        accounts
+    }
+
+    /// Create a mapping account with a product and a price account of `exponent`, returning the
+    /// price account. The feed aggregates from a single quote.
+    pub async fn add_feed(&mut self, exponent: i32) -> Keypair {
+        let authority = copy_keypair(&self.authority);
+        let permissions = self.permissions_pubkey();
+        let mapping = self.create_account(size_of::<MappingAccount>()).await;
+        let product = self.create_account(PC_PROD_ACC_SIZE as usize).await;
+        let price = self.create_account(size_of::<PriceAccount>()).await;
+
+        let init_mapping: CommandHeader = OracleCommand::InitMapping.into();
+        let add_product: CommandHeader = OracleCommand::AddProduct.into();
+        let add_price = AddPriceArgs {
+            header: OracleCommand::AddPrice.into(),
+            exponent,
+            price_type: PC_PTYPE_PRICE,
+        };
+        let set_min_pub = SetMinPubArgs {
+            header:             OracleCommand::SetMinPub.into(),
+            minimum_publishers: 1,
+            unused_:            [0; 3],
+        };
+        let instructions = [
+            self.instruction(
+                &init_mapping,
+                vec![
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new(mapping.pubkey(), true),
+                    AccountMeta::new_readonly(permissions, false),
+                ],
+            ),
+            self.instruction(
+                &add_product,
+                vec![
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new(mapping.pubkey(), true),
+                    AccountMeta::new(product.pubkey(), true),
+                    AccountMeta::new_readonly(permissions, false),
+                ],
+            ),
+            self.instruction(
+                &add_price,
+                vec![
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new(product.pubkey(), true),
+                    AccountMeta::new(price.pubkey(), true),
+                    AccountMeta::new(permissions, false),
+                ],
+            ),
+            self.instruction(
+                &set_min_pub,
+                vec![
+                    AccountMeta::new(authority.pubkey(), true),
+                    AccountMeta::new(price.pubkey(), true),
+                    AccountMeta::new_readonly(permissions, false),
+                ],
+            ),
+        ];
+        self.process(&instructions, &[&authority, &mapping, &product, &price])
+            .await
+            .unwrap();
+        price
+    }
+
+    pub async fn add_publisher(&mut self, price: &Keypair, publisher: Pubkey) {
+        let authority = copy_keypair(&self.authority);
+        let cmd = AddPublisherArgs {
+            header: OracleCommand::AddPublisher.into(),
+            publisher,
+        };
+        let instruction = self.instruction(
+            &cmd,
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(price.pubkey(), true),
+                AccountMeta::new_readonly(self.permissions_pubkey(), false),
+            ],
+        );
+        self.process(&[instruction], &[&authority, price])
+            .await
+            .unwrap();
+    }
+
+    /// Create an account of `size` bytes owned by the oracle program, rent exempt.
+    pub async fn create_account(&mut self, size: usize) -> Keypair {
+        let keypair = Keypair::new();
+        let instruction = system_instruction::create_account(
+            &self.payer(),
+            &keypair.pubkey(),
+            Rent::default().minimum_balance(size),
+            size as u64,
+            &self.program_id,
+        );
+        self.process(&[instruction], &[&keypair]).await.unwrap();
+        keypair
+    }
+
+    pub async fn airdrop(&mut self, to: &Pubkey, lamports: u64) -> Result<(), BanksClientError> {
+        let instruction = system_instruction::transfer(&self.payer(), to, lamports);
+        self.process(&[instruction], &[]).await
+    }
+
+    pub async fn account(&mut self, key: Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(key).await.unwrap()
+    }
+
+    pub async fn account_data(&mut self, key: Pubkey) -> Vec<u8> {
+        self.account(key).await.unwrap().data
+    }
+
+    /// Copy of the account at `key` as read by consumers, checking its header. Panics if the
+    /// account doesn't exist or isn't a `T`.
+    pub async fn load<T: PythAccount>(&mut self, key: Pubkey) -> T {
+        let data = self.account_data(key).await;
+        *load_checked::<T>(&data).unwrap()
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context.banks_client.get_sysvar().await.unwrap()
+    }
+
+    pub async fn rent(&mut self) -> Rent {
+        self.context.banks_client.get_rent().await.unwrap()
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) -> Result<(), ProgramTestError> {
+        self.context.warp_to_slot(slot)
+    }
+}
+
+/// Copy of `instruction` in which `key` isn't a signer.
+pub fn without_signer(instruction: &Instruction, key: &Pubkey) -> Instruction {
+    let mut instruction = instruction.clone();
+    for meta in instruction.accounts.iter_mut() {
+        if meta.pubkey == *key {
+            meta.is_signer = false;
+        }
+    }
+    instruction
+}
+
+/// Error of a transaction whose `index`-th instruction failed with `error`.
+pub fn oracle_error(index: u8, error: OracleError) -> TransactionError {
+    TransactionError::InstructionError(index, InstructionError::Custom(error as u32))
+}
+
+pub fn copy_keypair(keypair: &Keypair) -> Keypair {
+    Keypair::from_bytes(&keypair.to_bytes()).unwrap()
+}
@@ -0,0 +1,257 @@
+use {
+    bytemuck::Pod,
+    pyth_oracle::{
+        data_len_with_extension,
+        get_extension,
+        instruction::{
+            OracleCommand,
+            SetAggregationCountWindowArgs,
+            SetAggregationDelayArgs,
+            SetConfFloorArgs,
+            SetMaxLatencyArgs,
+            SetPriceExtremaWindowArgs,
+            SetQuoteOrderArgs,
+            SetRateLimitArgs,
+            SetStatusReasonTrackingArgs,
+        },
+        AggregationCounts,
+        AggregationDelay,
+        ConfFloor,
+        PriceAccountExtension,
+        PriceExtrema,
+        PublisherRateLimit,
+        QuoteOrder,
+        StatusReason,
+        AGGREGATION_COUNT_BUCKETS,
+        PRICE_EXTREMA_BUCKETS,
+    },
+    pyth_oracle_integration_tests::{
+        copy_keypair,
+        OracleTest,
+    },
+    pyth_oracle_interface::PriceAccount,
+    solana_sdk::{
+        instruction::{
+            AccountMeta,
+            InstructionError,
+        },
+        signature::{
+            Keypair,
+            Signer,
+        },
+        system_instruction,
+        transaction::TransactionError,
+    },
+};
+
+/// Fund the growth of the price account for the extension `E`, then send `cmd` to it from the
+/// authority, which must sign.
+async fn configure<E: PriceAccountExtension, T: Pod>(
+    test: &mut OracleTest,
+    price: &Keypair,
+    cmd: &T,
+) {
+    let authority = copy_keypair(&test.authority);
+    let account = test.account(price.pubkey()).await.unwrap();
+    let new_size = data_len_with_extension::<E>(&account.data).unwrap();
+    let missing_lamports = test
+        .rent()
+        .await
+        .minimum_balance(new_size)
+        .saturating_sub(account.lamports);
+    let transfer = system_instruction::transfer(&test.payer(), &price.pubkey(), missing_lamports);
+    test.process(&[transfer], &[]).await.unwrap();
+
+    let instruction = test.instruction(
+        cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(test.permissions_pubkey(), false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, price], &[authority.pubkey()])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_configure_feed() {
+    let mut test = OracleTest::start().await;
+    let authority = copy_keypair(&test.authority);
+    let price = test.add_feed(-5).await;
+
+    let cmd = SetMaxLatencyArgs {
+        header:      OracleCommand::SetMaxLatency.into(),
+        max_latency: 10,
+        unused_:     [0; 3],
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(test.permissions_pubkey(), false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, &price], &[authority.pubkey()])
+        .await
+        .unwrap();
+    assert_eq!(
+        test.load::<PriceAccount>(price.pubkey()).await.max_latency_,
+        10
+    );
+
+    configure::<PublisherRateLimit, _>(
+        &mut test,
+        &price,
+        &SetRateLimitArgs {
+            header: OracleCommand::SetRateLimit.into(),
+            window: 3,
+        },
+    )
+    .await;
+    configure::<AggregationDelay, _>(
+        &mut test,
+        &price,
+        &SetAggregationDelayArgs {
+            header: OracleCommand::SetAggregationDelay.into(),
+            slots:  0,
+        },
+    )
+    .await;
+    configure::<ConfFloor, _>(
+        &mut test,
+        &price,
+        &SetConfFloorArgs {
+            header:       OracleCommand::SetConfFloor.into(),
+            min_conf:     7,
+            min_conf_bps: 25,
+        },
+    )
+    .await;
+    configure::<StatusReason, _>(
+        &mut test,
+        &price,
+        &SetStatusReasonTrackingArgs {
+            header:  OracleCommand::SetStatusReasonTracking.into(),
+            enabled: 1,
+        },
+    )
+    .await;
+    configure::<QuoteOrder, _>(
+        &mut test,
+        &price,
+        &SetQuoteOrderArgs {
+            header:  OracleCommand::SetQuoteOrder.into(),
+            enabled: 1,
+        },
+    )
+    .await;
+    configure::<AggregationCounts, _>(
+        &mut test,
+        &price,
+        &SetAggregationCountWindowArgs {
+            header:       OracleCommand::SetAggregationCountWindow.into(),
+            window_slots: 10 * AGGREGATION_COUNT_BUCKETS as u64,
+        },
+    )
+    .await;
+
+    let data = test.account_data(price.pubkey()).await;
+    assert_eq!(
+        get_extension::<PublisherRateLimit>(&data)
+            .unwrap()
+            .unwrap()
+            .window,
+        3
+    );
+    assert_eq!(
+        get_extension::<AggregationDelay>(&data)
+            .unwrap()
+            .unwrap()
+            .slots,
+        0
+    );
+    let conf_floor = get_extension::<ConfFloor>(&data).unwrap().unwrap();
+    assert_eq!((conf_floor.min_conf, conf_floor.min_conf_bps), (7, 25));
+    assert_eq!(
+        get_extension::<StatusReason>(&data)
+            .unwrap()
+            .unwrap()
+            .enabled,
+        1
+    );
+    assert_eq!(
+        get_extension::<QuoteOrder>(&data).unwrap().unwrap().enabled,
+        1
+    );
+    assert_eq!(
+        get_extension::<AggregationCounts>(&data)
+            .unwrap()
+            .unwrap()
+            .bucket_slots,
+        10
+    );
+    // The account stays rent exempt as it grows
+    let account = test.account(price.pubkey()).await.unwrap();
+    assert!(test
+        .rent()
+        .await
+        .is_exempt(account.lamports, account.data.len()));
+}
+
+#[tokio::test]
+async fn test_extension_rent() {
+    let mut test = OracleTest::start().await;
+    let authority = copy_keypair(&test.authority);
+    let price = test.add_feed(-5).await;
+    let size = test.account_data(price.pubkey()).await.len();
+
+    let cmd = SetPriceExtremaWindowArgs {
+        header:       OracleCommand::SetPriceExtremaWindow.into(),
+        window_slots: 4 * PRICE_EXTREMA_BUCKETS as u64,
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(test.permissions_pubkey(), false),
+        ],
+    );
+
+    // The program doesn't pay for the growth of the account
+    assert_eq!(
+        test.process(&[instruction.clone()], &[&authority, &price])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::AccountNotRentExempt)
+    );
+    assert_eq!(test.account_data(price.pubkey()).await.len(), size);
+
+    // Funding it in the same transaction is enough
+    let new_size =
+        data_len_with_extension::<PriceExtrema>(&test.account_data(price.pubkey()).await).unwrap();
+    let account = test.account(price.pubkey()).await.unwrap();
+    let missing_lamports = test
+        .rent()
+        .await
+        .minimum_balance(new_size)
+        .saturating_sub(account.lamports);
+    let transfer = system_instruction::transfer(&test.payer(), &price.pubkey(), missing_lamports);
+    test.process(&[transfer, instruction], &[&authority, &price])
+        .await
+        .unwrap();
+
+    let data = test.account_data(price.pubkey()).await;
+    assert_eq!(data.len(), new_size);
+    assert_eq!(
+        get_extension::<PriceExtrema>(&data)
+            .unwrap()
+            .unwrap()
+            .bucket_slots,
+        4
+    );
+}
@@ -0,0 +1,235 @@
+use {
+    pyth_oracle::instruction::{
+        AddPriceArgs,
+        AddPublisherArgs,
+        CommandHeader,
+        DelPublisherArgs,
+        OracleCommand,
+        SetMinPubArgs,
+    },
+    pyth_oracle_integration_tests::{
+        copy_keypair,
+        OracleTest,
+        PC_PTYPE_PRICE,
+    },
+    pyth_oracle_interface::{
+        MappingAccount,
+        PriceAccount,
+        ProductAccount,
+        PC_PROD_ACC_SIZE,
+    },
+    solana_sdk::{
+        instruction::AccountMeta,
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signer,
+        },
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_feed_lifecycle() {
+    let mut test = OracleTest::start().await;
+    let authority = copy_keypair(&test.authority);
+    let permissions = test.permissions_pubkey();
+
+    let mapping = test.create_account(size_of::<MappingAccount>()).await;
+    let cmd: CommandHeader = OracleCommand::InitMapping.into();
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(mapping.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, &mapping], &[authority.pubkey()])
+        .await
+        .unwrap();
+    assert_eq!(
+        test.load::<MappingAccount>(mapping.pubkey())
+            .await
+            .number_of_products,
+        0
+    );
+
+    let product = test.create_account(PC_PROD_ACC_SIZE as usize).await;
+    let cmd: CommandHeader = OracleCommand::AddProduct.into();
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(mapping.pubkey(), true),
+            AccountMeta::new(product.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&authority, &mapping, &product],
+        &[authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    let mapping_data = test.load::<MappingAccount>(mapping.pubkey()).await;
+    assert_eq!(mapping_data.products(), &[product.pubkey().to_bytes()]);
+
+    let price = test.create_account(size_of::<PriceAccount>()).await;
+    let cmd = AddPriceArgs {
+        header:     OracleCommand::AddPrice.into(),
+        exponent:   -8,
+        price_type: PC_PTYPE_PRICE,
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(product.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&authority, &product, &price],
+        &[authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    let product_data = test.load::<ProductAccount>(product.pubkey()).await;
+    assert_eq!(product_data.first_price_account, price.pubkey().to_bytes());
+    let price_data = test.load::<PriceAccount>(price.pubkey()).await;
+    assert_eq!(price_data.exponent, -8);
+    assert_eq!(price_data.product_account, product.pubkey().to_bytes());
+
+    let publisher = Pubkey::new_unique();
+    let cmd = AddPublisherArgs {
+        header: OracleCommand::AddPublisher.into(),
+        publisher,
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, &price], &[authority.pubkey()])
+        .await
+        .unwrap();
+    let cmd = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 1,
+        unused_:            [0; 3],
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, &price], &[authority.pubkey()])
+        .await
+        .unwrap();
+    let price_data = test.load::<PriceAccount>(price.pubkey()).await;
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, publisher.to_bytes());
+    assert_eq!(price_data.min_pub_, 1);
+
+    let cmd = DelPublisherArgs {
+        header: OracleCommand::DelPublisher.into(),
+        publisher,
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&authority, &price], &[authority.pubkey()])
+        .await
+        .unwrap();
+    assert_eq!(test.load::<PriceAccount>(price.pubkey()).await.num_, 0);
+
+    // Deleting the accounts returns their lamports to the funding account
+    let cmd: CommandHeader = OracleCommand::DelPrice.into();
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(product.pubkey(), true),
+            AccountMeta::new(price.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&authority, &product, &price],
+        &[authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    assert!(test.account(price.pubkey()).await.is_none());
+    let product_data = test.load::<ProductAccount>(product.pubkey()).await;
+    assert_eq!(product_data.first_price_account, [0; 32]);
+
+    let cmd: CommandHeader = OracleCommand::DelProduct.into();
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(mapping.pubkey(), true),
+            AccountMeta::new(product.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&authority, &mapping, &product],
+        &[authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    assert!(test.account(product.pubkey()).await.is_none());
+    assert_eq!(
+        test.load::<MappingAccount>(mapping.pubkey())
+            .await
+            .number_of_products,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_unauthorized_funding_account() {
+    let mut test = OracleTest::start().await;
+    let permissions = test.permissions_pubkey();
+    let attacker = Keypair::new();
+    test.airdrop(&attacker.pubkey(), 1_000_000_000)
+        .await
+        .unwrap();
+
+    // A signed funding account without permissions is rejected all the same
+    let mapping = test.create_account(size_of::<MappingAccount>()).await;
+    let cmd: CommandHeader = OracleCommand::InitMapping.into();
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(attacker.pubkey(), true),
+            AccountMeta::new(mapping.pubkey(), true),
+            AccountMeta::new_readonly(permissions, false),
+        ],
+    );
+    assert!(test
+        .process(&[instruction], &[&attacker, &mapping])
+        .await
+        .is_err());
+    let mapping_data = test.account_data(mapping.pubkey()).await;
+    assert!(mapping_data.iter().all(|byte| *byte == 0));
+}
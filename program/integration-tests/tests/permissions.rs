@@ -0,0 +1,198 @@
+use {
+    bytemuck::pod_read_unaligned,
+    pyth_oracle::{
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+            SetPermissionsTimelockArgs,
+        },
+        OracleError,
+        PermissionAccount,
+        PermissionTimelock,
+    },
+    pyth_oracle_integration_tests::{
+        copy_keypair,
+        oracle_error,
+        OracleTest,
+    },
+    solana_sdk::{
+        clock::Clock,
+        instruction::AccountMeta,
+        native_token::LAMPORTS_PER_SOL,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        system_program,
+        sysvar::SysvarId,
+    },
+    std::mem::size_of,
+};
+
+async fn load_permissions(test: &mut OracleTest) -> PermissionAccount {
+    let data = test.account_data(test.permissions_pubkey()).await;
+    pod_read_unaligned(&data[..size_of::<PermissionAccount>()])
+}
+
+async fn load_timelock(test: &mut OracleTest) -> PermissionTimelock {
+    let data = test.account_data(test.permissions_pubkey()).await;
+    pod_read_unaligned(
+        &data[PermissionAccount::TIMELOCK_OFFSET..PermissionAccount::TIMELOCK_ACCOUNT_SPACE],
+    )
+}
+
+#[tokio::test]
+async fn test_upd_permissions() {
+    let mut test = OracleTest::start().await;
+    let upgrade_authority = copy_keypair(&test.upgrade_authority);
+    let master_authority = Keypair::new();
+    let security_authority = Keypair::new();
+
+    let instruction = test.upd_permissions(
+        master_authority.pubkey(),
+        test.authority.pubkey(),
+        security_authority.pubkey(),
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&upgrade_authority],
+        &[upgrade_authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    let permissions = load_permissions(&mut test).await;
+    assert_eq!(permissions.master_authority, master_authority.pubkey());
+    assert_eq!(permissions.data_curation_authority, test.authority.pubkey());
+    assert_eq!(permissions.security_authority, security_authority.pubkey());
+
+    // Only the upgrade authority of the program updates the permissions, the master authority
+    // can't take its place
+    test.airdrop(&master_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let mut instruction = test.upd_permissions(
+        master_authority.pubkey(),
+        master_authority.pubkey(),
+        master_authority.pubkey(),
+    );
+    instruction.accounts[0] = AccountMeta::new(master_authority.pubkey(), true);
+    assert!(test
+        .process(&[instruction], &[&master_authority])
+        .await
+        .is_err());
+    let permissions = load_permissions(&mut test).await;
+    assert_eq!(permissions.security_authority, security_authority.pubkey());
+}
+
+#[tokio::test]
+async fn test_permissions_timelock() {
+    let mut test = OracleTest::start().await;
+    let upgrade_authority = copy_keypair(&test.upgrade_authority);
+    let authority = copy_keypair(&test.authority);
+    let permissions_pubkey = test.permissions_pubkey();
+
+    let cmd = SetPermissionsTimelockArgs {
+        header: OracleCommand::SetPermissionsTimelock.into(),
+        delay:  5,
+    };
+    let instruction = test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(upgrade_authority.pubkey(), true),
+            AccountMeta::new_readonly(test.programdata_id, false),
+            AccountMeta::new(permissions_pubkey, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(Clock::id(), false),
+        ],
+    );
+    test.process_requiring_signatures(
+        instruction,
+        &[&upgrade_authority],
+        &[upgrade_authority.pubkey()],
+    )
+    .await
+    .unwrap();
+    assert_eq!(load_timelock(&mut test).await.delay, 5);
+
+    // Changes are staged until the delay has passed
+    test.warp_to_slot(10).unwrap();
+    let new_authority = Keypair::new();
+    let instruction = test.upd_permissions(
+        new_authority.pubkey(),
+        new_authority.pubkey(),
+        new_authority.pubkey(),
+    );
+    test.process(&[instruction], &[&upgrade_authority])
+        .await
+        .unwrap();
+    let timelock = load_timelock(&mut test).await;
+    assert_eq!(timelock.effective_slot, 15);
+    assert_eq!(timelock.pending_master_authority, new_authority.pubkey());
+    assert_eq!(
+        load_permissions(&mut test).await.master_authority,
+        authority.pubkey()
+    );
+
+    let apply: CommandHeader = OracleCommand::ApplyPermissions.into();
+    let instruction = test.instruction(
+        &apply,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(permissions_pubkey, false),
+            AccountMeta::new_readonly(Clock::id(), false),
+            AccountMeta::new_readonly(test.programdata_id, false),
+        ],
+    );
+    assert_eq!(
+        test.process(&[instruction], &[&authority])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        oracle_error(0, OracleError::PermissionsChangeNotReady)
+    );
+
+    // The security authority must sign to cancel the change
+    let cancel: CommandHeader = OracleCommand::CancelPermissions.into();
+    let cancel_instruction = test.instruction(
+        &cancel,
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(permissions_pubkey, false),
+        ],
+    );
+    test.process_requiring_signatures(cancel_instruction, &[&authority], &[authority.pubkey()])
+        .await
+        .unwrap();
+    assert!(!load_timelock(&mut test).await.has_pending_change());
+
+    // Anyone applies a change once its delay has passed
+    let instruction = test.upd_permissions(
+        new_authority.pubkey(),
+        new_authority.pubkey(),
+        new_authority.pubkey(),
+    );
+    test.process(&[instruction], &[&upgrade_authority])
+        .await
+        .unwrap();
+    test.warp_to_slot(20).unwrap();
+    let anyone = Keypair::new();
+    test.airdrop(&anyone.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let instruction = test.instruction(
+        &apply,
+        vec![
+            AccountMeta::new(anyone.pubkey(), true),
+            AccountMeta::new(permissions_pubkey, false),
+            AccountMeta::new_readonly(Clock::id(), false),
+            AccountMeta::new_readonly(test.programdata_id, false),
+        ],
+    );
+    test.process_requiring_signatures(instruction, &[&anyone], &[anyone.pubkey()])
+        .await
+        .unwrap();
+    let permissions = load_permissions(&mut test).await;
+    assert_eq!(permissions.master_authority, new_authority.pubkey());
+    assert_eq!(permissions.security_authority, new_authority.pubkey());
+    assert!(!load_timelock(&mut test).await.has_pending_change());
+}
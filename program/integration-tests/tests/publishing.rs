@@ -0,0 +1,240 @@
+use {
+    pyth_oracle::instruction::{
+        CommandHeader,
+        OracleCommand,
+        UpdPriceArgs,
+    },
+    pyth_oracle_integration_tests::{
+        without_signer,
+        OracleTest,
+    },
+    pyth_oracle_interface::{
+        PriceAccount,
+        PC_STATUS_TRADING,
+    },
+    solana_sdk::{
+        clock::Clock,
+        instruction::{
+            AccountMeta,
+            Instruction,
+            InstructionError,
+        },
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        signature::{
+            Keypair,
+            Signer,
+        },
+        sysvar::{
+            rent::Rent,
+            SysvarId,
+        },
+        transaction::TransactionError,
+    },
+};
+
+fn upd_price(
+    test: &OracleTest,
+    command: OracleCommand,
+    publisher: &Keypair,
+    price: Pubkey,
+    quote: (i64, u64),
+    publishing_slot: u64,
+) -> Instruction {
+    let cmd = UpdPriceArgs {
+        header: command.into(),
+        status: PC_STATUS_TRADING,
+        venue: 0,
+        unused_: [0; 3],
+        price: quote.0,
+        confidence: quote.1,
+        publishing_slot,
+        sequence: 0,
+    };
+    test.instruction(
+        &cmd,
+        vec![
+            AccountMeta::new(publisher.pubkey(), true),
+            AccountMeta::new(price, false),
+            AccountMeta::new_readonly(Clock::id(), false),
+        ],
+    )
+}
+
+#[tokio::test]
+async fn test_upd_price() {
+    let mut test = OracleTest::start().await;
+    let publisher = Keypair::new();
+    let other_publisher = Keypair::new();
+    for key in [publisher.pubkey(), other_publisher.pubkey()] {
+        test.airdrop(&key, LAMPORTS_PER_SOL).await.unwrap();
+    }
+    let price_keypair = test.add_feed(-5).await;
+    test.add_publisher(&price_keypair, publisher.pubkey()).await;
+    let price = price_keypair.pubkey();
+
+    test.warp_to_slot(10).unwrap();
+    let slot = test.clock().await.slot;
+    let instruction = upd_price(
+        &test,
+        OracleCommand::UpdPrice,
+        &publisher,
+        price,
+        (100, 1),
+        slot,
+    );
+    test.process_requiring_signatures(instruction, &[&publisher], &[publisher.pubkey()])
+        .await
+        .unwrap();
+    let price_data = test.load::<PriceAccount>(price).await;
+    assert_eq!(price_data.comp_[0].latest_.price_, 100);
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, slot);
+
+    // Only the publishers of the feed can publish
+    let instruction = upd_price(
+        &test,
+        OracleCommand::UpdPrice,
+        &other_publisher,
+        price,
+        (200, 1),
+        slot,
+    );
+    assert!(test
+        .process(&[instruction], &[&other_publisher])
+        .await
+        .is_err());
+
+    // The quote of the previous slot is aggregated with the first update of the next one
+    test.warp_to_slot(11).unwrap();
+    let slot = test.clock().await.slot;
+    let instruction = upd_price(
+        &test,
+        OracleCommand::UpdPrice,
+        &publisher,
+        price,
+        (110, 1),
+        slot,
+    );
+    test.process(&[instruction], &[&publisher]).await.unwrap();
+    let price_data = test.load::<PriceAccount>(price).await;
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 100);
+    assert_eq!(price_data.agg_.pub_slot_, slot);
+    assert_eq!(price_data.comp_[0].latest_.price_, 110);
+}
+
+#[tokio::test]
+async fn test_upd_price_no_fail_on_error() {
+    let mut test = OracleTest::start().await;
+    let publisher = Keypair::new();
+    test.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let price = test.add_feed(-5).await;
+    test.add_publisher(&price, publisher.pubkey()).await;
+    test.warp_to_slot(10).unwrap();
+    let before = test.account_data(price.pubkey()).await;
+
+    // The transaction succeeds whatever happens, the quote must still be rejected
+    let slot = test.clock().await.slot;
+    let instruction = upd_price(
+        &test,
+        OracleCommand::UpdPriceNoFailOnError,
+        &publisher,
+        price.pubkey(),
+        (100, 1),
+        slot,
+    );
+    let unsigned = without_signer(&instruction, &publisher.pubkey());
+    let _ = test.process(&[unsigned], &[&publisher]).await;
+    assert_eq!(test.account_data(price.pubkey()).await, before);
+
+    test.process(&[instruction], &[&publisher]).await.unwrap();
+    let price_data = test.load::<PriceAccount>(price.pubkey()).await;
+    assert_eq!(price_data.comp_[0].latest_.price_, 100);
+}
+
+#[tokio::test]
+async fn test_upd_price_sysvar() {
+    let mut test = OracleTest::start().await;
+    let publisher = Keypair::new();
+    test.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let price = test.add_feed(-5).await;
+    test.add_publisher(&price, publisher.pubkey()).await;
+    test.warp_to_slot(10).unwrap();
+    let slot = test.clock().await.slot;
+
+    // The clock can't be replaced with another account, even another sysvar
+    let mut instruction = upd_price(
+        &test,
+        OracleCommand::UpdPrice,
+        &publisher,
+        price.pubkey(),
+        (100, 1),
+        slot,
+    );
+    instruction.accounts[2] = AccountMeta::new_readonly(Rent::id(), false);
+    assert_eq!(
+        test.process(&[instruction], &[&publisher])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    // Nor can the publishing slot be ahead of the clock
+    let instruction = upd_price(
+        &test,
+        OracleCommand::UpdPrice,
+        &publisher,
+        price.pubkey(),
+        (100, 1),
+        slot + 1,
+    );
+    assert!(test.process(&[instruction], &[&publisher]).await.is_err());
+    let price_data = test.load::<PriceAccount>(price.pubkey()).await;
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 0);
+}
+
+#[tokio::test]
+async fn test_rotate_publisher() {
+    let mut test = OracleTest::start().await;
+    let publisher = Keypair::new();
+    let new_publisher = Keypair::new();
+    test.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let prices = [test.add_feed(-5).await, test.add_feed(-8).await];
+    for price in prices.iter() {
+        test.add_publisher(price, publisher.pubkey()).await;
+    }
+
+    let cmd: CommandHeader = OracleCommand::RotatePublisher.into();
+    let mut accounts = vec![
+        AccountMeta::new(publisher.pubkey(), true),
+        AccountMeta::new_readonly(new_publisher.pubkey(), true),
+        AccountMeta::new_readonly(test.permissions_pubkey(), false),
+    ];
+    accounts.extend(
+        prices
+            .iter()
+            .map(|price| AccountMeta::new(price.pubkey(), false)),
+    );
+    let instruction = test.instruction(&cmd, accounts);
+    // Both keys must sign, so that a publisher can't move to a key it doesn't control
+    test.process_requiring_signatures(
+        instruction,
+        &[&publisher, &new_publisher],
+        &[publisher.pubkey(), new_publisher.pubkey()],
+    )
+    .await
+    .unwrap();
+
+    for price in prices.iter() {
+        let price_data = test.load::<PriceAccount>(price.pubkey()).await;
+        assert_eq!(price_data.num_, 1);
+        assert_eq!(price_data.comp_[0].pub_, new_publisher.pubkey().to_bytes());
+    }
+}
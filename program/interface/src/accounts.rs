@@ -0,0 +1,513 @@
+use {
+    crate::{
+        error::InterfaceError,
+        FLAG_DEPRECATED,
+        PC_ACCTYPE_MAPPING,
+        PC_ACCTYPE_PRICE,
+        PC_ACCTYPE_PRODUCT,
+        PC_MAGIC,
+        PC_MAP_TABLE_SIZE,
+        PC_NUM_COMP,
+        PC_NUM_COMP_PYTHNET,
+        PC_PROD_ACC_SIZE,
+        PC_STATUS_TRADING,
+        PC_VERSION,
+    },
+    bytemuck::{
+        bytes_of,
+        from_bytes,
+        try_from_bytes,
+        Pod,
+        Zeroable,
+    },
+    core::mem::size_of,
+};
+
+/// Public key, with the layout of `solana_program::pubkey::Pubkey`
+pub type Pubkey = [u8; 32];
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct AccountHeader {
+    pub magic_number: u32,
+    pub version:      u32,
+    pub account_type: u32,
+    pub size:         u32,
+}
+
+/// An account type of the program, identified by the `account_type` of its header.
+pub trait PythAccount: Pod {
+    const ACCOUNT_TYPE: u32;
+
+    /// Size of the account data holding the struct
+    const MINIMUM_SIZE: usize = size_of::<Self>();
+}
+
+/// Interpret `data` as an initialized account of type `T` of the current version, e.g. the data
+/// of an account owned by the oracle program. The owner of the account isn't checked, callers
+/// must check it before trusting the data.
+pub fn load_checked<T: PythAccount>(data: &[u8]) -> Result<&T, InterfaceError> {
+    let account = load_account_data::<T>(data)?;
+    let header = load_header(data)?;
+    if header.version != PC_VERSION {
+        return Err(InterfaceError::InvalidAccountHeader);
+    }
+    Ok(account)
+}
+
+/// Same as `load_checked` without checking the version, for readers that handle several
+/// versions themselves.
+pub fn load_account_data<T: PythAccount>(data: &[u8]) -> Result<&T, InterfaceError> {
+    if data.len() < T::MINIMUM_SIZE {
+        return Err(InterfaceError::AccountTooSmall);
+    }
+
+    let header = load_header(data)?;
+    if header.magic_number != PC_MAGIC || header.account_type != T::ACCOUNT_TYPE {
+        return Err(InterfaceError::InvalidAccountHeader);
+    }
+
+    try_from_bytes(&data[..size_of::<T>()]).map_err(|_| InterfaceError::AccountDataMisaligned)
+}
+
+/// Type of the extension entry holding the successor of a deprecated price account
+const EXTENSION_TYPE_PRICE_SUCCESSOR: u32 = 12;
+
+/// Price account replacing the price account stored in `data`, if the feed is deprecated.
+/// Consumers of a deprecated feed should move to its successor. The extensions follow the
+/// `PriceAccount` as `[type: u32, length: u32, value, padding to 8 bytes]` entries, a deprecated
+/// feed with an invalid extension region has no known successor.
+pub fn get_successor(data: &[u8]) -> Result<Option<Pubkey>, InterfaceError> {
+    let price = load_account_data::<PriceAccount>(data)?;
+    if price.flags & FLAG_DEPRECATED == 0 {
+        return Ok(None);
+    }
+
+    let read_u32 = |offset: usize| {
+        data.get(offset..offset + 4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+    let mut offset = size_of::<PriceAccount>();
+    while let (Some(extension_type), Some(length)) = (read_u32(offset), read_u32(offset + 4)) {
+        let value_offset = offset + 8;
+        let value = match data.get(value_offset..value_offset + length as usize) {
+            Some(value) if extension_type != 0 => value,
+            _ => break,
+        };
+        if extension_type == EXTENSION_TYPE_PRICE_SUCCESSOR && value.len() == 32 {
+            let mut successor = [0; 32];
+            successor.copy_from_slice(value);
+            return Ok(Some(successor));
+        }
+        offset = (value_offset + value.len() + 7) / 8 * 8;
+    }
+    Ok(None)
+}
+
+/// Latest price of the feed stored in `data` in `target_exponent`, see
+/// `PriceSummary::get_price_in_exponent`. Only the `PriceSummary` at the start of the account is
+/// read, so `data` can be a prefix of the account data.
+pub fn get_price_in_exponent(
+    data: &[u8],
+    target_exponent: i32,
+    current_slot: u64,
+    max_age: u64,
+) -> Result<Option<LatestPrice>, InterfaceError> {
+    Ok(load_checked::<PriceSummary>(data)?.get_price_in_exponent(
+        target_exponent,
+        current_slot,
+        max_age,
+    ))
+}
+
+fn load_header(data: &[u8]) -> Result<&AccountHeader, InterfaceError> {
+    try_from_bytes(
+        data.get(..size_of::<AccountHeader>())
+            .ok_or(InterfaceError::AccountTooSmall)?,
+    )
+    .map_err(|_| InterfaceError::AccountDataMisaligned)
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct MappingAccount {
+    pub header:               AccountHeader,
+    pub number_of_products:   u32,
+    pub unused_:              u32,
+    pub next_mapping_account: Pubkey,
+    pub products_list:        [Pubkey; PC_MAP_TABLE_SIZE as usize],
+}
+
+// There are no derived traits for arrays of this size
+unsafe impl Pod for MappingAccount {
+}
+
+unsafe impl Zeroable for MappingAccount {
+}
+
+impl PythAccount for MappingAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_MAPPING;
+}
+
+impl MappingAccount {
+    pub fn products(&self) -> &[Pubkey] {
+        &self.products_list[..(self.number_of_products as usize).min(PC_MAP_TABLE_SIZE as usize)]
+    }
+}
+
+/// Header of a product account, the product metadata follows it.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ProductAccount {
+    pub header:              AccountHeader,
+    pub first_price_account: Pubkey,
+}
+
+impl PythAccount for ProductAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PRODUCT;
+    const MINIMUM_SIZE: usize = PC_PROD_ACC_SIZE as usize;
+}
+
+/// Price account, the extensions of the feed follow it.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PriceAccount {
+    pub header:             AccountHeader,
+    /// Type of the price account
+    pub price_type:         u32,
+    /// Exponent for the published prices
+    pub exponent:           i32,
+    /// Current number of authorized publishers
+    pub num_:               u32,
+    /// Number of valid quotes for the last aggregation
+    pub num_qt_:            u32,
+    /// Last slot with a succesful aggregation (status : TRADING)
+    pub last_slot_:         u64,
+    /// Second to last slot where aggregation was attempted
+    pub valid_slot_:        u64,
+    /// Ema for price
+    pub twap_:              PriceEma,
+    /// Ema for confidence
+    pub twac_:              PriceEma,
+    /// Last time aggregation was attempted
+    pub timestamp_:         i64,
+    /// Minimum valid publisher quotes for a succesful aggregation
+    pub min_pub_:           u8,
+    pub message_sent_:      u8,
+    /// Configurable max latency in slots between send and receive
+    pub max_latency_:       u8,
+    /// `FLAG_*` bits, bits that aren't defined were set by a newer version of the program
+    pub flags:              u8,
+    /// Globally unique price feed index used for publishing
+    pub feed_index:         u32,
+    /// Corresponding product account
+    pub product_account:    Pubkey,
+    /// Next price account in the list
+    pub next_price_account: Pubkey,
+    /// Second to last slot where aggregation was succesful (i.e. status : TRADING)
+    pub prev_slot_:         u64,
+    /// Aggregate price at prev_slot_
+    pub prev_price_:        i64,
+    /// Confidence interval at prev_slot_
+    pub prev_conf_:         u64,
+    /// Timestamp of prev_slot_
+    pub prev_timestamp_:    i64,
+    /// Last attempted aggregate results
+    pub agg_:               PriceInfo,
+    /// Publishers' price components, only the first `num_` are used
+    pub comp_:              [PriceComponent; PC_NUM_COMP_PYTHNET as usize],
+    /// Cumulative sums of aggregative price and confidence used to compute arithmetic moving averages
+    pub price_cumulative:   PriceCumulative,
+}
+
+impl PythAccount for PriceAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PRICE;
+}
+
+impl PriceAccount {
+    /// Fields of the price account up to the aggregate.
+    pub fn summary(&self) -> &PriceSummary {
+        from_bytes(&bytes_of(self)[..size_of::<PriceSummary>()])
+    }
+
+    /// See `PriceSummary::get_latest_price`.
+    pub fn get_latest_price(&self) -> LatestPrice {
+        self.summary().get_latest_price()
+    }
+
+    /// See `PriceSummary::get_price_no_older_than`.
+    pub fn get_price_no_older_than(&self, current_slot: u64, max_age: u64) -> Option<LatestPrice> {
+        self.summary()
+            .get_price_no_older_than(current_slot, max_age)
+    }
+
+    /// See `PriceSummary::get_price_unchecked`.
+    pub fn get_price_unchecked(&self) -> LatestPrice {
+        self.summary().get_price_unchecked()
+    }
+
+    /// See `PriceSummary::get_ema_price`.
+    pub fn get_ema_price(&self) -> LatestPrice {
+        self.summary().get_ema_price()
+    }
+
+    /// See `PriceSummary::get_price_in_exponent`.
+    pub fn get_price_in_exponent(
+        &self,
+        target_exponent: i32,
+        current_slot: u64,
+        max_age: u64,
+    ) -> Option<LatestPrice> {
+        self.summary()
+            .get_price_in_exponent(target_exponent, current_slot, max_age)
+    }
+
+    /// Components of the publishers of the feed.
+    pub fn components(&self) -> &[PriceComponent] {
+        &self.comp_[..(self.num_ as usize).min(PC_NUM_COMP as usize)]
+    }
+}
+
+/// The fields of a `PriceAccount` up to its aggregate, for clients reading many feeds that
+/// don't need the components of the publishers. Fetch the first `PriceSummary::MINIMUM_SIZE`
+/// bytes of the price account, e.g. with the `dataSlice` option of the RPC, and read them with
+/// `load_checked::<PriceSummary>`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PriceSummary {
+    pub header:             AccountHeader,
+    pub price_type:         u32,
+    pub exponent:           i32,
+    pub num_:               u32,
+    pub num_qt_:            u32,
+    pub last_slot_:         u64,
+    pub valid_slot_:        u64,
+    pub twap_:              PriceEma,
+    pub twac_:              PriceEma,
+    pub timestamp_:         i64,
+    pub min_pub_:           u8,
+    pub message_sent_:      u8,
+    pub max_latency_:       u8,
+    pub flags:              u8,
+    pub feed_index:         u32,
+    pub product_account:    Pubkey,
+    pub next_price_account: Pubkey,
+    pub prev_slot_:         u64,
+    pub prev_price_:        i64,
+    pub prev_conf_:         u64,
+    pub prev_timestamp_:    i64,
+    pub agg_:               PriceInfo,
+}
+
+impl PythAccount for PriceSummary {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PRICE;
+}
+
+impl PriceSummary {
+    /// Price that consumers should read: the aggregate if it is trading, otherwise the last
+    /// trading aggregate.
+    pub fn get_latest_price(&self) -> LatestPrice {
+        let (price, conf, publish_slot, publish_time) = if self.agg_.status_ == PC_STATUS_TRADING {
+            (
+                self.agg_.price_,
+                self.agg_.conf_,
+                self.agg_.pub_slot_,
+                self.timestamp_,
+            )
+        } else {
+            (
+                self.prev_price_,
+                self.prev_conf_,
+                self.prev_slot_,
+                self.prev_timestamp_,
+            )
+        };
+
+        LatestPrice {
+            price,
+            conf,
+            exponent: self.exponent,
+            publish_slot,
+            publish_time,
+        }
+    }
+
+    /// Latest price, unless it was published more than `max_age` slots before `current_slot`.
+    pub fn get_price_no_older_than(&self, current_slot: u64, max_age: u64) -> Option<LatestPrice> {
+        Some(self.get_latest_price()).filter(|price| price.is_no_older_than(current_slot, max_age))
+    }
+
+    /// Latest price whatever its age, under the name pyth-sdk gives it. Consumers should prefer
+    /// `get_price_no_older_than`.
+    pub fn get_price_unchecked(&self) -> LatestPrice {
+        self.get_latest_price()
+    }
+
+    /// Latest price in `target_exponent`, unless it was published more than `max_age` slots
+    /// before `current_slot` or doesn't fit in `target_exponent`. The price is rounded to the
+    /// nearest value and the confidence is rounded up, see `LatestPrice::normalize_to_exponent`.
+    pub fn get_price_in_exponent(
+        &self,
+        target_exponent: i32,
+        current_slot: u64,
+        max_age: u64,
+    ) -> Option<LatestPrice> {
+        self.get_price_no_older_than(current_slot, max_age)?
+            .normalize_to_exponent(target_exponent)
+    }
+
+    /// Exponential moving averages of the aggregate price and confidence, as of the slot of the
+    /// latest price.
+    pub fn get_ema_price(&self) -> LatestPrice {
+        let latest_price = self.get_latest_price();
+        LatestPrice {
+            price:        self.twap_.val_,
+            conf:         self.twac_.val_ as u64,
+            exponent:     self.exponent,
+            publish_slot: latest_price.publish_slot,
+            publish_time: latest_price.publish_time,
+        }
+    }
+}
+
+/// Price of a feed as read by consumers, see `PriceAccount::get_latest_price`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatestPrice {
+    pub price:        i64,
+    pub conf:         u64,
+    pub exponent:     i32,
+    pub publish_slot: u64,
+    /// Unix timestamp of the clock when the price was aggregated
+    pub publish_time: i64,
+}
+
+impl LatestPrice {
+    /// A price is fresh if it was published at most `max_age` slots before `current_slot`.
+    pub fn is_no_older_than(&self, current_slot: u64, max_age: u64) -> bool {
+        current_slot.saturating_sub(self.publish_slot) <= max_age
+    }
+
+    /// The same price and confidence in `target_exponent`, `None` if they don't fit. Scaling to a
+    /// larger exponent truncates towards zero.
+    pub fn scale_to_exponent(&self, target_exponent: i32) -> Option<LatestPrice> {
+        let mut delta = target_exponent.checked_sub(self.exponent)?;
+        let (mut price, mut conf) = (self.price, self.conf);
+        while delta > 0 && (price != 0 || conf != 0) {
+            price /= 10;
+            conf /= 10;
+            delta -= 1;
+        }
+        while delta < 0 {
+            price = price.checked_mul(10)?;
+            conf = conf.checked_mul(10)?;
+            delta += 1;
+        }
+        Some(LatestPrice {
+            price,
+            conf,
+            exponent: target_exponent,
+            publish_slot: self.publish_slot,
+            publish_time: self.publish_time,
+        })
+    }
+
+    /// The same price and confidence in `target_exponent`, `None` if they don't fit. Scaling to a
+    /// larger exponent rounds the price to the nearest value, half away from zero, and rounds the
+    /// confidence up so that the interval never gets narrower than the published one.
+    pub fn normalize_to_exponent(&self, target_exponent: i32) -> Option<LatestPrice> {
+        let delta = i64::from(target_exponent) - i64::from(self.exponent);
+        let (price, conf) = (i128::from(self.price), i128::from(self.conf));
+        let power = |delta: i64| {
+            u32::try_from(delta)
+                .ok()
+                .and_then(|n| 10i128.checked_pow(n))
+        };
+        let (price, conf) = if delta >= 0 {
+            match power(delta) {
+                Some(divisor) => (
+                    round_half_away_from_zero(price, divisor),
+                    conf / divisor + i128::from(conf % divisor != 0),
+                ),
+                // Every price rounds to zero and every confidence up to one
+                None => (0, i128::from(conf != 0)),
+            }
+        } else {
+            let factor = power(-delta)?;
+            (price.checked_mul(factor)?, conf.checked_mul(factor)?)
+        };
+        Some(LatestPrice {
+            price: i64::try_from(price).ok()?,
+            conf: u64::try_from(conf).ok()?,
+            exponent: target_exponent,
+            ..*self
+        })
+    }
+}
+
+/// `value / divisor` rounded to the nearest integer, half away from zero. `divisor` must be
+/// positive.
+fn round_half_away_from_zero(value: i128, divisor: i128) -> i128 {
+    let remainder = (value % divisor).abs();
+    // Written so that doubling the remainder can't overflow
+    if remainder >= divisor - remainder {
+        value / divisor + value.signum()
+    } else {
+        value / divisor
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct PriceInfo {
+    pub price_:           i64,
+    pub conf_:            u64,
+    pub status_:          u32,
+    /// Tags of a publisher quote: the venue the publisher sourced it from in the low byte, and
+    /// the status the publisher supplied with it in the next one
+    pub corp_act_status_: u32,
+    pub pub_slot_:        u64,
+}
+
+impl PriceInfo {
+    /// Venue the publisher sourced the quote from, 0 if untagged.
+    pub fn venue(&self) -> u8 {
+        self.corp_act_status_ as u8
+    }
+
+    /// Status the publisher supplied with the quote, which `status_` replaces with
+    /// `PC_STATUS_IGNORED` when the quote isn't valid.
+    pub fn publisher_status(&self) -> u32 {
+        (self.corp_act_status_ >> 8) & 0xff
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct PriceComponent {
+    pub pub_:    Pubkey,
+    pub agg_:    PriceInfo,
+    pub latest_: PriceInfo,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct PriceEma {
+    pub val_:   i64,
+    pub numer_: i64,
+    pub denom_: i64,
+}
+
+/// Cumulative sums of the aggregates, the TWAP between two snapshots `t` and `T` is
+/// `(T.price_cumulative.price - t.price_cumulative.price) / (T.agg_.pub_slot_ - t.agg_.pub_slot_)`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct PriceCumulative {
+    /// Cumulative sum of price * slot_gap
+    pub price:          i128,
+    /// Cumulative sum of conf * slot_gap
+    pub conf:           u128,
+    /// Cumulative number of slots where the price wasn't recently updated
+    pub num_down_slots: u64,
+    /// Padding for alignment
+    pub unused:         u64,
+}
@@ -0,0 +1,19 @@
+use core::fmt;
+
+/// Errors of reading the accounts of the program, named after the `OracleError` the program
+/// returns in the same situation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InterfaceError {
+    /// The account data is shorter than the account type
+    AccountTooSmall,
+    /// The magic number, the version or the account type doesn't match
+    InvalidAccountHeader,
+    /// The account data isn't aligned for the account type
+    AccountDataMisaligned,
+}
+
+impl fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
@@ -0,0 +1,77 @@
+//! Layouts of the accounts of the Pyth oracle program, for programs and clients that read them.
+//!
+//! This crate only depends on `bytemuck` and doesn't use `std`, so that on-chain programs can
+//! read price accounts without pulling in the oracle program or a specific Solana version.
+//! Public keys are plain byte arrays, convert them with `Pubkey::new_from_array`. The layouts
+//! are checked against the ones of the oracle program by its tests.
+#![no_std]
+
+mod accounts;
+mod error;
+
+pub use {
+    accounts::{
+        get_price_in_exponent,
+        get_successor,
+        load_account_data,
+        load_checked,
+        AccountHeader,
+        LatestPrice,
+        MappingAccount,
+        PriceAccount,
+        PriceComponent,
+        PriceCumulative,
+        PriceEma,
+        PriceInfo,
+        PriceSummary,
+        ProductAccount,
+        Pubkey,
+        PythAccount,
+    },
+    error::InterfaceError,
+};
+
+/// Magic number at the start of every account of the program
+pub const PC_MAGIC: u32 = 0xa1b2c3d4;
+/// Version of the accounts of the program
+pub const PC_VERSION: u32 = 2;
+
+pub const PC_ACCTYPE_MAPPING: u32 = 1;
+pub const PC_ACCTYPE_PRODUCT: u32 = 2;
+pub const PC_ACCTYPE_PRICE: u32 = 3;
+
+pub const PC_STATUS_UNKNOWN: u32 = 0;
+pub const PC_STATUS_TRADING: u32 = 1;
+pub const PC_STATUS_HALTED: u32 = 2;
+pub const PC_STATUS_AUCTION: u32 = 3;
+pub const PC_STATUS_IGNORED: u32 = 4;
+
+/// Maximum number of publishers of a price account, `PriceAccount::comp_` has room for more
+pub const PC_NUM_COMP: u32 = 64;
+/// Number of entries of `PriceAccount::comp_`
+pub const PC_NUM_COMP_PYTHNET: u32 = 128;
+/// Number of entries of `MappingAccount::products_list`
+pub const PC_MAP_TABLE_SIZE: u32 = 5000;
+/// Size of product accounts, the product metadata follows `ProductAccount`
+pub const PC_PROD_ACC_SIZE: u32 = 512;
+/// Maximum number of slots between a quote and the aggregate including it, when the price
+/// account doesn't set `max_latency_`
+pub const PC_MAX_SEND_LATENCY: u32 = 25;
+
+/// `PriceAccount::flags` bit set when the validator aggregates the price account
+pub const FLAG_ACCUMULATOR_V2: u8 = 0b1;
+/// `PriceAccount::flags` bit set when the program cleared the message buffer of the price account
+pub const FLAG_MESSAGE_BUFFER_CLEARED: u8 = 0b10;
+/// `PriceAccount::flags` bit set when quotes with a zero confidence interval are aggregated
+pub const FLAG_ALLOW_ZERO_CI: u8 = 0b100;
+/// `PriceAccount::flags` bit set when the feed is replaced by another price account, see
+/// `get_successor`
+pub const FLAG_DEPRECATED: u8 = 0b1000;
+/// `PriceAccount::flags` bit set when publishers can't update the feed in transactions holding
+/// instructions of other programs
+pub const FLAG_ISOLATED_UPDATES: u8 = 0b10000;
+/// `PriceAccount::flags` bit set while the program aggregates the price account, its aggregate
+/// and EMA may be out of step until it's cleared
+pub const FLAG_AGGREGATION_IN_PROGRESS: u8 = 0b100000;
+/// `PriceAccount::flags` bit set when the feed was listed without governance by its creator
+pub const FLAG_COMMUNITY: u8 = 0b1000000;
@@ -13,6 +13,7 @@ fn main() {
     let target_arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
 
     let has_feat_check = std::env::var("CARGO_FEATURE_CHECK").is_ok();
+    let has_feat_wasm = std::env::var("CARGO_FEATURE_WASM").is_ok();
 
     // OUT_DIR is the path cargo provides to a build directory under `target/` specifically for
     // isolated build artifacts. We use this to build the C program and then link against the
@@ -38,6 +39,9 @@ fn main() {
     // Solana in its GitHub Action.
     if has_feat_check {
         eprintln!("WARNING: `check` feature active, make build is skipped");
+    } else if has_feat_wasm {
+        // Only the processor calls into the C code, and the `wasm` feature leaves it out
+        eprintln!("`wasm` feature active, make build is skipped");
     } else {
         do_make_build(make_targets, &out_dir);
 
@@ -34,15 +34,39 @@ use {
     std::borrow::BorrowMut,
 };
 
+mod access_control;
+mod aggregation_config;
+mod audit_log;
+mod consistency_check;
+mod extensions;
+mod feature_gate;
+mod fee_vault;
+mod feed_registry;
 mod mapping;
+mod mapping_v2;
+mod multisig;
 mod permission;
 mod price;
 mod product;
+mod programdata;
+mod publisher_linkage;
+mod publisher_registry;
+mod rewards_ledger;
+#[cfg(feature = "serde")]
+mod serde_array;
+mod snapshot;
 
 // Some types only exist during use as a library.
+#[cfg(any(test, feature = "library", feature = "wasm"))]
+pub use extensions::get_component_publish_time;
+#[cfg(any(test, feature = "library", feature = "no-entrypoint"))]
+pub use mapping::{
+    find_product_by_symbol,
+    iter_products,
+};
 #[cfg(feature = "strum")]
 pub use price::MessageType;
-#[cfg(test)]
+#[cfg(any(test, feature = "library"))]
 pub use price::PriceCumulative;
 #[cfg(test)]
 pub use product::{
@@ -50,21 +74,183 @@ pub use product::{
     create_pc_str_t,
 };
 pub use {
-    mapping::MappingAccount,
-    permission::PermissionAccount,
+    access_control::{
+        AccessControlAccount,
+        MAX_ACCESS_CONTROL_READERS,
+    },
+    aggregation_config::AggregationConfigAccount,
+    audit_log::{
+        AuditLogAccount,
+        AuditRecord,
+        AUDIT_LOG_CAPACITY,
+    },
+    consistency_check::{
+        ConsistencyCheckAccount,
+        ConsistencyKind,
+        MAX_CONSISTENCY_FEEDS,
+    },
+    extensions::{
+        data_len_with_extension,
+        extension_space,
+        extensions_end,
+        get_access_control,
+        get_extension,
+        get_feed_creator,
+        get_successor,
+        init_extension,
+        iter_extensions,
+        set_extension,
+        verify_checksum,
+        AccessControl,
+        AggregateCache,
+        AggregationCounts,
+        AggregationDelay,
+        AuxData,
+        AverageMode,
+        BidAsk,
+        Checksum,
+        ComponentActivity,
+        ComponentAuxData,
+        ComponentSequence,
+        ComponentTime,
+        ConfFloor,
+        ConsistencyFlag,
+        ExtensionHeader,
+        ExtensionInfo,
+        ExtensionIter,
+        ExtensionType,
+        FeedCreator,
+        LandingLatency,
+        LatencySample,
+        LatencyStats,
+        PriceAccountExtension,
+        PriceExtrema,
+        PriceHistoryPointer,
+        PriceSuccessor,
+        PublishThreshold,
+        PublisherActivity,
+        PublisherCap,
+        PublisherLandingLatency,
+        PublisherRateLimit,
+        PublisherRewards,
+        PublisherSampling,
+        PublisherSequences,
+        PublisherStake,
+        PublisherTimestamps,
+        PublisherWeightCap,
+        PublishingSlotTolerance,
+        QuoteConversion,
+        QuoteOrder,
+        RateLimitEntry,
+        ReadStats,
+        RewardEntry,
+        SanityProgram,
+        SharedSettings,
+        SlotWeightedTwap,
+        StakeEntry,
+        StatusObserver,
+        StatusPolicy,
+        StatusReason,
+        StatusReasonCode,
+        TwapBucket,
+        UpdateCount,
+        UpdateFee,
+        UpdaterProgram,
+        AGGREGATION_COUNT_BUCKETS,
+        EXTENSIONS_OFFSET,
+        EXTENSIONS_RESERVED_SPACE,
+        EXTENSION_ALIGNMENT,
+        LANDING_LATENCY_EMA_SCALE,
+        LANDING_LATENCY_EMA_WINDOW,
+        LATENCY_STATS_ENTRIES,
+        LATENCY_STATS_SCALE,
+        MAX_CONF_FLOOR_BPS,
+        PRICE_EXTREMA_BUCKETS,
+        REWARD_SCORE_SCALE,
+        SLOT_WEIGHTED_TWAP_BUCKETS,
+    },
+    feature_gate::{
+        feature_name,
+        Feature,
+        FeatureGateAccount,
+        FeatureSwitch,
+    },
+    fee_vault::FeeVaultAccount,
+    feed_registry::FeedRegistryAccount,
+    mapping::{
+        MappingAccount,
+        ProductIter,
+    },
+    mapping_v2::{
+        symbol_hash,
+        MappingAccountV2,
+        MappingEntry,
+    },
+    multisig::{
+        MultisigAccount,
+        MAX_MULTISIG_SIGNERS,
+    },
+    permission::{
+        AccumulatorEmitter,
+        PermissionAccount,
+        PermissionTimelock,
+    },
     price::{
+        FeedKind,
+        FeedTombstoneMessage,
+        LatestPrice,
         PriceAccount,
         PriceAccountFlags,
         PriceComponent,
         PriceEma,
         PriceInfo,
+        ProductMetadataMessage,
+        PublisherPrice,
+        PublisherPricesMessage,
         PythOracleSerialize,
-        MAX_FEED_INDEX,
     },
     product::{
-        update_product_metadata,
+        get_product_metadata,
+        get_product_metadata_entries,
         ProductAccount,
     },
+    programdata::{
+        check_is_upgrade_authority_for_program,
+        check_is_upgradeable_program,
+        load_programdata_for_program,
+        ProgramdataAccount,
+    },
+    publisher_linkage::{
+        PublisherLink,
+        PublisherLinkageAccount,
+    },
+    publisher_registry::PublisherRegistryAccount,
+    rewards_ledger::RewardsLedgerAccount,
+    snapshot::SnapshotAccount,
+};
+#[cfg(not(feature = "wasm"))]
+pub use {
+    extensions::{
+        get_extension_mut,
+        get_price_and_extension_mut,
+        invalidate_quote_order,
+        realign_publisher_entries,
+        reserve_extension_space,
+        reward_score,
+        update_checksum,
+        DEFAULT_AGGREGATION_DELAY,
+        MAX_AGGREGATION_DELAY,
+    },
+    price::MAX_FEED_INDEX,
+    product::{
+        get_instruction_metadata,
+        patch_product_metadata,
+        update_product_metadata,
+        validate_product_metadata,
+        PRODUCT_METADATA_MAX_KEY_LEN,
+        PRODUCT_METADATA_MAX_VALUE_LEN,
+        PRODUCT_METADATA_REQUIRED_KEYS,
+    },
 };
 
 // PDA seeds for accounts.
@@ -75,9 +261,80 @@ pub const PERMISSIONS_SEED: &str = "permissions";
 /// The update price instruction can optionally invoke another program via CPI. The
 /// CPI will be signed with the PDA `[UPD_PRICE_WRITE_SEED, invoked_program_public_key]`
 /// such that the caller can authenticate its origin.
+#[cfg(not(feature = "wasm"))]
 pub const UPD_PRICE_WRITE_SEED: &str = "upd_price_write";
 
+/// Snapshots of price accounts are stored under the PDA
+/// `[SNAPSHOT_SEED, price_account_public_key, slot (little endian)]`.
+#[cfg(not(feature = "wasm"))]
+pub const SNAPSHOT_SEED: &str = "snapshot";
+
+/// Community products and price accounts are listed without governance under the PDA
+/// `[COMMUNITY_SEED, creator_public_key, seed]`, each creator has its own namespace.
+pub const COMMUNITY_SEED: &str = "community";
+
+/// There is a single feed registry under `FEED_REGISTRY_SEED`, see `FeedRegistryAccount`.
+pub const FEED_REGISTRY_SEED: &str = "feed_registry";
+
+/// There is a single publisher registry under `PUBLISHER_REGISTRY_SEED`, see
+/// `PublisherRegistryAccount`.
+pub const PUBLISHER_REGISTRY_SEED: &str = "publisher_registry";
+
+/// There is a single publisher linkage account under `PUBLISHER_LINKAGE_SEED`, see
+/// `PublisherLinkageAccount`.
+pub const PUBLISHER_LINKAGE_SEED: &str = "publisher_linkage";
+
+/// There is a single feature gate under `FEATURE_GATE_SEED`, see `FeatureGateAccount`.
+pub const FEATURE_GATE_SEED: &str = "feature_gate";
+
+/// There is a single audit log under `AUDIT_LOG_SEED`, see `AuditLogAccount`.
+pub const AUDIT_LOG_SEED: &str = "audit_log";
+
+/// There is a single fee vault under `FEE_VAULT_SEED`, see `FeeVaultAccount`.
+pub const FEE_VAULT_SEED: &str = "fee_vault";
+
+/// There is a single aggregation config under `AGGREGATION_CONFIG_SEED`, see
+/// `AggregationConfigAccount`.
+pub const AGGREGATION_CONFIG_SEED: &str = "aggregation_config";
+
+/// Every publisher has a rewards ledger under `[REWARDS_LEDGER_SEED, publisher_public_key]`, see
+/// `RewardsLedgerAccount`.
+pub const REWARDS_LEDGER_SEED: &str = "rewards_ledger";
+
+/// Products created by `InitProductPda` are stored under the PDA `[PRODUCT_SEED, symbol]`, see
+/// `find_product_pda`.
+pub const PRODUCT_SEED: &str = "product";
+
+/// Price accounts created by `InitPricePda` are stored under the PDA
+/// `[PRICE_SEED, product_public_key, price_type (little endian)]`, see `find_price_pda`.
+pub const PRICE_SEED: &str = "price";
+
+/// Address and bump seed of the product account `InitProductPda` creates for `symbol`, `None` if
+/// the symbol is longer than a seed.
+pub fn find_product_pda(program_id: &Pubkey, symbol: &[u8]) -> Option<(Pubkey, u8)> {
+    Pubkey::try_find_program_address(&[PRODUCT_SEED.as_bytes(), symbol], program_id)
+}
+
+/// Address and bump seed of the price account of kind `feed_kind` that `InitPricePda` creates for
+/// `product`. Together with `find_product_pda`, the address of a feed follows from its symbol and
+/// kind.
+pub fn find_price_pda(program_id: &Pubkey, product: &Pubkey, feed_kind: FeedKind) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PRICE_SEED.as_bytes(),
+            &product.to_bytes(),
+            &(feed_kind as u32).to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Zeroable, Pod)]
 pub struct AccountHeader {
     pub magic_number: u32,
@@ -0,0 +1,45 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_ACCESS_CONTROL,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Maximum number of readers of an access control account.
+pub const MAX_ACCESS_CONTROL_READERS: usize = 64;
+
+/// Readers allowed to consume the feeds pointing to the account in their `AccessControl`
+/// extension through `AssertPriceCondition`, maintained with `UpdAccessControl`. A reader is a
+/// wallet or a PDA of a consumer program, it proves its identity by signing the instruction.
+/// Several feeds of a data provider can share the same list.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AccessControlAccount {
+    pub header:      AccountHeader,
+    pub num_readers: u32,
+    pub unused_:     u32,
+    /// Only the first `num_readers` keys are readers
+    pub readers:     [Pubkey; MAX_ACCESS_CONTROL_READERS],
+}
+
+impl PythAccount for AccessControlAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_ACCESS_CONTROL;
+    const INITIAL_SIZE: u32 = size_of::<AccessControlAccount>() as u32;
+}
+
+impl AccessControlAccount {
+    pub fn readers(&self) -> &[Pubkey] {
+        &self.readers[..(self.num_readers as usize).min(MAX_ACCESS_CONTROL_READERS)]
+    }
+
+    pub fn is_reader(&self, key: &Pubkey) -> bool {
+        self.readers().contains(key)
+    }
+}
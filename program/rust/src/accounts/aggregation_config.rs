@@ -0,0 +1,98 @@
+use {
+    super::{
+        AccountHeader,
+        PriceAccount,
+        PythAccount,
+        AGGREGATION_CONFIG_SEED,
+    },
+    crate::{
+        c_oracle_header::{
+            MAX_CI_DIVISOR,
+            PC_ACCTYPE_AGGREGATION_CONFIG,
+            PRICE_ACCOUNT_DEFAULT_MIN_PUB,
+        },
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::pyth_assert,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Defaults of the aggregation shared by the feeds that don't set their own, maintained by
+/// governance with `SetAggregationConfig`, so that changing a default doesn't require updating
+/// every price account. The feeds following the `SharedSettings` aggregate with it and require it
+/// in `UpdPrice`, the other feeds aggregate with the defaults of the program. A value of 0 keeps
+/// the default of the program. There is a single
+/// aggregation config under `AGGREGATION_CONFIG_SEED`, created by the first
+/// `SetAggregationConfig`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AggregationConfigAccount {
+    pub header:         AccountHeader,
+    /// Quotes with a confidence above their price divided by this divisor are ignored, instead of
+    /// `MAX_CI_DIVISOR`
+    pub max_ci_divisor: i64,
+    /// Age in slots of the quotes that count in an aggregation, for the feeds with a
+    /// `max_latency_` of 0
+    pub max_latency:    u8,
+    /// Quorum of an aggregation, for the feeds whose `min_pub_` is still
+    /// `PRICE_ACCOUNT_DEFAULT_MIN_PUB`
+    pub min_pub:        u8,
+    pub unused_:        [u8; 6],
+}
+
+impl PythAccount for AggregationConfigAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_AGGREGATION_CONFIG;
+    const INITIAL_SIZE: u32 = size_of::<AggregationConfigAccount>() as u32;
+}
+
+impl AggregationConfigAccount {
+    /// Divisor of the confidence to price ratio above which the quotes are ignored.
+    pub fn max_ci_divisor(&self) -> i64 {
+        if self.max_ci_divisor != 0 {
+            self.max_ci_divisor
+        } else {
+            MAX_CI_DIVISOR
+        }
+    }
+
+    /// Replace the quorum and the maximum latency of the feed by the shared defaults, where the
+    /// feed didn't set its own.
+    pub fn apply_defaults(&self, price_data: &mut PriceAccount) {
+        if price_data.max_latency_ == 0 {
+            price_data.max_latency_ = self.max_latency;
+        }
+        if price_data.min_pub_ == PRICE_ACCOUNT_DEFAULT_MIN_PUB && self.min_pub != 0 {
+            price_data.min_pub_ = self.min_pub;
+        }
+    }
+
+    /// Whether `account` is the aggregation config of the program. Only the program can create
+    /// accounts of this type, at the address of `AGGREGATION_CONFIG_SEED`.
+    pub fn is_aggregation_config(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.try_borrow_data().map_or(false, |data| {
+                load_account_data::<AggregationConfigAccount>(&data).is_ok()
+            })
+    }
+
+    /// Check that `account` is at the address of `AGGREGATION_CONFIG_SEED`, for the feeds that
+    /// require the aggregation config.
+    pub fn check_address(program_id: &Pubkey, account: &AccountInfo) -> Result<(), ProgramError> {
+        let (config_pda_address, _) =
+            Pubkey::find_program_address(&[AGGREGATION_CONFIG_SEED.as_bytes()], program_id);
+        pyth_assert(
+            config_pda_address == *account.key,
+            OracleError::InvalidPda.into(),
+        )
+    }
+}
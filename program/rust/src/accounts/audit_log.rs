@@ -0,0 +1,82 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::{
+            PC_ACCTYPE_AUDIT_LOG,
+            PC_MAGIC,
+        },
+        deserialize::load_account_as,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Number of records the audit log keeps, the oldest ones are overwritten first.
+pub const AUDIT_LOG_CAPACITY: usize = 128;
+
+/// Ring buffer of the last `AUDIT_LOG_CAPACITY` governance instructions that succeeded, created
+/// with `InitAuditLog`. A governance instruction is recorded when the audit log follows its
+/// accounts, after the signers of a multisig, see `OracleCommand::audit_target`. There is a
+/// single audit log under `AUDIT_LOG_SEED`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AuditLogAccount {
+    pub header:      AccountHeader,
+    /// Records ever appended, the next one goes to `num_records % AUDIT_LOG_CAPACITY`
+    pub num_records: u64,
+    pub records:     [AuditRecord; AUDIT_LOG_CAPACITY],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct AuditRecord {
+    pub slot:    u64,
+    /// `OracleCommand` of the instruction
+    pub command: i32,
+    pub unused_: u32,
+    /// Funding account of the instruction, the multisig account when it signed
+    pub actor:   Pubkey,
+    /// Account the instruction governs, e.g. the price account of `AddPublisher` or the
+    /// permissions account of `UpdPermissions`
+    pub target:  Pubkey,
+}
+
+impl PythAccount for AuditLogAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_AUDIT_LOG;
+    const INITIAL_SIZE: u32 = size_of::<AuditLogAccount>() as u32;
+}
+
+impl AuditLogAccount {
+    /// Whether `account` holds the audit log of the program, without failing on other accounts.
+    pub fn is_audit_log(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.data_len() >= Self::MINIMUM_SIZE
+            && load_account_as::<AccountHeader>(account)
+                .map(|header| {
+                    header.magic_number == PC_MAGIC && header.account_type == Self::ACCOUNT_TYPE
+                })
+                .unwrap_or(false)
+    }
+
+    pub fn append(&mut self, record: AuditRecord) {
+        self.records[(self.num_records % AUDIT_LOG_CAPACITY as u64) as usize] = record;
+        self.num_records += 1;
+    }
+
+    /// Records still in the log, from the oldest to the latest.
+    pub fn records(&self) -> impl Iterator<Item = &AuditRecord> {
+        let len = self.num_records.min(AUDIT_LOG_CAPACITY as u64) as usize;
+        let start = (self.num_records - len as u64) as usize;
+        (start..start + len).map(move |i| &self.records[i % AUDIT_LOG_CAPACITY])
+    }
+}
@@ -0,0 +1,128 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_CONSISTENCY_CHECK,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    num_derive::{
+        FromPrimitive,
+        ToPrimitive,
+    },
+    num_traits::FromPrimitive as _,
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Largest number of feeds a consistency check links.
+pub const MAX_CONSISTENCY_FEEDS: usize = 3;
+
+/// Relation between the prices of related feeds, e.g. BTC/USD and USD/BTC, created by governance
+/// with `InitConsistencyCheck`. Anyone can crank `CheckConsistency` to compare the aggregates of
+/// the feeds, which raises the `ConsistencyFlag` extension of every feed of the check when the
+/// relation is off by more than `tolerance_bps`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ConsistencyCheckAccount {
+    pub header:             AccountHeader,
+    /// `ConsistencyKind` of the relation
+    pub kind:               u32,
+    /// Largest deviation in basis points between the two sides of the relation
+    pub tolerance_bps:      u32,
+    /// Price accounts of the relation, the kind says how many are used
+    pub feeds:              [Pubkey; MAX_CONSISTENCY_FEEDS],
+    /// Slot of the last crank that compared trading aggregates
+    pub last_check_slot:    u64,
+    /// Deviation in basis points measured by that crank
+    pub last_deviation_bps: u64,
+    /// Cranks that found the feeds inconsistent
+    pub num_violations:     u64,
+}
+
+impl PythAccount for ConsistencyCheckAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_CONSISTENCY_CHECK;
+    const INITIAL_SIZE: u32 = size_of::<ConsistencyCheckAccount>() as u32;
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ConsistencyKind {
+    /// `feeds[0] * feeds[1] == 1`, e.g. BTC/USD and USD/BTC
+    Inverse    = 1,
+    /// `feeds[0] * feeds[1] == feeds[2]`, e.g. BTC/ETH, ETH/USD and BTC/USD
+    Triangular = 2,
+}
+
+impl ConsistencyKind {
+    pub fn num_feeds(&self) -> usize {
+        match self {
+            ConsistencyKind::Inverse => 2,
+            ConsistencyKind::Triangular => 3,
+        }
+    }
+}
+
+impl ConsistencyCheckAccount {
+    /// Feeds of the relation, empty if the kind is unknown.
+    pub fn linked_feeds(&self) -> &[Pubkey] {
+        let len = ConsistencyKind::from_u32(self.kind).map_or(0, |kind| kind.num_feeds());
+        &self.feeds[..len]
+    }
+
+    /// Deviation in basis points of the relation for the `(price, exponent)` of each of its
+    /// feeds, saturating at `u64::MAX`. `None` if the kind is unknown or a price is missing.
+    pub fn deviation_bps(&self, prices: &[(i64, i32)]) -> Option<u64> {
+        let kind = ConsistencyKind::from_u32(self.kind)?;
+        if prices.len() != kind.num_feeds() {
+            return None;
+        }
+        let ((price_0, exponent_0), (price_1, exponent_1)) = (prices[0], prices[1]);
+        let product = (
+            i128::from(price_0) * i128::from(price_1),
+            exponent_0.checked_add(exponent_1)?,
+        );
+        let expected = match kind {
+            ConsistencyKind::Inverse => (1, 0),
+            ConsistencyKind::Triangular => (i128::from(prices[2].0), prices[2].1),
+        };
+        Some(relative_deviation_bps(product, expected))
+    }
+}
+
+/// Deviation in basis points of `value` from `expected`, both given as a mantissa and an
+/// exponent.
+fn relative_deviation_bps(
+    (value, value_exponent): (i128, i32),
+    (expected, expected_exponent): (i128, i32),
+) -> u64 {
+    let shift = value_exponent.abs_diff(expected_exponent);
+    let (value, expected) = if value_exponent >= expected_exponent {
+        to_common_exponent(value, expected, shift)
+    } else {
+        let (expected, value) = to_common_exponent(expected, value, shift);
+        (value, expected)
+    };
+
+    if expected == 0 {
+        return if value == 0 { 0 } else { u64::MAX };
+    }
+    value
+        .abs_diff(expected)
+        .checked_mul(10_000)
+        .and_then(|deviation| u64::try_from(deviation / expected.unsigned_abs()).ok())
+        .unwrap_or(u64::MAX)
+}
+
+/// `larger`, whose exponent is `shift` above the exponent of `smaller`, and `smaller` in the same
+/// exponent: the exponent of `smaller`, or the exponent of `larger` with `smaller` truncated when
+/// scaling `larger` overflows.
+fn to_common_exponent(larger: i128, smaller: i128, shift: u32) -> (i128, i128) {
+    let scale = 10i128.checked_pow(shift);
+    match scale.and_then(|scale| larger.checked_mul(scale)) {
+        Some(larger) => (larger, smaller),
+        None => (larger, scale.map_or(0, |scale| smaller / scale)),
+    }
+}
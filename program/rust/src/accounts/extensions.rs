@@ -0,0 +1,2371 @@
+//! Optional extensions appended to price accounts.
+//!
+//! The layout of `PriceAccount` is shared with the C aggregation code, so adding a field to it
+//! requires migrating every price account. Optional per-feed features instead live in a region
+//! that starts right after the fixed layout. This region is a list of type-length-value entries
+//! (similar to SPL token-2022 extensions) with the following schema :
+//! `[ExtensionHeader, value, padding, ExtensionHeader, value, padding, ...]`
+//!
+//! Every entry starts on an 8 bytes boundary relative to the start of the account. The list ends
+//! at the end of the account data or at the first header with type `Uninitialized`. Since newly
+//! allocated account space is zeroed, growing the account never changes the list.
+//!
+//! New per-feed features should be added as an extension rather than as a field of
+//! `PriceAccount`, read with `get_extension` and written with `set_extension`. The price accounts
+//! created by the program reserve `EXTENSIONS_RESERVED_SPACE` bytes for the region, so that the
+//! first extensions of a feed fit without growing its account. Accounts created by their funding
+//! account only have the space it allocated, and grow with `reserve_extension_space`.
+
+use {
+    super::{
+        PriceAccount,
+        PriceAccountFlags,
+        PriceComponent,
+        PriceInfo,
+        PublisherLink,
+    },
+    crate::{
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+        },
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::try_convert,
+    },
+    bytemuck::{
+        pod_read_unaligned,
+        try_from_bytes,
+        try_from_bytes_mut,
+        Pod,
+        Zeroable,
+    },
+    num_derive::{
+        FromPrimitive,
+        ToPrimitive,
+    },
+    num_traits::FromPrimitive as _,
+    solana_program::{
+        hash::{
+            hashv,
+            Hash,
+        },
+        pubkey::Pubkey,
+    },
+    std::mem::{
+        align_of,
+        size_of,
+    },
+};
+#[cfg(not(feature = "wasm"))]
+use {
+    crate::utils::{
+        get_rent,
+        pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+    },
+};
+
+/// Offset of the extension region in the price account data.
+pub const EXTENSIONS_OFFSET: usize = size_of::<PriceAccount>();
+
+/// Entries of the extension region are aligned to this many bytes.
+pub const EXTENSION_ALIGNMENT: usize = 8;
+
+/// Bytes of extension region allocated with the price accounts created by the program, see
+/// `PriceAccount::NEW_ACCOUNT_SPACE`.
+pub const EXTENSIONS_RESERVED_SPACE: usize = 512;
+
+/// Registry of the extension type ids.
+/// WARNING : NEW EXTENSION TYPES SHOULD BE ADDED AT THE END OF THE LIST
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum ExtensionType {
+    /// Marks the end of the extension list
+    Uninitialized           = 0,
+    /// Pointer to an account holding the price history of the feed
+    HistoryPointer          = 1,
+    /// Best bid and ask prices of the feed
+    BidAsk                  = 2,
+    /// Transaction landing latency of the publishers of the feed
+    LandingLatency          = 3,
+    /// Number of reads of the feed per epoch
+    ReadStats               = 4,
+    /// Limit on the rate of the updates of every publisher of the feed
+    RateLimit               = 5,
+    /// Number of slots between the updates of the publishers and the aggregation
+    AggregationDelay        = 6,
+    /// deprecated
+    PublishingSlotTolerance = 7,
+    /// Number of slots with a trading aggregate over a sliding window of recent slots
+    AggregationCounts       = 8,
+    /// Program checking every aggregate of the feed
+    SanityProgram           = 9,
+    /// Limit on the number of quotes of a single entity counted in the aggregates of the feed
+    PublisherWeightCap      = 10,
+    /// Order of the quotes of the feed, maintained to aggregate without sorting them
+    QuoteOrder              = 11,
+    /// Price account replacing the feed after its deprecation
+    PriceSuccessor          = 12,
+    /// Access control account listing the readers allowed to consume the feed
+    AccessControl           = 13,
+    /// Landing latency of the updates of the feed over its recent aggregations
+    LatencyStats            = 14,
+    /// Lowest and highest aggregate of the feed over its recent slots
+    PriceExtrema            = 15,
+    /// Smallest confidence interval of the aggregates of the feed
+    ConfFloor               = 16,
+    /// Reason the last aggregation of the feed left it without a price
+    StatusReason            = 17,
+    /// Staking program of the feed and the stake of its publishers
+    PublisherStake          = 18,
+    /// Key that listed a community feed
+    FeedCreator             = 19,
+    /// Largest number of publishers of the feed
+    PublisherCap            = 20,
+    /// Aggregate of the feed converted to another quote currency
+    QuoteConversion         = 21,
+    /// Whether the updates of the feed skip aggregating again within a slot
+    AggregateCache          = 22,
+    /// Number of quotes of the feed sampled in every aggregation
+    PublisherSampling       = 23,
+    /// Lamports paid to the fee vault by every update of the feed
+    UpdateFee               = 24,
+    /// Reward scores of the publishers of the feed not claimed yet
+    PublisherRewards        = 25,
+    /// Consistency check that found the feed inconsistent with related feeds
+    ConsistencyFlag         = 26,
+    /// Number of updates accepted from the publishers of the feed
+    UpdateCount             = 27,
+    /// Slot-weighted average of the aggregates, published instead of the EMA if selected
+    SlotWeightedTwap        = 28,
+    /// Unix timestamp of the latest quote of every publisher of the feed
+    PublisherTimestamps     = 29,
+    /// Deviation and heartbeat that trigger publishing a new aggregate of the feed
+    PublishThreshold        = 30,
+    /// Program notified of the status transitions of the aggregate of the feed
+    StatusObserver          = 31,
+    /// Hash of the fixed layout of the price account, checked by every instruction
+    Checksum                = 32,
+    /// Sequence number of the latest quote of every publisher of the feed
+    PublisherSequences      = 33,
+    /// How the aggregation combines the statuses the publishers supplied with their quotes
+    StatusPolicy            = 34,
+    /// Open interest and volume submitted by the publishers of the feed and their medians
+    AuxData                 = 35,
+    /// Windows missed by the publishers of the feed, leaving the stale ones out of the quorum
+    PublisherActivity       = 36,
+    /// Program that publishers forward their quotes through
+    UpdaterProgram          = 37,
+    /// Whether the feed aggregates with the shared aggregation config and feature gate
+    SharedSettings          = 38,
+}
+
+impl ExtensionType {
+    /// `extension_space` of the extension struct of this type, `None` for `Uninitialized`.
+    #[cfg(any(test, feature = "library"))]
+    pub fn space(&self) -> Option<usize> {
+        match self {
+            ExtensionType::Uninitialized => None,
+            ExtensionType::HistoryPointer => Some(extension_space::<PriceHistoryPointer>()),
+            ExtensionType::BidAsk => Some(extension_space::<BidAsk>()),
+            ExtensionType::LandingLatency => Some(extension_space::<PublisherLandingLatency>()),
+            ExtensionType::ReadStats => Some(extension_space::<ReadStats>()),
+            ExtensionType::RateLimit => Some(extension_space::<PublisherRateLimit>()),
+            ExtensionType::AggregationDelay => Some(extension_space::<AggregationDelay>()),
+            ExtensionType::PublishingSlotTolerance => {
+                Some(extension_space::<PublishingSlotTolerance>())
+            }
+            ExtensionType::AggregationCounts => Some(extension_space::<AggregationCounts>()),
+            ExtensionType::SanityProgram => Some(extension_space::<SanityProgram>()),
+            ExtensionType::PublisherWeightCap => Some(extension_space::<PublisherWeightCap>()),
+            ExtensionType::QuoteOrder => Some(extension_space::<QuoteOrder>()),
+            ExtensionType::PriceSuccessor => Some(extension_space::<PriceSuccessor>()),
+            ExtensionType::AccessControl => Some(extension_space::<AccessControl>()),
+            ExtensionType::LatencyStats => Some(extension_space::<LatencyStats>()),
+            ExtensionType::PriceExtrema => Some(extension_space::<PriceExtrema>()),
+            ExtensionType::ConfFloor => Some(extension_space::<ConfFloor>()),
+            ExtensionType::StatusReason => Some(extension_space::<StatusReason>()),
+            ExtensionType::PublisherStake => Some(extension_space::<PublisherStake>()),
+            ExtensionType::FeedCreator => Some(extension_space::<FeedCreator>()),
+            ExtensionType::PublisherCap => Some(extension_space::<PublisherCap>()),
+            ExtensionType::QuoteConversion => Some(extension_space::<QuoteConversion>()),
+            ExtensionType::AggregateCache => Some(extension_space::<AggregateCache>()),
+            ExtensionType::PublisherSampling => Some(extension_space::<PublisherSampling>()),
+            ExtensionType::UpdateFee => Some(extension_space::<UpdateFee>()),
+            ExtensionType::PublisherRewards => Some(extension_space::<PublisherRewards>()),
+            ExtensionType::ConsistencyFlag => Some(extension_space::<ConsistencyFlag>()),
+            ExtensionType::UpdateCount => Some(extension_space::<UpdateCount>()),
+            ExtensionType::SlotWeightedTwap => Some(extension_space::<SlotWeightedTwap>()),
+            ExtensionType::PublisherTimestamps => Some(extension_space::<PublisherTimestamps>()),
+            ExtensionType::PublishThreshold => Some(extension_space::<PublishThreshold>()),
+            ExtensionType::StatusObserver => Some(extension_space::<StatusObserver>()),
+            ExtensionType::Checksum => Some(extension_space::<Checksum>()),
+            ExtensionType::PublisherSequences => Some(extension_space::<PublisherSequences>()),
+            ExtensionType::StatusPolicy => Some(extension_space::<StatusPolicy>()),
+            ExtensionType::AuxData => Some(extension_space::<AuxData>()),
+            ExtensionType::PublisherActivity => Some(extension_space::<PublisherActivity>()),
+            ExtensionType::UpdaterProgram => Some(extension_space::<UpdaterProgram>()),
+            ExtensionType::SharedSettings => Some(extension_space::<SharedSettings>()),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ExtensionHeader {
+    /// One of `ExtensionType`, kept as a raw integer so that entries written by a newer program
+    /// version can be skipped
+    pub extension_type: u32,
+    /// Length of the value in bytes, excluding padding
+    pub length:         u32,
+}
+
+/// Attaches an extension type id to the struct stored as the value of an extension entry.
+/// Values are stored with 8 bytes alignment, so implementors can't require more than that.
+pub trait PriceAccountExtension: Pod {
+    const EXTENSION_TYPE: ExtensionType;
+}
+
+/// Pointer to an account holding the price history of the feed.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PriceHistoryPointer {
+    pub history_account: Pubkey,
+}
+
+impl PriceAccountExtension for PriceHistoryPointer {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::HistoryPointer;
+}
+
+/// Best bid and ask prices of the feed, in the exponent of the price account.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct BidAsk {
+    pub bid_price: i64,
+    pub ask_price: i64,
+}
+
+impl PriceAccountExtension for BidAsk {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::BidAsk;
+}
+
+/// `LandingLatency::ema` is in thousandths of a slot.
+pub const LANDING_LATENCY_EMA_SCALE: u64 = 1000;
+
+/// Every update moves the landing latency EMA by 1/LANDING_LATENCY_EMA_WINDOW of the difference
+/// with the new sample.
+pub const LANDING_LATENCY_EMA_WINDOW: u64 = 16;
+
+/// Transaction landing latency of a publisher, i.e. the number of slots between the
+/// `publishing_slot` of its updates and the slot they landed in.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct LandingLatency {
+    /// Publisher the statistics belong to. Components move when publishers are added or removed,
+    /// so the entry starts over when it doesn't match the component at the same index.
+    pub publisher: Pubkey,
+    /// Latency of the last update, in slots
+    pub latest:    u64,
+    /// Exponential moving average of the latency, see `LANDING_LATENCY_EMA_SCALE`
+    pub ema:       u64,
+}
+
+impl LandingLatency {
+    pub fn update(&mut self, publisher: &Pubkey, latency: u64) {
+        let sample = latency.saturating_mul(LANDING_LATENCY_EMA_SCALE);
+        if self.publisher != *publisher {
+            self.publisher = *publisher;
+            self.ema = sample;
+        } else {
+            // The result is between self.ema and sample, so it fits in a u64
+            self.ema = ((u128::from(self.ema) * u128::from(LANDING_LATENCY_EMA_WINDOW - 1)
+                + u128::from(sample))
+                / u128::from(LANDING_LATENCY_EMA_WINDOW)) as u64;
+        }
+        self.latest = latency;
+    }
+}
+
+/// Landing latency of every component of the feed, indexed like `PriceAccount::comp_`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherLandingLatency {
+    pub components: [LandingLatency; PC_NUM_COMP as usize],
+}
+
+impl PriceAccountExtension for PublisherLandingLatency {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::LandingLatency;
+}
+
+/// Number of reads of the feed by `GetPrice`, per epoch. Feeds opt in with `EnableReadStats`,
+/// which lets governance spot unused feeds and heavily used ones.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ReadStats {
+    /// Epoch of the last read
+    pub epoch:                u64,
+    /// Reads in `epoch`
+    pub reads:                u64,
+    /// Reads in the epoch before `epoch`
+    pub previous_epoch_reads: u64,
+}
+
+impl ReadStats {
+    pub fn record_read(&mut self, epoch: u64) {
+        if epoch != self.epoch {
+            self.previous_epoch_reads = if epoch == self.epoch.saturating_add(1) {
+                self.reads
+            } else {
+                0
+            };
+            self.epoch = epoch;
+            self.reads = 0;
+        }
+        self.reads = self.reads.saturating_add(1);
+    }
+}
+
+impl PriceAccountExtension for ReadStats {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::ReadStats;
+}
+
+/// Slot of the last accepted update of a publisher.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct RateLimitEntry {
+    /// Publisher the entry belongs to. The entries move with the components, see
+    /// `realign_publisher_entries`, an entry that doesn't match the component at the same index
+    /// doesn't apply.
+    pub publisher:        Pubkey,
+    pub last_update_slot: u64,
+}
+
+/// Limits every publisher of the feed to one accepted update every `window` slots.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherRateLimit {
+    /// Number of slots between two updates of a publisher, 0 and 1 both allow one update per slot
+    pub window:     u64,
+    /// Indexed like `PriceAccount::comp_`
+    pub components: [RateLimitEntry; PC_NUM_COMP as usize],
+}
+
+impl PublisherRateLimit {
+    /// Whether the publisher of component `index` can contribute an update in `slot`.
+    pub fn allows_update(&self, index: usize, publisher: &Pubkey, slot: u64) -> bool {
+        let entry = &self.components[index];
+        entry.publisher != *publisher
+            || slot >= entry.last_update_slot.saturating_add(self.window.max(1))
+    }
+
+    pub fn record_update(&mut self, index: usize, publisher: &Pubkey, slot: u64) {
+        self.components[index] = RateLimitEntry {
+            publisher:        *publisher,
+            last_update_slot: slot,
+        };
+    }
+
+    /// Move the entries to the components of their publishers after the components moved.
+    pub fn realign(&mut self, components: &[PriceComponent]) {
+        realign_entries(&mut self.components, components, |entry| entry.publisher);
+    }
+}
+
+impl PriceAccountExtension for PublisherRateLimit {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::RateLimit;
+}
+
+/// Aggregation delay of feeds without the `AggregationDelay` extension: the aggregate of a slot
+/// is computed by its first update, before the quote of that update is written, so it only
+/// covers the quotes that landed in earlier slots.
+#[cfg(not(feature = "wasm"))]
+pub const DEFAULT_AGGREGATION_DELAY: u64 = 1;
+
+/// Largest supported aggregation delay. The price account only holds the latest quote of every
+/// publisher, so the aggregation can't leave out quotes that landed more than a slot ago.
+#[cfg(not(feature = "wasm"))]
+pub const MAX_AGGREGATION_DELAY: u64 = 1;
+
+/// Number of slots between the updates of the publishers and the aggregate including them.
+/// With a delay of 0, every update aggregates the feed again right after writing its quote, so
+/// the aggregate of a slot covers the quotes that landed in that slot so far.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AggregationDelay {
+    pub slots: u64,
+}
+
+impl PriceAccountExtension for AggregationDelay {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::AggregationDelay;
+}
+
+/// Deprecated, publishing slots ahead of the clock are always rejected. Price accounts that
+/// held a tolerance keep the extension, but the program doesn't read it anymore.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublishingSlotTolerance {
+    pub slots: u64,
+}
+
+impl PriceAccountExtension for PublishingSlotTolerance {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublishingSlotTolerance;
+}
+
+/// Number of buckets of the sliding window of `AggregationCounts`.
+pub const AGGREGATION_COUNT_BUCKETS: usize = 16;
+
+/// Number of slots with a trading aggregate over a sliding window of recent slots, so that
+/// monitoring can notice a feed aggregating in fewer and fewer slots before it goes stale. The
+/// window is a ring of `AGGREGATION_COUNT_BUCKETS` buckets of `bucket_slots` slots each, bucket
+/// `slot / bucket_slots` being stored at that index modulo the number of buckets, so the window
+/// slides one bucket at a time.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AggregationCounts {
+    /// Width of the buckets in slots, 0 doesn't count aggregations
+    pub bucket_slots: u64,
+    /// Last slot counted
+    pub last_slot:    u64,
+    /// Slots counted in every bucket of the window
+    pub counts:       [u64; AGGREGATION_COUNT_BUCKETS],
+}
+
+impl AggregationCounts {
+    /// Number of slots of the window, 0 if aggregations aren't counted.
+    pub fn window_slots(&self) -> u64 {
+        self.bucket_slots
+            .saturating_mul(AGGREGATION_COUNT_BUCKETS as u64)
+    }
+
+    fn bucket_index(bucket: u64) -> usize {
+        (bucket % AGGREGATION_COUNT_BUCKETS as u64) as usize
+    }
+
+    /// First bucket of the window ending with the bucket of `slot`.
+    fn first_bucket(&self, slot: u64) -> u64 {
+        (slot / self.bucket_slots).saturating_sub(AGGREGATION_COUNT_BUCKETS as u64 - 1)
+    }
+
+    /// Count an aggregation in `slot`. Aggregating again in the same slot doesn't count.
+    pub fn record_aggregation(&mut self, slot: u64) {
+        if self.bucket_slots == 0 || slot <= self.last_slot {
+            return;
+        }
+        let bucket = slot / self.bucket_slots;
+        // Clear the buckets the window moved to since the last count
+        let first_cleared = (self.last_slot / self.bucket_slots + 1).max(self.first_bucket(slot));
+        for cleared in first_cleared..=bucket {
+            self.counts[Self::bucket_index(cleared)] = 0;
+        }
+        self.counts[Self::bucket_index(bucket)] += 1;
+        self.last_slot = slot;
+    }
+
+    /// Number of slots of the window ending at `slot` that were counted, and the number of slots
+    /// of that window up to `slot`. The window starts at the first slot of its first bucket, so
+    /// it only spans `window_slots` at the end of a bucket.
+    pub fn window_counts(&self, slot: u64) -> (u64, u64) {
+        if self.bucket_slots == 0 {
+            return (0, 0);
+        }
+        let last_bucket = self.last_slot / self.bucket_slots;
+        let first_bucket = self
+            .first_bucket(slot)
+            .max(self.first_bucket(self.last_slot));
+        let aggregated_slots = (first_bucket..=last_bucket)
+            .map(|bucket| self.counts[Self::bucket_index(bucket)])
+            .sum();
+        let window_slots = slot
+            .saturating_sub(first_bucket.saturating_mul(self.bucket_slots))
+            .saturating_add(1);
+        (aggregated_slots, window_slots)
+    }
+}
+
+impl PriceAccountExtension for AggregationCounts {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::AggregationCounts;
+}
+
+/// Number of buckets of the window of `PriceExtrema`.
+pub const PRICE_EXTREMA_BUCKETS: usize = 16;
+
+/// Lowest and highest trading aggregate over a sliding window of recent slots, so that consumers
+/// such as options protocols and liquidation engines can read extrema on chain without indexing
+/// every slot. The window is a ring of `PRICE_EXTREMA_BUCKETS` buckets of `bucket_slots` slots
+/// each, like `AggregationCounts`, so it slides one bucket at a time. An aggregate replaced in
+/// its slot still counts.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PriceExtrema {
+    /// Width of the buckets in slots, 0 doesn't track the extrema
+    pub bucket_slots: u64,
+    /// Slot of the last aggregate recorded
+    pub last_slot:    u64,
+    /// Lowest aggregate price of every bucket of the window, `i64::MAX` if it has none
+    pub min_prices:   [i64; PRICE_EXTREMA_BUCKETS],
+    /// Highest aggregate price of every bucket of the window, `i64::MIN` if it has none
+    pub max_prices:   [i64; PRICE_EXTREMA_BUCKETS],
+}
+
+impl PriceExtrema {
+    pub fn new(bucket_slots: u64) -> Self {
+        PriceExtrema {
+            bucket_slots,
+            last_slot: 0,
+            min_prices: [i64::MAX; PRICE_EXTREMA_BUCKETS],
+            max_prices: [i64::MIN; PRICE_EXTREMA_BUCKETS],
+        }
+    }
+
+    /// Number of slots of the window, 0 if the extrema aren't tracked.
+    pub fn window_slots(&self) -> u64 {
+        self.bucket_slots
+            .saturating_mul(PRICE_EXTREMA_BUCKETS as u64)
+    }
+
+    fn bucket_index(bucket: u64) -> usize {
+        (bucket % PRICE_EXTREMA_BUCKETS as u64) as usize
+    }
+
+    /// First bucket of the window ending with the bucket of `slot`.
+    fn first_bucket(&self, slot: u64) -> u64 {
+        (slot / self.bucket_slots).saturating_sub(PRICE_EXTREMA_BUCKETS as u64 - 1)
+    }
+
+    /// Record a trading aggregate `price` in `slot`.
+    pub fn record_aggregate(&mut self, slot: u64, price: i64) {
+        if self.bucket_slots == 0 || slot < self.last_slot {
+            return;
+        }
+        let bucket = slot / self.bucket_slots;
+        // Empty the buckets the window moved to since the last aggregate
+        let first_cleared = (self.last_slot / self.bucket_slots + 1).max(self.first_bucket(slot));
+        for cleared in first_cleared..=bucket {
+            self.min_prices[Self::bucket_index(cleared)] = i64::MAX;
+            self.max_prices[Self::bucket_index(cleared)] = i64::MIN;
+        }
+        let index = Self::bucket_index(bucket);
+        self.min_prices[index] = self.min_prices[index].min(price);
+        self.max_prices[index] = self.max_prices[index].max(price);
+        self.last_slot = slot;
+    }
+
+    /// Lowest and highest aggregate of the window ending at `slot`, `None` if it has no trading
+    /// aggregate, and the number of slots of that window up to `slot`. The window starts at the
+    /// first slot of its first bucket, like in `AggregationCounts::window_counts`.
+    pub fn window_extrema(&self, slot: u64) -> (Option<(i64, i64)>, u64) {
+        if self.bucket_slots == 0 {
+            return (None, 0);
+        }
+        let last_bucket = self.last_slot / self.bucket_slots;
+        let first_bucket = self
+            .first_bucket(slot)
+            .max(self.first_bucket(self.last_slot));
+        let (min_price, max_price) = (first_bucket..=last_bucket).map(Self::bucket_index).fold(
+            (i64::MAX, i64::MIN),
+            |(min_price, max_price), index| {
+                (
+                    min_price.min(self.min_prices[index]),
+                    max_price.max(self.max_prices[index]),
+                )
+            },
+        );
+        let window_slots = slot
+            .saturating_sub(first_bucket.saturating_mul(self.bucket_slots))
+            .saturating_add(1);
+        (
+            (min_price <= max_price).then_some((min_price, max_price)),
+            window_slots,
+        )
+    }
+}
+
+impl PriceAccountExtension for PriceExtrema {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PriceExtrema;
+}
+
+/// Number of aggregations `LatencyStats` can hold.
+pub const LATENCY_STATS_ENTRIES: usize = 16;
+
+/// `LatencyStats::mean_latency` is the mean latency in slots multiplied by this factor.
+pub const LATENCY_STATS_SCALE: u64 = 1000;
+
+/// Landing latency of the updates received between two aggregations.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct LatencySample {
+    pub num_updates:   u64,
+    /// Sum of the latencies of the updates, in slots
+    pub total_latency: u64,
+    /// Largest latency of an update, in slots
+    pub max_latency:   u64,
+}
+
+/// Landing latency of the updates of the feed, i.e. the number of slots between their
+/// `publishing_slot` and the slot they landed in, over its last `window` aggregations. The
+/// updates accumulate in `pending` and every aggregation closes it into the ring of samples,
+/// refreshing `mean_latency` and `max_latency` so that every team reads the same numbers.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct LatencyStats {
+    /// Number of aggregations of the window, at most `LATENCY_STATS_ENTRIES`, 0 doesn't track
+    /// latency
+    pub window:           u64,
+    /// Aggregations recorded so far, the sample of aggregation `i` is at index `i` modulo
+    /// `LATENCY_STATS_ENTRIES`
+    pub num_aggregations: u64,
+    /// Mean latency of the updates of the window, see `LATENCY_STATS_SCALE`
+    pub mean_latency:     u64,
+    /// Largest latency of an update of the window, in slots
+    pub max_latency:      u64,
+    /// Updates received since the last aggregation
+    pub pending:          LatencySample,
+    pub samples:          [LatencySample; LATENCY_STATS_ENTRIES],
+}
+
+impl LatencyStats {
+    pub fn new(window: u64) -> Self {
+        LatencyStats {
+            window,
+            ..LatencyStats::zeroed()
+        }
+    }
+
+    /// Record an update landing `latency` slots after its publishing slot.
+    pub fn record_update(&mut self, latency: u64) {
+        if self.window == 0 {
+            return;
+        }
+        self.pending.num_updates = self.pending.num_updates.saturating_add(1);
+        self.pending.total_latency = self.pending.total_latency.saturating_add(latency);
+        self.pending.max_latency = self.pending.max_latency.max(latency);
+    }
+
+    /// Close the updates received since the last aggregation into the window.
+    pub fn record_aggregation(&mut self) {
+        if self.window == 0 {
+            return;
+        }
+        self.samples[(self.num_aggregations % LATENCY_STATS_ENTRIES as u64) as usize] =
+            self.pending;
+        self.pending = LatencySample::zeroed();
+        self.num_aggregations = self.num_aggregations.saturating_add(1);
+
+        let window = self.window.min(self.num_aggregations);
+        let (num_updates, total_latency, max_latency) = (0..window)
+            .map(|i| {
+                let index = (self.num_aggregations - 1 - i) % LATENCY_STATS_ENTRIES as u64;
+                &self.samples[index as usize]
+            })
+            .fold((0u128, 0u128, 0u64), |(num, total, max), sample| {
+                (
+                    num + u128::from(sample.num_updates),
+                    total + u128::from(sample.total_latency),
+                    max.max(sample.max_latency),
+                )
+            });
+        self.mean_latency = if num_updates == 0 {
+            0
+        } else {
+            (total_latency * u128::from(LATENCY_STATS_SCALE) / num_updates)
+                .try_into()
+                .unwrap_or(u64::MAX)
+        };
+        self.max_latency = max_latency;
+    }
+}
+
+impl PriceAccountExtension for LatencyStats {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::LatencyStats;
+}
+
+/// Largest `ConfFloor::min_conf_bps`, a confidence interval as wide as the price.
+pub const MAX_CONF_FLOOR_BPS: u64 = 10_000;
+
+/// Smallest confidence interval of the aggregates of the feed. Thin feeds can aggregate a few
+/// agreeing quotes into an implausibly tight interval during quiet periods, the floor widens it
+/// after the aggregation, before the sanity program checks it. The floor is the larger of
+/// `min_conf` and `min_conf_bps` of the aggregate price.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ConfFloor {
+    /// Smallest confidence, in price units
+    pub min_conf:     u64,
+    /// Smallest confidence, in basis points of the absolute aggregate price, at most
+    /// `MAX_CONF_FLOOR_BPS`
+    pub min_conf_bps: u64,
+}
+
+impl ConfFloor {
+    /// `conf` raised to the floor for an aggregate at `price`.
+    pub fn apply(&self, price: i64, conf: u64) -> u64 {
+        let relative_floor = u128::from(price.unsigned_abs()) * u128::from(self.min_conf_bps)
+            / u128::from(MAX_CONF_FLOOR_BPS);
+        // The relative floor is at most the absolute price, which fits in a u64
+        conf.max(self.min_conf).max(relative_floor as u64)
+    }
+}
+
+impl PriceAccountExtension for ConfFloor {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::ConfFloor;
+}
+
+/// Why an aggregation left the feed without an aggregate price.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum StatusReasonCode {
+    /// The aggregation produced a price
+    None               = 0,
+    /// Fewer valid quotes than the minimum number of publishers of the feed
+    InsufficientQuorum = 1,
+    /// No component has a quote recent enough for the maximum latency of the feed
+    AllComponentsStale = 2,
+    /// The quotes or the aggregate have a zero confidence interval and the feed doesn't allow
+    /// them, see `PriceAccountFlags::ALLOW_ZERO_CI`
+    ZeroConfForbidden  = 3,
+    /// The sanity program of the feed vetoed the aggregate, or wasn't passed to check it
+    CircuitBreaker     = 4,
+    /// The `StatusPolicy` of the feed reported the halt or the auction of its publishers instead
+    /// of an aggregate
+    StatusPolicy       = 5,
+}
+
+/// Reason the last aggregation of the feed left its aggregate `PC_STATUS_UNKNOWN`, so that
+/// operators can tell why a feed went dark without going through the quotes of its components.
+/// Every aggregation records its reason while the tracking is enabled by `SetStatusReasonTracking`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct StatusReason {
+    /// `StatusReasonCode` of the last aggregation
+    pub code:    u32,
+    pub enabled: u8,
+    pub unused_: [u8; 3],
+    /// Slot of the last aggregation, 0 if none was recorded
+    pub slot:    u64,
+}
+
+impl StatusReason {
+    /// `None` for codes recorded by a newer version of the program.
+    pub fn code(&self) -> Option<StatusReasonCode> {
+        StatusReasonCode::from_u32(self.code)
+    }
+
+    pub fn record(&mut self, slot: u64, code: StatusReasonCode) {
+        if self.enabled == 0 {
+            return;
+        }
+        self.code = code as u32;
+        self.slot = slot;
+    }
+}
+
+impl PriceAccountExtension for StatusReason {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::StatusReason;
+}
+
+/// Stake of the publisher of a component, as reported by the staking program of the feed when
+/// the publisher was added.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct StakeEntry {
+    /// Publisher the entry belongs to. The entries move with the components when publishers are
+    /// added or removed, an entry that doesn't match the component at the same index doesn't
+    /// apply.
+    pub publisher: Pubkey,
+    pub amount:    u64,
+}
+
+/// Staking program holding the stake of the publishers of the feed in escrow accounts it owns.
+/// `AddPublisher` asks it for the stake of the new publisher, see `StakeCheckArgs`, rejects
+/// publishers staking less than `min_stake` and records the stake of the others. The recorded
+/// stakes are the basis of stake weighted aggregation and slashing, the aggregation doesn't use
+/// them yet.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherStake {
+    /// `Pubkey::default()` doesn't check the stake of new publishers
+    pub staking_program: Pubkey,
+    /// Smallest stake of a new publisher, in the units of the staking program
+    pub min_stake:       u64,
+    /// Indexed like `PriceAccount::comp_`
+    pub components:      [StakeEntry; PC_NUM_COMP as usize],
+}
+
+impl PublisherStake {
+    /// Recorded stake of the publisher of component `index`, 0 if none was recorded.
+    #[cfg(any(test, feature = "library"))]
+    pub fn stake_of(&self, index: usize, publisher: &Pubkey) -> u64 {
+        match self.components.get(index) {
+            Some(entry) if entry.publisher == *publisher => entry.amount,
+            _ => 0,
+        }
+    }
+
+    /// Record the stake of a publisher added at `index`, moving the entries of the `num_comps`
+    /// components that follow it the way the components moved.
+    pub fn insert(&mut self, index: usize, num_comps: usize, publisher: &Pubkey, amount: u64) {
+        let end = num_comps.min(self.components.len());
+        if index >= end {
+            return;
+        }
+        self.components.copy_within(index..end - 1, index + 1);
+        self.components[index] = StakeEntry {
+            publisher: *publisher,
+            amount,
+        };
+    }
+
+    /// Drop the stake of the publisher removed from `index`, moving the entries of the
+    /// `num_comps` components that followed it the way the components moved.
+    pub fn remove(&mut self, index: usize, num_comps: usize) {
+        let end = num_comps.min(self.components.len());
+        if index >= end {
+            return;
+        }
+        self.components.copy_within(index + 1..end, index);
+        self.components[end - 1] = StakeEntry::zeroed();
+    }
+}
+
+impl PriceAccountExtension for PublisherStake {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherStake;
+}
+
+/// Program that checks every aggregate of the feed before it's published, for checks specific to
+/// a deployment. See `SanityCheckArgs` for its interface. Publishers of the feed pass the program
+/// after the other accounts of `UpdPrice`, the aggregates computed without it are vetoed.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SanityProgram {
+    /// `Pubkey::default()` doesn't check the aggregates
+    pub program_id: Pubkey,
+}
+
+impl PriceAccountExtension for SanityProgram {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::SanityProgram;
+}
+
+/// Program that the quotes of the feed must be forwarded through, e.g. a relayer batching the
+/// quotes of several publishers. `UpdPrice` then only accepts the quotes of an instruction of
+/// this program invoking it, checked with the instructions sysvar passed as its last account.
+/// Publishers still sign their quotes, the program can't publish on their behalf.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct UpdaterProgram {
+    /// `Pubkey::default()` accepts the quotes of any instruction
+    pub program_id: Pubkey,
+}
+
+impl PriceAccountExtension for UpdaterProgram {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::UpdaterProgram;
+}
+
+/// Program notified of every `StatusTransition` of the feed, for risk systems that need to react
+/// to the feed going dark without polling it. Publishers pass it after the other accounts of
+/// `UpdPrice`, the transitions of the aggregations without it are only logged.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct StatusObserver {
+    /// `Pubkey::default()` doesn't notify any program
+    pub program_id: Pubkey,
+}
+
+impl PriceAccountExtension for StatusObserver {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::StatusObserver;
+}
+
+/// SHA-256 of the `PriceAccount` at the start of the price account, recomputed at the end of every
+/// instruction writing the account and checked at the start of the next one while enabled by
+/// `SetChecksum`. A mismatch means the account was written without going through the whole
+/// instruction, e.g. by a partial write or by a code path that forgot the checksum, and fails
+/// every instruction on the feed until governance reviews the account and enables the checksum
+/// again.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct Checksum {
+    /// 0 stops checking the account, the last hash is kept
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+    pub hash:    [u8; 32],
+}
+
+impl PriceAccountExtension for Checksum {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::Checksum;
+}
+
+impl Checksum {
+    /// `hash` of the account holding `price_data`.
+    pub fn hash_of(price_data: &PriceAccount) -> [u8; 32] {
+        hashv(&[bytemuck::bytes_of(price_data)]).to_bytes()
+    }
+}
+
+/// Check the `Checksum` of the price account stored in `data`, if it's enabled. Data that isn't a
+/// price account passes.
+pub fn verify_checksum(data: &[u8]) -> Result<(), OracleError> {
+    let price_data = match load_account_data::<PriceAccount>(data) {
+        Ok(price_data) => price_data,
+        Err(_) => return Ok(()),
+    };
+    match get_extension::<Checksum>(data) {
+        Ok(Some(checksum))
+            if checksum.enabled != 0 && checksum.hash != Checksum::hash_of(price_data) =>
+        {
+            Err(OracleError::ChecksumMismatch)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Recompute the `Checksum` of the price account stored in `data`, if it's enabled.
+#[cfg(not(feature = "wasm"))]
+pub fn update_checksum(data: &mut [u8]) {
+    if load_account_data::<PriceAccount>(data).is_err() {
+        return;
+    }
+    if let Ok(Some((price_data, checksum))) = get_price_and_extension_mut::<Checksum>(data) {
+        if checksum.enabled != 0 {
+            checksum.hash = Checksum::hash_of(price_data);
+        }
+    }
+}
+
+/// Limit on the number of valid quotes of the publishers controlled by the same entity that count
+/// in an aggregate, so that an entity holding several publisher keys doesn't weigh more in the
+/// median than the others. The most recent quotes of an entity count, the other ones are
+/// aggregated as `PC_STATUS_IGNORED`. The entities are copied from the `PublisherLinkageAccount`
+/// by `SetPublisherWeightCap`, which must be sent again after the publishers of the feed or their
+/// linkage change: publishers without an entry are their own entity.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherWeightCap {
+    /// 0 counts every quote
+    pub max_quotes_per_entity: u64,
+    /// Entry `i` holds the entity of component `i` when the cap was set
+    pub components:            [PublisherLink; PC_NUM_COMP as usize],
+}
+
+impl PriceAccountExtension for PublisherWeightCap {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherWeightCap;
+}
+
+impl PublisherWeightCap {
+    /// Entity of `publisher`, the publisher of component `index`. Components move when
+    /// publishers are added or removed, so the other entries are searched if entry `index` is
+    /// for another publisher.
+    pub fn entity_of(&self, index: usize, publisher: &Pubkey) -> Pubkey {
+        match self.components.get(index) {
+            Some(link) if link.publisher == *publisher => link.entity,
+            _ => self
+                .components
+                .iter()
+                .find(|link| link.publisher == *publisher)
+                .map(|link| link.entity)
+                .unwrap_or(*publisher),
+        }
+    }
+}
+
+/// Components of the feed sorted by each price the aggregation takes from their quotes:
+/// `price - conf`, `price` and `price + conf`. Every `UpdPrice` inserts or moves the component it
+/// updates, in O(number of components), so that the aggregation merges the valid quotes instead
+/// of sorting them. The aggregation checks that every valid quote is in the order and that it
+/// is sorted, and sorts the quotes otherwise, so the aggregates are the same with or without it.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct QuoteOrder {
+    /// 0 aggregates by sorting the quotes, without maintaining the order
+    pub enabled: u8,
+    pub unused_: [u8; 3],
+    /// Number of components in every list. The order is emptied when the components move or
+    /// their quotes change outside of `UpdPrice`, and refills as the publishers publish.
+    pub len:     u32,
+    /// Component indexes sorted by `price - conf`, `price` and `price + conf`, only the first
+    /// `len` entries of every list are used. The C aggregation reads them as one array.
+    pub lists:   [[u8; PC_NUM_COMP as usize]; 3],
+}
+
+impl PriceAccountExtension for QuoteOrder {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::QuoteOrder;
+}
+
+impl QuoteOrder {
+    /// Price of `component` that `list` is sorted by. The quotes whose prices overflow are never
+    /// valid, so their position doesn't matter.
+    fn key(component: &PriceComponent, list: usize) -> i128 {
+        let price = i128::from(component.latest_.price_);
+        let conf = i128::from(component.latest_.conf_);
+        match list {
+            0 => price - conf,
+            1 => price,
+            _ => price + conf,
+        }
+    }
+
+    /// Move component `index` of `components` to its place after its quote changed, inserting
+    /// it if it isn't in the order yet.
+    pub fn update(&mut self, components: &[PriceComponent], index: usize) {
+        let entry = index as u8;
+        let mut len = (self.len as usize).min(components.len());
+        let holding = self
+            .lists
+            .iter()
+            .filter(|order| order[..len].contains(&entry))
+            .count();
+        // Only an inconsistent order has the component in some lists but not all of them, or
+        // every component but this one
+        if (holding != 0 && holding != self.lists.len())
+            || (holding == 0 && len == components.len())
+        {
+            len = 0;
+        }
+        let contained = len != 0 && holding == self.lists.len();
+
+        for list in 0..self.lists.len() {
+            let order = &mut self.lists[list];
+            let mut others = len;
+            if contained {
+                if let Some(position) = order[..len].iter().position(|other| *other == entry) {
+                    order.copy_within(position + 1..len, position);
+                    others -= 1;
+                }
+            }
+
+            let key = Self::key(&components[index], list);
+            let target = order[..others]
+                .iter()
+                .position(|other| {
+                    components
+                        .get(usize::from(*other))
+                        .map_or(true, |component| Self::key(component, list) > key)
+                })
+                .unwrap_or(others);
+            order.copy_within(target..others, target + 1);
+            order[target] = entry;
+        }
+
+        if !contained {
+            len += 1;
+        }
+        self.len = len as u32;
+    }
+}
+
+/// Empty the `QuoteOrder` of `price_account`, after its components moved or their quotes changed
+/// outside of `UpdPrice`. Feeds without the extension are left as they are.
+#[cfg(not(feature = "wasm"))]
+pub fn invalidate_quote_order(price_account: &AccountInfo) -> Result<(), ProgramError> {
+    if let Some(order) = get_extension_mut::<QuoteOrder>(&mut price_account.try_borrow_mut_data()?)?
+    {
+        order.len = 0;
+    }
+    Ok(())
+}
+
+/// Move the entries of an extension indexed like `PriceAccount::comp_` to the components of their
+/// publishers, dropping the entries of the publishers that are no longer listed.
+fn realign_entries<T: Pod>(
+    entries: &mut [T],
+    components: &[PriceComponent],
+    publisher_of: fn(&T) -> Pubkey,
+) {
+    let previous = entries.to_vec();
+    for (index, entry) in entries.iter_mut().enumerate() {
+        *entry = components
+            .get(index)
+            .filter(|component| component.pub_ != Pubkey::default())
+            .and_then(|component| {
+                previous
+                    .iter()
+                    .find(|previous| publisher_of(previous) == component.pub_)
+            })
+            .copied()
+            .unwrap_or_else(T::zeroed);
+    }
+}
+
+/// Move the entries of the `PublisherRateLimit` and `PublisherSequences` of `price_account` to
+/// the components of their publishers, after publishers were added, removed or rotated. Otherwise
+/// the entries of the publishers that moved would stop applying, or apply to other publishers.
+#[cfg(not(feature = "wasm"))]
+pub fn realign_publisher_entries(price_account: &AccountInfo) -> Result<(), ProgramError> {
+    let mut data = price_account.try_borrow_mut_data()?;
+    if let Some((price_data, rate_limit)) =
+        get_price_and_extension_mut::<PublisherRateLimit>(&mut data)?
+    {
+        rate_limit.realign(&price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?]);
+    }
+    if let Some((price_data, sequences)) =
+        get_price_and_extension_mut::<PublisherSequences>(&mut data)?
+    {
+        sequences.realign(&price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?]);
+    }
+    Ok(())
+}
+
+/// Price account replacing the feed, set by `DeprecatePrice` along with the `DEPRECATED` flag
+/// when a symbol migrates, e.g. after a ticker change.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PriceSuccessor {
+    pub successor: Pubkey,
+}
+
+impl PriceAccountExtension for PriceSuccessor {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PriceSuccessor;
+}
+
+/// Price account replacing the price account stored in `data`, if the feed is deprecated. A
+/// deprecated feed with an invalid extension region has no known successor.
+pub fn get_successor(data: &[u8]) -> Option<Pubkey> {
+    let price_data = load_account_data::<PriceAccount>(data).ok()?;
+    if !price_data.flags.contains(PriceAccountFlags::DEPRECATED) {
+        return None;
+    }
+    match get_extension::<PriceSuccessor>(data) {
+        Ok(Some(successor)) => Some(successor.successor),
+        _ => None,
+    }
+}
+
+/// Key that listed a community feed with `AddCommunityPrice`, under the namespace of its key, see
+/// `COMMUNITY_SEED`. The creator manages the feed alongside the master authority, e.g. adds its
+/// publishers, but can't change its flags or extensions.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct FeedCreator {
+    pub creator: Pubkey,
+}
+
+impl PriceAccountExtension for FeedCreator {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::FeedCreator;
+}
+
+/// Largest number of publishers of the feed, below the `PC_NUM_COMP` slots of the price account,
+/// for feeds run by a small curated set of publishers. `AddPublisher` fails with
+/// `PublisherCapReached` once the feed has `max_publishers` publishers, 0 leaves only the
+/// structural limit.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherCap {
+    pub max_publishers: u32,
+    pub unused_:        u32,
+}
+
+impl PriceAccountExtension for PublisherCap {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherCap;
+}
+
+/// Aggregate of the feed converted to another quote currency with the aggregate of a conversion
+/// feed, e.g. asset/EUR from asset/USD and EUR/USD. `ConvertPrice` composes the latest aggregates
+/// of both feeds: the aggregate of the feed is divided by the conversion aggregate with `divide`
+/// (asset/USD / EUR/USD), otherwise multiplied by it (asset/USD * USD/EUR). The confidence
+/// interval of the converted price covers every price composed from the confidence intervals of
+/// the two aggregates, so it is slightly wider than the sum of their relative widths.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct QuoteConversion {
+    /// `Pubkey::default()` doesn't convert the aggregates
+    pub conversion_feed: Pubkey,
+    /// Exponent of the converted price
+    pub exponent:        i32,
+    /// 1 to divide by the conversion aggregate, 0 to multiply by it
+    pub divide:          u8,
+    pub unused_:         [u8; 3],
+    /// Latest converted aggregate. It's `PC_STATUS_TRADING` when both aggregates were trading,
+    /// at the slot of the older one. Otherwise only its status is updated.
+    pub converted:       PriceInfo,
+}
+
+impl QuoteConversion {
+    /// Price and confidence of `aggregate` at `exponent` converted with `conversion` at
+    /// `conversion_exponent`, at the exponent of the conversion. `None` if the result doesn't fit
+    /// or, when dividing, if the confidence interval of the conversion includes 0.
+    pub fn convert(
+        &self,
+        aggregate: &PriceInfo,
+        exponent: i32,
+        conversion: &PriceInfo,
+        conversion_exponent: i32,
+    ) -> Option<(i64, u64)> {
+        let price = u128::from(aggregate.price_.unsigned_abs());
+        let conf = u128::from(aggregate.conf_);
+        let conversion_price = u128::from(conversion.price_.unsigned_abs());
+        let conversion_conf = u128::from(conversion.conf_);
+
+        let (magnitude, converted_conf) = if self.divide != 0 {
+            if conversion_conf >= conversion_price {
+                return None;
+            }
+            // The widest quotient is (price + conf) / (conversion_price - conversion_conf)
+            let shift = exponent - conversion_exponent - self.exponent;
+            let conf_numerator = (conf * conversion_price).checked_add(conversion_conf * price)?;
+            let conf_denominator = conversion_price * (conversion_price - conversion_conf);
+            (
+                rescale(price, conversion_price, shift, false)?,
+                rescale(conf_numerator, conf_denominator, shift, true)?,
+            )
+        } else {
+            // The widest product is (price + conf) * (conversion_price + conversion_conf)
+            let shift = exponent + conversion_exponent - self.exponent;
+            let conf_numerator = (conf * conversion_price)
+                .checked_add(conversion_conf * price)?
+                .checked_add(conf * conversion_conf)?;
+            (
+                rescale(price * conversion_price, 1, shift, false)?,
+                rescale(conf_numerator, 1, shift, true)?,
+            )
+        };
+
+        let magnitude = i64::try_from(magnitude).ok()?;
+        let converted_price = if (aggregate.price_ < 0) != (conversion.price_ < 0) {
+            -magnitude
+        } else {
+            magnitude
+        };
+        Some((converted_price, u64::try_from(converted_conf).ok()?))
+    }
+}
+
+/// `numerator / denominator * 10^shift`, rounded toward zero or up with `round_up`.
+fn rescale(numerator: u128, denominator: u128, shift: i32, round_up: bool) -> Option<u128> {
+    let scale = 10u128.checked_pow(shift.unsigned_abs())?;
+    let (numerator, denominator) = if shift >= 0 {
+        (numerator.checked_mul(scale)?, denominator)
+    } else {
+        (numerator, denominator.checked_mul(scale)?)
+    };
+    let quotient = numerator / denominator;
+    if round_up && numerator % denominator != 0 {
+        Some(quotient + 1)
+    } else {
+        Some(quotient)
+    }
+}
+
+impl PriceAccountExtension for QuoteConversion {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::QuoteConversion;
+}
+
+/// Keeps the aggregate of a feed without aggregation delay for the rest of the slot once an
+/// update computed it: the later `UpdPrice` of the slot only store their quotes, which the first
+/// aggregation of the next slot covers. Feeds with many publishers save the cost of aggregating
+/// again on every update. Feeds aggregating the quotes of the earlier slots already aggregate
+/// once per slot.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AggregateCache {
+    /// 0 aggregates again on every update
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+}
+
+impl PriceAccountExtension for AggregateCache {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::AggregateCache;
+}
+
+/// Makes the feed aggregate with the `AggregationConfigAccount` and the `FeatureGateAccount` of the
+/// program. `UpdPrice` then rejects the quotes without them, so that a publisher can't aggregate
+/// with the defaults of the program instead by leaving them out. The other feeds aggregate with
+/// the defaults of the program and ignore the shared accounts.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SharedSettings {
+    /// 0 aggregates with the defaults of the program
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+}
+
+impl PriceAccountExtension for SharedSettings {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::SharedSettings;
+}
+
+impl SharedSettings {
+    /// Whether the feed stored in `data` aggregates with the shared settings.
+    pub fn is_followed(data: &[u8]) -> Result<bool, OracleError> {
+        Ok(get_extension::<SharedSettings>(data)?.map_or(false, |settings| settings.enabled != 0))
+    }
+}
+
+/// Sample of the valid quotes counted in every aggregation of a feed with a large set of
+/// publishers, to bound the cost of its aggregations. The sample is drawn from the most recent
+/// slot hash, which the publishers can't choose, so a publisher can't tell whether its quote will
+/// count before it lands. Aggregations without the slot hashes sysvar count every quote.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherSampling {
+    /// Number of valid quotes counted in every aggregation, at most `PC_NUM_COMP`, 0 counts
+    /// them all
+    pub sample_size: u32,
+    pub unused_:     u32,
+}
+
+impl PriceAccountExtension for PublisherSampling {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherSampling;
+}
+
+impl PublisherSampling {
+    /// Seed of the samples of the feed `price_account` in `slot`, given the most recent slot
+    /// hash. Every aggregation of the feed in the slot draws the same sample.
+    pub fn seed(slot_hash: &Hash, price_account: &Pubkey, slot: u64) -> Hash {
+        hashv(&[
+            slot_hash.as_ref(),
+            price_account.as_ref(),
+            &slot.to_le_bytes(),
+        ])
+    }
+
+    /// `sample_size` of the `candidates` drawn uniformly without replacement with `seed`, in
+    /// the order of `candidates`. Every candidate is drawn if there are at most `sample_size`.
+    pub fn sample(&self, seed: &Hash, candidates: &[usize]) -> Vec<usize> {
+        let sample_size = self.sample_size as usize;
+        if sample_size == 0 || candidates.len() <= sample_size {
+            return candidates.to_vec();
+        }
+
+        // Partial Fisher-Yates shuffle with a splitmix64 generator
+        let mut state = u64::from_le_bytes(seed.to_bytes()[..8].try_into().unwrap());
+        let mut drawn: Vec<usize> = (0..candidates.len()).collect();
+        for i in 0..sample_size {
+            state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^= z >> 31;
+            let j = i + (z % (drawn.len() - i) as u64) as usize;
+            drawn.swap(i, j);
+        }
+        drawn.truncate(sample_size);
+        drawn.sort_unstable();
+        drawn.into_iter().map(|i| candidates[i]).collect()
+    }
+}
+
+/// Fee paid by every update of a feed, in lamports, into the fee vault of the program, see
+/// `FeeVaultAccount`. The publishers list the fee vault and the system program in their updates
+/// to pay it, the updates without them are rejected. The updates without a quote, which only
+/// aggregate, don't pay. The fee is in lamports only, SPL tokens aren't supported.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct UpdateFee {
+    /// 0 lets the updates through for free
+    pub lamports: u64,
+}
+
+impl PriceAccountExtension for UpdateFee {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::UpdateFee;
+}
+
+/// Score of a quote at the aggregate price, see `reward_score`.
+pub const REWARD_SCORE_SCALE: u64 = 1000;
+
+/// Score earned by `quote` in an aggregation producing `aggregate`: `REWARD_SCORE_SCALE` at the
+/// aggregate price, falling linearly to half of it at the edges of the confidence interval of the
+/// aggregate, and 0 outside of it.
+#[cfg(not(feature = "wasm"))]
+pub fn reward_score(aggregate: &PriceInfo, quote: &PriceInfo) -> u64 {
+    let distance = (i128::from(quote.price_) - i128::from(aggregate.price_)).unsigned_abs();
+    let conf = u128::from(aggregate.conf_);
+    if distance > conf {
+        return 0;
+    }
+    // The distance is at most conf, so the result is between REWARD_SCORE_SCALE / 2 and
+    // REWARD_SCORE_SCALE
+    let penalty = match conf {
+        0 => 0,
+        _ => u128::from(REWARD_SCORE_SCALE) * distance / (2 * conf),
+    };
+    REWARD_SCORE_SCALE - penalty as u64
+}
+
+/// Reward score of a publisher in a feed, see `PublisherRewards`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct RewardEntry {
+    pub publisher:  Pubkey,
+    /// Score earned since the last claim of the publisher
+    pub unclaimed:  u64,
+    /// Slot of the last aggregation scored
+    pub slot:       u64,
+    /// Score earned in `slot`, taken back when the slot is aggregated again
+    pub slot_score: u64,
+}
+
+/// Scores earned by the publishers of the feed in its aggregations, see `reward_score`, until
+/// they claim them into their `RewardsLedgerAccount` with `ClaimRewards`. Every aggregation
+/// producing a price scores the valid quotes it counted, aggregating again in the same slot
+/// replaces the scores of the slot. The entries follow the publishers when components move, a
+/// removed publisher can still claim until its entry is reused for a new one. Only the score is
+/// recorded on-chain, paying the publishers for it is left to the programs reading the ledgers.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherRewards {
+    /// 0 stops scoring the aggregations, the scores earned can still be claimed
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+    pub entries: [RewardEntry; PC_NUM_COMP as usize],
+}
+
+impl PriceAccountExtension for PublisherRewards {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherRewards;
+}
+
+impl PublisherRewards {
+    /// Credit every component with its score in the aggregation of `slot`, indexed like
+    /// `components`, taking back the scores of an earlier aggregation of the slot.
+    pub fn record_aggregation(&mut self, components: &[PriceComponent], scores: &[u64], slot: u64) {
+        for (index, score) in scores.iter().enumerate() {
+            let entry = self.entry_mut(components, index);
+            if entry.slot == slot {
+                entry.unclaimed = entry.unclaimed.saturating_sub(entry.slot_score);
+            }
+            entry.unclaimed = entry.unclaimed.saturating_add(*score);
+            entry.slot = slot;
+            entry.slot_score = *score;
+        }
+    }
+
+    /// Take the score `publisher` can claim. The score of the last slot scored stays until the
+    /// next one is, since aggregating again in the slot can still change it.
+    pub fn claim(&mut self, publisher: &Pubkey) -> u64 {
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.publisher == *publisher)
+        {
+            Some(entry) => {
+                let claimable = entry.unclaimed.saturating_sub(entry.slot_score);
+                entry.unclaimed -= claimable;
+                claimable
+            }
+            None => 0,
+        }
+    }
+
+    /// Entry of the component at `index`, moved there from wherever it was. A new publisher takes
+    /// the entry of one that isn't a component of the feed anymore.
+    fn entry_mut(&mut self, components: &[PriceComponent], index: usize) -> &mut RewardEntry {
+        let publisher = components[index].pub_;
+        if self.entries[index].publisher != publisher {
+            let moved = self
+                .entries
+                .iter()
+                .position(|entry| entry.publisher == publisher)
+                .or_else(|| {
+                    self.entries.iter().position(|entry| {
+                        !components
+                            .iter()
+                            .any(|component| component.pub_ == entry.publisher)
+                    })
+                });
+            if let Some(moved) = moved {
+                self.entries.swap(index, moved);
+            }
+            if self.entries[index].publisher != publisher {
+                self.entries[index] = RewardEntry {
+                    publisher,
+                    ..RewardEntry::zeroed()
+                };
+            }
+        }
+        &mut self.entries[index]
+    }
+}
+
+/// Raised on the feeds of a `ConsistencyCheckAccount` by the `CheckConsistency` crank that found
+/// them inconsistent, consumers should distrust the aggregate of the feed while it is raised.
+/// Every feed of a check holds the extension from `InitConsistencyCheck`. A feed linked by
+/// several checks keeps the flag of the latest violation until that check passes again.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ConsistencyFlag {
+    /// `Pubkey::default()` while the flag isn't raised
+    pub check: Pubkey,
+    /// Slot of the crank that raised the flag
+    pub slot:  u64,
+}
+
+impl PriceAccountExtension for ConsistencyFlag {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::ConsistencyFlag;
+}
+
+impl ConsistencyFlag {
+    #[cfg(any(test, feature = "library"))]
+    pub fn is_raised(&self) -> bool {
+        self.check != Pubkey::default()
+    }
+}
+
+/// Number of updates the publishers of the feed published while the counting is enabled by
+/// `SetUpdateCounting`, reported in the `FeedTombstoneMessage` when the feed is deleted.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct UpdateCount {
+    /// 0 stops counting, the count is kept
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+    pub updates: u64,
+}
+
+impl PriceAccountExtension for UpdateCount {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::UpdateCount;
+}
+
+/// Clock of the latest quote of the publisher of a component.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ComponentTime {
+    /// Publisher the entry belongs to. Entries don't move with the components, an entry that
+    /// doesn't match the component at the same index doesn't apply.
+    pub publisher: Pubkey,
+    /// `pub_slot_` of the quote
+    pub slot:      u64,
+    /// Unix timestamp of the clock when the quote was accepted
+    pub time:      i64,
+}
+
+/// Unix timestamps of the quotes of the publishers of the feed, recorded while enabled by
+/// `SetPublisherTimestamps`. The price account only stores the slot of a quote, and the
+/// timestamp of the aggregate in `timestamp_`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherTimestamps {
+    /// 0 stops recording, the recorded timestamps are kept
+    pub enabled:    u8,
+    pub unused_:    [u8; 7],
+    /// Indexed like `PriceAccount::comp_`
+    pub components: [ComponentTime; PC_NUM_COMP as usize],
+}
+
+impl PublisherTimestamps {
+    /// Record the clock of the quote `publisher` just published in component `index`.
+    pub fn record(&mut self, index: usize, publisher: &Pubkey, slot: u64, time: i64) {
+        if let Some(entry) = self.components.get_mut(index) {
+            *entry = ComponentTime {
+                publisher: *publisher,
+                slot,
+                time,
+            };
+        }
+    }
+
+    /// Unix timestamp of `quote`, the `latest_` or `agg_` of the component `index` of
+    /// `publisher`. `None` if no timestamp was recorded for it: the quote was published while
+    /// recording was disabled, or the publisher published again since it was aggregated.
+    #[cfg(any(test, feature = "library", feature = "wasm"))]
+    pub fn time_of(&self, index: usize, publisher: &Pubkey, quote: &PriceInfo) -> Option<i64> {
+        self.components
+            .get(index)
+            .filter(|entry| entry.publisher == *publisher && entry.slot == quote.pub_slot_)
+            .map(|entry| entry.time)
+    }
+}
+
+impl PriceAccountExtension for PublisherTimestamps {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherTimestamps;
+}
+
+/// Unix timestamp of the latest quote of component `index` of the price account stored in
+/// `data`, if one was recorded, see `PublisherTimestamps::time_of`.
+#[cfg(any(test, feature = "library", feature = "wasm"))]
+pub fn get_component_publish_time(data: &[u8], index: usize) -> Option<i64> {
+    let price_data = load_account_data::<PriceAccount>(data).ok()?;
+    let component = price_data
+        .comp_
+        .get(index)
+        .filter(|_| index < price_data.num_ as usize)?;
+    match get_extension::<PublisherTimestamps>(data) {
+        Ok(Some(timestamps)) => timestamps.time_of(index, &component.pub_, &component.latest_),
+        _ => None,
+    }
+}
+
+/// Sequence number of the latest numbered quote of the publisher of a component.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ComponentSequence {
+    /// Publisher the entry belongs to. The entries move with the components, see
+    /// `realign_publisher_entries`, an entry that doesn't match the component at the same index
+    /// doesn't apply.
+    pub publisher: Pubkey,
+    pub sequence:  u64,
+}
+
+/// Sequence numbers of the quotes of the publishers of the feed, checked while enabled by
+/// `SetSequenceTracking`. A numbered quote must be numbered above the latest numbered quote of
+/// its publisher, so that a relayer can neither replay the quotes of a publisher nor land them
+/// out of order. Quotes without a number aren't checked.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherSequences {
+    /// 0 stops checking, the recorded numbers are kept
+    pub enabled:    u8,
+    pub unused_:    [u8; 7],
+    /// Indexed like `PriceAccount::comp_`
+    pub components: [ComponentSequence; PC_NUM_COMP as usize],
+}
+
+impl PublisherSequences {
+    /// Sequence number of the latest numbered quote of `publisher` in component `index`, 0 if
+    /// none was recorded.
+    pub fn latest(&self, index: usize, publisher: &Pubkey) -> u64 {
+        self.components
+            .get(index)
+            .filter(|entry| entry.publisher == *publisher)
+            .map_or(0, |entry| entry.sequence)
+    }
+
+    /// Whether `publisher` can publish a quote numbered `sequence` in component `index`.
+    pub fn allows(&self, index: usize, publisher: &Pubkey, sequence: u64) -> bool {
+        sequence == 0 || sequence > self.latest(index, publisher)
+    }
+
+    /// Record the number of the quote `publisher` just published in component `index`.
+    pub fn record(&mut self, index: usize, publisher: &Pubkey, sequence: u64) {
+        if sequence == 0 {
+            return;
+        }
+        if let Some(entry) = self.components.get_mut(index) {
+            *entry = ComponentSequence {
+                publisher: *publisher,
+                sequence,
+            };
+        }
+    }
+
+    /// Move the entries to the components of their publishers after the components moved.
+    pub fn realign(&mut self, components: &[PriceComponent]) {
+        realign_entries(&mut self.components, components, |entry| entry.publisher);
+    }
+}
+
+impl PriceAccountExtension for PublisherSequences {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherSequences;
+}
+
+/// Publishes the aggregates of the feed on change only, for stable assets whose aggregate rarely
+/// moves. An aggregation within `deviation_bps` of the published trading aggregate keeps it, so
+/// it doesn't change `agg_`, the EMA or the cumulative sums and doesn't send accumulator
+/// messages, until `heartbeat_slots` have passed since the published aggregate. Aggregating
+/// again in the slot of the published aggregate still replaces it. Consumers should allow for
+/// the heartbeat in the age they accept.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublishThreshold {
+    /// Deviation from the published aggregate above which a new aggregate is published, in basis
+    /// points of the absolute published price. 0 publishes every aggregate
+    pub deviation_bps:   u64,
+    /// Slots after which a new aggregate is published whatever its deviation. 0 publishes every
+    /// aggregate
+    pub heartbeat_slots: u64,
+}
+
+impl PublishThreshold {
+    /// Whether an aggregate at `price` in `slot` replaces the trading aggregate at
+    /// `published_price` published in `published_slot`.
+    pub fn publishes(
+        &self,
+        (published_price, published_slot): (i64, u64),
+        (price, slot): (i64, u64),
+    ) -> bool {
+        if self.deviation_bps == 0 || slot.saturating_sub(published_slot) >= self.heartbeat_slots {
+            return true;
+        }
+        let deviation = u128::from(price.abs_diff(published_price)) * 10_000;
+        deviation > u128::from(published_price.unsigned_abs()) * u128::from(self.deviation_bps)
+    }
+}
+
+impl PriceAccountExtension for PublishThreshold {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublishThreshold;
+}
+
+/// How the aggregation combines the statuses the publishers supplied with their quotes, see
+/// `PriceInfo::publisher_status`. Only trading quotes are aggregated, so without a policy an
+/// aggregation without enough valid trading quotes leaves the feed `PC_STATUS_UNKNOWN` even when
+/// its publishers agree that the market is halted. With a policy, enough recent quotes reporting
+/// a halt or an auction make it the status of the aggregate. The quotes are recent within the
+/// maximum latency of the feed, whether or not they were valid, and a halt wins over an auction.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct StatusPolicy {
+    /// Recent quotes reporting `PC_STATUS_HALTED` that make the aggregate halted, 0 never does
+    pub halted_quorum:    u8,
+    /// Recent quotes reporting `PC_STATUS_AUCTION` that make the aggregate in auction, 0 never
+    /// does
+    pub auction_quorum:   u8,
+    /// Nonzero if a quorum also replaces an aggregate of the trading quotes, which is then
+    /// dropped like a vetoed one. Otherwise it only replaces `PC_STATUS_UNKNOWN`.
+    pub override_trading: u8,
+    pub unused_:          [u8; 5],
+}
+
+impl StatusPolicy {
+    /// Status replacing the status of the aggregate of `price_data` just aggregated in `slot`,
+    /// `None` to keep it.
+    pub fn status(&self, price_data: &PriceAccount, slot: u64) -> Option<u32> {
+        let aggregate_status = price_data.agg_.status_;
+        if aggregate_status == PC_STATUS_TRADING && self.override_trading == 0 {
+            return None;
+        }
+
+        let mut halted = 0;
+        let mut auction = 0;
+        for component in price_data.comp_.iter().take(price_data.num_ as usize) {
+            if slot.saturating_sub(component.agg_.pub_slot_) > price_data.max_latency() {
+                continue;
+            }
+            match component.agg_.publisher_status() {
+                PC_STATUS_HALTED => halted += 1,
+                PC_STATUS_AUCTION => auction += 1,
+                _ => {}
+            }
+        }
+
+        if self.halted_quorum != 0 && halted >= self.halted_quorum {
+            Some(PC_STATUS_HALTED)
+        } else if self.auction_quorum != 0 && auction >= self.auction_quorum {
+            Some(PC_STATUS_AUCTION)
+        } else {
+            None
+        }
+    }
+}
+
+impl PriceAccountExtension for StatusPolicy {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::StatusPolicy;
+}
+
+/// Auxiliary metrics of the market of a feed submitted by one of its publishers.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ComponentAuxData {
+    /// Publisher the entry belongs to. Entries don't move with the components, an entry that
+    /// doesn't match the component at the same index doesn't apply.
+    pub publisher:     Pubkey,
+    /// Slot the metrics were submitted in, 0 if they never were
+    pub slot:          u64,
+    pub open_interest: u64,
+    pub volume_24h:    u64,
+}
+
+/// Open interest and 24h volume of the market of the feed, submitted by its publishers with
+/// `UpdAuxData` while enabled by `SetAuxData`. Every submission replaces the medians with the
+/// medians of the submissions recent within the maximum latency of the feed, so that derivatives
+/// protocols read canonical metrics next to the price. The metrics are in the units of the market,
+/// they don't follow the exponent of the feed.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AuxData {
+    /// 0 rejects the submissions, the medians are kept
+    pub enabled:         u8,
+    pub unused_:         [u8; 3],
+    /// Number of submissions the medians were computed from
+    pub num_submissions: u32,
+    /// Slot the medians were computed in, 0 if they never were. Consumers should check their age
+    /// like the age of the aggregate.
+    pub slot:            u64,
+    pub open_interest:   u64,
+    pub volume_24h:      u64,
+    /// Indexed like `PriceAccount::comp_`
+    pub components:      [ComponentAuxData; PC_NUM_COMP as usize],
+}
+
+impl AuxData {
+    /// Record the metrics the publisher of component `index` submitted in `slot`, and compute
+    /// the medians of the submissions of the `components` recent within `max_latency`.
+    pub fn record(
+        &mut self,
+        components: &[PriceComponent],
+        index: usize,
+        slot: u64,
+        max_latency: u64,
+        (open_interest, volume_24h): (u64, u64),
+    ) {
+        if let (Some(entry), Some(component)) =
+            (self.components.get_mut(index), components.get(index))
+        {
+            *entry = ComponentAuxData {
+                publisher: component.pub_,
+                slot,
+                open_interest,
+                volume_24h,
+            };
+        }
+
+        let recent: Vec<&ComponentAuxData> = components
+            .iter()
+            .zip(self.components.iter())
+            .filter(|(component, entry)| {
+                entry.publisher == component.pub_
+                    && entry.slot != 0
+                    && slot.saturating_sub(entry.slot) <= max_latency
+            })
+            .map(|(_, entry)| entry)
+            .collect();
+        if recent.is_empty() {
+            return;
+        }
+        self.open_interest = median(recent.iter().map(|entry| entry.open_interest).collect());
+        self.volume_24h = median(recent.iter().map(|entry| entry.volume_24h).collect());
+        self.num_submissions = recent.len() as u32;
+        self.slot = slot;
+    }
+}
+
+impl PriceAccountExtension for AuxData {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::AuxData;
+}
+
+/// Median of `values`, the mean of the two middle values, rounded down, for an even number of
+/// them. `values` must not be empty.
+fn median(mut values: Vec<u64>) -> u64 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 1 {
+        values[middle]
+    } else {
+        ((u128::from(values[middle - 1]) + u128::from(values[middle])) / 2) as u64
+    }
+}
+
+/// Activity of the publisher of a component of the feed.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ComponentActivity {
+    /// Publisher the entry belongs to. Entries don't move with the components, an entry that
+    /// doesn't match the component at the same index doesn't apply.
+    pub publisher: Pubkey,
+    /// Consecutive aggregation windows without a valid quote of the publisher
+    pub missed:    u32,
+    /// 1 if the publisher is left out of the quorum
+    pub inactive:  u8,
+    pub unused_:   [u8; 3],
+}
+
+/// Aggregation windows missed by the publishers of the feed, while enabled by
+/// `SetPublisherDemotion`. Every aggregation in a new slot is a window. A publisher missing
+/// `max_missed` consecutive windows is demoted: the quorum of the aggregation becomes the smaller
+/// of `min_pub` and the number of active publishers, so that dead publishers don't keep a feed
+/// from reaching its quorum. The next window with a valid quote of the publisher reactivates it.
+/// Every demotion and reactivation is logged as a `PublisherActivityChange`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherActivity {
+    /// Windows missed before a publisher is demoted, 0 never demotes
+    pub max_missed: u32,
+    pub unused_:    u32,
+    /// Slot of the latest window
+    pub slot:       u64,
+    /// Indexed like `PriceAccount::comp_`
+    pub components: [ComponentActivity; PC_NUM_COMP as usize],
+}
+
+impl PublisherActivity {
+    /// Activity of the publisher of `component` at `index` after the window in `slot`, in which
+    /// its quote is `valid` or not. The activity only changes in a new window.
+    pub fn next(
+        &self,
+        index: usize,
+        component: &PriceComponent,
+        valid: bool,
+        slot: u64,
+    ) -> ComponentActivity {
+        let current = match self.components.get(index) {
+            Some(entry) if entry.publisher == component.pub_ => *entry,
+            _ => ComponentActivity {
+                publisher: component.pub_,
+                ..ComponentActivity::zeroed()
+            },
+        };
+        if slot <= self.slot {
+            return current;
+        }
+        let missed = if valid {
+            0
+        } else {
+            current.missed.saturating_add(1)
+        };
+        ComponentActivity {
+            missed,
+            inactive: u8::from(self.max_missed != 0 && missed >= self.max_missed),
+            ..current
+        }
+    }
+
+    /// Number of publishers of the `components` active after the window in `slot`, where
+    /// `valid[i]` tells whether the quote of component `i` counts in it.
+    pub fn active_publishers(
+        &self,
+        components: &[PriceComponent],
+        valid: &[bool],
+        slot: u64,
+    ) -> usize {
+        components
+            .iter()
+            .zip(valid)
+            .enumerate()
+            .filter(|(index, (component, valid))| {
+                self.next(*index, component, **valid, slot).inactive == 0
+            })
+            .count()
+    }
+
+    /// Record the window in `slot`, returning the indexes of the components whose publisher
+    /// was demoted or reactivated by it.
+    pub fn record_window(
+        &mut self,
+        components: &[PriceComponent],
+        valid: &[bool],
+        slot: u64,
+    ) -> Vec<usize> {
+        if slot <= self.slot {
+            return vec![];
+        }
+        let mut changed = vec![];
+        for (index, (component, valid)) in components.iter().zip(valid).enumerate() {
+            let next = self.next(index, component, *valid, slot);
+            if let Some(entry) = self.components.get_mut(index) {
+                // A publisher without an entry was active
+                let inactive = if entry.publisher == next.publisher {
+                    entry.inactive
+                } else {
+                    0
+                };
+                if inactive != next.inactive {
+                    changed.push(index);
+                }
+                *entry = next;
+            }
+        }
+        self.slot = slot;
+        changed
+    }
+}
+
+impl PriceAccountExtension for PublisherActivity {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::PublisherActivity;
+}
+
+/// Number of buckets of the window of `SlotWeightedTwap`.
+pub const SLOT_WEIGHTED_TWAP_BUCKETS: usize = 16;
+
+/// Average the feed publishes in the `twap_` and `twac_` of its price account, and so in the
+/// `ema_price` and `ema_conf` of its price messages.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum AverageMode {
+    /// The exponential moving average of the C aggregation code
+    Ema          = 0,
+    /// Both averages are maintained and the EMA is published, so that consumers can compare
+    /// them before the feed switches
+    Migrating    = 1,
+    /// Both averages are maintained and the slot-weighted TWAP is published
+    SlotWeighted = 2,
+}
+
+/// Slots of a bucket of `SlotWeightedTwap` and the sums of the aggregates held over them. The
+/// sums are little endian `i128`s, extensions are only `EXTENSION_ALIGNMENT` aligned.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct TwapBucket {
+    /// Sum of the aggregate price held in every slot
+    pub price_sum: [u8; 16],
+    /// Sum of the aggregate confidence held in every slot
+    pub conf_sum:  [u8; 16],
+    pub slots:     u64,
+}
+
+impl TwapBucket {
+    pub fn price_sum(&self) -> i128 {
+        i128::from_le_bytes(self.price_sum)
+    }
+
+    pub fn conf_sum(&self) -> i128 {
+        i128::from_le_bytes(self.conf_sum)
+    }
+
+    // The slots of the buckets never overlap, so the sums are at most the largest price times
+    // the current slot and can't overflow, like in `PriceCumulative`.
+    fn add(&mut self, price: i64, conf: u64, slots: u64) {
+        self.price_sum = (self.price_sum() + i128::from(price) * i128::from(slots)).to_le_bytes();
+        self.conf_sum = (self.conf_sum() + i128::from(conf) * i128::from(slots)).to_le_bytes();
+        self.slots += slots;
+    }
+}
+
+/// Time-weighted average of the trading aggregates over a sliding window of recent slots, where
+/// every aggregate counts for the slots it held until the next one. Perp venues settle funding on
+/// such an average, which the EMA of the C code only approximates. The window is a ring of
+/// `SLOT_WEIGHTED_TWAP_BUCKETS` buckets of `bucket_slots` slots each, like `PriceExtrema`, so it
+/// slides one bucket at a time. `SetAverageMode` selects which average the feed publishes, see
+/// `AverageMode`, the EMA is maintained whatever the mode.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SlotWeightedTwap {
+    /// `AverageMode` of the feed
+    pub mode:         u32,
+    pub unused_:      u32,
+    /// Width of the buckets in slots, 0 doesn't maintain the average
+    pub bucket_slots: u64,
+    /// Slot of the last trading aggregate recorded, 0 if none was
+    pub last_slot:    u64,
+    /// Price of that aggregate, held until the next one
+    pub last_price:   i64,
+    /// Confidence of that aggregate, held until the next one
+    pub last_conf:    u64,
+    /// Average price of the window ending at `last_slot`, in the exponent of the feed
+    pub price:        i64,
+    /// Average confidence of the window ending at `last_slot`, in the exponent of the feed
+    pub conf:         u64,
+    pub buckets:      [TwapBucket; SLOT_WEIGHTED_TWAP_BUCKETS],
+}
+
+impl SlotWeightedTwap {
+    pub fn new(mode: AverageMode, bucket_slots: u64) -> Self {
+        SlotWeightedTwap {
+            mode: mode as u32,
+            bucket_slots,
+            ..SlotWeightedTwap::zeroed()
+        }
+    }
+
+    /// `None` for modes set by a newer version of the program.
+    pub fn mode(&self) -> Option<AverageMode> {
+        AverageMode::from_u32(self.mode)
+    }
+
+    /// Drop the aggregates recorded so far, keeping the mode and the window.
+    pub fn restart(&mut self) {
+        *self = SlotWeightedTwap {
+            mode: self.mode,
+            bucket_slots: self.bucket_slots,
+            ..SlotWeightedTwap::zeroed()
+        };
+    }
+
+    /// Number of slots of the window, 0 if the average isn't maintained.
+    pub fn window_slots(&self) -> u64 {
+        self.bucket_slots
+            .saturating_mul(SLOT_WEIGHTED_TWAP_BUCKETS as u64)
+    }
+
+    fn bucket_index(bucket: u64) -> usize {
+        (bucket % SLOT_WEIGHTED_TWAP_BUCKETS as u64) as usize
+    }
+
+    /// First bucket of the window ending with the bucket of `slot`.
+    fn first_bucket(&self, slot: u64) -> u64 {
+        (slot / self.bucket_slots).saturating_sub(SLOT_WEIGHTED_TWAP_BUCKETS as u64 - 1)
+    }
+
+    /// Record a trading aggregate in `slot`. The previous aggregate is weighted by the slots it
+    /// held, an aggregate replaced in its slot never counts.
+    pub fn record_aggregate(&mut self, slot: u64, price: i64, conf: u64) {
+        if self.bucket_slots == 0 || slot < self.last_slot {
+            return;
+        }
+        let bucket = slot / self.bucket_slots;
+        let first_bucket = self.first_bucket(slot);
+        // Empty the buckets the window moved to since the last aggregate
+        let first_cleared = (self.last_slot / self.bucket_slots + 1).max(first_bucket);
+        for cleared in first_cleared..=bucket {
+            self.buckets[Self::bucket_index(cleared)] = TwapBucket::zeroed();
+        }
+
+        if self.last_slot != 0 {
+            let mut start = self
+                .last_slot
+                .max(first_bucket.saturating_mul(self.bucket_slots));
+            while start < slot {
+                let start_bucket = start / self.bucket_slots;
+                let end = slot.min((start_bucket + 1).saturating_mul(self.bucket_slots));
+                self.buckets[Self::bucket_index(start_bucket)].add(
+                    self.last_price,
+                    self.last_conf,
+                    end - start,
+                );
+                start = end;
+            }
+        }
+        self.last_slot = slot;
+        self.last_price = price;
+        self.last_conf = conf;
+
+        let (price_sum, conf_sum, slots) = (first_bucket..=bucket)
+            .map(|bucket| self.buckets[Self::bucket_index(bucket)])
+            .fold(
+                (0i128, 0i128, 0u64),
+                |(price_sum, conf_sum, slots), bucket| {
+                    (
+                        price_sum + bucket.price_sum(),
+                        conf_sum + bucket.conf_sum(),
+                        slots + bucket.slots,
+                    )
+                },
+            );
+        // The first aggregate is its own average
+        if slots == 0 {
+            self.price = price;
+            self.conf = conf;
+        } else {
+            // Averages of i64 prices and u64 confidences fit in them
+            self.price = (price_sum / i128::from(slots)) as i64;
+            self.conf = (conf_sum / i128::from(slots)) as u64;
+        }
+    }
+
+    /// Average price and confidence to publish instead of the EMA, `None` if the feed publishes
+    /// the EMA.
+    pub fn published(&self) -> Option<(i64, u64)> {
+        (self.mode() == Some(AverageMode::SlotWeighted)
+            && self.bucket_slots != 0
+            && self.last_slot != 0)
+            .then_some((self.price, self.conf))
+    }
+}
+
+impl PriceAccountExtension for SlotWeightedTwap {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::SlotWeightedTwap;
+}
+
+/// Creator of the price account stored in `data`, if it's a community feed. Feeds listed by
+/// governance have no creator.
+pub fn get_feed_creator(data: &[u8]) -> Option<Pubkey> {
+    let price_data = load_account_data::<PriceAccount>(data).ok()?;
+    if !price_data.flags.contains(PriceAccountFlags::COMMUNITY) {
+        return None;
+    }
+    match get_extension::<FeedCreator>(data) {
+        Ok(Some(feed_creator)) => Some(feed_creator.creator),
+        _ => None,
+    }
+}
+
+/// Access control account of a permissioned feed, set by `SetAccessControl`. Only the readers
+/// it lists can consume the feed through `AssertPriceCondition`. The default key lifts the
+/// restriction. The account data stays public, the restriction only binds the consumers reading
+/// the feed through the program.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AccessControl {
+    pub access_control: Pubkey,
+}
+
+impl PriceAccountExtension for AccessControl {
+    const EXTENSION_TYPE: ExtensionType = ExtensionType::AccessControl;
+}
+
+/// Access control account of the price account stored in `data`, if the feed is permissioned.
+/// Fails on an invalid extension region rather than treating the feed as open.
+pub fn get_access_control(data: &[u8]) -> Result<Option<Pubkey>, OracleError> {
+    Ok(get_extension::<AccessControl>(data)?
+        .map(|extension| extension.access_control)
+        .filter(|access_control| *access_control != Pubkey::default()))
+}
+
+/// Location of an extension value in the account data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExtensionInfo {
+    pub extension_type: u32,
+    /// Offset of the value (not the header) from the start of the account data
+    pub offset:         usize,
+    pub length:         usize,
+}
+
+/// Iterator over the entries of the extension region. Yields an error and stops if an entry
+/// overflows the account data.
+pub struct ExtensionIter<'a> {
+    data:   &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Iterator for ExtensionIter<'a> {
+    type Item = Result<ExtensionInfo, OracleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value_offset = self.offset + size_of::<ExtensionHeader>();
+        if value_offset > self.data.len() {
+            return None;
+        }
+
+        let header: ExtensionHeader = pod_read_unaligned(&self.data[self.offset..value_offset]);
+        if header.extension_type == ExtensionType::Uninitialized as u32 {
+            return None;
+        }
+
+        let length = header.length as usize;
+        if length > self.data.len() - value_offset {
+            self.offset = self.data.len();
+            return Some(Err(OracleError::InvalidExtension));
+        }
+
+        self.offset = align_extension_offset(value_offset + length);
+        Some(Ok(ExtensionInfo {
+            extension_type: header.extension_type,
+            offset: value_offset,
+            length,
+        }))
+    }
+}
+
+fn align_extension_offset(offset: usize) -> usize {
+    (offset + EXTENSION_ALIGNMENT - 1) / EXTENSION_ALIGNMENT * EXTENSION_ALIGNMENT
+}
+
+/// Iterate over the extensions of the price account stored in `data`.
+pub fn iter_extensions(data: &[u8]) -> ExtensionIter {
+    ExtensionIter {
+        data,
+        offset: EXTENSIONS_OFFSET,
+    }
+}
+
+/// Number of bytes an extension of type `T` takes in the extension region, including its header
+/// and padding.
+pub const fn extension_space<T: PriceAccountExtension>() -> usize {
+    size_of::<ExtensionHeader>()
+        + (size_of::<T>() + EXTENSION_ALIGNMENT - 1) / EXTENSION_ALIGNMENT * EXTENSION_ALIGNMENT
+}
+
+/// Offset of the first byte after the last initialized extension.
+pub fn extensions_end(data: &[u8]) -> Result<usize, OracleError> {
+    let mut end = EXTENSIONS_OFFSET;
+    for info in iter_extensions(data) {
+        let info = info?;
+        end = align_extension_offset(info.offset + info.length);
+    }
+    Ok(end)
+}
+
+fn find_extension<T: PriceAccountExtension>(data: &[u8]) -> Result<Option<usize>, OracleError> {
+    if align_of::<T>() > EXTENSION_ALIGNMENT {
+        return Err(OracleError::InvalidExtension);
+    }
+
+    for info in iter_extensions(data) {
+        let info = info?;
+        if info.extension_type == T::EXTENSION_TYPE as u32 {
+            if info.length != size_of::<T>() {
+                return Err(OracleError::InvalidExtension);
+            }
+            return Ok(Some(info.offset));
+        }
+    }
+    Ok(None)
+}
+
+/// Get the extension of type `T` from the price account stored in `data`, returning `None` if the
+/// feed doesn't have it.
+pub fn get_extension<T: PriceAccountExtension>(data: &[u8]) -> Result<Option<&T>, OracleError> {
+    match find_extension::<T>(data)? {
+        Some(offset) => try_from_bytes(&data[offset..offset + size_of::<T>()])
+            .map(Some)
+            .map_err(|_| OracleError::InvalidExtension),
+        None => Ok(None),
+    }
+}
+
+/// Mutable version of `get_extension`.
+#[cfg(not(feature = "wasm"))]
+pub fn get_extension_mut<T: PriceAccountExtension>(
+    data: &mut [u8],
+) -> Result<Option<&mut T>, OracleError> {
+    match find_extension::<T>(data)? {
+        Some(offset) => try_from_bytes_mut(&mut data[offset..offset + size_of::<T>()])
+            .map(Some)
+            .map_err(|_| OracleError::InvalidExtension),
+        None => Ok(None),
+    }
+}
+
+/// Get the fixed layout of the price account stored in `data` along with its extension of type
+/// `T`, for the extensions that are updated from the components.
+#[cfg(not(feature = "wasm"))]
+pub fn get_price_and_extension_mut<T: PriceAccountExtension>(
+    data: &mut [u8],
+) -> Result<Option<(&PriceAccount, &mut T)>, OracleError> {
+    match find_extension::<T>(data)? {
+        Some(offset) => {
+            let (price_data, extension_data) = data.split_at_mut(offset);
+            let price_data = try_from_bytes(&price_data[..size_of::<PriceAccount>()])
+                .map_err(|_| OracleError::InvalidExtension)?;
+            try_from_bytes_mut(&mut extension_data[..size_of::<T>()])
+                .map(|extension| Some((price_data, extension)))
+                .map_err(|_| OracleError::InvalidExtension)
+        }
+        None => Ok(None),
+    }
+}
+
+/// Get the extension of type `T`, appending a zeroed one at the end of the list if the feed
+/// doesn't have it yet. Fails if `data` is too small to hold the new extension.
+pub fn init_extension<T: PriceAccountExtension>(data: &mut [u8]) -> Result<&mut T, OracleError> {
+    if let Some(offset) = find_extension::<T>(data)? {
+        return try_from_bytes_mut(&mut data[offset..offset + size_of::<T>()])
+            .map_err(|_| OracleError::InvalidExtension);
+    }
+
+    let header_offset = extensions_end(data)?;
+    let value_offset = header_offset + size_of::<ExtensionHeader>();
+    if header_offset + extension_space::<T>() > data.len() {
+        return Err(OracleError::AccountTooSmall);
+    }
+
+    let header = ExtensionHeader {
+        extension_type: T::EXTENSION_TYPE as u32,
+        length:         try_convert(size_of::<T>())?,
+    };
+    data[header_offset..value_offset].copy_from_slice(bytemuck::bytes_of(&header));
+    data[value_offset..header_offset + extension_space::<T>()].fill(0);
+
+    try_from_bytes_mut(&mut data[value_offset..value_offset + size_of::<T>()])
+        .map_err(|_| OracleError::InvalidExtension)
+}
+
+/// Set the extension of type `T` to `value`, appending it at the end of the list if the feed
+/// doesn't have it yet. Fails if `data` is too small to hold the new extension, see
+/// `reserve_extension_space`.
+pub fn set_extension<T: PriceAccountExtension>(
+    data: &mut [u8],
+    value: T,
+) -> Result<(), OracleError> {
+    *init_extension::<T>(data)? = value;
+    Ok(())
+}
+
+/// Account data length needed for the price account stored in `data` to hold an extension of
+/// type `T`.
+pub fn data_len_with_extension<T: PriceAccountExtension>(
+    data: &[u8],
+) -> Result<usize, OracleError> {
+    match find_extension::<T>(data)? {
+        Some(_) => Ok(data.len()),
+        None => Ok(data
+            .len()
+            .max(extensions_end(data)? + extension_space::<T>())),
+    }
+}
+
+/// Grow `price_account` so that it can hold an extension of type `T`. The account must already
+/// hold enough lamports to be rent exempt at its new size.
+#[cfg(not(feature = "wasm"))]
+pub fn reserve_extension_space<T: PriceAccountExtension>(
+    price_account: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let new_size = data_len_with_extension::<T>(&price_account.try_borrow_data()?)?;
+    if price_account.data_len() < new_size {
+        pyth_assert(
+            price_account.lamports() >= get_rent()?.minimum_balance(new_size),
+            ProgramError::AccountNotRentExempt,
+        )?;
+        price_account.realloc(new_size, true)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+        FEATURE_GATE_SEED,
+    },
+    crate::{
+        c_oracle_header::PC_ACCTYPE_FEATURE_GATE,
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::{
+            pyth_assert,
+            try_convert,
+        },
+    },
+    bytemuck::{
+        try_cast_slice,
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Named switches turning behaviors of the program on and off across every feed, maintained with
+/// `SetFeature`. A flip only takes effect `grace_period` slots after it was requested, so that
+/// publishers and consumers can prepare for it. The `num_features` switches follow this struct,
+/// unordered, a feature without a switch keeps its default. Only the feeds following the
+/// `SharedSettings` switch their features with it, the other feeds take the default of every
+/// feature. There is a single feature gate under `FEATURE_GATE_SEED`, it grows as features get
+/// switches.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct FeatureGateAccount {
+    pub header:       AccountHeader,
+    pub num_features: u32,
+    pub unused_:      u32,
+    /// Slots between a flip and the slot it takes effect, set by `SetFeatureGracePeriod`
+    pub grace_period: u64,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct FeatureSwitch {
+    /// Name of the feature, padded with zeros
+    pub name:            [u8; 32],
+    /// Slot from which `pending_enabled` replaces `enabled`
+    pub effective_slot:  u64,
+    pub enabled:         u8,
+    pub pending_enabled: u8,
+    pub unused_:         [u8; 6],
+}
+
+/// Behaviors of the program that the feature gate can switch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Feature {
+    /// Feeds with a `PublisherWeightCap` cap the quotes of every entity
+    WeightedAggregation,
+    /// Feeds with an `AggregationDelay` of 0 aggregate every update, otherwise they use the
+    /// default delay
+    SameSlotAggregation,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 2] = [Feature::WeightedAggregation, Feature::SameSlotAggregation];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Feature::WeightedAggregation => "weighted_aggregation",
+            Feature::SameSlotAggregation => "same_slot_aggregation",
+        }
+    }
+
+    /// Whether the feature is enabled without a switch.
+    pub fn enabled_by_default(&self) -> bool {
+        match self {
+            Feature::WeightedAggregation | Feature::SameSlotAggregation => true,
+        }
+    }
+
+    pub fn from_name(name: &[u8; 32]) -> Option<Feature> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| *name == feature_name(feature.name()))
+    }
+}
+
+/// `name` padded with zeros, the way `FeatureSwitch` stores it. Names longer than 32 bytes are
+/// truncated.
+pub fn feature_name(name: &str) -> [u8; 32] {
+    let mut padded = [0u8; 32];
+    let len = name.len().min(padded.len());
+    padded[..len].copy_from_slice(&name.as_bytes()[..len]);
+    padded
+}
+
+impl FeatureSwitch {
+    pub fn is_enabled(&self, slot: u64) -> bool {
+        if slot >= self.effective_slot {
+            self.pending_enabled != 0
+        } else {
+            self.enabled != 0
+        }
+    }
+
+    /// Flip the switch to `enabled` from `slot + grace_period`, keeping its state until then. A
+    /// flip replaces the one still pending.
+    pub fn flip(&mut self, enabled: bool, slot: u64, grace_period: u64) {
+        self.enabled = u8::from(self.is_enabled(slot));
+        self.pending_enabled = u8::from(enabled);
+        self.effective_slot = slot.saturating_add(grace_period);
+    }
+}
+
+impl PythAccount for FeatureGateAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_FEATURE_GATE;
+    const INITIAL_SIZE: u32 = size_of::<FeatureGateAccount>() as u32;
+}
+
+impl FeatureGateAccount {
+    /// Offset of the `position`-th switch in the account data.
+    pub fn entry_offset(position: usize) -> usize {
+        Self::MINIMUM_SIZE + position * size_of::<FeatureSwitch>()
+    }
+
+    /// Switches listed in the feature gate stored in `data`.
+    pub fn features(data: &[u8]) -> Result<&[FeatureSwitch], OracleError> {
+        let num_features: usize =
+            try_convert(load_account_data::<FeatureGateAccount>(data)?.num_features)?;
+        data.get(Self::MINIMUM_SIZE..Self::entry_offset(num_features))
+            .and_then(|entries| try_cast_slice(entries).ok())
+            .ok_or(OracleError::InvalidAccountHeader)
+    }
+
+    /// Whether `feature` is enabled in `slot` according to the feature gate stored in `data`.
+    pub fn is_enabled(data: &[u8], feature: Feature, slot: u64) -> Result<bool, OracleError> {
+        let name = feature_name(feature.name());
+        Ok(Self::features(data)?
+            .iter()
+            .find(|switch| switch.name == name)
+            .map_or(feature.enabled_by_default(), |switch| {
+                switch.is_enabled(slot)
+            }))
+    }
+
+    /// Whether `account` is the feature gate of the program. Only the program can create accounts
+    /// of this type, at the address of `FEATURE_GATE_SEED`.
+    pub fn is_feature_gate(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.try_borrow_data().map_or(false, |data| {
+                load_account_data::<FeatureGateAccount>(&data).is_ok()
+            })
+    }
+
+    /// Check that `account` is at the address of `FEATURE_GATE_SEED`, for the feeds that require
+    /// the feature gate.
+    pub fn check_address(program_id: &Pubkey, account: &AccountInfo) -> Result<(), ProgramError> {
+        let (feature_gate_pda_address, _) =
+            Pubkey::find_program_address(&[FEATURE_GATE_SEED.as_bytes()], program_id);
+        pyth_assert(
+            feature_gate_pda_address == *account.key,
+            OracleError::InvalidPda.into(),
+        )
+    }
+}
@@ -0,0 +1,54 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::{
+            PC_ACCTYPE_FEE_VAULT,
+            PC_MAGIC,
+        },
+        deserialize::load_account_as,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Lamports collected from the updates of the feeds with an `UpdateFee`, until governance
+/// withdraws them with `WithdrawFees` to fund cranking and publisher rewards. The lamports of
+/// the account beyond its rent exemption are the fees not withdrawn yet. There is a single fee
+/// vault under `FEE_VAULT_SEED`, created by the first `SetUpdateFee`.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct FeeVaultAccount {
+    pub header:          AccountHeader,
+    /// Lamports collected since the creation of the vault
+    pub total_collected: u64,
+    /// Lamports withdrawn since the creation of the vault
+    pub total_withdrawn: u64,
+}
+
+impl PythAccount for FeeVaultAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_FEE_VAULT;
+    const INITIAL_SIZE: u32 = size_of::<FeeVaultAccount>() as u32;
+}
+
+impl FeeVaultAccount {
+    /// Whether `account` holds the fee vault of the program, without failing on other accounts.
+    pub fn is_fee_vault(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.data_len() >= Self::MINIMUM_SIZE
+            && load_account_as::<AccountHeader>(account)
+                .map(|header| {
+                    header.magic_number == PC_MAGIC && header.account_type == Self::ACCOUNT_TYPE
+                })
+                .unwrap_or(false)
+    }
+}
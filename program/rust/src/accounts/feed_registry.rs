@@ -0,0 +1,84 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::PC_ACCTYPE_FEED_REGISTRY,
+        deserialize::{
+            load_account_as_mut,
+            load_account_data,
+        },
+        error::OracleError,
+        utils::try_convert,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Maps the feed index of every price account to its key, so that the accumulator and downstream
+/// programs can refer to feeds by their compact index. The entries follow this struct, the entry
+/// of feed index `i` is at `entry_offset(i)`. An entry holds the default key if the feed was
+/// deleted or never registered. There is a single registry under `FEED_REGISTRY_SEED`, it grows
+/// as feeds get registered.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct FeedRegistryAccount {
+    pub header: AccountHeader,
+}
+
+impl PythAccount for FeedRegistryAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_FEED_REGISTRY;
+    const INITIAL_SIZE: u32 = size_of::<FeedRegistryAccount>() as u32;
+}
+
+impl FeedRegistryAccount {
+    /// Offset of the entry of `feed_index` in the account data. Feed indexes start at 1.
+    pub fn entry_offset(feed_index: u32) -> Result<usize, OracleError> {
+        let position: usize = try_convert(
+            feed_index
+                .checked_sub(1)
+                .ok_or(OracleError::InvalidFeedIndex)?,
+        )?;
+        Ok(Self::MINIMUM_SIZE + position * size_of::<Pubkey>())
+    }
+
+    /// Price account registered under `feed_index`, if any.
+    pub fn get_entry(data: &[u8], feed_index: u32) -> Result<Option<Pubkey>, OracleError> {
+        let size: usize = try_convert(load_account_data::<FeedRegistryAccount>(data)?.header.size)?;
+        let start = Self::entry_offset(feed_index)?;
+        let entry = data
+            .get(..size)
+            .and_then(|data| data.get(start..start + size_of::<Pubkey>()))
+            .map(|entry| Pubkey::new_from_array(entry.try_into().unwrap()));
+        Ok(entry.filter(|key| *key != Pubkey::default()))
+    }
+
+    /// Write the entry of `feed_index`. The account must already be large enough for it, the
+    /// header size is raised to cover it.
+    pub fn set_entry(
+        account: &AccountInfo,
+        feed_index: u32,
+        price_account: &Pubkey,
+    ) -> Result<(), ProgramError> {
+        let start = Self::entry_offset(feed_index)?;
+        let end = start + size_of::<Pubkey>();
+        if account.data_len() < end {
+            return Err(OracleError::AccountTooSmall.into());
+        }
+        {
+            let mut header = load_account_as_mut::<AccountHeader>(account)?;
+            header.size = header.size.max(try_convert(end)?);
+        }
+        account.try_borrow_mut_data()?[start..end].copy_from_slice(price_account.as_ref());
+        Ok(())
+    }
+}
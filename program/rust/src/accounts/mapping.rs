@@ -1,12 +1,28 @@
+#[cfg(feature = "borsh")]
+use borsh::{
+    BorshDeserialize,
+    BorshSerialize,
+};
+// Only used to look products up by symbol, which the program itself doesn't do
+#[cfg(any(test, feature = "library", feature = "no-entrypoint"))]
+use {
+    super::get_product_metadata,
+    solana_program::program_error::ProgramError,
+};
 use {
     super::{
         AccountHeader,
         PythAccount,
     },
-    crate::c_oracle_header::{
-        PC_ACCTYPE_MAPPING,
-        PC_MAP_TABLE_SIZE,
-        PC_MAP_TABLE_T_PROD_OFFSET,
+    crate::{
+        c_oracle_header::{
+            PC_ACCTYPE_MAPPING,
+            PC_MAP_TABLE_SIZE,
+            PC_MAP_TABLE_T_PROD_OFFSET,
+        },
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::try_convert,
     },
     bytemuck::{
         Pod,
@@ -16,12 +32,14 @@ use {
 };
 
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone)]
 pub struct MappingAccount {
     pub header:               AccountHeader,
     pub number_of_products:   u32,
     pub unused_:              u32,
     pub next_mapping_account: Pubkey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::accounts::serde_array"))]
     pub products_list:        [Pubkey; PC_MAP_TABLE_SIZE as usize],
 }
 
@@ -37,3 +55,124 @@ unsafe impl Pod for MappingAccount {
 
 unsafe impl Zeroable for MappingAccount {
 }
+
+// Manual impls because borsh doesn't support arrays of size 5000 either
+#[cfg(feature = "borsh")]
+impl BorshSerialize for MappingAccount {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.header.serialize(writer)?;
+        self.number_of_products.serialize(writer)?;
+        self.unused_.serialize(writer)?;
+        self.next_mapping_account.serialize(writer)?;
+        self.products_list
+            .iter()
+            .try_for_each(|product| product.serialize(writer))
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for MappingAccount {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let mut mapping_account = MappingAccount::zeroed();
+        mapping_account.header = AccountHeader::deserialize(buf)?;
+        mapping_account.number_of_products = u32::deserialize(buf)?;
+        mapping_account.unused_ = u32::deserialize(buf)?;
+        mapping_account.next_mapping_account = Pubkey::deserialize(buf)?;
+        for product in mapping_account.products_list.iter_mut() {
+            *product = Pubkey::deserialize(buf)?;
+        }
+        Ok(mapping_account)
+    }
+}
+
+impl MappingAccount {
+    /// Products listed in this mapping account.
+    pub fn products(&self) -> Result<&[Pubkey], OracleError> {
+        self.products_list
+            .get(..try_convert::<_, usize>(self.number_of_products)?)
+            .ok_or(OracleError::InvalidAccountHeader)
+    }
+}
+
+/// Iterator over the products of a linked list of mapping accounts, see `iter_products`.
+pub struct ProductIter<'a, F> {
+    load_account:         F,
+    products:             &'a [Pubkey],
+    next_mapping_account: Pubkey,
+    /// Mapping accounts already listed, to stop on a list that loops
+    visited:              Vec<Pubkey>,
+}
+
+impl<'a, F: Fn(&Pubkey) -> Option<&'a [u8]>> Iterator for ProductIter<'a, F> {
+    type Item = Result<Pubkey, OracleError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.products.is_empty() {
+            // The last mapping account of the list points to the default key
+            if self.next_mapping_account == Pubkey::default() {
+                return None;
+            }
+            if self.visited.contains(&self.next_mapping_account) {
+                self.next_mapping_account = Pubkey::default();
+                return Some(Err(OracleError::MappingCycle));
+            }
+            self.visited.push(self.next_mapping_account);
+
+            let mapping_data = (self.load_account)(&self.next_mapping_account)
+                .ok_or(OracleError::AccountNotFound)
+                .and_then(load_account_data::<MappingAccount>);
+            match mapping_data.and_then(|mapping_data| {
+                Ok((mapping_data.products()?, mapping_data.next_mapping_account))
+            }) {
+                Ok((products, next_mapping_account)) => {
+                    self.products = products;
+                    self.next_mapping_account = next_mapping_account;
+                }
+                Err(err) => {
+                    self.next_mapping_account = Pubkey::default();
+                    return Some(Err(err));
+                }
+            }
+        }
+
+        let product = self.products[0];
+        self.products = &self.products[1..];
+        Some(Ok(product))
+    }
+}
+
+/// Iterate over the products listed in the linked list of mapping accounts starting at
+/// `first_mapping_account`. `load_account` returns the data of an account given its key, e.g.
+/// from a map of accounts fetched off-chain or from the accounts passed to an instruction.
+/// Yields an error and stops if a mapping account can't be loaded or if the list loops.
+#[cfg(any(test, feature = "library", feature = "no-entrypoint"))]
+pub fn iter_products<'a, F: Fn(&Pubkey) -> Option<&'a [u8]>>(
+    first_mapping_account: Pubkey,
+    load_account: F,
+) -> ProductIter<'a, F> {
+    ProductIter {
+        load_account,
+        products: &[],
+        next_mapping_account: first_mapping_account,
+        visited: Vec::new(),
+    }
+}
+
+/// Find the product whose `symbol` metadata is `symbol` in the linked list of mapping accounts
+/// starting at `first_mapping_account`. `load_account` is used to load both the mapping and the
+/// product accounts, see `iter_products`.
+#[cfg(any(test, feature = "library", feature = "no-entrypoint"))]
+pub fn find_product_by_symbol<'a, F: Fn(&Pubkey) -> Option<&'a [u8]>>(
+    first_mapping_account: Pubkey,
+    symbol: &str,
+    load_account: F,
+) -> Result<Option<Pubkey>, ProgramError> {
+    for product in iter_products(first_mapping_account, &load_account) {
+        let product = product?;
+        let product_data = load_account(&product).ok_or(OracleError::AccountNotFound)?;
+        if get_product_metadata(product_data, "symbol")? == Some(symbol.as_bytes()) {
+            return Ok(Some(product));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,144 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::PC_ACCTYPE_MAPPING_V2,
+        deserialize::{
+            load_account_data,
+            load_mut,
+        },
+        error::OracleError,
+        utils::try_convert,
+    },
+    bytemuck::{
+        try_cast_slice,
+        try_cast_slice_mut,
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        hash::hashv,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Products indexed by the hash of their symbol, replacing the linked list of `MappingAccount`s
+/// for readers looking up a product by symbol. The table of `capacity` entries follows this
+/// struct, a product is stored at the first free entry from `symbol_hash mod capacity` on. The
+/// capacity is set by the size of the account on `InitMappingV2`, products are indexed with
+/// `MigrateMapping`. Entries are never removed: the symbol of the product must be checked by the
+/// reader, the product may have been renamed or deleted since it was indexed.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct MappingAccountV2 {
+    pub header:             AccountHeader,
+    /// Number of entries of the table
+    pub capacity:           u32,
+    /// Number of entries in use
+    pub number_of_products: u32,
+}
+
+impl PythAccount for MappingAccountV2 {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_MAPPING_V2;
+    const INITIAL_SIZE: u32 = size_of::<MappingAccountV2>() as u32;
+}
+
+/// Entry of the table of a `MappingAccountV2`, free if `symbol_hash` is all zeros.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct MappingEntry {
+    /// `symbol_hash` of the symbol of the product
+    pub symbol_hash: [u8; 32],
+    pub product:     Pubkey,
+}
+
+/// Hash of `symbol` indexing its product in a `MappingAccountV2`, the SHA-256 of the symbol.
+pub fn symbol_hash(symbol: &[u8]) -> [u8; 32] {
+    hashv(&[symbol]).to_bytes()
+}
+
+impl MappingAccountV2 {
+    /// Number of entries of the table of an account holding `data_len` bytes.
+    pub fn capacity_for(data_len: usize) -> usize {
+        data_len.saturating_sub(Self::MINIMUM_SIZE) / size_of::<MappingEntry>()
+    }
+
+    /// Size of the account data holding a table of `capacity` entries.
+    pub fn space(capacity: usize) -> usize {
+        Self::MINIMUM_SIZE + capacity * size_of::<MappingEntry>()
+    }
+
+    /// Product indexed under `symbol_hash` in the mapping account stored in `data`, if any.
+    /// Probes the entries from `symbol_hash mod capacity` to the first free one.
+    pub fn find_product(
+        data: &[u8],
+        symbol_hash: &[u8; 32],
+    ) -> Result<Option<Pubkey>, OracleError> {
+        let entries = Self::entries(data)?;
+        Ok(Self::probe(entries, symbol_hash)
+            .map(|index| entries[index])
+            .filter(|entry| entry.symbol_hash == *symbol_hash)
+            .map(|entry| entry.product))
+    }
+
+    /// Index `product` under `symbol_hash`. Returns `false` if the product is already indexed
+    /// under it.
+    pub fn insert(
+        data: &mut [u8],
+        symbol_hash: &[u8; 32],
+        product: &Pubkey,
+    ) -> Result<bool, OracleError> {
+        let index =
+            Self::probe(Self::entries(data)?, symbol_hash).ok_or(OracleError::MappingFull)?;
+        let entry = &mut Self::entries_mut(data)?[index];
+        if entry.symbol_hash == *symbol_hash {
+            return if entry.product == *product {
+                Ok(false)
+            } else {
+                Err(OracleError::DuplicateSymbol)
+            };
+        }
+        *entry = MappingEntry {
+            symbol_hash: *symbol_hash,
+            product:     *product,
+        };
+        load_mut::<MappingAccountV2>(data)?.number_of_products += 1;
+        Ok(true)
+    }
+
+    /// Index of the entry holding `symbol_hash` or of the free entry where it belongs, `None` if
+    /// the table is full.
+    fn probe(entries: &[MappingEntry], symbol_hash: &[u8; 32]) -> Option<usize> {
+        if entries.is_empty() {
+            return None;
+        }
+        let start = (u64::from_le_bytes(symbol_hash[..8].try_into().unwrap())
+            % entries.len() as u64) as usize;
+        (start..entries.len()).chain(0..start).find(|index| {
+            let entry = &entries[*index];
+            entry.symbol_hash == *symbol_hash || entry.symbol_hash == [0; 32]
+        })
+    }
+
+    fn table_range(data: &[u8]) -> Result<(usize, usize), OracleError> {
+        let capacity: usize = try_convert(load_account_data::<MappingAccountV2>(data)?.capacity)?;
+        let end = Self::space(capacity);
+        if data.len() < end {
+            return Err(OracleError::AccountTooSmall);
+        }
+        Ok((Self::MINIMUM_SIZE, end))
+    }
+
+    fn entries(data: &[u8]) -> Result<&[MappingEntry], OracleError> {
+        let (start, end) = Self::table_range(data)?;
+        try_cast_slice(&data[start..end]).map_err(|_| OracleError::InvalidAccountHeader)
+    }
+
+    fn entries_mut(data: &mut [u8]) -> Result<&mut [MappingEntry], OracleError> {
+        let (start, end) = Self::table_range(data)?;
+        try_cast_slice_mut(&mut data[start..end]).map_err(|_| OracleError::InvalidAccountHeader)
+    }
+}
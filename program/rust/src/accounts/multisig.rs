@@ -0,0 +1,80 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::{
+            PC_ACCTYPE_MULTISIG,
+            PC_MAGIC,
+        },
+        deserialize::load_account_as,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Maximum number of signers of a multisig account.
+pub const MAX_MULTISIG_SIGNERS: usize = 11;
+
+/// A threshold of keys that can stand in for a single key as an authority of the
+/// `PermissionAccount`, created with `InitMultisig`. An instruction passes the multisig account
+/// in place of its funding account and lists at least `threshold` of the signers, signing the
+/// transaction, after its own accounts. The multisig account holds data, so it can't pay for the
+/// accounts an instruction creates.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct MultisigAccount {
+    pub header:      AccountHeader,
+    pub threshold:   u8,
+    pub num_signers: u8,
+    pub unused_:     [u8; 6],
+    /// Only the first `num_signers` keys are signers
+    pub signers:     [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+impl PythAccount for MultisigAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_MULTISIG;
+    const INITIAL_SIZE: u32 = size_of::<MultisigAccount>() as u32;
+}
+
+impl MultisigAccount {
+    /// Whether `account` holds a multisig account of the program, without failing on other
+    /// accounts.
+    pub fn is_multisig(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.data_len() >= Self::MINIMUM_SIZE
+            && load_account_as::<AccountHeader>(account)
+                .map(|header| {
+                    header.magic_number == PC_MAGIC && header.account_type == Self::ACCOUNT_TYPE
+                })
+                .unwrap_or(false)
+    }
+
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.signers[..usize::from(self.num_signers).min(MAX_MULTISIG_SIGNERS)]
+    }
+
+    /// Number of trailing `accounts` that are distinct signers of the multisig and signed the
+    /// transaction. Counting stops at the first account that isn't one.
+    pub fn count_trailing_signatures(&self, accounts: &[AccountInfo]) -> usize {
+        let mut signed = [false; MAX_MULTISIG_SIGNERS];
+        let mut count = 0;
+        for account in accounts.iter().rev() {
+            let position = match self.signers().iter().position(|key| key == account.key) {
+                Some(position) if account.is_signer && !signed[position] => position,
+                _ => break,
+            };
+            signed[position] = true;
+            count += 1;
+        }
+        count
+    }
+}
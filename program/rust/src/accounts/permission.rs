@@ -4,7 +4,11 @@ use {
         PythAccount,
     },
     crate::{
-        c_oracle_header::PC_ACCTYPE_PERMISSIONS,
+        c_oracle_header::{
+            PC_ACCTYPE_PERMISSIONS,
+            PC_MAGIC,
+        },
+        deserialize::load_account_as,
         instruction::OracleCommand,
     },
     bytemuck::{
@@ -17,14 +21,23 @@ use {
         pubkey::Pubkey,
     },
     std::{
-        cell::RefMut,
+        cell::{
+            Ref,
+            RefMut,
+        },
         mem::size_of,
     },
 };
 
 /// This account stores the pubkeys that can execute administrative instructions in the Pyth
-/// program. Only the upgrade authority of the program can update these permissions.
+/// program. Only the upgrade authority of the program can update these permissions. Any of the
+/// authorities can be a `MultisigAccount`.
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct PermissionAccount {
     /// pyth account header
@@ -43,10 +56,87 @@ pub struct PermissionAccount {
     /// - Add publishers
     /// - Delete publishers
     /// - Set minimum number of publishers
+    /// - Snapshot price accounts
+    /// - Cancel pending changes of the permissions
+    /// - Reset the EMAs of price accounts
     pub security_authority:      Pubkey,
 }
 
+/// Pending change of the permissions, stored after the last feed index of the permission account
+/// once a timelock was set. While `delay` is not 0, `UpdPermissions` and `SetPermissionsTimelock`
+/// only stage their change, which can be applied `delay` slots later by `ApplyPermissions` or
+/// dropped by the security authority with `CancelPermissions`. A new change replaces the pending
+/// one and restarts the delay.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PermissionTimelock {
+    /// Slots between staging a change and applying it, 0 applies changes immediately
+    pub delay:                           u64,
+    /// Slot from which the pending change can be applied, 0 if there is no pending change
+    pub effective_slot:                  u64,
+    pub pending_master_authority:        Pubkey,
+    pub pending_data_curation_authority: Pubkey,
+    pub pending_security_authority:      Pubkey,
+    pub pending_delay:                   u64,
+}
+
+impl PermissionTimelock {
+    pub fn has_pending_change(&self) -> bool {
+        self.effective_slot != 0
+    }
+
+    /// Stage a change of the authorities and of the delay, to be applied `delay` slots after
+    /// `current_slot`.
+    pub fn stage(
+        &mut self,
+        master_authority: Pubkey,
+        data_curation_authority: Pubkey,
+        security_authority: Pubkey,
+        delay: u64,
+        current_slot: u64,
+    ) {
+        self.pending_master_authority = master_authority;
+        self.pending_data_curation_authority = data_curation_authority;
+        self.pending_security_authority = security_authority;
+        self.pending_delay = delay;
+        self.effective_slot = current_slot.saturating_add(self.delay).max(1);
+    }
+
+    pub fn clear_pending_change(&mut self) {
+        *self = PermissionTimelock {
+            delay: self.delay,
+            ..PermissionTimelock::zeroed()
+        };
+    }
+}
+
+/// Program that `UpdPrice` pushes every new aggregate to, stored after the `PermissionTimelock`
+/// of the permission account once set by `SetAccumulatorEmitter`. Deployments pushing into the
+/// cross-chain pipeline this way don't need a cranker to read the message buffer.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct AccumulatorEmitter {
+    /// `Pubkey::default()` doesn't push the aggregates
+    pub program_id: Pubkey,
+}
+
 impl PermissionAccount {
+    /// Offset of the marker set once the publisher registry exists, after the last feed index
+    pub const PUBLISHER_REGISTRY_OFFSET: usize = size_of::<PermissionAccount>() + size_of::<u32>();
+    /// Size of a permission account holding the publisher registry marker
+    pub const PUBLISHER_REGISTRY_ACCOUNT_SPACE: usize =
+        Self::PUBLISHER_REGISTRY_OFFSET + size_of::<u32>();
+    /// Offset of the `PermissionTimelock`, after the last feed index and aligned
+    pub const TIMELOCK_OFFSET: usize = size_of::<PermissionAccount>() + size_of::<u64>();
+    /// Size of a permission account holding a `PermissionTimelock`
+    pub const TIMELOCK_ACCOUNT_SPACE: usize =
+        Self::TIMELOCK_OFFSET + size_of::<PermissionTimelock>();
+    /// Offset of the `AccumulatorEmitter`, after the timelock. A zeroed timelock applies changes
+    /// immediately, so permission accounts grown for the emitter behave as before.
+    pub const EMITTER_OFFSET: usize = Self::TIMELOCK_ACCOUNT_SPACE;
+    /// Size of a permission account holding an `AccumulatorEmitter`
+    pub const EMITTER_ACCOUNT_SPACE: usize = Self::EMITTER_OFFSET + size_of::<AccumulatorEmitter>();
+
     pub fn is_authorized(&self, key: &Pubkey, command: OracleCommand) -> bool {
         #[allow(clippy::match_like_matches_macro)]
         match (*key, command) {
@@ -54,10 +144,29 @@ impl PermissionAccount {
             (pubkey, OracleCommand::ResizePriceAccount) if pubkey == self.security_authority => {
                 true
             } // Allow for an admin key to resize the price account
+            (pubkey, OracleCommand::SnapshotPriceAccount) if pubkey == self.security_authority => {
+                true
+            }
+            (pubkey, OracleCommand::CancelPermissions) if pubkey == self.security_authority => true,
+            (pubkey, OracleCommand::ResetEma) if pubkey == self.security_authority => true,
+            (pubkey, OracleCommand::SetConfFloor) if pubkey == self.data_curation_authority => true,
+            (pubkey, OracleCommand::SetPublisherCap) if pubkey == self.data_curation_authority => {
+                true
+            }
             _ => false,
         }
     }
 
+    /// Last feed index handed out, 0 if the permissions account wasn't grown to hold it yet.
+    pub fn last_feed_index(account: &AccountInfo) -> Result<u32, ProgramError> {
+        let start = size_of::<PermissionAccount>();
+        let end = start + size_of::<u32>();
+        Ok(account
+            .try_borrow_data()?
+            .get(start..end)
+            .map_or(0, |data| *bytemuck::from_bytes(data)))
+    }
+
     pub fn load_last_feed_index_mut<'a>(
         account: &'a AccountInfo,
     ) -> Result<RefMut<'a, u32>, ProgramError> {
@@ -71,6 +180,88 @@ impl PermissionAccount {
             bytemuck::from_bytes_mut(&mut data[start..end])
         }))
     }
+
+    /// Whether `UpdPublisherRegistry` created the publisher registry. The instructions adding a
+    /// key to the publishers of a feed then need the registry, so that a publisher can't be added
+    /// without approval by leaving it out.
+    pub fn has_publisher_registry(account: &AccountInfo) -> Result<bool, ProgramError> {
+        Ok(account
+            .try_borrow_data()?
+            .get(Self::PUBLISHER_REGISTRY_OFFSET..Self::PUBLISHER_REGISTRY_ACCOUNT_SPACE)
+            .map_or(false, |marker| marker.iter().any(|byte| *byte != 0)))
+    }
+
+    pub fn load_publisher_registry_marker_mut<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<RefMut<'a, u32>, ProgramError> {
+        if account.data_len() < Self::PUBLISHER_REGISTRY_ACCOUNT_SPACE {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| {
+            bytemuck::from_bytes_mut(
+                &mut data[Self::PUBLISHER_REGISTRY_OFFSET..Self::PUBLISHER_REGISTRY_ACCOUNT_SPACE],
+            )
+        }))
+    }
+
+    /// The timelock of the permissions, `None` if no timelock was ever set.
+    pub fn load_timelock<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<Option<Ref<'a, PermissionTimelock>>, ProgramError> {
+        if account.data_len() < Self::TIMELOCK_ACCOUNT_SPACE {
+            return Ok(None);
+        }
+        Ok(Some(Ref::map(account.try_borrow_data()?, |data| {
+            bytemuck::from_bytes(&data[Self::TIMELOCK_OFFSET..Self::TIMELOCK_ACCOUNT_SPACE])
+        })))
+    }
+
+    pub fn load_timelock_mut<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<RefMut<'a, PermissionTimelock>, ProgramError> {
+        if account.data_len() < Self::TIMELOCK_ACCOUNT_SPACE {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| {
+            bytemuck::from_bytes_mut(&mut data[Self::TIMELOCK_OFFSET..Self::TIMELOCK_ACCOUNT_SPACE])
+        }))
+    }
+
+    /// The accumulator emitter of the program, `None` if none was ever set.
+    pub fn load_accumulator_emitter<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<Option<Ref<'a, AccumulatorEmitter>>, ProgramError> {
+        if account.data_len() < Self::EMITTER_ACCOUNT_SPACE {
+            return Ok(None);
+        }
+        Ok(Some(Ref::map(account.try_borrow_data()?, |data| {
+            bytemuck::from_bytes(&data[Self::EMITTER_OFFSET..Self::EMITTER_ACCOUNT_SPACE])
+        })))
+    }
+
+    pub fn load_accumulator_emitter_mut<'a>(
+        account: &'a AccountInfo,
+    ) -> Result<RefMut<'a, AccumulatorEmitter>, ProgramError> {
+        if account.data_len() < Self::EMITTER_ACCOUNT_SPACE {
+            return Err(ProgramError::AccountDataTooSmall);
+        }
+        Ok(RefMut::map(account.try_borrow_mut_data()?, |data| {
+            bytemuck::from_bytes_mut(&mut data[Self::EMITTER_OFFSET..Self::EMITTER_ACCOUNT_SPACE])
+        }))
+    }
+
+    /// Whether `account` is the permission account of the program. Only the program writes the
+    /// accounts it owns, and it only writes the permissions account type at the permissions PDA,
+    /// so the PDA doesn't need to be derived.
+    pub fn is_permissions_account(program_id: &Pubkey, account: &AccountInfo) -> bool {
+        account.owner == program_id
+            && account.data_len() >= size_of::<PermissionAccount>()
+            && load_account_as::<AccountHeader>(account)
+                .map(|header| {
+                    header.magic_number == PC_MAGIC && header.account_type == PC_ACCTYPE_PERMISSIONS
+                })
+                .unwrap_or(false)
+    }
 }
 
 impl PythAccount for PermissionAccount {
@@ -1,3 +1,5 @@
+#[cfg(any(test, feature = "library"))]
+use crate::error::OracleError;
 pub use price_pythnet::*;
 #[cfg(test)]
 use quickcheck::Arbitrary;
@@ -5,18 +7,34 @@ use {
     super::{
         AccountHeader,
         PythAccount,
+        EXTENSIONS_RESERVED_SPACE,
+    },
+    crate::c_oracle_header::{
+        PC_ACCTYPE_PRICE,
+        PC_PTYPE_FUNDING,
+        PC_PTYPE_INDEX,
+        PC_PTYPE_NAV,
+        PC_PTYPE_PRICE,
     },
-    crate::c_oracle_header::PC_ACCTYPE_PRICE,
     bytemuck::{
         Pod,
         Zeroable,
     },
+    num_derive::{
+        FromPrimitive,
+        ToPrimitive,
+    },
+    num_traits::FromPrimitive as _,
     pythnet_sdk::messages::{
         PriceFeedMessage,
         PublisherStakeCapsMessage,
         TwapMessage,
     },
-    solana_program::pubkey::Pubkey,
+    solana_program::{
+        clock::Clock,
+        hash::hashv,
+        pubkey::Pubkey,
+    },
     std::{
         mem::size_of,
         u16,
@@ -27,10 +45,16 @@ use {
 mod price_pythnet {
     pub type PriceAccount = PriceAccountPythnet;
 
+    #[cfg(feature = "borsh")]
+    use borsh::{
+        BorshDeserialize,
+        BorshSerialize,
+    };
     use {
         super::*,
         crate::c_oracle_header::{
             PC_MAX_SEND_LATENCY,
+            PC_MAX_SEND_LATENCY_NAV,
             PC_NUM_COMP_PYTHNET,
             PC_STATUS_TRADING,
         },
@@ -41,10 +65,15 @@ mod price_pythnet {
     /// an append-only change that adds extra publisher slots and
     /// PriceCumulative for TWAP processing.
     #[repr(C)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(
+        feature = "borsh",
+        derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+    )]
     #[derive(Copy, Clone, Pod, Zeroable)]
     pub struct PriceAccountPythnet {
         pub header:             AccountHeader,
-        /// Type of the price account
+        /// Type of the price account, see `FeedKind`
         pub price_type:         u32,
         /// Exponent for the published prices
         pub exponent:           i32,
@@ -90,6 +119,7 @@ mod price_pythnet {
         /// PC_NUM_COMP_PYTHNET slots are used due to stack size
         /// issues in the C code. For iterating over price components,
         /// PC_NUM_COMP must be used.
+        #[cfg_attr(feature = "serde", serde(with = "crate::accounts::serde_array"))]
         pub comp_:              [PriceComponent; PC_NUM_COMP_PYTHNET as usize],
         /// Cumulative sums of aggregative price and confidence used to compute arithmetic moving averages
         pub price_cumulative:   PriceCumulative,
@@ -97,10 +127,16 @@ mod price_pythnet {
 
     // Feed index is limited to 28 bites so that it can be packed
     // together with trading status in a single u32.
+    #[cfg(not(feature = "wasm"))]
     pub const MAX_FEED_INDEX: u32 = (1 << 28) - 1;
 
     bitflags! {
+        /// Bits that aren't defined below were set by a newer version of the program. Every
+        /// instruction only inserts or removes the flags it knows about and keeps the other bits
+        /// as they are, so that a cluster running an older version doesn't strip the features a
+        /// newer version enabled, e.g. while an upgrade is rolled out one cluster at a time.
         #[repr(C)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[derive(Copy, Clone, Pod, Zeroable)]
         pub struct PriceAccountFlags: u8 {
             /// If set, the program doesn't do accumulation, but validator does.
@@ -110,10 +146,126 @@ mod price_pythnet {
             const MESSAGE_BUFFER_CLEARED = 0b10;
             /// If set, the program allows publishing of zero confidence interval updates.
             const ALLOW_ZERO_CI = 0b100;
+            /// If set, the feed is replaced by the price account of its `PriceSuccessor`
+            /// extension and consumers should move to it.
+            const DEPRECATED = 0b1000;
+            /// If set, publishers can only update the feed in transactions without
+            /// instructions of other programs, see `is_isolated_transaction`.
+            const ISOLATED_UPDATES = 0b10000;
+            /// Set while the program aggregates the feed, e.g. while the sanity program checks
+            /// the aggregate before the EMA is updated. Instructions reading or writing the
+            /// aggregate fail while it is set, so that they never see `agg_` and the EMA out of
+            /// step, even if an aggregation could be interrupted.
+            const AGGREGATION_IN_PROGRESS = 0b100000;
+            /// If set, the feed was listed without governance by the creator in its
+            /// `FeedCreator` extension, see `AddCommunityPrice`. `UpdPrice` doesn't send the
+            /// aggregates of community feeds cross-chain.
+            const COMMUNITY = 0b1000000;
+        }
+    }
+
+    impl PriceAccountFlags {
+        /// Bits that this version of the program doesn't know about, see `PriceAccountFlags`.
+        #[cfg(any(test, feature = "library"))]
+        pub fn unknown_bits(&self) -> u8 {
+            self.bits() & !Self::all().bits()
+        }
+    }
+
+    // Flags are encoded as their bits, unknown flags included
+    #[cfg(feature = "borsh")]
+    impl BorshSerialize for PriceAccountFlags {
+        fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+            self.bits().serialize(writer)
+        }
+    }
+
+    #[cfg(feature = "borsh")]
+    impl BorshDeserialize for PriceAccountFlags {
+        fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+            Ok(Self::from_bits_retain(u8::deserialize(buf)?))
         }
     }
 
     impl PriceAccountPythnet {
+        /// Kind of the feed, `None` for the price types that predate `FeedKind`, whose quotes are
+        /// validated as spot prices.
+        pub fn feed_kind(&self) -> Option<FeedKind> {
+            FeedKind::from_u32(self.price_type)
+        }
+
+        /// Maximum age in slots of the quotes that count in an aggregation, as `upd_aggregate`
+        /// computes it in the C code. NAV feeds ignore `max_latency_`.
+        pub fn max_latency(&self) -> u64 {
+            match (self.feed_kind(), self.max_latency_) {
+                (Some(FeedKind::Nav), _) => u64::from(PC_MAX_SEND_LATENCY_NAV),
+                (_, 0) => u64::from(PC_MAX_SEND_LATENCY),
+                (_, max_latency) => u64::from(max_latency),
+            }
+        }
+
+        /// Price that consumers should read: the aggregate if it is trading, otherwise the last
+        /// trading aggregate. This is the same fallback as `as_price_feed_message`.
+        pub fn get_latest_price(&self) -> LatestPrice {
+            let (price, conf, publish_slot, publish_time) =
+                if self.agg_.status_ == PC_STATUS_TRADING {
+                    (
+                        self.agg_.price_,
+                        self.agg_.conf_,
+                        self.agg_.pub_slot_,
+                        self.timestamp_,
+                    )
+                } else {
+                    (
+                        self.prev_price_,
+                        self.prev_conf_,
+                        self.prev_slot_,
+                        self.prev_timestamp_,
+                    )
+                };
+
+            LatestPrice {
+                price,
+                conf,
+                exponent: self.exponent,
+                publish_slot,
+                publish_time,
+            }
+        }
+
+        /// Latest price, unless it was published more than `max_age` slots before
+        /// `current_slot`.
+        #[cfg(any(test, feature = "library"))]
+        pub fn get_price_no_older_than(
+            &self,
+            current_slot: u64,
+            max_age: u64,
+        ) -> Option<LatestPrice> {
+            Some(self.get_latest_price())
+                .filter(|price| price.is_no_older_than(current_slot, max_age))
+        }
+
+        /// Latest price whatever its age, under the name pyth-sdk gives it. Consumers should
+        /// prefer `get_price_no_older_than`.
+        #[cfg(any(test, feature = "library"))]
+        pub fn get_price_unchecked(&self) -> LatestPrice {
+            self.get_latest_price()
+        }
+
+        /// Exponential moving averages of the aggregate price and confidence, as of the slot of
+        /// the latest price, or the slot-weighted TWAP for feeds publishing it instead, see
+        /// `AverageMode`. This is the EMA of `as_price_feed_message`.
+        pub fn get_ema_price(&self) -> LatestPrice {
+            let latest_price = self.get_latest_price();
+            LatestPrice {
+                price:        self.twap_.val_,
+                conf:         self.twac_.val_ as u64,
+                exponent:     self.exponent,
+                publish_slot: latest_price.publish_slot,
+                publish_time: latest_price.publish_time,
+            }
+        }
+
         pub fn as_price_feed_message(&self, key: &Pubkey) -> PriceFeedMessage {
             let (price, conf, publish_time) = if self.agg_.status_ == PC_STATUS_TRADING {
                 (self.agg_.price_, self.agg_.conf_, self.timestamp_)
@@ -162,11 +314,77 @@ mod price_pythnet {
                 publish_slot: self.last_slot_,
             }
         }
+
+        /// Quotes of the publishers as they were snapshotted by the latest aggregation.
+        #[cfg(any(test, feature = "library"))]
+        pub fn as_publisher_prices_message(&self, key: &Pubkey) -> PublisherPricesMessage {
+            let publish_time = if self.agg_.status_ == PC_STATUS_TRADING {
+                self.timestamp_
+            } else {
+                self.prev_timestamp_
+            };
+
+            PublisherPricesMessage {
+                feed_id: key.to_bytes(),
+                publish_time,
+                publish_slot: self.agg_.pub_slot_,
+                exponent: self.exponent,
+                publishers: self
+                    .comp_
+                    .iter()
+                    .take(self.num_ as usize)
+                    .map(|component| PublisherPrice {
+                        publisher: component.pub_.to_bytes(),
+                        price:     component.agg_.price_,
+                        conf:      component.agg_.conf_,
+                        slot:      component.agg_.pub_slot_,
+                        venue:     component.agg_.venue(),
+                    })
+                    .collect(),
+            }
+        }
+
+        /// Final state of the feed sent by `DelPrice`, `num_updates` is read from the
+        /// `UpdateCount` extension.
+        pub fn as_tombstone_message(
+            &self,
+            key: &Pubkey,
+            clock: &Clock,
+            num_updates: u64,
+        ) -> FeedTombstoneMessage {
+            FeedTombstoneMessage {
+                feed_id: key.to_bytes(),
+                publish_time: clock.unix_timestamp,
+                publish_slot: clock.slot,
+                exponent: self.exponent,
+                price: self.agg_.price_,
+                conf: self.agg_.conf_,
+                status: self.agg_.status_,
+                aggregate_slot: self.agg_.pub_slot_,
+                num_updates,
+                publishers_hash: self.publishers_hash(),
+            }
+        }
+
+        /// Hash of the publisher keys of the components in their order.
+        pub fn publishers_hash(&self) -> [u8; 32] {
+            let publishers: Vec<&[u8]> = self
+                .comp_
+                .iter()
+                .take(self.num_ as usize)
+                .map(|component| component.pub_.as_ref())
+                .collect();
+            hashv(&publishers).to_bytes()
+        }
     }
 
     impl PythAccount for PriceAccountPythnet {
         const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PRICE;
         const INITIAL_SIZE: u32 = size_of::<PriceAccountPythnet>() as u32;
+        /// Price PDAs are created with room for their first extensions, see
+        /// `EXTENSIONS_RESERVED_SPACE`.
+        const NEW_ACCOUNT_SPACE: usize =
+            size_of::<PriceAccountPythnet>() + EXTENSIONS_RESERVED_SPACE;
     }
 
     // This struct can't overflow since :
@@ -177,6 +395,11 @@ mod price_pythnet {
     /// Informally the TWAP between time t and time T can be computed as :
     /// `(T.price_cumulative.price - t.price_cumulative.price) / (T.agg_.pub_slot_ - t.agg_.pub_slot_)`
     #[repr(C)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(
+        feature = "borsh",
+        derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+    )]
     #[derive(Copy, Clone, Pod, Zeroable)]
     pub struct PriceCumulative {
         /// Cumulative sum of price * slot_gap
@@ -208,8 +431,75 @@ mod price_pythnet {
     }
 }
 
+/// What a feed publishes, stored in the `price_type` of its price account when it is created. The
+/// kind tailors the validation of the quotes of the feed.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
+pub enum FeedKind {
+    /// Price of an asset
+    Spot    = PC_PTYPE_PRICE,
+    /// Funding rate of a perpetual, which can be negative and close to 0: the quotes count
+    /// whatever their confidence interval relative to the rate
+    Funding = PC_PTYPE_FUNDING,
+    /// Net asset value of a fund, published a few times a day: the quotes count in the aggregates
+    /// for `PC_MAX_SEND_LATENCY_NAV` slots
+    Nav     = PC_PTYPE_NAV,
+    /// Index computed from other prices, validated as a spot price
+    Index   = PC_PTYPE_INDEX,
+}
+
+/// Price of a feed as read by consumers, see `PriceAccount::get_latest_price`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LatestPrice {
+    pub price:        i64,
+    pub conf:         u64,
+    pub exponent:     i32,
+    pub publish_slot: u64,
+    /// Unix timestamp of the clock when the price was aggregated
+    pub publish_time: i64,
+}
+
+impl LatestPrice {
+    /// Freshness rule shared by on-chain and off-chain consumers: a price is fresh if it was
+    /// published at most `max_age` slots before `current_slot`.
+    #[cfg(any(test, feature = "library"))]
+    pub fn is_no_older_than(&self, current_slot: u64, max_age: u64) -> bool {
+        current_slot.saturating_sub(self.publish_slot) <= max_age
+    }
+
+    /// The same price and confidence in `target_exponent`, `None` if they don't fit. Scaling to a
+    /// larger exponent truncates towards zero, with the rounding of pyth-sdk.
+    #[cfg(any(test, feature = "library"))]
+    pub fn scale_to_exponent(&self, target_exponent: i32) -> Option<LatestPrice> {
+        let mut delta = target_exponent.checked_sub(self.exponent)?;
+        let (mut price, mut conf) = (self.price, self.conf);
+        while delta > 0 && (price != 0 || conf != 0) {
+            price /= 10;
+            conf /= 10;
+            delta -= 1;
+        }
+        while delta < 0 {
+            price = price.checked_mul(10)?;
+            conf = conf.checked_mul(10)?;
+            delta += 1;
+        }
+        Some(LatestPrice {
+            price,
+            conf,
+            exponent: target_exponent,
+            publish_slot: self.publish_slot,
+            publish_time: self.publish_time,
+        })
+    }
+}
+
 #[repr(C)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct PriceComponent {
     pub pub_:    Pubkey,
@@ -232,15 +522,43 @@ impl Arbitrary for PriceComponent {
 
 #[repr(C)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct PriceInfo {
     pub price_:           i64,
     pub conf_:            u64,
     pub status_:          u32,
+    /// Tags of a publisher quote: its venue in the low byte, see `UpdPriceArgs::venue`, and the
+    /// status supplied by its publisher in the next one. The aggregation snapshots them with the
+    /// rest of the quote
     pub corp_act_status_: u32,
     pub pub_slot_:        u64,
 }
 
+impl PriceInfo {
+    /// Venue of a publisher quote, 0 if untagged.
+    pub fn venue(&self) -> u8 {
+        self.corp_act_status_ as u8
+    }
+
+    /// Status the publisher supplied with a quote. `status_` only tells whether the quote is
+    /// valid: a trading quote whose confidence is too wide is stored `PC_STATUS_IGNORED`. Quotes
+    /// published before the status was kept read as `PC_STATUS_UNKNOWN`.
+    pub fn publisher_status(&self) -> u32 {
+        (self.corp_act_status_ >> 8) & 0xff
+    }
+
+    /// Tag a publisher quote with its venue and the status supplied by its publisher. Statuses
+    /// that don't fit in a byte are kept as `u8::MAX`.
+    pub fn set_tags(&mut self, venue: u8, publisher_status: u32) {
+        self.corp_act_status_ = u32::from(venue) | (publisher_status.min(0xff) << 8);
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for PriceInfo {
     fn arbitrary(g: &mut quickcheck::Gen) -> Self {
@@ -255,6 +573,11 @@ impl Arbitrary for PriceInfo {
 }
 
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct PriceEma {
     pub val_:   i64,
@@ -262,6 +585,64 @@ pub struct PriceEma {
     pub denom_: i64,
 }
 
+/// Per-publisher details of an aggregation, sent along with the `PriceFeedMessage` so that
+/// consumers can see which quotes the aggregate was computed from. This message isn't part of
+/// `pythnet_sdk` yet, it uses the next free discriminator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublisherPricesMessage {
+    pub feed_id:      [u8; 32],
+    pub publish_time: i64,
+    pub publish_slot: u64,
+    pub exponent:     i32,
+    pub publishers:   Vec<PublisherPrice>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublisherPrice {
+    pub publisher: [u8; 32],
+    pub price:     i64,
+    pub conf:      u64,
+    /// Slot at which the publisher published this quote
+    pub slot:      u64,
+    /// Venue the publisher tagged this quote with, 0 if untagged
+    pub venue:     u8,
+}
+
+/// Metadata of a product as set by `UpdProduct` or `PatchProduct`, sent so that consumers on
+/// other chains learn about new listings and symbol changes without reading the product account.
+/// This message isn't part of `pythnet_sdk` yet, it uses the next free discriminator.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProductMetadataMessage {
+    pub product_id:   [u8; 32],
+    pub publish_time: i64,
+    pub publish_slot: u64,
+    /// Key-value pairs in the order of the product account, each at most
+    /// `PRODUCT_METADATA_MAX_VALUE_LEN` bytes long
+    pub metadata:     Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Last aggregate of a price feed sent when its account is deleted, so that consumers on other
+/// chains can archive the feed and tell a deleted feed from a stalled one. This message isn't part
+/// of `pythnet_sdk` yet, it uses the next free discriminator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeedTombstoneMessage {
+    pub feed_id:         [u8; 32],
+    /// Time of the deletion
+    pub publish_time:    i64,
+    /// Slot of the deletion
+    pub publish_slot:    u64,
+    pub exponent:        i32,
+    pub price:           i64,
+    pub conf:            u64,
+    pub status:          u32,
+    /// Slot of the last aggregate
+    pub aggregate_slot:  u64,
+    /// Updates accepted while `SetUpdateCounting` was enabled, 0 if it never was
+    pub num_updates:     u64,
+    /// Hash of the keys of the publishers of the feed, see `publishers_hash`
+    pub publishers_hash: [u8; 32],
+}
+
 pub trait PythOracleSerialize {
     fn to_bytes(self) -> Vec<u8>;
 }
@@ -371,3 +752,250 @@ impl PythOracleSerialize for PublisherStakeCapsMessage {
         result
     }
 }
+
+impl PythOracleSerialize for PublisherPricesMessage {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut result = vec![Self::DISCRIMINATOR];
+        result.extend_from_slice(&self.feed_id);
+        result.extend_from_slice(&self.publish_time.to_be_bytes());
+        result.extend_from_slice(&self.publish_slot.to_be_bytes());
+        result.extend_from_slice(&self.exponent.to_be_bytes());
+        result.extend_from_slice(
+            &u16::try_from(self.publishers.len())
+                .unwrap_or(u16::MAX)
+                .to_be_bytes(),
+        );
+
+        for publisher in self.publishers {
+            result.extend_from_slice(&publisher.publisher);
+            result.extend_from_slice(&publisher.price.to_be_bytes());
+            result.extend_from_slice(&publisher.conf.to_be_bytes());
+            result.extend_from_slice(&publisher.slot.to_be_bytes());
+            result.push(publisher.venue);
+        }
+
+        result
+    }
+}
+
+impl PublisherPricesMessage {
+    pub const DISCRIMINATOR: u8 = 3;
+}
+
+// The validator writes the compressed messages and consumers decode them, the program does neither
+#[cfg(any(test, feature = "library"))]
+impl PublisherPricesMessage {
+    pub const COMPRESSED_DISCRIMINATOR: u8 = 6;
+
+    /// Encoding of the message written to the V2 accumulator, under its own discriminator. The
+    /// header is the one of `to_bytes`, followed by a bitmap of the publishers whose quote has a
+    /// venue, one bit per publisher starting from the low bit of the first byte. Each publisher
+    /// then takes its key, its price as a zigzag varint of the difference with the price of the
+    /// previous publisher, its confidence as a varint, the publish slot of the message minus the
+    /// slot of its quote as a zigzag varint, and its venue if its bit is set. Varints are LEB128.
+    pub fn to_compressed_bytes(&self) -> Vec<u8> {
+        let num_publishers = self.publishers.len().min(usize::from(u16::MAX));
+        let publishers = &self.publishers[..num_publishers];
+
+        let mut result = vec![Self::COMPRESSED_DISCRIMINATOR];
+        result.extend_from_slice(&self.feed_id);
+        result.extend_from_slice(&self.publish_time.to_be_bytes());
+        result.extend_from_slice(&self.publish_slot.to_be_bytes());
+        result.extend_from_slice(&self.exponent.to_be_bytes());
+        result.extend_from_slice(&(num_publishers as u16).to_be_bytes());
+
+        let mut venues = vec![0u8; (num_publishers + 7) / 8];
+        for (i, publisher) in publishers.iter().enumerate() {
+            if publisher.venue != 0 {
+                venues[i / 8] |= 1 << (i % 8);
+            }
+        }
+        result.extend_from_slice(&venues);
+
+        let mut previous_price = 0i64;
+        for publisher in publishers {
+            result.extend_from_slice(&publisher.publisher);
+            write_varint(
+                &mut result,
+                zigzag(publisher.price.wrapping_sub(previous_price)),
+            );
+            write_varint(&mut result, publisher.conf);
+            write_varint(
+                &mut result,
+                zigzag(self.publish_slot.wrapping_sub(publisher.slot) as i64),
+            );
+            if publisher.venue != 0 {
+                result.push(publisher.venue);
+            }
+            previous_price = publisher.price;
+        }
+
+        result
+    }
+
+    /// Decode a message written by `to_bytes` or by `to_compressed_bytes`, for consumers
+    /// verifying the messages of the accumulator off-chain.
+    pub fn decode(data: &[u8]) -> Result<Self, OracleError> {
+        let mut reader = MessageReader { data };
+        let discriminator = reader.read::<1>()?[0];
+        let compressed = match discriminator {
+            Self::DISCRIMINATOR => false,
+            Self::COMPRESSED_DISCRIMINATOR => true,
+            _ => return Err(OracleError::DeserializationError),
+        };
+        let feed_id = reader.read::<32>()?;
+        let publish_time = i64::from_be_bytes(reader.read()?);
+        let publish_slot = u64::from_be_bytes(reader.read()?);
+        let exponent = i32::from_be_bytes(reader.read()?);
+        let num_publishers = usize::from(u16::from_be_bytes(reader.read()?));
+
+        let mut publishers = Vec::with_capacity(num_publishers);
+        if compressed {
+            let venues = reader.read_slice((num_publishers + 7) / 8)?;
+            let mut previous_price = 0i64;
+            for i in 0..num_publishers {
+                let publisher = reader.read::<32>()?;
+                let price = previous_price.wrapping_add(unzigzag(reader.read_varint()?));
+                let conf = reader.read_varint()?;
+                let slot = publish_slot.wrapping_sub(unzigzag(reader.read_varint()?) as u64);
+                let venue = if venues[i / 8] & (1 << (i % 8)) != 0 {
+                    reader.read::<1>()?[0]
+                } else {
+                    0
+                };
+                publishers.push(PublisherPrice {
+                    publisher,
+                    price,
+                    conf,
+                    slot,
+                    venue,
+                });
+                previous_price = price;
+            }
+        } else {
+            for _ in 0..num_publishers {
+                publishers.push(PublisherPrice {
+                    publisher: reader.read()?,
+                    price:     i64::from_be_bytes(reader.read()?),
+                    conf:      u64::from_be_bytes(reader.read()?),
+                    slot:      u64::from_be_bytes(reader.read()?),
+                    venue:     reader.read::<1>()?[0],
+                });
+            }
+        }
+
+        if !reader.data.is_empty() {
+            return Err(OracleError::DeserializationError);
+        }
+        Ok(PublisherPricesMessage {
+            feed_id,
+            publish_time,
+            publish_slot,
+            exponent,
+            publishers,
+        })
+    }
+}
+
+#[cfg(any(test, feature = "library"))]
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(any(test, feature = "library"))]
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+#[cfg(any(test, feature = "library"))]
+fn write_varint(result: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        result.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    result.push(value as u8);
+}
+
+/// Reads the fields of an encoded message, failing on truncated data.
+#[cfg(any(test, feature = "library"))]
+struct MessageReader<'a> {
+    data: &'a [u8],
+}
+
+#[cfg(any(test, feature = "library"))]
+impl<'a> MessageReader<'a> {
+    fn read_slice(&mut self, len: usize) -> Result<&'a [u8], OracleError> {
+        if self.data.len() < len {
+            return Err(OracleError::DeserializationError);
+        }
+        let (value, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(value)
+    }
+
+    fn read<const N: usize>(&mut self) -> Result<[u8; N], OracleError> {
+        let mut value = [0u8; N];
+        value.copy_from_slice(self.read_slice(N)?);
+        Ok(value)
+    }
+
+    /// Varints longer than 10 bytes, or with bits beyond the 64th, are rejected.
+    fn read_varint(&mut self) -> Result<u64, OracleError> {
+        let mut value = 0u64;
+        for i in 0..10 {
+            let byte = self.read::<1>()?[0];
+            let bits = u64::from(byte & 0x7f);
+            if i == 9 && bits > 1 {
+                return Err(OracleError::DeserializationError);
+            }
+            value |= bits << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(OracleError::DeserializationError)
+    }
+}
+
+impl PythOracleSerialize for ProductMetadataMessage {
+    fn to_bytes(self) -> Vec<u8> {
+        const DISCRIMINATOR: u8 = 4;
+        let mut result = vec![DISCRIMINATOR];
+        result.extend_from_slice(&self.product_id);
+        result.extend_from_slice(&self.publish_time.to_be_bytes());
+        result.extend_from_slice(&self.publish_slot.to_be_bytes());
+        result.extend_from_slice(
+            &u16::try_from(self.metadata.len())
+                .unwrap_or(u16::MAX)
+                .to_be_bytes(),
+        );
+
+        // Keys and values are prefixed by their length, like in the product account
+        for (key, value) in self.metadata {
+            result.push(u8::try_from(key.len()).unwrap_or(u8::MAX));
+            result.extend_from_slice(&key);
+            result.push(u8::try_from(value.len()).unwrap_or(u8::MAX));
+            result.extend_from_slice(&value);
+        }
+
+        result
+    }
+}
+
+impl PythOracleSerialize for FeedTombstoneMessage {
+    fn to_bytes(self) -> Vec<u8> {
+        const DISCRIMINATOR: u8 = 5;
+        let mut result = vec![DISCRIMINATOR];
+        result.extend_from_slice(&self.feed_id);
+        result.extend_from_slice(&self.publish_time.to_be_bytes());
+        result.extend_from_slice(&self.publish_slot.to_be_bytes());
+        result.extend_from_slice(&self.exponent.to_be_bytes());
+        result.extend_from_slice(&self.price.to_be_bytes());
+        result.extend_from_slice(&self.conf.to_be_bytes());
+        result.extend_from_slice(&self.status.to_be_bytes());
+        result.extend_from_slice(&self.aggregate_slot.to_be_bytes());
+        result.extend_from_slice(&self.num_updates.to_be_bytes());
+        result.extend_from_slice(&self.publishers_hash);
+        result
+    }
+}
@@ -8,28 +8,40 @@ use {
             PC_ACCTYPE_PRODUCT,
             PC_PROD_ACC_SIZE,
         },
-        deserialize::load_checked,
-        instruction::CommandHeader,
-        utils::{
-            pyth_assert,
-            try_convert,
-        },
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::try_convert,
     },
     bytemuck::{
         Pod,
         Zeroable,
     },
     solana_program::{
-        account_info::AccountInfo,
-        entrypoint::ProgramResult,
         program_error::ProgramError,
-        program_memory::sol_memcpy,
         pubkey::Pubkey,
     },
     std::mem::size_of,
 };
+#[cfg(not(feature = "wasm"))]
+use {
+    crate::{
+        deserialize::load_checked,
+        instruction::CommandHeader,
+        utils::pyth_assert,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_memory::sol_memcpy,
+    },
+};
 
 #[repr(C)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 pub struct ProductAccount {
     pub header:              AccountHeader,
@@ -47,6 +59,7 @@ impl PythAccount for ProductAccount {
 /// where keys are strings and values are strings
 /// that is represented as a byte array with the following schema :
 /// `[len(key1), ...key1, len(val1), ...val1, len(key2), ...key2, len(val2), ...val2, ...]`
+#[cfg(not(feature = "wasm"))]
 pub fn update_product_metadata(
     instruction_data: &[u8],
     product_account: &AccountInfo,
@@ -91,6 +104,178 @@ pub fn update_product_metadata(
     Ok(())
 }
 
+/// Keys that must be present (with a non-empty value) in the metadata of every product.
+#[cfg(not(feature = "wasm"))]
+pub const PRODUCT_METADATA_REQUIRED_KEYS: [&str; 3] = ["symbol", "asset_type", "quote_currency"];
+/// Maximum length in bytes of a product metadata key.
+#[cfg(not(feature = "wasm"))]
+pub const PRODUCT_METADATA_MAX_KEY_LEN: usize = 32;
+/// Maximum length in bytes of a product metadata value.
+#[cfg(not(feature = "wasm"))]
+pub const PRODUCT_METADATA_MAX_VALUE_LEN: usize = 128;
+
+/// Validates the product metadata provided at the end of `instruction_data` against the schema
+/// expected by downstream consumers :
+/// - keys and values are UTF-8 strings within `PRODUCT_METADATA_MAX_KEY_LEN` and
+///   `PRODUCT_METADATA_MAX_VALUE_LEN` bytes, keys are not empty
+/// - every key appears at most once
+/// - every key of `PRODUCT_METADATA_REQUIRED_KEYS` is present with a non-empty value
+#[cfg(not(feature = "wasm"))]
+pub fn validate_product_metadata(instruction_data: &[u8]) -> ProgramResult {
+    pyth_assert(
+        instruction_data.len() >= size_of::<CommandHeader>(),
+        ProgramError::InvalidInstructionData,
+    )?;
+
+    let new_data = &instruction_data[size_of::<CommandHeader>()..];
+    let mut keys: Vec<&str> = vec![];
+    let mut idx = 0;
+    while idx < new_data.len() {
+        let key = read_pc_str_t(&new_data[idx..])?;
+        idx += key.len();
+        let value = read_pc_str_t(&new_data[idx..])?;
+        idx += value.len();
+
+        let key = std::str::from_utf8(&key[1..])
+            .map_err(|_| OracleError::InvalidProductMetadataString)?;
+        let value = std::str::from_utf8(&value[1..])
+            .map_err(|_| OracleError::InvalidProductMetadataString)?;
+        pyth_assert(
+            !key.is_empty()
+                && key.len() <= PRODUCT_METADATA_MAX_KEY_LEN
+                && value.len() <= PRODUCT_METADATA_MAX_VALUE_LEN,
+            OracleError::InvalidProductMetadataString.into(),
+        )?;
+        pyth_assert(
+            !keys.contains(&key),
+            OracleError::DuplicateProductMetadataKey.into(),
+        )?;
+        pyth_assert(
+            !PRODUCT_METADATA_REQUIRED_KEYS.contains(&key) || !value.is_empty(),
+            OracleError::MissingProductMetadataKey.into(),
+        )?;
+        keys.push(key);
+    }
+
+    for required_key in PRODUCT_METADATA_REQUIRED_KEYS.iter() {
+        pyth_assert(
+            keys.contains(required_key),
+            OracleError::MissingProductMetadataKey.into(),
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Look up the value of `key` in the metadata of the product account stored in `data`.
+pub fn get_product_metadata<'a>(
+    data: &'a [u8],
+    key: &str,
+) -> Result<Option<&'a [u8]>, ProgramError> {
+    let account_size: usize = try_convert(load_account_data::<ProductAccount>(data)?.header.size)?;
+    let kv_data = data
+        .get(size_of::<ProductAccount>()..account_size)
+        .ok_or(OracleError::InvalidAccountHeader)?;
+    find_metadata_value(kv_data, key)
+}
+
+/// Look up the value of `key` in the metadata following the command header of
+/// `instruction_data`, as `UpdProduct` would store it.
+#[cfg(not(feature = "wasm"))]
+pub fn get_instruction_metadata<'a>(
+    instruction_data: &'a [u8],
+    key: &str,
+) -> Result<Option<&'a [u8]>, ProgramError> {
+    let kv_data = instruction_data
+        .get(size_of::<CommandHeader>()..)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    find_metadata_value(kv_data, key)
+}
+
+fn find_metadata_value<'a>(kv_data: &'a [u8], key: &str) -> Result<Option<&'a [u8]>, ProgramError> {
+    let mut idx = 0;
+    while idx < kv_data.len() {
+        let kv_key = read_pc_str_t(&kv_data[idx..])?;
+        idx += kv_key.len();
+        let kv_value = read_pc_str_t(&kv_data[idx..])?;
+        idx += kv_value.len();
+
+        if &kv_key[1..] == key.as_bytes() {
+            return Ok(Some(&kv_value[1..]));
+        }
+    }
+    Ok(None)
+}
+
+/// Key-value pairs of the metadata of the product account stored in `data`, in storage order.
+pub fn get_product_metadata_entries(data: &[u8]) -> Result<Vec<(&[u8], &[u8])>, ProgramError> {
+    let account_size: usize = try_convert(load_account_data::<ProductAccount>(data)?.header.size)?;
+    let kv_data = data
+        .get(size_of::<ProductAccount>()..account_size)
+        .ok_or(OracleError::InvalidAccountHeader)?;
+
+    let mut entries = vec![];
+    let mut idx = 0;
+    while idx < kv_data.len() {
+        let kv_key = read_pc_str_t(&kv_data[idx..])?;
+        idx += kv_key.len();
+        let kv_value = read_pc_str_t(&kv_data[idx..])?;
+        idx += kv_value.len();
+        entries.push((&kv_key[1..], &kv_value[1..]));
+    }
+    Ok(entries)
+}
+
+/// Instruction data of an `UpdProduct` overwriting the metadata of the product account stored in
+/// `data` with its patch by the key-value pairs at the end of `instruction_data`. A pair replaces
+/// the value of its key in place or adds the key at the end, and a pair with an empty value
+/// deletes its key. The keys missing from the pairs keep their values.
+#[cfg(not(feature = "wasm"))]
+pub fn patch_product_metadata(
+    data: &[u8],
+    instruction_data: &[u8],
+) -> Result<Vec<u8>, ProgramError> {
+    pyth_assert(
+        instruction_data.len() >= size_of::<CommandHeader>(),
+        ProgramError::InvalidInstructionData,
+    )?;
+
+    let mut entries = get_product_metadata_entries(data)?;
+    let patch = &instruction_data[size_of::<CommandHeader>()..];
+    let mut patched_keys: Vec<&[u8]> = vec![];
+    let mut idx = 0;
+    while idx < patch.len() {
+        let key = read_pc_str_t(&patch[idx..])?;
+        idx += key.len();
+        let value = read_pc_str_t(&patch[idx..])?;
+        idx += value.len();
+
+        let (key, value) = (&key[1..], &value[1..]);
+        pyth_assert(
+            !patched_keys.contains(&key),
+            OracleError::DuplicateProductMetadataKey.into(),
+        )?;
+        patched_keys.push(key);
+        match entries.iter().position(|(entry_key, _)| *entry_key == key) {
+            Some(index) if value.is_empty() => {
+                entries.remove(index);
+            }
+            Some(index) => entries[index].1 = value,
+            None if value.is_empty() => {}
+            None => entries.push((key, value)),
+        }
+    }
+
+    let mut patched = instruction_data[..size_of::<CommandHeader>()].to_vec();
+    for (key, value) in entries {
+        for string in [key, value] {
+            patched.push(try_convert(string.len())?);
+            patched.extend_from_slice(string);
+        }
+    }
+    Ok(patched)
+}
+
 /// Read a `pc_str_t` from the beginning of `source`. Returns a slice of `source` containing
 /// the bytes of the `pc_str_t`.
 pub fn read_pc_str_t(source: &[u8]) -> Result<&[u8], ProgramError> {
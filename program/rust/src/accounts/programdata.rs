@@ -0,0 +1,98 @@
+use {
+    crate::{
+        deserialize::load_account_as,
+        error::OracleError,
+        utils::pyth_assert,
+    },
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        bpf_loader_upgradeable,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::cell::Ref,
+};
+
+/// This struct represents UpgradeableLoaderState from bpf-upgradable-loader.
+/// Solana uses bincode for the struct. However the bincode crate is too big the space we have onchain,
+/// therefore we will use bytemuck for deserialization
+#[repr(C)]
+#[derive(Pod, Zeroable, Copy, Clone)]
+pub struct ProgramdataAccount {
+    /// 3 is the variant for programdata
+    pub account_type:          u32,
+    /// Space for slot of last upgrade (we don't use this field)
+    pub slot:                  [u32; 2],
+    /// 0 if immutable, 1 if has upgrade authority
+    pub has_upgrade_authority: u8,
+    /// Upgrade authority of the program
+    pub upgrade_authority:     Pubkey,
+    /// Unused field needed for this struct to be Pod
+    pub unused:                [u8; 3],
+}
+
+impl ProgramdataAccount {
+    pub const ACCOUNT_TYPE: u32 = 3;
+
+    /// Address of the programdata account of `program_id` under the upgradeable loader.
+    pub fn address(program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id()).0
+    }
+
+    /// The upgrade authority of the program, `None` if the program is immutable.
+    pub fn upgrade_authority(&self) -> Option<Pubkey> {
+        if self.has_upgrade_authority == 1 {
+            Some(self.upgrade_authority)
+        } else {
+            None
+        }
+    }
+}
+
+/// Check that `programdata_account` is actually the buffer for `program_id`, and load it.
+pub fn load_programdata_for_program<'a>(
+    programdata_account: &'a AccountInfo,
+    program_id: &Pubkey,
+) -> Result<Ref<'a, ProgramdataAccount>, ProgramError> {
+    pyth_assert(
+        ProgramdataAccount::address(program_id) == *programdata_account.key,
+        OracleError::InvalidUpgradeAuthority.into(),
+    )?;
+    let programdata = load_account_as::<ProgramdataAccount>(programdata_account)?;
+    pyth_assert(
+        programdata.account_type == ProgramdataAccount::ACCOUNT_TYPE,
+        OracleError::InvalidUpgradeAuthority.into(),
+    )?;
+    Ok(programdata)
+}
+
+/// Check that `programdata_account` is actually the buffer for `program_id`.
+/// Check that the authority in `programdata_account` matches `upgrade_authority_account`.
+pub fn check_is_upgrade_authority_for_program(
+    upgrade_authority_account: &AccountInfo,
+    programdata_account: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    let programdata = load_programdata_for_program(programdata_account, program_id)?;
+    pyth_assert(
+        programdata.upgrade_authority() == Some(*upgrade_authority_account.key),
+        OracleError::InvalidUpgradeAuthority.into(),
+    )
+}
+
+/// Check that `programdata_account` is actually the buffer for `program_id` and that the program
+/// still has an upgrade authority.
+pub fn check_is_upgradeable_program(
+    programdata_account: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<(), ProgramError> {
+    let programdata = load_programdata_for_program(programdata_account, program_id)?;
+    pyth_assert(
+        programdata.upgrade_authority().is_some(),
+        OracleError::InvalidUpgradeAuthority.into(),
+    )
+}
@@ -0,0 +1,61 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::PC_ACCTYPE_PUBLISHER_LINKAGE,
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::try_convert,
+    },
+    bytemuck::{
+        try_cast_slice,
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Publisher keys controlled by the same entity, maintained with `UpdPublisherLinkage`. Feeds
+/// with a `PublisherWeightCap` count a limited number of quotes per entity in their aggregates.
+/// The `num_links` entries follow this struct, unordered, a publisher that isn't listed is its
+/// own entity. There is a single linkage account under `PUBLISHER_LINKAGE_SEED`, it grows as
+/// publishers get linked.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherLinkageAccount {
+    pub header:    AccountHeader,
+    pub num_links: u32,
+    pub unused_:   u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Pod, Zeroable)]
+pub struct PublisherLink {
+    pub publisher: Pubkey,
+    /// Any key identifying the entity, e.g. one of its publisher keys
+    pub entity:    Pubkey,
+}
+
+impl PythAccount for PublisherLinkageAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PUBLISHER_LINKAGE;
+    const INITIAL_SIZE: u32 = size_of::<PublisherLinkageAccount>() as u32;
+}
+
+impl PublisherLinkageAccount {
+    /// Offset of the `position`-th link in the account data.
+    pub fn entry_offset(position: usize) -> usize {
+        Self::MINIMUM_SIZE + position * size_of::<PublisherLink>()
+    }
+
+    /// Links listed in the linkage account stored in `data`.
+    pub fn links(data: &[u8]) -> Result<&[PublisherLink], OracleError> {
+        let num_links: usize =
+            try_convert(load_account_data::<PublisherLinkageAccount>(data)?.num_links)?;
+        data.get(Self::MINIMUM_SIZE..Self::entry_offset(num_links))
+            .and_then(|entries| try_cast_slice(entries).ok())
+            .ok_or(OracleError::InvalidAccountHeader)
+    }
+}
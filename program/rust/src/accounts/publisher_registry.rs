@@ -0,0 +1,57 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::{
+        c_oracle_header::PC_ACCTYPE_PUBLISHER_REGISTRY,
+        deserialize::load_account_data,
+        error::OracleError,
+        utils::try_convert,
+    },
+    bytemuck::{
+        try_cast_slice,
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Publishers approved by the master authority, maintained with `UpdPublisherRegistry`.
+/// `AddPublisher` only adds publishers listed here when the registry is passed to it, so that
+/// every feed draws from the same set. The `num_publishers` keys follow this struct, unordered.
+/// There is a single registry under `PUBLISHER_REGISTRY_SEED`, it grows as publishers get
+/// approved.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct PublisherRegistryAccount {
+    pub header:         AccountHeader,
+    pub num_publishers: u32,
+    pub unused_:        u32,
+}
+
+impl PythAccount for PublisherRegistryAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_PUBLISHER_REGISTRY;
+    const INITIAL_SIZE: u32 = size_of::<PublisherRegistryAccount>() as u32;
+}
+
+impl PublisherRegistryAccount {
+    /// Offset of the key of the `position`-th publisher in the account data.
+    pub fn entry_offset(position: usize) -> usize {
+        Self::MINIMUM_SIZE + position * size_of::<Pubkey>()
+    }
+
+    /// Approved publishers listed in the registry stored in `data`.
+    pub fn publishers(data: &[u8]) -> Result<&[Pubkey], OracleError> {
+        let num_publishers: usize =
+            try_convert(load_account_data::<PublisherRegistryAccount>(data)?.num_publishers)?;
+        data.get(Self::MINIMUM_SIZE..Self::entry_offset(num_publishers))
+            .and_then(|entries| try_cast_slice(entries).ok())
+            .ok_or(OracleError::InvalidAccountHeader)
+    }
+
+    pub fn is_approved(data: &[u8], publisher: &Pubkey) -> Result<bool, OracleError> {
+        Ok(Self::publishers(data)?.contains(publisher))
+    }
+}
@@ -0,0 +1,32 @@
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_REWARDS_LEDGER,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Reward score a publisher claimed from the `PublisherRewards` of the feeds it publishes to,
+/// with `ClaimRewards`. Every publisher has its own ledger under
+/// `[REWARDS_LEDGER_SEED, publisher_public_key]`, created by its first claim.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct RewardsLedgerAccount {
+    pub header:      AccountHeader,
+    pub publisher:   Pubkey,
+    /// Score claimed since the creation of the ledger
+    pub total_score: u64,
+    /// Claims since the creation of the ledger
+    pub num_claims:  u64,
+}
+
+impl PythAccount for RewardsLedgerAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_REWARDS_LEDGER;
+    const INITIAL_SIZE: u32 = size_of::<RewardsLedgerAccount>() as u32;
+}
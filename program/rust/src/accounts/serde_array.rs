@@ -0,0 +1,30 @@
+//! Serde only supports arrays of up to 32 elements, longer arrays of the accounts are encoded as
+//! sequences with `#[serde(with = "serde_array")]`.
+
+use {
+    serde::{
+        de::Error,
+        Deserialize,
+        Deserializer,
+        Serialize,
+        Serializer,
+    },
+    std::convert::TryInto,
+};
+
+pub fn serialize<S: Serializer, T: Serialize, const N: usize>(
+    array: &[T; N],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(array)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(
+    deserializer: D,
+) -> Result<[T; N], D::Error> {
+    let items = Vec::<T>::deserialize(deserializer)?;
+    let len = items.len();
+    items
+        .try_into()
+        .map_err(|_| D::Error::invalid_length(len, &format!("an array of {N} elements").as_str()))
+}
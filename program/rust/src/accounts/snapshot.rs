@@ -0,0 +1,66 @@
+#[cfg(not(feature = "wasm"))]
+use {
+    super::create,
+    crate::utils::get_rent,
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+    },
+};
+use {
+    super::{
+        AccountHeader,
+        PythAccount,
+    },
+    crate::c_oracle_header::PC_ACCTYPE_SNAPSHOT,
+    bytemuck::{
+        Pod,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// Copy of the raw data of a price account, taken at `slot` for post-incident forensics. The
+/// price account data follows this struct, the snapshot has its own account type so that it can
+/// never be mistaken for a price account.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct SnapshotAccount {
+    pub header:        AccountHeader,
+    pub price_account: Pubkey,
+    pub slot:          u64,
+}
+
+impl PythAccount for SnapshotAccount {
+    const ACCOUNT_TYPE: u32 = PC_ACCTYPE_SNAPSHOT;
+    const INITIAL_SIZE: u32 = size_of::<SnapshotAccount>() as u32;
+}
+
+impl SnapshotAccount {
+    /// Creates the snapshot PDA with room for `data_len` bytes of price account data, unless it
+    /// already exists.
+    #[cfg(not(feature = "wasm"))]
+    pub fn create_pda<'a>(
+        account: &AccountInfo<'a>,
+        funding_account: &AccountInfo<'a>,
+        system_program: &AccountInfo<'a>,
+        program_id: &Pubkey,
+        seeds: &[&[u8]],
+        data_len: usize,
+    ) -> Result<(), ProgramError> {
+        if account.data_len() == 0 {
+            let space = Self::MINIMUM_SIZE + data_len;
+            create(
+                funding_account,
+                account,
+                system_program,
+                program_id,
+                space,
+                get_rent()?.minimum_balance(space),
+                seeds,
+            )?;
+        }
+        Ok(())
+    }
+}
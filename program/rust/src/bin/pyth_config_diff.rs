@@ -0,0 +1,93 @@
+//! Report the drift between the configuration of the oracle on two clusters, e.g. pythnet and
+//! mainnet, field by field.
+//!
+//! Usage : `pyth-config-diff <left accounts dir> <right accounts dir>`
+//!
+//! Each directory holds the data of the accounts owned by the program on one cluster, one file
+//! per account named after its key (e.g. written by `solana account <key> --url <cluster>
+//! --output-file`). Every field that differs is printed with its value on both clusters, `-` when
+//! the field is missing on that cluster. Exits with an error if the configurations differ.
+
+use {
+    pyth_oracle::{
+        config_diff::{
+            diff_cluster_configs,
+            read_cluster_config,
+        },
+        solana_program::pubkey::Pubkey,
+    },
+    std::{
+        env,
+        error::Error,
+        fs,
+        path::Path,
+        str::FromStr,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-config-diff <left accounts dir> <right accounts dir>";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        return Err(USAGE.into());
+    }
+    let left_accounts = read_accounts(Path::new(&args[1]))?;
+    let right_accounts = read_accounts(Path::new(&args[2]))?;
+
+    let left = read_cluster_config(
+        left_accounts
+            .iter()
+            .map(|(key, data)| (*key, data.as_slice())),
+    );
+    let right = read_cluster_config(
+        right_accounts
+            .iter()
+            .map(|(key, data)| (*key, data.as_slice())),
+    );
+    let differences = diff_cluster_configs(&left, &right);
+    for difference in &differences {
+        println!(
+            "{}: {} | {}",
+            difference.field,
+            difference.left.as_deref().unwrap_or("-"),
+            difference.right.as_deref().unwrap_or("-"),
+        );
+    }
+    println!(
+        "Compared {} fields of {} and {} fields of {}, {} differ",
+        left.len(),
+        args[1],
+        right.len(),
+        args[2],
+        differences.len(),
+    );
+
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err("The configurations differ".into())
+    }
+}
+
+fn read_accounts(accounts_dir: &Path) -> Result<Vec<(Pubkey, Vec<u8>)>, Box<dyn Error>> {
+    let mut accounts = vec![];
+    for entry in fs::read_dir(accounts_dir)? {
+        let path = entry?.path();
+        let key = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| Pubkey::from_str(stem).ok());
+        if let Some(key) = key {
+            accounts.push((key, fs::read(&path)?));
+        }
+    }
+    Ok(accounts)
+}
@@ -0,0 +1,91 @@
+//! Write the genesis accounts of the oracle with the core feeds, ready to be loaded by a local
+//! validator.
+//!
+//! Usage : `pyth-genesis <program id> <authority> <output dir> [publisher ...]`
+//!
+//! `authority` is the master, data curation and security authority and every publisher is added
+//! to every feed. Each account is written to `output dir` as `<key>.json`, in the format of
+//! `solana account <key> --output json`, and the matching `--account` arguments are printed, e.g.
+//! `solana-test-validator --bpf-program <program id> oracle.so $(pyth-genesis ...)`.
+
+use {
+    pyth_oracle::{
+        genesis::{
+            build_genesis_accounts,
+            core_feeds,
+            GenesisAccount,
+            GenesisConfig,
+        },
+        solana_program::pubkey::Pubkey,
+    },
+    std::{
+        env,
+        error::Error,
+        fs,
+        path::Path,
+        str::FromStr,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-genesis <program id> <authority> <output dir> [publisher ...]";
+const SEED: &str = "pyth-genesis";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 4 {
+        return Err(USAGE.into());
+    }
+    let program_id = Pubkey::from_str(&args[1])?;
+    let authority = Pubkey::from_str(&args[2])?;
+    let output_dir = Path::new(&args[3]);
+    let publishers = args[4..]
+        .iter()
+        .map(|publisher| Pubkey::from_str(publisher))
+        .collect::<Result<_, _>>()?;
+
+    let accounts = build_genesis_accounts(&GenesisConfig {
+        program_id,
+        master_authority: authority,
+        data_curation_authority: authority,
+        security_authority: authority,
+        seed: SEED.to_string(),
+        publishers,
+        feeds: core_feeds(),
+    })?;
+
+    fs::create_dir_all(output_dir)?;
+    let mut validator_args = vec![];
+    for account in &accounts {
+        let path = output_dir.join(format!("{}.json", account.pubkey));
+        fs::write(&path, account_json(account))?;
+        validator_args.push(format!("--account {} {}", account.pubkey, path.display()));
+    }
+    eprintln!(
+        "Wrote {} accounts to {}",
+        accounts.len(),
+        output_dir.display()
+    );
+    println!("{}", validator_args.join(" "));
+
+    Ok(())
+}
+
+/// The account as printed by `solana account --output json`, which is what
+/// `solana-test-validator --account` reads.
+fn account_json(account: &GenesisAccount) -> String {
+    format!(
+        r#"{{"pubkey":"{}","account":{{"lamports":{},"data":["{}","base64"],"owner":"{}","executable":false,"rentEpoch":0,"space":{}}}}}"#,
+        account.pubkey,
+        account.lamports,
+        base64::encode(&account.data),
+        account.owner,
+        account.data.len(),
+    )
+}
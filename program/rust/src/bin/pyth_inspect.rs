@@ -0,0 +1,85 @@
+//! Print every field of a mapping, product, price or permission account of the program, with its
+//! flags, components, product metadata and extensions.
+//!
+//! Usage : `pyth-inspect <account file | account key> [cluster url]`
+//!
+//! The account file holds the data of the account (e.g. written by `solana account <key>
+//! --output-file`). Given the key of the account instead, the data is fetched with the `solana`
+//! CLI from the cluster at `cluster url`, or the cluster of the CLI configuration.
+
+use {
+    pyth_oracle::{
+        inspect::inspect_account,
+        solana_program::pubkey::Pubkey,
+    },
+    std::{
+        env,
+        error::Error,
+        fs,
+        path::Path,
+        process::Command,
+        str::FromStr,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-inspect <account file | account key> [cluster url]";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 && args.len() != 3 {
+        return Err(USAGE.into());
+    }
+
+    let path = Path::new(&args[1]);
+    let data = if path.exists() {
+        fs::read(path)?
+    } else {
+        let key = Pubkey::from_str(&args[1]).map_err(|_| USAGE)?;
+        fetch_account(&key, args.get(2).map(String::as_str))?
+    };
+
+    let fields = inspect_account(&data)?;
+    let width = fields
+        .iter()
+        .map(|(field, _)| field.len())
+        .max()
+        .unwrap_or(0);
+    for (field, value) in fields {
+        println!("{field:width$} : {value}");
+    }
+    Ok(())
+}
+
+/// Fetch the data of the account at `key` with `solana account`, which is already installed
+/// wherever the program is operated.
+fn fetch_account(key: &Pubkey, url: Option<&str>) -> Result<Vec<u8>, Box<dyn Error>> {
+    let output_file = env::temp_dir().join(format!("pyth-inspect-{key}"));
+    let mut command = Command::new("solana");
+    command
+        .arg("account")
+        .arg(key.to_string())
+        .arg("--output-file")
+        .arg(&output_file);
+    if let Some(url) = url {
+        command.arg("--url").arg(url);
+    }
+
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to fetch {key}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    let data = fs::read(&output_file)?;
+    fs::remove_file(&output_file)?;
+    Ok(data)
+}
@@ -0,0 +1,35 @@
+//! Write the byte layout of every account struct of the program as JSON, see
+//! `pyth_oracle::layout`.
+//!
+//! Usage : `pyth-layout [output file]`
+//!
+//! The layout is printed to stdout without an output file. `scripts/build-bpf.sh` publishes it
+//! next to the program, and `test_data/layout/v<PC_VERSION>.json` holds it for the tests.
+
+use {
+    pyth_oracle::layout::layout_json,
+    std::{
+        env,
+        error::Error,
+        fs,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-layout [output file]";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    match args.len() {
+        1 => print!("{}", layout_json()),
+        2 => fs::write(&args[1], layout_json())?,
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//! Emit the instructions rolling out a migration to every price account of the program, in
+//! batches meant to be proposed to governance one transaction at a time.
+//!
+//! Usage : `pyth-migrate <migration> <program id> <authority> <accounts dir> <output dir> [batch size]`
+//!
+//! `accounts dir` holds the data of the accounts owned by the program, one file per account named
+//! after its key (e.g. written by `solana account <key> --output-file`). Each batch is written to
+//! `output dir` as a bincode-serialized `Vec<Instruction>`, replacing the batches of the previous
+//! run. The plan only depends on the accounts, so a rollout is resumed by fetching the accounts
+//! again and running the tool on them: the accounts migrated by the landed batches are left out,
+//! and those of the failed batches are planned again.
+
+use {
+    pyth_oracle::{
+        migration::{
+            find_accounts_to_migrate,
+            find_accounts_with_unknown_flags,
+            plan_batches,
+            Migration,
+        },
+        solana_program::{
+            instruction::Instruction,
+            pubkey::Pubkey,
+        },
+    },
+    std::{
+        collections::HashMap,
+        env,
+        error::Error,
+        fs,
+        path::Path,
+        str::FromStr,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-migrate <enable-accumulator-v2|disable-accumulator-v2|allow-zero-ci|forbid-zero-ci|require-isolated-updates|allow-bundled-updates|enable-update-counting|enable-status-reason-tracking|reserve-extension-space> <program id> <authority> <accounts dir> <output dir> [batch size]";
+const DEFAULT_BATCH_SIZE: usize = 10;
+const BATCH_FILE_PREFIX: &str = "batch-";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 6 && args.len() != 7 {
+        return Err(USAGE.into());
+    }
+    let migration: Migration = args[1].parse()?;
+    let program_id = Pubkey::from_str(&args[2])?;
+    let authority = Pubkey::from_str(&args[3])?;
+    let accounts_dir = Path::new(&args[4]);
+    let output_dir = Path::new(&args[5]);
+    let batch_size = match args.get(6) {
+        Some(batch_size) => batch_size.parse()?,
+        None => DEFAULT_BATCH_SIZE,
+    };
+
+    let mut accounts = vec![];
+    for entry in fs::read_dir(accounts_dir)? {
+        let path = entry?.path();
+        let key = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| Pubkey::from_str(stem).ok());
+        if let Some(key) = key {
+            accounts.push((key, fs::read(&path)?));
+        }
+    }
+    for (price_account, unknown_bits) in
+        find_accounts_with_unknown_flags(accounts.iter().map(|(key, data)| (*key, data.as_slice())))
+    {
+        println!(
+            "Warning: {price_account} has flags {unknown_bits:#010b} set by a newer version of the \
+             program, they will be kept"
+        );
+    }
+    let to_migrate = find_accounts_to_migrate(
+        migration,
+        accounts.iter().map(|(key, data)| (*key, data.as_slice())),
+    );
+
+    let batches = plan_batches(&to_migrate, batch_size);
+    println!(
+        "Scanned {} accounts, {} price accounts need {:?}",
+        accounts.len(),
+        to_migrate.len(),
+        migration,
+    );
+
+    // The batches of the previous run are planned again from the accounts if they still apply
+    fs::create_dir_all(output_dir)?;
+    for entry in fs::read_dir(output_dir)? {
+        let entry = entry?;
+        if entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(BATCH_FILE_PREFIX)
+        {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    let accounts: HashMap<Pubkey, Vec<u8>> = accounts.into_iter().collect();
+    for (i, batch) in batches.iter().enumerate() {
+        let instructions: Vec<Instruction> = batch
+            .iter()
+            .flat_map(|price_account| {
+                migration.instructions(
+                    &program_id,
+                    &authority,
+                    price_account,
+                    &accounts[price_account],
+                )
+            })
+            .collect();
+        let batch_path = output_dir.join(format!("{BATCH_FILE_PREFIX}{i:05}.bin"));
+        fs::write(&batch_path, bincode::serialize(&instructions)?)?;
+        println!(
+            "[{}/{}] Wrote {} instructions to {}",
+            i + 1,
+            batches.len(),
+            instructions.len(),
+            batch_path.display()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,34 @@
+//! Write the conformance vectors of the aggregation as JSON, see `pyth_oracle::test_vectors`.
+//!
+//! Usage : `pyth-test-vectors [output file]`
+//!
+//! The vectors are printed to stdout without an output file. `scripts/build-bpf.sh` publishes
+//! them next to the program, and `test_data/vectors/aggregation.json` holds them for the tests.
+
+use {
+    pyth_oracle::test_vectors::test_vectors_json,
+    std::{
+        env,
+        error::Error,
+        fs,
+    },
+};
+
+const USAGE: &str = "Usage: pyth-test-vectors [output file]";
+
+fn main() {
+    if let Err(err) = run() {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args: Vec<String> = env::args().collect();
+    match args.len() {
+        1 => print!("{}", test_vectors_json()),
+        2 => fs::write(&args[1], test_vectors_json())?,
+        _ => return Err(USAGE.into()),
+    }
+    Ok(())
+}
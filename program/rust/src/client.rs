@@ -0,0 +1,142 @@
+//! Off-chain reading of price feeds.
+//!
+//! `CachingPriceReader` fetches the price accounts of many feeds with as few RPC requests as
+//! possible and applies the same freshness rule as on-chain consumers, see
+//! `PriceAccount::get_price_no_older_than`. It also keeps the successor of the deprecated feeds
+//! so that callers can move to the feeds replacing them.
+
+use {
+    crate::{
+        accounts::{
+            get_successor,
+            LatestPrice,
+        },
+        migration::read_price_account,
+    },
+    solana_program::pubkey::Pubkey,
+    std::collections::{
+        HashMap,
+        HashSet,
+    },
+};
+
+/// Source of account data, usually an RPC client calling `getMultipleAccounts`.
+pub trait AccountFetcher {
+    type Error;
+
+    /// Fetch the data of `keys` in a single request. The result has one entry per key, in the
+    /// same order, which is `None` if the account doesn't exist.
+    fn fetch_accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>, Self::Error>;
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClientError<E> {
+    Fetch(E),
+    AccountNotFound(Pubkey),
+    NotPriceAccount(Pubkey),
+}
+
+struct CachedFeed {
+    latest_price: LatestPrice,
+    /// Price account replacing the feed if it is deprecated
+    successor:    Option<Pubkey>,
+    /// Slot at which the price account was last fetched
+    fetched_slot: u64,
+}
+
+/// Caches the latest price of every feed it reads. A feed is fetched again only if its cached
+/// price is too old for the caller and it wasn't already fetched in the current slot.
+pub struct CachingPriceReader<F> {
+    fetcher: F,
+    feeds:   HashMap<Pubkey, CachedFeed>,
+}
+
+impl<F: AccountFetcher> CachingPriceReader<F> {
+    pub fn new(fetcher: F) -> Self {
+        CachingPriceReader {
+            fetcher,
+            feeds: HashMap::new(),
+        }
+    }
+
+    /// Fetch every feed of `feeds` that wasn't already fetched in `current_slot`, with a single
+    /// request. Duplicate feeds are fetched once.
+    pub fn refresh(
+        &mut self,
+        feeds: &[Pubkey],
+        current_slot: u64,
+    ) -> Result<(), ClientError<F::Error>> {
+        let mut seen = HashSet::new();
+        let keys: Vec<Pubkey> = feeds
+            .iter()
+            .filter(|feed| seen.insert(**feed))
+            .filter(|feed| {
+                self.feeds
+                    .get(feed)
+                    .map_or(true, |cached| cached.fetched_slot < current_slot)
+            })
+            .copied()
+            .collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let accounts = self
+            .fetcher
+            .fetch_accounts(&keys)
+            .map_err(ClientError::Fetch)?;
+        for (key, data) in keys.iter().zip(accounts) {
+            let data = data.ok_or(ClientError::AccountNotFound(*key))?;
+            let price_data = read_price_account(&data).ok_or(ClientError::NotPriceAccount(*key))?;
+            self.feeds.insert(
+                *key,
+                CachedFeed {
+                    latest_price: price_data.get_latest_price(),
+                    successor:    get_successor(&data),
+                    fetched_slot: current_slot,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Latest price of `feed`, unless it was published more than `max_age` slots before
+    /// `current_slot`. The cached price is used if it is fresh enough.
+    pub fn get_price_no_older_than(
+        &mut self,
+        feed: &Pubkey,
+        current_slot: u64,
+        max_age: u64,
+    ) -> Result<Option<LatestPrice>, ClientError<F::Error>> {
+        if let Some(price) = self.get_cached_price_no_older_than(feed, current_slot, max_age) {
+            return Ok(Some(price));
+        }
+        self.refresh(&[*feed], current_slot)?;
+        Ok(self.get_cached_price_no_older_than(feed, current_slot, max_age))
+    }
+
+    /// Slot at which the cached price of `feed` was published, if `feed` was fetched.
+    pub fn publish_slot(&self, feed: &Pubkey) -> Option<u64> {
+        self.feeds
+            .get(feed)
+            .map(|cached| cached.latest_price.publish_slot)
+    }
+
+    /// Price account replacing `feed` if it was deprecated when it was last fetched. The price
+    /// of a deprecated feed is still returned while it is published.
+    pub fn successor(&self, feed: &Pubkey) -> Option<Pubkey> {
+        self.feeds.get(feed).and_then(|cached| cached.successor)
+    }
+
+    fn get_cached_price_no_older_than(
+        &self,
+        feed: &Pubkey,
+        current_slot: u64,
+        max_age: u64,
+    ) -> Option<LatestPrice> {
+        self.feeds
+            .get(feed)
+            .map(|cached| cached.latest_price)
+            .filter(|price| price.is_no_older_than(current_slot, max_age))
+    }
+}
@@ -0,0 +1,420 @@
+//! Comparison of the configuration of the oracle on two clusters, see the `pyth-config-diff`
+//! binary.
+//!
+//! The configuration of a cluster is read from the accounts owned by the program and flattened
+//! into named fields: the authorities and timelock of the permission account, the signers of the
+//! multisig accounts, the approved publishers of the publisher registry, the entities of the
+//! linked publishers and the parameters, publishers, extensions and product metadata of every
+//! feed. Feeds are matched across clusters by the symbol of their product, since their keys
+//! differ from one cluster to the other.
+
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            get_feed_creator,
+            get_product_metadata_entries,
+            AccountHeader,
+            AccumulatorEmitter,
+            AggregateCache,
+            AggregationCounts,
+            AggregationDelay,
+            AuxData,
+            Checksum,
+            ConfFloor,
+            MultisigAccount,
+            PermissionAccount,
+            PermissionTimelock,
+            PriceAccount,
+            ProductAccount,
+            PublishThreshold,
+            PublisherActivity,
+            PublisherCap,
+            PublisherLinkageAccount,
+            PublisherRateLimit,
+            PublisherRegistryAccount,
+            PublisherRewards,
+            PublisherSampling,
+            PublisherSequences,
+            PublisherStake,
+            PublisherTimestamps,
+            PublisherWeightCap,
+            PythAccount,
+            QuoteConversion,
+            QuoteOrder,
+            SanityProgram,
+            SharedSettings,
+            SlotWeightedTwap,
+            StatusObserver,
+            StatusPolicy,
+            StatusReason,
+            UpdateCount,
+            UpdateFee,
+            UpdaterProgram,
+            DEFAULT_AGGREGATION_DELAY,
+        },
+        c_oracle_header::PC_MAGIC,
+    },
+    bytemuck::pod_read_unaligned,
+    solana_program::pubkey::Pubkey,
+    std::{
+        collections::{
+            BTreeMap,
+            BTreeSet,
+            HashMap,
+        },
+        mem::size_of,
+    },
+};
+
+/// A field whose value differs between the two clusters, `None` if the field is missing on that
+/// cluster.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigDifference {
+    pub field: String,
+    pub left:  Option<String>,
+    pub right: Option<String>,
+}
+
+/// Configuration of the oracle as named fields, e.g. `permissions.master_authority` or
+/// `feeds.Crypto.BTC/USD.min_pub`, read from the accounts owned by the program on a cluster.
+///
+/// The feeds of a product are named after its symbol, followed by `#<position>` for the price
+/// accounts after the first in its list. Price accounts that aren't listed by any product are
+/// named after their key. Accounts that can't be read are ignored.
+pub fn read_cluster_config<'a>(
+    accounts: impl IntoIterator<Item = (Pubkey, &'a [u8])>,
+) -> BTreeMap<String, String> {
+    let accounts: HashMap<Pubkey, &[u8]> = accounts.into_iter().collect();
+    let mut config = BTreeMap::new();
+
+    for (key, data) in &accounts {
+        if let Some(permissions) = read_account::<PermissionAccount>(data) {
+            add_permissions_fields(&mut config, &permissions, data);
+        }
+        if let Some(multisig) = read_account::<MultisigAccount>(data) {
+            config.insert(
+                format!("multisigs.{key}.threshold"),
+                multisig.threshold.to_string(),
+            );
+            for signer in multisig.signers() {
+                config.insert(
+                    format!("multisigs.{key}.signers.{signer}"),
+                    "listed".to_string(),
+                );
+            }
+        }
+        if read_account::<PublisherRegistryAccount>(data).is_some() {
+            for publisher in PublisherRegistryAccount::publishers(data).unwrap_or_default() {
+                config.insert(
+                    format!("publisher_registry.{publisher}"),
+                    "approved".to_string(),
+                );
+            }
+        }
+        if read_account::<PublisherLinkageAccount>(data).is_some() {
+            for link in PublisherLinkageAccount::links(data).unwrap_or_default() {
+                config.insert(
+                    format!("publisher_linkage.{}", link.publisher),
+                    link.entity.to_string(),
+                );
+            }
+        }
+    }
+
+    let mut feed_names: HashMap<Pubkey, String> = HashMap::new();
+    let mut products: Vec<(&Pubkey, &&[u8])> = accounts.iter().collect();
+    products.sort_by_key(|(key, _)| **key);
+    for (product_key, data) in products {
+        let product = match read_account::<ProductAccount>(data) {
+            Some(product) => product,
+            None => continue,
+        };
+        let metadata = get_product_metadata_entries(data).unwrap_or_default();
+        let symbol = metadata
+            .iter()
+            .find(|(key, _)| *key == b"symbol")
+            .map(|(_, value)| String::from_utf8_lossy(value).into_owned())
+            .unwrap_or_else(|| product_key.to_string());
+        for (key, value) in &metadata {
+            if *key != b"symbol" {
+                config.insert(
+                    format!("feeds.{symbol}.product.{}", String::from_utf8_lossy(key)),
+                    String::from_utf8_lossy(value).into_owned(),
+                );
+            }
+        }
+
+        // Walk the list of price accounts of the product, stopping at a loop
+        let mut price_key = product.first_price_account;
+        let mut position = 0;
+        while price_key != Pubkey::default() && !feed_names.contains_key(&price_key) {
+            let price_data = match accounts
+                .get(&price_key)
+                .and_then(|data| read_account::<PriceAccount>(data))
+            {
+                Some(price_data) => price_data,
+                None => break,
+            };
+            let name = match position {
+                0 => symbol.clone(),
+                _ => format!("{symbol}#{position}"),
+            };
+            feed_names.insert(price_key, name);
+            price_key = price_data.next_price_account;
+            position += 1;
+        }
+    }
+
+    for (price_key, data) in &accounts {
+        if let Some(price_data) = read_account::<PriceAccount>(data) {
+            let name = feed_names
+                .get(price_key)
+                .cloned()
+                .unwrap_or_else(|| price_key.to_string());
+            add_feed_fields(&mut config, &name, &price_data, data);
+        }
+    }
+
+    config
+}
+
+/// Fields whose value differs between `left` and `right`, sorted by name.
+pub fn diff_cluster_configs(
+    left: &BTreeMap<String, String>,
+    right: &BTreeMap<String, String>,
+) -> Vec<ConfigDifference> {
+    let fields: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    fields
+        .into_iter()
+        .filter(|field| left.get(*field) != right.get(*field))
+        .map(|field| ConfigDifference {
+            field: field.clone(),
+            left:  left.get(field).cloned(),
+            right: right.get(field).cloned(),
+        })
+        .collect()
+}
+
+/// Read the account of type `T` stored in `data`, returning `None` if `data` holds another type.
+/// Account data fetched off-chain isn't necessarily aligned, so the account is copied.
+fn read_account<T: PythAccount>(data: &[u8]) -> Option<T> {
+    if data.len() < T::MINIMUM_SIZE {
+        return None;
+    }
+
+    let header: AccountHeader = pod_read_unaligned(&data[..size_of::<AccountHeader>()]);
+    if header.magic_number != PC_MAGIC || header.account_type != T::ACCOUNT_TYPE {
+        return None;
+    }
+
+    Some(pod_read_unaligned(&data[..size_of::<T>()]))
+}
+
+fn add_permissions_fields(
+    config: &mut BTreeMap<String, String>,
+    permissions: &PermissionAccount,
+    data: &[u8],
+) {
+    config.insert(
+        "permissions.master_authority".to_string(),
+        permissions.master_authority.to_string(),
+    );
+    config.insert(
+        "permissions.data_curation_authority".to_string(),
+        permissions.data_curation_authority.to_string(),
+    );
+    config.insert(
+        "permissions.security_authority".to_string(),
+        permissions.security_authority.to_string(),
+    );
+
+    // Permission accounts without a timelock apply changes immediately
+    let timelock: Option<PermissionTimelock> = data
+        .get(PermissionAccount::TIMELOCK_OFFSET..PermissionAccount::TIMELOCK_ACCOUNT_SPACE)
+        .map(pod_read_unaligned);
+    config.insert(
+        "permissions.timelock_delay".to_string(),
+        timelock.map_or(0, |timelock| timelock.delay).to_string(),
+    );
+    if let Some(timelock) = timelock.filter(PermissionTimelock::has_pending_change) {
+        config.insert(
+            "permissions.pending_master_authority".to_string(),
+            timelock.pending_master_authority.to_string(),
+        );
+        config.insert(
+            "permissions.pending_data_curation_authority".to_string(),
+            timelock.pending_data_curation_authority.to_string(),
+        );
+        config.insert(
+            "permissions.pending_security_authority".to_string(),
+            timelock.pending_security_authority.to_string(),
+        );
+        config.insert(
+            "permissions.pending_timelock_delay".to_string(),
+            timelock.pending_delay.to_string(),
+        );
+    }
+
+    let emitter: Option<AccumulatorEmitter> = data
+        .get(PermissionAccount::EMITTER_OFFSET..PermissionAccount::EMITTER_ACCOUNT_SPACE)
+        .map(pod_read_unaligned);
+    if let Some(emitter) = emitter.filter(|emitter| emitter.program_id != Pubkey::default()) {
+        config.insert(
+            "permissions.accumulator_emitter".to_string(),
+            emitter.program_id.to_string(),
+        );
+    }
+}
+
+fn add_feed_fields(
+    config: &mut BTreeMap<String, String>,
+    name: &str,
+    price_data: &PriceAccount,
+    data: &[u8],
+) {
+    let mut insert = |field: &str, value: String| {
+        config.insert(format!("feeds.{name}.{field}"), value);
+    };
+    insert("price_type", price_data.price_type.to_string());
+    insert("exponent", price_data.exponent.to_string());
+    insert("min_pub", price_data.min_pub_.to_string());
+    insert("max_latency", price_data.max_latency_.to_string());
+    insert("flags", format!("{:#010b}", price_data.flags.bits()));
+    for component in price_data.comp_.iter().take(price_data.num_ as usize) {
+        insert(
+            &format!("publishers.{}", component.pub_),
+            "listed".to_string(),
+        );
+    }
+
+    if let Ok(Some(rate_limit)) = get_extension::<PublisherRateLimit>(data) {
+        insert("rate_limit_window", rate_limit.window.to_string());
+    }
+    if let Ok(Some(counts)) = get_extension::<AggregationCounts>(data) {
+        insert(
+            "aggregation_count_window",
+            counts.window_slots().to_string(),
+        );
+    }
+    let aggregation_delay = match get_extension::<AggregationDelay>(data) {
+        Ok(Some(delay)) => delay.slots,
+        _ => DEFAULT_AGGREGATION_DELAY,
+    };
+    insert("aggregation_delay", aggregation_delay.to_string());
+    if let Ok(Some(sanity)) = get_extension::<SanityProgram>(data) {
+        if sanity.program_id != Pubkey::default() {
+            insert("sanity_program", sanity.program_id.to_string());
+        }
+    }
+    if let Ok(Some(updater)) = get_extension::<UpdaterProgram>(data) {
+        if updater.program_id != Pubkey::default() {
+            insert("updater_program", updater.program_id.to_string());
+        }
+    }
+    if let Ok(Some(observer)) = get_extension::<StatusObserver>(data) {
+        if observer.program_id != Pubkey::default() {
+            insert("status_observer", observer.program_id.to_string());
+        }
+    }
+    if let Ok(Some(checksum)) = get_extension::<Checksum>(data) {
+        if checksum.enabled != 0 {
+            insert("checksum", "enabled".to_string());
+        }
+    }
+    if let Ok(Some(weight_cap)) = get_extension::<PublisherWeightCap>(data) {
+        insert(
+            "max_quotes_per_entity",
+            weight_cap.max_quotes_per_entity.to_string(),
+        );
+    }
+    if let Ok(Some(order)) = get_extension::<QuoteOrder>(data) {
+        insert("quote_order", (order.enabled != 0).to_string());
+    }
+    if let Ok(Some(conf_floor)) = get_extension::<ConfFloor>(data) {
+        insert("min_conf", conf_floor.min_conf.to_string());
+        insert("min_conf_bps", conf_floor.min_conf_bps.to_string());
+    }
+    if let Ok(Some(status_reason)) = get_extension::<StatusReason>(data) {
+        insert(
+            "status_reason_tracking",
+            (status_reason.enabled != 0).to_string(),
+        );
+    }
+    if let Ok(Some(stake)) = get_extension::<PublisherStake>(data) {
+        if stake.staking_program != Pubkey::default() {
+            insert("staking_program", stake.staking_program.to_string());
+            insert("min_stake", stake.min_stake.to_string());
+        }
+    }
+    if let Ok(Some(cap)) = get_extension::<PublisherCap>(data) {
+        if cap.max_publishers != 0 {
+            insert("max_publishers", cap.max_publishers.to_string());
+        }
+    }
+    if let Ok(Some(conversion)) = get_extension::<QuoteConversion>(data) {
+        if conversion.conversion_feed != Pubkey::default() {
+            insert("conversion_feed", conversion.conversion_feed.to_string());
+            insert("conversion_exponent", conversion.exponent.to_string());
+            insert("conversion_divide", (conversion.divide != 0).to_string());
+        }
+    }
+    if let Ok(Some(cache)) = get_extension::<AggregateCache>(data) {
+        insert("aggregate_cache", (cache.enabled != 0).to_string());
+    }
+    if let Ok(Some(settings)) = get_extension::<SharedSettings>(data) {
+        insert("shared_settings", (settings.enabled != 0).to_string());
+    }
+    if let Ok(Some(sampling)) = get_extension::<PublisherSampling>(data) {
+        if sampling.sample_size != 0 {
+            insert("sample_size", sampling.sample_size.to_string());
+        }
+    }
+    if let Ok(Some(update_fee)) = get_extension::<UpdateFee>(data) {
+        if update_fee.lamports != 0 {
+            insert("update_fee", update_fee.lamports.to_string());
+        }
+    }
+    if let Ok(Some(rewards)) = get_extension::<PublisherRewards>(data) {
+        insert("publisher_rewards", (rewards.enabled != 0).to_string());
+    }
+    if let Ok(Some(update_count)) = get_extension::<UpdateCount>(data) {
+        insert("update_counting", (update_count.enabled != 0).to_string());
+    }
+    if let Ok(Some(twap)) = get_extension::<SlotWeightedTwap>(data) {
+        insert("average_mode", twap.mode.to_string());
+        insert("twap_window", twap.window_slots().to_string());
+    }
+    if let Ok(Some(timestamps)) = get_extension::<PublisherTimestamps>(data) {
+        insert(
+            "publisher_timestamps",
+            (timestamps.enabled != 0).to_string(),
+        );
+    }
+    if let Ok(Some(sequences)) = get_extension::<PublisherSequences>(data) {
+        insert("publisher_sequences", (sequences.enabled != 0).to_string());
+    }
+    if let Ok(Some(policy)) = get_extension::<StatusPolicy>(data) {
+        insert("halted_quorum", policy.halted_quorum.to_string());
+        insert("auction_quorum", policy.auction_quorum.to_string());
+        insert(
+            "status_policy_overrides_trading",
+            (policy.override_trading != 0).to_string(),
+        );
+    }
+    if let Ok(Some(activity)) = get_extension::<PublisherActivity>(data) {
+        insert("max_missed_windows", activity.max_missed.to_string());
+    }
+    if let Ok(Some(aux_data)) = get_extension::<AuxData>(data) {
+        insert("aux_data", (aux_data.enabled != 0).to_string());
+    }
+    if let Ok(Some(threshold)) = get_extension::<PublishThreshold>(data) {
+        if threshold.deviation_bps != 0 {
+            insert("publish_deviation_bps", threshold.deviation_bps.to_string());
+            insert("heartbeat_slots", threshold.heartbeat_slots.to_string());
+        }
+    }
+    if let Some(creator) = get_feed_creator(data) {
+        insert("creator", creator.to_string());
+    }
+}
@@ -1,3 +1,8 @@
+#[cfg(not(feature = "wasm"))]
+use {
+    crate::utils::pyth_assert,
+    std::marker::PhantomData,
+};
 use {
     crate::{
         accounts::{
@@ -6,7 +11,6 @@ use {
         },
         c_oracle_header::PC_MAGIC,
         error::OracleError,
-        utils::pyth_assert,
     },
     bytemuck::{
         try_from_bytes,
@@ -71,24 +75,75 @@ pub fn load_account_as_mut<'a, T: Pod>(
     }))
 }
 
-pub fn load_checked<'a, T: PythAccount>(
-    account: &'a AccountInfo,
-    version: u32,
-) -> Result<RefMut<'a, T>, ProgramError> {
-    pyth_assert(
-        account.data_len() >= T::MINIMUM_SIZE,
-        OracleError::AccountTooSmall.into(),
-    )?;
+/// Interpret the bytes in `data` as an initialized Pyth account of type `T`. Unlike `load_checked`
+/// this doesn't check the version, it is meant for reading account data fetched off-chain.
+pub fn load_account_data<T: PythAccount>(data: &[u8]) -> Result<&T, OracleError> {
+    if data.len() < T::MINIMUM_SIZE {
+        return Err(OracleError::AccountTooSmall);
+    }
+
+    let account_header = load::<AccountHeader>(data)?;
+    if account_header.magic_number != PC_MAGIC || account_header.account_type != T::ACCOUNT_TYPE {
+        return Err(OracleError::InvalidAccountHeader);
+    }
+
+    load::<T>(data)
+}
+
+/// Initialized Pyth account of type `T`. The size and the header of the account are checked once
+/// when it's wrapped, then `load` and `load_mut` borrow it as a `T` as many times as needed, e.g.
+/// around the C aggregation or a CPI, which borrow the data themselves.
+#[cfg(not(feature = "wasm"))]
+pub struct OracleAccount<'a, 'b: 'a, T: PythAccount> {
+    account: &'a AccountInfo<'b>,
+    _type:   PhantomData<T>,
+}
 
-    {
-        let account_header = load_account_as::<AccountHeader>(account)?;
+#[cfg(not(feature = "wasm"))]
+impl<'a, 'b: 'a, T: PythAccount> OracleAccount<'a, 'b, T> {
+    /// Check that `account` holds an initialized Pyth account of type `T` at `version`.
+    pub fn new(account: &'a AccountInfo<'b>, version: u32) -> Result<Self, ProgramError> {
         pyth_assert(
-            account_header.magic_number == PC_MAGIC
-                && account_header.version == version
-                && account_header.account_type == T::ACCOUNT_TYPE,
-            OracleError::InvalidAccountHeader.into(),
+            account.data_len() >= T::MINIMUM_SIZE,
+            OracleError::AccountTooSmall.into(),
         )?;
+
+        {
+            let account_header = load_account_as::<AccountHeader>(account)?;
+            pyth_assert(
+                account_header.magic_number == PC_MAGIC
+                    && account_header.version == version
+                    && account_header.account_type == T::ACCOUNT_TYPE,
+                OracleError::InvalidAccountHeader.into(),
+            )?;
+        }
+
+        Ok(OracleAccount {
+            account,
+            _type: PhantomData,
+        })
+    }
+
+    /// The wrapped account, e.g. for its key or its extensions.
+    pub fn info(&self) -> &'a AccountInfo<'b> {
+        self.account
     }
 
-    load_account_as_mut::<T>(account)
+    pub fn load(&self) -> Result<Ref<'a, T>, ProgramError> {
+        load_account_as::<T>(self.account)
+    }
+
+    pub fn load_mut(&self) -> Result<RefMut<'a, T>, ProgramError> {
+        load_account_as_mut::<T>(self.account)
+    }
+}
+
+/// Check and mutably borrow the Pyth account of type `T` in `account`, see `OracleAccount` to
+/// borrow it more than once.
+#[cfg(not(feature = "wasm"))]
+pub fn load_checked<'a, T: PythAccount>(
+    account: &'a AccountInfo,
+    version: u32,
+) -> Result<RefMut<'a, T>, ProgramError> {
+    OracleAccount::<T>::new(account, version)?.load_mut()
 }
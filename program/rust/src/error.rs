@@ -5,6 +5,9 @@ use {
 };
 
 /// Errors that may be returned by the oracle program
+/// They are returned as `ProgramError::Custom` with their discriminant as the error code, which
+/// clients rely on to tell failures apart.
+/// WARNING : EXISTING CODES SHOULD NEVER CHANGE, NEW ERRORS SHOULD BE ADDED AT THE END OF THE LIST
 #[derive(Clone, Debug, Eq, Error, PartialEq)]
 pub enum OracleError {
     /// Generic catch all error
@@ -58,6 +61,124 @@ pub enum OracleError {
     FeedIndexAlreadyInitialized    = 622,
     #[error("NoNeedToResize")]
     NoNeedToResize                 = 623,
+    #[error("InvalidExtension")]
+    InvalidExtension               = 624,
+    #[error("MissingProductMetadataKey")]
+    MissingProductMetadataKey      = 625,
+    #[error("DuplicateProductMetadataKey")]
+    DuplicateProductMetadataKey    = 626,
+    #[error("InvalidProductMetadataString")]
+    InvalidProductMetadataString   = 627,
+    #[error("AccountNotFound")]
+    AccountNotFound                = 628,
+    /// The signer of a publisher instruction is not a publisher of the price account
+    #[error("PublisherNotFound")]
+    PublisherNotFound              = 629,
+    #[error("PublisherAlreadyExists")]
+    PublisherAlreadyExists         = 630,
+    #[error("MaxPublishersReached")]
+    MaxPublishersReached           = 631,
+    /// The publishing slot of a price update is not more recent than the publisher's latest
+    /// update
+    #[error("StaleSubmission")]
+    StaleSubmission                = 632,
+    #[error("InvalidExponent")]
+    InvalidExponent                = 633,
+    #[error("InvalidPriceType")]
+    InvalidPriceType               = 634,
+    #[error("MappingAccountFull")]
+    MappingAccountFull             = 635,
+    /// A price or confidence of the price account doesn't fit its type with the new exponent
+    #[error("PriceRescaleOverflow")]
+    PriceRescaleOverflow           = 636,
+    /// The publisher already contributed an update within the rate limit window of the feed
+    #[error("UpdateRateLimited")]
+    UpdateRateLimited              = 637,
+    /// Feed index 0 is never assigned, the price account was created before feed indexes existed
+    #[error("InvalidFeedIndex")]
+    InvalidFeedIndex               = 638,
+    /// The delay of the pending change of the permissions hasn't passed yet
+    #[error("PermissionsChangeNotReady")]
+    PermissionsChangeNotReady      = 639,
+    #[error("NoPendingPermissionsChange")]
+    NoPendingPermissionsChange     = 640,
+    /// The publisher isn't listed in the publisher registry
+    #[error("PublisherNotApproved")]
+    PublisherNotApproved           = 641,
+    /// The aggregate of the price account doesn't meet the condition of `AssertPriceCondition`
+    #[error("PriceConditionNotMet")]
+    PriceConditionNotMet           = 642,
+    /// The publishing slot of a price update is ahead of the clock
+    #[error("PublishingSlotInFuture")]
+    PublishingSlotInFuture         = 643,
+    /// Fewer signers of the multisig account signed the transaction than its threshold
+    #[error("MultisigThresholdNotMet")]
+    MultisigThresholdNotMet        = 644,
+    /// The feed requires isolated updates, but the transaction of the price update holds
+    /// instructions of other programs or the instructions sysvar wasn't passed to check it
+    #[error("UpdateNotIsolated")]
+    UpdateNotIsolated              = 645,
+    /// The compact price update isn't relative to the latest quote of the publisher
+    #[error("CompactBaseMismatch")]
+    CompactBaseMismatch            = 646,
+    /// The feed is permissioned and the reader didn't sign or isn't listed in its access control
+    /// account
+    #[error("ReaderNotAllowed")]
+    ReaderNotAllowed               = 647,
+    /// An aggregation of the price account started and didn't complete
+    #[error("AggregationInProgress")]
+    AggregationInProgress          = 648,
+    /// The stake escrow of a new publisher isn't owned by the staking program of the feed, the
+    /// staking program wasn't passed or it didn't return the stake
+    #[error("InvalidStakeEscrow")]
+    InvalidStakeEscrow             = 649,
+    /// The stake of a new publisher is below the minimum stake of the feed
+    #[error("InsufficientStake")]
+    InsufficientStake              = 650,
+    /// The feed already has the number of publishers of its `PublisherCap`
+    #[error("PublisherCapReached")]
+    PublisherCapReached            = 651,
+    /// The conversion feed doesn't match the `QuoteConversion` of the price account or the price
+    /// account doesn't convert its aggregate
+    #[error("InvalidConversionFeed")]
+    InvalidConversionFeed          = 652,
+    /// An account expected to be a sysvar isn't that sysvar
+    #[error("InvalidSysvarAccount")]
+    InvalidSysvarAccount           = 653,
+    /// The update of a feed with an `UpdateFee` didn't list the fee vault and the system program
+    #[error("UpdateFeeNotPaid")]
+    UpdateFeeNotPaid               = 654,
+    /// The price accounts don't match the feeds of the consistency check, in order
+    #[error("InvalidConsistencyFeeds")]
+    InvalidConsistencyFeeds        = 655,
+    /// The clock is behind a slot the price account already recorded, e.g. after a restart or on
+    /// a fork
+    #[error("ClockRegression")]
+    ClockRegression                = 656,
+    /// The hash-indexed mapping account has no free entry left
+    #[error("MappingFull")]
+    MappingFull                    = 657,
+    /// Another product is indexed under the same symbol hash
+    #[error("DuplicateSymbol")]
+    DuplicateSymbol                = 658,
+    /// The price account doesn't match its checksum, see `Checksum`
+    #[error("ChecksumMismatch")]
+    ChecksumMismatch               = 659,
+    /// The sequence number of the quote isn't above the one of the latest quote of the publisher
+    #[error("SequenceReplayed")]
+    SequenceReplayed               = 660,
+    /// The feed doesn't accept auxiliary data, see `SetAuxData`
+    #[error("AuxDataNotEnabled")]
+    AuxDataNotEnabled              = 661,
+    /// The feed only accepts the quotes forwarded by its `UpdaterProgram`
+    #[error("UpdateNotForwarded")]
+    UpdateNotForwarded             = 662,
+    /// The feed follows the `SharedSettings` and the shared account wasn't passed
+    #[error("MissingSharedSettings")]
+    MissingSharedSettings          = 663,
+    /// The linked list of mapping accounts loops back to a mapping account it already listed
+    #[error("MappingCycle")]
+    MappingCycle                   = 664,
 }
 
 impl From<OracleError> for ProgramError {
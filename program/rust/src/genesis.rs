@@ -0,0 +1,356 @@
+//! Genesis state of the oracle for new clusters, see the `pyth-genesis` binary.
+//!
+//! The accounts are built by running the instructions that would set up the oracle on a live
+//! cluster through the program itself, so they are exactly what the program would have written.
+//! Every key is derived from a seed, building the same config twice gives the same accounts.
+
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            FeedRegistryAccount,
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+            FEED_REGISTRY_SEED,
+            PERMISSIONS_SEED,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_PROD_ACC_SIZE,
+            PC_PTYPE_PRICE,
+            PC_VERSION,
+        },
+        error::OracleError,
+        instruction::{
+            AddPriceArgs,
+            AddPublisherArgs,
+            CommandHeader,
+            OracleCommand,
+            SetMinPubArgs,
+        },
+        migration::read_price_account,
+        processor::process_instruction,
+        utils::try_convert,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Epoch,
+        hash::hashv,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        rent::Rent,
+        system_program,
+    },
+    std::{
+        collections::HashMap,
+        mem::size_of,
+    },
+};
+
+/// A price feed created at genesis, with its product.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisFeed {
+    pub symbol:     String,
+    /// Product metadata other than the symbol
+    pub attributes: Vec<(String, String)>,
+    pub exponent:   i32,
+    pub min_pub:    u8,
+}
+
+impl GenesisFeed {
+    fn crypto(base: &str, exponent: i32) -> Self {
+        GenesisFeed {
+            symbol: format!("Crypto.{base}/USD"),
+            attributes: vec![
+                ("asset_type".to_string(), "Crypto".to_string()),
+                ("base".to_string(), base.to_string()),
+                ("quote_currency".to_string(), "USD".to_string()),
+                ("generic_symbol".to_string(), format!("{base}USD")),
+                ("description".to_string(), format!("{base}/USD")),
+            ],
+            exponent,
+            min_pub: 1,
+        }
+    }
+}
+
+/// The feeds most integrations expect to find on a cluster.
+pub fn core_feeds() -> Vec<GenesisFeed> {
+    vec![
+        GenesisFeed::crypto("BTC", -8),
+        GenesisFeed::crypto("ETH", -8),
+        GenesisFeed::crypto("SOL", -8),
+        GenesisFeed::crypto("USDC", -8),
+    ]
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisConfig {
+    pub program_id:              Pubkey,
+    pub master_authority:        Pubkey,
+    pub data_curation_authority: Pubkey,
+    pub security_authority:      Pubkey,
+    /// Keys of the mapping, product and price accounts are derived from it
+    pub seed:                    String,
+    /// Publishers added to every feed
+    pub publishers:              Vec<Pubkey>,
+    pub feeds:                   Vec<GenesisFeed>,
+}
+
+/// An account of the genesis state, owned by the program and rent exempt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenesisAccount {
+    pub pubkey:   Pubkey,
+    pub lamports: u64,
+    pub data:     Vec<u8>,
+    pub owner:    Pubkey,
+}
+
+/// Build the accounts of the oracle: the permission account, then the mapping account, then the
+/// product and price account of every feed in the order of `config.feeds`, then the feed registry.
+pub fn build_genesis_accounts(config: &GenesisConfig) -> Result<Vec<GenesisAccount>, ProgramError> {
+    let mut builder = GenesisBuilder::new(config);
+    let permissions_key = builder.create_permission_account();
+    let mapping_key = builder.create_account("mapping", size_of::<MappingAccount>());
+    let mut keys = vec![permissions_key, mapping_key];
+    let mut price_keys = vec![];
+
+    let master_authority = config.master_authority;
+    let header: CommandHeader = OracleCommand::InitMapping.into();
+    builder.process(
+        bytes_of(&header),
+        &[master_authority, mapping_key, permissions_key],
+    )?;
+
+    for feed in &config.feeds {
+        let product_key = builder.create_account(
+            &format!("product/{}", feed.symbol),
+            PC_PROD_ACC_SIZE as usize,
+        );
+        let price_key =
+            builder.create_account(&format!("price/{}", feed.symbol), size_of::<PriceAccount>());
+        keys.extend([product_key, price_key]);
+        price_keys.push(price_key);
+
+        let header: CommandHeader = OracleCommand::AddProduct.into();
+        builder.process(
+            bytes_of(&header),
+            &[master_authority, mapping_key, product_key, permissions_key],
+        )?;
+        builder.process(
+            &product_metadata_instruction_data(feed)?,
+            &[master_authority, product_key, permissions_key],
+        )?;
+
+        let cmd = AddPriceArgs {
+            header:     OracleCommand::AddPrice.into(),
+            exponent:   feed.exponent,
+            price_type: PC_PTYPE_PRICE,
+        };
+        builder.process(
+            bytes_of(&cmd),
+            &[master_authority, product_key, price_key, permissions_key],
+        )?;
+
+        for publisher in &config.publishers {
+            let cmd = AddPublisherArgs {
+                header:    OracleCommand::AddPublisher.into(),
+                publisher: *publisher,
+            };
+            builder.process(
+                bytes_of(&cmd),
+                &[master_authority, price_key, permissions_key],
+            )?;
+        }
+
+        let cmd = SetMinPubArgs {
+            header:             OracleCommand::SetMinPub.into(),
+            minimum_publishers: feed.min_pub,
+            unused_:            [0; 3],
+        };
+        builder.process(
+            bytes_of(&cmd),
+            &[master_authority, price_key, permissions_key],
+        )?;
+    }
+    keys.push(builder.create_feed_registry(&price_keys)?);
+
+    Ok(keys
+        .iter()
+        .filter_map(|key| builder.accounts.remove(key))
+        .collect())
+}
+
+/// `UpdProduct` instruction data setting the metadata of the product of `feed`.
+fn product_metadata_instruction_data(feed: &GenesisFeed) -> Result<Vec<u8>, ProgramError> {
+    let header: CommandHeader = OracleCommand::UpdProduct.into();
+    let mut data = bytes_of(&header).to_vec();
+    let symbol = ("symbol", feed.symbol.as_str());
+    let attributes = feed
+        .attributes
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()));
+    for (key, value) in std::iter::once(symbol).chain(attributes) {
+        for s in [key, value] {
+            let len =
+                u8::try_from(s.len()).map_err(|_| OracleError::InvalidProductMetadataString)?;
+            data.push(len);
+            data.extend_from_slice(s.as_bytes());
+        }
+    }
+    Ok(data)
+}
+
+struct GenesisBuilder<'a> {
+    config:   &'a GenesisConfig,
+    rent:     Rent,
+    accounts: HashMap<Pubkey, GenesisAccount>,
+}
+
+impl<'a> GenesisBuilder<'a> {
+    fn new(config: &'a GenesisConfig) -> Self {
+        GenesisBuilder {
+            config,
+            rent: Rent::default(),
+            accounts: HashMap::new(),
+        }
+    }
+
+    fn derive_key(&self, label: &str) -> Pubkey {
+        Pubkey::new_from_array(
+            hashv(&[self.config.seed.as_bytes(), b"/", label.as_bytes()]).to_bytes(),
+        )
+    }
+
+    /// Create a zeroed, rent exempt account of the program, as `CreateAccount` would.
+    fn create_account(&mut self, label: &str, size: usize) -> Pubkey {
+        let pubkey = self.derive_key(label);
+        self.accounts.insert(
+            pubkey,
+            GenesisAccount {
+                pubkey,
+                lamports: self.rent.minimum_balance(size),
+                data: vec![0; size],
+                owner: self.config.program_id,
+            },
+        );
+        pubkey
+    }
+
+    /// The permission account is written directly, on a live cluster it is created by
+    /// `UpdPermissions` which has to be signed by the upgrade authority of the program.
+    fn create_permission_account(&mut self) -> Pubkey {
+        let (pubkey, _) =
+            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.config.program_id);
+        let mut permissions = PermissionAccount::zeroed();
+        permissions.header = AccountHeader {
+            magic_number: PC_MAGIC,
+            version:      PC_VERSION,
+            account_type: PermissionAccount::ACCOUNT_TYPE,
+            size:         PermissionAccount::INITIAL_SIZE,
+        };
+        permissions.master_authority = self.config.master_authority;
+        permissions.data_curation_authority = self.config.data_curation_authority;
+        permissions.security_authority = self.config.security_authority;
+
+        // Sized for the last feed index from the start, so that `AddPrice` doesn't have to
+        // resize it
+        let mut data = vec![0; PermissionAccount::NEW_ACCOUNT_SPACE];
+        data[..size_of::<PermissionAccount>()].copy_from_slice(bytes_of(&permissions));
+        self.accounts.insert(
+            pubkey,
+            GenesisAccount {
+                pubkey,
+                lamports: self.rent.minimum_balance(data.len()),
+                data,
+                owner: self.config.program_id,
+            },
+        );
+        pubkey
+    }
+
+    /// The feed registry is written directly, on a live cluster `AddPrice` registers the price
+    /// accounts through CPIs to the system program, which can't run here.
+    fn create_feed_registry(&mut self, price_keys: &[Pubkey]) -> Result<Pubkey, ProgramError> {
+        let (pubkey, _) =
+            Pubkey::find_program_address(&[FEED_REGISTRY_SEED.as_bytes()], &self.config.program_id);
+        let mut data = vec![0; FeedRegistryAccount::MINIMUM_SIZE];
+        for price_key in price_keys {
+            let feed_index = read_price_account(&self.accounts[price_key].data)
+                .ok_or(OracleError::InvalidAccountHeader)?
+                .feed_index;
+            let start = FeedRegistryAccount::entry_offset(feed_index)?;
+            let end = start + size_of::<Pubkey>();
+            if data.len() < end {
+                data.resize(end, 0);
+            }
+            data[start..end].copy_from_slice(price_key.as_ref());
+        }
+        let header = AccountHeader {
+            magic_number: PC_MAGIC,
+            version:      PC_VERSION,
+            account_type: FeedRegistryAccount::ACCOUNT_TYPE,
+            size:         try_convert(data.len())?,
+        };
+        data[..size_of::<AccountHeader>()].copy_from_slice(bytes_of(&header));
+
+        self.accounts.insert(
+            pubkey,
+            GenesisAccount {
+                pubkey,
+                lamports: self.rent.minimum_balance(data.len()),
+                data,
+                owner: self.config.program_id,
+            },
+        );
+        Ok(pubkey)
+    }
+
+    /// Run an instruction of the program over the accounts. Every account is passed as a
+    /// writable signer, accounts that weren't created are empty system accounts.
+    fn process(&mut self, instruction_data: &[u8], keys: &[Pubkey]) -> Result<(), ProgramError> {
+        let mut accounts: Vec<GenesisAccount> = keys
+            .iter()
+            .map(|key| {
+                self.accounts.remove(key).unwrap_or(GenesisAccount {
+                    pubkey:   *key,
+                    lamports: 0,
+                    data:     vec![],
+                    owner:    system_program::id(),
+                })
+            })
+            .collect();
+
+        let result = {
+            let account_infos: Vec<AccountInfo> = accounts
+                .iter_mut()
+                .map(|account| {
+                    AccountInfo::new(
+                        &account.pubkey,
+                        true,
+                        true,
+                        &mut account.lamports,
+                        &mut account.data,
+                        &account.owner,
+                        false,
+                        Epoch::default(),
+                    )
+                })
+                .collect();
+            process_instruction(&self.config.program_id, &account_infos, instruction_data)
+        };
+
+        for account in accounts {
+            if account.owner == self.config.program_id {
+                self.accounts.insert(account.pubkey, account);
+            }
+        }
+        result
+    }
+}
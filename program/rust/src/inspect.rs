@@ -0,0 +1,308 @@
+//! Decoding of the mapping, product, price and permission accounts of the program into named
+//! fields, see the `pyth-inspect` binary.
+//!
+//! The fields are listed in the order of the account layout, e.g. `header.version`, `flags` or
+//! `components[0].latest.price`, followed by the product metadata or the extensions of price
+//! accounts. Flags and statuses are printed by name so that the output follows the layouts of
+//! this version of the program.
+
+use {
+    crate::{
+        accounts::{
+            get_product_metadata_entries,
+            iter_extensions,
+            AccountHeader,
+            AccumulatorEmitter,
+            ExtensionType,
+            MappingAccount,
+            PermissionAccount,
+            PermissionTimelock,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_ACCTYPE_MAPPING,
+            PC_ACCTYPE_PERMISSIONS,
+            PC_ACCTYPE_PRICE,
+            PC_ACCTYPE_PRODUCT,
+            PC_MAGIC,
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        OracleError,
+    },
+    bytemuck::pod_read_unaligned,
+    num_traits::FromPrimitive,
+    std::mem::size_of,
+};
+
+/// Fields of the account stored in `data` as `(name, value)` pairs, in the order of its layout.
+/// Fails if `data` doesn't hold a mapping, product, price or permission account.
+pub fn inspect_account(data: &[u8]) -> Result<Vec<(String, String)>, OracleError> {
+    if data.len() < size_of::<AccountHeader>() {
+        return Err(OracleError::InvalidAccountHeader);
+    }
+    let header: AccountHeader = pod_read_unaligned(&data[..size_of::<AccountHeader>()]);
+    if header.magic_number != PC_MAGIC {
+        return Err(OracleError::InvalidAccountHeader);
+    }
+
+    let mut fields = vec![];
+    let mut insert = |field: &str, value: String| fields.push((field.to_string(), value));
+    insert("header.magic_number", format!("{:#x}", header.magic_number));
+    insert("header.version", header.version.to_string());
+    insert(
+        "header.account_type",
+        format!(
+            "{} ({})",
+            header.account_type,
+            account_type_name(header.account_type)
+        ),
+    );
+    insert("header.size", header.size.to_string());
+
+    match header.account_type {
+        PC_ACCTYPE_MAPPING => {
+            let mapping = read_account::<MappingAccount>(data)?;
+            insert("number_of_products", mapping.number_of_products.to_string());
+            insert(
+                "next_mapping_account",
+                mapping.next_mapping_account.to_string(),
+            );
+            for (i, product) in mapping
+                .products_list
+                .iter()
+                .take(mapping.number_of_products as usize)
+                .enumerate()
+            {
+                insert(&format!("products_list[{i}]"), product.to_string());
+            }
+        }
+        PC_ACCTYPE_PRODUCT => {
+            let product = read_account::<ProductAccount>(data)?;
+            insert(
+                "first_price_account",
+                product.first_price_account.to_string(),
+            );
+            for (key, value) in
+                get_product_metadata_entries(data).map_err(|_| OracleError::InvalidAccountHeader)?
+            {
+                insert(
+                    &format!("metadata.{}", String::from_utf8_lossy(key)),
+                    String::from_utf8_lossy(value).into_owned(),
+                );
+            }
+        }
+        PC_ACCTYPE_PRICE => {
+            let price_data = read_account::<PriceAccount>(data)?;
+            add_price_fields(&mut insert, &price_data);
+            for (i, extension) in iter_extensions(data).enumerate() {
+                let extension = extension?;
+                let name = ExtensionType::from_u32(extension.extension_type)
+                    .map_or_else(|| "unknown".to_string(), |name| format!("{name:?}"));
+                insert(
+                    &format!("extensions[{i}]"),
+                    format!(
+                        "{} ({name}), {} bytes: {}",
+                        extension.extension_type,
+                        extension.length,
+                        to_hex(&data[extension.offset..extension.offset + extension.length]),
+                    ),
+                );
+            }
+        }
+        PC_ACCTYPE_PERMISSIONS => {
+            let permissions = read_account::<PermissionAccount>(data)?;
+            insert("master_authority", permissions.master_authority.to_string());
+            insert(
+                "data_curation_authority",
+                permissions.data_curation_authority.to_string(),
+            );
+            insert(
+                "security_authority",
+                permissions.security_authority.to_string(),
+            );
+            // Older permission accounts end before the last feed index or the timelock
+            if let Some(last_feed_index) = data
+                .get(size_of::<PermissionAccount>()..PermissionAccount::NEW_ACCOUNT_SPACE)
+                .map(pod_read_unaligned::<u32>)
+            {
+                insert("last_feed_index", last_feed_index.to_string());
+            }
+            if let Some(timelock) = data
+                .get(PermissionAccount::TIMELOCK_OFFSET..PermissionAccount::TIMELOCK_ACCOUNT_SPACE)
+                .map(pod_read_unaligned::<PermissionTimelock>)
+            {
+                insert("timelock.delay", timelock.delay.to_string());
+                insert(
+                    "timelock.effective_slot",
+                    timelock.effective_slot.to_string(),
+                );
+                insert(
+                    "timelock.pending_master_authority",
+                    timelock.pending_master_authority.to_string(),
+                );
+                insert(
+                    "timelock.pending_data_curation_authority",
+                    timelock.pending_data_curation_authority.to_string(),
+                );
+                insert(
+                    "timelock.pending_security_authority",
+                    timelock.pending_security_authority.to_string(),
+                );
+                insert("timelock.pending_delay", timelock.pending_delay.to_string());
+            }
+            if let Some(emitter) = data
+                .get(PermissionAccount::EMITTER_OFFSET..PermissionAccount::EMITTER_ACCOUNT_SPACE)
+                .map(pod_read_unaligned::<AccumulatorEmitter>)
+            {
+                insert("accumulator_emitter", emitter.program_id.to_string());
+            }
+        }
+        _ => return Err(OracleError::InvalidAccountHeader),
+    }
+
+    Ok(fields)
+}
+
+/// Names of the flags set in `flags`, separated by `|`, followed by the bits this version of the
+/// program doesn't know about.
+pub fn format_price_flags(flags: PriceAccountFlags) -> String {
+    let mut names: Vec<String> = flags
+        .iter_names()
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if flags.unknown_bits() != 0 {
+        names.push(format!("{:#010b}", flags.unknown_bits()));
+    }
+    if names.is_empty() {
+        "none".to_string()
+    } else {
+        names.join(" | ")
+    }
+}
+
+fn add_price_fields(insert: &mut impl FnMut(&str, String), price_data: &PriceAccount) {
+    insert("price_type", price_data.price_type.to_string());
+    insert("exponent", price_data.exponent.to_string());
+    insert("num", price_data.num_.to_string());
+    insert("num_qt", price_data.num_qt_.to_string());
+    insert("last_slot", price_data.last_slot_.to_string());
+    insert("valid_slot", price_data.valid_slot_.to_string());
+    insert("twap.val", price_data.twap_.val_.to_string());
+    insert("twap.numer", price_data.twap_.numer_.to_string());
+    insert("twap.denom", price_data.twap_.denom_.to_string());
+    insert("twac.val", price_data.twac_.val_.to_string());
+    insert("twac.numer", price_data.twac_.numer_.to_string());
+    insert("twac.denom", price_data.twac_.denom_.to_string());
+    insert("timestamp", price_data.timestamp_.to_string());
+    insert("min_pub", price_data.min_pub_.to_string());
+    insert("message_sent", price_data.message_sent_.to_string());
+    insert("max_latency", price_data.max_latency_.to_string());
+    insert(
+        "flags",
+        format!(
+            "{:#010b} ({})",
+            price_data.flags.bits(),
+            format_price_flags(price_data.flags)
+        ),
+    );
+    insert("feed_index", price_data.feed_index.to_string());
+    insert("product_account", price_data.product_account.to_string());
+    insert(
+        "next_price_account",
+        price_data.next_price_account.to_string(),
+    );
+    insert("prev_slot", price_data.prev_slot_.to_string());
+    insert("prev_price", price_data.prev_price_.to_string());
+    insert("prev_conf", price_data.prev_conf_.to_string());
+    insert("prev_timestamp", price_data.prev_timestamp_.to_string());
+    add_price_info_fields(insert, "agg", &price_data.agg_);
+    for (i, component) in price_data
+        .comp_
+        .iter()
+        .take(price_data.num_ as usize)
+        .enumerate()
+    {
+        insert(
+            &format!("components[{i}].publisher"),
+            component.pub_.to_string(),
+        );
+        add_price_info_fields(insert, &format!("components[{i}].agg"), &component.agg_);
+        add_price_info_fields(
+            insert,
+            &format!("components[{i}].latest"),
+            &component.latest_,
+        );
+    }
+    insert(
+        "price_cumulative.price",
+        price_data.price_cumulative.price.to_string(),
+    );
+    insert(
+        "price_cumulative.conf",
+        price_data.price_cumulative.conf.to_string(),
+    );
+    insert(
+        "price_cumulative.num_down_slots",
+        price_data.price_cumulative.num_down_slots.to_string(),
+    );
+}
+
+fn add_price_info_fields(insert: &mut impl FnMut(&str, String), name: &str, info: &PriceInfo) {
+    insert(&format!("{name}.price"), info.price_.to_string());
+    insert(&format!("{name}.conf"), info.conf_.to_string());
+    insert(
+        &format!("{name}.status"),
+        format!("{} ({})", info.status_, status_name(info.status_)),
+    );
+    insert(&format!("{name}.venue"), info.venue().to_string());
+    insert(
+        &format!("{name}.publisher_status"),
+        format!(
+            "{} ({})",
+            info.publisher_status(),
+            status_name(info.publisher_status())
+        ),
+    );
+    insert(&format!("{name}.pub_slot"), info.pub_slot_.to_string());
+}
+
+/// Read the account of type `T` stored in `data`. Account data read off-chain isn't necessarily
+/// aligned, so the account is copied.
+fn read_account<T: PythAccount>(data: &[u8]) -> Result<T, OracleError> {
+    data.get(..size_of::<T>())
+        .map(pod_read_unaligned)
+        .ok_or(OracleError::AccountTooSmall)
+}
+
+fn account_type_name(account_type: u32) -> &'static str {
+    match account_type {
+        PC_ACCTYPE_MAPPING => "mapping",
+        PC_ACCTYPE_PRODUCT => "product",
+        PC_ACCTYPE_PRICE => "price",
+        PC_ACCTYPE_PERMISSIONS => "permissions",
+        _ => "unsupported",
+    }
+}
+
+fn status_name(status: u32) -> &'static str {
+    match status {
+        PC_STATUS_UNKNOWN => "unknown",
+        PC_STATUS_TRADING => "trading",
+        PC_STATUS_HALTED => "halted",
+        PC_STATUS_AUCTION => "auction",
+        PC_STATUS_IGNORED => "ignored",
+        _ => "invalid",
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
@@ -1,6 +1,13 @@
 use {
     crate::{
-        c_oracle_header::PC_VERSION,
+        accounts::{
+            PriceInfo,
+            MAX_MULTISIG_SIGNERS,
+        },
+        c_oracle_header::{
+            CU_BUDGET_PER_IX,
+            PC_VERSION,
+        },
         deserialize::load,
         error::OracleError,
     },
@@ -14,107 +21,716 @@ use {
     },
     num_traits::FromPrimitive,
     solana_program::pubkey::Pubkey,
+    std::mem::size_of,
 };
 
 /// WARNING : NEW COMMANDS SHOULD BE ADDED AT THE END OF THE LIST
 #[repr(i32)]
-#[derive(PartialEq, Eq, FromPrimitive, ToPrimitive)]
+#[derive(Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 pub enum OracleCommand {
     /// Initialize first mapping list account
     // account[0] funding account       [signer writable]
     // account[1] mapping account       [signer writable]
     // account[2] permissions account   []
-    InitMapping           = 0,
+    InitMapping                = 0,
     /// deprecated
-    AddMapping            = 1,
+    AddMapping                 = 1,
     /// Initialize and add new product reference data account
     // account[0] funding account       [signer writable]
     // account[1] mapping account       [signer writable]
     // account[2] new product account   [signer writable]
     // account[3] permissions account   []
-    AddProduct            = 2,
+    AddProduct                 = 2,
     /// Update product account
     // account[0] funding account       [signer writable]
     // account[1] product account       [signer writable]
     // account[2] permissions account   []
-    UpdProduct            = 3,
+    // account[3] buffer program        [] (optional, sends the metadata to the message buffer)
+    // account[4] whitelist             [] (optional)
+    // account[5] oracle auth PDA       [] (optional)
+    // account[6] message buffer data   [writable] (optional)
+    UpdProduct                 = 3,
     /// Add new price account to a product account
     // account[0] funding account       [signer writable]
     // account[1] product account       [writable]
     // account[2] new price account     [writable]
     // account[3] permissions account   [writable]
-    AddPrice              = 4,
+    // account[4] feed registry         [writable] (optional)
+    // account[5] system program        [] (optional)
+    AddPrice                   = 4,
     /// Add publisher to symbol account
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
     // account[2] permissions account   []
-    AddPublisher          = 5,
+    // account[3] publisher registry    [] (optional, required once the registry exists)
+    // account[3|4] stake escrow        [] (optional, required by feeds with a staking program)
+    // account[4|5] staking program     [] (optional, required by feeds with a staking program)
+    AddPublisher               = 5,
     /// Delete publisher from symbol account
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
     // account[2] permissions account   []
-    DelPublisher          = 6,
+    DelPublisher               = 6,
     /// Publish component price
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] sysvar_clock account  []
-    UpdPrice              = 7,
+    UpdPrice                   = 7,
     /// Compute aggregate price
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] sysvar_clock account  []
-    AggPrice              = 8,
-    /// (Re)initialize price account
+    AggPrice                   = 8,
+    /// (Re)initialize price account, and configure it if the arguments hold an `InitPriceConfig`,
+    /// see `InitPriceConfigArgs`
     // account[0] funding account       [signer writable]
     // account[1] new price account     [signer writable]
     // account[2] permissions account   []
-    InitPrice             = 9,
+    // account[3] feed registry         [writable] (optional)
+    // account[4] system program        [] (optional)
+    InitPrice                  = 9,
     /// deprecated
-    InitTest              = 10,
+    InitTest                   = 10,
     /// deprecated
-    UpdTest               = 11,
+    UpdTest                    = 11,
     /// Set min publishers
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
     // account[2] permissions account   []
-    SetMinPub             = 12,
+    SetMinPub                  = 12,
     /// Publish component price, never returning an error even if the update failed
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] sysvar_clock account  []
-    UpdPriceNoFailOnError = 13,
+    UpdPriceNoFailOnError      = 13,
     /// deprecated
-    ResizePriceAccount    = 14,
+    ResizePriceAccount         = 14,
     /// Deletes a price account
     // account[0] funding account       [signer writable]
     // account[1] product account       [signer writable]
     // account[2] price account         [signer writable]
     // account[3] permissions account   []
-    DelPrice              = 15,
+    // account[4] feed registry         [writable] (optional)
+    // account[4 or 5] buffer program   [] (optional, sends a tombstone to the message buffer)
+    // account[5 or 6] whitelist        [] (optional)
+    // account[6 or 7] oracle auth PDA  [] (optional)
+    // account[7 or 8] message buffer   [writable] (optional)
+    DelPrice                   = 15,
     /// Deletes a product account
     // account[0] funding account       [signer writable]
     // account[1] mapping account       [signer writable]
     // account[2] product account       [signer writable]
     // account[3] permissions account   []
-    DelProduct            = 16,
+    DelProduct                 = 16,
     /// Update authorities
     // account[0] upgrade authority     [signer writable]
     // account[1] programdata account   []
     // account[2] permissions account   [writable]
     // account[3] system program        []
-    UpdPermissions        = 17,
+    // account[4] sysvar_clock account  [] (required if a timelock is set)
+    UpdPermissions             = 17,
     /// Set max latency
     // account[0] funding account       [signer writable]
     // account[1] price account         [signer writable]
     // account[2] permissions account   []
-    SetMaxLatency         = 18,
+    SetMaxLatency              = 18,
     /// Init price feed index
     // account[0] funding account       [signer writable]
     // account[1] price account         [writable]
     // account[2] permissions account   [writable]
-    InitPriceFeedIndex    = 19,
+    InitPriceFeedIndex         = 19,
     // account[0] mapping account [writable]
-    ResizeMapping         = 20,
+    ResizeMapping              = 20,
+    /// List the extensions of a price account as return data
+    // account[0] price account         []
+    GetExtensions              = 21,
+    /// Set the exponent of a price account, rescaling its prices
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetExponent                = 22,
+    /// Copy the data of a price account into a new snapshot account
+    // account[0] funding account       [signer writable]
+    // account[1] price account         []
+    // account[2] snapshot account      [writable]
+    // account[3] sysvar_clock account  []
+    // account[4] permissions account   []
+    // account[5] system program        []
+    SnapshotPriceAccount       = 23,
+    /// Limit the rate of the updates of every publisher of a price account, growing the account
+    /// to hold the rate limit extension if needed
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetRateLimit               = 24,
+    /// List the compute budget of every instruction as return data
+    GetComputeBudgets          = 25,
+    /// Set the delay of the changes of the permissions, staging the change if a delay is already
+    /// set
+    // account[0] upgrade authority     [signer writable]
+    // account[1] programdata account   []
+    // account[2] permissions account   [writable]
+    // account[3] system program        []
+    // account[4] sysvar_clock account  []
+    SetPermissionsTimelock     = 26,
+    /// Apply the pending change of the permissions once its delay has passed
+    // account[0] funding account       [signer writable]
+    // account[1] permissions account   [writable]
+    // account[2] sysvar_clock account  []
+    // account[3] programdata account   []
+    ApplyPermissions           = 27,
+    /// Drop the pending change of the permissions
+    // account[0] funding account       [signer writable]
+    // account[1] permissions account   [writable]
+    CancelPermissions          = 28,
+    /// Approve a publisher in the publisher registry or revoke it, creating or growing the
+    /// registry if needed
+    // account[0] funding account       [signer writable]
+    // account[1] publisher registry    [writable]
+    // account[2] permissions account   [writable]
+    // account[3] system program        []
+    UpdPublisherRegistry       = 29,
+    /// Set the number of slots between the updates of the publishers and the aggregate
+    /// including them
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetAggregationDelay        = 30,
+    /// Fail unless the aggregate of a price account meets a condition, so that consumers can
+    /// guard their transactions with it. Returns the successor of a deprecated feed. A
+    /// permissioned feed also requires a reader listed in its access control account.
+    // account[0] price account           []
+    // account[1] sysvar_clock account    []
+    // account[2] reader                  [signer] (permissioned feeds)
+    // account[3] access control account  [] (permissioned feeds)
+    AssertPriceCondition       = 31,
+    /// deprecated
+    SetPublishingSlotTolerance = 32,
+    /// Initialize a multisig account, which can then be set as an authority of the permissions
+    // account[0] funding account       [signer writable]
+    // account[1] multisig account      [signer writable]
+    InitMultisig               = 33,
+    /// Set the number of recent slots over which the slots with a trading aggregate are counted
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetAggregationCountWindow  = 34,
+    /// Return the number of recent slots with a trading aggregate, for liveness monitoring
+    // account[0] price account         []
+    // account[1] sysvar_clock account  []
+    GetAggregationCounts       = 35,
+    /// Set the program that checks every aggregate of the feed, see `SanityCheckArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetSanityProgram           = 36,
+    /// Remove the components of the publishers that stopped publishing, or that are no longer
+    /// approved in the publisher registry
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    // account[3] sysvar_clock account  []
+    // account[4] publisher registry    [] (optional)
+    PrunePublishers            = 37,
+    /// Link a publisher to the entity controlling it in the publisher linkage account or unlink
+    /// it, creating or growing the linkage account if needed
+    // account[0] funding account       [signer writable]
+    // account[1] publisher linkage     [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    UpdPublisherLinkage        = 38,
+    /// Limit the number of quotes of a single entity counted in the aggregates of the feed,
+    /// copying the entities of its publishers from the publisher linkage account
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    // account[3] publisher linkage     []
+    SetPublisherWeightCap      = 39,
+    /// Restart the EMAs of the feed from its current aggregate, discarding their history
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    ResetEma                   = 40,
+    /// Add a price account with the configuration and the publishers of an existing one to its
+    /// product
+    // account[0] funding account        [signer writable]
+    // account[1] product account        [writable]
+    // account[2] source price account   []
+    // account[3] new price account      [writable]
+    // account[4] permissions account    [writable]
+    // account[5] feed registry          [writable] (optional)
+    // account[6] system program         [] (optional)
+    ClonePrice                 = 41,
+    /// Maintain the order of the quotes of the feed to aggregate without sorting them, or stop
+    /// maintaining it
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetQuoteOrder              = 42,
+    /// Mark the quote of a publisher as halted or in auction without publishing a price
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] sysvar_clock account  []
+    UpdPublisherStatus         = 43,
+    /// Flag the feed as deprecated and point it to the price account replacing it
+    // account[0] funding account           [signer writable]
+    // account[1] price account             [signer writable]
+    // account[2] permissions account       []
+    // account[3] successor price account   []
+    DeprecatePrice             = 44,
+    /// Publish component price with compact arguments relative to the latest quote of the
+    /// publisher
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] sysvar_clock account  []
+    UpdPriceCompact            = 45,
+    /// Update some keys of the metadata of a product account, preserving the others
+    // account[0] funding account       [signer writable]
+    // account[1] product account       [signer writable]
+    // account[2] permissions account   []
+    // account[3] buffer program        [] (optional, sends the metadata to the message buffer)
+    // account[4] whitelist             [] (optional)
+    // account[5] oracle auth PDA       [] (optional)
+    // account[6] message buffer data   [writable] (optional)
+    PatchProduct               = 46,
+    /// Flip a switch of the feature gate after its grace period
+    // account[0] funding account       [signer writable]
+    // account[1] feature gate          [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    // account[4] sysvar_clock account  []
+    SetFeature                 = 47,
+    /// Set the grace period of the later flips of the feature gate
+    // account[0] funding account       [signer writable]
+    // account[1] feature gate          [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetFeatureGracePeriod      = 48,
+    /// Add or remove a reader of an access control account, initializing the account if needed
+    // account[0] funding account          [signer writable]
+    // account[1] access control account   [signer writable]
+    // account[2] permissions account      []
+    UpdAccessControl           = 49,
+    /// Restrict the consumers of the feed to the readers of an access control account, or lift
+    /// the restriction if the account is omitted
+    // account[0] funding account          [signer writable]
+    // account[1] price account            [signer writable]
+    // account[2] permissions account      []
+    // account[3] access control account   [] (optional)
+    SetAccessControl           = 50,
+    /// Set the number of recent aggregations over which the landing latency of the updates of
+    /// the feed is tracked, see `LatencyStats`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetLatencyStatsWindow      = 51,
+    /// Set the number of recent slots over which the lowest and highest aggregates are tracked
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPriceExtremaWindow      = 52,
+    /// Return the lowest and highest aggregates over the recent slots, see `PriceExtremaSummary`
+    // account[0] price account         []
+    // account[1] sysvar_clock account  []
+    GetPriceExtrema            = 53,
+    /// Move the components of a publisher to a new key in every listed price account, keeping
+    /// their quotes
+    // account[0] publisher account     [signer writable]
+    // account[1] new publisher key     [signer]
+    // account[2] permissions account   []
+    // account[3] publisher registry    [] (only once the registry exists)
+    // account[3|4..] price accounts    [writable]
+    RotatePublisher            = 54,
+    /// Set the smallest confidence interval of the aggregates of a feed
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetConfFloor               = 55,
+    /// Start or stop recording why the aggregations of a feed leave it without a price
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetStatusReasonTracking    = 56,
+    /// Set the staking program checking the stake of the publishers added to a feed, see
+    /// `StakeCheckArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetStakeProgram            = 57,
+    /// Create a product without governance under the namespace of its creator, see
+    /// `COMMUNITY_SEED`. The metadata follows the `AddCommunityProductArgs`, like in `UpdProduct`.
+    // account[0] creator account       [signer writable]
+    // account[1] product account       [writable]
+    // account[2] system program        []
+    AddCommunityProduct        = 58,
+    /// Create a price account of a community product of the creator, flagged as a `COMMUNITY`
+    /// feed that its creator manages, without a feed index and off the accumulator
+    // account[0] creator account       [signer writable]
+    // account[1] product account       [writable]
+    // account[2] price account         [writable]
+    // account[3] system program        []
+    AddCommunityPrice          = 59,
+    /// Set the largest number of publishers of a feed, below the slots of its price account
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublisherCap            = 60,
+    /// Set the feed whose aggregate converts the aggregate of a price account to another quote
+    /// currency, see `QuoteConversion`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetQuoteConversion         = 61,
+    /// Convert the latest aggregate of a price account with the latest aggregate of its
+    /// conversion feed. Anyone can send it.
+    // account[0] price account         [writable]
+    // account[1] conversion feed       []
+    ConvertPrice               = 62,
+    /// Keep the aggregate of a feed without aggregation delay for the rest of the slot once an
+    /// update computed it, see `AggregateCache`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetAggregateCache          = 63,
+    /// Set the number of quotes of a feed sampled in every aggregation, see `PublisherSampling`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublisherSampling       = 64,
+    /// Create the audit log recording the governance instructions, see `AuditLogAccount`
+    // account[0] funding account       [signer writable]
+    // account[1] audit log             [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    InitAuditLog               = 65,
+    /// Set the lamports paid to the fee vault by every update of a feed, see `UpdateFee`,
+    /// creating the fee vault if needed
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    // account[3] fee vault             [writable]
+    // account[4] system program        []
+    SetUpdateFee               = 66,
+    /// Withdraw collected fees from the fee vault, see `FeeVaultAccount`
+    // account[0] funding account       [signer writable]
+    // account[1] fee vault             [writable]
+    // account[2] permissions account   []
+    // account[3] recipient             [writable]
+    WithdrawFees               = 67,
+    /// Start or stop scoring the quotes of a feed in its aggregations, see `PublisherRewards`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublisherRewards        = 68,
+    /// Move the reward score of a publisher in a feed to its `RewardsLedgerAccount`, creating the
+    /// ledger if needed
+    // account[0] publisher account     [signer writable]
+    // account[1] price account         [writable]
+    // account[2] rewards ledger        [writable]
+    // account[3] system program        []
+    ClaimRewards               = 69,
+    /// Link related feeds in a consistency check account, growing each price account to hold
+    /// its `ConsistencyFlag`
+    // account[0] funding account       [signer writable]
+    // account[1] consistency check     [signer writable]
+    // account[2] permissions account   []
+    // account[3..] price accounts      [writable] (2 or 3, see `ConsistencyKind`)
+    InitConsistencyCheck       = 70,
+    /// Compare the aggregates of the feeds of a consistency check, raising or clearing their
+    /// `ConsistencyFlag`. Anyone can send it.
+    // account[0] consistency check     [writable]
+    // account[1] sysvar_clock account  []
+    // account[2..] price accounts      [writable] (in the order of the check)
+    CheckConsistency           = 71,
+    /// Initialize and add a new product account at the PDA derived from its symbol, see
+    /// `find_product_pda`
+    // account[0] funding account       [signer writable]
+    // account[1] mapping account       [signer writable]
+    // account[2] product account       [writable]
+    // account[3] permissions account   []
+    // account[4] system program        []
+    InitProductPda             = 72,
+    /// Add a new price account at the PDA derived from its product and feed kind, see
+    /// `find_price_pda`
+    // account[0] funding account       [signer writable]
+    // account[1] product account       [writable]
+    // account[2] price account         [writable]
+    // account[3] permissions account   [writable]
+    // account[4] system program        []
+    // account[5] feed registry         [writable] (optional)
+    InitPricePda               = 73,
+    /// Start or stop counting the updates accepted from the publishers of a feed, reported by
+    /// `DelPrice` in the `FeedTombstoneMessage`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetUpdateCounting          = 74,
+    /// Select the average a feed publishes and the window of its slot-weighted TWAP, see
+    /// `SetAverageModeArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetAverageMode             = 75,
+    /// Initialize a hash-indexed mapping account, its capacity is set by the size of the account
+    // account[0] funding account       [signer writable]
+    // account[1] mapping account v2    [signer writable]
+    // account[2] permissions account   []
+    InitMappingV2              = 76,
+    /// Index the products listed in a mapping account in a hash-indexed mapping account, see
+    /// `MigrateMappingArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] mapping account v2    [signer writable]
+    // account[2] permissions account   []
+    // account[3] mapping account       []
+    // account[4..] product accounts    [] (in the order of the mapping account)
+    MigrateMapping             = 77,
+    /// Start or stop recording the unix timestamp of the quotes of the publishers of a feed
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublisherTimestamps     = 78,
+    /// Publish the aggregates of a feed only when they deviate from the published one or after a
+    /// heartbeat, see `SetPublishThresholdArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublishThreshold        = 79,
+    /// Set the program notified of the status transitions of the aggregate of a feed, see
+    /// `StatusTransition`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetStatusObserver          = 80,
+    /// Start or stop checking the price account against its `Checksum`. Starting records the
+    /// checksum of the account as it is, which is how governance repairs a feed whose account no
+    /// longer matches it.
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetChecksum                = 81,
+    /// Start or stop checking the sequence numbers of the quotes of a feed, see
+    /// `PublisherSequences`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetSequenceTracking        = 82,
+    /// Set how the aggregation of a feed combines the statuses supplied by its publishers, see
+    /// `SetStatusPolicyArgs`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetStatusPolicy            = 83,
+    /// Set the program that `UpdPrice` pushes every new aggregate to, see `AccumulatorEmitter`
+    // account[0] upgrade authority     [signer writable]
+    // account[1] programdata account   []
+    // account[2] permissions account   [writable]
+    // account[3] system program        []
+    SetAccumulatorEmitter      = 84,
+    /// Start or stop accepting the open interest and volume of the publishers of a feed, see
+    /// `AuxData`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetAuxData                 = 85,
+    /// Submit the open interest and volume of the market of a feed as one of its publishers
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] sysvar_clock account  []
+    UpdAuxData                 = 86,
+    /// Return the aggregate that aggregating the current quotes of a feed would publish, without
+    /// changing the feed, see `SimulatedAggregate`
+    // account[0] price account         []
+    // account[1] sysvar_clock account  []
+    // account[2] feature gate account  [] (feeds following the `SharedSettings`)
+    // account[3] aggregation config    [] (feeds following the `SharedSettings`)
+    SimulateAggregate          = 87,
+    /// Set the number of aggregation windows after which a stale publisher of a feed is left out
+    /// of its quorum, see `PublisherActivity`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetPublisherDemotion       = 88,
+    /// Set the program that the quotes of a feed must be forwarded through, see
+    /// `UpdaterProgram`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetUpdaterProgram          = 89,
+    /// Set the defaults of the aggregation shared by the feeds that don't set their own,
+    /// initializing the aggregation config if needed, see `AggregationConfigAccount`
+    // account[0] funding account       [signer writable]
+    // account[1] aggregation config    [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    SetAggregationConfig       = 90,
+    /// Grow a price account created before the extension region was reserved to the size of the
+    /// new price accounts, see `EXTENSIONS_RESERVED_SPACE`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [writable]
+    // account[2] permissions account   []
+    ReserveExtensionSpace      = 91,
+    /// Start counting the reads of the feed by `GetPrice`, see `ReadStats`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    EnableReadStats            = 92,
+    /// Return the latest price and the EMA of the feed, see `PriceSummary`
+    // account[0] price account           [] (writable if the feed counts its reads)
+    // account[1] sysvar_clock account    []
+    // account[2] reader                  [signer] (permissioned feeds)
+    // account[3] access control account  [] (permissioned feeds)
+    GetPrice                   = 93,
+    /// Grow the feed registry by up to 10 KiB towards the size holding the entry of the last feed
+    /// index, creating it if needed
+    // account[0] funding account       [signer writable]
+    // account[1] feed registry         [writable]
+    // account[2] permissions account   []
+    // account[3] system program        []
+    ResizeFeedRegistry         = 94,
+    /// Make a feed aggregate with the aggregation config and the feature gate of the program,
+    /// requiring them in `UpdPrice`, see `SharedSettings`
+    // account[0] funding account       [signer writable]
+    // account[1] price account         [signer writable]
+    // account[2] permissions account   []
+    SetSharedSettings          = 95,
+}
+
+impl OracleCommand {
+    /// Compute units to request for the instruction, the worst case measured by
+    /// `test_compute_budgets` with headroom. Update it when the benchmark reports a higher cost.
+    /// `None` for the instructions that were removed.
+    pub fn compute_budget(&self) -> Option<u32> {
+        use OracleCommand::*;
+
+        match self {
+            InitMapping => Some(5_000),
+            AddProduct => Some(10_000),
+            // Sending the metadata to the message buffer takes most of it
+            UpdProduct => Some(40_000),
+            // Creating and growing the feed registry takes most of it
+            AddPrice => Some(40_000),
+            // Sorting the publishers takes most of it
+            AddPublisher => Some(40_000),
+            DelPublisher => Some(40_000),
+            UpdPrice => Some(CU_BUDGET_PER_IX),
+            AggPrice => Some(CU_BUDGET_PER_IX),
+            InitPrice => Some(40_000),
+            SetMinPub => Some(5_000),
+            UpdPriceNoFailOnError => Some(CU_BUDGET_PER_IX),
+            DelPrice => Some(20_000),
+            DelProduct => Some(10_000),
+            UpdPermissions => Some(30_000),
+            SetMaxLatency => Some(5_000),
+            ResizeMapping => Some(10_000),
+            GetExtensions => Some(5_000),
+            SetExponent => Some(15_000),
+            SnapshotPriceAccount => Some(30_000),
+            SetRateLimit => Some(15_000),
+            GetComputeBudgets => Some(5_000),
+            SetPermissionsTimelock => Some(30_000),
+            ApplyPermissions => Some(15_000),
+            CancelPermissions => Some(5_000),
+            UpdPublisherRegistry => Some(20_000),
+            SetAggregationDelay => Some(15_000),
+            AssertPriceCondition => Some(5_000),
+            InitMultisig => Some(10_000),
+            SetAggregationCountWindow => Some(15_000),
+            GetAggregationCounts => Some(5_000),
+            SetSanityProgram => Some(15_000),
+            PrunePublishers => Some(40_000),
+            UpdPublisherLinkage => Some(20_000),
+            // Looking up the components of every link takes most of it
+            SetPublisherWeightCap => Some(40_000),
+            ResetEma => Some(15_000),
+            ClonePrice => Some(40_000),
+            SetQuoteOrder => Some(15_000),
+            UpdPublisherStatus => Some(10_000),
+            DeprecatePrice => Some(15_000),
+            UpdPriceCompact => Some(CU_BUDGET_PER_IX),
+            PatchProduct => Some(45_000),
+            SetFeature => Some(20_000),
+            SetFeatureGracePeriod => Some(15_000),
+            UpdAccessControl => Some(10_000),
+            SetAccessControl => Some(15_000),
+            SetLatencyStatsWindow => Some(15_000),
+            SetPriceExtremaWindow => Some(15_000),
+            GetPriceExtrema => Some(5_000),
+            // Sized for a handful of price accounts, longer lists need a larger budget
+            RotatePublisher => Some(40_000),
+            SetConfFloor => Some(15_000),
+            SetStatusReasonTracking => Some(15_000),
+            SetStakeProgram => Some(15_000),
+            // Deriving the addresses and creating the accounts take most of it
+            AddCommunityProduct => Some(40_000),
+            AddCommunityPrice => Some(80_000),
+            SetPublisherCap => Some(15_000),
+            SetQuoteConversion => Some(15_000),
+            ConvertPrice => Some(10_000),
+            SetAggregateCache => Some(15_000),
+            SetPublisherSampling => Some(15_000),
+            InitAuditLog => Some(20_000),
+            // Creating the fee vault takes most of it
+            SetUpdateFee => Some(30_000),
+            WithdrawFees => Some(10_000),
+            SetPublisherRewards => Some(15_000),
+            // Creating the rewards ledger takes most of it
+            ClaimRewards => Some(30_000),
+            InitConsistencyCheck => Some(30_000),
+            CheckConsistency => Some(15_000),
+            // Deriving the addresses and creating the accounts take most of it
+            InitProductPda => Some(50_000),
+            InitPricePda => Some(80_000),
+            SetUpdateCounting => Some(15_000),
+            SetAverageMode => Some(15_000),
+            InitMappingV2 => Some(10_000),
+            // Hashing the symbols takes most of it
+            MigrateMapping => Some(60_000),
+            SetPublisherTimestamps => Some(15_000),
+            SetPublishThreshold => Some(15_000),
+            SetStatusObserver => Some(15_000),
+            SetChecksum => Some(15_000),
+            SetSequenceTracking => Some(15_000),
+            SetStatusPolicy => Some(15_000),
+            SetAccumulatorEmitter => Some(30_000),
+            SetAuxData => Some(15_000),
+            // Sorting the submissions takes most of it
+            UpdAuxData => Some(40_000),
+            // Running the aggregation takes most of it
+            SimulateAggregate => Some(CU_BUDGET_PER_IX),
+            SetPublisherDemotion => Some(15_000),
+            SetUpdaterProgram => Some(15_000),
+            SetAggregationConfig => Some(20_000),
+            ReserveExtensionSpace => Some(10_000),
+            EnableReadStats => Some(15_000),
+            GetPrice => Some(10_000),
+            ResizeFeedRegistry => Some(20_000),
+            SetSharedSettings => Some(15_000),
+            AddMapping
+            | InitTest
+            | UpdTest
+            | ResizePriceAccount
+            | InitPriceFeedIndex
+            | SetPublishingSlotTolerance => None,
+        }
+    }
+
+    /// Position of the account that a governance instruction changes, recorded in the audit log
+    /// with the instruction. `None` for the instructions that aren't recorded.
+    pub fn audit_target(&self) -> Option<usize> {
+        use OracleCommand::*;
+
+        match self {
+            AddPublisher | DelPublisher | PrunePublishers | DeprecatePrice => Some(1),
+            UpdPublisherRegistry | UpdPublisherLinkage => Some(1),
+            UpdPermissions | SetPermissionsTimelock | SetAccumulatorEmitter => Some(2),
+            ApplyPermissions | CancelPermissions => Some(1),
+            UpdAccessControl | SetAccessControl => Some(1),
+            SetFeature | SetFeatureGracePeriod => Some(1),
+            SetUpdateFee | WithdrawFees => Some(1),
+            SetAggregationConfig => Some(1),
+            _ => None,
+        }
+    }
 }
 
 #[repr(C)]
@@ -141,6 +757,64 @@ pub struct AddPriceArgs {
     pub price_type: u32,
 }
 pub type InitPriceArgs = AddPriceArgs;
+pub type InitPricePdaArgs = AddPriceArgs;
+
+/// Version of the `InitPriceConfig` this program reads.
+pub const INIT_PRICE_CONFIG_VERSION: u32 = 1;
+
+/// Configuration of a feed applied by `InitPrice` in the instruction that (re)initializes it, so
+/// that a new feed is never live half configured between a series of configuration instructions.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct InitPriceConfig {
+    /// `INIT_PRICE_CONFIG_VERSION`, other versions are rejected
+    pub version:           u32,
+    /// `AverageMode` the feed publishes, see `SetAverageModeArgs`
+    pub average_mode:      u32,
+    /// See `SetMinPubArgs`
+    pub min_pub:           u8,
+    /// See `SetMaxLatencyArgs`, 0 for the default latency
+    pub max_latency:       u8,
+    /// `PriceAccountFlags` among `ALLOW_ZERO_CI` and `ISOLATED_UPDATES` the feed has, the other
+    /// bits must be 0
+    pub flags:             u8,
+    pub unused_:           [u8; 5],
+    /// Window of the slot-weighted TWAP, see `SetAverageModeArgs`. 0 with the `Ema` mode doesn't
+    /// grow the price account
+    pub twap_window_slots: u64,
+}
+
+/// Arguments of `InitPrice` followed by the configuration of the feed. The `FeedKind` of the
+/// feed is the `price_type` it was added with, which the arguments must match as without a
+/// configuration.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct InitPriceConfigArgs {
+    pub header:     CommandHeader,
+    pub exponent:   i32,
+    pub price_type: u32,
+    pub config:     InitPriceConfig,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct AddCommunityProductArgs {
+    pub header: CommandHeader,
+    /// Seed of the product account in the namespace of the creator
+    pub seed:   [u8; 32],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct AddCommunityPriceArgs {
+    pub header:       CommandHeader,
+    pub exponent:     i32,
+    pub price_type:   u32,
+    /// Seed the product account was created with by `AddCommunityProduct`
+    pub product_seed: [u8; 32],
+    /// Seed of the price account in the namespace of the creator
+    pub price_seed:   [u8; 32],
+}
 
 #[repr(C)]
 #[derive(Zeroable, Pod, Copy, Clone)]
@@ -164,10 +838,105 @@ pub struct SetMinPubArgs {
 pub struct UpdPriceArgs {
     pub header:          CommandHeader,
     pub status:          u32,
-    pub unused_:         u32,
+    /// Venue the quote was sourced from, an identifier agreed with the publishers. 0 if the quote
+    /// isn't tagged
+    pub venue:           u8,
+    pub unused_:         [u8; 3],
     pub price:           i64,
     pub confidence:      u64,
     pub publishing_slot: u64,
+    /// Number of the quote, above the number of the latest quote of the publisher on feeds
+    /// tracking them, see `PublisherSequences`. 0 if the quote isn't numbered.
+    pub sequence:        u64,
+}
+
+impl UpdPriceArgs {
+    /// Size of the arguments without `sequence`, still accepted as a quote that isn't numbered.
+    pub const UNSEQUENCED_SIZE: usize = 40;
+
+    /// Decode the arguments of `UpdPrice`, with or without `sequence`.
+    pub fn decode(data: &[u8]) -> Result<Self, OracleError> {
+        if data.len() >= size_of::<UpdPriceArgs>() {
+            return load::<UpdPriceArgs>(data).copied();
+        }
+        let unsequenced = data
+            .get(..Self::UNSEQUENCED_SIZE)
+            .ok_or(OracleError::InstructionDataTooShort)?;
+        let mut args = UpdPriceArgs::zeroed();
+        bytemuck::bytes_of_mut(&mut args)[..Self::UNSEQUENCED_SIZE].copy_from_slice(unsequenced);
+        Ok(args)
+    }
+}
+
+/// Arguments of `UpdPriceCompact`, the `UpdPriceArgs` of a publisher encoded relative to its
+/// latest quote in the price account to shrink the transactions of frequent updates. The low
+/// bits of the publishing slot of that quote are included, so that an update computed from a
+/// quote that didn't land is rejected instead of publishing the wrong price. The first quote of
+/// a publisher usually needs `UpdPrice`, since it's relative to a zero price.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdPriceCompactArgs {
+    pub header:      CommandHeader,
+    /// Price minus the price of the latest quote
+    pub price_delta: i32,
+    pub confidence:  u32,
+    /// Low 16 bits of the publishing slot of the latest quote
+    pub base_slot:   u16,
+    /// Publishing slot minus the publishing slot of the latest quote
+    pub slot_delta:  u16,
+    pub status:      u8,
+    /// Venue the quote was sourced from, see `UpdPriceArgs`
+    pub venue:       u8,
+    pub unused_:     [u8; 2],
+}
+
+impl UpdPriceCompactArgs {
+    /// Encode the `UpdPrice` arguments `args` relative to the `latest` quote of the publisher,
+    /// or `None` if they don't fit. Numbered quotes don't fit.
+    pub fn encode(args: &UpdPriceArgs, latest: &PriceInfo) -> Option<Self> {
+        if args.sequence != 0 {
+            return None;
+        }
+        Some(UpdPriceCompactArgs {
+            header:      CommandHeader {
+                version: args.header.version,
+                command: OracleCommand::UpdPriceCompact as i32,
+            },
+            price_delta: i32::try_from(args.price.checked_sub(latest.price_)?).ok()?,
+            confidence:  u32::try_from(args.confidence).ok()?,
+            base_slot:   latest.pub_slot_ as u16,
+            slot_delta:  u16::try_from(args.publishing_slot.checked_sub(latest.pub_slot_)?).ok()?,
+            status:      u8::try_from(args.status).ok()?,
+            venue:       args.venue,
+            unused_:     [0; 2],
+        })
+    }
+
+    /// Decode the `UpdPrice` arguments relative to the `latest` quote of the publisher.
+    pub fn decode(&self, latest: &PriceInfo) -> Result<UpdPriceArgs, OracleError> {
+        if self.base_slot != latest.pub_slot_ as u16 {
+            return Err(OracleError::CompactBaseMismatch);
+        }
+        Ok(UpdPriceArgs {
+            header:          CommandHeader {
+                version: self.header.version,
+                command: OracleCommand::UpdPrice as i32,
+            },
+            status:          u32::from(self.status),
+            venue:           self.venue,
+            unused_:         [0; 3],
+            price:           latest
+                .price_
+                .checked_add(i64::from(self.price_delta))
+                .ok_or(OracleError::CompactBaseMismatch)?,
+            confidence:      u64::from(self.confidence),
+            publishing_slot: latest
+                .pub_slot_
+                .checked_add(u64::from(self.slot_delta))
+                .ok_or(OracleError::CompactBaseMismatch)?,
+            sequence:        0,
+        })
+    }
 }
 
 #[repr(C)]
@@ -179,6 +948,14 @@ pub struct UpdPermissionsArgs {
     pub security_authority:      Pubkey,
 }
 
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPermissionsTimelockArgs {
+    pub header: CommandHeader,
+    /// Slots between staging a change of the permissions and applying it, 0 disables the timelock
+    pub delay:  u64,
+}
+
 #[repr(C)]
 #[derive(Zeroable, Clone, Copy, Pod)]
 pub struct SetMaxLatencyArgs {
@@ -186,3 +963,597 @@ pub struct SetMaxLatencyArgs {
     pub max_latency: u8,
     pub unused_:     [u8; 3],
 }
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetExponentArgs {
+    pub header:   CommandHeader,
+    pub exponent: i32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetRateLimitArgs {
+    pub header: CommandHeader,
+    /// Number of slots between two updates of a publisher, 0 and 1 both allow one update per slot
+    pub window: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAggregationDelayArgs {
+    pub header: CommandHeader,
+    /// 0 aggregates the quotes of the current slot, 1 only the quotes of earlier slots
+    pub slots:  u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct InitMultisigArgs {
+    pub header:      CommandHeader,
+    /// Number of distinct signers needed, between 1 and `num_signers`
+    pub threshold:   u8,
+    pub num_signers: u8,
+    pub unused_:     [u8; 6],
+    /// Only the first `num_signers` keys are read, they must be distinct
+    pub signers:     [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAggregationCountWindowArgs {
+    pub header:       CommandHeader,
+    /// Number of slots of the window, a multiple of `AGGREGATION_COUNT_BUCKETS`, 0 stops counting
+    pub window_slots: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPriceExtremaWindowArgs {
+    pub header:       CommandHeader,
+    /// Number of slots of the window, a multiple of `PRICE_EXTREMA_BUCKETS`, 0 stops tracking
+    pub window_slots: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetConfFloorArgs {
+    pub header:       CommandHeader,
+    /// Smallest confidence of the aggregates, in price units
+    pub min_conf:     u64,
+    /// Smallest confidence of the aggregates, in basis points of the absolute aggregate price,
+    /// at most `MAX_CONF_FLOOR_BPS`
+    pub min_conf_bps: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetStatusReasonTrackingArgs {
+    pub header:  CommandHeader,
+    /// 0 stops recording, the last reason is kept
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetStakeProgramArgs {
+    pub header:          CommandHeader,
+    /// `Pubkey::default()` stops checking the stake of new publishers. Changing the program
+    /// drops the stakes recorded from the previous one.
+    pub staking_program: Pubkey,
+    /// Smallest stake of a new publisher, in the units of the staking program
+    pub min_stake:       u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherCapArgs {
+    pub header:         CommandHeader,
+    /// At least the current number of publishers of the feed and at most `PC_NUM_COMP`, 0
+    /// removes the cap
+    pub max_publishers: u32,
+    pub unused_:        u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetQuoteConversionArgs {
+    pub header:          CommandHeader,
+    /// `Pubkey::default()` stops converting the aggregate
+    pub conversion_feed: Pubkey,
+    /// Exponent of the converted price
+    pub exponent:        i32,
+    /// 1 to divide by the aggregate of the conversion feed, 0 to multiply by it
+    pub divide:          u8,
+    pub unused_:         [u8; 3],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAggregateCacheArgs {
+    pub header:  CommandHeader,
+    /// 0 aggregates again on every update
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetSharedSettingsArgs {
+    pub header:  CommandHeader,
+    /// 0 aggregates with the defaults of the program again
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherSamplingArgs {
+    pub header:      CommandHeader,
+    /// At most `PC_NUM_COMP`, 0 counts every quote
+    pub sample_size: u32,
+    pub unused_:     u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetUpdateFeeArgs {
+    pub header:   CommandHeader,
+    /// 0 lets the updates through for free
+    pub lamports: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct WithdrawFeesArgs {
+    pub header:   CommandHeader,
+    /// At most the lamports of the fee vault beyond its rent exemption
+    pub lamports: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherRewardsArgs {
+    pub header:  CommandHeader,
+    /// 0 stops scoring the aggregations
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetUpdateCountingArgs {
+    pub header:  CommandHeader,
+    /// 0 stops counting, the count is kept
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAverageModeArgs {
+    pub header:       CommandHeader,
+    /// `AverageMode` to publish, `Ema` with a 0 window stops maintaining the slot-weighted TWAP
+    pub mode:         u32,
+    pub unused_:      u32,
+    /// Number of slots of the window of the slot-weighted TWAP, a multiple of
+    /// `SLOT_WEIGHTED_TWAP_BUCKETS`
+    pub window_slots: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct MigrateMappingArgs {
+    pub header:  CommandHeader,
+    /// Position in the mapping account of the first product account passed
+    pub start:   u32,
+    pub unused_: u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherTimestampsArgs {
+    pub header:  CommandHeader,
+    /// 0 stops recording, the recorded timestamps are kept
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublishThresholdArgs {
+    pub header:          CommandHeader,
+    /// Deviation from the published aggregate above which a new aggregate is published, in basis
+    /// points of the absolute published price. 0 publishes every aggregate
+    pub deviation_bps:   u64,
+    /// Slots after which a new aggregate is published whatever its deviation
+    pub heartbeat_slots: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct InitConsistencyCheckArgs {
+    pub header:        CommandHeader,
+    /// `ConsistencyKind` of the relation
+    pub kind:          u32,
+    /// Largest deviation in basis points between the two sides of the relation
+    pub tolerance_bps: u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetStatusObserverArgs {
+    pub header:     CommandHeader,
+    /// `Pubkey::default()` stops notifying a program
+    pub program_id: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetChecksumArgs {
+    pub header:  CommandHeader,
+    /// 0 stops checking the price account
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetSequenceTrackingArgs {
+    pub header:  CommandHeader,
+    /// 0 stops checking the sequence numbers, the recorded ones are kept
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAuxDataArgs {
+    pub header:  CommandHeader,
+    /// 0 stops accepting submissions, the medians are kept
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdAuxDataArgs {
+    pub header:        CommandHeader,
+    /// Open interest of the market, in its units
+    pub open_interest: u64,
+    /// Volume traded on the market in the last 24 hours, in its units
+    pub volume_24h:    u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherDemotionArgs {
+    pub header:     CommandHeader,
+    /// Consecutive aggregation windows a publisher can miss before it is demoted, 0 stops
+    /// demoting and reactivates every publisher at the next window
+    pub max_missed: u32,
+    pub unused_:    u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAccumulatorEmitterArgs {
+    pub header:     CommandHeader,
+    /// `Pubkey::default()` stops pushing the aggregates
+    pub program_id: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetStatusPolicyArgs {
+    pub header:           CommandHeader,
+    /// Recent quotes reporting a halt that make the aggregate `PC_STATUS_HALTED`, 0 never does
+    pub halted_quorum:    u8,
+    /// Recent quotes reporting an auction that make the aggregate `PC_STATUS_AUCTION`, 0 never
+    /// does
+    pub auction_quorum:   u8,
+    /// Nonzero if a quorum also replaces an aggregate of the trading quotes
+    pub override_trading: u8,
+    pub unused_:          [u8; 5],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetSanityProgramArgs {
+    pub header:     CommandHeader,
+    /// `Pubkey::default()` stops checking the aggregates
+    pub program_id: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetUpdaterProgramArgs {
+    pub header:     CommandHeader,
+    /// `Pubkey::default()` accepts the quotes of any instruction again
+    pub program_id: Pubkey,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetAggregationConfigArgs {
+    pub header:         CommandHeader,
+    /// 0 ignores the quotes above `MAX_CI_DIVISOR` again
+    pub max_ci_divisor: i64,
+    /// 0 keeps the latency of the program, `PC_MAX_SEND_LATENCY`
+    pub max_latency:    u8,
+    /// 0 keeps the quorum of the feeds
+    pub min_pub:        u8,
+    pub unused_:        [u8; 6],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdPublisherRegistryArgs {
+    pub header:    CommandHeader,
+    pub publisher: Pubkey,
+    /// 1 to approve the publisher, 0 to revoke it
+    pub approved:  u8,
+    pub unused_:   [u8; 3],
+}
+
+/// Condition on the aggregate of a price account. Prices are in the exponent of the price
+/// account. Every bound is inclusive and its loosest value skips the check.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct AssertPriceConditionArgs {
+    pub header:             CommandHeader,
+    /// `i64::MIN` to skip the check
+    pub min_price:          i64,
+    /// `i64::MAX` to skip the check
+    pub max_price:          i64,
+    /// Largest confidence interval in basis points of the price, `u64::MAX` to skip the check
+    pub max_conf_ratio_bps: u64,
+    /// Largest number of slots since the aggregation, `u64::MAX` to skip the check
+    pub max_age:            u64,
+    /// Status the aggregate must have, `PC_STATUS_UNKNOWN` to skip the check
+    pub required_status:    u32,
+    pub unused_:            u32,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct PrunePublishersArgs {
+    pub header:         CommandHeader,
+    /// Publishers whose latest quote is more than this many slots old are removed, including
+    /// the ones that never published. 0 keeps publishers regardless of their latest quote.
+    pub max_idle_slots: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdPublisherLinkageArgs {
+    pub header:    CommandHeader,
+    pub publisher: Pubkey,
+    /// `Pubkey::default()` unlinks the publisher
+    pub entity:    Pubkey,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetFeatureArgs {
+    pub header:  CommandHeader,
+    /// Name of the feature padded with zeros, see `feature_name`
+    pub name:    [u8; 32],
+    /// 1 to enable the feature, 0 to disable it
+    pub enabled: u8,
+    pub unused_: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetFeatureGracePeriodArgs {
+    pub header:       CommandHeader,
+    pub grace_period: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdAccessControlArgs {
+    pub header:  CommandHeader,
+    pub reader:  Pubkey,
+    /// 1 to add the reader, 0 to remove it
+    pub allowed: u8,
+    pub unused_: [u8; 7],
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetLatencyStatsWindowArgs {
+    pub header: CommandHeader,
+    /// Number of aggregations of the window, at most `LATENCY_STATS_ENTRIES`, 0 stops tracking
+    pub window: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetPublisherWeightCapArgs {
+    pub header:                CommandHeader,
+    /// Number of quotes of an entity counted in an aggregate, 0 counts every quote
+    pub max_quotes_per_entity: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct SetQuoteOrderArgs {
+    pub header:  CommandHeader,
+    /// 0 stops maintaining the order, the aggregation then sorts the quotes
+    pub enabled: u64,
+}
+
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone)]
+pub struct UpdPublisherStatusArgs {
+    pub header:          CommandHeader,
+    /// `PC_STATUS_HALTED` or `PC_STATUS_AUCTION`
+    pub status:          u32,
+    pub unused_:         u32,
+    pub publishing_slot: u64,
+}
+
+/// Instruction data of the call of the aggregation into the sanity program of a feed, with the
+/// price account as its only account. The aggregate is only published if the sanity program
+/// returns `SANITY_CHECK_APPROVED`, otherwise its status is `PC_STATUS_UNKNOWN`. The sanity
+/// program must not fail, since that fails the price update that aggregated.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SanityCheckArgs {
+    pub price_account: Pubkey,
+    pub slot:          u64,
+    /// Candidate aggregate, in the exponent of the price account
+    pub price:         i64,
+    pub conf:          u64,
+    pub exponent:      i32,
+    /// Number of quotes the candidate aggregate is computed from
+    pub num_quoters:   u32,
+    /// Last aggregate with trading status before the candidate
+    pub prev_price:    i64,
+    pub prev_conf:     u64,
+    pub prev_slot:     u64,
+}
+
+/// Return data of a sanity program approving an aggregate, anything else vetoes it.
+pub const SANITY_CHECK_APPROVED: &[u8] = &[1];
+
+/// Change of the status of the aggregate of a feed, or veto of its aggregate by its sanity
+/// program. Aggregations log it with `sol_log_data` as `[STATUS_TRANSITION_LOG_TAG, transition]`
+/// and pass it as the instruction data of the call into the `StatusObserver` of the feed, with
+/// the price account as its only account. The observer runs in the middle of the aggregation,
+/// before the EMA is updated, and must not fail, since that fails the price update.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StatusTransition {
+    pub price_account: Pubkey,
+    pub slot:          u64,
+    /// Status of the aggregate before the aggregation
+    pub prev_status:   u32,
+    /// Status of the aggregate after the aggregation
+    pub status:        u32,
+    /// `StatusReasonCode` of the aggregation, `CircuitBreaker` if the aggregate was vetoed
+    pub reason:        u32,
+    pub exponent:      i32,
+    /// Aggregate after the aggregation, the last published one if it isn't trading
+    pub price:         i64,
+    pub conf:          u64,
+}
+
+/// First field of the `sol_log_data` logs of `StatusTransition`s.
+pub const STATUS_TRANSITION_LOG_TAG: &[u8] = b"StatusTransition";
+
+/// Demotion or reactivation of a publisher of a feed, see `PublisherActivity`. Aggregations log
+/// it with `sol_log_data` as `[PUBLISHER_ACTIVITY_LOG_TAG, change]`.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PublisherActivityChange {
+    pub price_account: Pubkey,
+    pub publisher:     Pubkey,
+    /// Slot of the aggregation window that changed the activity
+    pub slot:          u64,
+    /// Consecutive windows the publisher missed, 0 on a reactivation
+    pub missed:        u32,
+    /// 1 if the publisher was reactivated, 0 if it was demoted
+    pub active:        u8,
+    pub unused_:       [u8; 3],
+}
+
+/// First field of the `sol_log_data` logs of `PublisherActivityChange`s.
+pub const PUBLISHER_ACTIVITY_LOG_TAG: &[u8] = b"PublisherActivity";
+
+/// Instruction data of the call of `AddPublisher` into the staking program of a feed, with the
+/// stake escrow of the publisher as its only account. The escrow must be owned by the staking
+/// program, which returns the stake of the publisher as a little endian `u64`. Any other return
+/// data fails the instruction.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct StakeCheckArgs {
+    pub publisher:     Pubkey,
+    pub price_account: Pubkey,
+}
+
+/// Entry of the return data of `GetExtensions`, one per extension of the price account.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ExtensionEntry {
+    pub extension_type: u32,
+    /// Offset of the extension value from the start of the price account data
+    pub offset:         u32,
+    /// Length of the extension value in bytes
+    pub length:         u32,
+}
+
+/// Return data of `GetAggregationCounts`. Feeds that don't count aggregations return zeros,
+/// besides `slot`.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct AggregationCountsSummary {
+    /// Slot of the clock the window ends at
+    pub slot:             u64,
+    /// Number of slots of the window up to `slot`
+    pub window_slots:     u64,
+    /// Number of slots of the window with a trading aggregate
+    pub aggregated_slots: u64,
+}
+
+/// Return data of `GetPriceExtrema`. Feeds that don't track their extrema return zeros, besides
+/// `slot`.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PriceExtremaSummary {
+    /// Slot of the clock the window ends at
+    pub slot:         u64,
+    /// Number of slots of the window up to `slot`
+    pub window_slots: u64,
+    /// Lowest trading aggregate price of the window, in the exponent of the feed
+    pub min_price:    i64,
+    /// Highest trading aggregate price of the window, in the exponent of the feed
+    pub max_price:    i64,
+    /// 1 if the window holds a trading aggregate, the prices are 0 otherwise
+    pub traded:       u8,
+    pub unused_:      [u8; 7],
+}
+
+/// Return data of `GetPrice`, see `PriceAccount::get_latest_price` and
+/// `PriceAccount::get_ema_price`.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PriceSummary {
+    /// Latest trading aggregate price, in the exponent of the feed
+    pub price:        i64,
+    pub conf:         u64,
+    pub ema_price:    i64,
+    pub ema_conf:     u64,
+    /// Slot the latest price was aggregated in
+    pub publish_slot: u64,
+    /// Unix timestamp of the clock when the latest price was aggregated
+    pub publish_time: i64,
+    pub exponent:     i32,
+    pub unused_:      u32,
+}
+
+/// Return data of `SimulateAggregate`: the aggregate of the feed as the aggregation of its
+/// current quotes in `slot` would leave it. The sanity program isn't called and the quotes aren't
+/// sampled, since both depend on accounts that the simulation doesn't take.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SimulatedAggregate {
+    /// Slot of the clock the aggregation was simulated in
+    pub slot:    u64,
+    /// Aggregate price, in the exponent of the feed
+    pub price:   i64,
+    pub conf:    u64,
+    /// Status of the aggregate, e.g. `PC_STATUS_TRADING`
+    pub status:  u32,
+    /// Number of quotes the aggregate was computed from
+    pub num_qt:  u32,
+    /// `StatusReasonCode` of an aggregation that wouldn't publish a price
+    pub reason:  u32,
+    /// 1 if the aggregation would publish a new aggregate, the price and the confidence are the
+    /// current ones otherwise
+    pub updated: u8,
+    pub unused_: [u8; 3],
+}
+
+/// Entry of the return data of `GetComputeBudgets`, one per instruction of the program.
+#[repr(C)]
+#[derive(Zeroable, Pod, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ComputeBudgetEntry {
+    /// The `OracleCommand` of the instruction
+    pub command:       i32,
+    pub compute_units: u32,
+}
@@ -0,0 +1,547 @@
+//! Byte layout of the accounts of the program, see the `pyth-layout` binary.
+//!
+//! Clients parse the accounts by offset rather than through this crate, so the layout is
+//! published with every build of the program as a JSON artifact listing the size of every account
+//! struct, extension and nested record, and the offset and size of each of their fields. The
+//! layout of a version of the account header never changes: `test_layout` compares it to the
+//! artifact stored under `test_data/layout` for `PC_VERSION`.
+
+use {
+    crate::{
+        accounts::{
+            AccessControl,
+            AccessControlAccount,
+            AccountHeader,
+            AccumulatorEmitter,
+            AggregateCache,
+            AggregationConfigAccount,
+            AggregationCounts,
+            AggregationDelay,
+            AuditLogAccount,
+            AuditRecord,
+            AuxData,
+            BidAsk,
+            Checksum,
+            ComponentActivity,
+            ComponentAuxData,
+            ComponentSequence,
+            ComponentTime,
+            ConfFloor,
+            ConsistencyCheckAccount,
+            ConsistencyFlag,
+            ExtensionHeader,
+            FeatureGateAccount,
+            FeatureSwitch,
+            FeeVaultAccount,
+            FeedCreator,
+            FeedRegistryAccount,
+            LandingLatency,
+            LatencySample,
+            LatencyStats,
+            MappingAccount,
+            MappingAccountV2,
+            MappingEntry,
+            MultisigAccount,
+            PermissionAccount,
+            PermissionTimelock,
+            PriceAccount,
+            PriceComponent,
+            PriceCumulative,
+            PriceEma,
+            PriceExtrema,
+            PriceHistoryPointer,
+            PriceInfo,
+            PriceSuccessor,
+            ProductAccount,
+            PublishThreshold,
+            PublisherActivity,
+            PublisherCap,
+            PublisherLandingLatency,
+            PublisherLink,
+            PublisherLinkageAccount,
+            PublisherRateLimit,
+            PublisherRegistryAccount,
+            PublisherRewards,
+            PublisherSampling,
+            PublisherSequences,
+            PublisherStake,
+            PublisherTimestamps,
+            PublisherWeightCap,
+            PublishingSlotTolerance,
+            QuoteConversion,
+            QuoteOrder,
+            RateLimitEntry,
+            ReadStats,
+            RewardEntry,
+            RewardsLedgerAccount,
+            SanityProgram,
+            SharedSettings,
+            SlotWeightedTwap,
+            SnapshotAccount,
+            StakeEntry,
+            StatusObserver,
+            StatusPolicy,
+            StatusReason,
+            TwapBucket,
+            UpdateCount,
+            UpdateFee,
+            UpdaterProgram,
+        },
+        c_oracle_header::PC_VERSION,
+    },
+    bytemuck::Zeroable,
+    std::{
+        fmt::Write,
+        mem::{
+            size_of,
+            size_of_val,
+        },
+    },
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldLayout {
+    pub name:   &'static str,
+    pub offset: usize,
+    pub size:   usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StructLayout {
+    pub name:   &'static str,
+    pub size:   usize,
+    /// In the order of their offsets
+    pub fields: Vec<FieldLayout>,
+}
+
+/// Layout of `$struct` with the offset and size of each of `$fields`, measured on a zeroed
+/// instance so that it follows the compiler rather than a copy of the definition.
+macro_rules! struct_layout {
+    ($struct:ident { $($field:ident),* $(,)? }) => {{
+        let value = $struct::zeroed();
+        let base = &value as *const $struct as usize;
+        StructLayout {
+            name:   stringify!($struct),
+            size:   size_of::<$struct>(),
+            fields: vec![$(FieldLayout {
+                name:   stringify!($field),
+                offset: &value.$field as *const _ as usize - base,
+                size:   size_of_val(&value.$field),
+            }),*],
+        }
+    }};
+}
+
+/// Layout of every account struct, then of the records nested in them, then of the extensions of
+/// price accounts.
+pub fn struct_layouts() -> Vec<StructLayout> {
+    vec![
+        struct_layout!(AccountHeader {
+            magic_number,
+            version,
+            account_type,
+            size,
+        }),
+        struct_layout!(MappingAccount {
+            header,
+            number_of_products,
+            unused_,
+            next_mapping_account,
+            products_list,
+        }),
+        struct_layout!(MappingAccountV2 {
+            header,
+            capacity,
+            number_of_products,
+        }),
+        struct_layout!(MappingEntry {
+            symbol_hash,
+            product,
+        }),
+        struct_layout!(ProductAccount {
+            header,
+            first_price_account,
+        }),
+        struct_layout!(PriceAccount {
+            header,
+            price_type,
+            exponent,
+            num_,
+            num_qt_,
+            last_slot_,
+            valid_slot_,
+            twap_,
+            twac_,
+            timestamp_,
+            min_pub_,
+            message_sent_,
+            max_latency_,
+            flags,
+            feed_index,
+            product_account,
+            next_price_account,
+            prev_slot_,
+            prev_price_,
+            prev_conf_,
+            prev_timestamp_,
+            agg_,
+            comp_,
+            price_cumulative,
+        }),
+        struct_layout!(PermissionAccount {
+            header,
+            master_authority,
+            data_curation_authority,
+            security_authority,
+        }),
+        struct_layout!(SnapshotAccount {
+            header,
+            price_account,
+            slot,
+        }),
+        struct_layout!(FeedRegistryAccount { header }),
+        struct_layout!(PublisherRegistryAccount {
+            header,
+            num_publishers,
+            unused_,
+        }),
+        struct_layout!(MultisigAccount {
+            header,
+            threshold,
+            num_signers,
+            unused_,
+            signers,
+        }),
+        struct_layout!(PublisherLinkageAccount {
+            header,
+            num_links,
+            unused_,
+        }),
+        struct_layout!(FeatureGateAccount {
+            header,
+            num_features,
+            unused_,
+            grace_period,
+        }),
+        struct_layout!(AccessControlAccount {
+            header,
+            num_readers,
+            unused_,
+            readers,
+        }),
+        struct_layout!(AuditLogAccount {
+            header,
+            num_records,
+            records,
+        }),
+        struct_layout!(FeeVaultAccount {
+            header,
+            total_collected,
+            total_withdrawn,
+        }),
+        struct_layout!(AggregationConfigAccount {
+            header,
+            max_ci_divisor,
+            max_latency,
+            min_pub,
+            unused_,
+        }),
+        struct_layout!(RewardsLedgerAccount {
+            header,
+            publisher,
+            total_score,
+            num_claims,
+        }),
+        struct_layout!(ConsistencyCheckAccount {
+            header,
+            kind,
+            tolerance_bps,
+            feeds,
+            last_check_slot,
+            last_deviation_bps,
+            num_violations,
+        }),
+        struct_layout!(PriceInfo {
+            price_,
+            conf_,
+            status_,
+            corp_act_status_,
+            pub_slot_,
+        }),
+        struct_layout!(PriceEma {
+            val_,
+            numer_,
+            denom_,
+        }),
+        struct_layout!(PriceComponent {
+            pub_,
+            agg_,
+            latest_,
+        }),
+        struct_layout!(PriceCumulative {
+            price,
+            conf,
+            num_down_slots,
+            unused,
+        }),
+        struct_layout!(PermissionTimelock {
+            delay,
+            effective_slot,
+            pending_master_authority,
+            pending_data_curation_authority,
+            pending_security_authority,
+            pending_delay,
+        }),
+        struct_layout!(AccumulatorEmitter { program_id }),
+        struct_layout!(PublisherLink { publisher, entity }),
+        struct_layout!(FeatureSwitch {
+            name,
+            effective_slot,
+            enabled,
+            pending_enabled,
+            unused_,
+        }),
+        struct_layout!(AuditRecord {
+            slot,
+            command,
+            unused_,
+            actor,
+            target,
+        }),
+        struct_layout!(RewardEntry {
+            publisher,
+            unclaimed,
+            slot,
+            slot_score,
+        }),
+        struct_layout!(ExtensionHeader {
+            extension_type,
+            length,
+        }),
+        struct_layout!(PriceHistoryPointer { history_account }),
+        struct_layout!(BidAsk {
+            bid_price,
+            ask_price,
+        }),
+        struct_layout!(LandingLatency {
+            publisher,
+            latest,
+            ema,
+        }),
+        struct_layout!(PublisherLandingLatency { components }),
+        struct_layout!(ReadStats {
+            epoch,
+            reads,
+            previous_epoch_reads,
+        }),
+        struct_layout!(RateLimitEntry {
+            publisher,
+            last_update_slot,
+        }),
+        struct_layout!(PublisherRateLimit { window, components }),
+        struct_layout!(AggregationDelay { slots }),
+        struct_layout!(PublishingSlotTolerance { slots }),
+        struct_layout!(AggregationCounts {
+            bucket_slots,
+            last_slot,
+            counts,
+        }),
+        struct_layout!(SanityProgram { program_id }),
+        struct_layout!(StatusObserver { program_id }),
+        struct_layout!(Checksum {
+            enabled,
+            unused_,
+            hash,
+        }),
+        struct_layout!(PublisherWeightCap {
+            max_quotes_per_entity,
+            components,
+        }),
+        struct_layout!(QuoteOrder {
+            enabled,
+            unused_,
+            len,
+            lists,
+        }),
+        struct_layout!(PriceSuccessor { successor }),
+        struct_layout!(AccessControl { access_control }),
+        struct_layout!(LatencySample {
+            num_updates,
+            total_latency,
+            max_latency,
+        }),
+        struct_layout!(LatencyStats {
+            window,
+            num_aggregations,
+            mean_latency,
+            max_latency,
+            pending,
+            samples,
+        }),
+        struct_layout!(PriceExtrema {
+            bucket_slots,
+            last_slot,
+            min_prices,
+            max_prices,
+        }),
+        struct_layout!(ConfFloor {
+            min_conf,
+            min_conf_bps,
+        }),
+        struct_layout!(StatusReason {
+            code,
+            enabled,
+            unused_,
+            slot,
+        }),
+        struct_layout!(StakeEntry { publisher, amount }),
+        struct_layout!(PublisherStake {
+            staking_program,
+            min_stake,
+            components,
+        }),
+        struct_layout!(FeedCreator { creator }),
+        struct_layout!(PublisherCap {
+            max_publishers,
+            unused_,
+        }),
+        struct_layout!(QuoteConversion {
+            conversion_feed,
+            exponent,
+            divide,
+            unused_,
+            converted,
+        }),
+        struct_layout!(AggregateCache { enabled, unused_ }),
+        struct_layout!(PublisherSampling {
+            sample_size,
+            unused_,
+        }),
+        struct_layout!(UpdateFee { lamports }),
+        struct_layout!(PublisherRewards {
+            enabled,
+            unused_,
+            entries,
+        }),
+        struct_layout!(ConsistencyFlag { check, slot }),
+        struct_layout!(UpdateCount {
+            enabled,
+            unused_,
+            updates,
+        }),
+        struct_layout!(TwapBucket {
+            price_sum,
+            conf_sum,
+            slots,
+        }),
+        struct_layout!(SlotWeightedTwap {
+            mode,
+            unused_,
+            bucket_slots,
+            last_slot,
+            last_price,
+            last_conf,
+            price,
+            conf,
+            buckets,
+        }),
+        struct_layout!(ComponentTime {
+            publisher,
+            slot,
+            time,
+        }),
+        struct_layout!(PublisherTimestamps {
+            enabled,
+            unused_,
+            components,
+        }),
+        struct_layout!(PublishThreshold {
+            deviation_bps,
+            heartbeat_slots,
+        }),
+        struct_layout!(ComponentSequence {
+            publisher,
+            sequence,
+        }),
+        struct_layout!(PublisherSequences {
+            enabled,
+            unused_,
+            components,
+        }),
+        struct_layout!(StatusPolicy {
+            halted_quorum,
+            auction_quorum,
+            override_trading,
+            unused_,
+        }),
+        struct_layout!(ComponentAuxData {
+            publisher,
+            slot,
+            open_interest,
+            volume_24h,
+        }),
+        struct_layout!(AuxData {
+            enabled,
+            unused_,
+            num_submissions,
+            slot,
+            open_interest,
+            volume_24h,
+            components,
+        }),
+        struct_layout!(ComponentActivity {
+            publisher,
+            missed,
+            inactive,
+            unused_,
+        }),
+        struct_layout!(PublisherActivity {
+            max_missed,
+            unused_,
+            slot,
+            components,
+        }),
+        struct_layout!(UpdaterProgram { program_id }),
+        struct_layout!(SharedSettings { enabled, unused_ }),
+    ]
+}
+
+/// The layouts of `struct_layouts` as the JSON artifact, one field per line so that the changes
+/// of the artifact read as a diff of the fields.
+pub fn layout_json() -> String {
+    let mut json = String::new();
+    let structs = struct_layouts();
+    // Writing to a `String` never fails
+    let _ = writeln!(json, "{{");
+    let _ = writeln!(json, "  \"version\": {PC_VERSION},");
+    let _ = writeln!(json, "  \"structs\": [");
+    for (i, layout) in structs.iter().enumerate() {
+        let _ = writeln!(json, "    {{");
+        let _ = writeln!(json, "      \"name\": \"{}\",", layout.name);
+        let _ = writeln!(json, "      \"size\": {},", layout.size);
+        let _ = writeln!(json, "      \"fields\": [");
+        for (j, field) in layout.fields.iter().enumerate() {
+            let _ = writeln!(
+                json,
+                "        {{ \"name\": \"{}\", \"offset\": {}, \"size\": {} }}{}",
+                field.name,
+                field.offset,
+                field.size,
+                separator(j, layout.fields.len())
+            );
+        }
+        let _ = writeln!(json, "      ]");
+        let _ = writeln!(json, "    }}{}", separator(i, structs.len()));
+    }
+    let _ = writeln!(json, "  ]");
+    let _ = writeln!(json, "}}");
+    json
+}
+
+fn separator(index: usize, len: usize) -> &'static str {
+    if index + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
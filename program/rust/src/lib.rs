@@ -1,18 +1,42 @@
 // Allow non upper case globals from C
 #![allow(non_upper_case_globals)]
 
+// The `wasm` feature leaves out the processor, which the off-chain tooling of `library` runs
+#[cfg(all(feature = "wasm", feature = "library"))]
+compile_error!("The `wasm` and `library` features can't be enabled together");
+
 mod accounts;
 mod c_oracle_header;
 mod deserialize;
 mod error;
+#[cfg(any(feature = "library", feature = "wasm"))]
+pub mod instruction;
+#[cfg(not(any(feature = "library", feature = "wasm")))]
 mod instruction;
+#[cfg(not(feature = "wasm"))]
 mod processor;
 mod utils;
 
+#[cfg(any(test, feature = "library"))]
+pub mod client;
+#[cfg(any(test, feature = "library"))]
+pub mod config_diff;
+#[cfg(any(test, feature = "library"))]
+pub mod genesis;
+#[cfg(any(test, feature = "library"))]
+pub mod inspect;
+#[cfg(any(test, feature = "library"))]
+pub mod layout;
+#[cfg(any(test, feature = "library"))]
+pub mod listing_cost;
+#[cfg(any(test, feature = "library"))]
+pub mod migration;
+#[cfg(any(test, feature = "test-vectors"))]
+pub mod test_vectors;
 #[cfg(any(test, feature = "library"))]
 pub mod validator;
 
-#[cfg(feature = "library")]
+#[cfg(any(feature = "library", feature = "wasm"))]
 pub use solana_program;
 
 #[cfg(test)]
@@ -31,25 +55,176 @@ mod log;
 pub use crate::error::OracleError;
 #[cfg(feature = "strum")]
 pub use accounts::MessageType;
-#[cfg(feature = "library")]
+#[cfg(any(feature = "library", feature = "wasm"))]
 pub use accounts::{
+    check_is_upgrade_authority_for_program,
+    check_is_upgradeable_program,
+    data_len_with_extension,
+    get_access_control,
+    get_component_publish_time,
+    get_extension,
+    get_feed_creator,
+    get_successor,
+    init_extension,
+    iter_extensions,
+    load_programdata_for_program,
+    set_extension,
+    verify_checksum,
+    AccessControl,
+    AccessControlAccount,
     AccountHeader,
+    AccumulatorEmitter,
+    AggregateCache,
+    AggregationConfigAccount,
+    AggregationCounts,
+    AggregationDelay,
+    AuditLogAccount,
+    AuditRecord,
+    AuxData,
+    AverageMode,
+    BidAsk,
+    Checksum,
+    ComponentActivity,
+    ComponentAuxData,
+    ComponentSequence,
+    ComponentTime,
+    ConfFloor,
+    ConsistencyCheckAccount,
+    ConsistencyFlag,
+    ConsistencyKind,
+    ExtensionHeader,
+    ExtensionInfo,
+    ExtensionType,
+    Feature,
+    FeatureGateAccount,
+    FeatureSwitch,
+    FeeVaultAccount,
+    FeedCreator,
+    FeedKind,
+    FeedRegistryAccount,
+    FeedTombstoneMessage,
+    LandingLatency,
+    LatencySample,
+    LatencyStats,
+    LatestPrice,
     MappingAccount,
+    MappingAccountV2,
+    MappingEntry,
+    MultisigAccount,
     PermissionAccount,
+    PermissionTimelock,
     PriceAccount,
+    PriceAccountExtension,
     PriceAccountFlags,
     PriceComponent,
     PriceEma,
+    PriceExtrema,
+    PriceHistoryPointer,
     PriceInfo,
+    PriceSuccessor,
     ProductAccount,
+    ProductMetadataMessage,
+    ProgramdataAccount,
+    PublishThreshold,
+    PublisherActivity,
+    PublisherCap,
+    PublisherLandingLatency,
+    PublisherLink,
+    PublisherLinkageAccount,
+    PublisherPrice,
+    PublisherPricesMessage,
+    PublisherRateLimit,
+    PublisherRegistryAccount,
+    PublisherRewards,
+    PublisherSampling,
+    PublisherSequences,
+    PublisherStake,
+    PublisherTimestamps,
+    PublisherWeightCap,
+    PublishingSlotTolerance,
     PythAccount,
     PythOracleSerialize,
+    QuoteConversion,
+    QuoteOrder,
+    RateLimitEntry,
+    ReadStats,
+    RewardEntry,
+    RewardsLedgerAccount,
+    SanityProgram,
+    SharedSettings,
+    SlotWeightedTwap,
+    StakeEntry,
+    StatusObserver,
+    StatusPolicy,
+    StatusReason,
+    StatusReasonCode,
+    TwapBucket,
+    UpdateCount,
+    UpdateFee,
+    UpdaterProgram,
+    AGGREGATION_CONFIG_SEED,
+    AGGREGATION_COUNT_BUCKETS,
+    AUDIT_LOG_CAPACITY,
+    AUDIT_LOG_SEED,
+    COMMUNITY_SEED,
+    EXTENSIONS_OFFSET,
+    EXTENSIONS_RESERVED_SPACE,
+    FEATURE_GATE_SEED,
+    FEED_REGISTRY_SEED,
+    FEE_VAULT_SEED,
+    LATENCY_STATS_ENTRIES,
+    LATENCY_STATS_SCALE,
+    MAX_ACCESS_CONTROL_READERS,
+    MAX_CONF_FLOOR_BPS,
+    MAX_CONSISTENCY_FEEDS,
+    MAX_MULTISIG_SIGNERS,
+    PERMISSIONS_SEED,
+    PRICE_EXTREMA_BUCKETS,
+    PRICE_SEED,
+    PRODUCT_SEED,
+    PUBLISHER_LINKAGE_SEED,
+    PUBLISHER_REGISTRY_SEED,
+    REWARDS_LEDGER_SEED,
+    REWARD_SCORE_SCALE,
+    SLOT_WEIGHTED_TWAP_BUCKETS,
+};
+#[cfg(any(feature = "library", feature = "no-entrypoint"))]
+pub use accounts::{
+    find_price_pda,
+    find_product_by_symbol,
+    find_product_pda,
+    get_product_metadata,
+    get_product_metadata_entries,
+    iter_products,
+    symbol_hash,
+    ProductIter,
 };
 #[cfg(feature = "library")]
 pub use {
-    processor::find_publisher_index,
+    instruction::{
+        AggregationCountsSummary,
+        ComputeBudgetEntry,
+        PriceExtremaSummary,
+        PriceSummary,
+        PublisherActivityChange,
+        SanityCheckArgs,
+        SimulatedAggregate,
+        StakeCheckArgs,
+        StatusTransition,
+        PUBLISHER_ACTIVITY_LOG_TAG,
+        SANITY_CHECK_APPROVED,
+        STATUS_TRANSITION_LOG_TAG,
+    },
+    processor::{
+        find_publisher_index,
+        get_aggregation_counts_summary,
+        get_compute_budget_entries,
+        get_price_extrema_summary,
+        get_price_summary,
+    },
     utils::get_status_for_conf_price_ratio,
 };
+#[cfg(not(feature = "no-entrypoint"))]
 use {
     processor::process_instruction,
     solana_program::entrypoint,
@@ -66,4 +241,9 @@ use {
 // We also generate bindings for the constants in oracle.h (as well as other things
 // included in bindings.h).
 
+// The `wasm` feature leaves out the processor, the only caller of the C code, and skips building
+// the archives. The account types and the instructions then compile to wasm32-unknown-unknown,
+// e.g. for browser dashboards decoding price accounts.
+
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
@@ -0,0 +1,107 @@
+//! Account sizes and rent of new price feeds, so that listing tooling can quote the cost of a
+//! listing and fund the payer before submitting its transactions.
+//!
+//! Listing a feed creates its product account and its price account. Instructions that enable an
+//! extension grow the price account in place and require it to already hold enough lamports to be
+//! rent exempt at its new size, so the price account should be funded for all of its extensions
+//! when it is created. Every `SnapshotPriceAccount` creates a snapshot account holding a copy of
+//! the price account data, extensions included, funded by the caller.
+
+use {
+    crate::{
+        accounts::{
+            ExtensionType,
+            PriceAccount,
+            ProductAccount,
+            PythAccount,
+            SnapshotAccount,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        error::OracleError,
+    },
+    solana_program::rent::Rent,
+    std::mem::size_of,
+};
+
+/// Configuration of a feed to be listed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeedListing {
+    /// Number of publishers the feed should accept, at most `PC_NUM_COMP`. The price account and
+    /// the per-publisher extensions have room for `PC_NUM_COMP` publishers whatever this number,
+    /// so it doesn't change the sizes.
+    pub publisher_capacity: u32,
+    /// Extensions enabled on the price account, in any order. Duplicates are counted once.
+    pub extensions:         Vec<ExtensionType>,
+    /// Number of snapshots of the price account that will be taken
+    pub history_depth:      u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListingAccountKind {
+    Product,
+    Price,
+    Snapshot,
+}
+
+/// An account created for the listing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccountCost {
+    pub kind:     ListingAccountKind,
+    /// Account data length in bytes
+    pub size:     usize,
+    /// Rent exempt balance of the account
+    pub lamports: u64,
+}
+
+/// Accounts created for a listing: the product account, then the price account, then
+/// `history_depth` snapshot accounts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListingCost {
+    pub accounts: Vec<AccountCost>,
+}
+
+impl ListingCost {
+    pub fn total_size(&self) -> usize {
+        self.accounts.iter().map(|account| account.size).sum()
+    }
+
+    pub fn total_lamports(&self) -> u64 {
+        self.accounts.iter().map(|account| account.lamports).sum()
+    }
+}
+
+/// Sizes and rent exempt balances of the accounts created to list `listing`, under `rent`.
+pub fn get_listing_cost(listing: &FeedListing, rent: &Rent) -> Result<ListingCost, OracleError> {
+    if listing.publisher_capacity > PC_NUM_COMP {
+        return Err(OracleError::MaxPublishersReached);
+    }
+
+    let mut extensions = listing.extensions.clone();
+    extensions.sort_by_key(|extension_type| *extension_type as u32);
+    extensions.dedup();
+
+    let mut price_account_size = size_of::<PriceAccount>();
+    for extension_type in extensions {
+        price_account_size += extension_type
+            .space()
+            .ok_or(OracleError::InvalidExtension)?;
+    }
+
+    let account_cost = |kind, size| AccountCost {
+        kind,
+        size,
+        lamports: rent.minimum_balance(size),
+    };
+    let mut accounts = vec![
+        account_cost(ListingAccountKind::Product, ProductAccount::MINIMUM_SIZE),
+        account_cost(ListingAccountKind::Price, price_account_size),
+    ];
+    for _ in 0..listing.history_depth {
+        accounts.push(account_cost(
+            ListingAccountKind::Snapshot,
+            SnapshotAccount::MINIMUM_SIZE + price_account_size,
+        ));
+    }
+
+    Ok(ListingCost { accounts })
+}
@@ -0,0 +1,275 @@
+//! Planning of bulk migrations of price accounts, see the `pyth-migrate` binary.
+//!
+//! Every migration is applied to a price account by an instruction signed by the master
+//! authority. Whether a price account still needs a migration is read from its data, so a
+//! rollout that got interrupted is resumed by scanning the accounts again.
+
+use {
+    crate::{
+        accounts::{
+            data_len_with_extension,
+            get_extension,
+            AccountHeader,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+            StatusReason,
+            UpdateCount,
+            PERMISSIONS_SEED,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_VERSION,
+        },
+        instruction::{
+            AddPublisherArgs,
+            CommandHeader,
+            OracleCommand,
+            SetStatusReasonTrackingArgs,
+            SetUpdateCountingArgs,
+        },
+        processor::{
+            ALLOW_BUNDLED_UPDATES,
+            ALLOW_ZERO_CI,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+            FORBID_ZERO_CI,
+            REQUIRE_ISOLATED_UPDATES,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        pod_read_unaligned,
+    },
+    solana_program::{
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        pubkey::Pubkey,
+        rent::Rent,
+        system_instruction,
+    },
+    std::{
+        mem::size_of,
+        str::FromStr,
+    },
+};
+
+/// Migrations that can be rolled out to every price account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Migration {
+    EnableAccumulatorV2,
+    DisableAccumulatorV2,
+    AllowZeroCi,
+    ForbidZeroCi,
+    RequireIsolatedUpdates,
+    AllowBundledUpdates,
+    /// Adds the `UpdateCount` extension
+    EnableUpdateCounting,
+    /// Adds the `StatusReason` extension
+    EnableStatusReasonTracking,
+    /// Grows the price accounts to `PriceAccount::NEW_ACCOUNT_SPACE`
+    ReserveExtensionSpace,
+}
+
+impl FromStr for Migration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "enable-accumulator-v2" => Ok(Migration::EnableAccumulatorV2),
+            "disable-accumulator-v2" => Ok(Migration::DisableAccumulatorV2),
+            "allow-zero-ci" => Ok(Migration::AllowZeroCi),
+            "forbid-zero-ci" => Ok(Migration::ForbidZeroCi),
+            "require-isolated-updates" => Ok(Migration::RequireIsolatedUpdates),
+            "allow-bundled-updates" => Ok(Migration::AllowBundledUpdates),
+            "enable-update-counting" => Ok(Migration::EnableUpdateCounting),
+            "enable-status-reason-tracking" => Ok(Migration::EnableStatusReasonTracking),
+            "reserve-extension-space" => Ok(Migration::ReserveExtensionSpace),
+            _ => Err(format!("Unknown migration: {s}")),
+        }
+    }
+}
+
+impl Migration {
+    /// Whether the price account stored in `data` doesn't have this migration applied yet.
+    /// Accounts whose extension region can't be read are left out, the program would reject the
+    /// migration.
+    pub fn is_needed(&self, data: &[u8]) -> bool {
+        let flags = match read_price_account(data) {
+            Some(price_data) => price_data.flags,
+            None => return false,
+        };
+        match self {
+            Migration::EnableAccumulatorV2 => !flags.contains(PriceAccountFlags::ACCUMULATOR_V2),
+            Migration::DisableAccumulatorV2 => flags.contains(PriceAccountFlags::ACCUMULATOR_V2),
+            Migration::AllowZeroCi => !flags.contains(PriceAccountFlags::ALLOW_ZERO_CI),
+            Migration::ForbidZeroCi => flags.contains(PriceAccountFlags::ALLOW_ZERO_CI),
+            Migration::RequireIsolatedUpdates => {
+                !flags.contains(PriceAccountFlags::ISOLATED_UPDATES)
+            }
+            Migration::AllowBundledUpdates => flags.contains(PriceAccountFlags::ISOLATED_UPDATES),
+            Migration::EnableUpdateCounting => match get_extension::<UpdateCount>(data) {
+                Ok(Some(count)) => count.enabled == 0,
+                Ok(None) => true,
+                Err(_) => false,
+            },
+            Migration::EnableStatusReasonTracking => match get_extension::<StatusReason>(data) {
+                Ok(Some(reason)) => reason.enabled == 0,
+                Ok(None) => true,
+                Err(_) => false,
+            },
+            Migration::ReserveExtensionSpace => data.len() < PriceAccount::NEW_ACCOUNT_SPACE,
+        }
+    }
+
+    /// Length of the price account stored in `data` once this migration is applied.
+    pub fn data_len_after(&self, data: &[u8]) -> usize {
+        let new_len = match self {
+            Migration::EnableUpdateCounting => data_len_with_extension::<UpdateCount>(data).ok(),
+            Migration::EnableStatusReasonTracking => {
+                data_len_with_extension::<StatusReason>(data).ok()
+            }
+            Migration::ReserveExtensionSpace => Some(PriceAccount::NEW_ACCOUNT_SPACE),
+            _ => None,
+        };
+        new_len.map_or(data.len(), |new_len| new_len.max(data.len()))
+    }
+
+    /// Instructions applying this migration to `price_account`, which holds `data`. The
+    /// migrations growing the account start with a transfer of the rent of the new space from
+    /// `authority`, assuming the account is rent exempt at its current size.
+    pub fn instructions(
+        &self,
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        price_account: &Pubkey,
+        data: &[u8],
+    ) -> Vec<Instruction> {
+        let mut instructions = vec![];
+        let new_len = self.data_len_after(data);
+        if new_len > data.len() {
+            // The clusters the program is deployed to use the default rent
+            let rent = Rent::default();
+            instructions.push(system_instruction::transfer(
+                authority,
+                price_account,
+                rent.minimum_balance(new_len) - rent.minimum_balance(data.len()),
+            ));
+        }
+        instructions.push(self.instruction(program_id, authority, price_account));
+        instructions
+    }
+
+    /// Instruction of the program applying this migration to `price_account`. Price account
+    /// flags are set by adding a magic publisher, see `add_publisher`. Extensions are added by the
+    /// instruction configuring them, which grows the account.
+    pub fn instruction(
+        &self,
+        program_id: &Pubkey,
+        authority: &Pubkey,
+        price_account: &Pubkey,
+    ) -> Instruction {
+        let header = |command: OracleCommand| CommandHeader {
+            version: PC_VERSION,
+            command: command as i32,
+        };
+        let add_publisher = |publisher: [u8; 32]| {
+            bytes_of(&AddPublisherArgs {
+                header:    header(OracleCommand::AddPublisher),
+                publisher: Pubkey::from(publisher),
+            })
+            .to_vec()
+        };
+        let data = match self {
+            Migration::EnableAccumulatorV2 => add_publisher(ENABLE_ACCUMULATOR_V2),
+            Migration::DisableAccumulatorV2 => add_publisher(DISABLE_ACCUMULATOR_V2),
+            Migration::AllowZeroCi => add_publisher(ALLOW_ZERO_CI),
+            Migration::ForbidZeroCi => add_publisher(FORBID_ZERO_CI),
+            Migration::RequireIsolatedUpdates => add_publisher(REQUIRE_ISOLATED_UPDATES),
+            Migration::AllowBundledUpdates => add_publisher(ALLOW_BUNDLED_UPDATES),
+            Migration::EnableUpdateCounting => bytes_of(&SetUpdateCountingArgs {
+                header:  header(OracleCommand::SetUpdateCounting),
+                enabled: 1,
+            })
+            .to_vec(),
+            Migration::EnableStatusReasonTracking => bytes_of(&SetStatusReasonTrackingArgs {
+                header:  header(OracleCommand::SetStatusReasonTracking),
+                enabled: 1,
+            })
+            .to_vec(),
+            Migration::ReserveExtensionSpace => {
+                bytes_of(&header(OracleCommand::ReserveExtensionSpace)).to_vec()
+            }
+        };
+        let (permissions_account, _bump) =
+            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+
+        Instruction {
+            program_id: *program_id,
+            accounts: vec![
+                AccountMeta::new(*authority, true),
+                AccountMeta::new(*price_account, false),
+                AccountMeta::new_readonly(permissions_account, false),
+            ],
+            data,
+        }
+    }
+}
+
+/// Read the price account stored in `data`, returning `None` if `data` isn't a price account.
+/// Account data fetched off-chain isn't necessarily aligned, so the account is copied.
+pub fn read_price_account(data: &[u8]) -> Option<PriceAccount> {
+    if data.len() < size_of::<PriceAccount>() {
+        return None;
+    }
+
+    let header: AccountHeader = pod_read_unaligned(&data[..size_of::<AccountHeader>()]);
+    if header.magic_number != PC_MAGIC || header.account_type != PriceAccount::ACCOUNT_TYPE {
+        return None;
+    }
+
+    Some(pod_read_unaligned(&data[..size_of::<PriceAccount>()]))
+}
+
+/// Keys of the price accounts in `accounts` that need `migration`, sorted so that the plan is
+/// the same across runs. Accounts that aren't price accounts are ignored.
+pub fn find_accounts_to_migrate<'a>(
+    migration: Migration,
+    accounts: impl IntoIterator<Item = (Pubkey, &'a [u8])>,
+) -> Vec<Pubkey> {
+    let mut to_migrate: Vec<Pubkey> = accounts
+        .into_iter()
+        .filter(|(_, data)| migration.is_needed(data))
+        .map(|(key, _)| key)
+        .collect();
+    to_migrate.sort();
+    to_migrate
+}
+
+/// Keys of the price accounts in `accounts` that have flags this version of the crate doesn't
+/// know about, with those flags, sorted by key. The flags were set by a newer version of the
+/// program. Migrations keep them, but the tooling can't tell what they mean, so the accounts are
+/// worth a look before rolling a migration out.
+pub fn find_accounts_with_unknown_flags<'a>(
+    accounts: impl IntoIterator<Item = (Pubkey, &'a [u8])>,
+) -> Vec<(Pubkey, u8)> {
+    let mut with_unknown_flags: Vec<(Pubkey, u8)> = accounts
+        .into_iter()
+        .filter_map(|(key, data)| {
+            let unknown_bits = read_price_account(data)?.flags.unknown_bits();
+            (unknown_bits != 0).then_some((key, unknown_bits))
+        })
+        .collect();
+    with_unknown_flags.sort();
+    with_unknown_flags
+}
+
+/// Split `to_migrate` into batches of at most `batch_size` accounts.
+pub fn plan_batches(to_migrate: &[Pubkey], batch_size: usize) -> Vec<Vec<Pubkey>> {
+    to_migrate
+        .chunks(batch_size.max(1))
+        .map(|batch| batch.to_vec())
+        .collect()
+}
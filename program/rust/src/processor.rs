@@ -1,77 +1,279 @@
 use {
     crate::{
         accounts::{
+            update_checksum,
+            verify_checksum,
             AccountHeader,
+            AuditLogAccount,
+            AuditRecord,
+            FeedRegistryAccount,
+            MultisigAccount,
             PermissionAccount,
             PythAccount,
+            FEED_REGISTRY_SEED,
             MAX_FEED_INDEX,
+            UPD_PRICE_WRITE_SEED,
+        },
+        deserialize::{
+            load,
+            load_account_as_mut,
+            load_checked,
         },
-        deserialize::load_account_as_mut,
         error::OracleError,
         instruction::{
             load_command_header_checked,
+            CommandHeader,
             OracleCommand,
         },
         utils::{
+            get_rent,
             pyth_assert,
+            send_lamports,
             try_convert,
+            validate_accounts,
+            AccountCheck,
         },
     },
     solana_program::{
-        entrypoint::ProgramResult,
+        entrypoint::{
+            ProgramResult,
+            MAX_PERMITTED_DATA_INCREASE,
+        },
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        program::invoke_signed,
+        program_error::ProgramError,
         pubkey::Pubkey,
         sysvar::slot_history::AccountInfo,
     },
+    std::mem::size_of,
 };
 
+mod add_community_price;
+mod add_community_product;
 mod add_price;
 mod add_product;
 mod add_publisher;
+mod apply_permissions;
+mod assert_price_condition;
+mod cancel_permissions;
+mod check_consistency;
+mod claim_rewards;
+mod clone_price;
+mod convert_price;
 mod del_price;
 mod del_product;
 mod del_publisher;
+mod deprecate_price;
+mod enable_read_stats;
+mod get_aggregation_counts;
+mod get_compute_budgets;
+mod get_extensions;
+mod get_price;
+mod get_price_extrema;
+mod init_audit_log;
+mod init_consistency_check;
 mod init_mapping;
+mod init_mapping_v2;
+mod init_multisig;
 mod init_price;
+mod init_price_pda;
+mod init_product_pda;
+mod migrate_mapping;
+mod prune_publishers;
+mod reserve_extension_space;
+mod reset_ema;
+mod resize_feed_registry;
 mod resize_mapping;
+mod rotate_publisher;
+mod set_access_control;
+mod set_accumulator_emitter;
+mod set_aggregate_cache;
+mod set_aggregation_config;
+mod set_aggregation_count_window;
+mod set_aggregation_delay;
+mod set_aux_data;
+mod set_average_mode;
+mod set_checksum;
+mod set_conf_floor;
+mod set_exponent;
+mod set_feature;
+mod set_feature_grace_period;
+mod set_latency_stats_window;
 mod set_max_latency;
 mod set_min_pub;
+mod set_permissions_timelock;
+mod set_price_extrema_window;
+mod set_publish_threshold;
+mod set_publisher_cap;
+mod set_publisher_demotion;
+mod set_publisher_rewards;
+mod set_publisher_sampling;
+mod set_publisher_timestamps;
+mod set_publisher_weight_cap;
+mod set_quote_conversion;
+mod set_quote_order;
+mod set_rate_limit;
+mod set_sanity_program;
+mod set_sequence_tracking;
+mod set_shared_settings;
+mod set_stake_program;
+mod set_status_observer;
+mod set_status_policy;
+mod set_status_reason_tracking;
+mod set_update_counting;
+mod set_update_fee;
+mod set_updater_program;
+mod simulate_aggregate;
+mod snapshot_price_account;
+mod upd_access_control;
+mod upd_aux_data;
 mod upd_permissions;
 mod upd_price;
+mod upd_price_compact;
 mod upd_product;
+mod upd_publisher_linkage;
+mod upd_publisher_registry;
+mod upd_publisher_status;
+mod withdraw_fees;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "library"))]
 pub use add_publisher::{
+    ALLOW_BUNDLED_UPDATES,
     ALLOW_ZERO_CI,
     DISABLE_ACCUMULATOR_V2,
     ENABLE_ACCUMULATOR_V2,
     FORBID_ZERO_CI,
+    REQUIRE_ISOLATED_UPDATES,
 };
 use solana_program::{
+    clock::Clock,
     program_error::ProgramError,
     rent::Rent,
     sysvar::Sysvar,
 };
 pub use {
+    add_community_price::add_community_price,
+    add_community_product::add_community_product,
     add_price::add_price,
     add_product::add_product,
-    add_publisher::add_publisher,
+    add_publisher::{
+        add_publisher,
+        check_publisher_approved,
+        sort_price_comps,
+    },
+    apply_permissions::apply_permissions,
+    assert_price_condition::assert_price_condition,
+    cancel_permissions::cancel_permissions,
+    check_consistency::check_consistency,
+    claim_rewards::claim_rewards,
+    clone_price::clone_price,
+    convert_price::convert_price,
     del_price::del_price,
     del_product::del_product,
     del_publisher::del_publisher,
+    deprecate_price::deprecate_price,
+    enable_read_stats::enable_read_stats,
+    get_aggregation_counts::{
+        get_aggregation_counts,
+        get_aggregation_counts_summary,
+    },
+    get_compute_budgets::{
+        get_compute_budget_entries,
+        get_compute_budgets,
+    },
+    get_extensions::{
+        get_extension_entries,
+        get_extensions,
+    },
+    get_price::{
+        get_price,
+        get_price_summary,
+    },
+    get_price_extrema::{
+        get_price_extrema,
+        get_price_extrema_summary,
+    },
+    init_audit_log::init_audit_log,
+    init_consistency_check::init_consistency_check,
     init_mapping::init_mapping,
+    init_mapping_v2::init_mapping_v2,
+    init_multisig::init_multisig,
     init_price::init_price,
+    init_price_pda::init_price_pda,
+    init_product_pda::init_product_pda,
+    migrate_mapping::migrate_mapping,
+    prune_publishers::prune_publishers,
+    reserve_extension_space::reserve_extension_space,
+    reset_ema::reset_ema,
+    resize_feed_registry::resize_feed_registry,
     resize_mapping::resize_mapping,
+    rotate_publisher::rotate_publisher,
+    set_access_control::set_access_control,
+    set_accumulator_emitter::set_accumulator_emitter,
+    set_aggregate_cache::set_aggregate_cache,
+    set_aggregation_config::set_aggregation_config,
+    set_aggregation_count_window::set_aggregation_count_window,
+    set_aggregation_delay::set_aggregation_delay,
+    set_aux_data::set_aux_data,
+    set_average_mode::set_average_mode,
+    set_checksum::set_checksum,
+    set_conf_floor::set_conf_floor,
+    set_exponent::set_exponent,
+    set_feature::{
+        init_feature_gate,
+        set_feature,
+    },
+    set_feature_grace_period::set_feature_grace_period,
+    set_latency_stats_window::set_latency_stats_window,
     set_max_latency::set_max_latency,
     set_min_pub::set_min_pub,
+    set_permissions_timelock::set_permissions_timelock,
+    set_price_extrema_window::set_price_extrema_window,
+    set_publish_threshold::set_publish_threshold,
+    set_publisher_cap::set_publisher_cap,
+    set_publisher_demotion::set_publisher_demotion,
+    set_publisher_rewards::set_publisher_rewards,
+    set_publisher_sampling::set_publisher_sampling,
+    set_publisher_timestamps::set_publisher_timestamps,
+    set_publisher_weight_cap::set_publisher_weight_cap,
+    set_quote_conversion::set_quote_conversion,
+    set_quote_order::set_quote_order,
+    set_rate_limit::set_rate_limit,
+    set_sanity_program::set_sanity_program,
+    set_sequence_tracking::set_sequence_tracking,
+    set_shared_settings::set_shared_settings,
+    set_stake_program::set_stake_program,
+    set_status_observer::set_status_observer,
+    set_status_policy::set_status_policy,
+    set_status_reason_tracking::set_status_reason_tracking,
+    set_update_counting::set_update_counting,
+    set_update_fee::set_update_fee,
+    set_updater_program::set_updater_program,
+    simulate_aggregate::simulate_aggregate,
+    snapshot_price_account::snapshot_price_account,
+    upd_access_control::upd_access_control,
+    upd_aux_data::upd_aux_data,
     upd_permissions::upd_permissions,
     upd_price::{
         c_upd_aggregate,
+        c_upd_aggregate_sorted,
         c_upd_twap,
+        find_capped_components,
         find_publisher_index,
+        simulate_aggregation,
         upd_price,
         upd_price_no_fail_on_error,
+        upd_price_with_args,
     },
+    upd_price_compact::upd_price_compact,
     upd_product::upd_product,
+    upd_publisher_linkage::upd_publisher_linkage,
+    upd_publisher_registry::upd_publisher_registry,
+    upd_publisher_status::upd_publisher_status,
+    withdraw_fees::withdraw_fees,
 };
 
 
@@ -83,7 +285,23 @@ pub fn process_instruction(
 ) -> ProgramResult {
     use OracleCommand::*;
 
-    match load_command_header_checked(instruction_data)? {
+    let command = load_command_header_checked(instruction_data)?;
+    let (accounts, audit_log_account) = split_audit_log(program_id, &command, accounts);
+    let multisig_accounts;
+    let accounts = match sign_with_multisig(program_id, accounts, instruction_data)? {
+        Some(signed_accounts) => {
+            multisig_accounts = signed_accounts;
+            multisig_accounts.as_slice()
+        }
+        None => accounts,
+    };
+
+    let audit_target = command.audit_target();
+    // `SetChecksum` is how governance repairs an account that fails the check
+    if command != SetChecksum {
+        verify_checksums(program_id, accounts)?;
+    }
+    match command {
         InitMapping => init_mapping(program_id, accounts, instruction_data),
         AddMapping => Err(OracleError::UnrecognizedInstruction.into()),
         AddProduct => add_product(program_id, accounts, instruction_data),
@@ -113,7 +331,197 @@ pub fn process_instruction(
             Err(OracleError::UnrecognizedInstruction.into())
         }
         ResizeMapping => resize_mapping(program_id, accounts, instruction_data),
+        GetExtensions => get_extensions(program_id, accounts, instruction_data),
+        SetExponent => set_exponent(program_id, accounts, instruction_data),
+        SnapshotPriceAccount => snapshot_price_account(program_id, accounts, instruction_data),
+        SetRateLimit => set_rate_limit(program_id, accounts, instruction_data),
+        GetComputeBudgets => get_compute_budgets(program_id, accounts, instruction_data),
+        SetPermissionsTimelock => set_permissions_timelock(program_id, accounts, instruction_data),
+        ApplyPermissions => apply_permissions(program_id, accounts, instruction_data),
+        CancelPermissions => cancel_permissions(program_id, accounts, instruction_data),
+        UpdPublisherRegistry => upd_publisher_registry(program_id, accounts, instruction_data),
+        SetAggregationDelay => set_aggregation_delay(program_id, accounts, instruction_data),
+        AssertPriceCondition => assert_price_condition(program_id, accounts, instruction_data),
+        SetPublishingSlotTolerance => {
+            solana_program::msg!(
+                "Oracle publishing slot tolerance instruction has been removed. Bailing out!"
+            );
+            Err(OracleError::UnrecognizedInstruction.into())
+        }
+        InitMultisig => init_multisig(program_id, accounts, instruction_data),
+        SetAggregationCountWindow => {
+            set_aggregation_count_window(program_id, accounts, instruction_data)
+        }
+        GetAggregationCounts => get_aggregation_counts(program_id, accounts, instruction_data),
+        SetSanityProgram => set_sanity_program(program_id, accounts, instruction_data),
+        PrunePublishers => prune_publishers(program_id, accounts, instruction_data),
+        UpdPublisherLinkage => upd_publisher_linkage(program_id, accounts, instruction_data),
+        SetPublisherWeightCap => set_publisher_weight_cap(program_id, accounts, instruction_data),
+        ResetEma => reset_ema(program_id, accounts, instruction_data),
+        ClonePrice => clone_price(program_id, accounts, instruction_data),
+        SetQuoteOrder => set_quote_order(program_id, accounts, instruction_data),
+        UpdPublisherStatus => upd_publisher_status(program_id, accounts, instruction_data),
+        DeprecatePrice => deprecate_price(program_id, accounts, instruction_data),
+        UpdPriceCompact => upd_price_compact(program_id, accounts, instruction_data),
+        PatchProduct => upd_product(program_id, accounts, instruction_data),
+        SetFeature => set_feature(program_id, accounts, instruction_data),
+        SetFeatureGracePeriod => set_feature_grace_period(program_id, accounts, instruction_data),
+        UpdAccessControl => upd_access_control(program_id, accounts, instruction_data),
+        SetAccessControl => set_access_control(program_id, accounts, instruction_data),
+        SetLatencyStatsWindow => set_latency_stats_window(program_id, accounts, instruction_data),
+        SetPriceExtremaWindow => set_price_extrema_window(program_id, accounts, instruction_data),
+        GetPriceExtrema => get_price_extrema(program_id, accounts, instruction_data),
+        RotatePublisher => rotate_publisher(program_id, accounts, instruction_data),
+        SetConfFloor => set_conf_floor(program_id, accounts, instruction_data),
+        SetStatusReasonTracking => {
+            set_status_reason_tracking(program_id, accounts, instruction_data)
+        }
+        SetStakeProgram => set_stake_program(program_id, accounts, instruction_data),
+        AddCommunityProduct => add_community_product(program_id, accounts, instruction_data),
+        AddCommunityPrice => add_community_price(program_id, accounts, instruction_data),
+        SetPublisherCap => set_publisher_cap(program_id, accounts, instruction_data),
+        SetQuoteConversion => set_quote_conversion(program_id, accounts, instruction_data),
+        ConvertPrice => convert_price(program_id, accounts, instruction_data),
+        SetAggregateCache => set_aggregate_cache(program_id, accounts, instruction_data),
+        SetPublisherSampling => set_publisher_sampling(program_id, accounts, instruction_data),
+        InitAuditLog => init_audit_log(program_id, accounts, instruction_data),
+        SetUpdateFee => set_update_fee(program_id, accounts, instruction_data),
+        WithdrawFees => withdraw_fees(program_id, accounts, instruction_data),
+        SetPublisherRewards => set_publisher_rewards(program_id, accounts, instruction_data),
+        ClaimRewards => claim_rewards(program_id, accounts, instruction_data),
+        InitConsistencyCheck => init_consistency_check(program_id, accounts, instruction_data),
+        CheckConsistency => check_consistency(program_id, accounts, instruction_data),
+        InitProductPda => init_product_pda(program_id, accounts, instruction_data),
+        InitPricePda => init_price_pda(program_id, accounts, instruction_data),
+        SetUpdateCounting => set_update_counting(program_id, accounts, instruction_data),
+        SetAverageMode => set_average_mode(program_id, accounts, instruction_data),
+        InitMappingV2 => init_mapping_v2(program_id, accounts, instruction_data),
+        MigrateMapping => migrate_mapping(program_id, accounts, instruction_data),
+        SetPublisherTimestamps => set_publisher_timestamps(program_id, accounts, instruction_data),
+        SetPublishThreshold => set_publish_threshold(program_id, accounts, instruction_data),
+        SetStatusObserver => set_status_observer(program_id, accounts, instruction_data),
+        SetChecksum => set_checksum(program_id, accounts, instruction_data),
+        SetSequenceTracking => set_sequence_tracking(program_id, accounts, instruction_data),
+        SetStatusPolicy => set_status_policy(program_id, accounts, instruction_data),
+        SetAccumulatorEmitter => set_accumulator_emitter(program_id, accounts, instruction_data),
+        SetAuxData => set_aux_data(program_id, accounts, instruction_data),
+        UpdAuxData => upd_aux_data(program_id, accounts, instruction_data),
+        SimulateAggregate => simulate_aggregate(program_id, accounts, instruction_data),
+        SetPublisherDemotion => set_publisher_demotion(program_id, accounts, instruction_data),
+        SetUpdaterProgram => set_updater_program(program_id, accounts, instruction_data),
+        SetAggregationConfig => set_aggregation_config(program_id, accounts, instruction_data),
+        ReserveExtensionSpace => reserve_extension_space(program_id, accounts, instruction_data),
+        EnableReadStats => enable_read_stats(program_id, accounts, instruction_data),
+        GetPrice => get_price(program_id, accounts, instruction_data),
+        ResizeFeedRegistry => resize_feed_registry(program_id, accounts, instruction_data),
+        SetSharedSettings => set_shared_settings(program_id, accounts, instruction_data),
+    }?;
+    update_checksums(program_id, accounts)?;
+
+    match (audit_log_account, audit_target) {
+        (Some(audit_log_account), Some(target)) => record_governance(
+            program_id,
+            audit_log_account,
+            accounts,
+            target,
+            instruction_data,
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Split the audit log off the accounts of a governance instruction listing it last, see
+/// `OracleCommand::audit_target`. The other instructions keep all their accounts.
+fn split_audit_log<'b, 'a>(
+    program_id: &Pubkey,
+    command: &OracleCommand,
+    accounts: &'b [AccountInfo<'a>],
+) -> (&'b [AccountInfo<'a>], Option<&'b AccountInfo<'a>>) {
+    match accounts.split_last() {
+        Some((last, others))
+            if command.audit_target().is_some()
+                && AuditLogAccount::is_audit_log(program_id, last) =>
+        {
+            (others, Some(last))
+        }
+        _ => (accounts, None),
+    }
+}
+
+/// Check the `Checksum` of the price accounts the instruction can write.
+fn verify_checksums(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    for account in accounts
+        .iter()
+        .filter(|account| account.is_writable && account.owner == program_id)
+    {
+        verify_checksum(&account.try_borrow_data()?)?;
+    }
+    Ok(())
+}
+
+/// Recompute the `Checksum` of the price accounts the instruction can write, once it succeeded.
+fn update_checksums(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    for account in accounts
+        .iter()
+        .filter(|account| account.is_writable && account.owner == program_id)
+    {
+        update_checksum(&mut account.try_borrow_mut_data()?);
     }
+    Ok(())
+}
+
+/// Append the governance instruction that just succeeded to the audit log, with its funding
+/// account as the actor and its `target`-th account as the target.
+fn record_governance(
+    program_id: &Pubkey,
+    audit_log_account: &AccountInfo,
+    accounts: &[AccountInfo],
+    target: usize,
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let header = load::<CommandHeader>(instruction_data)?;
+    validate_accounts(program_id, &[(audit_log_account, AccountCheck::Writable)])?;
+    let (actor, target) = match (accounts.first(), accounts.get(target)) {
+        (Some(actor), Some(target)) => Ok((actor.key, target.key)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let mut audit_log_data = load_checked::<AuditLogAccount>(audit_log_account, header.version)?;
+    audit_log_data.append(AuditRecord {
+        slot:    Clock::get()?.slot,
+        command: header.command,
+        unused_: 0,
+        actor:   *actor,
+        target:  *target,
+    });
+    Ok(())
+}
+
+/// When the first account of the instruction (its funding account) is a multisig account, check
+/// that at least `threshold` of its signers follow the accounts of the instruction and signed the
+/// transaction. The instruction then runs without them, with the multisig account as a signer.
+/// Returns `None` for any other instruction.
+fn sign_with_multisig<'a>(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo<'a>],
+    instruction_data: &[u8],
+) -> Result<Option<Vec<AccountInfo<'a>>>, ProgramError> {
+    let multisig_account = match accounts.first() {
+        Some(account) if MultisigAccount::is_multisig(program_id, account) => account,
+        _ => return Ok(None),
+    };
+    let version = load::<CommandHeader>(instruction_data)?.version;
+    let multisig_data = load_checked::<MultisigAccount>(multisig_account, version)?;
+
+    let num_signatures = multisig_data.count_trailing_signatures(&accounts[1..]);
+    pyth_assert(
+        num_signatures >= usize::from(multisig_data.threshold),
+        OracleError::MultisigThresholdNotMet.into(),
+    )?;
+
+    let mut signed_accounts = accounts[..accounts.len() - num_signatures].to_vec();
+    signed_accounts[0].is_signer = true;
+    Ok(Some(signed_accounts))
 }
 
 fn reserve_new_price_feed_index(permissions_account: &AccountInfo) -> Result<u32, ProgramError> {
@@ -138,3 +546,251 @@ fn reserve_new_price_feed_index(permissions_account: &AccountInfo) -> Result<u32
     )?;
     Ok(*last_feed_index)
 }
+
+/// Check that `registry_account` is the feed registry, creating it the first time. The funding
+/// account pays for its rent.
+fn load_feed_registry<'a>(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo<'a>,
+    registry_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    version: u32,
+) -> ProgramResult {
+    let (registry_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[FEED_REGISTRY_SEED.as_bytes()], program_id);
+    pyth_assert(
+        registry_pda_address == *registry_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(program_id, &[(system_program, AccountCheck::SystemProgram)])?;
+
+    FeedRegistryAccount::initialize_pda(
+        registry_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[FEED_REGISTRY_SEED.as_bytes(), &[bump_seed]],
+        version,
+    )?;
+    validate_accounts(program_id, &[(registry_account, AccountCheck::Writable)])?;
+    {
+        // Validate that registry_account contains the appropriate account header
+        let _registry_data = load_checked::<FeedRegistryAccount>(registry_account, version)?;
+    }
+    Ok(())
+}
+
+/// Grow the feed registry to `new_size`, the funding account pays for its rent. The runtime lets
+/// an instruction grow an account by at most `MAX_PERMITTED_DATA_INCREASE` bytes.
+fn grow_feed_registry<'a>(
+    funding_account: &AccountInfo<'a>,
+    registry_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    new_size: usize,
+) -> ProgramResult {
+    pyth_assert(
+        new_size <= registry_account.data_len() + MAX_PERMITTED_DATA_INCREASE,
+        OracleError::AccountTooSmall.into(),
+    )?;
+    let minimum_balance = get_rent()?.minimum_balance(new_size);
+    if registry_account.lamports() < minimum_balance {
+        send_lamports(
+            funding_account,
+            registry_account,
+            system_program,
+            minimum_balance - registry_account.lamports(),
+        )?;
+    }
+    registry_account.realloc(new_size, true)
+}
+
+/// Record `price_account` under `feed_index` in the feed registry. The registry is created the
+/// first time and grown to hold the entry, the funding account pays for its rent. A registry more
+/// than `MAX_PERMITTED_DATA_INCREASE` bytes short of the entry must be grown with
+/// `ResizeFeedRegistry` first. Price accounts without a feed index aren't registered.
+fn register_price_feed_index<'a>(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo<'a>,
+    registry_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    feed_index: u32,
+    price_account: &Pubkey,
+    version: u32,
+) -> ProgramResult {
+    load_feed_registry(
+        program_id,
+        funding_account,
+        registry_account,
+        system_program,
+        version,
+    )?;
+    if feed_index == 0 {
+        return Ok(());
+    }
+
+    let new_size = FeedRegistryAccount::entry_offset(feed_index)? + size_of::<Pubkey>();
+    if registry_account.data_len() < new_size {
+        grow_feed_registry(funding_account, registry_account, system_program, new_size)?;
+    }
+    FeedRegistryAccount::set_entry(registry_account, feed_index, price_account)
+}
+
+/// Clear the entry of `feed_index` in the feed registry if it still points to `price_account`.
+/// Price accounts without a feed index were never registered.
+fn unregister_price_feed_index(
+    program_id: &Pubkey,
+    registry_account: &AccountInfo,
+    feed_index: u32,
+    price_account: &Pubkey,
+    version: u32,
+) -> ProgramResult {
+    let (registry_pda_address, _) =
+        Pubkey::find_program_address(&[FEED_REGISTRY_SEED.as_bytes()], program_id);
+    pyth_assert(
+        registry_pda_address == *registry_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(program_id, &[(registry_account, AccountCheck::Writable)])?;
+    {
+        // Validate that registry_account contains the appropriate account header
+        let _registry_data = load_checked::<FeedRegistryAccount>(registry_account, version)?;
+    }
+    if feed_index == 0 {
+        return Ok(());
+    }
+
+    let entry = FeedRegistryAccount::get_entry(&registry_account.try_borrow_data()?, feed_index)?;
+    if entry == Some(*price_account) {
+        FeedRegistryAccount::set_entry(registry_account, feed_index, &Pubkey::default())?;
+    }
+    Ok(())
+}
+
+// Wrapper struct for the accounts required to add data to the accumulator program.
+struct MessageBufferAccounts<'a, 'b: 'a> {
+    program_id:          &'a AccountInfo<'b>,
+    whitelist:           &'a AccountInfo<'b>,
+    oracle_auth_pda:     &'a AccountInfo<'b>,
+    message_buffer_data: &'a AccountInfo<'b>,
+}
+
+/// Replace the messages of `key` in the message buffer with `messages`, through the `put_all`
+/// instruction of the message buffer program. The CPI is signed by the PDA
+/// `[UPD_PRICE_WRITE_SEED, message buffer program]`, which the message buffer program
+/// authenticates against its whitelist. An empty list clears the messages of `key`.
+fn put_all_messages(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    buffer_accounts: &MessageBufferAccounts,
+    key: &Pubkey,
+    messages: Vec<Vec<u8>>,
+) -> ProgramResult {
+    // Check that the oracle PDA is correctly configured for the program we are calling.
+    let oracle_auth_seeds: &[&[u8]] = &[
+        UPD_PRICE_WRITE_SEED.as_bytes(),
+        &buffer_accounts.program_id.key.to_bytes(),
+    ];
+    let (expected_oracle_auth_pda, bump) =
+        Pubkey::find_program_address(oracle_auth_seeds, program_id);
+    pyth_assert(
+        expected_oracle_auth_pda == *buffer_accounts.oracle_auth_pda.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    let account_metas = vec![
+        AccountMeta {
+            pubkey:      *buffer_accounts.whitelist.key,
+            is_signer:   false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey:      *buffer_accounts.oracle_auth_pda.key,
+            is_signer:   true,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey:      *buffer_accounts.message_buffer_data.key,
+            is_signer:   false,
+            is_writable: true,
+        },
+    ];
+
+    // anchor discriminator for "global:put_all"
+    let discriminator: [u8; 8] = [212, 225, 193, 91, 151, 238, 20, 93];
+    let create_inputs_ix = Instruction::new_with_borsh(
+        *buffer_accounts.program_id.key,
+        &(discriminator, key.to_bytes(), messages),
+        account_metas,
+    );
+
+    let auth_seeds_with_bump: &[&[u8]] = &[
+        UPD_PRICE_WRITE_SEED.as_bytes(),
+        &buffer_accounts.program_id.key.to_bytes(),
+        &[bump],
+    ];
+
+    invoke_signed(&create_inputs_ix, accounts, &[auth_seeds_with_bump])
+}
+
+// Wrapper struct for the accounts required to push the aggregates to the accumulator emitter.
+struct AccumulatorEmitterAccounts<'a, 'b: 'a> {
+    permissions:     &'a AccountInfo<'b>,
+    program_id:      &'a AccountInfo<'b>,
+    oracle_auth_pda: &'a AccountInfo<'b>,
+}
+
+/// Push `messages` about the new aggregate of `price_account` to the `AccumulatorEmitter` of the
+/// permission account, through its `put_snapshot` instruction. Like `put_all_messages`, the CPI is
+/// signed by the PDA `[UPD_PRICE_WRITE_SEED, emitter program]`. Nothing is pushed while no emitter
+/// is set, so publishers can list the accounts before governance sets it.
+fn push_snapshot(
+    program_id: &Pubkey,
+    emitter_accounts: &AccumulatorEmitterAccounts,
+    price_account: &AccountInfo,
+    messages: Vec<Vec<u8>>,
+) -> ProgramResult {
+    let emitter_id =
+        match PermissionAccount::load_accumulator_emitter(emitter_accounts.permissions)? {
+            Some(emitter) if emitter.program_id != Pubkey::default() => emitter.program_id,
+            _ => return Ok(()),
+        };
+    pyth_assert(
+        *emitter_accounts.program_id.key == emitter_id,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let oracle_auth_seeds: &[&[u8]] = &[UPD_PRICE_WRITE_SEED.as_bytes(), &emitter_id.to_bytes()];
+    let (expected_oracle_auth_pda, bump) =
+        Pubkey::find_program_address(oracle_auth_seeds, program_id);
+    pyth_assert(
+        expected_oracle_auth_pda == *emitter_accounts.oracle_auth_pda.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    // anchor discriminator for "global:put_snapshot"
+    let discriminator: [u8; 8] = [196, 221, 66, 214, 225, 127, 157, 248];
+    let instruction = Instruction::new_with_borsh(
+        emitter_id,
+        &(discriminator, price_account.key.to_bytes(), messages),
+        vec![
+            AccountMeta::new_readonly(*emitter_accounts.oracle_auth_pda.key, true),
+            AccountMeta::new_readonly(*price_account.key, false),
+        ],
+    );
+
+    let auth_seeds_with_bump: &[&[u8]] = &[
+        UPD_PRICE_WRITE_SEED.as_bytes(),
+        &emitter_id.to_bytes(),
+        &[bump],
+    ];
+
+    invoke_signed(
+        &instruction,
+        &[
+            emitter_accounts.oracle_auth_pda.clone(),
+            price_account.clone(),
+            emitter_accounts.program_id.clone(),
+        ],
+        &[auth_seeds_with_bump],
+    )
+}
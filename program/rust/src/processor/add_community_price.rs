@@ -0,0 +1,153 @@
+use {
+    crate::{
+        accounts::{
+            data_len_with_extension,
+            init_extension,
+            FeedCreator,
+            FeedKind,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+            COMMUNITY_SEED,
+        },
+        c_oracle_header::PRICE_ACCOUNT_DEFAULT_MIN_PUB,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::AddCommunityPriceArgs,
+        utils::{
+            check_exponent_range,
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Create a price account without governance for a product of the creator listed by
+/// `AddCommunityProduct`. The price account is the PDA `[COMMUNITY_SEED, creator, price_seed]`,
+/// created and paid for by the creator, and holds the `FeedCreator` extension. It's flagged as a
+/// `COMMUNITY` feed, so that consumers can tell it apart from the feeds curated by governance, and
+/// its creator can add its publishers. Community feeds stay out of the cross-chain pipeline: they
+/// don't take a feed index from the permissions account and aren't on the accumulator, unless
+/// governance enables it with `AddPublisher`.
+// account[0] creator account       [signer writable]
+// account[1] product account       [writable]
+// account[2] price account         [writable]
+// account[3] system program        []
+pub fn add_community_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<AddCommunityPriceArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<AddCommunityPriceArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    check_exponent_range(cmd.exponent)?;
+    pyth_assert(
+        FeedKind::from_u32(cmd.price_type).is_some(),
+        OracleError::InvalidPriceType.into(),
+    )?;
+
+    let (creator_account, product_account, price_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (creator_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+
+    // Creators can only list prices for their own products
+    let creator_key = creator_account.key.to_bytes();
+    let (product_pda_address, _) = Pubkey::find_program_address(
+        &[COMMUNITY_SEED.as_bytes(), &creator_key, &cmd.product_seed],
+        program_id,
+    );
+    pyth_assert(
+        product_pda_address == *product_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(program_id, &[(product_account, AccountCheck::Writable)])?;
+    {
+        // Validate that product_account contains the appropriate account header
+        let _product_data = load_checked::<ProductAccount>(product_account, cmd.header.version)?;
+    }
+
+    let (price_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[COMMUNITY_SEED.as_bytes(), &creator_key, &cmd.price_seed],
+        program_id,
+    );
+    pyth_assert(
+        price_pda_address == *price_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    // The seed of an existing account can't be reused
+    pyth_assert(
+        price_account.data_len() == 0,
+        OracleError::InvalidFreshAccount.into(),
+    )?;
+
+    PriceAccount::initialize_pda(
+        price_account,
+        creator_account,
+        system_program,
+        program_id,
+        &[
+            COMMUNITY_SEED.as_bytes(),
+            &creator_key,
+            &cmd.price_seed,
+            &[bump_seed],
+        ],
+        cmd.header.version,
+    )?;
+    validate_accounts(program_id, &[(price_account, AccountCheck::Writable)])?;
+
+    // Grow the new price account to hold the creator
+    let new_size = data_len_with_extension::<FeedCreator>(&price_account.try_borrow_data()?)?;
+    let minimum_balance = get_rent()?.minimum_balance(new_size);
+    if price_account.lamports() < minimum_balance {
+        send_lamports(
+            creator_account,
+            price_account,
+            system_program,
+            minimum_balance - price_account.lamports(),
+        )?;
+    }
+    price_account.realloc(new_size, true)?;
+    init_extension::<FeedCreator>(&mut price_account.try_borrow_mut_data()?)?.creator =
+        *creator_account.key;
+
+    let mut product_data = load_checked::<ProductAccount>(product_account, cmd.header.version)?;
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    price_data.exponent = cmd.exponent;
+    price_data.price_type = cmd.price_type;
+    price_data.product_account = *product_account.key;
+    price_data.next_price_account = product_data.first_price_account;
+    price_data.min_pub_ = PRICE_ACCOUNT_DEFAULT_MIN_PUB;
+    price_data.flags.insert(PriceAccountFlags::COMMUNITY);
+
+    product_data.first_price_account = *price_account.key;
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use {
+    crate::{
+        accounts::{
+            update_product_metadata,
+            validate_product_metadata,
+            ProductAccount,
+            PythAccount,
+            COMMUNITY_SEED,
+        },
+        deserialize::load,
+        instruction::AddCommunityProductArgs,
+        utils::{
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Create a product without governance, e.g. to list a long-tail asset. The product account is
+/// the PDA `[COMMUNITY_SEED, creator, seed]`, created and paid for by the creator, and isn't
+/// listed in the mapping accounts. Its metadata follows the `AddCommunityProductArgs` and must
+/// match the schema checked by `validate_product_metadata`. Only governance can update it.
+// account[0] creator account       [signer writable]
+// account[1] product account       [writable]
+// account[2] system program        []
+pub fn add_community_product(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<AddCommunityProductArgs>(instruction_data)?;
+
+    let (creator_account, product_account, system_program) = match accounts {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (creator_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+
+    // The metadata is checked like in `UpdProduct`, which expects it after a command header
+    let metadata = [
+        bytes_of(&cmd.header),
+        &instruction_data[size_of::<AddCommunityProductArgs>()..],
+    ]
+    .concat();
+    validate_product_metadata(&metadata)?;
+
+    let (product_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[
+            COMMUNITY_SEED.as_bytes(),
+            &creator_account.key.to_bytes(),
+            &cmd.seed,
+        ],
+        program_id,
+    );
+    pyth_assert(
+        product_pda_address == *product_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    // The seed of an existing product can't be reused
+    pyth_assert(
+        product_account.data_len() == 0,
+        OracleError::InvalidFreshAccount.into(),
+    )?;
+
+    ProductAccount::initialize_pda(
+        product_account,
+        creator_account,
+        system_program,
+        program_id,
+        &[
+            COMMUNITY_SEED.as_bytes(),
+            &creator_account.key.to_bytes(),
+            &cmd.seed,
+            &[bump_seed],
+        ],
+        cmd.header.version,
+    )?;
+    validate_accounts(program_id, &[(product_account, AccountCheck::Writable)])?;
+
+    update_product_metadata(&metadata, product_account, cmd.header.version)
+}
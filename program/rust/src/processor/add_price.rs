@@ -1,16 +1,17 @@
 use {
-    super::reserve_new_price_feed_index,
+    super::{
+        register_price_feed_index,
+        reserve_new_price_feed_index,
+    },
     crate::{
         accounts::{
+            FeedKind,
             PriceAccount,
             PriceAccountFlags,
             ProductAccount,
             PythAccount,
         },
-        c_oracle_header::{
-            PC_PTYPE_UNKNOWN,
-            PRICE_ACCOUNT_DEFAULT_MIN_PUB,
-        },
+        c_oracle_header::PRICE_ACCOUNT_DEFAULT_MIN_PUB,
         deserialize::{
             load,
             load_checked,
@@ -20,24 +21,28 @@ use {
             check_exponent_range,
             check_permissioned_funding_account,
             check_valid_funding_account,
-            check_valid_writable_account,
             pyth_assert,
+            validate_accounts,
+            AccountCheck,
         },
         OracleError,
     },
+    num_traits::FromPrimitive,
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
 };
 
-/// Add new price account to a product account
+/// Add new price account to a product account. When the feed registry is passed, the new price
+/// account is registered under its feed index.
 // account[0] funding account        [signer writable]
 // account[1] product account        [writable]
 // account[2] new price account      [writable]
 // account[3] permissions account    [writable]
+// account[4] feed registry          [writable] (optional)
+// account[5] system program         [] (optional)
 pub fn add_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -47,15 +52,17 @@ pub fn add_price(
 
     check_exponent_range(cmd_args.exponent)?;
     pyth_assert(
-        cmd_args.price_type != PC_PTYPE_UNKNOWN,
-        ProgramError::InvalidArgument,
+        FeedKind::from_u32(cmd_args.price_type).is_some(),
+        OracleError::InvalidPriceType.into(),
     )?;
 
 
-    let (funding_account, product_account, price_account, permissions_account) = match accounts {
-        [x, y, z, p] => Ok((x, y, z, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, product_account, price_account, permissions_account, maybe_registry) =
+        match accounts {
+            [x, y, z, p] => Ok((x, y, z, p, None)),
+            [x, y, z, p, r, s] => Ok((x, y, z, p, Some((r, s)))),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     check_valid_funding_account(funding_account)?;
     check_permissioned_funding_account(
@@ -72,7 +79,7 @@ pub fn add_price(
         permissions_account,
         &cmd_args.header,
     )?;
-    check_valid_writable_account(program_id, permissions_account)?;
+    validate_accounts(program_id, &[(permissions_account, AccountCheck::Writable)])?;
 
     let mut product_data =
         load_checked::<ProductAccount>(product_account, cmd_args.header.version)?;
@@ -92,6 +99,21 @@ pub fn add_price(
     }
 
     product_data.first_price_account = *price_account.key;
+    let feed_index = price_data.feed_index;
+    drop(price_data);
+    drop(product_data);
+
+    if let Some((registry_account, system_program)) = maybe_registry {
+        register_price_feed_index(
+            program_id,
+            funding_account,
+            registry_account,
+            system_program,
+            feed_index,
+            price_account.key,
+            cmd_args.header.version,
+        )?;
+    }
 
     Ok(())
 }
@@ -2,6 +2,7 @@ use {
     crate::{
         accounts::{
             update_product_metadata,
+            validate_product_metadata,
             MappingAccount,
             ProductAccount,
             PythAccount,
@@ -23,7 +24,6 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
-        program_error::ProgramError,
         pubkey::Pubkey,
     },
     std::mem::{
@@ -66,12 +66,16 @@ pub fn add_product(
         hdr,
     )?;
 
+    // Inline metadata must match the schema that `UpdProduct` enforces
+    if instruction_data.len() > size_of::<CommandHeader>() {
+        validate_product_metadata(instruction_data)?;
+    }
 
     let mut mapping_data = load_checked::<MappingAccount>(tail_mapping_account, hdr.version)?;
     // The mapping account must have free space to add the product account
     pyth_assert(
         mapping_data.number_of_products < PC_MAP_TABLE_SIZE,
-        ProgramError::InvalidArgument,
+        OracleError::MappingAccountFull.into(),
     )?;
 
     ProductAccount::initialize(new_product_account, hdr.version)?;
@@ -1,22 +1,37 @@
 use {
     crate::{
         accounts::{
+            get_extension,
+            get_extension_mut,
+            invalidate_quote_order,
+            realign_publisher_entries,
+            PermissionAccount,
             PriceAccount,
             PriceAccountFlags,
             PriceComponent,
+            PublisherCap,
+            PublisherRegistryAccount,
+            PublisherStake,
             PythAccount,
+            PUBLISHER_REGISTRY_SEED,
         },
         c_oracle_header::PC_NUM_COMP,
         deserialize::{
             load,
             load_checked,
+            OracleAccount,
+        },
+        instruction::{
+            AddPublisherArgs,
+            StakeCheckArgs,
         },
-        instruction::AddPublisherArgs,
         utils::{
             check_permissioned_funding_account,
             check_valid_funding_account,
             pyth_assert,
             try_convert,
+            validate_accounts,
+            AccountCheck,
         },
         OracleError,
     },
@@ -24,6 +39,14 @@ use {
     solana_program::{
         account_info::AccountInfo,
         entrypoint::ProgramResult,
+        instruction::{
+            AccountMeta,
+            Instruction,
+        },
+        program::{
+            get_return_data,
+            invoke,
+        },
         program_error::ProgramError,
         program_memory::{
             sol_memcmp,
@@ -46,11 +69,30 @@ pub const ALLOW_ZERO_CI: [u8; 32] = [
 pub const FORBID_ZERO_CI: [u8; 32] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,
 ];
+pub const REQUIRE_ISOLATED_UPDATES: [u8; 32] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5,
+];
+pub const ALLOW_BUNDLED_UPDATES: [u8; 32] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,
+];
+/// Keys configuring the feed instead of adding a publisher
+const CONFIGURATION_KEYS: [[u8; 32]; 6] = [
+    ENABLE_ACCUMULATOR_V2,
+    DISABLE_ACCUMULATOR_V2,
+    ALLOW_ZERO_CI,
+    FORBID_ZERO_CI,
+    REQUIRE_ISOLATED_UPDATES,
+    ALLOW_BUNDLED_UPDATES,
+];
 
 /// Add publisher to symbol account
 // account[0] funding account       [signer writable]
 // account[1] price account         [signer writable]
 // account[2] permissions account   []
+// account[3] publisher registry    [] (optional until the registry exists, the publisher must be
+//                                     approved in it)
+// account[3|4] stake escrow        [] (optional, required by feeds with a staking program)
+// account[4|5] staking program     [] (optional, required by feeds with a staking program)
 pub fn add_publisher(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -63,10 +105,14 @@ pub fn add_publisher(
         ProgramError::InvalidArgument,
     )?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, price_account, permissions_account, maybe_registry, maybe_stake) =
+        match accounts {
+            [x, y, p] => Ok((x, y, p, None, None)),
+            [x, y, p, r] => Ok((x, y, p, Some(r), None)),
+            [x, y, p, e, s] => Ok((x, y, p, None, Some((e, s)))),
+            [x, y, p, r, e, s] => Ok((x, y, p, Some(r), Some((e, s)))),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     check_valid_funding_account(funding_account)?;
     check_permissioned_funding_account(
@@ -77,7 +123,19 @@ pub fn add_publisher(
         &cmd_args.header,
     )?;
 
-    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    // The configuration of the feeds is up to governance, the creators of community feeds only
+    // manage their publishers
+    if CONFIGURATION_KEYS.contains(&cmd_args.publisher.to_bytes()) {
+        pyth_assert(
+            load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?
+                .master_authority
+                == *funding_account.key,
+            OracleError::PermissionViolation.into(),
+        )?;
+    }
+
+    let price = OracleAccount::<PriceAccount>::new(price_account, cmd_args.header.version)?;
+    let mut price_data = price.load_mut()?;
 
     // Hack: we use add_publisher instruction to configure the price feeds for some operations.
     // This is mostly because we are constrained on contract size and can't add separate
@@ -96,15 +154,43 @@ pub fn add_publisher(
     } else if cmd_args.publisher == Pubkey::from(FORBID_ZERO_CI) {
         price_data.flags.remove(PriceAccountFlags::ALLOW_ZERO_CI);
         return Ok(());
+    } else if cmd_args.publisher == Pubkey::from(REQUIRE_ISOLATED_UPDATES) {
+        price_data.flags.insert(PriceAccountFlags::ISOLATED_UPDATES);
+        return Ok(());
+    } else if cmd_args.publisher == Pubkey::from(ALLOW_BUNDLED_UPDATES) {
+        price_data.flags.remove(PriceAccountFlags::ISOLATED_UPDATES);
+        return Ok(());
     }
 
+    // Once the registry exists, leaving it out doesn't skip the approval
+    pyth_assert(
+        maybe_registry.is_some()
+            || !PermissionAccount::has_publisher_registry(permissions_account)?,
+        OracleError::PublisherNotApproved.into(),
+    )?;
+    if let Some(registry_account) = maybe_registry {
+        check_publisher_approved(program_id, registry_account, &cmd_args.publisher)?;
+    }
+
+    // The staking program can't be called while the price account is borrowed
+    let num_publishers = price_data.num_;
+    drop(price_data);
+    if let Some(cap) = get_extension::<PublisherCap>(&price_account.try_borrow_data()?)? {
+        pyth_assert(
+            cap.max_publishers == 0 || num_publishers < cap.max_publishers,
+            OracleError::PublisherCapReached.into(),
+        )?;
+    }
+    let stake = check_publisher_stake(price_account, &cmd_args.publisher, maybe_stake)?;
+    let mut price_data = price.load_mut()?;
+
     if price_data.num_ >= PC_NUM_COMP {
-        return Err(ProgramError::InvalidArgument);
+        return Err(OracleError::MaxPublishersReached.into());
     }
 
     for i in 0..(try_convert::<u32, usize>(price_data.num_)?) {
         if cmd_args.publisher == price_data.comp_[i].pub_ {
-            return Err(ProgramError::InvalidArgument);
+            return Err(OracleError::PublisherAlreadyExists.into());
         }
     }
 
@@ -118,15 +204,100 @@ pub fn add_publisher(
     price_data.num_ += 1;
 
     // Sort the publishers in the list
-    {
-        let num_comps = try_convert::<u32, usize>(price_data.num_)?;
-        sort_price_comps(&mut price_data.comp_, num_comps)?;
-    }
+    let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+    sort_price_comps(&mut price_data.comp_, num_comps)?;
+    let index = price_data.comp_[..num_comps]
+        .iter()
+        .position(|comp| comp.pub_ == cmd_args.publisher)
+        .ok_or(OracleError::PublisherNotFound)?;
 
     price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
+    drop(price_data);
+    invalidate_quote_order(price_account)?;
+    realign_publisher_entries(price_account)?;
+
+    // Keep the recorded stakes aligned with the components
+    if let Some(publisher_stake) =
+        get_extension_mut::<PublisherStake>(&mut price_account.try_borrow_mut_data()?)?
+    {
+        publisher_stake.insert(
+            index,
+            num_comps,
+            &cmd_args.publisher,
+            stake.unwrap_or_default(),
+        );
+    }
     Ok(())
 }
 
+/// Check that `registry_account` is the publisher registry and that it approved `publisher`.
+pub fn check_publisher_approved(
+    program_id: &Pubkey,
+    registry_account: &AccountInfo,
+    publisher: &Pubkey,
+) -> Result<(), ProgramError> {
+    let (registry_pda_address, _) =
+        Pubkey::find_program_address(&[PUBLISHER_REGISTRY_SEED.as_bytes()], program_id);
+    pyth_assert(
+        registry_pda_address == *registry_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(program_id, &[(registry_account, AccountCheck::Readable)])?;
+    pyth_assert(
+        PublisherRegistryAccount::is_approved(&registry_account.try_borrow_data()?, publisher)?,
+        OracleError::PublisherNotApproved.into(),
+    )
+}
+
+/// Stake of `publisher` in its escrow account, as reported by the staking program of the feed.
+/// `None` if the feed doesn't check the stake of its publishers, the stake accounts are then
+/// ignored.
+fn check_publisher_stake(
+    price_account: &AccountInfo,
+    publisher: &Pubkey,
+    maybe_stake: Option<(&AccountInfo, &AccountInfo)>,
+) -> Result<Option<u64>, ProgramError> {
+    let (staking_program_id, min_stake) =
+        match get_extension::<PublisherStake>(&price_account.try_borrow_data()?)? {
+            Some(stake) if stake.staking_program != Pubkey::default() => {
+                (stake.staking_program, stake.min_stake)
+            }
+            _ => return Ok(None),
+        };
+    let (escrow_account, staking_program) = match maybe_stake {
+        Some((escrow, program))
+            if *program.key == staking_program_id && *escrow.owner == staking_program_id =>
+        {
+            (escrow, program)
+        }
+        _ => return Err(OracleError::InvalidStakeEscrow.into()),
+    };
+
+    let args = StakeCheckArgs {
+        publisher:     *publisher,
+        price_account: *price_account.key,
+    };
+    invoke(
+        &Instruction::new_with_bytes(
+            staking_program_id,
+            bytemuck::bytes_of(&args),
+            vec![AccountMeta::new_readonly(*escrow_account.key, false)],
+        ),
+        &[escrow_account.clone(), staking_program.clone()],
+    )?;
+
+    let amount = match get_return_data() {
+        Some((program_id, data)) if program_id == staking_program_id && data.len() == 8 => {
+            let mut amount = [0u8; 8];
+            amount.copy_from_slice(&data);
+            u64::from_le_bytes(amount)
+        }
+        _ => return Err(OracleError::InvalidStakeEscrow.into()),
+    };
+    pyth_assert(amount >= min_stake, OracleError::InsufficientStake.into())?;
+    Ok(Some(amount))
+}
+
 /// A copy of rust slice/sort.rs heapsort implementation which is small and fast. We couldn't use
 /// the sort directly because it was only accessible behind a unstable feature flag at the time of
 /// writing this code.
@@ -177,7 +348,10 @@ fn heapsort(v: &mut [(Pubkey, usize)]) {
 ///
 /// num_publishers is the number of publishers in the list that should be sorted. It is explicitly
 /// passed to avoid callers mistake of passing the full slice which may contain uninitialized values.
-fn sort_price_comps(comps: &mut [PriceComponent], num_comps: usize) -> Result<(), ProgramError> {
+pub fn sort_price_comps(
+    comps: &mut [PriceComponent],
+    num_comps: usize,
+) -> Result<(), ProgramError> {
     let comps = comps
         .get_mut(..num_comps)
         .ok_or(ProgramError::InvalidArgument)?;
@@ -0,0 +1,92 @@
+use {
+    crate::{
+        accounts::{
+            check_is_upgradeable_program,
+            PermissionAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+};
+
+/// Apply the pending change of the permissions once its delay has passed. Anyone can apply it,
+/// the change was authorized by the upgrade authority when it was staged. Once the program is
+/// made immutable, its permissions are frozen with it and the pending change can't be applied.
+// account[0] funding account       [signer writable]
+// account[1] permissions account   [writable]
+// account[2] sysvar_clock account  []
+// account[3] programdata account   []
+pub fn apply_permissions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, permissions_account, clock_account, programdata_account) = match accounts
+    {
+        [x, y, z, p] => Ok((x, y, z, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (permissions_account, AccountCheck::Permissions),
+            (permissions_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    check_is_upgradeable_program(programdata_account, program_id)?;
+    {
+        // Validate that permissions_account contains the appropriate account header
+        let _permissions_data =
+            load_checked::<PermissionAccount>(permissions_account, hdr.version)?;
+    }
+
+    let clock = Clock::from_account_info(clock_account)?;
+    let timelock = *PermissionAccount::load_timelock(permissions_account)?
+        .ok_or(OracleError::NoPendingPermissionsChange)?;
+    pyth_assert(
+        timelock.has_pending_change(),
+        OracleError::NoPendingPermissionsChange.into(),
+    )?;
+    pyth_assert(
+        clock.slot >= timelock.effective_slot,
+        OracleError::PermissionsChangeNotReady.into(),
+    )?;
+
+    {
+        let mut permissions_account_data =
+            load_checked::<PermissionAccount>(permissions_account, hdr.version)?;
+        permissions_account_data.master_authority = timelock.pending_master_authority;
+        permissions_account_data.data_curation_authority = timelock.pending_data_curation_authority;
+        permissions_account_data.security_authority = timelock.pending_security_authority;
+    }
+
+    let mut timelock_data = PermissionAccount::load_timelock_mut(permissions_account)?;
+    timelock_data.delay = timelock.pending_delay;
+    timelock_data.clear_pending_change();
+
+    Ok(())
+}
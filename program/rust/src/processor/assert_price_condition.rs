@@ -0,0 +1,110 @@
+use {
+    crate::{
+        accounts::{
+            get_successor,
+            PriceAccount,
+            PriceInfo,
+        },
+        c_oracle_header::PC_STATUS_UNKNOWN,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::AssertPriceConditionArgs,
+        utils::{
+            check_aggregation_complete,
+            check_reader_allowed,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Fail with `PriceConditionNotMet` unless the aggregate of the price account meets the
+/// condition of the instruction. Nothing is written, consumer protocols add the instruction to
+/// their transactions to guard them. When the feed is deprecated, its successor is set as the
+/// return data so that the consumers calling it through CPI can follow the redirect. A
+/// permissioned feed fails with `ReaderNotAllowed` unless a reader listed in its access control
+/// account signs, consumer programs sign with their PDA.
+// account[0] price account           []
+// account[1] sysvar_clock account    []
+// account[2] reader                  [signer] (permissioned feeds)
+// account[3] access control account  [] (permissioned feeds)
+pub fn assert_price_condition(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<AssertPriceConditionArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<AssertPriceConditionArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (price_account, clock_account, reader_accounts) = match accounts {
+        [x, y] => Ok((x, y, None)),
+        [x, y, r, a] => Ok((x, y, Some((r, a)))),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    check_reader_allowed(
+        program_id,
+        price_account,
+        reader_accounts,
+        cmd.header.version,
+    )?;
+
+    {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+        check_aggregation_complete(&price_data)?;
+        pyth_assert(
+            meets_condition(&price_data.agg_, cmd, clock.slot),
+            OracleError::PriceConditionNotMet.into(),
+        )?;
+    }
+
+    if let Some(successor) = get_successor(&price_account.try_borrow_data()?) {
+        set_return_data(successor.as_ref());
+    }
+
+    Ok(())
+}
+
+fn meets_condition(aggregate: &PriceInfo, cmd: &AssertPriceConditionArgs, slot: u64) -> bool {
+    let status_met =
+        cmd.required_status == PC_STATUS_UNKNOWN || aggregate.status_ == cmd.required_status;
+    let price_met = cmd.min_price <= aggregate.price_ && aggregate.price_ <= cmd.max_price;
+    // conf / |price| <= max_conf_ratio_bps / 10_000, without rounding
+    let conf_met = cmd.max_conf_ratio_bps == u64::MAX
+        || u128::from(aggregate.conf_) * 10_000
+            <= u128::from(cmd.max_conf_ratio_bps) * u128::from(aggregate.price_.unsigned_abs());
+    let age_met =
+        cmd.max_age == u64::MAX || slot.saturating_sub(aggregate.pub_slot_) <= cmd.max_age;
+
+    status_met && price_met && conf_met && age_met
+}
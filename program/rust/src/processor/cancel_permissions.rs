@@ -0,0 +1,54 @@
+use {
+    crate::{
+        accounts::PermissionAccount,
+        deserialize::load,
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Drop the pending change of the permissions, e.g. a hostile or mistaken change staged by the
+/// upgrade authority. The delay of the timelock is left unchanged.
+// account[0] funding account       [signer writable]
+// account[1] permissions account   [writable]
+pub fn cancel_permissions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, permissions_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        permissions_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    let mut timelock = PermissionAccount::load_timelock_mut(permissions_account)
+        .map_err(|_| OracleError::NoPendingPermissionsChange)?;
+    pyth_assert(
+        timelock.has_pending_change(),
+        OracleError::NoPendingPermissionsChange.into(),
+    )?;
+    timelock.clear_pending_change();
+
+    Ok(())
+}
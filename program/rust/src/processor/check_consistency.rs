@@ -0,0 +1,119 @@
+use {
+    crate::{
+        accounts::{
+            get_extension_mut,
+            ConsistencyCheckAccount,
+            ConsistencyFlag,
+            PriceAccount,
+            MAX_CONSISTENCY_FEEDS,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_aggregation_complete,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Compare the latest aggregates of the feeds of a consistency check. When the relation is off by
+/// more than the tolerance of the check, the `ConsistencyFlag` of every feed is raised with the
+/// check, otherwise the flags the check raised earlier are cleared. Nothing changes unless every
+/// aggregate is trading.
+// account[0] consistency check     [writable]
+// account[1] sysvar_clock account  []
+// account[2..] price accounts      [writable]
+pub fn check_consistency(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (check_account, clock_account, price_accounts) = match accounts {
+        [x, y, feeds @ ..] => Ok((x, y, feeds)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (check_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    for price_account in price_accounts {
+        validate_accounts(program_id, &[(price_account, AccountCheck::Writable)])?;
+    }
+
+    let mut check_data = load_checked::<ConsistencyCheckAccount>(check_account, cmd.version)?;
+    pyth_assert(
+        check_data
+            .linked_feeds()
+            .iter()
+            .eq(price_accounts.iter().map(|account| account.key)),
+        OracleError::InvalidConsistencyFeeds.into(),
+    )?;
+
+    let mut prices = [(0, 0); MAX_CONSISTENCY_FEEDS];
+    for (price, price_account) in prices.iter_mut().zip(price_accounts) {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.version)?;
+        check_aggregation_complete(&price_data)?;
+        if price_data.agg_.status_ != PC_STATUS_TRADING {
+            return Ok(());
+        }
+        *price = (price_data.agg_.price_, price_data.exponent);
+    }
+    let deviation_bps = check_data
+        .deviation_bps(&prices[..price_accounts.len()])
+        .ok_or(ProgramError::InvalidAccountData)?;
+
+    let clock = Clock::from_account_info(clock_account)?;
+    let violated = deviation_bps > u64::from(check_data.tolerance_bps);
+    check_data.last_check_slot = clock.slot;
+    check_data.last_deviation_bps = deviation_bps;
+    if violated {
+        check_data.num_violations += 1;
+    }
+
+    for price_account in price_accounts {
+        let mut price_account_data = price_account.try_borrow_mut_data()?;
+        if let Some(flag) = get_extension_mut::<ConsistencyFlag>(&mut price_account_data)? {
+            if violated {
+                flag.check = *check_account.key;
+                flag.slot = clock.slot;
+            } else if flag.check == *check_account.key {
+                *flag = ConsistencyFlag {
+                    check: Pubkey::default(),
+                    slot:  0,
+                };
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use {
+    crate::{
+        accounts::{
+            get_extension_mut,
+            PriceAccount,
+            PublisherRewards,
+            PythAccount,
+            RewardsLedgerAccount,
+            REWARDS_LEDGER_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Move the reward score the publisher earned in the feed to its rewards ledger, see
+/// `PublisherRewards`. The ledger is created at the address of
+/// `[REWARDS_LEDGER_SEED, publisher_public_key]` by the first claim, paid by the publisher. Feeds
+/// without the extension have nothing to claim.
+// account[0] publisher account     [signer writable]
+// account[1] price account         [writable]
+// account[2] rewards ledger        [writable]
+// account[3] system program        []
+pub fn claim_rewards(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (publisher_account, price_account, rewards_ledger_account, system_program) = match accounts
+    {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (publisher_account, AccountCheck::Funding),
+            (price_account, AccountCheck::Writable),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (rewards_ledger_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[
+            REWARDS_LEDGER_SEED.as_bytes(),
+            &publisher_account.key.to_bytes(),
+        ],
+        program_id,
+    );
+    pyth_assert(
+        rewards_ledger_pda_address == *rewards_ledger_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    RewardsLedgerAccount::initialize_pda(
+        rewards_ledger_account,
+        publisher_account,
+        system_program,
+        program_id,
+        &[
+            REWARDS_LEDGER_SEED.as_bytes(),
+            &publisher_account.key.to_bytes(),
+            &[bump_seed],
+        ],
+        cmd.version,
+    )?;
+    validate_accounts(
+        program_id,
+        &[(rewards_ledger_account, AccountCheck::Writable)],
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.version)?;
+    }
+    let score =
+        match get_extension_mut::<PublisherRewards>(&mut price_account.try_borrow_mut_data()?)? {
+            Some(rewards) => rewards.claim(publisher_account.key),
+            None => 0,
+        };
+
+    let mut rewards_ledger_data =
+        load_checked::<RewardsLedgerAccount>(rewards_ledger_account, cmd.version)?;
+    rewards_ledger_data.publisher = *publisher_account.key;
+    rewards_ledger_data.total_score += score;
+    rewards_ledger_data.num_claims += 1;
+
+    Ok(())
+}
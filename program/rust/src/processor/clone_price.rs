@@ -0,0 +1,161 @@
+use {
+    super::{
+        register_price_feed_index,
+        reserve_new_price_feed_index,
+    },
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Add a new price account to the product of an existing one, with the same configuration: price
+/// type, exponent, minimum number of publishers, max latency, flags and publishers. The prices
+/// and the extensions of the source aren't copied, nor its deprecation. When the feed registry is passed, the new
+/// price account is registered under its feed index.
+// account[0] funding account        [signer writable]
+// account[1] product account        [writable]
+// account[2] source price account   []
+// account[3] new price account      [writable]
+// account[4] permissions account    [writable]
+// account[5] feed registry          [writable] (optional)
+// account[6] system program         [] (optional)
+pub fn clone_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (
+        funding_account,
+        product_account,
+        source_account,
+        price_account,
+        permissions_account,
+        maybe_registry,
+    ) = match accounts {
+        [w, x, y, z, p] => Ok((w, x, y, z, p, None)),
+        [w, x, y, z, p, r, s] => Ok((w, x, y, z, p, Some((r, s)))),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        product_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+    validate_accounts(
+        program_id,
+        &[
+            (permissions_account, AccountCheck::Writable),
+            (source_account, AccountCheck::Readable),
+        ],
+    )?;
+
+    // The configuration of the source is read first, so that the source isn't borrowed while the
+    // new price account is initialized
+    let source_data = load_checked::<PriceAccount>(source_account, hdr.version)?;
+    pyth_assert(
+        source_data.product_account == *product_account.key,
+        ProgramError::InvalidArgument,
+    )?;
+    let exponent = source_data.exponent;
+    let price_type = source_data.price_type;
+    let min_pub = source_data.min_pub_;
+    let max_latency = source_data.max_latency_;
+    let flags = source_data.flags;
+    let publishers: Vec<Pubkey> = source_data.comp_[..try_convert::<u32, usize>(source_data.num_)?]
+        .iter()
+        .map(|component| component.pub_)
+        .collect();
+    drop(source_data);
+
+    let mut product_data = load_checked::<ProductAccount>(product_account, hdr.version)?;
+
+    let mut price_data = PriceAccount::initialize(price_account, hdr.version)?;
+    price_data.exponent = exponent;
+    price_data.price_type = price_type;
+    price_data.product_account = *product_account.key;
+    price_data.next_price_account = product_data.first_price_account;
+    price_data.min_pub_ = min_pub;
+    price_data.max_latency_ = max_latency;
+    price_data.feed_index = reserve_new_price_feed_index(permissions_account)?;
+
+    // The new price account has never published a message, like in `add_price`, and doesn't
+    // inherit the deprecation, the aggregation state or the community listing of the source
+    price_data.flags = flags
+        - PriceAccountFlags::MESSAGE_BUFFER_CLEARED
+        - PriceAccountFlags::DEPRECATED
+        - PriceAccountFlags::AGGREGATION_IN_PROGRESS
+        - PriceAccountFlags::COMMUNITY;
+    if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
+        price_data
+            .flags
+            .insert(PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+
+    price_data.num_ = try_convert(publishers.len())?;
+    for (component, publisher) in price_data.comp_.iter_mut().zip(publishers) {
+        component.pub_ = publisher;
+    }
+
+    product_data.first_price_account = *price_account.key;
+    let feed_index = price_data.feed_index;
+    drop(price_data);
+    drop(product_data);
+
+    if let Some((registry_account, system_program)) = maybe_registry {
+        register_price_feed_index(
+            program_id,
+            funding_account,
+            registry_account,
+            system_program,
+            feed_index,
+            price_account.key,
+            hdr.version,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use {
+    crate::{
+        accounts::{
+            get_extension_mut,
+            PriceAccount,
+            QuoteConversion,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_aggregation_complete,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Convert the latest aggregate of the price account to the quote currency of its
+/// `QuoteConversion` with the latest aggregate of the conversion feed. The converted aggregate is
+/// trading at the slot of the older aggregate when both are trading and the result fits,
+/// otherwise only its status is set to unknown and the last converted price is kept.
+// account[0] price account         [writable]
+// account[1] conversion feed       []
+pub fn convert_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (price_account, conversion_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Writable),
+            (conversion_account, AccountCheck::Readable),
+        ],
+    )?;
+
+    let (conversion_agg, conversion_exponent) = {
+        let conversion_data = load_checked::<PriceAccount>(conversion_account, cmd.version)?;
+        check_aggregation_complete(&conversion_data)?;
+        (conversion_data.agg_, conversion_data.exponent)
+    };
+    let (agg, exponent) = {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.version)?;
+        check_aggregation_complete(&price_data)?;
+        (price_data.agg_, price_data.exponent)
+    };
+
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let conversion = get_extension_mut::<QuoteConversion>(&mut price_account_data)?
+        .filter(|conversion| conversion.conversion_feed == *conversion_account.key)
+        .ok_or(OracleError::InvalidConversionFeed)?;
+
+    let converted =
+        if agg.status_ == PC_STATUS_TRADING && conversion_agg.status_ == PC_STATUS_TRADING {
+            conversion.convert(&agg, exponent, &conversion_agg, conversion_exponent)
+        } else {
+            None
+        };
+    match converted {
+        Some((price, conf)) => {
+            conversion.converted.price_ = price;
+            conversion.converted.conf_ = conf;
+            conversion.converted.status_ = PC_STATUS_TRADING;
+            conversion.converted.pub_slot_ = agg.pub_slot_.min(conversion_agg.pub_slot_);
+        }
+        None => conversion.converted.status_ = PC_STATUS_UNKNOWN,
+    }
+
+    Ok(())
+}
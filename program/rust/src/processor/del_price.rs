@@ -1,11 +1,20 @@
 use {
+    super::{
+        put_all_messages,
+        unregister_price_feed_index,
+        MessageBufferAccounts,
+    },
     crate::{
         accounts::{
+            get_extension,
             PriceAccount,
             ProductAccount,
+            PythOracleSerialize,
+            UpdateCount,
         },
         deserialize::{
             load,
+            load_account_data,
             load_checked,
         },
         instruction::CommandHeader,
@@ -18,9 +27,11 @@ use {
     },
     solana_program::{
         account_info::AccountInfo,
+        clock::Clock,
         entrypoint::ProgramResult,
         program_error::ProgramError,
         pubkey::Pubkey,
+        sysvar::Sysvar,
     },
 };
 
@@ -31,6 +42,16 @@ use {
 // account[1] product account       [signer writable]
 // account[2] price account         [signer writable]
 // account[3] permissions account   []
+// account[4] feed registry         [writable] (optional)
+// account[4 or 5] buffer program   [] (optional, the message buffer program)
+// account[5 or 6] whitelist        [] (optional)
+// account[6 or 7] oracle auth PDA  [] (optional, [UPD_PRICE_WRITE_SEED, buffer program])
+// account[7 or 8] message buffer   [writable] (optional)
+/// When the feed registry is passed, the entry of the price account is cleared.
+///
+/// When the message buffer accounts are passed, the last aggregate of the feed is sent to the
+/// message buffer as a `FeedTombstoneMessage`, replacing the price messages of the feed.
+///
 /// Warning: This function is dangerous and will break any programs that depend on the deleted
 /// price account!
 pub fn del_price(
@@ -38,8 +59,42 @@ pub fn del_price(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, product_account, price_account, permissions_account) = match accounts {
-        [w, x, y, p] => Ok((w, x, y, p)),
+    let (
+        funding_account,
+        product_account,
+        price_account,
+        permissions_account,
+        maybe_registry,
+        maybe_accumulator_accounts,
+    ) = match accounts {
+        [w, x, y, p] => Ok((w, x, y, p, None, None)),
+        [w, x, y, p, r] => Ok((w, x, y, p, Some(r), None)),
+        [w, x, y, p, a, b, c, d] => Ok((
+            w,
+            x,
+            y,
+            p,
+            None,
+            Some(MessageBufferAccounts {
+                program_id:          a,
+                whitelist:           b,
+                oracle_auth_pda:     c,
+                message_buffer_data: d,
+            }),
+        )),
+        [w, x, y, p, r, a, b, c, d] => Ok((
+            w,
+            x,
+            y,
+            p,
+            Some(r),
+            Some(MessageBufferAccounts {
+                program_id:          a,
+                whitelist:           b,
+                oracle_auth_pda:     c,
+                message_buffer_data: d,
+            }),
+        )),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -61,7 +116,7 @@ pub fn del_price(
         cmd_args,
     )?;
 
-    {
+    let feed_index = {
         let mut product_data = load_checked::<ProductAccount>(product_account, cmd_args.version)?;
         let price_data = load_checked::<PriceAccount>(price_account, cmd_args.version)?;
         pyth_assert(
@@ -75,6 +130,37 @@ pub fn del_price(
         )?;
 
         product_data.first_price_account = price_data.next_price_account;
+        price_data.feed_index
+    };
+
+    if let Some(registry_account) = maybe_registry {
+        unregister_price_feed_index(
+            program_id,
+            registry_account,
+            feed_index,
+            price_account.key,
+            cmd_args.version,
+        )?;
+    }
+
+    if let Some(accumulator_accounts) = maybe_accumulator_accounts {
+        let message = {
+            let price_account_data = price_account.try_borrow_data()?;
+            let num_updates = get_extension::<UpdateCount>(&price_account_data)?
+                .map_or(0, |update_count| update_count.updates);
+            load_account_data::<PriceAccount>(&price_account_data)?.as_tombstone_message(
+                price_account.key,
+                &Clock::get()?,
+                num_updates,
+            )
+        };
+        put_all_messages(
+            program_id,
+            accounts,
+            &accumulator_accounts,
+            price_account.key,
+            vec![message.to_bytes()],
+        )?;
     }
 
     // Zero out the balance of the price account to delete it.
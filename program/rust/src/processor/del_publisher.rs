@@ -1,8 +1,12 @@
 use {
     crate::{
         accounts::{
+            get_extension_mut,
+            invalidate_quote_order,
+            realign_publisher_entries,
             PriceAccount,
             PriceComponent,
+            PublisherStake,
             PythAccount,
         },
         deserialize::{
@@ -64,7 +68,8 @@ pub fn del_publisher(
 
     for i in 0..(try_convert::<u32, usize>(price_data.num_)?) {
         if cmd_args.publisher == price_data.comp_[i].pub_ {
-            for j in i + 1..(try_convert::<u32, usize>(price_data.num_)?) {
+            let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+            for j in i + 1..num_comps {
                 price_data.comp_[j - 1] = price_data.comp_[j];
             }
             price_data.num_ -= 1;
@@ -75,8 +80,15 @@ pub fn del_publisher(
                 size_of::<PriceComponent>(),
             );
             price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
-            return Ok(());
+            drop(price_data);
+            if let Some(publisher_stake) =
+                get_extension_mut::<PublisherStake>(&mut price_account.try_borrow_mut_data()?)?
+            {
+                publisher_stake.remove(i, num_comps);
+            }
+            invalidate_quote_order(price_account)?;
+            return realign_publisher_entries(price_account);
         }
     }
-    Err(ProgramError::InvalidArgument)
+    Err(OracleError::PublisherNotFound.into())
 }
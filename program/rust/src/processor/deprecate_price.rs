@@ -0,0 +1,95 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceSuccessor,
+        },
+        deserialize::{
+            load,
+            load_checked,
+            OracleAccount,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Flag the feed as `DEPRECATED` and store the price account replacing it in its
+/// `PriceSuccessor` extension, so that consumers can follow a symbol migration. The feed keeps
+/// aggregating while consumers move. The successor can't be deprecated itself, which keeps the
+/// redirects free of cycles, but a feed can be deprecated again to point it elsewhere. The price
+/// account is grown to hold the extension the first time, so it must already hold enough
+/// lamports to be rent exempt at its new size.
+// account[0] funding account           [signer writable]
+// account[1] price account             [signer writable]
+// account[2] permissions account       []
+// account[3] successor price account   []
+pub fn deprecate_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, successor_account) = match accounts {
+        [x, y, p, s] => Ok((x, y, p, s)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+    validate_accounts(program_id, &[(successor_account, AccountCheck::Readable)])?;
+    pyth_assert(
+        successor_account.key != price_account.key,
+        ProgramError::InvalidArgument,
+    )?;
+
+    {
+        let successor_data = load_checked::<PriceAccount>(successor_account, hdr.version)?;
+        pyth_assert(
+            !successor_data.flags.contains(PriceAccountFlags::DEPRECATED),
+            ProgramError::InvalidArgument,
+        )?;
+    }
+
+    // Validate that price_account contains the appropriate account header
+    let price = OracleAccount::<PriceAccount>::new(price_account, hdr.version)?;
+
+    reserve_extension_space::<PriceSuccessor>(price_account)?;
+    init_extension::<PriceSuccessor>(&mut price_account.try_borrow_mut_data()?)?.successor =
+        *successor_account.key;
+    price
+        .load_mut()?
+        .flags
+        .insert(PriceAccountFlags::DEPRECATED);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            ReadStats,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Start counting the reads of the feed by `GetPrice`, see `ReadStats`. Enabling it again keeps
+/// the counts. The price account is grown to hold the `ReadStats` extension the first time, so it
+/// must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn enable_read_stats(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    reserve_extension_space::<ReadStats>(price_account)?;
+    init_extension::<ReadStats>(&mut price_account.try_borrow_mut_data()?)?;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            AggregationCounts,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            AggregationCountsSummary,
+            CommandHeader,
+        },
+        utils::{
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+};
+
+/// Return the number of slots with a trading aggregate over the window of the price account
+/// that ends at the current slot, as an `AggregationCountsSummary`. Monitoring compares it to the
+/// number of slots of the window to notice a feed degrading before it goes stale.
+// account[0] price account         []
+// account[1] sysvar_clock account  []
+pub fn get_aggregation_counts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (price_account, clock_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    let summary = get_aggregation_counts_summary(&price_account.try_borrow_data()?, clock.slot)?;
+    set_return_data(bytemuck::bytes_of(&summary));
+
+    Ok(())
+}
+
+/// Summarize the aggregation counts of the price account stored in `data` at `slot`.
+pub fn get_aggregation_counts_summary(
+    data: &[u8],
+    slot: u64,
+) -> Result<AggregationCountsSummary, OracleError> {
+    let (aggregated_slots, window_slots) = match get_extension::<AggregationCounts>(data)? {
+        Some(counts) => counts.window_counts(slot),
+        None => (0, 0),
+    };
+    Ok(AggregationCountsSummary {
+        slot,
+        window_slots,
+        aggregated_slots,
+    })
+}
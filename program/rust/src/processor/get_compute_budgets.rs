@@ -0,0 +1,52 @@
+use {
+    crate::{
+        deserialize::load,
+        instruction::{
+            CommandHeader,
+            ComputeBudgetEntry,
+            OracleCommand,
+        },
+        OracleError,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+    },
+};
+
+/// List the compute budget of every instruction of the program so that publishers and governance
+/// tooling can request the right amount of compute units. The return data is the list of
+/// `ComputeBudgetEntry`, in the order of the commands. Removed instructions aren't listed.
+pub fn get_compute_budgets(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match accounts {
+        [] => Ok(()),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let _hdr = load::<CommandHeader>(instruction_data)?;
+
+    set_return_data(bytemuck::cast_slice(&get_compute_budget_entries()));
+
+    Ok(())
+}
+
+/// The compute budget of every instruction of the program, see `OracleCommand::compute_budget`.
+pub fn get_compute_budget_entries() -> Vec<ComputeBudgetEntry> {
+    (0..)
+        .map_while(OracleCommand::from_i32)
+        .filter_map(|command| {
+            let compute_units = command.compute_budget()?;
+            Some(ComputeBudgetEntry {
+                command: command as i32,
+                compute_units,
+            })
+        })
+        .collect()
+}
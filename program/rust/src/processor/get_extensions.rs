@@ -0,0 +1,80 @@
+use {
+    crate::{
+        accounts::{
+            iter_extensions,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            CommandHeader,
+            ExtensionEntry,
+        },
+        utils::{
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program::{
+            set_return_data,
+            MAX_RETURN_DATA,
+        },
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// List the extensions enabled on a price account so that consumers can feature-detect them at
+/// runtime. The return data is the list of `ExtensionEntry`, in the order they are stored in the
+/// account. Extensions unknown to this program version are listed as well.
+// account[0] price account         []
+pub fn get_extensions(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let price_account = match accounts {
+        [x] => Ok(x),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(program_id, &[(price_account, AccountCheck::Readable)])?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    let entries = get_extension_entries(&price_account.try_borrow_data()?)?;
+    pyth_assert(
+        entries.len() * size_of::<ExtensionEntry>() <= MAX_RETURN_DATA,
+        OracleError::InvalidExtension.into(),
+    )?;
+    set_return_data(bytemuck::cast_slice(&entries));
+
+    Ok(())
+}
+
+/// List the extensions of the price account stored in `data`.
+pub fn get_extension_entries(data: &[u8]) -> Result<Vec<ExtensionEntry>, OracleError> {
+    iter_extensions(data)
+        .map(|info| {
+            let info = info?;
+            Ok(ExtensionEntry {
+                extension_type: info.extension_type,
+                offset:         try_convert(info.offset)?,
+                length:         try_convert(info.length)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,102 @@
+use {
+    crate::{
+        accounts::{
+            get_extension_mut,
+            PriceAccount,
+            ReadStats,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            CommandHeader,
+            PriceSummary,
+        },
+        utils::{
+            check_aggregation_complete,
+            check_reader_allowed,
+            check_valid_writable_account,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+};
+
+/// Return the latest price and the EMA of the feed as a `PriceSummary`. Feeds holding the
+/// `ReadStats` extension count the read in the current epoch, so the price account must be
+/// writable for them. A permissioned feed fails with `ReaderNotAllowed` unless a reader listed in
+/// its access control account signs, like in `AssertPriceCondition`.
+// account[0] price account           [] (writable if the feed counts its reads)
+// account[1] sysvar_clock account    []
+// account[2] reader                  [signer] (permissioned feeds)
+// account[3] access control account  [] (permissioned feeds)
+pub fn get_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (price_account, clock_account, reader_accounts) = match accounts {
+        [x, y] => Ok((x, y, None)),
+        [x, y, r, a] => Ok((x, y, Some((r, a)))),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    check_reader_allowed(program_id, price_account, reader_accounts, hdr.version)?;
+
+    let summary = {
+        let price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+        check_aggregation_complete(&price_data)?;
+        get_price_summary(&price_data)
+    };
+
+    if let Some(read_stats) =
+        get_extension_mut::<ReadStats>(&mut price_account.try_borrow_mut_data()?)?
+    {
+        check_valid_writable_account(program_id, price_account)?;
+        read_stats.record_read(clock.epoch);
+    }
+
+    set_return_data(bytemuck::bytes_of(&summary));
+
+    Ok(())
+}
+
+/// Summarize the latest price and the EMA of `price_data`.
+pub fn get_price_summary(price_data: &PriceAccount) -> PriceSummary {
+    let latest_price = price_data.get_latest_price();
+    let ema_price = price_data.get_ema_price();
+    PriceSummary {
+        price:        latest_price.price,
+        conf:         latest_price.conf,
+        ema_price:    ema_price.price,
+        ema_conf:     ema_price.conf,
+        publish_slot: latest_price.publish_slot,
+        publish_time: latest_price.publish_time,
+        exponent:     latest_price.exponent,
+        unused_:      0,
+    }
+}
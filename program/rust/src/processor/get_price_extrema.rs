@@ -0,0 +1,93 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            PriceAccount,
+            PriceExtrema,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            CommandHeader,
+            PriceExtremaSummary,
+        },
+        utils::{
+            check_aggregation_complete,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+};
+
+/// Return the lowest and highest trading aggregates of the price account over the window that
+/// ends at the current slot, as a `PriceExtremaSummary`.
+// account[0] price account         []
+// account[1] sysvar_clock account  []
+pub fn get_price_extrema(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (price_account, clock_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    {
+        let price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+        check_aggregation_complete(&price_data)?;
+    }
+
+    let summary = get_price_extrema_summary(&price_account.try_borrow_data()?, clock.slot)?;
+    set_return_data(bytemuck::bytes_of(&summary));
+
+    Ok(())
+}
+
+/// Summarize the extrema of the price account stored in `data` at `slot`.
+pub fn get_price_extrema_summary(
+    data: &[u8],
+    slot: u64,
+) -> Result<PriceExtremaSummary, OracleError> {
+    let (extrema, window_slots) = match get_extension::<PriceExtrema>(data)? {
+        Some(extrema) => extrema.window_extrema(slot),
+        None => (None, 0),
+    };
+    let mut summary = PriceExtremaSummary {
+        slot,
+        window_slots,
+        ..PriceExtremaSummary::zeroed()
+    };
+    if let Some((min_price, max_price)) = extrema {
+        summary.min_price = min_price;
+        summary.max_price = max_price;
+        summary.traded = 1;
+    }
+    Ok(summary)
+}
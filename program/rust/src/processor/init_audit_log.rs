@@ -0,0 +1,87 @@
+use {
+    crate::{
+        accounts::{
+            AuditLogAccount,
+            PythAccount,
+            AUDIT_LOG_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Create the audit log at the address of `AUDIT_LOG_SEED`, paid by the funding account. Only the
+/// master authority can create it, the governance instructions listing it afterwards append a
+/// record to it. Sending it again once the audit log exists leaves it unchanged.
+// account[0] funding account       [signer writable]
+// account[1] audit log             [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+pub fn init_audit_log(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, audit_log_account, permissions_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (audit_log_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[AUDIT_LOG_SEED.as_bytes()], program_id);
+    pyth_assert(
+        audit_log_pda_address == *audit_log_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    AuditLogAccount::initialize_pda(
+        audit_log_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[AUDIT_LOG_SEED.as_bytes(), &[bump_seed]],
+        cmd.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        audit_log_account,
+        funding_account,
+        permissions_account,
+        cmd,
+    )?;
+
+    // Validate that audit_log_account contains the appropriate account header
+    let _audit_log_data = load_checked::<AuditLogAccount>(audit_log_account, cmd.version)?;
+    Ok(())
+}
@@ -0,0 +1,102 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            ConsistencyCheckAccount,
+            ConsistencyFlag,
+            ConsistencyKind,
+            PriceAccount,
+            PythAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::InitConsistencyCheckArgs,
+        utils::{
+            check_permissioned_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Initialize a consistency check linking the price accounts in the order of its relation, see
+/// `ConsistencyKind`. Every price account is grown to hold the `ConsistencyFlag` extension the
+/// first time, so it must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] consistency check     [signer writable]
+// account[2] permissions account   []
+// account[3..] price accounts      [writable]
+pub fn init_consistency_check(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<InitConsistencyCheckArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<InitConsistencyCheckArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, check_account, permissions_account, price_accounts) = match accounts {
+        [x, y, z, feeds @ ..] => Ok((x, y, z, feeds)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let kind = ConsistencyKind::from_u32(cmd.kind).ok_or(ProgramError::InvalidArgument)?;
+    pyth_assert(
+        price_accounts.len() == kind.num_feeds(),
+        OracleError::InvalidNumberOfAccounts.into(),
+    )?;
+
+    check_permissioned_funding_account(
+        program_id,
+        check_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    for (i, price_account) in price_accounts.iter().enumerate() {
+        validate_accounts(program_id, &[(price_account, AccountCheck::Writable)])?;
+        // A feed listed twice would always be consistent with itself
+        pyth_assert(
+            !price_accounts[..i]
+                .iter()
+                .any(|other| other.key == price_account.key),
+            ProgramError::InvalidArgument,
+        )?;
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    {
+        let mut check_data =
+            ConsistencyCheckAccount::initialize(check_account, cmd.header.version)?;
+        check_data.kind = cmd.kind;
+        check_data.tolerance_bps = cmd.tolerance_bps;
+        for (feed, price_account) in check_data.feeds.iter_mut().zip(price_accounts) {
+            *feed = *price_account.key;
+        }
+    }
+
+    for price_account in price_accounts {
+        reserve_extension_space::<ConsistencyFlag>(price_account)?;
+        init_extension::<ConsistencyFlag>(&mut price_account.try_borrow_mut_data()?)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use {
+    crate::{
+        accounts::{
+            MappingAccountV2,
+            PythAccount,
+        },
+        deserialize::load,
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+    },
+};
+
+/// Initialize a hash-indexed mapping account. The table takes the rest of the account, which
+/// must have room for at least one entry, see `MappingAccountV2::space`.
+// account[0] funding account       [signer writable]
+// account[1] mapping account v2    [signer writable]
+// account[2] permissions account   []
+pub fn init_mapping_v2(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, fresh_mapping_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        fresh_mapping_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    let capacity = MappingAccountV2::capacity_for(fresh_mapping_account.data_len());
+    pyth_assert(capacity > 0, OracleError::AccountTooSmall.into())?;
+
+    let mut mapping_data = MappingAccountV2::initialize(fresh_mapping_account, hdr.version)?;
+    mapping_data.capacity = try_convert(capacity)?;
+    mapping_data.header.size = try_convert(MappingAccountV2::space(capacity))?;
+
+    Ok(())
+}
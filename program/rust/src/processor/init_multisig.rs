@@ -0,0 +1,79 @@
+use {
+    crate::{
+        accounts::{
+            MultisigAccount,
+            PythAccount,
+            MAX_MULTISIG_SIGNERS,
+        },
+        deserialize::load,
+        instruction::InitMultisigArgs,
+        utils::{
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Initialize a multisig account. Anyone can create one, it only gets authority over the oracle
+/// once the upgrade authority sets it in the permissions.
+// account[0] funding account       [signer writable]
+// account[1] multisig account      [signer writable]
+pub fn init_multisig(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<InitMultisigArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<InitMultisigArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, fresh_multisig_account) = match accounts {
+        [x, y] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (fresh_multisig_account, AccountCheck::Writable),
+        ],
+    )?;
+
+    let num_signers = usize::from(cmd.num_signers);
+    pyth_assert(
+        cmd.threshold > 0
+            && cmd.threshold <= cmd.num_signers
+            && num_signers <= MAX_MULTISIG_SIGNERS,
+        ProgramError::InvalidArgument,
+    )?;
+    // Each signer counts once, a key listed twice would overstate the size of the multisig
+    let signers = &cmd.signers[..num_signers];
+    pyth_assert(
+        signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| !signers[..i].contains(signer)),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let mut multisig_data =
+        MultisigAccount::initialize(fresh_multisig_account, cmd.header.version)?;
+    multisig_data.threshold = cmd.threshold;
+    multisig_data.num_signers = cmd.num_signers;
+    multisig_data.signers[..num_signers].copy_from_slice(signers);
+
+    Ok(())
+}
@@ -1,15 +1,31 @@
 use {
+    super::{
+        register_price_feed_index,
+        set_average_mode::{
+            apply_average_mode,
+            check_average_mode,
+        },
+    },
     crate::{
         accounts::{
+            get_extension,
+            invalidate_quote_order,
+            AverageMode,
             PriceAccount,
+            PriceAccountFlags,
             PriceEma,
             PriceInfo,
+            SlotWeightedTwap,
         },
         deserialize::{
             load,
             load_checked,
         },
-        instruction::InitPriceArgs,
+        instruction::{
+            InitPriceArgs,
+            InitPriceConfigArgs,
+            INIT_PRICE_CONFIG_VERSION,
+        },
         utils::{
             check_exponent_range,
             check_permissioned_funding_account,
@@ -29,10 +45,15 @@ use {
     std::mem::size_of,
 };
 
-/// (Re)initialize price account
+/// (Re)initialize price account. When the feed registry is passed, the price account is
+/// (re)registered under its feed index, which registers price accounts created before the
+/// registry existed. Arguments holding an `InitPriceConfig` configure the feed in the same
+/// instruction, otherwise it keeps its configuration.
 // account[0] funding account       [signer writable]
 // account[1] new price account     [signer writable]
 // account[2] permissions account   []
+// account[3] feed registry         [writable] (optional)
+// account[4] system program        [] (optional)
 pub fn init_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -42,8 +63,26 @@ pub fn init_price(
 
     check_exponent_range(cmd_args.exponent)?;
 
-    let (funding_account, price_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
+    // The flags describing how the feed is published, the other ones are kept
+    let configurable_flags = PriceAccountFlags::ALLOW_ZERO_CI | PriceAccountFlags::ISOLATED_UPDATES;
+    let config = if instruction_data.len() >= size_of::<InitPriceConfigArgs>() {
+        let config = load::<InitPriceConfigArgs>(instruction_data)?.config;
+        let flags = PriceAccountFlags::from_bits(config.flags)
+            .filter(|flags| configurable_flags.contains(*flags))
+            .ok_or(ProgramError::InvalidArgument)?;
+        pyth_assert(
+            config.version == INIT_PRICE_CONFIG_VERSION,
+            ProgramError::InvalidArgument,
+        )?;
+        let average_mode = check_average_mode(config.average_mode, config.twap_window_slots)?;
+        Some((config, flags, average_mode))
+    } else {
+        None
+    };
+
+    let (funding_account, price_account, permissions_account, maybe_registry) = match accounts {
+        [x, y, p] => Ok((x, y, p, None)),
+        [x, y, p, r, s] => Ok((x, y, p, Some((r, s)))),
         _ => Err(OracleError::InvalidNumberOfAccounts),
     }?;
 
@@ -60,7 +99,7 @@ pub fn init_price(
     let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
     pyth_assert(
         price_data.price_type == cmd_args.price_type,
-        ProgramError::InvalidArgument,
+        OracleError::InvalidPriceType.into(),
     )?;
 
     price_data.exponent = cmd_args.exponent;
@@ -100,5 +139,39 @@ pub fn init_price(
         );
     }
 
+    if let Some((config, flags, _)) = config {
+        price_data.min_pub_ = config.min_pub;
+        price_data.max_latency_ = config.max_latency;
+        price_data.flags.remove(configurable_flags);
+        price_data.flags.insert(flags);
+    }
+
+    let feed_index = price_data.feed_index;
+    drop(price_data);
+    invalidate_quote_order(price_account)?;
+
+    // Feeds publishing the EMA only need the extension if they already hold it
+    if let Some((config, _, average_mode)) = config {
+        let has_twap = matches!(
+            get_extension::<SlotWeightedTwap>(&price_account.try_borrow_data()?),
+            Ok(Some(_))
+        );
+        if average_mode != AverageMode::Ema || config.twap_window_slots != 0 || has_twap {
+            apply_average_mode(price_account, average_mode, config.twap_window_slots)?;
+        }
+    }
+
+    if let Some((registry_account, system_program)) = maybe_registry {
+        register_price_feed_index(
+            program_id,
+            funding_account,
+            registry_account,
+            system_program,
+            feed_index,
+            price_account.key,
+            cmd_args.header.version,
+        )?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,155 @@
+use {
+    super::{
+        register_price_feed_index,
+        reserve_new_price_feed_index,
+    },
+    crate::{
+        accounts::{
+            find_price_pda,
+            FeedKind,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+            PRICE_SEED,
+        },
+        c_oracle_header::PRICE_ACCOUNT_DEFAULT_MIN_PUB,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::InitPricePdaArgs,
+        utils::{
+            check_exponent_range,
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Add a new price account to a product account like `AddPrice`, at the PDA
+/// `[PRICE_SEED, product_public_key, price_type]` instead of a keypair, see `find_price_pda`. A
+/// product has at most one such price account of each `FeedKind`.
+// account[0] funding account        [signer writable]
+// account[1] product account        [writable]
+// account[2] price account          [writable]
+// account[3] permissions account    [writable]
+// account[4] system program         []
+// account[5] feed registry          [writable] (optional)
+pub fn init_price_pda(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<InitPricePdaArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<InitPricePdaArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    check_exponent_range(cmd_args.exponent)?;
+    let feed_kind = FeedKind::from_u32(cmd_args.price_type).ok_or(OracleError::InvalidPriceType)?;
+
+    let (
+        funding_account,
+        product_account,
+        price_account,
+        permissions_account,
+        system_program,
+        maybe_registry,
+    ) = match accounts {
+        [u, v, w, x, y] => Ok((u, v, w, x, y, None)),
+        [u, v, w, x, y, z] => Ok((u, v, w, x, y, Some(z))),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        product_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+    validate_accounts(
+        program_id,
+        &[
+            (permissions_account, AccountCheck::Writable),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+
+    let (price_pda_address, bump_seed) = find_price_pda(program_id, product_account.key, feed_kind);
+    pyth_assert(
+        price_pda_address == *price_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    // The product already has a price account of this kind
+    pyth_assert(
+        price_account.data_len() == 0,
+        OracleError::InvalidFreshAccount.into(),
+    )?;
+
+    let mut product_data =
+        load_checked::<ProductAccount>(product_account, cmd_args.header.version)?;
+
+    PriceAccount::initialize_pda(
+        price_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[
+            PRICE_SEED.as_bytes(),
+            &product_account.key.to_bytes(),
+            &cmd_args.price_type.to_le_bytes(),
+            &[bump_seed],
+        ],
+        cmd_args.header.version,
+    )?;
+    validate_accounts(program_id, &[(price_account, AccountCheck::Writable)])?;
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    price_data.exponent = cmd_args.exponent;
+    price_data.price_type = cmd_args.price_type;
+    price_data.product_account = *product_account.key;
+    price_data.next_price_account = product_data.first_price_account;
+    price_data.min_pub_ = PRICE_ACCOUNT_DEFAULT_MIN_PUB;
+    price_data.feed_index = reserve_new_price_feed_index(permissions_account)?;
+
+    if !cfg!(feature = "no-default-accumulator-v2") {
+        price_data
+            .flags
+            .insert(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    }
+
+    product_data.first_price_account = *price_account.key;
+    let feed_index = price_data.feed_index;
+    drop(price_data);
+    drop(product_data);
+
+    if let Some(registry_account) = maybe_registry {
+        register_price_feed_index(
+            program_id,
+            funding_account,
+            registry_account,
+            system_program,
+            feed_index,
+            price_account.key,
+            cmd_args.header.version,
+        )?;
+    }
+
+    Ok(())
+}
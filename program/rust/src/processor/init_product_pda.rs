@@ -0,0 +1,120 @@
+use {
+    crate::{
+        accounts::{
+            find_product_pda,
+            get_instruction_metadata,
+            update_product_metadata,
+            validate_product_metadata,
+            MappingAccount,
+            ProductAccount,
+            PythAccount,
+            PRODUCT_SEED,
+        },
+        c_oracle_header::PC_MAP_TABLE_SIZE,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::{
+        size_of,
+        size_of_val,
+    },
+};
+
+/// Initialize and add a new product account like `AddProduct`, at the PDA
+/// `[PRODUCT_SEED, symbol]` instead of a keypair, see `find_product_pda`. The symbol is read from
+/// the metadata following the command header, which must match the schema checked by
+/// `validate_product_metadata`. Changing the symbol later with `UpdProduct` doesn't move the
+/// account.
+// account[0] funding account       [signer writable]
+// account[1] mapping account       [signer writable]
+// account[2] product account       [writable]
+// account[3] permissions account   []
+// account[4] system program        []
+pub fn init_product_pda(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (
+        funding_account,
+        tail_mapping_account,
+        product_account,
+        permissions_account,
+        system_program,
+    ) = match accounts {
+        [v, w, x, y, z] => Ok((v, w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        tail_mapping_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+    validate_accounts(program_id, &[(system_program, AccountCheck::SystemProgram)])?;
+
+    validate_product_metadata(instruction_data)?;
+    let symbol = get_instruction_metadata(instruction_data, "symbol")?
+        .ok_or(OracleError::MissingProductMetadataKey)?;
+    let (product_pda_address, bump_seed) =
+        find_product_pda(program_id, symbol).ok_or(ProgramError::InvalidArgument)?;
+    pyth_assert(
+        product_pda_address == *product_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    // A symbol can only be listed once
+    pyth_assert(
+        product_account.data_len() == 0,
+        OracleError::InvalidFreshAccount.into(),
+    )?;
+
+    let mut mapping_data = load_checked::<MappingAccount>(tail_mapping_account, hdr.version)?;
+    // The mapping account must have free space to add the product account
+    pyth_assert(
+        mapping_data.number_of_products < PC_MAP_TABLE_SIZE,
+        OracleError::MappingAccountFull.into(),
+    )?;
+
+    ProductAccount::initialize_pda(
+        product_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[PRODUCT_SEED.as_bytes(), symbol, &[bump_seed]],
+        hdr.version,
+    )?;
+    validate_accounts(program_id, &[(product_account, AccountCheck::Writable)])?;
+
+    let current_index: usize = try_convert(mapping_data.number_of_products)?;
+    mapping_data.products_list[current_index] = *product_account.key;
+    mapping_data.number_of_products += 1;
+    mapping_data.header.size = try_convert::<_, u32>(
+        size_of::<MappingAccount>() - size_of_val(&mapping_data.products_list),
+    )? + mapping_data.number_of_products
+        * try_convert::<_, u32>(size_of::<Pubkey>())?;
+
+    update_product_metadata(instruction_data, product_account, hdr.version)
+}
@@ -0,0 +1,101 @@
+use {
+    crate::{
+        accounts::{
+            get_product_metadata,
+            symbol_hash,
+            MappingAccount,
+            MappingAccountV2,
+            ProductAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::MigrateMappingArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Index products listed in a mapping account in a hash-indexed mapping account, under the hash
+/// of their symbol. The product accounts are the ones listed from position `start` of the
+/// mapping account on, so that a long list is migrated over several transactions. Products
+/// without a symbol are skipped, products already indexed are left as they are, so that the
+/// instruction can be sent again for products listed later.
+// account[0] funding account       [signer writable]
+// account[1] mapping account v2    [signer writable]
+// account[2] permissions account   []
+// account[3] mapping account       []
+// account[4..] product accounts    []
+pub fn migrate_mapping(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<MigrateMappingArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<MigrateMappingArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, mapping_v2_account, permissions_account, mapping_account, products) =
+        match accounts {
+            [w, x, y, z, products @ ..] => Ok((w, x, y, z, products)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        mapping_v2_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+    validate_accounts(program_id, &[(mapping_account, AccountCheck::Readable)])?;
+
+    let mapping_data = load_checked::<MappingAccount>(mapping_account, cmd.header.version)?;
+    let start: usize = try_convert(cmd.start)?;
+    let listed = mapping_data
+        .products()?
+        .get(start..start.saturating_add(products.len()))
+        .ok_or(ProgramError::InvalidArgument)?;
+    pyth_assert(
+        listed.iter().eq(products.iter().map(|product| product.key)),
+        ProgramError::InvalidArgument,
+    )?;
+
+    {
+        // Validate that mapping_v2_account contains the appropriate account header
+        let _mapping_v2_data =
+            load_checked::<MappingAccountV2>(mapping_v2_account, cmd.header.version)?;
+    }
+    let mut mapping_v2_data = mapping_v2_account.try_borrow_mut_data()?;
+    for product in products {
+        validate_accounts(program_id, &[(product, AccountCheck::Readable)])?;
+        {
+            let _product_data = load_checked::<ProductAccount>(product, cmd.header.version)?;
+        }
+        let product_data = product.try_borrow_data()?;
+        if let Some(symbol) = get_product_metadata(&product_data, "symbol")? {
+            MappingAccountV2::insert(&mut mapping_v2_data, &symbol_hash(symbol), product.key)?;
+        }
+    }
+
+    Ok(())
+}
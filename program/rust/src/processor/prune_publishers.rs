@@ -0,0 +1,132 @@
+use {
+    crate::{
+        accounts::{
+            invalidate_quote_order,
+            realign_publisher_entries,
+            PriceAccount,
+            PriceComponent,
+            PublisherRegistryAccount,
+            PythAccount,
+            PUBLISHER_REGISTRY_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::PrunePublishersArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::bytes_of_mut,
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        program_memory::sol_memset,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Remove the components of the publishers that haven't published for more than
+/// `max_idle_slots`, and of the publishers that are no longer approved in the publisher registry
+/// if it's passed. The remaining components keep their order, so the list stays sorted.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+// account[3] sysvar_clock account  []
+// account[4] publisher registry    [] (optional)
+pub fn prune_publishers(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<PrunePublishersArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<PrunePublishersArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, clock_account, maybe_registry) =
+        match accounts {
+            [x, y, p, c] => Ok((x, y, p, c, None)),
+            [x, y, p, c, r] => Ok((x, y, p, c, Some(r))),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    validate_accounts(
+        program_id,
+        &[(clock_account, AccountCheck::Sysvar(clock::id()))],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+    let registry_data = match maybe_registry {
+        Some(registry_account) => {
+            let (registry_pda_address, _) =
+                Pubkey::find_program_address(&[PUBLISHER_REGISTRY_SEED.as_bytes()], program_id);
+            pyth_assert(
+                registry_pda_address == *registry_account.key,
+                OracleError::InvalidPda.into(),
+            )?;
+            validate_accounts(program_id, &[(registry_account, AccountCheck::Readable)])?;
+            Some(registry_account.try_borrow_data()?)
+        }
+        None => None,
+    };
+    let approved_publishers = match registry_data.as_ref() {
+        Some(data) => Some(PublisherRegistryAccount::publishers(data)?),
+        None => None,
+    };
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+
+    let mut kept = 0;
+    for i in 0..num_comps {
+        let component = price_data.comp_[i];
+        let idle = cmd_args.max_idle_slots != 0
+            && clock.slot.saturating_sub(component.latest_.pub_slot_) > cmd_args.max_idle_slots;
+        let revoked = approved_publishers
+            .map(|publishers| !publishers.contains(&component.pub_))
+            .unwrap_or(false);
+        if !idle && !revoked {
+            price_data.comp_[kept] = component;
+            kept += 1;
+        }
+    }
+    for i in kept..num_comps {
+        sol_memset(
+            bytes_of_mut(&mut price_data.comp_[i]),
+            0,
+            size_of::<PriceComponent>(),
+        );
+    }
+    price_data.num_ = try_convert(kept)?;
+    price_data.header.size = try_convert::<_, u32>(PriceAccount::INITIAL_SIZE)?;
+    drop(price_data);
+    invalidate_quote_order(price_account)?;
+    realign_publisher_entries(price_account)?;
+
+    Ok(())
+}
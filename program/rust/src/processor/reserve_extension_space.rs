@@ -0,0 +1,77 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PythAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            get_rent,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Grow the price account to `PriceAccount::NEW_ACCOUNT_SPACE`, so that the price accounts created
+/// before the extension region was reserved get the room of the new ones. The price account must
+/// already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn reserve_extension_space(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    pyth_assert(
+        price_account.data_len() < PriceAccount::NEW_ACCOUNT_SPACE,
+        OracleError::NoNeedToResize.into(),
+    )?;
+    pyth_assert(
+        price_account.lamports() >= get_rent()?.minimum_balance(PriceAccount::NEW_ACCOUNT_SPACE),
+        ProgramError::AccountNotRentExempt,
+    )?;
+    price_account.realloc(PriceAccount::NEW_ACCOUNT_SPACE, true)?;
+
+    Ok(())
+}
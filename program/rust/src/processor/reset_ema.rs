@@ -0,0 +1,97 @@
+use {
+    crate::{
+        accounts::{
+            get_price_and_extension_mut,
+            PriceAccount,
+            SlotWeightedTwap,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        processor::c_upd_twap,
+        utils::{
+            check_aggregation_complete,
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Slots since the last update past which the C code restarts the EMAs from the aggregate, see
+/// `PD_EMA_MAX_DIFF`
+const EMA_RESET_SLOTS: i64 = 4146;
+
+/// Restart `twap_` and `twac_` from the current aggregate, discarding their history, e.g. after a
+/// bad print that would otherwise take weeks to decay out of them. The slot-weighted TWAP of the
+/// feed, if it maintains one, restarts from the aggregate too. The aggregate must be trading.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] permissions account   []
+pub fn reset_ema(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    {
+        let price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+        check_aggregation_complete(&price_data)?;
+        pyth_assert(
+            price_data.agg_.status_ == PC_STATUS_TRADING,
+            OracleError::NeedsSuccesfulAggregation.into(),
+        )?;
+    }
+
+    unsafe {
+        // NOTE: c_upd_twap must use a raw pointer to price data, like c_upd_aggregate
+        c_upd_twap(
+            price_account.try_borrow_mut_data()?.as_mut_ptr(),
+            EMA_RESET_SLOTS,
+        );
+    }
+
+    if let Some((price_data, twap)) =
+        get_price_and_extension_mut::<SlotWeightedTwap>(&mut price_account.try_borrow_mut_data()?)?
+    {
+        twap.restart();
+        twap.record_aggregate(
+            price_data.agg_.pub_slot_,
+            price_data.agg_.price_,
+            price_data.agg_.conf_,
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use {
+    super::{
+        grow_feed_registry,
+        load_feed_registry,
+    },
+    crate::{
+        accounts::{
+            FeedRegistryAccount,
+            PermissionAccount,
+            PythAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::{
+            ProgramResult,
+            MAX_PERMITTED_DATA_INCREASE,
+        },
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::{
+        cmp::min,
+        mem::size_of,
+    },
+};
+
+/// Grow the feed registry by up to `MAX_PERMITTED_DATA_INCREASE` bytes towards the size holding
+/// the entry of the last feed index, creating it if needed. Registering a feed grows the registry
+/// by one entry, this is sent repeatedly beforehand when the registry is further behind, e.g. the
+/// first time on a cluster that already has feeds. Anyone can send it, the funding account pays
+/// the rent.
+// account[0] funding account       [signer writable]
+// account[1] feed registry         [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+pub fn resize_feed_registry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, registry_account, permissions_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (permissions_account, AccountCheck::Permissions),
+        ],
+    )?;
+    load_feed_registry(
+        program_id,
+        funding_account,
+        registry_account,
+        system_program,
+        hdr.version,
+    )?;
+    {
+        // Validate that permissions_account contains the appropriate account header
+        let _permissions_data =
+            load_checked::<PermissionAccount>(permissions_account, hdr.version)?;
+    }
+
+    let last_feed_index = PermissionAccount::last_feed_index(permissions_account)?;
+    let target_size = match last_feed_index {
+        0 => FeedRegistryAccount::MINIMUM_SIZE,
+        _ => FeedRegistryAccount::entry_offset(last_feed_index)? + size_of::<Pubkey>(),
+    };
+    pyth_assert(
+        registry_account.data_len() < target_size,
+        OracleError::NoNeedToResize.into(),
+    )?;
+    let new_size = min(
+        target_size,
+        registry_account.data_len() + MAX_PERMITTED_DATA_INCREASE,
+    );
+    grow_feed_registry(funding_account, registry_account, system_program, new_size)
+}
@@ -12,8 +12,9 @@ use {
         },
         instruction::CommandHeader,
         utils::{
-            check_valid_writable_account,
             pyth_assert,
+            validate_accounts,
+            AccountCheck,
         },
         OracleError,
     },
@@ -45,7 +46,7 @@ pub fn resize_mapping(
 
     let hdr = load::<CommandHeader>(instruction_data)?;
 
-    check_valid_writable_account(program_id, mapping_account)?;
+    validate_accounts(program_id, &[(mapping_account, AccountCheck::Writable)])?;
 
     {
         let account_header = load_account_as::<AccountHeader>(mapping_account)?;
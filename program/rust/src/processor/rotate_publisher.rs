@@ -0,0 +1,119 @@
+use {
+    super::{
+        check_publisher_approved,
+        find_publisher_index,
+        sort_price_comps,
+    },
+    crate::{
+        accounts::{
+            invalidate_quote_order,
+            realign_publisher_entries,
+            PermissionAccount,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_aggregation_complete,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Hand the components of a publisher over to a new key in every listed price account, so that
+/// rotating a key doesn't need a `DelPublisher` and `AddPublisher` per feed that would drop the
+/// quote of the publisher. Both keys sign. The components keep their latest and aggregated
+/// quotes and move to their sorted position. The rotation fails as a whole if a price account
+/// doesn't list the publisher or already lists the new key. Once the publisher registry exists,
+/// the new key must be approved in it.
+// account[0] publisher account     [signer writable]
+// account[1] new publisher key     [signer]
+// account[2] permissions account   []
+// account[3] publisher registry    [] (only once the registry exists)
+// account[3|4..] price accounts    [writable]
+pub fn rotate_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (publisher_account, new_publisher_account, permissions_account, others) = match accounts {
+        [x, y, p, others @ ..] => Ok((x, y, p, others)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (publisher_account, AccountCheck::Funding),
+            (new_publisher_account, AccountCheck::Signer),
+            (permissions_account, AccountCheck::Permissions),
+        ],
+    )?;
+    {
+        // Validate that permissions_account contains the appropriate account header
+        let _permissions_data =
+            load_checked::<PermissionAccount>(permissions_account, hdr.version)?;
+    }
+    pyth_assert(
+        publisher_account.key != new_publisher_account.key,
+        ProgramError::InvalidArgument,
+    )?;
+
+    // Once the registry exists, the new key needs its approval like a new publisher
+    let price_accounts = match others {
+        [registry, prices @ ..]
+            if PermissionAccount::has_publisher_registry(permissions_account)? =>
+        {
+            check_publisher_approved(program_id, registry, new_publisher_account.key)?;
+            prices
+        }
+        prices => prices,
+    };
+    pyth_assert(
+        !price_accounts.is_empty(),
+        OracleError::InvalidNumberOfAccounts.into(),
+    )?;
+
+    for price_account in price_accounts {
+        validate_accounts(program_id, &[(price_account, AccountCheck::Writable)])?;
+        {
+            let mut price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+            check_aggregation_complete(&price_data)?;
+            let num_comps = try_convert::<u32, usize>(price_data.num_)?;
+            pyth_assert(
+                find_publisher_index(&price_data.comp_[..num_comps], new_publisher_account.key)
+                    .is_none(),
+                OracleError::PublisherAlreadyExists.into(),
+            )?;
+            let index = find_publisher_index(&price_data.comp_[..num_comps], publisher_account.key)
+                .ok_or(OracleError::PublisherNotFound)?;
+            price_data.comp_[index].pub_ = *new_publisher_account.key;
+            sort_price_comps(&mut price_data.comp_, num_comps)?;
+        }
+        invalidate_quote_order(price_account)?;
+        realign_publisher_entries(price_account)?;
+    }
+
+    Ok(())
+}
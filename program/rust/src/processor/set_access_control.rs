@@ -0,0 +1,93 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            AccessControl,
+            AccessControlAccount,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Store the access control account in the `AccessControl` extension of the feed, so that only
+/// its readers can consume the feed through `AssertPriceCondition`. Without the access control
+/// account the feed is open to everyone again. The price account is grown to hold the extension
+/// the first time, so it must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account          [signer writable]
+// account[1] price account            [signer writable]
+// account[2] permissions account      []
+// account[3] access control account   [] (optional)
+pub fn set_access_control(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, access_control_account) =
+        match accounts {
+            [x, y, p] => Ok((x, y, p, None)),
+            [x, y, p, a] => Ok((x, y, p, Some(a))),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        hdr,
+    )?;
+
+    let access_control = match access_control_account {
+        Some(access_control_account) => {
+            validate_accounts(
+                program_id,
+                &[(access_control_account, AccountCheck::Readable)],
+            )?;
+            // Validate that access_control_account contains the appropriate account header
+            let _access_control_data =
+                load_checked::<AccessControlAccount>(access_control_account, hdr.version)?;
+            *access_control_account.key
+        }
+        None => Pubkey::default(),
+    };
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    reserve_extension_space::<AccessControl>(price_account)?;
+    init_extension::<AccessControl>(&mut price_account.try_borrow_mut_data()?)?.access_control =
+        access_control;
+
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use {
+    crate::{
+        accounts::{
+            check_is_upgrade_authority_for_program,
+            AccountHeader,
+            PermissionAccount,
+            PERMISSIONS_SEED,
+        },
+        deserialize::{
+            load,
+            load_account_as_mut,
+            load_checked,
+        },
+        instruction::SetAccumulatorEmitterArgs,
+        utils::{
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the program that `UpdPrice` pushes every new aggregate to, see `AccumulatorEmitter`. The
+/// permission account is grown to hold the `AccumulatorEmitter` the first time, paid by the
+/// upgrade authority. The emitter isn't a change of the authorities, so it applies immediately
+/// whatever the timelock of the permissions.
+// account[0] upgrade authority     [signer writable]
+// account[1] programdata account   []
+// account[2] permissions account   [writable]
+// account[3] system program        []
+pub fn set_accumulator_emitter(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, programdata_account, permissions_account, system_program) = match accounts
+    {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let cmd_args = load::<SetAccumulatorEmitterArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAccumulatorEmitterArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    // The oracle can't invoke itself
+    pyth_assert(
+        cmd_args.program_id != *program_id,
+        ProgramError::InvalidArgument,
+    )?;
+
+    validate_accounts(program_id, &[(funding_account, AccountCheck::Funding)])?;
+    check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
+
+    let (permission_pda_address, _) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+    pyth_assert(
+        permission_pda_address == *permissions_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(
+        program_id,
+        &[
+            (system_program, AccountCheck::SystemProgram),
+            (permissions_account, AccountCheck::Writable),
+        ],
+    )?;
+
+    {
+        // Validate that the permissions account contains the appropriate account header
+        let _permissions_account_data =
+            load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+    }
+
+    if permissions_account.data_len() < PermissionAccount::EMITTER_ACCOUNT_SPACE {
+        let new_size = PermissionAccount::EMITTER_ACCOUNT_SPACE;
+        let minimum_balance = get_rent()?.minimum_balance(new_size);
+        if permissions_account.lamports() < minimum_balance {
+            send_lamports(
+                funding_account,
+                permissions_account,
+                system_program,
+                minimum_balance - permissions_account.lamports(),
+            )?;
+        }
+        permissions_account.realloc(new_size, true)?;
+        let mut header = load_account_as_mut::<AccountHeader>(permissions_account)?;
+        header.size = try_convert(new_size)?;
+    }
+
+    PermissionAccount::load_accumulator_emitter_mut(permissions_account)?.program_id =
+        cmd_args.program_id;
+
+    Ok(())
+}
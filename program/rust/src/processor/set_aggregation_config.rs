@@ -0,0 +1,94 @@
+use {
+    crate::{
+        accounts::{
+            AggregationConfigAccount,
+            PythAccount,
+            AGGREGATION_CONFIG_SEED,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetAggregationConfigArgs,
+        utils::{
+            check_permissioned_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the defaults of the aggregation shared by the feeds that don't set their own, see
+/// `AggregationConfigAccount`. The defaults apply from the next aggregation of every feed. The
+/// aggregation config is created the first time, paid by the funding account.
+// account[0] funding account       [signer writable]
+// account[1] aggregation config    [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+pub fn set_aggregation_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetAggregationConfigArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAggregationConfigArgs>()
+            && cmd_args.max_ci_divisor >= 0
+            && u32::from(cmd_args.min_pub) <= PC_NUM_COMP,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, config_account, permissions_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (config_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[AGGREGATION_CONFIG_SEED.as_bytes()], program_id);
+    pyth_assert(
+        config_pda_address == *config_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    AggregationConfigAccount::initialize_pda(
+        config_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[AGGREGATION_CONFIG_SEED.as_bytes(), &[bump_seed]],
+        cmd_args.header.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        config_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    let mut config_data =
+        load_checked::<AggregationConfigAccount>(config_account, cmd_args.header.version)?;
+    config_data.max_ci_divisor = cmd_args.max_ci_divisor;
+    config_data.max_latency = cmd_args.max_latency;
+    config_data.min_pub = cmd_args.min_pub;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            AggregationCounts,
+            PriceAccount,
+            AGGREGATION_COUNT_BUCKETS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetAggregationCountWindowArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of recent slots over which the slots with a trading aggregate are counted.
+/// Changing the window starts the counts over. The price account is grown to hold the
+/// `AggregationCounts` extension the first time, so it must already hold enough lamports to be
+/// rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_aggregation_count_window(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetAggregationCountWindowArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAggregationCountWindowArgs>()
+            && cmd.window_slots % AGGREGATION_COUNT_BUCKETS as u64 == 0,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<AggregationCounts>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let counts = init_extension::<AggregationCounts>(&mut price_account_data)?;
+    let bucket_slots = cmd.window_slots / AGGREGATION_COUNT_BUCKETS as u64;
+    if counts.bucket_slots != bucket_slots {
+        *counts = AggregationCounts {
+            bucket_slots,
+            last_slot: 0,
+            counts: [0; AGGREGATION_COUNT_BUCKETS],
+        };
+    }
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            AggregationDelay,
+            PriceAccount,
+            MAX_AGGREGATION_DELAY,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetAggregationDelayArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of slots between the updates of the publishers and the aggregate including
+/// them. The price account is grown to hold the `AggregationDelay` extension the first time, so
+/// it must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_aggregation_delay(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetAggregationDelayArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAggregationDelayArgs>()
+            && cmd.slots <= MAX_AGGREGATION_DELAY,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<AggregationDelay>(price_account)?;
+    init_extension::<AggregationDelay>(&mut price_account.try_borrow_mut_data()?)?.slots =
+        cmd.slots;
+
+    Ok(())
+}
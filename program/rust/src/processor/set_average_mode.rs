@@ -0,0 +1,104 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            AverageMode,
+            PriceAccount,
+            SlotWeightedTwap,
+            SLOT_WEIGHTED_TWAP_BUCKETS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetAverageModeArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Select the average the feed publishes, see `AverageMode`, and the number of recent slots over
+/// which its slot-weighted TWAP is maintained. The published average switches with the next
+/// aggregation. Changing the window starts the TWAP over, so a feed should migrate with the
+/// window it keeps. The price account is grown to hold the `SlotWeightedTwap` extension the first
+/// time, so it must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_average_mode(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetAverageModeArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetAverageModeArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    let mode = check_average_mode(cmd.mode, cmd.window_slots)?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    apply_average_mode(price_account, mode, cmd.window_slots)
+}
+
+/// The `AverageMode` encoded as `mode`, if it can be published with a slot-weighted TWAP over
+/// `window_slots`, see `SetAverageModeArgs`.
+pub fn check_average_mode(mode: u32, window_slots: u64) -> Result<AverageMode, ProgramError> {
+    let mode = AverageMode::from_u32(mode).ok_or(ProgramError::InvalidArgument)?;
+    pyth_assert(
+        window_slots % SLOT_WEIGHTED_TWAP_BUCKETS as u64 == 0
+            && (window_slots != 0 || mode == AverageMode::Ema),
+        ProgramError::InvalidArgument,
+    )?;
+    Ok(mode)
+}
+
+/// Select the average `price_account` publishes, checked by `check_average_mode`.
+pub fn apply_average_mode(
+    price_account: &AccountInfo,
+    mode: AverageMode,
+    window_slots: u64,
+) -> ProgramResult {
+    reserve_extension_space::<SlotWeightedTwap>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let twap = init_extension::<SlotWeightedTwap>(&mut price_account_data)?;
+    let bucket_slots = window_slots / SLOT_WEIGHTED_TWAP_BUCKETS as u64;
+    if twap.bucket_slots != bucket_slots {
+        *twap = SlotWeightedTwap::new(mode, bucket_slots);
+    }
+    twap.mode = mode as u32;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            Checksum,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetChecksumArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Start or stop checking the price account against its `Checksum`. The checksum is recorded by
+/// `process_instruction` once this instruction succeeds, and this instruction doesn't check the
+/// account beforehand, so enabling it again accepts the account as it is. The price account is
+/// grown to hold the `Checksum` extension the first time, so it must already hold enough lamports
+/// to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_checksum(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetChecksumArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetChecksumArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<Checksum>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    init_extension::<Checksum>(&mut price_account_data)?.enabled = u8::from(cmd.enabled != 0);
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use {
+    crate::{
+        accounts::{
+            reserve_extension_space,
+            set_extension,
+            ConfFloor,
+            PriceAccount,
+            MAX_CONF_FLOOR_BPS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetConfFloorArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the smallest confidence interval of the aggregates of the feed, see `ConfFloor`. A floor
+/// of zeros leaves the aggregates as computed. The data curation authority can set it as well as
+/// the master authority. The price account is grown to hold the `ConfFloor` extension the first
+/// time, so it must already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_conf_floor(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetConfFloorArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetConfFloorArgs>()
+            && cmd.min_conf_bps <= MAX_CONF_FLOOR_BPS,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<ConfFloor>(price_account)?;
+    set_extension(
+        &mut price_account.try_borrow_mut_data()?,
+        ConfFloor {
+            min_conf:     cmd.min_conf,
+            min_conf_bps: cmd.min_conf_bps,
+        },
+    )?;
+
+    Ok(())
+}
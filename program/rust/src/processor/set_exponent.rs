@@ -0,0 +1,168 @@
+use {
+    crate::{
+        accounts::{
+            get_extension_mut,
+            invalidate_quote_order,
+            BidAsk,
+            PriceAccount,
+            PriceEma,
+            PriceInfo,
+            SlotWeightedTwap,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetExponentArgs,
+        utils::{
+            check_exponent_range,
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the exponent of a price account, rescaling every price and confidence stored in it so that
+/// they keep their value. Rescaling to a larger exponent drops digits: prices are rounded to the
+/// nearest value and confidences are rounded up. Publishers must publish with the new exponent
+/// right after this instruction.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_exponent(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetExponentArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetExponentArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+    check_exponent_range(cmd.exponent)?;
+
+    let shift = {
+        let mut price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+        let shift = price_data.exponent - cmd.exponent;
+
+        rescale_price_info(&mut price_data.agg_, shift)?;
+        let num_components = price_data.num_ as usize;
+        for component in price_data.comp_.iter_mut().take(num_components) {
+            rescale_price_info(&mut component.agg_, shift)?;
+            rescale_price_info(&mut component.latest_, shift)?;
+        }
+        rescale_price(&mut price_data.prev_price_, shift)?;
+        rescale_conf(&mut price_data.prev_conf_, shift)?;
+        rescale_ema(&mut price_data.twap_, shift, Rounding::Nearest)?;
+        rescale_ema(&mut price_data.twac_, shift, Rounding::Up)?;
+
+        let cumulative = &mut price_data.price_cumulative;
+        cumulative.price = rescale(cumulative.price, shift, Rounding::Nearest)?;
+        cumulative.conf = u128::try_from(rescale(
+            i128::try_from(cumulative.conf).map_err(|_| OracleError::PriceRescaleOverflow)?,
+            shift,
+            Rounding::Up,
+        )?)
+        .map_err(|_| OracleError::PriceRescaleOverflow)?;
+
+        price_data.exponent = cmd.exponent;
+        shift
+    };
+
+    if let Some(bid_ask) = get_extension_mut::<BidAsk>(&mut price_account.try_borrow_mut_data()?)? {
+        rescale_price(&mut bid_ask.bid_price, shift)?;
+        rescale_price(&mut bid_ask.ask_price, shift)?;
+    }
+    if let Some(twap) =
+        get_extension_mut::<SlotWeightedTwap>(&mut price_account.try_borrow_mut_data()?)?
+    {
+        rescale_price(&mut twap.last_price, shift)?;
+        rescale_conf(&mut twap.last_conf, shift)?;
+        rescale_price(&mut twap.price, shift)?;
+        rescale_conf(&mut twap.conf, shift)?;
+        for bucket in twap.buckets.iter_mut() {
+            bucket.price_sum = rescale(bucket.price_sum(), shift, Rounding::Nearest)?.to_le_bytes();
+            bucket.conf_sum = rescale(bucket.conf_sum(), shift, Rounding::Up)?.to_le_bytes();
+        }
+    }
+    invalidate_quote_order(price_account)?;
+
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Rounding {
+    Nearest,
+    /// Rounds positive values up, only used for values that can't be negative
+    Up,
+}
+
+/// Multiply `value` by `10^shift`, `shift` being the difference between the old and the new
+/// exponent.
+fn rescale(value: i128, shift: i32, rounding: Rounding) -> Result<i128, OracleError> {
+    // Exponents are at most 2 * MAX_NUM_DECIMALS apart, so the factor always fits
+    let factor = 10i128.pow(shift.unsigned_abs());
+    if shift >= 0 {
+        return value
+            .checked_mul(factor)
+            .ok_or(OracleError::PriceRescaleOverflow);
+    }
+
+    let (quotient, remainder) = (value / factor, value % factor);
+    let adjustment = match rounding {
+        Rounding::Nearest if 2 * remainder.abs() >= factor => remainder.signum(),
+        Rounding::Up if remainder > 0 => 1,
+        _ => 0,
+    };
+    Ok(quotient + adjustment)
+}
+
+fn rescale_price(price: &mut i64, shift: i32) -> Result<(), OracleError> {
+    *price = i64::try_from(rescale(i128::from(*price), shift, Rounding::Nearest)?)
+        .map_err(|_| OracleError::PriceRescaleOverflow)?;
+    Ok(())
+}
+
+fn rescale_conf(conf: &mut u64, shift: i32) -> Result<(), OracleError> {
+    *conf = u64::try_from(rescale(i128::from(*conf), shift, Rounding::Up)?)
+        .map_err(|_| OracleError::PriceRescaleOverflow)?;
+    Ok(())
+}
+
+fn rescale_price_info(price_info: &mut PriceInfo, shift: i32) -> Result<(), OracleError> {
+    rescale_price(&mut price_info.price_, shift)?;
+    rescale_conf(&mut price_info.conf_, shift)
+}
+
+/// The weighted sum `numer_` and the sum of the weights `denom_` of the EMAs are packed decimals
+/// that carry their own exponent, so they don't depend on the exponent of the feed. Only the
+/// published value `val_` is rescaled.
+fn rescale_ema(ema: &mut PriceEma, shift: i32, rounding: Rounding) -> Result<(), OracleError> {
+    ema.val_ = i64::try_from(rescale(i128::from(ema.val_), shift, rounding)?)
+        .map_err(|_| OracleError::PriceRescaleOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,199 @@
+use {
+    crate::{
+        accounts::{
+            Feature,
+            FeatureGateAccount,
+            FeatureSwitch,
+            PythAccount,
+            FEATURE_GATE_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+            OracleAccount,
+        },
+        instruction::{
+            CommandHeader,
+            SetFeatureArgs,
+        },
+        utils::{
+            check_permissioned_funding_account,
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Flip a switch of the feature gate, adding the switch if the feature doesn't have one yet. The
+/// flip takes effect after the grace period of the feature gate, the feature keeps its state
+/// until then. A switch added for a feature the program doesn't know starts disabled. The
+/// feature gate is created the first time and grown as features get switches, paid by the
+/// funding account.
+// account[0] funding account       [signer writable]
+// account[1] feature gate          [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+// account[4] sysvar_clock account  []
+pub fn set_feature(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetFeatureArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetFeatureArgs>()
+            && cmd_args.name[0] != 0
+            && cmd_args.enabled <= 1,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, feature_gate_account, permissions_account, system_program, clock_account) =
+        match accounts {
+            [v, w, x, y, z] => Ok((v, w, x, y, z)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    validate_accounts(
+        program_id,
+        &[(clock_account, AccountCheck::Sysvar(clock::id()))],
+    )?;
+    init_feature_gate(
+        program_id,
+        funding_account,
+        feature_gate_account,
+        permissions_account,
+        system_program,
+        &cmd_args.header,
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    let feature_gate =
+        OracleAccount::<FeatureGateAccount>::new(feature_gate_account, cmd_args.header.version)?;
+    let grace_period = feature_gate.load()?.grace_period;
+    let (num_features, position) = {
+        let data = feature_gate_account.try_borrow_data()?;
+        let features = FeatureGateAccount::features(&data)?;
+        (
+            features.len(),
+            features
+                .iter()
+                .position(|switch| switch.name == cmd_args.name),
+        )
+    };
+
+    let position = match position {
+        Some(position) => position,
+        None => {
+            let start = FeatureGateAccount::entry_offset(num_features);
+            let end = FeatureGateAccount::entry_offset(num_features + 1);
+            if feature_gate_account.data_len() < end {
+                let minimum_balance = get_rent()?.minimum_balance(end);
+                if feature_gate_account.lamports() < minimum_balance {
+                    send_lamports(
+                        funding_account,
+                        feature_gate_account,
+                        system_program,
+                        minimum_balance - feature_gate_account.lamports(),
+                    )?;
+                }
+                feature_gate_account.realloc(end, true)?;
+            }
+            let enabled = u8::from(
+                Feature::from_name(&cmd_args.name)
+                    .map_or(false, |feature| feature.enabled_by_default()),
+            );
+            let switch = FeatureSwitch {
+                name: cmd_args.name,
+                enabled,
+                pending_enabled: enabled,
+                ..FeatureSwitch::zeroed()
+            };
+            feature_gate_account.try_borrow_mut_data()?[start..end]
+                .copy_from_slice(bytes_of(&switch));
+
+            let mut feature_gate_data = feature_gate.load_mut()?;
+            feature_gate_data.num_features = try_convert(num_features + 1)?;
+            feature_gate_data.header.size = try_convert(end)?;
+            num_features
+        }
+    };
+
+    let start = FeatureGateAccount::entry_offset(position);
+    let end = FeatureGateAccount::entry_offset(position + 1);
+    let mut data = feature_gate_account.try_borrow_mut_data()?;
+    bytemuck::from_bytes_mut::<FeatureSwitch>(&mut data[start..end]).flip(
+        cmd_args.enabled != 0,
+        clock.slot,
+        grace_period,
+    );
+
+    Ok(())
+}
+
+/// Create the feature gate if it doesn't exist yet, and check that the funding account may
+/// change it.
+pub fn init_feature_gate<'a>(
+    program_id: &Pubkey,
+    funding_account: &AccountInfo<'a>,
+    feature_gate_account: &AccountInfo<'a>,
+    permissions_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    header: &CommandHeader,
+) -> ProgramResult {
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (feature_gate_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[FEATURE_GATE_SEED.as_bytes()], program_id);
+    pyth_assert(
+        feature_gate_pda_address == *feature_gate_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    FeatureGateAccount::initialize_pda(
+        feature_gate_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[FEATURE_GATE_SEED.as_bytes(), &[bump_seed]],
+        header.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        feature_gate_account,
+        funding_account,
+        permissions_account,
+        header,
+    )?;
+
+    // Validate that feature_gate_account contains the appropriate account header
+    let _feature_gate_data =
+        load_checked::<FeatureGateAccount>(feature_gate_account, header.version)?;
+    Ok(())
+}
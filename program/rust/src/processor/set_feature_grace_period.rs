@@ -0,0 +1,60 @@
+use {
+    super::init_feature_gate,
+    crate::{
+        accounts::FeatureGateAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetFeatureGracePeriodArgs,
+        utils::pyth_assert,
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of slots between a flip of the feature gate and the slot it takes effect. The
+/// flips already requested keep their effective slot. The feature gate is created the first time,
+/// paid by the funding account.
+// account[0] funding account       [signer writable]
+// account[1] feature gate          [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+pub fn set_feature_grace_period(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<SetFeatureGracePeriodArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetFeatureGracePeriodArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, feature_gate_account, permissions_account, system_program) =
+        match accounts {
+            [w, x, y, z] => Ok((w, x, y, z)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    init_feature_gate(
+        program_id,
+        funding_account,
+        feature_gate_account,
+        permissions_account,
+        system_program,
+        &cmd_args.header,
+    )?;
+
+    load_checked::<FeatureGateAccount>(feature_gate_account, cmd_args.header.version)?
+        .grace_period = cmd_args.grace_period;
+
+    Ok(())
+}
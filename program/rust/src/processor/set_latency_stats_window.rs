@@ -0,0 +1,78 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            LatencyStats,
+            PriceAccount,
+            LATENCY_STATS_ENTRIES,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetLatencyStatsWindowArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of recent aggregations over which the landing latency of the updates is
+/// tracked. Changing the window starts the statistics over. The price account is grown to hold
+/// the `LatencyStats` extension the first time, so it must already hold enough lamports to be
+/// rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_latency_stats_window(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetLatencyStatsWindowArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetLatencyStatsWindowArgs>()
+            && cmd.window <= LATENCY_STATS_ENTRIES as u64,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<LatencyStats>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let latency_stats = init_extension::<LatencyStats>(&mut price_account_data)?;
+    if latency_stats.window != cmd.window {
+        *latency_stats = LatencyStats::new(cmd.window);
+    }
+
+    Ok(())
+}
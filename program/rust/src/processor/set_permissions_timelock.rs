@@ -0,0 +1,126 @@
+use {
+    crate::{
+        accounts::{
+            check_is_upgrade_authority_for_program,
+            AccountHeader,
+            PermissionAccount,
+            PERMISSIONS_SEED,
+        },
+        deserialize::{
+            load,
+            load_account_as_mut,
+            load_checked,
+        },
+        instruction::SetPermissionsTimelockArgs,
+        utils::{
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Set the delay between staging a change of the permissions and applying it. The permission
+/// account is grown to hold the `PermissionTimelock` the first time, paid by the upgrade
+/// authority. If a delay is already set, the new delay is staged like any other change of the
+/// permissions.
+// account[0] upgrade authority     [signer writable]
+// account[1] programdata account   []
+// account[2] permissions account   [writable]
+// account[3] system program        []
+// account[4] sysvar_clock account  []
+pub fn set_permissions_timelock(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (funding_account, programdata_account, permissions_account, system_program, clock_account) =
+        match accounts {
+            [v, w, x, y, z] => Ok((v, w, x, y, z)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    let cmd_args = load::<SetPermissionsTimelockArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPermissionsTimelockArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    validate_accounts(program_id, &[(funding_account, AccountCheck::Funding)])?;
+    check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
+
+    let (permission_pda_address, _) =
+        Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], program_id);
+    pyth_assert(
+        permission_pda_address == *permissions_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(
+        program_id,
+        &[
+            (system_program, AccountCheck::SystemProgram),
+            (permissions_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+
+    let authorities = {
+        let permissions_account_data =
+            load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+        (
+            permissions_account_data.master_authority,
+            permissions_account_data.data_curation_authority,
+            permissions_account_data.security_authority,
+        )
+    };
+
+    if permissions_account.data_len() < PermissionAccount::TIMELOCK_ACCOUNT_SPACE {
+        let new_size = PermissionAccount::TIMELOCK_ACCOUNT_SPACE;
+        let minimum_balance = get_rent()?.minimum_balance(new_size);
+        if permissions_account.lamports() < minimum_balance {
+            send_lamports(
+                funding_account,
+                permissions_account,
+                system_program,
+                minimum_balance - permissions_account.lamports(),
+            )?;
+        }
+        permissions_account.realloc(new_size, true)?;
+        let mut header = load_account_as_mut::<AccountHeader>(permissions_account)?;
+        header.size = try_convert(new_size)?;
+    }
+
+    let mut timelock = PermissionAccount::load_timelock_mut(permissions_account)?;
+    if timelock.delay == 0 {
+        timelock.delay = cmd_args.delay;
+    } else {
+        let clock = Clock::from_account_info(clock_account)?;
+        let (master_authority, data_curation_authority, security_authority) = authorities;
+        timelock.stage(
+            master_authority,
+            data_curation_authority,
+            security_authority,
+            cmd_args.delay,
+            clock.slot,
+        );
+    }
+
+    Ok(())
+}
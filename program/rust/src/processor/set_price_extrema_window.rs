@@ -0,0 +1,79 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PriceExtrema,
+            PRICE_EXTREMA_BUCKETS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPriceExtremaWindowArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of recent slots over which the lowest and highest trading aggregates are
+/// tracked. Changing the window starts the extrema over. The price account is grown to hold the
+/// `PriceExtrema` extension the first time, so it must already hold enough lamports to be rent
+/// exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_price_extrema_window(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPriceExtremaWindowArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPriceExtremaWindowArgs>()
+            && cmd.window_slots % PRICE_EXTREMA_BUCKETS as u64 == 0,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<PriceExtrema>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let extrema = init_extension::<PriceExtrema>(&mut price_account_data)?;
+    let bucket_slots = cmd.window_slots / PRICE_EXTREMA_BUCKETS as u64;
+    if extrema.bucket_slots != bucket_slots {
+        *extrema = PriceExtrema::new(bucket_slots);
+    }
+
+    Ok(())
+}
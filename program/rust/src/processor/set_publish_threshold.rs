@@ -0,0 +1,78 @@
+use {
+    crate::{
+        accounts::{
+            reserve_extension_space,
+            set_extension,
+            PriceAccount,
+            PublishThreshold,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPublishThresholdArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the deviation and the heartbeat that trigger publishing a new aggregate of the feed, see
+/// `PublishThreshold`. A deviation of 0 publishes every aggregate. The price account is grown to
+/// hold the `PublishThreshold` extension the first time, so it must already hold enough lamports
+/// to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_publish_threshold(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPublishThresholdArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublishThresholdArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<PublishThreshold>(price_account)?;
+    set_extension(
+        &mut price_account.try_borrow_mut_data()?,
+        PublishThreshold {
+            deviation_bps:   cmd.deviation_bps,
+            heartbeat_slots: cmd.heartbeat_slots,
+        },
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PublisherCap,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPublisherCapArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the largest number of publishers of the feed, see `PublisherCap`. The cap can't be below
+/// the current number of publishers, which must be removed first. The data curation authority can
+/// set it as well as the master authority. The price account is grown to hold the `PublisherCap`
+/// extension the first time, so it must already hold enough lamports to be rent exempt at its
+/// new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_publisher_cap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPublisherCapArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublisherCapArgs>()
+            && cmd.max_publishers <= PC_NUM_COMP,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+        pyth_assert(
+            cmd.max_publishers == 0 || cmd.max_publishers >= price_data.num_,
+            ProgramError::InvalidArgument,
+        )?;
+    }
+
+    reserve_extension_space::<PublisherCap>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    init_extension::<PublisherCap>(&mut price_account_data)?.max_publishers = cmd.max_publishers;
+
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PublisherActivity,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPublisherDemotionArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the number of consecutive aggregation windows after which a publisher of the feed that
+/// doesn't publish a valid quote is left out of the quorum, see `PublisherActivity`. 0 stops
+/// demoting. The missed windows already recorded are kept. The price account is grown to hold the
+/// `PublisherActivity` extension the first time, so it must already hold enough lamports to be
+/// rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_publisher_demotion(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPublisherDemotionArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublisherDemotionArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<PublisherActivity>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    init_extension::<PublisherActivity>(&mut price_account_data)?.max_missed = cmd.max_missed;
+
+    Ok(())
+}
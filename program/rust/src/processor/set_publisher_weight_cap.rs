@@ -0,0 +1,107 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PriceComponent,
+            PublisherLink,
+            PublisherLinkageAccount,
+            PublisherWeightCap,
+            PUBLISHER_LINKAGE_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetPublisherWeightCapArgs,
+        processor::find_publisher_index,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Limit the number of quotes of a single entity counted in the aggregates of the feed, and copy
+/// the entities of its current publishers from the publisher linkage account. The price account
+/// is grown to hold the `PublisherWeightCap` extension the first time, so it must already hold
+/// enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+// account[3] publisher linkage     []
+pub fn set_publisher_weight_cap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetPublisherWeightCapArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetPublisherWeightCapArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, linkage_account) = match accounts {
+        [x, y, p, l] => Ok((x, y, p, l)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    let (linkage_pda_address, _) =
+        Pubkey::find_program_address(&[PUBLISHER_LINKAGE_SEED.as_bytes()], program_id);
+    pyth_assert(
+        linkage_pda_address == *linkage_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    validate_accounts(program_id, &[(linkage_account, AccountCheck::Readable)])?;
+    let linkage_data = linkage_account.try_borrow_data()?;
+    let links = PublisherLinkageAccount::links(&linkage_data)?;
+
+    // The components are copied so that the extension can be written while they are looked up
+    let components: Vec<PriceComponent> = {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+        price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?].to_vec()
+    };
+
+    reserve_extension_space::<PublisherWeightCap>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let weight_cap = init_extension::<PublisherWeightCap>(&mut price_account_data)?;
+    weight_cap.max_quotes_per_entity = cmd.max_quotes_per_entity;
+    weight_cap.components.fill(PublisherLink::zeroed());
+    for (entry, component) in weight_cap.components.iter_mut().zip(&components) {
+        *entry = PublisherLink {
+            publisher: component.pub_,
+            entity:    component.pub_,
+        };
+    }
+    for link in links {
+        if let Some(index) = find_publisher_index(&components, &link.publisher) {
+            weight_cap.components[index].entity = link.entity;
+        }
+    }
+
+    Ok(())
+}
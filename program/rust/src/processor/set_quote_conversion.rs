@@ -0,0 +1,87 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PriceInfo,
+            QuoteConversion,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetQuoteConversionArgs,
+        utils::{
+            check_exponent_range,
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the feed converting the aggregate of the price account to another quote currency, see
+/// `QuoteConversion`. Changing the conversion drops the last converted aggregate. The price
+/// account is grown to hold the `QuoteConversion` extension the first time, so it must already
+/// hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_quote_conversion(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetQuoteConversionArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetQuoteConversionArgs>() && cmd.divide <= 1,
+        ProgramError::InvalidArgument,
+    )?;
+    check_exponent_range(cmd.exponent)?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+    // The feed can't convert its own aggregate
+    pyth_assert(
+        cmd.conversion_feed != *price_account.key,
+        OracleError::InvalidConversionFeed.into(),
+    )?;
+    load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+
+    reserve_extension_space::<QuoteConversion>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let conversion = init_extension::<QuoteConversion>(&mut price_account_data)?;
+    if conversion.conversion_feed != cmd.conversion_feed
+        || conversion.exponent != cmd.exponent
+        || conversion.divide != cmd.divide
+    {
+        conversion.converted = PriceInfo::zeroed();
+    }
+    conversion.conversion_feed = cmd.conversion_feed;
+    conversion.exponent = cmd.exponent;
+    conversion.divide = cmd.divide;
+
+    Ok(())
+}
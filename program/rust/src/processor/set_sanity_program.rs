@@ -0,0 +1,74 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            SanityProgram,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetSanityProgramArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the program that checks every aggregate of the feed before it's published. The price
+/// account is grown to hold the `SanityProgram` extension the first time, so it must already
+/// hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_sanity_program(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetSanityProgramArgs>(instruction_data)?;
+
+    // The oracle can't check its own aggregates
+    pyth_assert(
+        instruction_data.len() == size_of::<SetSanityProgramArgs>()
+            && cmd.program_id != *program_id,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<SanityProgram>(price_account)?;
+    init_extension::<SanityProgram>(&mut price_account.try_borrow_mut_data()?)?.program_id =
+        cmd.program_id;
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            PublisherStake,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetStakeProgramArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the staking program checking the stake of the publishers added to the feed and the
+/// minimum stake, see `PublisherStake`. The publishers of the feed already listed keep no
+/// recorded stake until they are added again. The price account is grown to hold the
+/// `PublisherStake` extension the first time, so it must already hold enough lamports to be rent
+/// exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_stake_program(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetStakeProgramArgs>(instruction_data)?;
+
+    // The stake escrows can't be accounts of the oracle
+    pyth_assert(
+        instruction_data.len() == size_of::<SetStakeProgramArgs>()
+            && cmd.staking_program != *program_id,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<PublisherStake>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let stake = init_extension::<PublisherStake>(&mut price_account_data)?;
+    if stake.staking_program != cmd.staking_program {
+        // The stakes were reported by the previous program
+        stake.components = Zeroable::zeroed();
+    }
+    stake.staking_program = cmd.staking_program;
+    stake.min_stake = cmd.min_stake;
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use {
+    crate::{
+        accounts::{
+            reserve_extension_space,
+            set_extension,
+            PriceAccount,
+            StatusPolicy,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetStatusPolicyArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set how the aggregation of the feed combines the statuses supplied by its publishers, see
+/// `StatusPolicy`. Quorums of 0 restore the implicit rule that only trading quotes count. The
+/// price account is grown to hold the `StatusPolicy` extension the first time, so it must
+/// already hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_status_policy(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetStatusPolicyArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetStatusPolicyArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+    // A quorum above the number of components could never be reached
+    pyth_assert(
+        u32::from(cmd.halted_quorum) <= PC_NUM_COMP && u32::from(cmd.auction_quorum) <= PC_NUM_COMP,
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<StatusPolicy>(price_account)?;
+    set_extension(
+        &mut price_account.try_borrow_mut_data()?,
+        StatusPolicy {
+            halted_quorum:    cmd.halted_quorum,
+            auction_quorum:   cmd.auction_quorum,
+            override_trading: u8::from(cmd.override_trading != 0),
+            unused_:          [0; 5],
+        },
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            PriceAccount,
+            StatusReason,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetStatusReasonTrackingArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Start or stop recording why the aggregations of the feed leave it `PC_STATUS_UNKNOWN`, see
+/// `StatusReason`. Stopping keeps the last recorded reason. The price account is grown to hold
+/// the `StatusReason` extension the first time, so it must already hold enough lamports to be
+/// rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+pub fn set_status_reason_tracking(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetStatusReasonTrackingArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetStatusReasonTrackingArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account) = match accounts {
+        [x, y, p] => Ok((x, y, p)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<StatusReason>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    init_extension::<StatusReason>(&mut price_account_data)?.enabled = u8::from(cmd.enabled != 0);
+
+    Ok(())
+}
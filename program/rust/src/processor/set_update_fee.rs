@@ -0,0 +1,104 @@
+use {
+    crate::{
+        accounts::{
+            init_extension,
+            reserve_extension_space,
+            FeeVaultAccount,
+            PriceAccount,
+            PythAccount,
+            UpdateFee,
+            FEE_VAULT_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::SetUpdateFeeArgs,
+        utils::{
+            check_permissioned_funding_account,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Set the lamports every quote of the feed pays to the fee vault, see `UpdateFee`. The fee vault
+/// is created at the address of `FEE_VAULT_SEED` the first time, paid by the funding account. The
+/// price account is grown to hold the `UpdateFee` extension the first time, so it must already
+/// hold enough lamports to be rent exempt at its new size.
+// account[0] funding account       [signer writable]
+// account[1] price account         [signer writable]
+// account[2] permissions account   []
+// account[3] fee vault             [writable]
+// account[4] system program        []
+pub fn set_update_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<SetUpdateFeeArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<SetUpdateFeeArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, permissions_account, fee_vault_account, system_program) =
+        match accounts {
+            [v, w, x, y, z] => Ok((v, w, x, y, z)),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        price_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    let (fee_vault_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[FEE_VAULT_SEED.as_bytes()], program_id);
+    pyth_assert(
+        fee_vault_pda_address == *fee_vault_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    FeeVaultAccount::initialize_pda(
+        fee_vault_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[FEE_VAULT_SEED.as_bytes(), &[bump_seed]],
+        cmd.header.version,
+    )?;
+    validate_accounts(program_id, &[(fee_vault_account, AccountCheck::Writable)])?;
+
+    {
+        // Validate that the accounts contain the appropriate account headers
+        let _fee_vault_data =
+            load_checked::<FeeVaultAccount>(fee_vault_account, cmd.header.version)?;
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    reserve_extension_space::<UpdateFee>(price_account)?;
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    init_extension::<UpdateFee>(&mut price_account_data)?.lamports = cmd.lamports;
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use {
+    super::simulate_aggregation,
+    crate::{
+        accounts::{
+            AggregationConfigAccount,
+            Feature,
+            FeatureGateAccount,
+            PriceAccount,
+            SharedSettings,
+        },
+        deserialize::{
+            load,
+            load_account_data,
+            load_checked,
+        },
+        instruction::CommandHeader,
+        utils::{
+            check_aggregation_complete,
+            pyth_assert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program::set_return_data,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+};
+
+/// Return the aggregate that aggregating the current quotes of the feed in the current slot would
+/// publish, as a `SimulatedAggregate`, so that publishers and operators can preview the effect of
+/// a quote or of a configuration change before it lands. The price account isn't changed. For the
+/// feeds following the `SharedSettings`, which require them like in `UpdPrice`, the feature gate
+/// decides whether the quotes are capped per entity and the aggregation config provides the
+/// defaults of the feed. They follow the clock in any order.
+// account[0] price account         []
+// account[1] sysvar_clock account  []
+// account[2] feature gate account  [] (feeds following the `SharedSettings`)
+// account[3] aggregation config    [] (feeds following the `SharedSettings`)
+pub fn simulate_aggregate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (price_account, clock_account, optional_accounts) = match accounts {
+        [x, y, optional @ ..] if optional.len() <= 2 => Ok((x, y, optional)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    {
+        let price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+        check_aggregation_complete(&price_data)?;
+    }
+
+    let mut feature_gate = None;
+    let mut aggregation_config = None;
+    for account in optional_accounts {
+        if FeatureGateAccount::is_feature_gate(program_id, account) {
+            feature_gate = Some(account);
+        } else {
+            pyth_assert(
+                AggregationConfigAccount::is_aggregation_config(program_id, account),
+                ProgramError::InvalidArgument,
+            )?;
+            aggregation_config = Some(account);
+        }
+    }
+    let (aggregation_config, weighted_aggregation) =
+        if SharedSettings::is_followed(&price_account.try_borrow_data()?)? {
+            let config = aggregation_config.ok_or(OracleError::MissingSharedSettings)?;
+            let feature_gate = feature_gate.ok_or(OracleError::MissingSharedSettings)?;
+            AggregationConfigAccount::check_address(program_id, config)?;
+            FeatureGateAccount::check_address(program_id, feature_gate)?;
+            (
+                Some(*load_account_data::<AggregationConfigAccount>(
+                    &config.try_borrow_data()?,
+                )?),
+                FeatureGateAccount::is_enabled(
+                    &feature_gate.try_borrow_data()?,
+                    Feature::WeightedAggregation,
+                    clock.slot,
+                )?,
+            )
+        } else {
+            (None, Feature::WeightedAggregation.enabled_by_default())
+        };
+
+    let simulated = simulate_aggregation(
+        price_account,
+        &clock,
+        weighted_aggregation,
+        aggregation_config.as_ref(),
+    )?;
+    set_return_data(bytemuck::bytes_of(&simulated));
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+            SnapshotAccount,
+            SNAPSHOT_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+        },
+        utils::{
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Copy the raw data of a price account into a snapshot account funded by the caller, so that the
+/// exact on-chain state can be preserved before remediation overwrites it. The snapshot account is
+/// the PDA `[SNAPSHOT_SEED, price account, slot]`, a price account can be snapshotted at most once
+/// per slot.
+// account[0] funding account       [signer writable]
+// account[1] price account         []
+// account[2] snapshot account      [writable]
+// account[3] sysvar_clock account  []
+// account[4] permissions account   []
+// account[5] system program        []
+pub fn snapshot_price_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (
+        funding_account,
+        price_account,
+        snapshot_account,
+        clock_account,
+        permissions_account,
+        system_program,
+    ) = match accounts {
+        [u, v, w, x, y, z] => Ok((u, v, w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    let hdr = load::<CommandHeader>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<CommandHeader>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (permissions_account, AccountCheck::Permissions),
+            (price_account, AccountCheck::Readable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    pyth_assert(
+        load_checked::<PermissionAccount>(permissions_account, hdr.version)?
+            .is_authorized(funding_account.key, OracleCommand::SnapshotPriceAccount),
+        OracleError::PermissionViolation.into(),
+    )?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, hdr.version)?;
+    }
+
+    let clock = Clock::from_account_info(clock_account)?;
+    let slot_bytes = clock.slot.to_le_bytes();
+    let (snapshot_pda_address, bump_seed) = Pubkey::find_program_address(
+        &[
+            SNAPSHOT_SEED.as_bytes(),
+            &price_account.key.to_bytes(),
+            &slot_bytes,
+        ],
+        program_id,
+    );
+    pyth_assert(
+        snapshot_pda_address == *snapshot_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    SnapshotAccount::create_pda(
+        snapshot_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[
+            SNAPSHOT_SEED.as_bytes(),
+            &price_account.key.to_bytes(),
+            &slot_bytes,
+            &[bump_seed],
+        ],
+        price_account.data_len(),
+    )?;
+
+    validate_accounts(program_id, &[(snapshot_account, AccountCheck::Writable)])?;
+    let snapshot_len = snapshot_account.data_len();
+    pyth_assert(
+        snapshot_len == SnapshotAccount::MINIMUM_SIZE + price_account.data_len(),
+        OracleError::AccountTooSmall.into(),
+    )?;
+
+    {
+        // Fails if this price account was already snapshotted in this slot
+        let mut snapshot_data = SnapshotAccount::initialize(snapshot_account, hdr.version)?;
+        snapshot_data.header.size = try_convert(snapshot_len)?;
+        snapshot_data.price_account = *price_account.key;
+        snapshot_data.slot = clock.slot;
+    }
+
+    snapshot_account.try_borrow_mut_data()?[SnapshotAccount::MINIMUM_SIZE..]
+        .copy_from_slice(&price_account.try_borrow_data()?);
+
+    Ok(())
+}
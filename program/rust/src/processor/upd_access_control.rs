@@ -0,0 +1,93 @@
+use {
+    crate::{
+        accounts::{
+            AccessControlAccount,
+            PythAccount,
+            MAX_ACCESS_CONTROL_READERS,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::UpdAccessControlArgs,
+        utils::{
+            check_permissioned_funding_account,
+            check_valid_funding_account,
+            pyth_assert,
+            valid_fresh_account,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Add a reader to an access control account or remove it, initializing the account the first
+/// time. Adding a listed reader or removing an unlisted one does nothing. Fails with
+/// `InvalidArgument` when the list is full.
+// account[0] funding account          [signer writable]
+// account[1] access control account   [signer writable]
+// account[2] permissions account      []
+pub fn upd_access_control(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<UpdAccessControlArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdAccessControlArgs>()
+            && cmd.allowed <= 1
+            && cmd.reader != Pubkey::default(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, access_control_account, permissions_account) = match accounts {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_valid_funding_account(funding_account)?;
+    check_permissioned_funding_account(
+        program_id,
+        access_control_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+
+    if valid_fresh_account(access_control_account) {
+        AccessControlAccount::initialize(access_control_account, cmd.header.version)?;
+    }
+    let mut access_control_data =
+        load_checked::<AccessControlAccount>(access_control_account, cmd.header.version)?;
+    let num_readers = access_control_data.readers().len();
+    let position = access_control_data
+        .readers()
+        .iter()
+        .position(|reader| *reader == cmd.reader);
+
+    match (cmd.allowed != 0, position) {
+        (true, None) => {
+            pyth_assert(
+                num_readers < MAX_ACCESS_CONTROL_READERS,
+                ProgramError::InvalidArgument,
+            )?;
+            access_control_data.readers[num_readers] = cmd.reader;
+            access_control_data.num_readers += 1;
+        }
+        (false, Some(position)) => {
+            access_control_data.readers[position] = access_control_data.readers[num_readers - 1];
+            access_control_data.readers[num_readers - 1] = Pubkey::default();
+            access_control_data.num_readers -= 1;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
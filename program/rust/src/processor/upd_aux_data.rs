@@ -0,0 +1,94 @@
+use {
+    super::find_publisher_index,
+    crate::{
+        accounts::{
+            get_price_and_extension_mut,
+            AuxData,
+            PriceAccount,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::UpdAuxDataArgs,
+        utils::{
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Submit the open interest and 24h volume of the market of the feed as one of its publishers.
+/// The submission replaces the previous one of the publisher and the medians of the `AuxData`
+/// extension are recomputed from the submissions recent within the maximum latency of the feed.
+/// The price and the aggregate aren't changed. Fails with `AuxDataNotEnabled` unless `SetAuxData`
+/// enabled the feed.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] sysvar_clock account  []
+pub fn upd_aux_data(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<UpdAuxDataArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdAuxDataArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, clock_account) = match accounts {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (price_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+    {
+        // Validate that price_account contains the appropriate account header
+        let _price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    }
+
+    let mut price_account_data = price_account.try_borrow_mut_data()?;
+    let (price_data, aux_data) =
+        match get_price_and_extension_mut::<AuxData>(&mut price_account_data)? {
+            Some((price_data, aux_data)) if aux_data.enabled != 0 => (price_data, aux_data),
+            _ => return Err(OracleError::AuxDataNotEnabled.into()),
+        };
+    let components = &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?];
+    let publisher_index = find_publisher_index(components, funding_account.key)
+        .ok_or(OracleError::PublisherNotFound)?;
+    aux_data.record(
+        components,
+        publisher_index,
+        clock.slot,
+        price_data.max_latency(),
+        (cmd.open_interest, cmd.volume_24h),
+    );
+
+    Ok(())
+}
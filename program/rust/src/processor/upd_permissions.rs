@@ -1,6 +1,7 @@
 use {
     crate::{
         accounts::{
+            check_is_upgrade_authority_for_program,
             PermissionAccount,
             PythAccount,
             PERMISSIONS_SEED,
@@ -11,42 +12,48 @@ use {
         },
         instruction::UpdPermissionsArgs,
         utils::{
-            check_is_upgrade_authority_for_program,
-            check_valid_funding_account,
-            check_valid_writable_account,
             pyth_assert,
+            validate_accounts,
+            AccountCheck,
         },
         OracleError,
     },
     solana_program::{
         account_info::AccountInfo,
+        clock::Clock,
         entrypoint::ProgramResult,
         pubkey::Pubkey,
-        system_program::check_id,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
     },
 };
 
 /// Updates permissions for the pyth oracle program
 /// This function can create and update the permissions accounts, which stores
-/// several public keys that can execute administrative instructions in the pyth program
+/// several public keys that can execute administrative instructions in the pyth program.
+/// If a timelock is set, the change is only staged, see `PermissionTimelock`.
 // account[0] upgrade authority     [signer writable]
 // account[1] programdata account   []
 // account[2] permissions account   [writable]
 // account[3] system program        []
+// account[4] sysvar_clock account  [] (required if a timelock is set)
 pub fn upd_permissions(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let [funding_account, programdata_account, permissions_account, system_program] = match accounts
-    {
-        [w, x, y, z] => Ok([w, x, y, z]),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, programdata_account, permissions_account, system_program, maybe_clock) =
+        match accounts {
+            [w, x, y, z] => Ok((w, x, y, z, None)),
+            [w, x, y, z, c] => Ok((w, x, y, z, Some(c))),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     let cmd_args = load::<UpdPermissionsArgs>(instruction_data)?;
 
-    check_valid_funding_account(funding_account)?;
+    validate_accounts(program_id, &[(funding_account, AccountCheck::Funding)])?;
     check_is_upgrade_authority_for_program(funding_account, programdata_account, program_id)?;
 
     let (permission_pda_address, bump_seed) =
@@ -55,12 +62,13 @@ pub fn upd_permissions(
         permission_pda_address == *permissions_account.key,
         OracleError::InvalidPda.into(),
     )?;
-
-    pyth_assert(
-        check_id(system_program.key),
-        OracleError::InvalidSystemAccount.into(),
-    )?;
-
+    validate_accounts(program_id, &[(system_program, AccountCheck::SystemProgram)])?;
+    if let Some(clock_account) = maybe_clock {
+        validate_accounts(
+            program_id,
+            &[(clock_account, AccountCheck::Sysvar(clock::id()))],
+        )?;
+    }
 
     // Create PermissionAccount if it doesn't exist
     PermissionAccount::initialize_pda(
@@ -72,7 +80,28 @@ pub fn upd_permissions(
         cmd_args.header.version,
     )?;
 
-    check_valid_writable_account(program_id, permissions_account)?;
+    // The permissions account is owned by the program once created
+    validate_accounts(program_id, &[(permissions_account, AccountCheck::Writable)])?;
+
+    let delay =
+        PermissionAccount::load_timelock(permissions_account)?.map_or(0, |timelock| timelock.delay);
+    if delay != 0 {
+        {
+            // Validate that permissions_account contains the appropriate account header
+            let _permissions_account_data =
+                load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
+        }
+        let clock_account = maybe_clock.ok_or(OracleError::InvalidNumberOfAccounts)?;
+        let clock = Clock::from_account_info(clock_account)?;
+        PermissionAccount::load_timelock_mut(permissions_account)?.stage(
+            cmd_args.master_authority,
+            cmd_args.data_curation_authority,
+            cmd_args.security_authority,
+            delay,
+            clock.slot,
+        );
+        return Ok(());
+    }
 
     let mut permissions_account_data =
         load_checked::<PermissionAccount>(permissions_account, cmd_args.header.version)?;
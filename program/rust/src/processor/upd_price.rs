@@ -1,25 +1,89 @@
 use {
+    super::{
+        push_snapshot,
+        put_all_messages,
+        AccumulatorEmitterAccounts,
+        MessageBufferAccounts,
+    },
     crate::{
         accounts::{
+            get_extension,
+            get_extension_mut,
+            get_price_and_extension_mut,
+            reward_score,
+            AggregateCache,
+            AggregationConfigAccount,
+            AggregationCounts,
+            AggregationDelay,
+            ConfFloor,
+            Feature,
+            FeatureGateAccount,
+            FeeVaultAccount,
+            FeedKind,
+            LatencyStats,
+            PermissionAccount,
             PriceAccount,
             PriceAccountFlags,
             PriceComponent,
+            PriceExtrema,
             PriceInfo,
+            PublishThreshold,
+            PublisherActivity,
+            PublisherLandingLatency,
+            PublisherRateLimit,
+            PublisherRewards,
+            PublisherSampling,
+            PublisherSequences,
+            PublisherTimestamps,
+            PublisherWeightCap,
             PythOracleSerialize,
-            UPD_PRICE_WRITE_SEED,
+            QuoteOrder,
+            SanityProgram,
+            SharedSettings,
+            SlotWeightedTwap,
+            StatusObserver,
+            StatusPolicy,
+            StatusReason,
+            StatusReasonCode,
+            UpdateCount,
+            UpdateFee,
+            UpdaterProgram,
+            DEFAULT_AGGREGATION_DELAY,
+        },
+        c_oracle_header::{
+            MAX_CI_DIVISOR,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
         },
         deserialize::{
             load,
+            load_account_data,
             load_checked,
+            load_mut,
+            OracleAccount,
+        },
+        instruction::{
+            PublisherActivityChange,
+            SanityCheckArgs,
+            SimulatedAggregate,
+            StatusTransition,
+            UpdPriceArgs,
+            PUBLISHER_ACTIVITY_LOG_TAG,
+            SANITY_CHECK_APPROVED,
+            STATUS_TRANSITION_LOG_TAG,
         },
-        instruction::UpdPriceArgs,
         utils::{
-            check_valid_funding_account,
-            check_valid_writable_account,
-            get_status_for_conf_price_ratio,
+            check_aggregation_complete,
+            get_status_for_max_ci_divisor,
             is_component_update,
+            is_forwarded_by,
+            is_isolated_transaction,
             pyth_assert,
+            send_lamports,
             try_convert,
+            validate_accounts,
+            AccountCheck,
         },
         OracleError,
     },
@@ -27,15 +91,28 @@ use {
         account_info::AccountInfo,
         clock::Clock,
         entrypoint::ProgramResult,
+        hash::Hash,
         instruction::{
             AccountMeta,
             Instruction,
         },
-        program::invoke_signed,
+        log::sol_log_data,
+        program::{
+            get_return_data,
+            invoke,
+        },
         program_error::ProgramError,
         program_memory::sol_memcmp,
         pubkey::Pubkey,
-        sysvar::Sysvar,
+        system_program,
+        sysvar::{
+            self,
+            Sysvar,
+        },
+    },
+    std::{
+        cmp::Reverse,
+        mem::size_of,
     },
 };
 
@@ -44,7 +121,14 @@ use {
 extern "C" {
     pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
 
-    #[allow(unused)]
+    pub fn c_upd_aggregate_sorted(
+        _input: *mut u8,
+        clock_slot: u64,
+        clock_timestamp: i64,
+        order: *const u8,
+        order_len: u32,
+    ) -> bool;
+
     pub fn c_upd_twap(_input: *mut u8, nslots: i64);
 }
 
@@ -53,7 +137,14 @@ extern "C" {
 extern "C" {
     pub fn c_upd_aggregate_pythnet(_input: *mut u8, clock_slot: u64, clock_timestamp: i64) -> bool;
 
-    #[allow(unused)]
+    pub fn c_upd_aggregate_sorted(
+        _input: *mut u8,
+        clock_slot: u64,
+        clock_timestamp: i64,
+        order: *const u8,
+        order_len: u32,
+    ) -> bool;
+
     pub fn c_upd_twap(_input: *mut u8, nslots: i64);
 }
 
@@ -62,7 +153,9 @@ pub unsafe fn c_upd_aggregate(input: *mut u8, clock_slot: u64, clock_timestamp:
     c_upd_aggregate_pythnet(input, clock_slot, clock_timestamp)
 }
 
-/// Publish component price, never returning an error even if the update failed
+/// Publish component price, never returning an error even if the update failed, unless the
+/// update failed in the middle of an aggregation: the error then aborts the transaction so that
+/// the partial aggregation is rolled back.
 // account[0] funding account       [signer writable]
 // account[1] price account         [writable]
 // account[2] sysvar_clock account  []
@@ -72,14 +165,33 @@ pub fn upd_price_no_fail_on_error(
     instruction_data: &[u8],
 ) -> ProgramResult {
     match upd_price(program_id, accounts, instruction_data) {
+        Err(err) if is_aggregation_interrupted(accounts) => Err(err),
         Err(_) => Ok(()),
         Ok(value) => Ok(value),
     }
 }
 
+/// Whether the price account of a failed update is left flagged as aggregating.
+fn is_aggregation_interrupted(accounts: &[AccountInfo]) -> bool {
+    accounts.get(1).map_or(false, |price_account| {
+        price_account.try_borrow_data().map_or(false, |data| {
+            load_account_data::<PriceAccount>(&data).map_or(false, |price_data| {
+                price_data
+                    .flags
+                    .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS)
+            })
+        })
+    })
+}
+
 /// Update a publisher's price for the provided product. If this update is
 /// the first update in a slot, this operation will also trigger price aggregation
-/// and result in a new aggregate price in the account.
+/// and result in a new aggregate price in the account. The aggregate only covers the quotes
+/// that landed in earlier slots, unless the feed has an `AggregationDelay` of 0: then every
+/// update aggregates again after writing its quote, or only the first one of the slot with an
+/// `AggregateCache`. The clock repeating the slot of the latest aggregate only writes the quote,
+/// while a clock behind the latest aggregate or the latest quote of the publisher, e.g. after a
+/// restart or on a fork, fails with `ClockRegression` and leaves the account unchanged.
 ///
 /// account[0] the publisher's account (funds the tx) [signer writable]
 ///            fails if the publisher's public key is not permissioned for the price account.
@@ -97,49 +209,178 @@ pub fn upd_price_no_fail_on_error(
 ///            which allows the called-into program to authenticate that it is being invoked by the oracle
 ///            program. []
 /// account[6] message buffer data [writable]
+///
+/// Feeds with a `SanityProgram` need it as the last account, after either form of the instruction,
+/// to publish their aggregates. Feeds with a `StatusObserver` notify it of their status transitions
+/// when it's passed before or after the sanity program. Feeds following the `SharedSettings` need
+/// the `AggregationConfigAccount` next, to aggregate with the shared defaults where they don't set
+/// their own quorum or latency, and the feature gate after it to switch their `Feature`s. The
+/// other feeds ignore them and take the default of every feature. The permissions account, the
+/// `AccumulatorEmitter` it sets and the oracle PDA derived from seeds ["upd_price_write", emitter]
+/// can follow to push every new aggregate to the emitter. Feeds with an `UpdateFee` need the fee
+/// vault and the system program next for the publisher to pay the fee of every quote, their quotes
+/// are rejected without them. Feeds with a `PublisherSampling` need the slot hashes sysvar next to
+/// sample their quotes, they count every quote without it. Feeds with the `ISOLATED_UPDATES` flag
+/// need the instructions sysvar after all of these to accept the updates, and only accept the ones
+/// in transactions without instructions of other programs apart from the compute budget program.
+/// Feeds with an `UpdaterProgram` need it too, and only accept the updates of instructions of that
+/// program.
 pub fn upd_price(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let cmd_args = load::<UpdPriceArgs>(instruction_data)?;
+    upd_price_with_args(
+        program_id,
+        accounts,
+        &UpdPriceArgs::decode(instruction_data)?,
+    )
+}
+
+/// `upd_price` with decoded arguments, shared by the instructions encoding them differently.
+pub fn upd_price_with_args(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    cmd_args: &UpdPriceArgs,
+) -> ProgramResult {
+    let (accounts_before_sysvar, instructions_sysvar) = match accounts.split_last() {
+        Some((last, others)) if sysvar::instructions::check_id(last.key) => (others, Some(last)),
+        _ => (accounts, None),
+    };
+    let (accounts_before_slot_hashes, slot_hashes) = match accounts_before_sysvar.split_last() {
+        Some((last, others)) if sysvar::slot_hashes::check_id(last.key) => (others, Some(last)),
+        _ => (accounts_before_sysvar, None),
+    };
+    let (accounts_before_fees, fee_payment) = match accounts_before_slot_hashes {
+        [others @ .., fee_vault, system]
+            if system_program::check_id(system.key)
+                && FeeVaultAccount::is_fee_vault(program_id, fee_vault) =>
+        {
+            (others, Some((fee_vault, system)))
+        }
+        _ => (accounts_before_slot_hashes, None),
+    };
+    let (accounts_before_emitter, emitter_accounts) = match accounts_before_fees {
+        [others @ .., permissions, emitter, oracle_auth_pda]
+            if PermissionAccount::is_permissions_account(program_id, permissions) =>
+        {
+            (
+                others,
+                Some(AccumulatorEmitterAccounts {
+                    permissions,
+                    program_id: emitter,
+                    oracle_auth_pda,
+                }),
+            )
+        }
+        _ => (accounts_before_fees, None),
+    };
+    let (accounts_before_feature_gate, feature_gate) = match accounts_before_emitter.split_last() {
+        Some((last, others)) if FeatureGateAccount::is_feature_gate(program_id, last) => {
+            (others, Some(last))
+        }
+        _ => (accounts_before_emitter, None),
+    };
+    let (accounts_before_config, aggregation_config) =
+        match accounts_before_feature_gate.split_last() {
+            Some((last, others))
+                if AggregationConfigAccount::is_aggregation_config(program_id, last) =>
+            {
+                (others, Some(last))
+            }
+            _ => (accounts_before_feature_gate, None),
+        };
 
+    // The sanity program and the status observer of the feed are told apart by their key
     #[allow(unused_variables)]
-    let (funding_account, price_account, clock_account, maybe_accumulator_accounts) = match accounts
-    {
-        [x, y, z] => Ok((x, y, z, None)),
-        // Note: this version of the instruction exists for backward compatibility when publishers were including a
-        // now superfluous account in the instruction.
-        [x, y, _, z] => Ok((x, y, z, None)),
-        [x, y, z, a, b, c, d] => Ok((
-            x,
-            y,
-            z,
-            Some(MessageBufferAccounts {
-                program_id:          a,
-                whitelist:           b,
-                oracle_auth_pda:     c,
-                message_buffer_data: d,
-            }),
-        )),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
-
-    check_valid_funding_account(funding_account)?;
-    check_valid_writable_account(program_id, price_account)?;
-    // Check clock
+    let (funding_account, price_account, clock_account, maybe_accumulator_accounts, programs) =
+        match accounts_before_config {
+            // The clock is the last account of the legacy version below
+            [x, y, z, programs @ ..]
+                if programs.len() <= 2
+                    && !programs
+                        .iter()
+                        .any(|program| sysvar::clock::check_id(program.key)) =>
+            {
+                Ok((x, y, z, None, programs))
+            }
+            // Note: this version of the instruction exists for backward compatibility when publishers were including a
+            // now superfluous account in the instruction.
+            [x, y, _, z] => Ok((x, y, z, None, &[][..])),
+            [x, y, z, a, b, c, d, programs @ ..] if programs.len() <= 2 => Ok((
+                x,
+                y,
+                z,
+                Some(MessageBufferAccounts {
+                    program_id:          a,
+                    whitelist:           b,
+                    oracle_auth_pda:     c,
+                    message_buffer_data: d,
+                }),
+                programs,
+            )),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (price_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(sysvar::clock::id())),
+        ],
+    )?;
     let clock = Clock::from_account_info(clock_account)?;
 
+    // The defaults shared by the feeds are copied, the aggregation borrows the price account
+    // while it applies them. The publishers of the feeds following them can't leave them out.
+    let (aggregation_config, feature_gate) =
+        if SharedSettings::is_followed(&price_account.try_borrow_data()?)? {
+            let config = aggregation_config.ok_or(OracleError::MissingSharedSettings)?;
+            let feature_gate = feature_gate.ok_or(OracleError::MissingSharedSettings)?;
+            AggregationConfigAccount::check_address(program_id, config)?;
+            FeatureGateAccount::check_address(program_id, feature_gate)?;
+            (
+                Some(*load_account_data::<AggregationConfigAccount>(
+                    &config.try_borrow_data()?,
+                )?),
+                Some(feature_gate),
+            )
+        } else {
+            (None, None)
+        };
+
+    // Feeds opt into sampling their quotes by holding the extension, the sample is drawn from the
+    // slot hashes
+    let sampling_seed = match slot_hashes {
+        Some(slot_hashes) => {
+            validate_accounts(
+                program_id,
+                &[(slot_hashes, AccountCheck::Sysvar(sysvar::slot_hashes::id()))],
+            )?;
+            Some(PublisherSampling::seed(
+                &most_recent_slot_hash(slot_hashes)?,
+                price_account.key,
+                clock.slot,
+            ))
+        }
+        None => None,
+    };
+
+    // Verify that symbol account is initialized
+    let price = OracleAccount::<PriceAccount>::new(price_account, cmd_args.header.version)?;
+
     let publisher_index: usize;
     let latest_aggregate_price: PriceInfo;
     let flags: PriceAccountFlags;
+    let feed_kind: Option<FeedKind>;
 
     // The price_data borrow happens in a scope because it must be
     // dropped before we borrow again as raw data pointer for the C
     // aggregation logic.
     {
-        // Verify that symbol account is initialized
-        let price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+        let price_data = price.load()?;
+        check_aggregation_complete(&price_data)?;
 
         publisher_index = match find_publisher_index(
             &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
@@ -147,62 +388,276 @@ pub fn upd_price(
         ) {
             Some(index) => index,
             None => {
-                return Err(OracleError::PermissionViolation.into());
+                return Err(OracleError::PublisherNotFound.into());
             }
         };
 
         latest_aggregate_price = price_data.agg_;
         let latest_publisher_price = price_data.comp_[publisher_index].latest_;
 
-        // Check that publisher is publishing a more recent price
+        // Neither aggregate nor overwrite the quote with a clock that went backwards, the
+        // aggregation relies on the quotes not being ahead of the clock
         pyth_assert(
-            !is_component_update(cmd_args)?
-                || (cmd_args.publishing_slot > latest_publisher_price.pub_slot_
-                    && cmd_args.publishing_slot <= clock.slot),
-            ProgramError::InvalidArgument,
+            clock.slot >= latest_aggregate_price.pub_slot_
+                && (!is_component_update(cmd_args)?
+                    || clock.slot >= latest_publisher_price.pub_slot_),
+            OracleError::ClockRegression.into(),
         )?;
 
+        // Check that publisher is publishing a more recent price, without claiming a slot in the
+        // future
+        if is_component_update(cmd_args)? {
+            pyth_assert(
+                cmd_args.publishing_slot > latest_publisher_price.pub_slot_,
+                OracleError::StaleSubmission.into(),
+            )?;
+            pyth_assert(
+                cmd_args.publishing_slot <= clock.slot,
+                OracleError::PublishingSlotInFuture.into(),
+            )?;
+        }
+
         flags = price_data.flags;
+        feed_kind = price_data.feed_kind();
     }
 
-    if !flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
-        // Try to update the aggregate
-        #[allow(unused_variables)]
-        if clock.slot > latest_aggregate_price.pub_slot_ {
-            let updated = unsafe {
-                // NOTE: c_upd_aggregate must use a raw pointer to price
-                // data. Solana's `<account>.borrow_*` methods require exclusive
-                // access, i.e. no other borrow can exist for the account.
-                c_upd_aggregate(
-                    price_account.try_borrow_mut_data()?.as_mut_ptr(),
-                    clock.slot,
-                    clock.unix_timestamp,
-                )
-            };
+    // Feeds opt into isolated updates with a flag, to keep other programs from publishing and
+    // using a price atomically
+    if is_component_update(cmd_args)? && flags.contains(PriceAccountFlags::ISOLATED_UPDATES) {
+        let isolated = match instructions_sysvar {
+            Some(instructions_sysvar) => is_isolated_transaction(program_id, instructions_sysvar)?,
+            None => false,
+        };
+        pyth_assert(isolated, OracleError::UpdateNotIsolated.into())?;
+    }
 
-            // If the aggregate was successfully updated, calculate the difference and update TWAP.
-            if updated {
-                let agg_diff = (clock.slot as i64)
-                    - load_checked::<PriceAccount>(price_account, cmd_args.header.version)?
-                        .prev_slot_ as i64;
-                // Encapsulate TWAP update logic in a function to minimize unsafe block scope.
-                unsafe {
-                    c_upd_twap(price_account.try_borrow_mut_data()?.as_mut_ptr(), agg_diff);
-                }
-                let mut price_data =
-                    load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
-                // We want to send a message every time the aggregate price updates. However, during the migration,
-                // not every publisher will necessarily provide the accumulator accounts. The message_sent_ flag
-                // ensures that after every aggregate update, the next publisher who provides the accumulator accounts
-                // will send the message.
-                price_data.message_sent_ = 0;
-                price_data.update_price_cumulative();
+    // Feeds opt into relaying their quotes through a program by holding the extension
+    if is_component_update(cmd_args)? {
+        if let Some(updater) = get_extension::<UpdaterProgram>(&price_account.try_borrow_data()?)? {
+            if updater.program_id != Pubkey::default() {
+                let forwarded = match instructions_sysvar {
+                    Some(instructions_sysvar) => {
+                        is_forwarded_by(&updater.program_id, instructions_sysvar)?
+                    }
+                    None => false,
+                };
+                pyth_assert(forwarded, OracleError::UpdateNotForwarded.into())?;
             }
         }
     }
 
+    // Feeds opt into checking the sequence numbers of the quotes by holding the extension, so
+    // that a relayer can't replay or reorder the quotes of a publisher
+    if is_component_update(cmd_args)? {
+        if let Some(sequences) =
+            get_extension::<PublisherSequences>(&price_account.try_borrow_data()?)?
+        {
+            pyth_assert(
+                sequences.enabled == 0
+                    || sequences.allows(publisher_index, funding_account.key, cmd_args.sequence),
+                OracleError::SequenceReplayed.into(),
+            )?;
+        }
+    }
+
+    // Feeds opt into rate limiting by holding the extension
+    if is_component_update(cmd_args)? {
+        if let Some(rate_limit) =
+            get_extension::<PublisherRateLimit>(&price_account.try_borrow_data()?)?
+        {
+            pyth_assert(
+                rate_limit.allows_update(publisher_index, funding_account.key, clock.slot),
+                OracleError::UpdateRateLimited.into(),
+            )?;
+        }
+    }
+
+    // Feeds opt into charging their quotes by holding the extension, the quote is rejected unless
+    // the publisher passes the fee vault. The fee is only charged once the quote is stored.
+    let mut update_fee = None;
+    if is_component_update(cmd_args)? {
+        if let Some(fee) = get_extension::<UpdateFee>(&price_account.try_borrow_data()?)? {
+            if fee.lamports > 0 {
+                let (fee_vault, system_program) =
+                    fee_payment.ok_or(OracleError::UpdateFeeNotPaid)?;
+                update_fee = Some((fee.lamports, fee_vault, system_program));
+            }
+        }
+    }
+
+    let is_enabled = |feature: Feature| -> Result<bool, ProgramError> {
+        Ok(match feature_gate {
+            Some(feature_gate) => FeatureGateAccount::is_enabled(
+                &feature_gate.try_borrow_data()?,
+                feature,
+                clock.slot,
+            )?,
+            None => feature.enabled_by_default(),
+        })
+    };
+    let weighted_aggregation = is_enabled(Feature::WeightedAggregation)?;
+
+    // Feeds opt into a different aggregation delay by holding the extension. An invalid
+    // extension region falls back to the default delay, as does a delay of 0 while same slot
+    // aggregation is disabled.
+    let aggregation_delay =
+        match get_extension::<AggregationDelay>(&price_account.try_borrow_data()?) {
+            Ok(Some(delay)) if delay.slots != 0 || is_enabled(Feature::SameSlotAggregation)? => {
+                delay.slots
+            }
+            _ => DEFAULT_AGGREGATION_DELAY,
+        };
+    let aggregate_in_program = !flags.contains(PriceAccountFlags::ACCUMULATOR_V2);
+
+    // Try to update the aggregate with the quotes of the earlier slots
+    let mut published = false;
+    if aggregate_in_program
+        && aggregation_delay != 0
+        && clock.slot > latest_aggregate_price.pub_slot_
+    {
+        published |= update_aggregate(
+            &price,
+            &clock,
+            programs,
+            weighted_aggregation,
+            sampling_seed.as_ref(),
+            aggregation_config.as_ref(),
+        )?;
+    }
+
+    // Try to update the publisher's price
+    if is_component_update(cmd_args)? {
+        // IMPORTANT: If the publisher does not meet the price/conf
+        // ratio condition, its price will not count for the aggregate.
+        // Funding rates are close to 0, so their quotes skip the condition.
+        let status: u32 = if feed_kind == Some(FeedKind::Funding) {
+            cmd_args.status
+        } else {
+            get_status_for_max_ci_divisor(
+                cmd_args.price,
+                cmd_args.confidence,
+                cmd_args.status,
+                aggregation_config
+                    .as_ref()
+                    .map_or(MAX_CI_DIVISOR, AggregationConfigAccount::max_ci_divisor),
+            )?
+        };
+
+        {
+            let mut price_data = price.load_mut()?;
+            let publisher_price = &mut price_data.comp_[publisher_index].latest_;
+            publisher_price.price_ = cmd_args.price;
+            publisher_price.conf_ = cmd_args.confidence;
+            publisher_price.status_ = status;
+            publisher_price.set_tags(cmd_args.venue, cmd_args.status);
+            publisher_price.pub_slot_ = cmd_args.publishing_slot;
+        }
+
+        // Feeds opt into landing latency statistics by holding the extension. An invalid
+        // extension region never fails the update.
+        if let Ok(Some(landing_latency)) =
+            get_extension_mut::<PublisherLandingLatency>(&mut price_account.try_borrow_mut_data()?)
+        {
+            landing_latency.components[publisher_index].update(
+                funding_account.key,
+                clock.slot.saturating_sub(cmd_args.publishing_slot),
+            );
+        }
+        if let Ok(Some(latency_stats)) =
+            get_extension_mut::<LatencyStats>(&mut price_account.try_borrow_mut_data()?)
+        {
+            latency_stats.record_update(clock.slot.saturating_sub(cmd_args.publishing_slot));
+        }
+        if let Some(rate_limit) =
+            get_extension_mut::<PublisherRateLimit>(&mut price_account.try_borrow_mut_data()?)?
+        {
+            rate_limit.record_update(publisher_index, funding_account.key, clock.slot);
+        }
+        if let Ok(Some(update_count)) =
+            get_extension_mut::<UpdateCount>(&mut price_account.try_borrow_mut_data()?)
+        {
+            if update_count.enabled != 0 {
+                update_count.updates += 1;
+            }
+        }
+        if let Ok(Some(timestamps)) =
+            get_extension_mut::<PublisherTimestamps>(&mut price_account.try_borrow_mut_data()?)
+        {
+            if timestamps.enabled != 0 {
+                timestamps.record(
+                    publisher_index,
+                    funding_account.key,
+                    cmd_args.publishing_slot,
+                    clock.unix_timestamp,
+                );
+            }
+        }
+        if let Some(sequences) =
+            get_extension_mut::<PublisherSequences>(&mut price_account.try_borrow_mut_data()?)?
+        {
+            if sequences.enabled != 0 {
+                sequences.record(publisher_index, funding_account.key, cmd_args.sequence);
+            }
+        }
+        // Feeds opt into maintaining the order of their quotes by holding the extension. An
+        // invalid extension region never fails the update.
+        if let Ok(Some((price_data, order))) =
+            get_price_and_extension_mut::<QuoteOrder>(&mut price_account.try_borrow_mut_data()?)
+        {
+            if order.enabled != 0 {
+                order.update(
+                    &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+                    publisher_index,
+                );
+            }
+        }
+
+        if let Some((fee, fee_vault, system_program)) = update_fee {
+            send_lamports(funding_account, fee_vault, system_program, fee)?;
+            load_checked::<FeeVaultAccount>(fee_vault, cmd_args.header.version)?.total_collected +=
+                fee;
+        }
+    }
+
+    // Without delay, aggregate again with the quote that was just written, unless the feed keeps
+    // the aggregate of the slot once it's computed. An invalid extension region aggregates again.
+    let aggregate_cached = latest_aggregate_price.pub_slot_ == clock.slot
+        && matches!(
+            get_extension::<AggregateCache>(&price_account.try_borrow_data()?),
+            Ok(Some(cache)) if cache.enabled != 0
+        );
+    if aggregate_in_program && aggregation_delay == 0 && !aggregate_cached {
+        published |= update_aggregate(
+            &price,
+            &clock,
+            programs,
+            weighted_aggregation,
+            sampling_seed.as_ref(),
+            aggregation_config.as_ref(),
+        )?;
+    }
+
+    // Deployments pushing the aggregates into the cross-chain pipeline send every new one to the
+    // accumulator emitter, if the publisher listed it. Community feeds aren't curated by
+    // governance, so they stay out of it.
+    let cross_chain = !flags.contains(PriceAccountFlags::COMMUNITY);
+    if published && cross_chain {
+        if let Some(emitter_accounts) = &emitter_accounts {
+            let messages = {
+                let price_data = price.load()?;
+                vec![
+                    price_data
+                        .as_price_feed_message(price_account.key)
+                        .to_bytes(),
+                    price_data.as_twap_message(price_account.key).to_bytes(),
+                ]
+            };
+            push_snapshot(program_id, emitter_accounts, price_account, messages)?;
+        }
+    }
+
     // Reload price data as a struct after c_upd_aggregate() borrow is dropped
-    let mut price_data = load_checked::<PriceAccount>(price_account, cmd_args.header.version)?;
+    let mut price_data = price.load_mut()?;
 
     // Feature-gated accumulator-specific code, used only on pythnet/pythtest
     let need_message_buffer_update = if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
@@ -210,42 +665,12 @@ pub fn upd_price(
         !flags.contains(PriceAccountFlags::MESSAGE_BUFFER_CLEARED)
     } else {
         // V1
-        price_data.message_sent_ == 0
+        price_data.message_sent_ == 0 && cross_chain
     };
 
     if need_message_buffer_update {
         if let Some(accumulator_accounts) = maybe_accumulator_accounts {
-            // Check that the oracle PDA is correctly configured for the program we are calling.
-            let oracle_auth_seeds: &[&[u8]] = &[
-                UPD_PRICE_WRITE_SEED.as_bytes(),
-                &accumulator_accounts.program_id.key.to_bytes(),
-            ];
-            let (expected_oracle_auth_pda, bump) =
-                Pubkey::find_program_address(oracle_auth_seeds, program_id);
-            pyth_assert(
-                expected_oracle_auth_pda == *accumulator_accounts.oracle_auth_pda.key,
-                OracleError::InvalidPda.into(),
-            )?;
-
-            let account_metas = vec![
-                AccountMeta {
-                    pubkey:      *accumulator_accounts.whitelist.key,
-                    is_signer:   false,
-                    is_writable: false,
-                },
-                AccountMeta {
-                    pubkey:      *accumulator_accounts.oracle_auth_pda.key,
-                    is_signer:   true,
-                    is_writable: false,
-                },
-                AccountMeta {
-                    pubkey:      *accumulator_accounts.message_buffer_data.key,
-                    is_signer:   false,
-                    is_writable: true,
-                },
-            ];
-
-            let message = if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
+            let messages = if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
                 vec![]
             } else {
                 vec![
@@ -255,22 +680,13 @@ pub fn upd_price(
                     price_data.as_twap_message(price_account.key).to_bytes(),
                 ]
             };
-
-            // anchor discriminator for "global:put_all"
-            let discriminator: [u8; 8] = [212, 225, 193, 91, 151, 238, 20, 93];
-            let create_inputs_ix = Instruction::new_with_borsh(
-                *accumulator_accounts.program_id.key,
-                &(discriminator, price_account.key.to_bytes(), message),
-                account_metas,
-            );
-
-            let auth_seeds_with_bump: &[&[u8]] = &[
-                UPD_PRICE_WRITE_SEED.as_bytes(),
-                &accumulator_accounts.program_id.key.to_bytes(),
-                &[bump],
-            ];
-
-            invoke_signed(&create_inputs_ix, accounts, &[auth_seeds_with_bump])?;
+            put_all_messages(
+                program_id,
+                accounts,
+                &accumulator_accounts,
+                price_account.key,
+                messages,
+            )?;
             price_data.message_sent_ = 1;
             if flags.contains(PriceAccountFlags::ACCUMULATOR_V2) {
                 price_data
@@ -280,23 +696,706 @@ pub fn upd_price(
         }
     }
 
-    // Try to update the publisher's price
-    if is_component_update(cmd_args)? {
-        // IMPORTANT: If the publisher does not meet the price/conf
-        // ratio condition, its price will not count for the next
-        // aggregate.
-        let status: u32 =
-            get_status_for_conf_price_ratio(cmd_args.price, cmd_args.confidence, cmd_args.status)?;
+    Ok(())
+}
+
+/// Aggregate the quotes of the price account in the current slot. Aggregating again in the slot of
+/// the latest aggregate replaces it: the previous aggregate is kept and the slot isn't counted
+/// twice in the TWAP and the cumulative sums. The `PublisherWeightCap` of the feed limits the
+/// quotes of every entity with `weighted_aggregation`, its sanity program can veto the aggregate
+/// and its `PublishThreshold` can keep the published one. Feeds maintaining a `QuoteOrder` merge
+/// their quotes along it instead of sorting them, and feeds without their own quorum or latency
+/// use the defaults of the `AggregationConfigAccount`. The aggregation is flagged as in progress
+/// until `agg_`, the EMA and the cumulative sums are all updated. Returns whether the aggregation
+/// published a new aggregate.
+fn update_aggregate(
+    price: &OracleAccount<PriceAccount>,
+    clock: &Clock,
+    programs: &[AccountInfo],
+    weighted_aggregation: bool,
+    sampling_seed: Option<&Hash>,
+    aggregation_config: Option<&AggregationConfigAccount>,
+) -> Result<bool, ProgramError> {
+    price
+        .load_mut()?
+        .flags
+        .insert(PriceAccountFlags::AGGREGATION_IN_PROGRESS);
+    let updated = aggregate_quotes(
+        price,
+        clock,
+        programs,
+        weighted_aggregation,
+        sampling_seed,
+        aggregation_config,
+    )?;
+    price
+        .load_mut()?
+        .flags
+        .remove(PriceAccountFlags::AGGREGATION_IN_PROGRESS);
+    Ok(updated)
+}
+
+fn aggregate_quotes(
+    price: &OracleAccount<PriceAccount>,
+    clock: &Clock,
+    programs: &[AccountInfo],
+    weighted_aggregation: bool,
+    sampling_seed: Option<&Hash>,
+    aggregation_config: Option<&AggregationConfigAccount>,
+) -> Result<bool, ProgramError> {
+    let price_account = price.info();
+    let (
+        valid_slot,
+        prev_slot,
+        prev_price,
+        prev_conf,
+        prev_timestamp,
+        timestamp,
+        aggregate,
+        last_slot,
+        reaggregation,
+        traded_in_slot,
+    ) = {
+        let price_data = price.load()?;
+        (
+            price_data.valid_slot_,
+            price_data.prev_slot_,
+            price_data.prev_price_,
+            price_data.prev_conf_,
+            price_data.prev_timestamp_,
+            price_data.timestamp_,
+            price_data.agg_,
+            price_data.last_slot_,
+            price_data.agg_.pub_slot_ == clock.slot,
+            price_data.last_slot_ == clock.slot,
+        )
+    };
+
+    // The order is copied so that the C code doesn't read the account it writes through another
+    // pointer
+    let quote_order = match get_extension::<QuoteOrder>(&price_account.try_borrow_data()?) {
+        Ok(Some(order)) if order.enabled != 0 => Some((order.lists, order.len)),
+        _ => None,
+    };
+
+    // Feeds without their own quorum or latency aggregate with the shared defaults, and feeds
+    // demoting their stale publishers with a quorum of their active publishers. The quorum and the
+    // latency of the feed are restored right after.
+    let (min_pub, max_latency) = {
+        let price_data = price.load()?;
+        (price_data.min_pub_, price_data.max_latency_)
+    };
+    if let Some(config) = aggregation_config {
+        config.apply_defaults(&mut price.load_mut()?);
+    }
+    if let Some(active_publishers) = record_publisher_activity(price_account, clock.slot)? {
+        let mut price_data = price.load_mut()?;
+        price_data.min_pub_ = demoted_quorum(price_data.min_pub_, active_publishers);
+    }
+
+    // The quotes over the cap of their entity, and the valid quotes left out of the sample of
+    // the slot, are ignored by the aggregation. Their component keeps `PC_STATUS_IGNORED` in
+    // `agg_` to show it didn't count.
+    let mut ignored_components = if weighted_aggregation {
+        find_capped_components(&price_account.try_borrow_data()?, clock.slot)?
+    } else {
+        vec![]
+    };
+    if let Some(seed) = sampling_seed {
+        let unsampled_components =
+            find_unsampled_components(price_account, clock.slot, &ignored_components, seed)?;
+        ignored_components.extend(unsampled_components);
+    }
+    if !ignored_components.is_empty() {
+        let mut price_data = price.load_mut()?;
+        for index in &ignored_components {
+            price_data.comp_[*index].latest_.status_ = PC_STATUS_IGNORED;
+        }
+    }
+
+    let mut updated = unsafe {
+        // NOTE: c_upd_aggregate must use a raw pointer to price
+        // data. Solana's `<account>.borrow_*` methods require exclusive
+        // access, i.e. no other borrow can exist for the account.
+        match &quote_order {
+            Some((lists, len)) => c_upd_aggregate_sorted(
+                price_account.try_borrow_mut_data()?.as_mut_ptr(),
+                clock.slot,
+                clock.unix_timestamp,
+                lists.as_ptr() as *const u8,
+                *len,
+            ),
+            None => c_upd_aggregate(
+                price_account.try_borrow_mut_data()?.as_mut_ptr(),
+                clock.slot,
+                clock.unix_timestamp,
+            ),
+        }
+    };
+
+    if !ignored_components.is_empty() {
+        let mut price_data = price.load_mut()?;
+        for index in &ignored_components {
+            price_data.comp_[*index].latest_.status_ = PC_STATUS_TRADING;
+        }
+    }
+    {
+        let mut price_data = price.load_mut()?;
+        price_data.min_pub_ = min_pub;
+        price_data.max_latency_ = max_latency;
+    }
 
+    // Feeds holding a status policy report the halt or the auction of their publishers instead
+    // of an aggregate. An aggregate of the trading quotes it replaces is dropped like a vetoed
+    // one.
+    let policy_status = match get_extension::<StatusPolicy>(&price_account.try_borrow_data()?) {
+        Ok(Some(policy)) => policy.status(&price.load()?, clock.slot),
+        _ => None,
+    };
+    if let Some(status) = policy_status {
+        let mut price_data = price.load_mut()?;
+        if updated {
+            price_data.agg_.price_ = aggregate.price_;
+            price_data.agg_.conf_ = aggregate.conf_;
+            price_data.last_slot_ = last_slot;
+            updated = false;
+        }
+        price_data.agg_.status_ = status;
+    }
+
+    // The floor widens the aggregate before the sanity program checks it
+    if updated {
+        let conf_floor = match get_extension::<ConfFloor>(&price_account.try_borrow_data()?) {
+            Ok(Some(conf_floor)) => Some(*conf_floor),
+            _ => None,
+        };
+        if let Some(conf_floor) = conf_floor {
+            let mut price_data = price.load_mut()?;
+            price_data.agg_.conf_ = conf_floor.apply(price_data.agg_.price_, price_data.agg_.conf_);
+        }
+    }
+
+    // Every aggregation closes a latency sample, whether or not it produced an aggregate
+    if let Ok(Some(latency_stats)) =
+        get_extension_mut::<LatencyStats>(&mut price_account.try_borrow_mut_data()?)
+    {
+        latency_stats.record_aggregation();
+    }
+
+    if reaggregation {
+        let mut price_data = price.load_mut()?;
+        price_data.valid_slot_ = valid_slot;
+        price_data.prev_slot_ = prev_slot;
+        price_data.prev_price_ = prev_price;
+        price_data.prev_conf_ = prev_conf;
+        price_data.prev_timestamp_ = prev_timestamp;
+    }
+
+    // A vetoed aggregate is dropped like one without enough valid quotes
+    let vetoed = updated && !approve_aggregate(price, clock, programs)?;
+    if vetoed {
+        let mut price_data = price.load_mut()?;
+        price_data.agg_.price_ = aggregate.price_;
+        price_data.agg_.conf_ = aggregate.conf_;
+        price_data.agg_.status_ = PC_STATUS_UNKNOWN;
+        price_data.last_slot_ = last_slot;
+        updated = false;
+    }
+
+    // Feeds publishing on change keep their trading aggregate while the new one stays within the
+    // threshold, as if the aggregation didn't happen. The aggregate of the current slot is already
+    // published, aggregating again replaces it.
+    let held = updated
+        && !reaggregation
+        && aggregate.status_ == PC_STATUS_TRADING
+        && matches!(
+            get_extension::<PublishThreshold>(&price_account.try_borrow_data()?),
+            Ok(Some(threshold)) if !threshold.publishes(
+                (aggregate.price_, aggregate.pub_slot_),
+                (price.load()?.agg_.price_, clock.slot),
+            )
+        );
+    if held {
+        let mut price_data = price.load_mut()?;
+        price_data.agg_ = aggregate;
+        price_data.timestamp_ = timestamp;
+        price_data.valid_slot_ = valid_slot;
+        price_data.prev_slot_ = prev_slot;
+        price_data.prev_price_ = prev_price;
+        price_data.prev_conf_ = prev_conf;
+        price_data.prev_timestamp_ = prev_timestamp;
+        price_data.last_slot_ = last_slot;
+        updated = false;
+    }
+
+    let reason = if updated || held {
+        StatusReasonCode::None
+    } else if vetoed {
+        StatusReasonCode::CircuitBreaker
+    } else if policy_status.is_some() {
+        StatusReasonCode::StatusPolicy
+    } else {
+        unknown_status_reason(&price.load()?, clock.slot)
+    };
+
+    // Feeds holding the extension record why the aggregation left them without a price
+    if let Ok(Some(status_reason)) =
+        get_extension_mut::<StatusReason>(&mut price_account.try_borrow_mut_data()?)
+    {
+        status_reason.record(clock.slot, reason);
+    }
+
+    // Risk systems are pushed the status transitions and the vetoes instead of polling the feed
+    let transition = {
+        let price_data = price.load()?;
+        StatusTransition {
+            price_account: *price_account.key,
+            slot:          clock.slot,
+            prev_status:   aggregate.status_,
+            status:        price_data.agg_.status_,
+            reason:        reason as u32,
+            exponent:      price_data.exponent,
+            price:         price_data.agg_.price_,
+            conf:          price_data.agg_.conf_,
+        }
+    };
+    if vetoed || transition.status != transition.prev_status {
+        notify_status_transition(price_account, programs, &transition)?;
+    }
+
+    // Feeds opt into scoring their quotes by holding the extension, an aggregation without a price
+    // scores nothing. An invalid extension region never fails the update.
+    if let Ok(Some((price_data, rewards))) =
+        get_price_and_extension_mut::<PublisherRewards>(&mut price_account.try_borrow_mut_data()?)
+    {
+        if rewards.enabled != 0 {
+            let components = &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?];
+            let scores: Vec<u64> = components
+                .iter()
+                .map(|component| {
+                    if (updated || held) && is_valid_quote(price_data, &component.agg_, clock.slot)
+                    {
+                        reward_score(&price_data.agg_, &component.agg_)
+                    } else {
+                        0
+                    }
+                })
+                .collect();
+            rewards.record_aggregation(components, &scores, clock.slot);
+        }
+    }
+
+    // If the aggregate was successfully updated, calculate the difference and update TWAP.
+    if updated {
+        if !traded_in_slot {
+            let agg_diff = (clock.slot as i64) - price.load()?.prev_slot_ as i64;
+            // Encapsulate TWAP update logic in a function to minimize unsafe block scope.
+            unsafe {
+                c_upd_twap(price_account.try_borrow_mut_data()?.as_mut_ptr(), agg_diff);
+            }
+        }
+        let mut price_data = price.load_mut()?;
+        let aggregate_price = price_data.agg_.price_;
+        // We want to send a message every time the aggregate price updates. However, during the migration,
+        // not every publisher will necessarily provide the accumulator accounts. The message_sent_ flag
+        // ensures that after every aggregate update, the next publisher who provides the accumulator accounts
+        // will send the message.
+        price_data.message_sent_ = 0;
+        if !traded_in_slot {
+            price_data.update_price_cumulative();
+        }
+        drop(price_data);
+
+        // Feeds opt into counting the slots they aggregate in by holding the extension. An
+        // invalid extension region never fails the update.
+        if let Ok(Some(counts)) =
+            get_extension_mut::<AggregationCounts>(&mut price_account.try_borrow_mut_data()?)
         {
-            let publisher_price = &mut price_data.comp_[publisher_index].latest_;
-            publisher_price.price_ = cmd_args.price;
-            publisher_price.conf_ = cmd_args.confidence;
-            publisher_price.status_ = status;
-            publisher_price.pub_slot_ = cmd_args.publishing_slot;
+            counts.record_aggregation(clock.slot);
+        }
+        if let Ok(Some(extrema)) =
+            get_extension_mut::<PriceExtrema>(&mut price_account.try_borrow_mut_data()?)
+        {
+            extrema.record_aggregate(clock.slot, aggregate_price);
+        }
+        // The EMA the C code just updated stays in `numer_` and `denom_`, only the published values
+        // are replaced
+        let published_twap = match get_price_and_extension_mut::<SlotWeightedTwap>(
+            &mut price_account.try_borrow_mut_data()?,
+        ) {
+            Ok(Some((price_data, twap))) => {
+                twap.record_aggregate(clock.slot, price_data.agg_.price_, price_data.agg_.conf_);
+                twap.published()
+            }
+            _ => None,
+        };
+        if let Some((twap_price, twap_conf)) = published_twap {
+            let mut price_data = price.load_mut()?;
+            price_data.twap_.val_ = twap_price;
+            price_data.twac_.val_ = twap_conf as i64;
         }
     }
 
+    Ok(updated)
+}
+
+/// Run the aggregation of the current quotes of the price account in the slot of `clock` on a
+/// copy of its fixed layout, leaving the account unchanged. The quotes are capped, ordered and
+/// combined with the statuses of the publishers like in `aggregate_quotes`, but the sanity
+/// program isn't called and the quotes aren't sampled.
+pub fn simulate_aggregation(
+    price_account: &AccountInfo,
+    clock: &Clock,
+    weighted_aggregation: bool,
+    aggregation_config: Option<&AggregationConfigAccount>,
+) -> Result<SimulatedAggregate, ProgramError> {
+    let ignored_components = if weighted_aggregation {
+        find_capped_components(&price_account.try_borrow_data()?, clock.slot)?
+    } else {
+        vec![]
+    };
+
+    let data = price_account.try_borrow_data()?;
+    let quote_order = match get_extension::<QuoteOrder>(&data) {
+        Ok(Some(order)) if order.enabled != 0 => Some((order.lists, order.len)),
+        _ => None,
+    };
+    let status_policy = match get_extension::<StatusPolicy>(&data) {
+        Ok(Some(policy)) => Some(*policy),
+        _ => None,
+    };
+    let conf_floor = match get_extension::<ConfFloor>(&data) {
+        Ok(Some(conf_floor)) => Some(*conf_floor),
+        _ => None,
+    };
+
+    // The copy is made of words so that the C code reads it aligned
+    let mut copy = vec![0u64; (size_of::<PriceAccount>() + 7) / 8];
+    let copy_data: &mut [u8] = bytemuck::cast_slice_mut(&mut copy);
+    copy_data[..size_of::<PriceAccount>()].copy_from_slice(
+        data.get(..size_of::<PriceAccount>())
+            .ok_or(OracleError::AccountTooSmall)?,
+    );
+    if let Some(config) = aggregation_config {
+        config.apply_defaults(load_mut::<PriceAccount>(copy_data)?);
+    }
+    let active_publishers = match get_extension::<PublisherActivity>(&data) {
+        Ok(Some(activity)) if activity.max_missed != 0 => {
+            let (components, valid) = current_quotes(load::<PriceAccount>(copy_data)?, clock.slot)?;
+            Some(activity.active_publishers(components, &valid, clock.slot))
+        }
+        _ => None,
+    };
+    drop(data);
+
+    let aggregate = {
+        let price_data = load_mut::<PriceAccount>(copy_data)?;
+        for index in &ignored_components {
+            price_data.comp_[*index].latest_.status_ = PC_STATUS_IGNORED;
+        }
+        if let Some(active_publishers) = active_publishers {
+            price_data.min_pub_ = demoted_quorum(price_data.min_pub_, active_publishers);
+        }
+        price_data.agg_
+    };
+    let mut updated = unsafe {
+        match &quote_order {
+            Some((lists, len)) => c_upd_aggregate_sorted(
+                copy_data.as_mut_ptr(),
+                clock.slot,
+                clock.unix_timestamp,
+                lists.as_ptr() as *const u8,
+                *len,
+            ),
+            None => c_upd_aggregate(copy_data.as_mut_ptr(), clock.slot, clock.unix_timestamp),
+        }
+    };
+
+    let price_data = load_mut::<PriceAccount>(copy_data)?;
+    let policy_status = status_policy.and_then(|policy| policy.status(price_data, clock.slot));
+    if let Some(status) = policy_status {
+        if updated {
+            price_data.agg_.price_ = aggregate.price_;
+            price_data.agg_.conf_ = aggregate.conf_;
+            updated = false;
+        }
+        price_data.agg_.status_ = status;
+    }
+    if let (true, Some(conf_floor)) = (updated, conf_floor) {
+        price_data.agg_.conf_ = conf_floor.apply(price_data.agg_.price_, price_data.agg_.conf_);
+    }
+
+    let reason = if updated {
+        StatusReasonCode::None
+    } else if policy_status.is_some() {
+        StatusReasonCode::StatusPolicy
+    } else {
+        unknown_status_reason(price_data, clock.slot)
+    };
+    Ok(SimulatedAggregate {
+        slot:    clock.slot,
+        price:   price_data.agg_.price_,
+        conf:    price_data.agg_.conf_,
+        status:  price_data.agg_.status_,
+        num_qt:  price_data.num_qt_,
+        reason:  reason as u32,
+        updated: u8::from(updated),
+        unused_: [0; 3],
+    })
+}
+
+/// Record the aggregation window in `slot` in the `PublisherActivity` of the feed, logging the
+/// publishers it demoted or reactivated. Returns the number of active publishers, `None` if the
+/// feed doesn't demote its publishers. An invalid extension region doesn't demote any publisher.
+fn record_publisher_activity(
+    price_account: &AccountInfo,
+    slot: u64,
+) -> Result<Option<usize>, ProgramError> {
+    let mut data = price_account.try_borrow_mut_data()?;
+    let (price_data, activity) = match get_price_and_extension_mut::<PublisherActivity>(&mut data) {
+        Ok(Some(entry)) => entry,
+        _ => return Ok(None),
+    };
+    let (components, valid) = current_quotes(price_data, slot)?;
+
+    for index in activity.record_window(components, &valid, slot) {
+        let entry = &activity.components[index];
+        let change = PublisherActivityChange {
+            price_account: *price_account.key,
+            publisher: entry.publisher,
+            slot,
+            missed: entry.missed,
+            active: u8::from(entry.inactive == 0),
+            unused_: [0; 3],
+        };
+        sol_log_data(&[PUBLISHER_ACTIVITY_LOG_TAG, bytemuck::bytes_of(&change)]);
+    }
+
+    if activity.max_missed == 0 {
+        return Ok(None);
+    }
+    Ok(Some(activity.active_publishers(components, &valid, slot)))
+}
+
+/// The components of the feed, and whether an aggregation in `slot` would count their latest
+/// quote.
+fn current_quotes(
+    price_data: &PriceAccount,
+    slot: u64,
+) -> Result<(&[PriceComponent], Vec<bool>), OracleError> {
+    let components = &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?];
+    let valid = components
+        .iter()
+        .map(|component| is_valid_quote(price_data, &component.latest_, slot))
+        .collect();
+    Ok((components, valid))
+}
+
+/// Quorum of an aggregation of a feed requiring `min_pub` quotes with `active_publishers`
+/// publishers left after the demotions. A quorum is never lowered below one quote.
+fn demoted_quorum(min_pub: u8, active_publishers: usize) -> u8 {
+    min_pub.min(u8::try_from(active_publishers.max(1)).unwrap_or(u8::MAX))
+}
+
+/// Indexes of the components of the feed stored in `data` whose quotes don't count in an
+/// aggregation in `slot`, because more recent quotes of the same entity reach the
+/// `PublisherWeightCap` of the feed. Ties are broken by component order. An invalid extension
+/// region doesn't cap any quote.
+pub fn find_capped_components(data: &[u8], slot: u64) -> Result<Vec<usize>, ProgramError> {
+    let weight_cap = match get_extension::<PublisherWeightCap>(data) {
+        Ok(Some(weight_cap)) if weight_cap.max_quotes_per_entity != 0 => weight_cap,
+        _ => return Ok(vec![]),
+    };
+    let price_data = load_account_data::<PriceAccount>(data)?;
+    let components = &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?];
+
+    let mut valid_components: Vec<usize> = (0..components.len())
+        .filter(|index| is_valid_quote(price_data, &components[*index].latest_, slot))
+        .collect();
+    valid_components.sort_by_key(|index| (Reverse(components[*index].latest_.pub_slot_), *index));
+
+    let mut entity_counts: Vec<(Pubkey, u64)> = vec![];
+    let mut capped_components = vec![];
+    for index in valid_components {
+        let entity = weight_cap.entity_of(index, &components[index].pub_);
+        match entity_counts.iter_mut().find(|(key, _)| *key == entity) {
+            Some((_, count)) if *count >= weight_cap.max_quotes_per_entity => {
+                capped_components.push(index)
+            }
+            Some((_, count)) => *count += 1,
+            None => entity_counts.push((entity, 1)),
+        }
+    }
+    Ok(capped_components)
+}
+
+/// Indexes of the valid quotes that an aggregation in `slot` leaves out of the sample of the
+/// `PublisherSampling` of the feed drawn with `seed`, apart from the `ignored` ones. An invalid
+/// extension region samples every quote.
+fn find_unsampled_components(
+    price_account: &AccountInfo,
+    slot: u64,
+    ignored: &[usize],
+    seed: &Hash,
+) -> Result<Vec<usize>, ProgramError> {
+    let data = price_account.try_borrow_data()?;
+    let sampling = match get_extension::<PublisherSampling>(&data) {
+        Ok(Some(sampling)) if sampling.sample_size != 0 => sampling,
+        _ => return Ok(vec![]),
+    };
+    let price_data = load_account_data::<PriceAccount>(&data)?;
+    let components = &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?];
+
+    let candidates: Vec<usize> = (0..components.len())
+        .filter(|index| {
+            !ignored.contains(index)
+                && is_valid_quote(price_data, &components[*index].latest_, slot)
+        })
+        .collect();
+    let sample = sampling.sample(seed, &candidates);
+    Ok(candidates
+        .into_iter()
+        .filter(|index| !sample.contains(index))
+        .collect())
+}
+
+/// Hash of the most recent slot in the slot hashes sysvar. Its data is too large to deserialize,
+/// only the first entry is read.
+fn most_recent_slot_hash(slot_hashes: &AccountInfo) -> Result<Hash, ProgramError> {
+    let data = slot_hashes.try_borrow_data()?;
+    let num_entries = data
+        .get(..8)
+        .map_or(0, |len| u64::from_le_bytes(len.try_into().unwrap()));
+    // Entries are a slot followed by its hash
+    match data.get(16..48) {
+        Some(hash) if num_entries != 0 => Ok(Hash::new(hash)),
+        _ => Err(OracleError::InvalidSysvarAccount.into()),
+    }
+}
+
+/// Why an aggregation in `slot` didn't produce an aggregate, from the quotes it copied to the
+/// `agg_` of the components.
+fn unknown_status_reason(price_data: &PriceAccount, slot: u64) -> StatusReasonCode {
+    let quorum = u32::from(price_data.min_pub_).max(1);
+    if price_data.num_qt_ >= quorum {
+        // The quotes were valid but their aggregate has no confidence interval
+        return StatusReasonCode::ZeroConfForbidden;
+    }
+
+    let recent_quotes = price_data
+        .comp_
+        .iter()
+        .take(price_data.num_ as usize)
+        .map(|component| &component.agg_)
+        .filter(|quote| slot.saturating_sub(quote.pub_slot_) <= price_data.max_latency())
+        .collect::<Vec<_>>();
+    if recent_quotes.is_empty() {
+        return StatusReasonCode::AllComponentsStale;
+    }
+    let zero_conf_quotes = if price_data.flags.contains(PriceAccountFlags::ALLOW_ZERO_CI) {
+        0
+    } else {
+        recent_quotes
+            .iter()
+            .filter(|quote| quote.status_ == PC_STATUS_TRADING && quote.conf_ == 0)
+            .count()
+    };
+    if price_data.num_qt_ as usize + zero_conf_quotes >= quorum as usize {
+        StatusReasonCode::ZeroConfForbidden
+    } else {
+        StatusReasonCode::InsufficientQuorum
+    }
+}
+
+/// Whether an aggregation in `slot` counts `quote`, with the same checks as `upd_aggregate` in
+/// the C code.
+fn is_valid_quote(price_data: &PriceAccount, quote: &PriceInfo, slot: u64) -> bool {
+    let conf = match i64::try_from(quote.conf_) {
+        Ok(conf) => conf,
+        Err(_) => return false,
+    };
+    quote.status_ == PC_STATUS_TRADING
+        && (conf > 0 || price_data.flags.contains(PriceAccountFlags::ALLOW_ZERO_CI))
+        && i64::MIN + conf <= quote.price_
+        && quote.price_ <= i64::MAX - conf
+        && slot.saturating_sub(quote.pub_slot_) <= price_data.max_latency()
+}
+
+/// Ask the sanity program of the feed to approve the aggregate that was just computed. Feeds
+/// without one approve every aggregate, an invalid extension region falls back to that.
+fn approve_aggregate(
+    price: &OracleAccount<PriceAccount>,
+    clock: &Clock,
+    programs: &[AccountInfo],
+) -> Result<bool, ProgramError> {
+    let price_account = price.info();
+    let sanity_program_id = match get_extension::<SanityProgram>(&price_account.try_borrow_data()?)
+    {
+        Ok(Some(sanity)) if sanity.program_id != Pubkey::default() => sanity.program_id,
+        _ => return Ok(true),
+    };
+    let sanity_program = match programs
+        .iter()
+        .find(|program| *program.key == sanity_program_id)
+    {
+        Some(account) => account,
+        // The aggregate can't be checked
+        None => return Ok(false),
+    };
+
+    let args = {
+        let price_data = price.load()?;
+        SanityCheckArgs {
+            price_account: *price_account.key,
+            slot:          clock.slot,
+            price:         price_data.agg_.price_,
+            conf:          price_data.agg_.conf_,
+            exponent:      price_data.exponent,
+            num_quoters:   price_data.num_qt_,
+            prev_price:    price_data.prev_price_,
+            prev_conf:     price_data.prev_conf_,
+            prev_slot:     price_data.prev_slot_,
+        }
+    };
+    invoke(
+        &Instruction::new_with_bytes(
+            sanity_program_id,
+            bytemuck::bytes_of(&args),
+            vec![AccountMeta::new_readonly(*price_account.key, false)],
+        ),
+        &[price_account.clone(), sanity_program.clone()],
+    )?;
+
+    Ok(match get_return_data() {
+        Some((program_id, data)) => {
+            program_id == sanity_program_id && data == SANITY_CHECK_APPROVED
+        }
+        None => false,
+    })
+}
+
+/// Log `transition` and pass it to the `StatusObserver` of the feed, if it's among `programs`.
+fn notify_status_transition(
+    price_account: &AccountInfo,
+    programs: &[AccountInfo],
+    transition: &StatusTransition,
+) -> ProgramResult {
+    sol_log_data(&[STATUS_TRANSITION_LOG_TAG, bytemuck::bytes_of(transition)]);
+
+    let observer_id = match get_extension::<StatusObserver>(&price_account.try_borrow_data()?) {
+        Ok(Some(observer)) if observer.program_id != Pubkey::default() => observer.program_id,
+        _ => return Ok(()),
+    };
+    if let Some(observer) = programs.iter().find(|program| *program.key == observer_id) {
+        invoke(
+            &Instruction::new_with_bytes(
+                observer_id,
+                bytemuck::bytes_of(transition),
+                vec![AccountMeta::new_readonly(*price_account.key, false)],
+            ),
+            &[price_account.clone(), observer.clone()],
+        )?;
+    }
     Ok(())
 }
 
@@ -358,15 +1457,6 @@ pub fn find_publisher_index(comps: &[PriceComponent], key: &Pubkey) -> Option<us
     }
 }
 
-#[allow(dead_code)]
-// Wrapper struct for the accounts required to add data to the accumulator program.
-struct MessageBufferAccounts<'a, 'b: 'a> {
-    program_id:          &'a AccountInfo<'b>,
-    whitelist:           &'a AccountInfo<'b>,
-    oracle_auth_pda:     &'a AccountInfo<'b>,
-    message_buffer_data: &'a AccountInfo<'b>,
-}
-
 #[cfg(test)]
 mod test {
     use {
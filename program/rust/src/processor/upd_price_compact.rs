@@ -0,0 +1,74 @@
+use {
+    super::{
+        find_publisher_index,
+        upd_price_with_args,
+    },
+    crate::{
+        accounts::PriceAccount,
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::{
+            UpdPriceArgs,
+            UpdPriceCompactArgs,
+        },
+        utils::{
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Publish component price with `UpdPriceCompactArgs`, decoded relative to the latest quote of
+/// the publisher and then processed like `UpdPrice`, with the same accounts.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] sysvar_clock account  []
+pub fn upd_price_compact(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<UpdPriceCompactArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdPriceCompactArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account) = match accounts {
+        [x, y, ..] => Ok((x, y)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (price_account, AccountCheck::Writable),
+        ],
+    )?;
+
+    let cmd_args: UpdPriceArgs = {
+        let price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+        let publisher_index = find_publisher_index(
+            &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+            funding_account.key,
+        )
+        .ok_or(OracleError::PublisherNotFound)?;
+        cmd.decode(&price_data.comp_[publisher_index].latest_)?
+    };
+
+    upd_price_with_args(program_id, accounts, &cmd_args)
+}
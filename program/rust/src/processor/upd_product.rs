@@ -1,14 +1,26 @@
 use {
+    super::{
+        put_all_messages,
+        MessageBufferAccounts,
+    },
     crate::{
         accounts::{
+            get_product_metadata_entries,
+            patch_product_metadata,
             update_product_metadata,
+            validate_product_metadata,
             ProductAccount,
+            ProductMetadataMessage,
+            PythOracleSerialize,
         },
         deserialize::{
             load,
             load_checked,
         },
-        instruction::CommandHeader,
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+        },
         utils::{
             check_permissioned_funding_account,
             check_valid_funding_account,
@@ -17,25 +29,51 @@ use {
     },
     solana_program::{
         account_info::AccountInfo,
+        clock::Clock,
         entrypoint::ProgramResult,
         pubkey::Pubkey,
+        sysvar::Sysvar,
     },
 };
 
 /// Update the metadata associated with a product, overwriting any existing metadata.
 /// The metadata is provided as a list of key-value pairs at the end of the `instruction_data`.
+/// It must match the schema checked by `validate_product_metadata`.
+///
+/// `PatchProduct` patches the existing metadata with the key-value pairs instead, see
+/// `patch_product_metadata`, so that updating a field can't clobber the others with stale
+/// values. The patched metadata must match the same schema.
+///
+/// When the message buffer accounts are passed, the new metadata is also sent to the message
+/// buffer as a `ProductMetadataMessage`, like the price messages of `UpdPrice`.
 // account[0] funding account       [signer writable]
 // account[1] product account       [signer writable]
 // account[2] permissions account   []
+// account[3] buffer program        [] (optional, the message buffer program)
+// account[4] whitelist             [] (optional)
+// account[5] oracle auth PDA       [] (optional, [UPD_PRICE_WRITE_SEED, buffer program])
+// account[6] message buffer data   [writable] (optional)
 pub fn upd_product(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let (funding_account, product_account, permissions_account) = match accounts {
-        [x, y, p] => Ok((x, y, p)),
-        _ => Err(OracleError::InvalidNumberOfAccounts),
-    }?;
+    let (funding_account, product_account, permissions_account, maybe_accumulator_accounts) =
+        match accounts {
+            [x, y, p] => Ok((x, y, p, None)),
+            [x, y, p, a, b, c, d] => Ok((
+                x,
+                y,
+                p,
+                Some(MessageBufferAccounts {
+                    program_id:          a,
+                    whitelist:           b,
+                    oracle_auth_pda:     c,
+                    message_buffer_data: d,
+                }),
+            )),
+            _ => Err(OracleError::InvalidNumberOfAccounts),
+        }?;
 
     let hdr = load::<CommandHeader>(instruction_data)?;
 
@@ -54,7 +92,35 @@ pub fn upd_product(
         let mut _product_data = load_checked::<ProductAccount>(product_account, hdr.version)?;
     }
 
-    update_product_metadata(instruction_data, product_account, hdr.version)?;
+    if hdr.command == OracleCommand::PatchProduct as i32 {
+        let patched_data =
+            patch_product_metadata(&product_account.try_borrow_data()?, instruction_data)?;
+        validate_product_metadata(&patched_data)?;
+        update_product_metadata(&patched_data, product_account, hdr.version)?;
+    } else {
+        validate_product_metadata(instruction_data)?;
+        update_product_metadata(instruction_data, product_account, hdr.version)?;
+    }
+
+    if let Some(accumulator_accounts) = maybe_accumulator_accounts {
+        let clock = Clock::get()?;
+        let message = ProductMetadataMessage {
+            product_id:   product_account.key.to_bytes(),
+            publish_time: clock.unix_timestamp,
+            publish_slot: clock.slot,
+            metadata:     get_product_metadata_entries(&product_account.try_borrow_data()?)?
+                .into_iter()
+                .map(|(key, value)| (key.to_vec(), value.to_vec()))
+                .collect(),
+        };
+        put_all_messages(
+            program_id,
+            accounts,
+            &accumulator_accounts,
+            product_account.key,
+            vec![message.to_bytes()],
+        )?;
+    }
 
     Ok(())
 }
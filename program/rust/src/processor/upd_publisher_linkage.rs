@@ -0,0 +1,153 @@
+use {
+    crate::{
+        accounts::{
+            PublisherLink,
+            PublisherLinkageAccount,
+            PythAccount,
+            PUBLISHER_LINKAGE_SEED,
+        },
+        deserialize::{
+            load,
+            OracleAccount,
+        },
+        instruction::UpdPublisherLinkageArgs,
+        utils::{
+            check_permissioned_funding_account,
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        program_memory::sol_memset,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Link a publisher to the entity controlling it, replacing its previous entity, or unlink it if
+/// the entity is the default key. The linkage account is created the first time and grown as
+/// publishers get linked, paid by the funding account. Feeds only see the change the next time
+/// their `PublisherWeightCap` is set.
+// account[0] funding account       [signer writable]
+// account[1] publisher linkage     [writable]
+// account[2] permissions account   []
+// account[3] system program        []
+pub fn upd_publisher_linkage(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<UpdPublisherLinkageArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdPublisherLinkageArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, linkage_account, permissions_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (linkage_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[PUBLISHER_LINKAGE_SEED.as_bytes()], program_id);
+    pyth_assert(
+        linkage_pda_address == *linkage_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    PublisherLinkageAccount::initialize_pda(
+        linkage_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[PUBLISHER_LINKAGE_SEED.as_bytes(), &[bump_seed]],
+        cmd_args.header.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        linkage_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    // Validate that linkage_account contains the appropriate account header
+    let linkage =
+        OracleAccount::<PublisherLinkageAccount>::new(linkage_account, cmd_args.header.version)?;
+    let (num_links, position) = {
+        let data = linkage_account.try_borrow_data()?;
+        let links = PublisherLinkageAccount::links(&data)?;
+        (
+            links.len(),
+            links
+                .iter()
+                .position(|link| link.publisher == cmd_args.publisher),
+        )
+    };
+
+    let link = PublisherLink {
+        publisher: cmd_args.publisher,
+        entity:    cmd_args.entity,
+    };
+    let new_num_links = match (cmd_args.entity != Pubkey::default(), position) {
+        (false, None) => return Err(OracleError::PublisherNotFound.into()),
+        (true, Some(position)) => {
+            let start = PublisherLinkageAccount::entry_offset(position);
+            let end = PublisherLinkageAccount::entry_offset(position + 1);
+            linkage_account.try_borrow_mut_data()?[start..end].copy_from_slice(bytes_of(&link));
+            num_links
+        }
+        (true, None) => {
+            let start = PublisherLinkageAccount::entry_offset(num_links);
+            let end = PublisherLinkageAccount::entry_offset(num_links + 1);
+            if linkage_account.data_len() < end {
+                let minimum_balance = get_rent()?.minimum_balance(end);
+                if linkage_account.lamports() < minimum_balance {
+                    send_lamports(
+                        funding_account,
+                        linkage_account,
+                        system_program,
+                        minimum_balance - linkage_account.lamports(),
+                    )?;
+                }
+                linkage_account.realloc(end, true)?;
+            }
+            linkage_account.try_borrow_mut_data()?[start..end].copy_from_slice(bytes_of(&link));
+            num_links + 1
+        }
+        (false, Some(position)) => {
+            // The last link takes the place of the removed one
+            let last = PublisherLinkageAccount::entry_offset(num_links - 1);
+            let mut data = linkage_account.try_borrow_mut_data()?;
+            data.copy_within(
+                last..last + size_of::<PublisherLink>(),
+                PublisherLinkageAccount::entry_offset(position),
+            );
+            sol_memset(&mut data[last..], 0, size_of::<PublisherLink>());
+            num_links - 1
+        }
+    };
+
+    let mut linkage_data = linkage.load_mut()?;
+    linkage_data.num_links = try_convert(new_num_links)?;
+    linkage_data.header.size = try_convert(PublisherLinkageAccount::entry_offset(new_num_links))?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            PermissionAccount,
+            PublisherRegistryAccount,
+            PythAccount,
+            PUBLISHER_REGISTRY_SEED,
+        },
+        deserialize::{
+            load,
+            load_account_as_mut,
+            OracleAccount,
+        },
+        instruction::UpdPublisherRegistryArgs,
+        utils::{
+            check_permissioned_funding_account,
+            get_rent,
+            pyth_assert,
+            send_lamports,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        program_memory::sol_memset,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Approve a publisher in the publisher registry or revoke it. The registry is created the first
+/// time and grown as publishers get approved, paid by the funding account. The permissions account
+/// records that the registry exists, it's only written the first time. Revoking a publisher
+/// doesn't remove it from the price accounts it was already added to.
+// account[0] funding account       [signer writable]
+// account[1] publisher registry    [writable]
+// account[2] permissions account   [writable]
+// account[3] system program        []
+pub fn upd_publisher_registry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd_args = load::<UpdPublisherRegistryArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdPublisherRegistryArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, registry_account, permissions_account, system_program) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (system_program, AccountCheck::SystemProgram),
+        ],
+    )?;
+    let (registry_pda_address, bump_seed) =
+        Pubkey::find_program_address(&[PUBLISHER_REGISTRY_SEED.as_bytes()], program_id);
+    pyth_assert(
+        registry_pda_address == *registry_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+
+    PublisherRegistryAccount::initialize_pda(
+        registry_account,
+        funding_account,
+        system_program,
+        program_id,
+        &[PUBLISHER_REGISTRY_SEED.as_bytes(), &[bump_seed]],
+        cmd_args.header.version,
+    )?;
+    check_permissioned_funding_account(
+        program_id,
+        registry_account,
+        funding_account,
+        permissions_account,
+        &cmd_args.header,
+    )?;
+
+    // Validate that registry_account contains the appropriate account header
+    let registry =
+        OracleAccount::<PublisherRegistryAccount>::new(registry_account, cmd_args.header.version)?;
+    let (num_publishers, position) = {
+        let data = registry_account.try_borrow_data()?;
+        let publishers = PublisherRegistryAccount::publishers(&data)?;
+        (
+            publishers.len(),
+            publishers
+                .iter()
+                .position(|publisher| *publisher == cmd_args.publisher),
+        )
+    };
+
+    let new_num_publishers = match (cmd_args.approved != 0, position) {
+        (true, Some(_)) => return Err(OracleError::PublisherAlreadyExists.into()),
+        (false, None) => return Err(OracleError::PublisherNotFound.into()),
+        (true, None) => {
+            let start = PublisherRegistryAccount::entry_offset(num_publishers);
+            let end = PublisherRegistryAccount::entry_offset(num_publishers + 1);
+            if registry_account.data_len() < end {
+                let minimum_balance = get_rent()?.minimum_balance(end);
+                if registry_account.lamports() < minimum_balance {
+                    send_lamports(
+                        funding_account,
+                        registry_account,
+                        system_program,
+                        minimum_balance - registry_account.lamports(),
+                    )?;
+                }
+                registry_account.realloc(end, true)?;
+            }
+            registry_account.try_borrow_mut_data()?[start..end]
+                .copy_from_slice(cmd_args.publisher.as_ref());
+            num_publishers + 1
+        }
+        (false, Some(position)) => {
+            // The last publisher takes the place of the revoked one
+            let last = PublisherRegistryAccount::entry_offset(num_publishers - 1);
+            let mut data = registry_account.try_borrow_mut_data()?;
+            data.copy_within(
+                last..last + size_of::<Pubkey>(),
+                PublisherRegistryAccount::entry_offset(position),
+            );
+            sol_memset(&mut data[last..], 0, size_of::<Pubkey>());
+            num_publishers - 1
+        }
+    };
+
+    {
+        let mut registry_data = registry.load_mut()?;
+        registry_data.num_publishers = try_convert(new_num_publishers)?;
+        registry_data.header.size =
+            try_convert(PublisherRegistryAccount::entry_offset(new_num_publishers))?;
+    }
+
+    // The instructions adding publishers always get the permissions account, it tells them
+    // that they need the registry
+    if !PermissionAccount::has_publisher_registry(permissions_account)? {
+        validate_accounts(program_id, &[(permissions_account, AccountCheck::Writable)])?;
+        if permissions_account.data_len() < PermissionAccount::PUBLISHER_REGISTRY_ACCOUNT_SPACE {
+            let new_size = PermissionAccount::PUBLISHER_REGISTRY_ACCOUNT_SPACE;
+            let minimum_balance = get_rent()?.minimum_balance(new_size);
+            if permissions_account.lamports() < minimum_balance {
+                send_lamports(
+                    funding_account,
+                    permissions_account,
+                    system_program,
+                    minimum_balance - permissions_account.lamports(),
+                )?;
+            }
+            permissions_account.realloc(new_size, true)?;
+            let mut header = load_account_as_mut::<AccountHeader>(permissions_account)?;
+            header.size = try_convert(new_size)?;
+        }
+        *PermissionAccount::load_publisher_registry_marker_mut(permissions_account)? = 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use {
+    super::find_publisher_index,
+    crate::{
+        accounts::PriceAccount,
+        c_oracle_header::{
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::UpdPublisherStatusArgs,
+        utils::{
+            pyth_assert,
+            try_convert,
+            validate_accounts,
+            AccountCheck,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        clock::Clock,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvar::{
+            clock,
+            Sysvar,
+        },
+    },
+    std::mem::size_of,
+};
+
+/// Mark the quote of a publisher as halted or in auction, for when its market doesn't trade
+/// continuously. Only the status and the publishing slot of the quote change: the aggregation
+/// leaves it out like any quote that isn't trading, while `PrunePublishers` sees the publisher
+/// as active. The publishing slot follows the same rules as in `UpdPrice`. The aggregate isn't
+/// updated, the next `UpdPrice` aggregates without the quote.
+// account[0] funding account       [signer writable]
+// account[1] price account         [writable]
+// account[2] sysvar_clock account  []
+pub fn upd_publisher_status(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<UpdPublisherStatusArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<UpdPublisherStatusArgs>()
+            && (cmd.status == PC_STATUS_HALTED || cmd.status == PC_STATUS_AUCTION),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, price_account, clock_account) = match accounts {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    validate_accounts(
+        program_id,
+        &[
+            (funding_account, AccountCheck::Funding),
+            (price_account, AccountCheck::Writable),
+            (clock_account, AccountCheck::Sysvar(clock::id())),
+        ],
+    )?;
+    let clock = Clock::from_account_info(clock_account)?;
+
+
+    let mut price_data = load_checked::<PriceAccount>(price_account, cmd.header.version)?;
+    let publisher_index = find_publisher_index(
+        &price_data.comp_[..try_convert::<u32, usize>(price_data.num_)?],
+        funding_account.key,
+    )
+    .ok_or(OracleError::PublisherNotFound)?;
+
+    let publisher_price = &mut price_data.comp_[publisher_index].latest_;
+    pyth_assert(
+        cmd.publishing_slot > publisher_price.pub_slot_,
+        OracleError::StaleSubmission.into(),
+    )?;
+    pyth_assert(
+        cmd.publishing_slot <= clock.slot,
+        OracleError::PublishingSlotInFuture.into(),
+    )?;
+
+    publisher_price.status_ = cmd.status;
+    let venue = publisher_price.venue();
+    publisher_price.set_tags(venue, cmd.status);
+    publisher_price.pub_slot_ = cmd.publishing_slot;
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use {
+    crate::{
+        accounts::{
+            FeeVaultAccount,
+            FEE_VAULT_SEED,
+        },
+        deserialize::{
+            load,
+            load_checked,
+        },
+        instruction::WithdrawFeesArgs,
+        utils::{
+            check_permissioned_funding_account,
+            get_rent,
+            pyth_assert,
+        },
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Move collected fees from the fee vault to the recipient. Only the master authority can
+/// withdraw, and the vault keeps enough lamports to stay rent exempt.
+// account[0] funding account       [signer writable]
+// account[1] fee vault             [writable]
+// account[2] permissions account   []
+// account[3] recipient             [writable]
+pub fn withdraw_fees(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let cmd = load::<WithdrawFeesArgs>(instruction_data)?;
+
+    pyth_assert(
+        instruction_data.len() == size_of::<WithdrawFeesArgs>(),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let (funding_account, fee_vault_account, permissions_account, recipient) = match accounts {
+        [w, x, y, z] => Ok((w, x, y, z)),
+        _ => Err(OracleError::InvalidNumberOfAccounts),
+    }?;
+
+    check_permissioned_funding_account(
+        program_id,
+        fee_vault_account,
+        funding_account,
+        permissions_account,
+        &cmd.header,
+    )?;
+    pyth_assert(
+        Pubkey::find_program_address(&[FEE_VAULT_SEED.as_bytes()], program_id).0
+            == *fee_vault_account.key,
+        OracleError::InvalidPda.into(),
+    )?;
+    pyth_assert(
+        recipient.is_writable,
+        OracleError::InvalidWritableAccount.into(),
+    )?;
+
+    let mut fee_vault_data =
+        load_checked::<FeeVaultAccount>(fee_vault_account, cmd.header.version)?;
+    let available = fee_vault_account
+        .lamports()
+        .saturating_sub(get_rent()?.minimum_balance(fee_vault_account.data_len()));
+    pyth_assert(cmd.lamports <= available, ProgramError::InsufficientFunds)?;
+
+    **fee_vault_account.lamports.borrow_mut() -= cmd.lamports;
+    **recipient.lamports.borrow_mut() += cmd.lamports;
+    fee_vault_data.total_withdrawn += cmd.lamports;
+
+    Ok(())
+}
@@ -0,0 +1,968 @@
+//! Conformance vectors of the aggregation, see the `pyth-test-vectors` binary.
+//!
+//! Other implementations of the oracle (the JS and Python parsers, alternative runtimes) check
+//! themselves against these vectors rather than against a copy of the C aggregation. Each vector
+//! lists the components of a feed and the aggregate the program computes from them, as constants
+//! and as the JSON artifact published with every build of the program. The vectors are the
+//! quote sets of `test_data/aggregation`, `test_test_vectors` checks them against the current
+//! implementation and `test_data/vectors/aggregation.json` against `test_vectors_json`.
+
+use {
+    crate::c_oracle_header::{
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+    std::fmt::Write,
+};
+
+/// Slot of the aggregation of every vector. The previous aggregate of the feed was published in
+/// the slot before it.
+pub const VECTOR_SLOT: u64 = 1001;
+
+/// Latest quote of a publisher of the feed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentVector {
+    pub price:    i64,
+    pub conf:     u64,
+    pub status:   u32,
+    pub pub_slot: u64,
+}
+
+/// Components of a feed and the aggregate computed from them. The feed has the default
+/// `max_latency_`, no `min_pub_` and doesn't accept quotes with a confidence of zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AggregationVector {
+    pub name:       &'static str,
+    pub exponent:   i32,
+    pub slot:       u64,
+    pub components: &'static [ComponentVector],
+    pub price:      i64,
+    pub conf:       u64,
+    pub status:     u32,
+    /// Number of components that took part in the aggregate
+    pub num_qt:     u32,
+}
+
+const fn component(price: i64, conf: u64, status: u32, pub_slot: u64) -> ComponentVector {
+    ComponentVector {
+        price,
+        conf,
+        status,
+        pub_slot,
+    }
+}
+
+pub const AGGREGATION_VECTORS: &[AggregationVector] = &[
+    AggregationVector {
+        name:       "aggregation/1",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10000,
+        conf:       1000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/2",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(-10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(-10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(-10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      -10000,
+        conf:       1000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/3",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(0, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(0, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(0, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      0,
+        conf:       1000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/4",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10500, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10500,
+        conf:       500,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/5",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(20000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      15000,
+        conf:       4000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/6",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(20000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      15000,
+        conf:       4000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/7",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(1000000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100010,
+        conf:       899980,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/8",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10020, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10020,
+        conf:       970,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/9",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10020, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11020, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10510,
+        conf:       500,
+        status:     PC_STATUS_TRADING,
+        num_qt:     4,
+    },
+    AggregationVector {
+        name:       "aggregation/10",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(500000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(501000, 20, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(500000000000, 10000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      501000,
+        conf:       499989499000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/11",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(500000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(501000000000, 10000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(500000000000, 20000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      500000000000,
+        conf:       499999499990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/12",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10010000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10010020, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10011000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10011020, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10010510,
+        conf:       500,
+        status:     PC_STATUS_TRADING,
+        num_qt:     4,
+    },
+    AggregationVector {
+        name:       "aggregation/13",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(13000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(13020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(14000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(14020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(16000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(16020, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      13005,
+        conf:       1985,
+        status:     PC_STATUS_TRADING,
+        num_qt:     14,
+    },
+    AggregationVector {
+        name:       "aggregation/14",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10010, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11010, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11500, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      11000,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     5,
+    },
+    AggregationVector {
+        name:       "aggregation/15",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(13000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(14000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(16000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      13000,
+        conf:       2000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/16",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11030, 900, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12030, 1123, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(13200, 940, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(14020, 1070, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15200, 1123, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(16320, 1213, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      13153,
+        conf:       2053,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/17",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(16320, 1213, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(15200, 1123, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(14020, 1070, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(13200, 940, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12030, 1123, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11030, 900, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 1100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      13153,
+        conf:       2053,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/18",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11100, 350, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      11000,
+        conf:       250,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/19",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11100, 350, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(8000, 5000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10900,
+        conf:       900,
+        status:     PC_STATUS_TRADING,
+        num_qt:     4,
+    },
+    AggregationVector {
+        name:       "aggregation/20",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11100, 350, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(8000, 5000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12000, 4000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      11000,
+        conf:       2000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     5,
+    },
+    AggregationVector {
+        name:       "aggregation/21",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10110, 130, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11000, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11100, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(11110, 130, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12000, 100, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12100, 200, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(12110, 130, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      11100,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     9,
+    },
+    AggregationVector {
+        name:       "aggregation/22",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000000, 5000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10010000, 5000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000000, 1000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10001000,
+        conf:       4000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     3,
+    },
+    AggregationVector {
+        name:       "aggregation/23",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(9900, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10100, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      10100,
+        conf:       10,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/24",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(90000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100010,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/25",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(99000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100010,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/26",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100010,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/27",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100500, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100500,
+        conf:       500,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/28",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100990,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/29",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(111000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100990,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/30",
+        exponent:   0,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(100000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(101000, 10, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(1110000, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      100990,
+        conf:       990,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/31",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4329605500000,
+                1486500000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4325720000000,
+                1290000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(43254, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                4329150000000,
+                1500000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329999999999,
+                3999999999,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4331609000000,
+                2422000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329198852938,
+                3044669090,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(0, 200000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                4327900000000,
+                2000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(4332090000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      4328634500000,
+        conf:       2914500000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     10,
+    },
+    AggregationVector {
+        name:       "aggregation/32",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4329605500000,
+                1486500000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4325720000000,
+                1290000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329150000000,
+                1500000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329999999999,
+                3999999999,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4331609000000,
+                2422000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329198852938,
+                3044669090,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(0, 200000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                4327900000000,
+                2000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(4332090000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      4329187000000,
+        conf:       3187000000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     9,
+    },
+    AggregationVector {
+        name:       "aggregation/33",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4329605500000,
+                1486500000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4325720000000,
+                1290000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(43254, 1, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                4329150000000,
+                1500000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329999999999,
+                3999999999,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4331609000000,
+                2422000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4329198852938,
+                3044669090,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4327900000000,
+                2000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(4332090000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      4329187000000,
+        conf:       3187000000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     9,
+    },
+    AggregationVector {
+        name:       "aggregation/34",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4289406500000,
+                1453500000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 4,
+            ),
+            component(4291320000000, 160000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                4290050000000,
+                1700000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 4,
+            ),
+            component(
+                4279480000000,
+                16965586211,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 4,
+            ),
+            component(
+                4289883000000,
+                2754000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 4,
+            ),
+            component(4290054475519, 785800000, PC_STATUS_TRADING, VECTOR_SLOT - 5),
+            component(
+                4290271973194,
+                2576988362,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 4,
+            ),
+            component(4290171000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 8),
+            component(4290050000000, 500000000, PC_STATUS_TRADING, VECTOR_SLOT - 4),
+            component(4290171000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 4),
+            component(4290050000000, 100001165, PC_STATUS_TRADING, VECTOR_SLOT - 4),
+        ],
+        price:      4290170000000,
+        conf:       670275519,
+        status:     PC_STATUS_TRADING,
+        num_qt:     11,
+    },
+    AggregationVector {
+        name:       "aggregation/35",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4000000000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000100000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000200000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000300000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000400000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(40000000000, 10000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(40000000000, 10000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      3999400000000,
+        conf:       3959390000000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/36",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(1000000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(1001000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(1002000000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(50000000000, 49000000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(30000000000, 29000000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(40000000000, 39010000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(
+                101500000000,
+                100510000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+        ],
+        price:      1002000000,
+        conf:       48998000000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/37",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(
+                4000000000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000100000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000200000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000300000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(
+                4000400000000,
+                1000000000,
+                PC_STATUS_TRADING,
+                VECTOR_SLOT - 1,
+            ),
+            component(400000000000000, 10000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(400000000000000, 10000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      4001000000000,
+        conf:       395998999990000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     7,
+    },
+    AggregationVector {
+        name:       "aggregation/38",
+        exponent:   -3,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(10000, 0, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 0, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(10000, 0, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      0,
+        conf:       0,
+        status:     PC_STATUS_UNKNOWN,
+        num_qt:     0,
+    },
+    AggregationVector {
+        name:       "aggregation/39",
+        exponent:   -8,
+        slot:       VECTOR_SLOT,
+        components: &[
+            component(21000000, 100000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+            component(209900000, 1000000, PC_STATUS_TRADING, VECTOR_SLOT - 1),
+        ],
+        price:      115000000,
+        conf:       94900000,
+        status:     PC_STATUS_TRADING,
+        num_qt:     2,
+    },
+];
+
+/// The vectors of `AGGREGATION_VECTORS` as the JSON artifact, one component per line.
+pub fn test_vectors_json() -> String {
+    let mut json = String::new();
+    // Writing to a `String` never fails
+    let _ = writeln!(json, "{{");
+    let _ = writeln!(json, "  \"aggregation\": [");
+    for (i, vector) in AGGREGATION_VECTORS.iter().enumerate() {
+        let _ = writeln!(json, "    {{");
+        let _ = writeln!(json, "      \"name\": \"{}\",", vector.name);
+        let _ = writeln!(json, "      \"exponent\": {},", vector.exponent);
+        let _ = writeln!(json, "      \"slot\": {},", vector.slot);
+        let _ = writeln!(json, "      \"components\": [");
+        for (j, component) in vector.components.iter().enumerate() {
+            let _ = writeln!(
+                json,
+                "        {{ \"price\": {}, \"conf\": {}, \"status\": {}, \"pub_slot\": {} }}{}",
+                component.price,
+                component.conf,
+                component.status,
+                component.pub_slot,
+                separator(j, vector.components.len())
+            );
+        }
+        let _ = writeln!(json, "      ],");
+        let _ = writeln!(json, "      \"price\": {},", vector.price);
+        let _ = writeln!(json, "      \"conf\": {},", vector.conf);
+        let _ = writeln!(json, "      \"status\": {},", vector.status);
+        let _ = writeln!(json, "      \"num_qt\": {}", vector.num_qt);
+        let _ = writeln!(json, "    }}{}", separator(i, AGGREGATION_VECTORS.len()));
+    }
+    let _ = writeln!(json, "  ]");
+    let _ = writeln!(json, "}}");
+    json
+}
+
+fn separator(index: usize, len: usize) -> &'static str {
+    if index + 1 < len {
+        ","
+    } else {
+        ""
+    }
+}
@@ -1,34 +1,119 @@
 mod pyth_simulator;
+mod test_access_control;
+mod test_account_fixtures;
+mod test_accumulator_emitter;
 mod test_add_price;
 mod test_add_product;
 mod test_add_publisher;
+mod test_aggregate_cache;
 mod test_aggregate_v2;
 mod test_aggregation;
+mod test_aggregation_config;
+mod test_aggregation_cost;
+mod test_aggregation_counts;
+mod test_aggregation_delay;
+mod test_aggregation_in_progress;
+mod test_aggregation_reference;
 mod test_aggregation_zero_conf;
+mod test_assert_price_condition;
+mod test_audit_log;
+mod test_aux_data;
 mod test_c_code;
+mod test_chaos;
 mod test_check_valid_signable_account_or_permissioned_funding_account;
+mod test_checksum;
+mod test_client;
+mod test_clock_regression;
+mod test_clone_price;
+mod test_community_feed;
+mod test_compute_budgets;
+mod test_conf_floor;
+mod test_config_diff;
+mod test_consistency_check;
 mod test_del_price;
 mod test_del_product;
 mod test_del_publisher;
+mod test_deprecate_price;
 mod test_ema;
+mod test_extensions;
+mod test_feature_gate;
+mod test_feed_kind;
+mod test_feed_registry;
 mod test_full_publisher_set;
+mod test_genesis;
+mod test_get_extensions;
 mod test_init_mapping;
 mod test_init_price;
+mod test_inspect;
+mod test_instruction_fixtures;
+mod test_interface;
+mod test_isolated_updates;
+mod test_landing_latency;
+mod test_latency_stats;
+mod test_layout;
+mod test_listing_cost;
+mod test_mapping;
+mod test_mapping_v2;
 mod test_message;
+mod test_migration;
+mod test_multisig;
+mod test_oracle_account;
+mod test_pda_accounts;
 mod test_permission_migration;
+mod test_permissions_timelock;
+mod test_price_account_flags;
+mod test_price_extrema;
+mod test_prune_publishers;
 mod test_publish;
 mod test_publish_batch;
+mod test_publish_threshold;
+mod test_publisher_activity;
+mod test_publisher_cap;
+mod test_publisher_registry;
+mod test_publisher_rewards;
+mod test_publisher_sampling;
+mod test_publisher_sequences;
+mod test_publisher_timestamps;
+mod test_publisher_weight_cap;
+mod test_publishing_slot;
+mod test_quote_conversion;
+mod test_quote_order;
+mod test_quote_venue;
+mod test_read_stats;
+mod test_replay;
+mod test_reset_ema;
 mod test_resize_mapping;
+mod test_rotate_publisher;
+mod test_sanity_program;
+#[cfg(all(feature = "serde", feature = "borsh"))]
+mod test_serialization;
+mod test_set_exponent;
 mod test_set_max_latency;
 mod test_set_min_pub;
+mod test_set_rate_limit;
+mod test_simulate_aggregate;
 mod test_sizes;
+mod test_slot_weighted_twap;
+mod test_snapshot_price_account;
+mod test_stake_escrow;
+mod test_status_observer;
+mod test_status_policy;
+mod test_status_reason;
+mod test_test_vectors;
 mod test_upd_aggregate;
 mod test_upd_permissions;
 mod test_upd_price;
+mod test_upd_price_compact;
 mod test_upd_price_no_fail_on_error;
 mod test_upd_price_with_validator;
 mod test_upd_product;
+mod test_upd_publisher_status;
+mod test_update_count;
+mod test_update_fee;
+mod test_updater_program;
 mod test_utils;
+mod test_validate_accounts;
+mod test_zero_conf_vectors;
 
 
 mod test_twap;
@@ -1,51 +1,133 @@
 use {
     crate::{
         accounts::{
+            create_pc_str_t,
+            data_len_with_extension,
+            feature_name,
+            find_price_pda,
+            find_product_pda,
+            AccessControl,
+            AccessControlAccount,
+            FeedKind,
             MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceSuccessor,
+            PublisherRateLimit,
+            PublisherRewards,
+            PublisherStake,
+            PublisherWeightCap,
+            QuoteOrder,
+            SanityProgram,
+            SharedSettings,
+            StatusObserver,
+            UpdateFee,
+            UpdaterProgram,
+            AGGREGATION_CONFIG_SEED,
+            AUDIT_LOG_SEED,
+            COMMUNITY_SEED,
+            FEATURE_GATE_SEED,
+            FEED_REGISTRY_SEED,
+            FEE_VAULT_SEED,
             PERMISSIONS_SEED,
+            PUBLISHER_LINKAGE_SEED,
+            PUBLISHER_REGISTRY_SEED,
+            REWARDS_LEDGER_SEED,
+            SNAPSHOT_SEED,
+            UPD_PRICE_WRITE_SEED,
         },
         c_oracle_header::{
             PC_PROD_ACC_SIZE,
             PC_PTYPE_PRICE,
+            PC_STATUS_UNKNOWN,
+        },
+        deserialize::{
+            load,
+            load_mut,
         },
-        deserialize::load,
         instruction::{
+            AddCommunityPriceArgs,
+            AddCommunityProductArgs,
             AddPriceArgs,
             AddPublisherArgs,
+            AssertPriceConditionArgs,
             CommandHeader,
+            InitPriceArgs,
+            InitPricePdaArgs,
             OracleCommand,
+            PrunePublishersArgs,
+            SanityCheckArgs,
+            SetAccumulatorEmitterArgs,
+            SetAggregationConfigArgs,
+            SetFeatureArgs,
+            SetFeatureGracePeriodArgs,
+            SetMinPubArgs,
+            SetPermissionsTimelockArgs,
+            SetPublisherRewardsArgs,
+            SetPublisherWeightCapArgs,
+            SetQuoteOrderArgs,
+            SetRateLimitArgs,
+            SetSanityProgramArgs,
+            SetSharedSettingsArgs,
+            SetStakeProgramArgs,
+            SetStatusObserverArgs,
+            SetUpdateFeeArgs,
+            SetUpdaterProgramArgs,
+            StatusTransition,
+            UpdAccessControlArgs,
             UpdPermissionsArgs,
             UpdPriceArgs,
+            UpdPriceCompactArgs,
+            UpdPublisherLinkageArgs,
+            UpdPublisherRegistryArgs,
+            UpdPublisherStatusArgs,
+            WithdrawFeesArgs,
+            SANITY_CHECK_APPROVED,
         },
+        migration::Migration,
     },
     bytemuck::{
         bytes_of,
+        pod_read_unaligned,
         Pod,
     },
+    num_traits::FromPrimitive,
     serde::{
         Deserialize,
         Serialize,
     },
     solana_program::{
+        account_info::AccountInfo,
         bpf_loader_upgradeable::{
             self,
             UpgradeableLoaderState,
         },
         clock::Clock,
+        entrypoint::ProgramResult,
         hash::Hash,
         instruction::{
             AccountMeta,
             Instruction,
         },
         native_token::LAMPORTS_PER_SOL,
+        program::{
+            invoke,
+            set_return_data,
+        },
+        program_error::ProgramError,
         pubkey::Pubkey,
         rent::Rent,
         stake_history::Epoch,
         system_instruction,
         system_program,
-        sysvar::SysvarId,
+        sysvar::{
+            self,
+            SysvarId,
+        },
     },
     solana_program_test::{
+        processor,
         read_file,
         BanksClientError,
         ProgramTest,
@@ -73,15 +155,154 @@ use {
 /// Simulator for the state of the pyth program on Solana. You can run solana transactions against
 /// this struct to test how pyth instructions execute in the Solana runtime.
 pub struct PythSimulator {
-    program_id:            Pubkey,
-    context:               ProgramTestContext,
+    program_id:                    Pubkey,
+    context:                       ProgramTestContext,
     /// Hash used to submit the last transaction. The hash must be advanced for each new
     /// transaction; otherwise, replayed transactions in different states can return stale
     /// results.
-    last_blockhash:        Hash,
-    programdata_id:        Pubkey,
-    pub upgrade_authority: Keypair,
-    pub genesis_keypair:   Keypair,
+    last_blockhash:                Hash,
+    programdata_id:                Pubkey,
+    pub upgrade_authority:         Keypair,
+    pub genesis_keypair:           Keypair,
+    /// Compute units consumed by the last successful transaction
+    last_compute_units:            u64,
+    /// Sanity program approving the aggregates up to `SANITY_MAX_PRICE`, see
+    /// `process_sanity_check`
+    pub sanity_program_id:         Pubkey,
+    /// Staking program returning the stake held in its escrow accounts, see
+    /// `process_stake_check`
+    pub staking_program_id:        Pubkey,
+    /// Message buffer program recording the messages it receives, see `process_put_all`
+    pub message_buffer_program_id: Pubkey,
+    /// Status observer rejecting the transitions to `PC_STATUS_UNKNOWN`, see
+    /// `process_status_transition`
+    pub status_observer_id:        Pubkey,
+    /// Accumulator emitter rejecting the snapshots of negative aggregates, see
+    /// `process_put_snapshot`
+    pub accumulator_emitter_id:    Pubkey,
+    /// Updater program forwarding the instructions it receives to the oracle, see
+    /// `process_forward`
+    pub updater_program_id:        Pubkey,
+    /// Whether the governance instructions list the audit log, once `init_audit_log` created it
+    audit_log:                     bool,
+}
+
+/// Size of the message buffer accounts of the simulator.
+pub const MESSAGE_BUFFER_SIZE: usize = 1024;
+
+/// Largest aggregate price approved by the sanity program of the simulator.
+pub const SANITY_MAX_PRICE: i64 = 1_000;
+
+/// Approve the aggregates up to `SANITY_MAX_PRICE`, only while the price account is flagged as
+/// aggregating: the check runs in the middle of the aggregation, before the EMA is updated.
+fn process_sanity_check(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let args: SanityCheckArgs = pod_read_unaligned(instruction_data);
+    let price_data: PriceAccount =
+        pod_read_unaligned(&accounts[0].try_borrow_data()?[..size_of::<PriceAccount>()]);
+    if args.price <= SANITY_MAX_PRICE
+        && price_data
+            .flags
+            .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS)
+    {
+        set_return_data(SANITY_CHECK_APPROVED);
+    }
+    Ok(())
+}
+
+/// Return the stake held in the escrow account, the little endian `u64` at the start of its data.
+fn process_stake_check(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    _instruction_data: &[u8],
+) -> ProgramResult {
+    let amount: u64 = pod_read_unaligned(&accounts[0].try_borrow_data()?[..size_of::<u64>()]);
+    set_return_data(&amount.to_le_bytes());
+    Ok(())
+}
+
+/// Reject the transitions of the aggregate to `PC_STATUS_UNKNOWN`, so that tests can tell which
+/// price updates notified the observer.
+fn process_status_transition(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let transition: StatusTransition = pod_read_unaligned(instruction_data);
+    if transition.price_account != *accounts[0].key {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if transition.status == PC_STATUS_UNKNOWN {
+        return Err(ProgramError::Custom(0));
+    }
+    Ok(())
+}
+
+/// Record the `put_all` instruction at the start of the message buffer account, so that tests can
+/// read the messages sent by the oracle. Only the oracle auth PDA, which signs the CPI, can write.
+fn process_put_all(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if !accounts[1].is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    accounts[2].try_borrow_mut_data()?[..instruction_data.len()].copy_from_slice(instruction_data);
+    Ok(())
+}
+
+/// Accept the snapshots signed by the oracle auth PDA for the price account they list, apart from
+/// the snapshots of negative aggregates, so that tests can tell which price updates pushed one.
+fn process_put_snapshot(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if !accounts[0].is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if instruction_data.get(8..40) != Some(&accounts[1].key.to_bytes()[..]) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    // The price of the first message, a `PriceFeedMessage`, follows the discriminator, the key,
+    // the lengths of the list and of the message, the message discriminator and the feed id
+    let price = instruction_data
+        .get(81..89)
+        .map(|bytes| i64::from_be_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    if price < 0 {
+        return Err(ProgramError::Custom(0));
+    }
+    Ok(())
+}
+
+/// Invoke the program passed first with the instruction data and the rest of the accounts, keeping
+/// their privileges, like a relayer forwarding the quotes of publishers.
+fn process_forward(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (program, forwarded) = accounts
+        .split_first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let instruction = Instruction::new_with_bytes(
+        *program.key,
+        instruction_data,
+        forwarded
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey:      *account.key,
+                is_signer:   account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+    );
+    invoke(&instruction, forwarded)
 }
 
 pub struct Quote {
@@ -148,6 +369,45 @@ impl PythSimulator {
         program_test.add_account(program_key, program_account);
         program_test.add_account(programdata_key, programdata_account);
 
+        // The sanity program only exists as a native program
+        let sanity_program_id = Pubkey::new_unique();
+        program_test.prefer_bpf(false);
+        program_test.add_program(
+            "sanity_program",
+            sanity_program_id,
+            processor!(process_sanity_check),
+        );
+        let staking_program_id = Pubkey::new_unique();
+        program_test.add_program(
+            "staking_program",
+            staking_program_id,
+            processor!(process_stake_check),
+        );
+        let message_buffer_program_id = Pubkey::new_unique();
+        program_test.add_program(
+            "message_buffer_program",
+            message_buffer_program_id,
+            processor!(process_put_all),
+        );
+        let status_observer_id = Pubkey::new_unique();
+        program_test.add_program(
+            "status_observer",
+            status_observer_id,
+            processor!(process_status_transition),
+        );
+        let accumulator_emitter_id = Pubkey::new_unique();
+        program_test.add_program(
+            "accumulator_emitter",
+            accumulator_emitter_id,
+            processor!(process_put_snapshot),
+        );
+        let updater_program_id = Pubkey::new_unique();
+        program_test.add_program(
+            "updater_program",
+            updater_program_id,
+            processor!(process_forward),
+        );
+
         // Start validator
         let context = program_test.start_with_context().await;
         let genesis_keypair = copy_keypair(&context.payer);
@@ -159,6 +419,14 @@ impl PythSimulator {
             programdata_id: programdata_key,
             upgrade_authority: upgrade_authority_keypair,
             genesis_keypair: copy_keypair(&genesis_keypair),
+            last_compute_units: 0,
+            sanity_program_id,
+            staking_program_id,
+            message_buffer_program_id,
+            status_observer_id,
+            accumulator_emitter_id,
+            updater_program_id,
+            audit_log: false,
         };
 
         // Transfer money to upgrade_authority so it can call the instructions
@@ -191,7 +459,46 @@ impl PythSimulator {
         signers: &Vec<&Keypair>,
         payer: &Keypair,
     ) -> Result<(), BanksClientError> {
-        let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+        self.process_ixs_with_compute_units(instructions, signers, payer)
+            .await
+            .map(|_| ())
+    }
+
+    /// Same as `process_ixs`, but returns the number of compute units consumed by the transaction.
+    async fn process_ixs_with_compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+        payer: &Keypair,
+    ) -> Result<u64, BanksClientError> {
+        let transaction = self.sign_transaction(instructions, signers, payer).await;
+
+        let result = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(transaction)
+            .await?;
+        result.result.map_err(BanksClientError::TransactionError)?;
+        self.last_compute_units = result.metadata.unwrap().compute_units_consumed;
+        Ok(self.last_compute_units)
+    }
+
+    /// Compute units consumed by the last successful transaction sent by the simulator.
+    pub fn last_compute_units(&self) -> u64 {
+        self.last_compute_units
+    }
+
+    async fn sign_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &Vec<&Keypair>,
+        payer: &Keypair,
+    ) -> Transaction {
+        let instructions: Vec<Instruction> = instructions
+            .iter()
+            .map(|instruction| self.with_audit_log(instruction))
+            .collect();
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
 
         let blockhash = self
             .context
@@ -203,11 +510,23 @@ impl PythSimulator {
 
         transaction.partial_sign(&[payer], self.last_blockhash);
         transaction.partial_sign(signers, self.last_blockhash);
+        transaction
+    }
 
-        self.context
-            .banks_client
-            .process_transaction(transaction)
-            .await
+    /// `instruction` listing the audit log last if it's a governance instruction of the oracle and
+    /// the audit log exists.
+    fn with_audit_log(&self, instruction: &Instruction) -> Instruction {
+        let mut instruction = instruction.clone();
+        let is_governance = load::<CommandHeader>(&instruction.data)
+            .ok()
+            .and_then(|header| OracleCommand::from_i32(header.command))
+            .map_or(false, |command| command.audit_target().is_some());
+        if self.audit_log && instruction.program_id == self.program_id && is_governance {
+            instruction
+                .accounts
+                .push(AccountMeta::new(self.get_audit_log_pubkey(), false));
+        }
+        instruction
     }
 
     /// Create an account owned by the pyth program containing `size` bytes.
@@ -320,6 +639,27 @@ impl PythSimulator {
         &mut self,
         product_keypair: &Keypair,
         expo: i32,
+    ) -> Result<Keypair, BanksClientError> {
+        self.add_price_with_accounts(product_keypair, expo, vec![])
+            .await
+    }
+
+    /// Same as `add_price`, registering the new price account in the feed registry.
+    pub async fn add_price_with_registry(
+        &mut self,
+        product_keypair: &Keypair,
+        expo: i32,
+    ) -> Result<Keypair, BanksClientError> {
+        let registry_accounts = self.get_feed_registry_accounts();
+        self.add_price_with_accounts(product_keypair, expo, registry_accounts)
+            .await
+    }
+
+    async fn add_price_with_accounts(
+        &mut self,
+        product_keypair: &Keypair,
+        expo: i32,
+        extra_accounts: Vec<AccountMeta>,
     ) -> Result<Keypair, BanksClientError> {
         let price_keypair = self
             .create_pyth_account(size_of::<crate::accounts::PriceAccount>())
@@ -330,12 +670,43 @@ impl PythSimulator {
             exponent:   expo,
             price_type: PC_PTYPE_PRICE,
         };
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(product_keypair.pubkey(), true),
+            AccountMeta::new(price_keypair.pubkey(), true),
+            AccountMeta::new(self.get_permissions_pubkey(), false),
+        ];
+        accounts.extend(extra_accounts);
+        let instruction = Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![product_keypair, &price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+        .map(|_| price_keypair)
+    }
+
+    /// Add a price account with the configuration and the publishers of `source` to its product
+    /// (using the clone_price instruction).
+    pub async fn clone_price(
+        &mut self,
+        product_keypair: &Keypair,
+        source: Pubkey,
+    ) -> Result<Keypair, BanksClientError> {
+        let price_keypair = self
+            .create_pyth_account(size_of::<crate::accounts::PriceAccount>())
+            .await;
+
+        let cmd: CommandHeader = OracleCommand::ClonePrice.into();
         let instruction = Instruction::new_with_bytes(
             self.program_id,
             bytes_of(&cmd),
             vec![
                 AccountMeta::new(self.genesis_keypair.pubkey(), true),
                 AccountMeta::new(product_keypair.pubkey(), true),
+                AccountMeta::new_readonly(source, false),
                 AccountMeta::new(price_keypair.pubkey(), true),
                 AccountMeta::new(self.get_permissions_pubkey(), false),
             ],
@@ -350,6 +721,34 @@ impl PythSimulator {
         .map(|_| price_keypair)
     }
 
+    /// Reinitialize a price account (using the init_price instruction) and register it in the
+    /// feed registry.
+    pub async fn init_price_with_registry(
+        &mut self,
+        price_keypair: &Keypair,
+        expo: i32,
+    ) -> Result<(), BanksClientError> {
+        let cmd = InitPriceArgs {
+            header:     OracleCommand::InitPrice.into(),
+            exponent:   expo,
+            price_type: PC_PTYPE_PRICE,
+        };
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(price_keypair.pubkey(), true),
+            AccountMeta::new(self.get_permissions_pubkey(), false),
+        ];
+        accounts.extend(self.get_feed_registry_accounts());
+        let instruction = Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
     /// Add a publisher to a price account (using the add_publisher instruction).
     pub async fn add_publisher(
         &mut self,
@@ -378,153 +777,221 @@ impl PythSimulator {
         .await
     }
 
-    /// Update price of a component price account (using the upd_price instruction).
-    pub async fn upd_price(
+    /// Same as `add_publisher` for a price account that doesn't sign, e.g. a community feed,
+    /// signed and paid by `funding`.
+    pub async fn add_publisher_with_funding(
         &mut self,
-        publisher: &Keypair,
-        price_account: Pubkey,
-        quote: Quote,
+        funding: &Keypair,
+        price: Pubkey,
+        publisher: Pubkey,
     ) -> Result<(), BanksClientError> {
-        self.upd_price_batch(
+        let cmd = AddPublisherArgs {
+            header: OracleCommand::AddPublisher.into(),
             publisher,
-            &HashMap::from_iter(once(("".to_string(), price_account))),
-            &HashMap::from_iter(once(("".to_string(), quote))),
-        )
-        .await
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(funding.pubkey(), true),
+                AccountMeta::new(price, false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], funding).await
     }
 
-    /// Update price in multiple price account atomically (using the upd_price instruction)
-    pub async fn upd_price_batch(
+    /// Create the community product of `creator` under `seed` (using the add_community_product
+    /// instruction) with the metadata `strings`, alternating keys and values. Returns the address
+    /// of the product account.
+    pub async fn add_community_product(
         &mut self,
-        publisher: &Keypair,
-        price_accounts: &HashMap<String, Pubkey>,
-        quotes: &HashMap<String, Quote>,
-    ) -> Result<(), BanksClientError> {
-        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
-        let mut instructions: Vec<Instruction> = vec![];
-
-        for (key, price_account) in price_accounts {
-            let cmd = UpdPriceArgs {
-                header:          OracleCommand::UpdPrice.into(),
-                status:          quotes[key].status,
-                unused_:         0,
-                price:           quotes[key].price,
-                confidence:      quotes[key].confidence,
-                publishing_slot: slot,
-            };
-            instructions.push(Instruction::new_with_bytes(
-                self.program_id,
-                bytes_of(&cmd),
-                vec![
-                    AccountMeta::new(publisher.pubkey(), true),
-                    AccountMeta::new(*price_account, false),
-                    AccountMeta::new(Clock::id(), false),
-                ],
-            ));
+        creator: &Keypair,
+        seed: [u8; 32],
+        strings: &[&str],
+    ) -> Result<Pubkey, BanksClientError> {
+        let product = self.get_community_pubkey(&creator.pubkey(), &seed);
+        let cmd = AddCommunityProductArgs {
+            header: OracleCommand::AddCommunityProduct.into(),
+            seed,
+        };
+        let mut instruction_data = bytes_of(&cmd).to_vec();
+        for s in strings {
+            instruction_data.extend(create_pc_str_t(s));
         }
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            &instruction_data,
+            vec![
+                AccountMeta::new(creator.pubkey(), true),
+                AccountMeta::new(product, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
 
-        self.process_ixs(&instructions, &vec![publisher], publisher)
+        self.process_ixs(&[instruction], &vec![], creator)
             .await
+            .map(|_| product)
     }
 
-    // /// Delete a price account from an existing product account (using the del_price instruction).
-    pub async fn del_price(
+    /// Create an empty message buffer account of the message buffer program of the simulator.
+    pub fn create_message_buffer(&mut self) -> Pubkey {
+        let message_buffer = Pubkey::new_unique();
+        let data = vec![0; MESSAGE_BUFFER_SIZE];
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: self.message_buffer_program_id,
+            executable: false,
+            rent_epoch: Epoch::default(),
+        };
+        self.context.set_account(&message_buffer, &account.into());
+        message_buffer
+    }
+
+    /// Optional accounts of the instructions sending messages to `message_buffer`.
+    fn message_buffer_accounts(&self, message_buffer: Pubkey) -> [AccountMeta; 4] {
+        let (oracle_auth_pda, _) = Pubkey::find_program_address(
+            &[
+                UPD_PRICE_WRITE_SEED.as_bytes(),
+                &self.message_buffer_program_id.to_bytes(),
+            ],
+            &self.program_id,
+        );
+        [
+            AccountMeta::new_readonly(self.message_buffer_program_id, false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(oracle_auth_pda, false),
+            AccountMeta::new(message_buffer, false),
+        ]
+    }
+
+    /// Overwrite the metadata of a product (using the upd_product instruction), sending it to
+    /// `message_buffer` if any.
+    pub async fn upd_product(
         &mut self,
         product_keypair: &Keypair,
-        price_keypair: &Keypair,
+        strings: &[&str],
+        message_buffer: Option<Pubkey>,
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::DelPrice.into();
-        let instruction = Instruction::new_with_bytes(
-            self.program_id,
-            bytes_of(&cmd),
-            vec![
-                AccountMeta::new(self.genesis_keypair.pubkey(), true),
-                AccountMeta::new(product_keypair.pubkey(), true),
-                AccountMeta::new(price_keypair.pubkey(), true),
-                AccountMeta::new(self.get_permissions_pubkey(), false),
-            ],
-        );
+        let cmd: CommandHeader = OracleCommand::UpdProduct.into();
+        let mut instruction_data = bytes_of(&cmd).to_vec();
+        for s in strings {
+            instruction_data.extend(create_pc_str_t(s));
+        }
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(product_keypair.pubkey(), true),
+            AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+        ];
+        if let Some(message_buffer) = message_buffer {
+            accounts.extend(self.message_buffer_accounts(message_buffer));
+        }
+        let instruction = Instruction::new_with_bytes(self.program_id, &instruction_data, accounts);
 
         self.process_ixs(
             &[instruction],
-            &vec![product_keypair, price_keypair],
+            &vec![product_keypair],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
     }
 
-    /// Update permissions (using the upd_permissions intruction) and return the pubkey of the
-    /// permissions account
-    pub async fn upd_permissions(
+    /// Create the community price account of `creator` under `price_seed` for its product under
+    /// `product_seed` (using the add_community_price instruction). Returns the address of the
+    /// price account.
+    pub async fn add_community_price(
         &mut self,
-        cmd_args: UpdPermissionsArgs,
-        payer: &Keypair,
+        creator: &Keypair,
+        product_seed: [u8; 32],
+        price_seed: [u8; 32],
+        exponent: i32,
     ) -> Result<Pubkey, BanksClientError> {
-        let permissions_pubkey = self.get_permissions_pubkey();
-
+        let product = self.get_community_pubkey(&creator.pubkey(), &product_seed);
+        let price = self.get_community_pubkey(&creator.pubkey(), &price_seed);
+        let cmd = AddCommunityPriceArgs {
+            header: OracleCommand::AddCommunityPrice.into(),
+            exponent,
+            price_type: PC_PTYPE_PRICE,
+            product_seed,
+            price_seed,
+        };
         let instruction = Instruction::new_with_bytes(
             self.program_id,
-            bytes_of(&cmd_args),
+            bytes_of(&cmd),
             vec![
-                AccountMeta::new(payer.pubkey(), true),
-                AccountMeta::new_readonly(self.programdata_id, false),
-                AccountMeta::new(permissions_pubkey, false),
+                AccountMeta::new(creator.pubkey(), true),
+                AccountMeta::new(product, false),
+                AccountMeta::new(price, false),
                 AccountMeta::new_readonly(system_program::id(), false),
             ],
         );
 
-        self.process_ixs(&[instruction], &vec![], payer)
+        self.process_ixs(&[instruction], &vec![], creator)
             .await
-            .map(|_| permissions_pubkey)
-    }
-
-    pub async fn truncate_account(&mut self, key: Pubkey, size: usize) {
-        let mut account = self.get_account(key).await.unwrap();
-        account.data.truncate(size);
-        self.context.set_account(&key, &account.into());
+            .map(|_| price)
     }
 
-    pub async fn resize_mapping(
+    /// Create the product account `product` and add it to the mapping account (using the
+    /// init_product_pda instruction) with the metadata `strings`, alternating keys and values.
+    pub async fn init_product_pda(
         &mut self,
         mapping_keypair: &Keypair,
+        product: Pubkey,
+        strings: &[&str],
     ) -> Result<(), BanksClientError> {
-        let cmd: CommandHeader = OracleCommand::ResizeMapping.into();
+        let cmd: CommandHeader = OracleCommand::InitProductPda.into();
+        let mut instruction_data = bytes_of(&cmd).to_vec();
+        for s in strings {
+            instruction_data.extend(create_pc_str_t(s));
+        }
         let instruction = Instruction::new_with_bytes(
             self.program_id,
-            bytes_of(&cmd),
-            vec![AccountMeta::new(mapping_keypair.pubkey(), false)],
+            &instruction_data,
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(mapping_keypair.pubkey(), true),
+                AccountMeta::new(product, false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
         );
 
         self.process_ixs(
             &[instruction],
-            &vec![],
+            &vec![mapping_keypair],
             &copy_keypair(&self.genesis_keypair),
         )
         .await
     }
 
-    /// Get the account at `key`. Returns `None` if no such account exists.
-    pub async fn get_account(&mut self, key: Pubkey) -> Option<Account> {
-        self.context.banks_client.get_account(key).await.unwrap()
-    }
-
-    /// Get the content of an account as a value of type `T`. This function returns a copy of the
-    /// account data -- you cannot mutate the result to mutate the on-chain account data.
-    /// Returns None if the account does not exist. Panics if the account data cannot be read as a
-    /// `T`.
-    pub async fn get_account_data_as<T: Pod>(&mut self, key: Pubkey) -> Option<T> {
-        self.get_account(key)
-            .await
-            .map(|x| *load::<T>(&x.data).unwrap())
-    }
-
-    pub fn is_owned_by_oracle(&self, account: &Account) -> bool {
-        account.owner == self.program_id
-    }
-
-    pub async fn airdrop(&mut self, to: &Pubkey, lamports: u64) -> Result<(), BanksClientError> {
-        let instruction =
-            system_instruction::transfer(&self.genesis_keypair.pubkey(), to, lamports);
+    /// Create the price account `price` of kind `feed_kind` for `product` (using the
+    /// init_price_pda instruction), registered in the feed registry.
+    pub async fn init_price_pda(
+        &mut self,
+        product: Pubkey,
+        price: Pubkey,
+        feed_kind: FeedKind,
+        exponent: i32,
+    ) -> Result<(), BanksClientError> {
+        let cmd = InitPricePdaArgs {
+            header: OracleCommand::InitPricePda.into(),
+            exponent,
+            price_type: feed_kind as u32,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(product, false),
+                AccountMeta::new(price, false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new(self.get_feed_registry_pubkey(), false),
+            ],
+        );
 
         self.process_ixs(
             &[instruction],
@@ -534,10 +1001,1680 @@ impl PythSimulator {
         .await
     }
 
-    pub fn get_permissions_pubkey(&self) -> Pubkey {
-        let (permissions_pubkey, __bump) =
-            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.program_id);
-        permissions_pubkey
+    /// Same as `add_publisher` but checking that the publisher is approved in the publisher
+    /// registry.
+    pub async fn add_publisher_with_registry(
+        &mut self,
+        price_keypair: &Keypair,
+        publisher: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let cmd = AddPublisherArgs {
+            header: OracleCommand::AddPublisher.into(),
+            publisher,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(price_keypair.pubkey(), true),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(self.get_publisher_registry_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(
+            &[instruction],
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Same as `add_publisher` but passing the stake escrow of the publisher and the staking
+    /// program of the simulator.
+    pub async fn add_publisher_with_stake(
+        &mut self,
+        price_keypair: &Keypair,
+        publisher: Pubkey,
+        escrow: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let cmd = AddPublisherArgs {
+            header: OracleCommand::AddPublisher.into(),
+            publisher,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(price_keypair.pubkey(), true),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(escrow, false),
+                AccountMeta::new_readonly(self.staking_program_id, false),
+            ],
+        );
+
+        self.process_ixs(
+            &[instruction],
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Create a stake escrow account holding `amount`, owned by `owner`, see
+    /// `process_stake_check`.
+    pub fn create_stake_escrow(&mut self, owner: Pubkey, amount: u64) -> Pubkey {
+        let escrow = Pubkey::new_unique();
+        let data = amount.to_le_bytes().to_vec();
+        let account = Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+            rent_epoch: Epoch::default(),
+        };
+        self.context.set_account(&escrow, &account.into());
+        escrow
+    }
+
+    /// Approve `publisher` in the publisher registry or revoke it (using the
+    /// upd_publisher_registry instruction), signed and paid by `authority`.
+    pub async fn upd_publisher_registry(
+        &mut self,
+        publisher: Pubkey,
+        approved: bool,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = UpdPublisherRegistryArgs {
+            header: OracleCommand::UpdPublisherRegistry.into(),
+            publisher,
+            approved: approved.into(),
+            unused_: [0; 3],
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_publisher_registry_pubkey(), false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Link `publisher` to `entity` in the publisher linkage account, or unlink it if `entity` is
+    /// the default key (using the upd_publisher_linkage instruction), signed and paid by
+    /// `authority`.
+    pub async fn upd_publisher_linkage(
+        &mut self,
+        publisher: Pubkey,
+        entity: Pubkey,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = UpdPublisherLinkageArgs {
+            header: OracleCommand::UpdPublisherLinkage.into(),
+            publisher,
+            entity,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_publisher_linkage_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Create the audit log (using the init_audit_log instruction), signed and paid by
+    /// `authority`. The governance instructions sent afterwards list it.
+    pub async fn init_audit_log(&mut self, authority: &Keypair) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::InitAuditLog.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_audit_log_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await?;
+        self.audit_log = true;
+        Ok(())
+    }
+
+    /// Flip the switch of the feature `name` of the feature gate (using the set_feature
+    /// instruction), signed and paid by `authority`.
+    pub async fn set_feature(
+        &mut self,
+        name: &str,
+        enabled: bool,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = SetFeatureArgs {
+            header:  OracleCommand::SetFeature.into(),
+            name:    feature_name(name),
+            enabled: u8::from(enabled),
+            unused_: [0; 7],
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_feature_gate_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(Clock::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Set the grace period of the feature gate (using the set_feature_grace_period
+    /// instruction), signed and paid by `authority`.
+    pub async fn set_feature_grace_period(
+        &mut self,
+        grace_period: u64,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = SetFeatureGracePeriodArgs {
+            header: OracleCommand::SetFeatureGracePeriod.into(),
+            grace_period,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_feature_gate_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Set the defaults of the aggregation config (using the set_aggregation_config
+    /// instruction), signed and paid by `authority`.
+    pub async fn set_aggregation_config(
+        &mut self,
+        max_ci_divisor: i64,
+        max_latency: u8,
+        min_pub: u8,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = SetAggregationConfigArgs {
+            header: OracleCommand::SetAggregationConfig.into(),
+            max_ci_divisor,
+            max_latency,
+            min_pub,
+            unused_: [0; 6],
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_aggregation_config_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Remove the idle publishers of a price account (using the prune_publishers instruction),
+    /// and the ones that aren't approved in the publisher registry if `with_registry`.
+    pub async fn prune_publishers(
+        &mut self,
+        price_keypair: &Keypair,
+        max_idle_slots: u64,
+        with_registry: bool,
+    ) -> Result<(), BanksClientError> {
+        let cmd = PrunePublishersArgs {
+            header: OracleCommand::PrunePublishers.into(),
+            max_idle_slots,
+        };
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(price_keypair.pubkey(), true),
+            AccountMeta::new(self.get_permissions_pubkey(), false),
+            AccountMeta::new_readonly(Clock::id(), false),
+        ];
+        if with_registry {
+            accounts.push(AccountMeta::new_readonly(
+                self.get_publisher_registry_pubkey(),
+                false,
+            ));
+        }
+        let instruction = Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the minimum number of publishers of a price account (using the set_min_pub
+    /// instruction).
+    pub async fn set_min_pub(
+        &mut self,
+        price_keypair: &Keypair,
+        minimum_publishers: u8,
+    ) -> Result<(), BanksClientError> {
+        let cmd = SetMinPubArgs {
+            header: OracleCommand::SetMinPub.into(),
+            minimum_publishers,
+            unused_: [0; 3],
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(price_keypair.pubkey(), true),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(
+            &[instruction],
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Limit the rate of the updates of the publishers of a price account (using the
+    /// set_rate_limit instruction), funding the price account for its new size.
+    pub async fn set_rate_limit(
+        &mut self,
+        price_keypair: &Keypair,
+        window: u64,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<PublisherRateLimit>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetRateLimitArgs {
+            header: OracleCommand::SetRateLimit.into(),
+            window,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the sanity program of a price account (using the set_sanity_program instruction),
+    /// funding the price account for its new size.
+    pub async fn set_sanity_program(
+        &mut self,
+        price_keypair: &Keypair,
+        program_id: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<SanityProgram>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetSanityProgramArgs {
+            header: OracleCommand::SetSanityProgram.into(),
+            program_id,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the updater program of a price account (using the set_updater_program instruction),
+    /// funding the price account for its new size.
+    pub async fn set_updater_program(
+        &mut self,
+        price_keypair: &Keypair,
+        program_id: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<UpdaterProgram>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetUpdaterProgramArgs {
+            header: OracleCommand::SetUpdaterProgram.into(),
+            program_id,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Make a price account follow the shared settings or not (using the set_shared_settings
+    /// instruction), funding the price account for its new size.
+    pub async fn set_shared_settings(
+        &mut self,
+        price_keypair: &Keypair,
+        enabled: bool,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<SharedSettings>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetSharedSettingsArgs {
+            header:  OracleCommand::SetSharedSettings.into(),
+            enabled: u64::from(enabled),
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the status observer of a price account (using the set_status_observer instruction),
+    /// funding the price account for its new size.
+    pub async fn set_status_observer(
+        &mut self,
+        price_keypair: &Keypair,
+        program_id: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<StatusObserver>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetStatusObserverArgs {
+            header: OracleCommand::SetStatusObserver.into(),
+            program_id,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the staking program of a price account (using the set_stake_program instruction),
+    /// funding the price account for its new size.
+    pub async fn set_stake_program(
+        &mut self,
+        price_keypair: &Keypair,
+        staking_program: Pubkey,
+        min_stake: u64,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<PublisherStake>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetStakeProgramArgs {
+            header: OracleCommand::SetStakeProgram.into(),
+            staking_program,
+            min_stake,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Cap the quotes per entity of a price account (using the set_publisher_weight_cap
+    /// instruction), funding the price account for its new size.
+    pub async fn set_publisher_weight_cap(
+        &mut self,
+        price_keypair: &Keypair,
+        max_quotes_per_entity: u64,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<PublisherWeightCap>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetPublisherWeightCapArgs {
+            header: OracleCommand::SetPublisherWeightCap.into(),
+            max_quotes_per_entity,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                    AccountMeta::new_readonly(self.get_publisher_linkage_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Maintain the order of the quotes of a price account or stop maintaining it (using the
+    /// set_quote_order instruction), funding the growth of the account for the extension.
+    pub async fn set_quote_order(
+        &mut self,
+        price_keypair: &Keypair,
+        enabled: bool,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<QuoteOrder>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetQuoteOrderArgs {
+            header:  OracleCommand::SetQuoteOrder.into(),
+            enabled: u64::from(enabled),
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Set the fee of every quote of the feed (using the set_update_fee instruction), funding the
+    /// growth of the price account and the creation of the fee vault from the genesis account.
+    pub async fn set_update_fee(
+        &mut self,
+        price_keypair: &Keypair,
+        lamports: u64,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<UpdateFee>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetUpdateFeeArgs {
+            header: OracleCommand::SetUpdateFee.into(),
+            lamports,
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                    AccountMeta::new(self.get_fee_vault_pubkey(), false),
+                    AccountMeta::new_readonly(system_program::id(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Start or stop scoring the quotes of the feed (using the set_publisher_rewards
+    /// instruction), funding the growth of the price account from the genesis account.
+    pub async fn set_publisher_rewards(
+        &mut self,
+        price_keypair: &Keypair,
+        enabled: bool,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<PublisherRewards>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd = SetPublisherRewardsArgs {
+            header:  OracleCommand::SetPublisherRewards.into(),
+            enabled: u64::from(enabled),
+        };
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new(self.get_permissions_pubkey(), false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Move the reward score of `publisher` in the feed to its rewards ledger (using the
+    /// claim_rewards instruction), signed and paid by `publisher`.
+    pub async fn claim_rewards(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::ClaimRewards.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(publisher.pubkey(), true),
+                AccountMeta::new(price_account, false),
+                AccountMeta::new(self.get_rewards_ledger_pubkey(&publisher.pubkey()), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], publisher).await
+    }
+
+    /// Move `lamports` from the fee vault to `recipient` (using the withdraw_fees instruction),
+    /// signed and paid by `authority`.
+    pub async fn withdraw_fees(
+        &mut self,
+        recipient: Pubkey,
+        lamports: u64,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = WithdrawFeesArgs {
+            header: OracleCommand::WithdrawFees.into(),
+            lamports,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_fee_vault_pubkey(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new(recipient, false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Deprecate a price account in favor of `successor` (using the deprecate_price
+    /// instruction), funding the growth of the account for the extension.
+    pub async fn deprecate_price(
+        &mut self,
+        price_keypair: &Keypair,
+        successor: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<PriceSuccessor>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd: CommandHeader = OracleCommand::DeprecatePrice.into();
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(
+                self.program_id,
+                bytes_of(&cmd),
+                vec![
+                    AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                    AccountMeta::new(price_keypair.pubkey(), true),
+                    AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                    AccountMeta::new_readonly(successor, false),
+                ],
+            ),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Create an access control account, returning its keypair.
+    pub async fn create_access_control(&mut self) -> Keypair {
+        self.create_pyth_account(size_of::<AccessControlAccount>())
+            .await
+    }
+
+    /// Add `reader` to an access control account or remove it (using the upd_access_control
+    /// instruction), signed by `authority`.
+    pub async fn upd_access_control(
+        &mut self,
+        access_control_keypair: &Keypair,
+        reader: Pubkey,
+        allowed: bool,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd = UpdAccessControlArgs {
+            header: OracleCommand::UpdAccessControl.into(),
+            reader,
+            allowed: u8::from(allowed),
+            unused_: [0; 7],
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(access_control_keypair.pubkey(), true),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![access_control_keypair], authority)
+            .await
+    }
+
+    /// Restrict the consumers of a price account to the readers of `access_control`, or lift
+    /// the restriction if `None` (using the set_access_control instruction), funding the growth
+    /// of the account for the extension.
+    pub async fn set_access_control(
+        &mut self,
+        price_keypair: &Keypair,
+        access_control: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let price_account = self.get_account(price_keypair.pubkey()).await.unwrap();
+        let new_size = data_len_with_extension::<AccessControl>(&price_account.data).unwrap();
+        let missing_lamports = Rent::default()
+            .minimum_balance(new_size)
+            .saturating_sub(price_account.lamports);
+
+        let cmd: CommandHeader = OracleCommand::SetAccessControl.into();
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(price_keypair.pubkey(), true),
+            AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+        ];
+        if let Some(access_control) = access_control {
+            accounts.push(AccountMeta::new_readonly(access_control, false));
+        }
+        let instructions = [
+            system_instruction::transfer(
+                &self.genesis_keypair.pubkey(),
+                &price_keypair.pubkey(),
+                missing_lamports,
+            ),
+            Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts),
+        ];
+
+        self.process_ixs(
+            &instructions,
+            &vec![price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Check the aggregate of a price account (using the assert_price_condition instruction),
+    /// signed by `reader` with `access_control` if the feed is permissioned.
+    pub async fn assert_price_condition(
+        &mut self,
+        price: Pubkey,
+        condition: AssertPriceConditionArgs,
+        reader: Option<(&Keypair, Pubkey)>,
+    ) -> Result<(), BanksClientError> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(price, false),
+            AccountMeta::new_readonly(Clock::id(), false),
+        ];
+        let mut signers = vec![];
+        if let Some((reader, access_control)) = reader {
+            accounts.push(AccountMeta::new_readonly(reader.pubkey(), true));
+            accounts.push(AccountMeta::new_readonly(access_control, false));
+            signers.push(reader);
+        }
+        let instruction =
+            Instruction::new_with_bytes(self.program_id, bytes_of(&condition), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &signers,
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Restart the EMAs of a price account from its aggregate (using the reset_ema instruction),
+    /// signed by `authority`.
+    pub async fn reset_ema(
+        &mut self,
+        price_account: Pubkey,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::ResetEma.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(price_account, false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Same as `upd_price`, passing the sanity program of the simulator after the accounts of the
+    /// instruction.
+    pub async fn upd_price_with_sanity_program(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(self.sanity_program_id, false));
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price`, passing the status observer of the simulator after the accounts of
+    /// the instruction.
+    pub async fn upd_price_with_status_observer(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(self.status_observer_id, false));
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price`, passing the permissions account, `emitter` and the oracle auth PDA of
+    /// `emitter` after the accounts of the instruction.
+    pub async fn upd_price_with_emitter(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+        emitter: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        let (oracle_auth_pda, _) = Pubkey::find_program_address(
+            &[UPD_PRICE_WRITE_SEED.as_bytes(), &emitter.to_bytes()],
+            &self.program_id,
+        );
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+            AccountMeta::new_readonly(emitter, false),
+            AccountMeta::new_readonly(oracle_auth_pda, false),
+        ]);
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price`, passing the instructions sysvar after the accounts of the instruction,
+    /// in a transaction with `before` and `after` around it.
+    pub async fn upd_price_with_instructions_sysvar(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+        before: Vec<Instruction>,
+        after: Vec<Instruction>,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(sysvar::instructions::id(), false));
+
+        let instructions: Vec<Instruction> = before
+            .into_iter()
+            .chain(once(instruction))
+            .chain(after)
+            .collect();
+        self.process_ixs(&instructions, &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price_with_instructions_sysvar`, forwarding the instruction through the
+    /// updater program of the simulator.
+    pub async fn upd_price_through_updater_program(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        let accounts = once(AccountMeta::new_readonly(self.program_id, false))
+            .chain(instruction.accounts)
+            .chain(once(AccountMeta::new_readonly(
+                sysvar::instructions::id(),
+                false,
+            )))
+            .collect();
+        let instruction =
+            Instruction::new_with_bytes(self.updater_program_id, &instruction.data, accounts);
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price`, passing the aggregation config and the feature gate after the accounts
+    /// of the instruction, for the feeds following the shared settings.
+    pub async fn upd_price_with_shared_settings(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(self.get_aggregation_config_pubkey(), false),
+            AccountMeta::new_readonly(self.get_feature_gate_pubkey(), false),
+        ]);
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price`, passing the fee vault and the system program after the accounts of the
+    /// instruction to pay the `UpdateFee` of the feed.
+    pub async fn upd_price_with_update_fee(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        instruction.accounts.extend([
+            AccountMeta::new(self.get_fee_vault_pubkey(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]);
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Update price of a component price account (using the upd_price instruction).
+    pub async fn upd_price(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<(), BanksClientError> {
+        self.upd_price_batch(
+            publisher,
+            &HashMap::from_iter(once(("".to_string(), price_account))),
+            &HashMap::from_iter(once(("".to_string(), quote))),
+        )
+        .await
+    }
+
+    /// Update price in multiple price account atomically (using the upd_price instruction)
+    pub async fn upd_price_batch(
+        &mut self,
+        publisher: &Keypair,
+        price_accounts: &HashMap<String, Pubkey>,
+        quotes: &HashMap<String, Quote>,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let instructions: Vec<Instruction> = price_accounts
+            .iter()
+            .map(|(key, price_account)| {
+                self.upd_price_instruction(publisher, *price_account, &quotes[key], slot)
+            })
+            .collect();
+
+        self.process_ixs(&instructions, &vec![publisher], publisher)
+            .await
+    }
+
+    /// Update price of a component price account (using the upd_price instruction), returning the
+    /// number of compute units consumed by the transaction.
+    pub async fn upd_price_compute_units(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<u64, BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+
+        self.process_ixs_with_compute_units(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price_compute_units`, passing every optional account of the instruction: the
+    /// sanity program and the status observer of the simulator, the aggregation config, the
+    /// feature gate, the accumulator emitter of the simulator, the fee vault and the slot hashes.
+    pub async fn upd_price_with_all_accounts_compute_units(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<u64, BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        let emitter = self.accumulator_emitter_id;
+        let (oracle_auth_pda, _) = Pubkey::find_program_address(
+            &[UPD_PRICE_WRITE_SEED.as_bytes(), &emitter.to_bytes()],
+            &self.program_id,
+        );
+        instruction.accounts.extend([
+            AccountMeta::new_readonly(self.sanity_program_id, false),
+            AccountMeta::new_readonly(self.status_observer_id, false),
+            AccountMeta::new_readonly(self.get_aggregation_config_pubkey(), false),
+            AccountMeta::new_readonly(self.get_feature_gate_pubkey(), false),
+            AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+            AccountMeta::new_readonly(emitter, false),
+            AccountMeta::new_readonly(oracle_auth_pda, false),
+            AccountMeta::new(self.get_fee_vault_pubkey(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        ]);
+
+        self.process_ixs_with_compute_units(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    /// Same as `upd_price_compute_units`, encoding the quote relative to the latest quote of
+    /// `publisher` (using the upd_price_compact instruction).
+    pub async fn upd_price_compact_compute_units(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: Quote,
+    ) -> Result<u64, BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let price_data = self
+            .get_account_data_as::<PriceAccount>(price_account)
+            .await
+            .unwrap();
+        let latest = price_data.comp_[..price_data.num_ as usize]
+            .iter()
+            .find(|component| component.pub_ == publisher.pubkey())
+            .unwrap()
+            .latest_;
+        let mut instruction = self.upd_price_instruction(publisher, price_account, &quote, slot);
+        let cmd = UpdPriceCompactArgs::encode(
+            &pod_read_unaligned::<UpdPriceArgs>(&instruction.data),
+            &latest,
+        )
+        .unwrap();
+        instruction.data = bytes_of(&cmd).to_vec();
+
+        self.process_ixs_with_compute_units(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    fn upd_price_instruction(
+        &self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        quote: &Quote,
+        slot: u64,
+    ) -> Instruction {
+        let cmd = UpdPriceArgs {
+            header:          OracleCommand::UpdPrice.into(),
+            status:          quote.status,
+            venue:           0,
+            unused_:         [0; 3],
+            price:           quote.price,
+            confidence:      quote.confidence,
+            publishing_slot: slot,
+            sequence:        0,
+        };
+        Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(publisher.pubkey(), true),
+                AccountMeta::new(price_account, false),
+                AccountMeta::new(Clock::id(), false),
+            ],
+        )
+    }
+
+    /// Mark the quote of `publisher` as halted or in auction in the current slot (using the
+    /// upd_publisher_status instruction).
+    pub async fn upd_publisher_status(
+        &mut self,
+        publisher: &Keypair,
+        price_account: Pubkey,
+        status: u32,
+    ) -> Result<(), BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let cmd = UpdPublisherStatusArgs {
+            header: OracleCommand::UpdPublisherStatus.into(),
+            status,
+            unused_: 0,
+            publishing_slot: slot,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(publisher.pubkey(), true),
+                AccountMeta::new(price_account, false),
+                AccountMeta::new_readonly(Clock::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![publisher], publisher)
+            .await
+    }
+
+    // /// Delete a price account from an existing product account (using the del_price instruction).
+    pub async fn del_price(
+        &mut self,
+        product_keypair: &Keypair,
+        price_keypair: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        self.del_price_with_accounts(product_keypair, price_keypair, vec![])
+            .await
+    }
+
+    /// Same as `del_price`, clearing the entry of the price account in the feed registry.
+    pub async fn del_price_with_registry(
+        &mut self,
+        product_keypair: &Keypair,
+        price_keypair: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let registry_account = AccountMeta::new(self.get_feed_registry_pubkey(), false);
+        self.del_price_with_accounts(product_keypair, price_keypair, vec![registry_account])
+            .await
+    }
+
+    /// Same as `del_price`, sending the tombstone of the price account to `message_buffer`.
+    pub async fn del_price_with_message_buffer(
+        &mut self,
+        product_keypair: &Keypair,
+        price_keypair: &Keypair,
+        message_buffer: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let accounts = self.message_buffer_accounts(message_buffer).to_vec();
+        self.del_price_with_accounts(product_keypair, price_keypair, accounts)
+            .await
+    }
+
+    async fn del_price_with_accounts(
+        &mut self,
+        product_keypair: &Keypair,
+        price_keypair: &Keypair,
+        extra_accounts: Vec<AccountMeta>,
+    ) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::DelPrice.into();
+        let mut accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(product_keypair.pubkey(), true),
+            AccountMeta::new(price_keypair.pubkey(), true),
+            AccountMeta::new(self.get_permissions_pubkey(), false),
+        ];
+        accounts.extend(extra_accounts);
+        let instruction = Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![product_keypair, price_keypair],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Update permissions (using the upd_permissions intruction) and return the pubkey of the
+    /// permissions account
+    pub async fn upd_permissions(
+        &mut self,
+        cmd_args: UpdPermissionsArgs,
+        payer: &Keypair,
+    ) -> Result<Pubkey, BanksClientError> {
+        let permissions_pubkey = self.get_permissions_pubkey();
+
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd_args),
+            vec![
+                AccountMeta::new(payer.pubkey(), true),
+                AccountMeta::new_readonly(self.programdata_id, false),
+                AccountMeta::new(permissions_pubkey, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(Clock::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], payer)
+            .await
+            .map(|_| permissions_pubkey)
+    }
+
+    /// Set the delay of the timelock on permission changes (using the set_permissions_timelock
+    /// instruction), signed by the upgrade authority.
+    pub async fn set_permissions_timelock(&mut self, delay: u64) -> Result<(), BanksClientError> {
+        let cmd = SetPermissionsTimelockArgs {
+            header: OracleCommand::SetPermissionsTimelock.into(),
+            delay,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.upgrade_authority.pubkey(), true),
+                AccountMeta::new_readonly(self.programdata_id, false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(Clock::id(), false),
+            ],
+        );
+
+        let upgrade_authority = copy_keypair(&self.upgrade_authority);
+        self.process_ixs(&[instruction], &vec![], &upgrade_authority)
+            .await
+    }
+
+    /// Set the accumulator emitter of the program (using the set_accumulator_emitter
+    /// instruction), signed by the upgrade authority.
+    pub async fn set_accumulator_emitter(
+        &mut self,
+        program_id: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let cmd = SetAccumulatorEmitterArgs {
+            header: OracleCommand::SetAccumulatorEmitter.into(),
+            program_id,
+        };
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.upgrade_authority.pubkey(), true),
+                AccountMeta::new_readonly(self.programdata_id, false),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        let upgrade_authority = copy_keypair(&self.upgrade_authority);
+        self.process_ixs(&[instruction], &vec![], &upgrade_authority)
+            .await
+    }
+
+    /// Apply the pending permission change (using the apply_permissions instruction).
+    pub async fn apply_permissions(&mut self) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::ApplyPermissions.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(self.genesis_keypair.pubkey(), true),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(Clock::id(), false),
+                AccountMeta::new_readonly(self.programdata_id, false),
+            ],
+        );
+
+        let payer = copy_keypair(&self.genesis_keypair);
+        self.process_ixs(&[instruction], &vec![], &payer).await
+    }
+
+    /// Cancel the pending permission change (using the cancel_permissions instruction).
+    pub async fn cancel_permissions(
+        &mut self,
+        authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::CancelPermissions.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new(self.get_permissions_pubkey(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority).await
+    }
+
+    /// Overwrite the upgrade authority in the programdata account of the program, as the
+    /// upgradeable loader would. `None` makes the program immutable.
+    pub async fn set_upgrade_authority(&mut self, upgrade_authority: Option<Pubkey>) {
+        let mut account = self.get_account(self.programdata_id).await.unwrap();
+        let header = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot:                      1,
+            upgrade_authority_address: upgrade_authority,
+        })
+        .unwrap();
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        account.data[..metadata_len].fill(0);
+        account.data[..header.len()].copy_from_slice(&header);
+        self.context
+            .set_account(&self.programdata_id, &account.into());
+    }
+
+    /// Snapshot a price account (using the snapshot_price_account instruction) in the current
+    /// slot, returning the pubkey of the snapshot account.
+    pub async fn snapshot_price_account(
+        &mut self,
+        price_account: Pubkey,
+        authority: &Keypair,
+    ) -> Result<Pubkey, BanksClientError> {
+        let slot = self.context.banks_client.get_sysvar::<Clock>().await?.slot;
+        let (snapshot_pubkey, _bump) = Pubkey::find_program_address(
+            &[
+                SNAPSHOT_SEED.as_bytes(),
+                &price_account.to_bytes(),
+                &slot.to_le_bytes(),
+            ],
+            &self.program_id,
+        );
+
+        let cmd: CommandHeader = OracleCommand::SnapshotPriceAccount.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![
+                AccountMeta::new(authority.pubkey(), true),
+                AccountMeta::new_readonly(price_account, false),
+                AccountMeta::new(snapshot_pubkey, false),
+                AccountMeta::new_readonly(Clock::id(), false),
+                AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+        );
+
+        self.process_ixs(&[instruction], &vec![], authority)
+            .await
+            .map(|_| snapshot_pubkey)
+    }
+
+    pub async fn truncate_account(&mut self, key: Pubkey, size: usize) {
+        let mut account = self.get_account(key).await.unwrap();
+        account.data.truncate(size);
+        self.context.set_account(&key, &account.into());
+    }
+
+    pub async fn resize_mapping(
+        &mut self,
+        mapping_keypair: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::ResizeMapping.into();
+        let instruction = Instruction::new_with_bytes(
+            self.program_id,
+            bytes_of(&cmd),
+            vec![AccountMeta::new(mapping_keypair.pubkey(), false)],
+        );
+
+        self.process_ixs(
+            &[instruction],
+            &vec![],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Grow the feed registry by one step, paid by the genesis keypair.
+    pub async fn resize_feed_registry(&mut self) -> Result<(), BanksClientError> {
+        let cmd: CommandHeader = OracleCommand::ResizeFeedRegistry.into();
+        let accounts = vec![
+            AccountMeta::new(self.genesis_keypair.pubkey(), true),
+            AccountMeta::new(self.get_feed_registry_pubkey(), false),
+            AccountMeta::new_readonly(self.get_permissions_pubkey(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ];
+        let instruction = Instruction::new_with_bytes(self.program_id, bytes_of(&cmd), accounts);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    /// Overwrite the last feed index handed out, e.g. to mimic a cluster that created feeds
+    /// before the feed registry existed.
+    pub async fn set_last_feed_index(&mut self, feed_index: u32) {
+        let key = self.get_permissions_pubkey();
+        let mut account = self.get_account(key).await.unwrap();
+        let start = size_of::<PermissionAccount>();
+        account.data[start..start + size_of::<u32>()].copy_from_slice(&feed_index.to_le_bytes());
+        self.context.set_account(&key, &account.into());
+    }
+
+    /// Overwrite the feed index of a price account, e.g. to mimic a feed created before feed
+    /// indexes were handed out.
+    pub async fn set_feed_index(&mut self, price_account: Pubkey, feed_index: u32) {
+        let mut account = self.get_account(price_account).await.unwrap();
+        load_mut::<PriceAccount>(&mut account.data)
+            .unwrap()
+            .feed_index = feed_index;
+        self.context.set_account(&price_account, &account.into());
+    }
+
+    /// Apply `migration` to `price_account` with the instructions that `pyth-migrate` plans from
+    /// its current data, signed by the master authority.
+    pub async fn migrate(
+        &mut self,
+        migration: Migration,
+        price_account: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let data = self.get_account(price_account).await.unwrap().data;
+        let instructions = migration.instructions(
+            &self.program_id,
+            &self.genesis_keypair.pubkey(),
+            &price_account,
+            &data,
+        );
+
+        self.process_ixs(&instructions, &vec![], &copy_keypair(&self.genesis_keypair))
+            .await
+    }
+
+    /// Get the account at `key`. Returns `None` if no such account exists.
+    pub async fn get_account(&mut self, key: Pubkey) -> Option<Account> {
+        self.context.banks_client.get_account(key).await.unwrap()
+    }
+
+    /// Get the content of an account as a value of type `T`. This function returns a copy of the
+    /// account data -- you cannot mutate the result to mutate the on-chain account data.
+    /// Returns None if the account does not exist. Panics if the account data cannot be read as a
+    /// `T`.
+    pub async fn get_account_data_as<T: Pod>(&mut self, key: Pubkey) -> Option<T> {
+        self.get_account(key)
+            .await
+            .map(|x| *load::<T>(&x.data).unwrap())
+    }
+
+    pub fn is_owned_by_oracle(&self, account: &Account) -> bool {
+        account.owner == self.program_id
+    }
+
+    pub async fn airdrop(&mut self, to: &Pubkey, lamports: u64) -> Result<(), BanksClientError> {
+        let instruction =
+            system_instruction::transfer(&self.genesis_keypair.pubkey(), to, lamports);
+
+        self.process_ixs(
+            &[instruction],
+            &vec![],
+            &copy_keypair(&self.genesis_keypair),
+        )
+        .await
+    }
+
+    pub fn get_permissions_pubkey(&self) -> Pubkey {
+        let (permissions_pubkey, __bump) =
+            Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], &self.program_id);
+        permissions_pubkey
+    }
+
+    /// Address of the community account of `creator` under `seed`.
+    pub fn get_community_pubkey(&self, creator: &Pubkey, seed: &[u8; 32]) -> Pubkey {
+        let (community_pubkey, _) = Pubkey::find_program_address(
+            &[COMMUNITY_SEED.as_bytes(), &creator.to_bytes(), seed],
+            &self.program_id,
+        );
+        community_pubkey
+    }
+
+    pub fn get_product_pda(&self, symbol: &str) -> Pubkey {
+        let (product_pubkey, _bump) =
+            find_product_pda(&self.program_id, symbol.as_bytes()).unwrap();
+        product_pubkey
+    }
+
+    pub fn get_price_pda(&self, product: &Pubkey, feed_kind: FeedKind) -> Pubkey {
+        let (price_pubkey, _bump) = find_price_pda(&self.program_id, product, feed_kind);
+        price_pubkey
+    }
+
+    pub fn get_feed_registry_pubkey(&self) -> Pubkey {
+        let (registry_pubkey, _bump) =
+            Pubkey::find_program_address(&[FEED_REGISTRY_SEED.as_bytes()], &self.program_id);
+        registry_pubkey
+    }
+
+    /// Trailing accounts of the instructions that maintain the feed registry.
+    pub fn get_publisher_registry_pubkey(&self) -> Pubkey {
+        let (registry_pubkey, _) =
+            Pubkey::find_program_address(&[PUBLISHER_REGISTRY_SEED.as_bytes()], &self.program_id);
+        registry_pubkey
+    }
+
+    pub fn get_publisher_linkage_pubkey(&self) -> Pubkey {
+        let (linkage_pubkey, _) =
+            Pubkey::find_program_address(&[PUBLISHER_LINKAGE_SEED.as_bytes()], &self.program_id);
+        linkage_pubkey
+    }
+
+    pub fn get_feature_gate_pubkey(&self) -> Pubkey {
+        let (feature_gate_pubkey, _) =
+            Pubkey::find_program_address(&[FEATURE_GATE_SEED.as_bytes()], &self.program_id);
+        feature_gate_pubkey
+    }
+
+    pub fn get_audit_log_pubkey(&self) -> Pubkey {
+        let (audit_log_pubkey, _) =
+            Pubkey::find_program_address(&[AUDIT_LOG_SEED.as_bytes()], &self.program_id);
+        audit_log_pubkey
+    }
+
+    pub fn get_aggregation_config_pubkey(&self) -> Pubkey {
+        let (aggregation_config_pubkey, _) =
+            Pubkey::find_program_address(&[AGGREGATION_CONFIG_SEED.as_bytes()], &self.program_id);
+        aggregation_config_pubkey
+    }
+
+    pub fn get_fee_vault_pubkey(&self) -> Pubkey {
+        let (fee_vault_pubkey, _) =
+            Pubkey::find_program_address(&[FEE_VAULT_SEED.as_bytes()], &self.program_id);
+        fee_vault_pubkey
+    }
+
+    pub fn get_rewards_ledger_pubkey(&self, publisher: &Pubkey) -> Pubkey {
+        let (rewards_ledger_pubkey, _) = Pubkey::find_program_address(
+            &[REWARDS_LEDGER_SEED.as_bytes(), &publisher.to_bytes()],
+            &self.program_id,
+        );
+        rewards_ledger_pubkey
+    }
+
+    fn get_feed_registry_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.get_feed_registry_pubkey(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
     }
 
     /// Setup 3 product accounts with 1 price account each and add a publisher to all of them.
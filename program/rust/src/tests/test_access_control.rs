@@ -0,0 +1,212 @@
+use {
+    crate::{
+        accounts::{
+            get_access_control,
+            AccessControlAccount,
+            MAX_ACCESS_CONTROL_READERS,
+        },
+        c_oracle_header::PC_STATUS_UNKNOWN,
+        error::OracleError,
+        instruction::{
+            AssertPriceConditionArgs,
+            OracleCommand,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+fn any_price() -> AssertPriceConditionArgs {
+    AssertPriceConditionArgs {
+        header:             OracleCommand::AssertPriceCondition.into(),
+        min_price:          i64::MIN,
+        max_price:          i64::MAX,
+        max_conf_ratio_bps: u64::MAX,
+        max_age:            u64::MAX,
+        required_status:    PC_STATUS_UNKNOWN,
+        unused_:            0,
+    }
+}
+
+#[tokio::test]
+async fn test_access_control() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    let access_control_keypair = sim.create_access_control().await;
+    let access_control = access_control_keypair.pubkey();
+    let reader = Keypair::new();
+    let other_reader = Keypair::new();
+
+    // Only the master authority maintains the readers
+    let attacker = Keypair::new();
+    sim.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.upd_access_control(&access_control_keypair, reader.pubkey(), true, &attacker)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.upd_access_control(&access_control_keypair, reader.pubkey(), true, &authority)
+        .await
+        .unwrap();
+    // Adding a listed reader does nothing
+    sim.upd_access_control(&access_control_keypair, reader.pubkey(), true, &authority)
+        .await
+        .unwrap();
+    let access_control_data = sim
+        .get_account_data_as::<AccessControlAccount>(access_control)
+        .await
+        .unwrap();
+    assert_eq!(access_control_data.readers(), &[reader.pubkey()]);
+
+    // Feeds are open until they reference an access control account
+    sim.assert_price_condition(price, any_price(), None)
+        .await
+        .unwrap();
+    sim.set_access_control(&price_keypair, Some(access_control))
+        .await
+        .unwrap();
+    let price_data = sim.get_account(price).await.unwrap().data;
+    assert_eq!(get_access_control(&price_data), Ok(Some(access_control)));
+
+    assert_eq!(
+        sim.assert_price_condition(price, any_price(), None)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::ReaderNotAllowed.into()
+    );
+    assert_eq!(
+        sim.assert_price_condition(price, any_price(), Some((&other_reader, access_control)))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::ReaderNotAllowed.into()
+    );
+    // The reader must come with the access control account of the feed
+    let other_access_control_keypair = sim.create_access_control().await;
+    sim.upd_access_control(
+        &other_access_control_keypair,
+        reader.pubkey(),
+        true,
+        &authority,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        sim.assert_price_condition(
+            price,
+            any_price(),
+            Some((&reader, other_access_control_keypair.pubkey()))
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::ReaderNotAllowed.into()
+    );
+    sim.assert_price_condition(price, any_price(), Some((&reader, access_control)))
+        .await
+        .unwrap();
+
+    // Removed readers lose access
+    sim.upd_access_control(
+        &access_control_keypair,
+        other_reader.pubkey(),
+        true,
+        &authority,
+    )
+    .await
+    .unwrap();
+    sim.upd_access_control(&access_control_keypair, reader.pubkey(), false, &authority)
+        .await
+        .unwrap();
+    let access_control_data = sim
+        .get_account_data_as::<AccessControlAccount>(access_control)
+        .await
+        .unwrap();
+    assert_eq!(access_control_data.readers(), &[other_reader.pubkey()]);
+    assert_eq!(
+        sim.assert_price_condition(price, any_price(), Some((&reader, access_control)))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::ReaderNotAllowed.into()
+    );
+    sim.assert_price_condition(price, any_price(), Some((&other_reader, access_control)))
+        .await
+        .unwrap();
+
+    // Lifting the restriction
+    sim.set_access_control(&price_keypair, None).await.unwrap();
+    let price_data = sim.get_account(price).await.unwrap().data;
+    assert_eq!(get_access_control(&price_data), Ok(None));
+    sim.assert_price_condition(price, any_price(), None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_access_control_full() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let access_control_keypair = sim.create_access_control().await;
+
+    for _ in 0..MAX_ACCESS_CONTROL_READERS {
+        sim.upd_access_control(
+            &access_control_keypair,
+            Pubkey::new_unique(),
+            true,
+            &authority,
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        sim.upd_access_control(
+            &access_control_keypair,
+            Pubkey::new_unique(),
+            true,
+            &authority
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+    // Removing an unlisted reader does nothing
+    sim.upd_access_control(
+        &access_control_keypair,
+        Pubkey::new_unique(),
+        false,
+        &authority,
+    )
+    .await
+    .unwrap();
+    let access_control_data = sim
+        .get_account_data_as::<AccessControlAccount>(access_control_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        access_control_data.readers().len(),
+        MAX_ACCESS_CONTROL_READERS
+    );
+}
@@ -0,0 +1,129 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        migration::Migration,
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::path::Path,
+};
+
+#[test]
+fn test_account_fixture_round_trip() {
+    let program_id = Pubkey::new_unique();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    {
+        let price_account = price_setup.as_account_info();
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.exponent = -5;
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = Pubkey::new_unique();
+        price_data.comp_[0].latest_.price_ = 42;
+    }
+
+    let fixture = price_setup.to_fixture("Round trip");
+    assert_eq!(fixture.size, PriceAccount::MINIMUM_SIZE);
+    // Trailing zero bytes aren't stored
+    assert!(fixture.data.len() < 2 * fixture.size);
+    let mut restored_setup = AccountSetup::from_fixture(&fixture);
+    assert_same_account(
+        &price_setup.as_account_info(),
+        &restored_setup.as_account_info(),
+    );
+
+    let path = std::env::temp_dir().join(format!("{}.json", Pubkey::new_unique()));
+    price_setup.save_fixture(&path, "Round trip");
+    let mut loaded_setup = AccountSetup::load_fixture(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_same_account(
+        &price_setup.as_account_info(),
+        &loaded_setup.as_account_info(),
+    );
+}
+
+/// Price accounts written before `ACCUMULATOR_V2` existed can still be loaded and migrated,
+/// without touching anything but their flags.
+#[test]
+fn test_price_account_without_accumulator_v2() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test_data/accounts/price_without_accumulator_v2.json");
+    let mut price_setup = AccountSetup::load_fixture(&path);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    let program_id = *price_account.owner;
+    let original_data = price_account.try_borrow_data().unwrap().to_vec();
+
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.exponent, -8);
+        assert_eq!(price_data.min_pub_, 1);
+        assert_eq!(price_data.flags, PriceAccountFlags::empty());
+        assert_eq!(price_data.num_, 1);
+        assert_eq!(price_data.comp_[0].pub_, Pubkey::new_from_array([1; 32]));
+        assert_eq!(price_data.comp_[0].latest_.price_, 100_000_000);
+        assert_eq!(price_data.comp_[0].latest_.conf_, 5);
+        assert_eq!(price_data.comp_[0].latest_.status_, PC_STATUS_TRADING);
+        assert_eq!(price_data.comp_[0].latest_.pub_slot_, 100);
+        assert!(Migration::EnableAccumulatorV2.is_needed(&price_data));
+    }
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let mut permissions_account = permissions_setup.as_account_info();
+    permissions_account.is_signer = false;
+    permissions_account.is_writable = false;
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let instruction = Migration::EnableAccumulatorV2.instruction(
+        &program_id,
+        funding_account.key,
+        price_account.key,
+    );
+    process_instruction(
+        &program_id,
+        &[funding_account, price_account.clone(), permissions_account],
+        &instruction.data,
+    )
+    .unwrap();
+
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .flags,
+        PriceAccountFlags::ACCUMULATOR_V2
+    );
+    let data = price_account.try_borrow_data().unwrap();
+    let changed_bytes = data
+        .iter()
+        .zip(&original_data)
+        .filter(|(byte, original)| byte != original)
+        .count();
+    assert_eq!(changed_bytes, 1);
+}
+
+fn assert_same_account(expected: &AccountInfo, actual: &AccountInfo) {
+    assert_eq!(actual.key, expected.key);
+    assert_eq!(actual.owner, expected.owner);
+    assert_eq!(actual.lamports(), expected.lamports());
+    assert_eq!(
+        *actual.try_borrow_data().unwrap(),
+        *expected.try_borrow_data().unwrap()
+    );
+}
@@ -0,0 +1,105 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        tests::pyth_simulator::{
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[tokio::test]
+async fn test_accumulator_emitter() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    let emitter = sim.accumulator_emitter_id;
+
+    // Publishers can list the emitter before governance sets it, nothing is pushed until then
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(-100), emitter)
+        .await
+        .unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(100), emitter)
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, -100);
+
+    sim.set_accumulator_emitter(emitter).await.unwrap();
+    let permissions_pubkey = sim.get_permissions_pubkey();
+    assert_eq!(
+        sim.get_account(permissions_pubkey)
+            .await
+            .unwrap()
+            .data
+            .len(),
+        PermissionAccount::EMITTER_ACCOUNT_SPACE
+    );
+
+    // Every new aggregate is pushed, the emitter of the simulator rejects the negative ones
+    sim.warp_to_slot(12).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(-100), emitter)
+        .await
+        .unwrap();
+    sim.warp_to_slot(13).await.unwrap();
+    assert!(sim
+        .upd_price_with_emitter(&publisher, price, quote(100), emitter)
+        .await
+        .is_err());
+
+    // Updates that don't list the emitter aren't pushed
+    sim.upd_price(&publisher, price, quote(100)).await.unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.price_, -100);
+
+    // Nor can they list another program in its place
+    sim.warp_to_slot(14).await.unwrap();
+    assert!(sim
+        .upd_price_with_emitter(&publisher, price, quote(-100), Pubkey::new_unique())
+        .await
+        .is_err());
+
+    // Unsetting the emitter stops pushing
+    sim.set_accumulator_emitter(Pubkey::default())
+        .await
+        .unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(-100), emitter)
+        .await
+        .unwrap();
+    sim.warp_to_slot(15).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(100), emitter)
+        .await
+        .unwrap();
+}
+
+fn quote(price: i64) -> Quote {
+    Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    }
+}
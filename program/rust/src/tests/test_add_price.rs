@@ -23,10 +23,7 @@ use {
         tests::test_utils::AccountSetup,
     },
     bytemuck::bytes_of,
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
 };
 
 #[test]
@@ -182,7 +179,29 @@ fn test_add_price() {
             ],
             instruction_data_add_price
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidPriceType.into())
+    );
+
+    // Unknown feed kind
+    hdr_add_price = AddPriceArgs {
+        header:     OracleCommand::AddPrice.into(),
+        exponent:   6,
+        price_type: 5,
+    };
+    instruction_data_add_price = bytes_of::<AddPriceArgs>(&hdr_add_price);
+
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                product_account.clone(),
+                price_account.clone(),
+                permissions_account.clone(),
+            ],
+            instruction_data_add_price
+        ),
+        Err(OracleError::InvalidPriceType.into())
     );
 
     // Fresh product account
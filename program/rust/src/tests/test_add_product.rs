@@ -31,13 +31,20 @@ use {
     solana_program::{
         account_info::AccountInfo,
         clock::Epoch,
-        program_error::ProgramError,
         pubkey::Pubkey,
         rent::Rent,
     },
     std::mem::size_of,
 };
 
+const METADATA: [&str; 6] = [
+    "symbol",
+    "Crypto.BTC/USD",
+    "asset_type",
+    "Crypto",
+    "quote_currency",
+    "USD",
+];
 
 #[test]
 fn test_add_product() {
@@ -99,7 +106,7 @@ fn test_add_product() {
     }
     assert!(account_has_key_values(&product_account, &[]).unwrap());
 
-    size = populate_instruction(&mut instruction_data, &["foo", "bar"]);
+    size = populate_instruction(&mut instruction_data, &METADATA);
     // Add product with metadata
     assert!(process_instruction(
         &program_id,
@@ -122,7 +129,7 @@ fn test_add_product() {
         );
         assert!(mapping_data.products_list[1] == *product_account_2.key);
     }
-    assert!(account_has_key_values(&product_account_2, &["foo", "bar"]).unwrap());
+    assert!(account_has_key_values(&product_account_2, &METADATA).unwrap());
 
     // invalid account size
     let product_key_3 = Pubkey::new_unique();
@@ -159,7 +166,16 @@ fn test_add_product() {
 
     for i in 0..PC_MAP_TABLE_SIZE {
         clear_account(&product_account).unwrap();
-        size = populate_instruction(&mut instruction_data, &["symbol", &i.to_string()[..]]);
+        let symbol = i.to_string();
+        let metadata = [
+            "symbol",
+            &symbol[..],
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ];
+        size = populate_instruction(&mut instruction_data, &metadata);
 
         assert!(process_instruction(
             &program_id,
@@ -178,7 +194,7 @@ fn test_add_product() {
             MappingAccount::INITIAL_SIZE + (i + 1) * 32
         );
         assert_eq!(mapping_data.number_of_products, i + 1);
-        assert!(account_has_key_values(&product_account, &["symbol", &i.to_string()[..]]).unwrap());
+        assert!(account_has_key_values(&product_account, &metadata).unwrap());
     }
 
     clear_account(&product_account).unwrap();
@@ -194,13 +210,122 @@ fn test_add_product() {
             ],
             &instruction_data[..size]
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::MappingAccountFull.into())
     );
 
     let mapping_data = load_checked::<MappingAccount>(&mapping_account, PC_VERSION).unwrap();
     assert_eq!(mapping_data.number_of_products, PC_MAP_TABLE_SIZE);
 }
 
+#[test]
+fn test_add_product_invalid_metadata() {
+    let mut instruction_data = [0u8; PC_PROD_ACC_SIZE as usize];
+
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut mapping_setup = AccountSetup::new::<MappingAccount>(&program_id);
+    let mapping_account = mapping_setup.as_account_info();
+    MappingAccount::initialize(&mapping_account, PC_VERSION).unwrap();
+
+    let mut product_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let product_account = product_setup.as_account_info();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let long_key = "k".repeat(33);
+    let long_value = "v".repeat(129);
+    let cases: [(&[&str], OracleError); 4] = [
+        (
+            &["symbol", "Crypto.BTC/USD", "asset_type", "Crypto"],
+            OracleError::MissingProductMetadataKey,
+        ),
+        (
+            &[
+                "symbol",
+                "Crypto.BTC/USD",
+                "asset_type",
+                "Crypto",
+                "quote_currency",
+                "USD",
+                "symbol",
+                "Crypto.ETH/USD",
+            ],
+            OracleError::DuplicateProductMetadataKey,
+        ),
+        (
+            &[
+                "symbol",
+                "Crypto.BTC/USD",
+                "asset_type",
+                "Crypto",
+                "quote_currency",
+                "USD",
+                long_key.as_str(),
+                "value",
+            ],
+            OracleError::InvalidProductMetadataString,
+        ),
+        (
+            &[
+                "symbol",
+                long_value.as_str(),
+                "asset_type",
+                "Crypto",
+                "quote_currency",
+                "USD",
+            ],
+            OracleError::InvalidProductMetadataString,
+        ),
+    ];
+
+    for (metadata, error) in cases {
+        let size = populate_instruction(&mut instruction_data, metadata);
+        assert_eq!(
+            process_instruction(
+                &program_id,
+                &[
+                    funding_account.clone(),
+                    mapping_account.clone(),
+                    product_account.clone(),
+                    permissions_account.clone()
+                ],
+                &instruction_data[..size]
+            ),
+            Err(error.into())
+        );
+    }
+
+    // Metadata that isn't UTF-8
+    let mut size = populate_instruction(&mut instruction_data, &METADATA);
+    instruction_data[size..size + 3].copy_from_slice(&[1, b'k', 1]);
+    instruction_data[size + 3] = 0xff;
+    size += 4;
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                mapping_account.clone(),
+                product_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction_data[..size]
+        ),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+
+    // Nothing was added
+    let mapping_data = load_checked::<MappingAccount>(&mapping_account, PC_VERSION).unwrap();
+    assert_eq!(mapping_data.number_of_products, 0);
+    assert!(load_checked::<ProductAccount>(&product_account, PC_VERSION).is_err());
+}
 
 // Create an add_product instruction that sets the product metadata to strings
 pub fn populate_instruction(instruction_data: &mut [u8], strings: &[&str]) -> usize {
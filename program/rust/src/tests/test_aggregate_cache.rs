@@ -0,0 +1,54 @@
+use crate::{
+    accounts::{
+        AggregateCache,
+        AggregationDelay,
+    },
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_aggregate_cache() {
+    let mut feed = FeedSimulator::new(2)
+        .with_extension::<AggregationDelay>()
+        .with_extension::<AggregateCache>();
+    feed.set_aggregation_delay(0).unwrap();
+    feed.set_aggregate_cache(true).unwrap();
+    assert_eq!(feed.extension::<AggregateCache>().enabled, 1);
+    let mut reference = feed
+        .new_sibling(2)
+        .with_extension::<AggregationDelay>()
+        .with_extension::<AggregateCache>();
+    reference.set_aggregation_delay(0).unwrap();
+
+    // The first update of the slot aggregates, the later ones only store their quotes
+    for feed in [&mut feed, &mut reference] {
+        feed.warp_to_slot(10);
+        feed.publish(0, 100, 1).unwrap();
+        feed.publish(1, 200, 1).unwrap();
+    }
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 100);
+    assert_eq!(price_data.agg_.pub_slot_, 10);
+    assert_eq!(price_data.num_qt_, 1);
+    assert_eq!(price_data.comp_[1].latest_.price_, 200);
+    assert_ne!(reference.price_data().agg_.price_, 100);
+
+    // The first update of the next slot covers the stored quotes
+    for feed in [&mut feed, &mut reference] {
+        feed.warp_to_slot(11);
+        feed.publish(0, 100, 1).unwrap();
+    }
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.pub_slot_, 11);
+    assert_eq!(price_data.num_qt_, 2);
+    assert_eq!(price_data.agg_.price_, reference.price_data().agg_.price_);
+    assert_eq!(price_data.prev_price_, 100);
+
+    // Without the cache every update aggregates again
+    feed.set_aggregate_cache(false).unwrap();
+    feed.publish(1, 300, 1).unwrap();
+    assert_ne!(
+        feed.price_data().agg_.price_,
+        reference.price_data().agg_.price_
+    );
+}
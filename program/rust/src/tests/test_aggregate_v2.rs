@@ -25,8 +25,8 @@ use {
             ENABLE_ACCUMULATOR_V2,
         },
         tests::test_utils::{
-            update_clock_slot,
             AccountSetup,
+            TestClock,
         },
     },
     bytemuck::bytes_of,
@@ -103,16 +103,15 @@ fn update_price(accounts: &mut Accounts, price: i64, conf: u64, slot: u64) {
     cmd.publishing_slot = slot;
     cmd.unused_ = 0;
 
-    let mut clock = accounts.clock_account.as_account_info();
-    clock.is_signer = false;
-    clock.is_writable = false;
+    let mut clock = TestClock::new(accounts.clock_account.as_account_info());
+    clock.warp_to_slot(slot);
 
     process_instruction(
         &accounts.program_id,
         &[
             accounts.publisher_account.as_account_info(),
             accounts.price_account.as_account_info(),
-            clock,
+            clock.account(),
         ],
         instruction_data,
     )
@@ -128,7 +127,6 @@ fn test_aggregate_v2_toggle() {
 
     // Update the price, no aggregation will happen on the first slot.
     {
-        update_clock_slot(&mut accounts.clock_account.as_account_info(), 1);
         update_price(accounts, 42, 2, 1);
         let info = accounts.price_account.as_account_info();
         let price_data = load_checked::<PriceAccount>(&info, PC_VERSION).unwrap();
@@ -138,7 +136,6 @@ fn test_aggregate_v2_toggle() {
 
     // Update again, component is now TRADING so aggregation should trigger.
     {
-        update_clock_slot(&mut accounts.clock_account.as_account_info(), 2);
         update_price(accounts, 42, 2, 2);
         let info = accounts.price_account.as_account_info();
         let price_data = load_checked::<PriceAccount>(&info, PC_VERSION).unwrap();
@@ -152,7 +149,6 @@ fn test_aggregate_v2_toggle() {
     // Update again, with accumulator bit set, aggregation should not have
     // happened, as its now the validators job.
     {
-        update_clock_slot(&mut accounts.clock_account.as_account_info(), 3);
         update_price(accounts, 42, 2, 3);
         let info = accounts.price_account.as_account_info();
         let price_data = load_checked::<PriceAccount>(&info, PC_VERSION).unwrap();
@@ -164,7 +160,6 @@ fn test_aggregate_v2_toggle() {
 
     // Confirm disabling v2 Aggregation re-enables the aggregation flow.
     {
-        update_clock_slot(&mut accounts.clock_account.as_account_info(), 4);
         update_price(accounts, 42, 2, 4);
         let info = accounts.price_account.as_account_info();
         let price_data = load_checked::<PriceAccount>(&info, PC_VERSION).unwrap();
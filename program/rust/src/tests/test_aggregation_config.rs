@@ -0,0 +1,237 @@
+use {
+    crate::{
+        accounts::{
+            AggregationConfigAccount,
+            PriceAccount,
+            SharedSettings,
+        },
+        c_oracle_header::{
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PRICE_ACCOUNT_DEFAULT_MIN_PUB,
+        },
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+fn publish_all(feed: &mut FeedSimulator, slot: u64) {
+    feed.warp_to_slot(slot);
+    for publisher in 0..3 {
+        feed.publish(publisher, 100, 1).unwrap();
+    }
+}
+
+#[test]
+fn test_aggregation_config_quorum() {
+    let mut feed = FeedSimulator::new(3).with_min_pub(PRICE_ACCOUNT_DEFAULT_MIN_PUB);
+
+    // Feeds that don't follow the shared settings ignore the config, the default quorum of the
+    // feed isn't reached
+    feed.pass_aggregation_config(0, 0, 2);
+    publish_all(&mut feed, 10);
+    publish_all(&mut feed, 11);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+
+    // The feed aggregates with the shared quorum once it follows them, and keeps its own
+    let mut feed = feed.follow_shared_settings();
+    feed.pass_aggregation_config(0, 0, 2);
+    publish_all(&mut feed, 12);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.num_qt_, 3);
+    assert_eq!(price_data.min_pub_, PRICE_ACCOUNT_DEFAULT_MIN_PUB);
+
+    // Feeds that set their own quorum keep it
+    let mut feed = FeedSimulator::new(3)
+        .with_min_pub(5)
+        .follow_shared_settings();
+    feed.pass_aggregation_config(0, 0, 2);
+    publish_all(&mut feed, 10);
+    publish_all(&mut feed, 11);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+}
+
+#[test]
+fn test_aggregation_config_latency() {
+    let mut feed = FeedSimulator::new(3).with_min_pub(1);
+    publish_all(&mut feed, 10);
+    feed.warp_to_slot(12);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_TRADING);
+
+    // The quotes of two slots ago are too old with a shared latency of one slot
+    let mut feed = feed.follow_shared_settings();
+    feed.pass_aggregation_config(0, 1, 0);
+    publish_all(&mut feed, 20);
+    feed.warp_to_slot(22);
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.num_qt_, 0);
+    assert_eq!(price_data.max_latency_, 0);
+}
+
+#[test]
+fn test_aggregation_config_max_ci_divisor() {
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 10).unwrap();
+    assert_eq!(
+        feed.price_data().comp_[0].latest_.status_,
+        PC_STATUS_TRADING
+    );
+
+    // A confidence of 10% of the price is too wide with a divisor of 100
+    let mut feed = feed.follow_shared_settings();
+    feed.pass_aggregation_config(100, 0, 0);
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 10).unwrap();
+    assert_eq!(
+        feed.price_data().comp_[0].latest_.status_,
+        PC_STATUS_IGNORED
+    );
+    feed.warp_to_slot(12);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        feed.price_data().comp_[0].latest_.status_,
+        PC_STATUS_TRADING
+    );
+}
+
+#[test]
+fn test_shared_settings_required() {
+    let mut feed = FeedSimulator::new(3).with_extension::<SharedSettings>();
+    feed.set_shared_settings(true).unwrap();
+    assert!(SharedSettings::is_followed(feed.price_account_data()).unwrap());
+
+    // The publishers of a feed following the shared settings can't leave the config or the
+    // feature gate out
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::MissingSharedSettings.into())
+    );
+    feed.pass_aggregation_config(0, 0, 1);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::MissingSharedSettings.into())
+    );
+    feed.pass_feature_gate(&[]);
+    feed.publish(0, 100, 1).unwrap();
+
+    // The feed aggregates with the defaults of the program again once it stops following them
+    feed.set_shared_settings(false).unwrap();
+    assert!(!SharedSettings::is_followed(feed.price_account_data()).unwrap());
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+}
+
+#[tokio::test]
+async fn test_set_aggregation_config() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let aggregation_config = sim.get_aggregation_config_pubkey();
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    let quote = || Quote {
+        price:      100,
+        confidence: 1,
+        status:     PC_STATUS_TRADING,
+    };
+
+    // Only the master authority can set the defaults
+    let attacker = Keypair::new();
+    sim.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.set_aggregation_config(0, 0, 1, &attacker)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.set_aggregation_config(-1, 0, 1, &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    sim.set_aggregation_config(0, 0, 1, &authority)
+        .await
+        .unwrap();
+    let config_data = sim
+        .get_account_data_as::<AggregationConfigAccount>(aggregation_config)
+        .await
+        .unwrap();
+    assert_eq!(config_data.max_ci_divisor, 0);
+    assert_eq!(config_data.max_latency, 0);
+    assert_eq!(config_data.min_pub, 1);
+
+    // The feed follows the shared settings, its publisher can't leave them out
+    sim.set_feature_grace_period(0, &authority).await.unwrap();
+    sim.set_shared_settings(&price_keypair, true).await.unwrap();
+    sim.warp_to_slot(10).await.unwrap();
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::MissingSharedSettings.into()
+    );
+
+    // The single publisher reaches the shared quorum of the feed
+    sim.upd_price_with_shared_settings(&publisher, price, quote())
+        .await
+        .unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price_with_shared_settings(&publisher, price, quote())
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.min_pub_, PRICE_ACCOUNT_DEFAULT_MIN_PUB);
+
+    // Updating the defaults reaches every feed at once
+    sim.set_aggregation_config(0, 0, 2, &authority)
+        .await
+        .unwrap();
+    sim.warp_to_slot(12).await.unwrap();
+    sim.upd_price_with_shared_settings(&publisher, price, quote())
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+}
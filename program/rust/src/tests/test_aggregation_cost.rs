@@ -0,0 +1,134 @@
+use {
+    crate::{
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+        },
+        processor::DISABLE_ACCUMULATOR_V2,
+        tests::pyth_simulator::{
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+/// Publisher counts at which the cost of aggregation is measured.
+const PUBLISHER_COUNTS: [usize; 5] = [1, 8, 16, 32, 64];
+
+/// Upper bound on the compute units that each additional publisher adds to the update that
+/// triggers the aggregation. The bound has headroom over the measured cost, it is meant to catch
+/// regressions and to size the publisher cap against the compute budget, not to be tight.
+const MAX_CU_PER_PUBLISHER: u64 = 1_500;
+
+/// The cost per additional publisher may grow slowly with the publisher count (sorting is
+/// `n log n`), but should stay within this factor between `8..16` and `32..64` publishers.
+/// Quadratic behavior would multiply it by 4.
+const MAX_MARGINAL_COST_GROWTH: u64 = 2;
+
+/// Measure the compute units of the `upd_price` that aggregates `num_publishers` fresh quotes. The
+/// accumulator is disabled on the price account so that the aggregation runs in the program, and
+/// the minimum number of publishers is lowered so that every publisher count gets aggregated.
+async fn aggregation_cost(
+    sim: &mut PythSimulator,
+    publishers: &[Keypair],
+    num_publishers: usize,
+    slot: &mut u64,
+) -> u64 {
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -5).await.unwrap();
+    sim.add_publisher(&price_keypair, Pubkey::from(DISABLE_ACCUMULATOR_V2))
+        .await
+        .unwrap();
+    sim.set_min_pub(&price_keypair, 1).await.unwrap();
+    for publisher in &publishers[..num_publishers] {
+        sim.add_publisher(&price_keypair, publisher.pubkey())
+            .await
+            .unwrap();
+    }
+
+    *slot += 1;
+    sim.warp_to_slot(*slot).await.unwrap();
+    for (i, publisher) in publishers[..num_publishers].iter().enumerate() {
+        sim.upd_price(
+            publisher,
+            price_keypair.pubkey(),
+            Quote {
+                // Spread out and unsorted so that the sort does real work
+                price:      1_000_000 + ((i * 37) % 101) as i64,
+                confidence: 10 + (i % 7) as u64,
+                status:     PC_STATUS_TRADING,
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    *slot += 1;
+    sim.warp_to_slot(*slot).await.unwrap();
+    sim.upd_price_compute_units(
+        &publishers[0],
+        price_keypair.pubkey(),
+        Quote {
+            price:      1_000_000,
+            confidence: 10,
+            status:     PC_STATUS_TRADING,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// Compute units are deterministic, so the cost of aggregation is bounded as a function of the
+/// publisher count without flakiness: at most `MAX_CU_PER_PUBLISHER` per publisher, with a cost
+/// per publisher growing by at most `MAX_MARGINAL_COST_GROWTH`.
+#[tokio::test]
+async fn test_aggregation_cost() {
+    let mut sim = PythSimulator::new().await;
+    let max_publishers = *PUBLISHER_COUNTS.iter().max().unwrap();
+    assert!(max_publishers <= PC_NUM_COMP as usize);
+
+    let publishers: Vec<Keypair> = (0..max_publishers).map(|_| Keypair::new()).collect();
+    for publisher in &publishers {
+        sim.airdrop(&publisher.pubkey(), 100 * LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+
+    let mut slot = 1;
+    let mut costs = vec![];
+    for num_publishers in PUBLISHER_COUNTS {
+        let cost = aggregation_cost(&mut sim, &publishers, num_publishers, &mut slot).await;
+        costs.push((num_publishers as u64, cost));
+    }
+
+    let (_, base_cost) = costs[0];
+    for &(num_publishers, cost) in &costs[1..] {
+        assert!(
+            cost <= base_cost + MAX_CU_PER_PUBLISHER * (num_publishers - 1),
+            "{num_publishers} publishers cost {cost} CU, more than {MAX_CU_PER_PUBLISHER} CU per \
+             publisher over {base_cost} CU for a single publisher"
+        );
+    }
+
+    let marginal_costs: Vec<u64> = costs
+        .windows(2)
+        .map(|pair| {
+            let ((n_0, cost_0), (n_1, cost_1)) = (pair[0], pair[1]);
+            cost_1.saturating_sub(cost_0) / (n_1 - n_0)
+        })
+        .collect();
+    let (first, last) = (marginal_costs[1], marginal_costs[marginal_costs.len() - 1]);
+    assert!(
+        last <= MAX_MARGINAL_COST_GROWTH * first.max(1),
+        "cost per publisher grows from {first} CU to {last} CU, aggregation is superlinear"
+    );
+}
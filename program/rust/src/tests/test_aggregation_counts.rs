@@ -0,0 +1,136 @@
+use {
+    crate::{
+        accounts::{
+            AggregationCounts,
+            AggregationDelay,
+            AGGREGATION_COUNT_BUCKETS,
+        },
+        instruction::AggregationCountsSummary,
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::Zeroable,
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_aggregation_counts() {
+    // Feeds without the extension don't count aggregations
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        feed.get_aggregation_counts(),
+        AggregationCountsSummary {
+            slot:             11,
+            window_slots:     0,
+            aggregated_slots: 0,
+        }
+    );
+
+    let mut feed = FeedSimulator::new(1).with_extension::<AggregationCounts>();
+    assert_eq!(
+        feed.set_aggregation_count_window(17),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_aggregation_count_window(64).unwrap();
+    assert_eq!(feed.extension::<AggregationCounts>().bucket_slots, 4);
+    assert_eq!(feed.extension::<AggregationCounts>().window_slots(), 64);
+
+    // The first update aggregates without any recent quote
+    for slot in 100..108 {
+        feed.warp_to_slot(slot);
+        feed.publish(0, 100, 1).unwrap();
+    }
+    assert_eq!(
+        feed.get_aggregation_counts(),
+        AggregationCountsSummary {
+            slot:             107,
+            window_slots:     64,
+            aggregated_slots: 7,
+        }
+    );
+
+    // Every other slot
+    for slot in (108..140).step_by(2) {
+        feed.warp_to_slot(slot);
+        feed.publish(0, 100, 1).unwrap();
+    }
+    feed.warp_to_slot(139);
+    assert_eq!(
+        feed.get_aggregation_counts(),
+        AggregationCountsSummary {
+            slot:             139,
+            window_slots:     64,
+            aggregated_slots: 23,
+        }
+    );
+
+    // The window slides one bucket at a time, slots 101 to 103 fall out of it
+    feed.warp_to_slot(166);
+    assert_eq!(
+        feed.get_aggregation_counts(),
+        AggregationCountsSummary {
+            slot:             166,
+            window_slots:     63,
+            aggregated_slots: 20,
+        }
+    );
+
+    // Without updates the window empties
+    feed.warp_to_slot(300);
+    assert_eq!(feed.get_aggregation_counts().aggregated_slots, 0);
+
+    // Setting the same window keeps the counts, another one starts over
+    feed.set_aggregation_count_window(64).unwrap();
+    assert_eq!(feed.extension::<AggregationCounts>().last_slot, 138);
+    feed.set_aggregation_count_window(32).unwrap();
+    assert_eq!(feed.extension::<AggregationCounts>().last_slot, 0);
+    feed.set_aggregation_count_window(0).unwrap();
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(301);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.extension::<AggregationCounts>().last_slot, 0);
+    assert_eq!(feed.get_aggregation_counts().window_slots, 0);
+
+    // Aggregating again in the same slot counts once
+    let mut feed = FeedSimulator::new(2)
+        .with_extension::<AggregationDelay>()
+        .with_extension::<AggregationCounts>();
+    feed.set_aggregation_delay(0).unwrap();
+    feed.set_aggregation_count_window(16).unwrap();
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.publish(1, 101, 1).unwrap();
+    assert_eq!(feed.get_aggregation_counts().aggregated_slots, 1);
+}
+
+#[test]
+fn test_aggregation_counts_ring() {
+    let mut counts = AggregationCounts {
+        bucket_slots: 4,
+        ..AggregationCounts::zeroed()
+    };
+    assert_eq!(AGGREGATION_COUNT_BUCKETS, 16);
+
+    for slot in 101..108 {
+        counts.record_aggregation(slot);
+    }
+    counts.record_aggregation(107);
+    counts.record_aggregation(50);
+    assert_eq!(counts.last_slot, 107);
+    assert_eq!(counts.window_counts(107), (7, 64));
+
+    // Only the bucket of slots 104 to 107 is left in the window
+    assert_eq!(counts.window_counts(164), (4, 61));
+    // Counting in a later bucket clears the buckets in between
+    counts.record_aggregation(165);
+    assert_eq!(counts.window_counts(165), (5, 62));
+    assert_eq!(counts.window_counts(168), (1, 61));
+
+    // A count after a long gap starts from an empty window
+    counts.record_aggregation(1_000);
+    assert_eq!(counts.window_counts(1_000), (1, 61));
+    assert_eq!(counts.counts.iter().sum::<u64>(), 1);
+}
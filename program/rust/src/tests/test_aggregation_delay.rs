@@ -0,0 +1,82 @@
+use {
+    crate::{
+        accounts::AggregationDelay,
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_aggregation_delay() {
+    // By default the aggregate of a slot leaves out the quotes of that slot
+    let mut feed = FeedSimulator::new(2);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    feed.warp_to_slot(11);
+    feed.publish(0, 110, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.price_, 100);
+
+    let mut feed = FeedSimulator::new(2).with_extension::<AggregationDelay>();
+    feed.set_aggregation_delay(0).unwrap();
+    assert_eq!(feed.extension::<AggregationDelay>().slots, 0);
+
+    // Without delay, every update aggregates the quotes of the current slot
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 100);
+    assert_eq!(price_data.agg_.pub_slot_, 10);
+    assert_eq!(price_data.num_qt_, 1);
+
+    feed.publish(1, 200, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_qt_, 2);
+    assert_ne!(price_data.agg_.price_, 100);
+    let aggregate_10 = price_data.agg_.price_;
+    // The first aggregate of the slot doesn't become the previous aggregate
+    assert_eq!(price_data.prev_slot_, 0);
+
+    feed.warp_to_slot(11);
+    feed.publish(0, 110, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.prev_slot_, 10);
+    assert_eq!(price_data.prev_price_, aggregate_10);
+    let cumulative = price_data.price_cumulative;
+    let twap = price_data.twap_;
+
+    // Aggregating again in the same slot doesn't count the slot twice
+    feed.publish(1, 210, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.pub_slot_, 11);
+    assert_ne!(price_data.agg_.price_, 110);
+    assert_eq!(price_data.prev_slot_, 10);
+    assert_eq!(price_data.prev_price_, aggregate_10);
+    assert_eq!(price_data.valid_slot_, 10);
+    assert_eq!(price_data.price_cumulative.price, cumulative.price);
+    assert_eq!(price_data.price_cumulative.conf, cumulative.conf);
+    assert_eq!(price_data.twap_.val_, twap.val_);
+    let aggregate_11 = price_data.agg_.price_;
+
+    // Back to the default delay
+    feed.set_aggregation_delay(1).unwrap();
+    feed.warp_to_slot(12);
+    feed.publish(0, 120, 1).unwrap();
+    feed.publish(1, 220, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.pub_slot_, 12);
+    assert_eq!(price_data.agg_.price_, aggregate_11);
+    assert_eq!(price_data.prev_price_, aggregate_11);
+
+    // Older quotes can't be left out of the aggregate
+    assert_eq!(
+        feed.set_aggregation_delay(2),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(feed.extension::<AggregationDelay>().slots, 1);
+}
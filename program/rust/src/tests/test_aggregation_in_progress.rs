@@ -0,0 +1,128 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        instruction::{
+            AssertPriceConditionArgs,
+            OracleCommand,
+        },
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+                SANITY_MAX_PRICE,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+fn any_price() -> AssertPriceConditionArgs {
+    AssertPriceConditionArgs {
+        header:             OracleCommand::AssertPriceCondition.into(),
+        min_price:          i64::MIN,
+        max_price:          i64::MAX,
+        max_conf_ratio_bps: u64::MAX,
+        max_age:            u64::MAX,
+        required_status:    PC_STATUS_UNKNOWN,
+        unused_:            0,
+    }
+}
+
+#[test]
+fn test_aggregation_in_progress() {
+    let mut feed = FeedSimulator::new(2);
+    feed.post_updates(&[
+        &[Some((100, 1)), Some((102, 1))],
+        &[Some((110, 1)), Some((112, 1))],
+        &[Some((120, 1)), Some((122, 1))],
+    ]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    // The flag is cleared once the aggregation completes
+    assert!(!price_data
+        .flags
+        .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS));
+
+    // The runtime rolls back an aggregation interrupted by an error along with its transaction.
+    // The flag guarantees that the instructions interleaved with an unfinished aggregation
+    // don't build on it or read it, without relying on that.
+    let mut interrupted = price_data;
+    interrupted
+        .flags
+        .insert(PriceAccountFlags::AGGREGATION_IN_PROGRESS);
+    feed.set_flags(interrupted.flags);
+    feed.advance_slot();
+    assert_eq!(
+        feed.publish(0, 130, 1),
+        Err(OracleError::AggregationInProgress.into())
+    );
+    assert_eq!(
+        feed.assert_price_condition(any_price()),
+        Err(OracleError::AggregationInProgress.into())
+    );
+    let price_data_after = feed.price_data();
+    assert!(bytes_of(&price_data_after) == bytes_of(&interrupted));
+
+    feed.set_flags(price_data.flags);
+    feed.publish(0, 130, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 121);
+    assert_eq!(price_data.comp_[0].latest_.price_, 130);
+    assert!(!price_data
+        .flags
+        .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS));
+    feed.assert_price_condition(any_price()).unwrap();
+}
+
+/// The sanity program of the simulator only approves aggregates while the price account is
+/// flagged, so an approved aggregate shows the flag is set while the program is invoked in the
+/// middle of the aggregation, and cleared when the transaction completes.
+#[tokio::test]
+async fn test_aggregation_in_progress_during_sanity_check() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.set_sanity_program(&price_keypair, sim.sanity_program_id)
+        .await
+        .unwrap();
+
+    for slot in [10, 11] {
+        sim.warp_to_slot(slot).await.unwrap();
+        let quote = Quote {
+            price:      SANITY_MAX_PRICE,
+            confidence: 1,
+            status:     PC_STATUS_TRADING,
+        };
+        sim.upd_price_with_sanity_program(&publisher, price, quote)
+            .await
+            .unwrap();
+    }
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, SANITY_MAX_PRICE);
+    assert!(!price_data
+        .flags
+        .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS));
+}
@@ -0,0 +1,240 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+        },
+        c_oracle_header::{
+            PC_MAX_SEND_LATENCY,
+            PC_NUM_COMP,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        processor::{
+            c_upd_aggregate,
+            c_upd_aggregate_sorted,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    rand::{
+        rngs::StdRng,
+        seq::SliceRandom,
+        Rng,
+        SeedableRng,
+    },
+};
+
+/// Reimplementation of `upd_aggregate` of the C code (`upd_aggregate.h` and `price_model.c`).
+/// The tests below check that it matches the C code bit for bit, so that the C aggregation can be
+/// replaced with it or refactored against it.
+fn reference_upd_aggregate(price_data: &mut PriceAccount, slot: u64, timestamp: i64) -> bool {
+    if price_data.agg_.status_ == PC_STATUS_TRADING {
+        price_data.prev_slot_ = price_data.agg_.pub_slot_;
+        price_data.prev_price_ = price_data.agg_.price_;
+        price_data.prev_conf_ = price_data.agg_.conf_;
+        price_data.prev_timestamp_ = price_data.timestamp_;
+    }
+
+    price_data.valid_slot_ = price_data.agg_.pub_slot_;
+    price_data.agg_.pub_slot_ = slot;
+    price_data.timestamp_ = timestamp;
+
+    let allow_zero_ci = price_data.flags.contains(PriceAccountFlags::ALLOW_ZERO_CI);
+    let max_latency = match price_data.max_latency_ {
+        0 => i64::from(PC_MAX_SEND_LATENCY),
+        max_latency => i64::from(max_latency),
+    };
+    let mut quotes = vec![];
+    for component in price_data.comp_[..price_data.num_ as usize].iter_mut() {
+        component.agg_ = component.latest_;
+        let slot_diff = (slot as i64).wrapping_sub(component.agg_.pub_slot_ as i64);
+        let price = component.agg_.price_;
+        let conf = component.agg_.conf_ as i64;
+        if component.agg_.status_ == PC_STATUS_TRADING
+            && (allow_zero_ci || conf > 0)
+            && price.checked_sub(conf).is_some()
+            && price.checked_add(conf).is_some()
+            && slot_diff <= max_latency
+        {
+            quotes.extend([price - conf, price, price + conf]);
+        }
+    }
+
+    let num_valid = quotes.len() / 3;
+    price_data.num_qt_ = num_valid as u32;
+    if num_valid == 0 || num_valid < usize::from(price_data.min_pub_) {
+        price_data.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+
+    quotes.sort();
+    let count = quotes.len();
+    let p25 = quotes[count / 4];
+    let p50 = if count % 2 == 1 {
+        quotes[count / 2]
+    } else {
+        // Floor of the average, without intermediate overflow
+        let (left, right) = (quotes[count / 2 - 1], quotes[count / 2]);
+        ((i128::from(left) + i128::from(right)) >> 1) as i64
+    };
+    let p75 = quotes[count - 1 - count / 4];
+    let conf = (p50 - p25).max(p75 - p50);
+    if !allow_zero_ci && conf <= 0 {
+        price_data.agg_.status_ = PC_STATUS_UNKNOWN;
+        return false;
+    }
+
+    price_data.agg_.status_ = PC_STATUS_TRADING;
+    price_data.last_slot_ = slot;
+    price_data.agg_.price_ = p50;
+    price_data.agg_.conf_ = conf as u64;
+    true
+}
+
+/// A price account about to aggregate in `slot`. The quotes of a feed are in the same range, so
+/// that the spread of the valid quotes fits in an `i64`, but the ranges go up to the bounds of
+/// `i64` where `price - conf` or `price + conf` overflows. Prices and confidences are drawn
+/// from few values to get ties.
+fn random_price_account(rng: &mut StdRng, slot: u64) -> PriceAccount {
+    let mut price_data = PriceAccount::zeroed();
+    price_data.num_ = rng.gen_range(0..=PC_NUM_COMP);
+    price_data.min_pub_ = *[0, 0, 1, 3, 5, u8::MAX].choose(rng).unwrap();
+    price_data.max_latency_ = *[0, 0, 1, 5, 30, u8::MAX].choose(rng).unwrap();
+    if rng.gen_bool(0.3) {
+        price_data.flags.insert(PriceAccountFlags::ALLOW_ZERO_CI);
+    }
+    price_data.exponent = rng.gen_range(-12..=0);
+    price_data.timestamp_ = rng.gen();
+    price_data.agg_ = random_quote(rng, slot, 0, i64::MAX);
+
+    let (center, spread): (i64, i64) = match rng.gen_range(0..4) {
+        0 => (rng.gen_range(1..1_000), 10),
+        1 => (rng.gen_range(0..1 << 40), rng.gen_range(1..1 << 20)),
+        2 => (i64::MAX - rng.gen_range(0..100), 100),
+        _ => (i64::MIN + rng.gen_range(0..100), 100),
+    };
+    for component in price_data.comp_[..price_data.num_ as usize].iter_mut() {
+        component.agg_ = random_quote(rng, slot, center, spread);
+        component.latest_ = random_quote(rng, slot, center, spread);
+    }
+    price_data
+}
+
+fn random_quote(rng: &mut StdRng, slot: u64, center: i64, spread: i64) -> PriceInfo {
+    PriceInfo {
+        price_:           center.saturating_add(rng.gen_range(-spread..=spread)),
+        conf_:            if rng.gen_bool(0.1) {
+            0
+        } else {
+            rng.gen_range(0..=spread as u64)
+        },
+        status_:          if rng.gen_bool(0.8) {
+            PC_STATUS_TRADING
+        } else {
+            rng.gen_range(0..=4)
+        },
+        corp_act_status_: 0,
+        // Mostly recent, sometimes too old or in the future
+        pub_slot_:        slot
+            .saturating_sub(rng.gen_range(0..=40))
+            .saturating_add(rng.gen_range(0..=1)),
+    }
+}
+
+/// Hint of `c_upd_aggregate_sorted` listing the components of `price_data` sorted by
+/// `price - conf`, `price` and `price + conf`, the way `QuoteOrder` maintains it.
+fn quote_order(price_data: &PriceAccount) -> [[u8; PC_NUM_COMP as usize]; 3] {
+    let mut lists = [[0u8; PC_NUM_COMP as usize]; 3];
+    for (list, side) in lists.iter_mut().zip([-1i128, 0, 1]) {
+        let mut indexes: Vec<u8> = (0..price_data.num_ as u8).collect();
+        indexes.sort_by_key(|i| {
+            let quote = &price_data.comp_[usize::from(*i)].latest_;
+            i128::from(quote.price_) + side * i128::from(quote.conf_)
+        });
+        list[..indexes.len()].copy_from_slice(&indexes);
+    }
+    lists
+}
+
+fn c_aggregate(
+    price_data: &mut PriceAccount,
+    slot: u64,
+    timestamp: i64,
+    order: Option<(&[[u8; PC_NUM_COMP as usize]; 3], u32)>,
+) -> bool {
+    let input = (price_data as *mut PriceAccount) as *mut u8;
+    unsafe {
+        match order {
+            Some((lists, len)) => {
+                c_upd_aggregate_sorted(input, slot, timestamp, lists.as_ptr() as *const u8, len)
+            }
+            None => c_upd_aggregate(input, slot, timestamp),
+        }
+    }
+}
+
+#[test]
+fn test_aggregation_matches_c() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut num_trading = 0;
+    for _ in 0..20_000 {
+        let slot = rng.gen_range(0..1_000);
+        let timestamp = rng.gen();
+        let price_data = random_price_account(&mut rng, slot);
+
+        let mut expected = price_data;
+        let expected_updated = reference_upd_aggregate(&mut expected, slot, timestamp);
+        let mut actual = price_data;
+        let updated = c_aggregate(&mut actual, slot, timestamp, None);
+
+        assert_eq!(updated, expected_updated);
+        assert!(bytes_of(&actual) == bytes_of(&expected));
+        num_trading += usize::from(updated);
+    }
+    // Both outcomes are covered
+    assert!(num_trading > 1_000 && num_trading < 19_000);
+}
+
+/// The sorted aggregation merges the quotes along the hint when it is valid, and sorts them
+/// otherwise. Both paths must match the reference.
+#[test]
+fn test_sorted_aggregation_matches_c() {
+    let mut rng = StdRng::seed_from_u64(11);
+    for _ in 0..20_000 {
+        let slot = rng.gen_range(0..1_000);
+        let timestamp = rng.gen();
+        let price_data = random_price_account(&mut rng, slot);
+
+        let mut lists = quote_order(&price_data);
+        let mut len = price_data.num_;
+        match rng.gen_range(0..5) {
+            // Missing components
+            0 => len = rng.gen_range(0..=len),
+            // Out of order or duplicate components
+            1 if len > 1 => {
+                let list = rng.gen_range(0..3);
+                let (i, j) = (rng.gen_range(0..len), rng.gen_range(0..len));
+                if rng.gen_bool(0.5) {
+                    lists[list].swap(i as usize, j as usize);
+                } else {
+                    lists[list][i as usize] = lists[list][j as usize];
+                }
+            }
+            // Components that don't exist
+            2 => len = rng.gen_range(len..=PC_NUM_COMP),
+            _ => {}
+        }
+
+        let mut expected = price_data;
+        let expected_updated = reference_upd_aggregate(&mut expected, slot, timestamp);
+        let mut actual = price_data;
+        let updated = c_aggregate(&mut actual, slot, timestamp, Some((&lists, len)));
+
+        assert_eq!(updated, expected_updated);
+        assert!(bytes_of(&actual) == bytes_of(&expected));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::{
+    c_oracle_header::{
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+    error::OracleError,
+    instruction::{
+        AssertPriceConditionArgs,
+        OracleCommand,
+    },
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_assert_price_condition() {
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.publish(0, 10_000, 100).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 10_000, 100).unwrap();
+    assert_eq!(feed.price_data().agg_.price_, 10_000);
+
+    // Every check skipped
+    feed.assert_price_condition(any_price()).unwrap();
+
+    feed.assert_price_condition(AssertPriceConditionArgs {
+        required_status: PC_STATUS_TRADING,
+        min_price: 10_000,
+        max_price: 10_000,
+        max_conf_ratio_bps: 200,
+        max_age: 0,
+        ..any_price()
+    })
+    .unwrap();
+
+    for condition in [
+        AssertPriceConditionArgs {
+            min_price: 10_001,
+            ..any_price()
+        },
+        AssertPriceConditionArgs {
+            max_price: 9_999,
+            ..any_price()
+        },
+        AssertPriceConditionArgs {
+            max_conf_ratio_bps: 10,
+            ..any_price()
+        },
+    ] {
+        assert_eq!(
+            feed.assert_price_condition(condition),
+            Err(OracleError::PriceConditionNotMet.into())
+        );
+    }
+
+    // The aggregate gets older without updates
+    feed.warp_to_slot(15);
+    feed.assert_price_condition(AssertPriceConditionArgs {
+        max_age: 4,
+        ..any_price()
+    })
+    .unwrap();
+    assert_eq!(
+        feed.assert_price_condition(AssertPriceConditionArgs {
+            max_age: 3,
+            ..any_price()
+        }),
+        Err(OracleError::PriceConditionNotMet.into())
+    );
+
+    // The next aggregate has no valid quote
+    feed.upd_price(0, PC_STATUS_UNKNOWN, 10_000, 100, 15)
+        .unwrap();
+    feed.warp_to_slot(16);
+    feed.upd_price(0, PC_STATUS_UNKNOWN, 10_000, 100, 16)
+        .unwrap();
+    assert_eq!(
+        feed.assert_price_condition(AssertPriceConditionArgs {
+            required_status: PC_STATUS_TRADING,
+            ..any_price()
+        }),
+        Err(OracleError::PriceConditionNotMet.into())
+    );
+}
+
+fn any_price() -> AssertPriceConditionArgs {
+    AssertPriceConditionArgs {
+        header:             OracleCommand::AssertPriceCondition.into(),
+        min_price:          i64::MIN,
+        max_price:          i64::MAX,
+        max_conf_ratio_bps: u64::MAX,
+        max_age:            u64::MAX,
+        required_status:    PC_STATUS_UNKNOWN,
+        unused_:            0,
+    }
+}
@@ -0,0 +1,148 @@
+use {
+    crate::{
+        accounts::{
+            AuditLogAccount,
+            AuditRecord,
+            AUDIT_LOG_CAPACITY,
+        },
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    bytemuck::Zeroable,
+    num_traits::ToPrimitive,
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+fn record(slot: u64) -> AuditRecord {
+    AuditRecord {
+        slot,
+        ..AuditRecord::zeroed()
+    }
+}
+
+#[test]
+fn test_audit_log_ring() {
+    let mut audit_log = AuditLogAccount::zeroed();
+    assert_eq!(audit_log.records().count(), 0);
+
+    for slot in 0..3 {
+        audit_log.append(record(slot));
+    }
+    let slots: Vec<u64> = audit_log.records().map(|record| record.slot).collect();
+    assert_eq!(slots, vec![0, 1, 2]);
+
+    // The oldest records are overwritten once the log is full
+    for slot in 3..AUDIT_LOG_CAPACITY as u64 + 5 {
+        audit_log.append(record(slot));
+    }
+    assert_eq!(audit_log.num_records, AUDIT_LOG_CAPACITY as u64 + 5);
+    assert_eq!(audit_log.records[0].slot, AUDIT_LOG_CAPACITY as u64);
+    let slots: Vec<u64> = audit_log.records().map(|record| record.slot).collect();
+    let expected: Vec<u64> = (5..AUDIT_LOG_CAPACITY as u64 + 5).collect();
+    assert_eq!(slots, expected);
+}
+
+#[tokio::test]
+async fn test_audit_log() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let audit_log = sim.get_audit_log_pubkey();
+
+    // Only the master authority can create the audit log
+    let attacker = Keypair::new();
+    sim.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.init_audit_log(&attacker).await.unwrap_err().unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert!(sim.get_account(audit_log).await.is_none());
+
+    sim.init_audit_log(&authority).await.unwrap();
+    let audit_log_data = sim
+        .get_account_data_as::<AuditLogAccount>(audit_log)
+        .await
+        .unwrap();
+    assert_eq!(audit_log_data.num_records, 0);
+
+    // Listing products and prices isn't recorded, adding a publisher is
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -5).await.unwrap();
+    sim.warp_to_slot(5).await.unwrap();
+    sim.add_publisher(&price_keypair, Pubkey::new_unique())
+        .await
+        .unwrap();
+    sim.set_min_pub(&price_keypair, 1).await.unwrap();
+
+    // Failed governance instructions aren't recorded
+    assert_eq!(
+        sim.set_feature_grace_period(10, &attacker)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.warp_to_slot(8).await.unwrap();
+    sim.set_feature_grace_period(10, &authority).await.unwrap();
+
+    let upgrade_authority = copy_keypair(&sim.upgrade_authority);
+    sim.upd_permissions(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::UpdPermissions.into(),
+            master_authority:        authority.pubkey(),
+            data_curation_authority: authority.pubkey(),
+            security_authority:      attacker.pubkey(),
+        },
+        &upgrade_authority,
+    )
+    .await
+    .unwrap();
+
+    let audit_log_data = sim
+        .get_account_data_as::<AuditLogAccount>(audit_log)
+        .await
+        .unwrap();
+    let records: Vec<AuditRecord> = audit_log_data.records().copied().collect();
+    assert_eq!(
+        records,
+        vec![
+            AuditRecord {
+                slot:    5,
+                command: OracleCommand::AddPublisher.to_i32().unwrap(),
+                unused_: 0,
+                actor:   authority.pubkey(),
+                target:  price_keypair.pubkey(),
+            },
+            AuditRecord {
+                slot:    8,
+                command: OracleCommand::SetFeatureGracePeriod.to_i32().unwrap(),
+                unused_: 0,
+                actor:   authority.pubkey(),
+                target:  sim.get_feature_gate_pubkey(),
+            },
+            AuditRecord {
+                slot:    8,
+                command: OracleCommand::UpdPermissions.to_i32().unwrap(),
+                unused_: 0,
+                actor:   upgrade_authority.pubkey(),
+                target:  sim.get_permissions_pubkey(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,77 @@
+use crate::{
+    accounts::AuxData,
+    c_oracle_header::PC_MAX_SEND_LATENCY,
+    error::OracleError,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_aux_data() {
+    let mut feed = FeedSimulator::new(3).with_extension::<AuxData>();
+    feed.warp_to_slot(10);
+
+    // Submissions are rejected until governance enables them
+    assert_eq!(
+        feed.upd_aux_data(0, 100, 1_000),
+        Err(OracleError::AuxDataNotEnabled.into())
+    );
+    feed.set_aux_data(true).unwrap();
+
+    feed.upd_aux_data(0, 100, 1_000).unwrap();
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 100);
+    assert_eq!(aux_data.volume_24h, 1_000);
+    assert_eq!(aux_data.num_submissions, 1);
+    assert_eq!(aux_data.slot, 10);
+
+    // The mean of the two middle submissions for an even number of them
+    feed.upd_aux_data(1, 301, 3_000).unwrap();
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 200);
+    assert_eq!(aux_data.volume_24h, 2_000);
+    assert_eq!(aux_data.num_submissions, 2);
+
+    feed.warp_to_slot(11);
+    feed.upd_aux_data(2, 250, 500).unwrap();
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 250);
+    assert_eq!(aux_data.volume_24h, 1_000);
+    assert_eq!(aux_data.num_submissions, 3);
+    assert_eq!(aux_data.slot, 11);
+
+    // A new submission replaces the previous one of the publisher
+    feed.upd_aux_data(2, 50, 5_000).unwrap();
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 100);
+    assert_eq!(aux_data.volume_24h, 3_000);
+    assert_eq!(aux_data.num_submissions, 3);
+
+    // Submissions older than the maximum latency are left out
+    feed.warp_to_slot(11 + u64::from(PC_MAX_SEND_LATENCY));
+    feed.upd_aux_data(1, 400, 4_000).unwrap();
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 225);
+    assert_eq!(aux_data.volume_24h, 4_500);
+    assert_eq!(aux_data.num_submissions, 2);
+
+    // Disabling keeps the medians
+    feed.set_aux_data(false).unwrap();
+    assert_eq!(
+        feed.upd_aux_data(1, 1, 1),
+        Err(OracleError::AuxDataNotEnabled.into())
+    );
+    let aux_data = feed.extension::<AuxData>();
+    assert_eq!(aux_data.open_interest, 225);
+    assert_eq!(aux_data.enabled, 0);
+}
+
+#[test]
+fn test_aux_data_requires_extension() {
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+
+    assert_eq!(
+        feed.upd_aux_data(0, 100, 1_000),
+        Err(OracleError::AuxDataNotEnabled.into())
+    );
+}
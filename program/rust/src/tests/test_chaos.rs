@@ -0,0 +1,256 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAX_SEND_LATENCY,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        instruction::{
+            OracleCommand,
+            UpdPriceArgs,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            AccountSetup,
+            TestClock,
+        },
+    },
+    bytemuck::bytes_of,
+    rand::{
+        rngs::StdRng,
+        seq::SliceRandom,
+        Rng,
+        SeedableRng,
+    },
+    solana_program::pubkey::Pubkey,
+    std::env,
+};
+
+/// Number of slots simulated by default, override with `CHAOS_SLOTS` for longer runs.
+const DEFAULT_NUM_SLOTS: u64 = 500;
+/// Seed used by default, override with `CHAOS_SEED` to explore other runs.
+const DEFAULT_SEED: u64 = 42;
+
+const NUM_HONEST_PUBLISHERS: usize = 16;
+const TRUE_PRICE_START: i64 = 1_000_000;
+
+#[derive(Clone, Copy, Debug)]
+enum Behavior {
+    Honest,
+    /// Publishes prices orders of magnitude away from the market with a tight confidence
+    ExtremeOutlier,
+    /// Publishes a wrong price once in a while, with publishing slots lagging far behind
+    StaleSlot,
+    /// Publishes a wrong price with a confidence of zero
+    ZeroConf,
+    /// Alternates between a very high and a very low price, and between statuses
+    Flapping,
+}
+
+struct Publisher {
+    behavior:      Behavior,
+    setup:         AccountSetup,
+    last_pub_slot: u64,
+    /// Latest quote (price, conf, publishing slot) of an honest publisher
+    latest:        Option<(i64, u64, u64)>,
+}
+
+impl Publisher {
+    fn next_quote(
+        &self,
+        rng: &mut StdRng,
+        slot: u64,
+        true_price: i64,
+    ) -> Option<(u32, i64, u64, u64)> {
+        match self.behavior {
+            Behavior::Honest => {
+                if slot > 1 && rng.gen_bool(0.1) {
+                    return None;
+                }
+                Some((
+                    PC_STATUS_TRADING,
+                    true_price + rng.gen_range(-100..=100),
+                    rng.gen_range(200..=1000),
+                    slot,
+                ))
+            }
+            Behavior::ExtremeOutlier => {
+                let price = if rng.gen_bool(0.5) {
+                    i64::MAX / 2
+                } else {
+                    true_price / 1000
+                };
+                Some((PC_STATUS_TRADING, price, 1, slot))
+            }
+            Behavior::StaleSlot => {
+                if !rng.gen_bool(0.05) {
+                    return None;
+                }
+                Some((PC_STATUS_TRADING, true_price * 3, 1, self.last_pub_slot + 1))
+            }
+            Behavior::ZeroConf => Some((PC_STATUS_TRADING, true_price * 2, 0, slot)),
+            Behavior::Flapping => {
+                if slot % 2 == 0 {
+                    Some((PC_STATUS_TRADING, true_price * 10, 1, slot))
+                } else {
+                    Some((PC_STATUS_UNKNOWN, true_price / 10, 1, slot))
+                }
+            }
+        }
+    }
+}
+
+fn env_or_default(name: &str, default: u64) -> u64 {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+/// A minority of adversarial publishers (less than a quarter of the publishers, so that they can't
+/// move any of the quartiles used by the aggregation) should never push the aggregate outside of
+/// the range of the honest publishers' quotes.
+#[test]
+fn test_chaos_adversarial_publishers() {
+    let num_slots = env_or_default("CHAOS_SLOTS", DEFAULT_NUM_SLOTS);
+    let seed = env_or_default("CHAOS_SEED", DEFAULT_SEED);
+
+    for allow_zero_ci in [false, true] {
+        run_chaos(num_slots, seed, allow_zero_ci);
+    }
+}
+
+fn run_chaos(num_slots: u64, seed: u64, allow_zero_ci: bool) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let program_id = Pubkey::new_unique();
+
+    let mut publishers: Vec<Publisher> = [
+        Behavior::ExtremeOutlier,
+        Behavior::StaleSlot,
+        Behavior::ZeroConf,
+        Behavior::Flapping,
+    ]
+    .into_iter()
+    .chain([Behavior::Honest; NUM_HONEST_PUBLISHERS])
+    .map(|behavior| Publisher {
+        behavior,
+        setup: AccountSetup::new_funding(),
+        last_pub_slot: 0,
+        latest: None,
+    })
+    .collect();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = publishers.len() as u32;
+        for (i, publisher) in publishers.iter_mut().enumerate() {
+            price_data.comp_[i].pub_ = *publisher.setup.as_account_info().key;
+        }
+        price_data
+            .flags
+            .set(PriceAccountFlags::ALLOW_ZERO_CI, allow_zero_ci);
+    }
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock = TestClock::new(clock_setup.as_account_info());
+
+    let mut true_price = TRUE_PRICE_START;
+    let mut num_trading = 0;
+
+    for slot in 1..=num_slots {
+        clock.warp_to_slot(slot);
+        true_price += rng.gen_range(-50..=50);
+
+        // The aggregate of this slot is computed from the quotes published before it
+        let honest_quotes: Vec<(i64, u64)> = publishers
+            .iter()
+            .filter_map(|publisher| publisher.latest)
+            .filter(|(_, _, pub_slot)| slot - pub_slot <= PC_MAX_SEND_LATENCY as u64)
+            .map(|(price, conf, _)| (price, conf))
+            .collect();
+
+        let mut order: Vec<usize> = (0..publishers.len()).collect();
+        order.shuffle(&mut rng);
+        for i in order {
+            let quote = publishers[i].next_quote(&mut rng, slot, true_price);
+            let (status, price, conf, pub_slot) = match quote {
+                Some(quote) => quote,
+                None => continue,
+            };
+
+            let instruction_data = UpdPriceArgs {
+                header: OracleCommand::UpdPrice.into(),
+                status,
+                venue: 0,
+                unused_: [0; 3],
+                price,
+                confidence: conf,
+                publishing_slot: pub_slot,
+                sequence: 0,
+            };
+            let publisher = &mut publishers[i];
+            assert!(process_instruction(
+                &program_id,
+                &[
+                    publisher.setup.as_account_info(),
+                    price_account.clone(),
+                    clock.account()
+                ],
+                bytes_of(&instruction_data)
+            )
+            .is_ok());
+
+            publisher.last_pub_slot = pub_slot;
+            if let Behavior::Honest = publisher.behavior {
+                publisher.latest = Some((price, conf, pub_slot));
+            }
+        }
+
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        if price_data.agg_.status_ != PC_STATUS_TRADING || honest_quotes.is_empty() {
+            continue;
+        }
+        num_trading += 1;
+
+        let low = honest_quotes
+            .iter()
+            .map(|(price, conf)| price - *conf as i64)
+            .min()
+            .unwrap();
+        let high = honest_quotes
+            .iter()
+            .map(|(price, conf)| price + *conf as i64)
+            .max()
+            .unwrap();
+        assert!(
+            low <= price_data.agg_.price_ && price_data.agg_.price_ <= high,
+            "seed {seed}, slot {slot}, allow_zero_ci {allow_zero_ci}: aggregate {} outside of \
+             honest range [{low}, {high}]",
+            price_data.agg_.price_
+        );
+        assert!(
+            price_data.agg_.conf_ as i64 <= high - low,
+            "seed {seed}, slot {slot}, allow_zero_ci {allow_zero_ci}: aggregate confidence {} \
+             wider than honest range [{low}, {high}]",
+            price_data.agg_.conf_
+        );
+    }
+
+    // Honest quotes are always fresh after the first slot, so the feed should always be trading
+    assert!(
+        num_trading == num_slots - 1,
+        "seed {seed}, allow_zero_ci {allow_zero_ci}: only {num_trading} trading slots"
+    );
+}
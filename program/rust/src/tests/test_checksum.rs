@@ -0,0 +1,71 @@
+use crate::{
+    accounts::{
+        Checksum,
+        PriceAccountFlags,
+    },
+    error::OracleError,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_checksum() {
+    let mut feed = FeedSimulator::new(2).with_extension::<Checksum>();
+
+    // Nothing is recorded until the check is enabled
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.extension::<Checksum>().hash, [0; 32]);
+
+    feed.set_checksum(true).unwrap();
+    let checksum = feed.extension::<Checksum>();
+    assert_eq!(checksum.enabled, 1);
+    assert_eq!(checksum.hash, Checksum::hash_of(&feed.price_data()));
+
+    // Every instruction records the checksum of the account it leaves
+    feed.publish(1, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 101, 1).unwrap();
+    assert_ne!(feed.extension::<Checksum>().hash, checksum.hash);
+    assert_eq!(
+        feed.extension::<Checksum>().hash,
+        Checksum::hash_of(&feed.price_data())
+    );
+
+    // A write outside of an instruction fails every instruction on the feed, including the
+    // rejected ones that don't write it
+    let flags = feed.price_data().flags;
+    feed.set_flags(flags | PriceAccountFlags::ALLOW_ZERO_CI);
+    assert_eq!(
+        feed.publish(1, 101, 1),
+        Err(OracleError::ChecksumMismatch.into())
+    );
+    assert_eq!(
+        feed.publish(0, 101, 1),
+        Err(OracleError::ChecksumMismatch.into())
+    );
+
+    // Until governance accepts the account as it is
+    feed.set_checksum(true).unwrap();
+    feed.publish(1, 101, 1).unwrap();
+
+    // Stopping keeps the last checksum
+    feed.set_checksum(false).unwrap();
+    let checksum = feed.extension::<Checksum>();
+    assert_eq!(checksum.enabled, 0);
+    feed.set_flags(flags);
+    feed.warp_to_slot(12);
+    feed.publish(0, 102, 1).unwrap();
+    assert_eq!(feed.extension::<Checksum>().hash, checksum.hash);
+}
+
+#[test]
+fn test_checksum_missing() {
+    // Feeds without the extension aren't checked
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    let flags = feed.price_data().flags;
+    feed.set_flags(flags | PriceAccountFlags::ALLOW_ZERO_CI);
+    feed.warp_to_slot(11);
+    feed.publish(0, 101, 1).unwrap();
+}
@@ -0,0 +1,266 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            init_extension,
+            AccountHeader,
+            LatestPrice,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceSuccessor,
+            PythAccount,
+            EXTENSIONS_OFFSET,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        client::{
+            AccountFetcher,
+            CachingPriceReader,
+            ClientError,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    solana_program::pubkey::Pubkey,
+    std::{
+        cell::RefCell,
+        collections::HashMap,
+    },
+};
+
+#[derive(Default)]
+struct MockFetcher {
+    accounts: HashMap<Pubkey, Vec<u8>>,
+    requests: RefCell<Vec<Vec<Pubkey>>>,
+}
+
+impl AccountFetcher for &MockFetcher {
+    type Error = ();
+
+    fn fetch_accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>, ()> {
+        self.requests.borrow_mut().push(keys.to_vec());
+        Ok(keys
+            .iter()
+            .map(|key| self.accounts.get(key).cloned())
+            .collect())
+    }
+}
+
+fn price_account_data(status: u32, agg_slot: u64, prev_slot: u64) -> Vec<u8> {
+    let mut price_data = PriceAccount::zeroed();
+    price_data.header = AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: PriceAccount::ACCOUNT_TYPE,
+        size:         PriceAccount::INITIAL_SIZE,
+    };
+    price_data.exponent = -5;
+    price_data.agg_.status_ = status;
+    price_data.agg_.price_ = 100;
+    price_data.agg_.conf_ = 1;
+    price_data.agg_.pub_slot_ = agg_slot;
+    price_data.timestamp_ = 1_000;
+    price_data.prev_price_ = 90;
+    price_data.prev_conf_ = 2;
+    price_data.prev_slot_ = prev_slot;
+    price_data.prev_timestamp_ = 900;
+    bytes_of(&price_data).to_vec()
+}
+
+#[test]
+fn test_latest_price() {
+    let trading: PriceAccount =
+        bytemuck::pod_read_unaligned(&price_account_data(PC_STATUS_TRADING, 10, 8));
+    assert_eq!(
+        trading.get_latest_price(),
+        LatestPrice {
+            price:        100,
+            conf:         1,
+            exponent:     -5,
+            publish_slot: 10,
+            publish_time: 1_000,
+        }
+    );
+    assert!(trading.get_price_no_older_than(15, 5).is_some());
+    assert!(trading.get_price_no_older_than(16, 5).is_none());
+    // A slot before the publish slot doesn't underflow
+    assert!(trading.get_price_no_older_than(9, 0).is_some());
+
+    // Falls back to the last trading aggregate
+    let unknown: PriceAccount =
+        bytemuck::pod_read_unaligned(&price_account_data(PC_STATUS_UNKNOWN, 10, 8));
+    assert_eq!(
+        unknown.get_latest_price(),
+        LatestPrice {
+            price:        90,
+            conf:         2,
+            exponent:     -5,
+            publish_slot: 8,
+            publish_time: 900,
+        }
+    );
+    assert!(unknown.get_price_no_older_than(10, 1).is_none());
+}
+
+#[test]
+fn test_pyth_sdk_getters() {
+    let mut price_data: PriceAccount =
+        bytemuck::pod_read_unaligned(&price_account_data(PC_STATUS_TRADING, 10, 8));
+    price_data.twap_.val_ = 95;
+    price_data.twac_.val_ = 3;
+    assert_eq!(
+        price_data.get_price_unchecked(),
+        price_data.get_latest_price()
+    );
+    assert_eq!(
+        price_data.get_ema_price(),
+        LatestPrice {
+            price:        95,
+            conf:         3,
+            exponent:     -5,
+            publish_slot: 10,
+            publish_time: 1_000,
+        }
+    );
+
+    let price = LatestPrice {
+        price:        123_456,
+        conf:         789,
+        exponent:     -5,
+        publish_slot: 10,
+        publish_time: 1_000,
+    };
+    assert_eq!(price.scale_to_exponent(-5), Some(price));
+    assert_eq!(
+        price.scale_to_exponent(-8),
+        Some(LatestPrice {
+            price: 123_456_000,
+            conf: 789_000,
+            exponent: -8,
+            ..price
+        })
+    );
+    // Scaling to a larger exponent truncates towards zero
+    assert_eq!(
+        price.scale_to_exponent(-3),
+        Some(LatestPrice {
+            price: 1_234,
+            conf: 7,
+            exponent: -3,
+            ..price
+        })
+    );
+    let negative = LatestPrice {
+        price: -123_456,
+        ..price
+    };
+    assert_eq!(negative.scale_to_exponent(-3).unwrap().price, -1_234);
+    assert_eq!(
+        price.scale_to_exponent(10),
+        Some(LatestPrice {
+            price: 0,
+            conf: 0,
+            exponent: 10,
+            ..price
+        })
+    );
+    // The price doesn't fit, or the exponents are too far apart
+    assert_eq!(price.scale_to_exponent(-30), None);
+    assert_eq!(price.scale_to_exponent(i32::MAX), None);
+}
+
+#[test]
+fn test_caching_price_reader() {
+    let btc = Pubkey::new_unique();
+    let eth = Pubkey::new_unique();
+    let missing = Pubkey::new_unique();
+    let not_price = Pubkey::new_unique();
+
+    let mut fetcher = MockFetcher::default();
+    fetcher
+        .accounts
+        .insert(btc, price_account_data(PC_STATUS_TRADING, 10, 8));
+    fetcher
+        .accounts
+        .insert(eth, price_account_data(PC_STATUS_TRADING, 12, 11));
+    fetcher.accounts.insert(not_price, vec![0; 100]);
+
+    let mut reader = CachingPriceReader::new(&fetcher);
+
+    // Feeds are deduplicated and fetched in a single request
+    reader.refresh(&[btc, eth, btc], 12).unwrap();
+    assert_eq!(*fetcher.requests.borrow(), vec![vec![btc, eth]]);
+    assert_eq!(reader.publish_slot(&btc), Some(10));
+    assert_eq!(reader.publish_slot(&eth), Some(12));
+
+    // Feeds already fetched in this slot aren't fetched again
+    reader.refresh(&[btc, eth], 12).unwrap();
+    assert_eq!(fetcher.requests.borrow().len(), 1);
+
+    // Fresh enough cached prices are returned without fetching
+    assert_eq!(
+        reader
+            .get_price_no_older_than(&btc, 14, 5)
+            .unwrap()
+            .unwrap()
+            .price,
+        100
+    );
+    assert_eq!(fetcher.requests.borrow().len(), 1);
+
+    // Stale cached prices are fetched again, and are still stale here
+    assert_eq!(reader.get_price_no_older_than(&btc, 20, 5), Ok(None));
+    assert_eq!(fetcher.requests.borrow().len(), 2);
+    assert_eq!(fetcher.requests.borrow()[1], vec![btc]);
+    assert_eq!(reader.get_price_no_older_than(&btc, 20, 5), Ok(None));
+    assert_eq!(fetcher.requests.borrow().len(), 2);
+
+    assert_eq!(
+        reader.get_price_no_older_than(&missing, 20, 5),
+        Err(ClientError::AccountNotFound(missing))
+    );
+    assert_eq!(
+        reader.refresh(&[not_price], 20),
+        Err(ClientError::NotPriceAccount(not_price))
+    );
+    assert_eq!(reader.publish_slot(&missing), None);
+}
+
+#[test]
+fn test_caching_price_reader_successor() {
+    let old = Pubkey::new_unique();
+    let new = Pubkey::new_unique();
+
+    let mut old_data = price_account_data(PC_STATUS_TRADING, 10, 8);
+    old_data.resize(EXTENSIONS_OFFSET + extension_space::<PriceSuccessor>(), 0);
+    init_extension::<PriceSuccessor>(&mut old_data)
+        .unwrap()
+        .successor = new;
+    let mut fetcher = MockFetcher::default();
+    fetcher.accounts.insert(old, old_data.clone());
+    fetcher
+        .accounts
+        .insert(new, price_account_data(PC_STATUS_TRADING, 10, 8));
+
+    // The extension is only followed once the feed is flagged
+    let mut reader = CachingPriceReader::new(&fetcher);
+    reader.refresh(&[old, new], 10).unwrap();
+    assert_eq!(reader.successor(&old), None);
+
+    bytemuck::from_bytes_mut::<PriceAccount>(&mut old_data[..EXTENSIONS_OFFSET])
+        .flags
+        .insert(PriceAccountFlags::DEPRECATED);
+    fetcher.accounts.insert(old, old_data);
+    let mut reader = CachingPriceReader::new(&fetcher);
+    reader.refresh(&[old, new], 10).unwrap();
+    assert_eq!(reader.successor(&old), Some(new));
+    assert_eq!(reader.successor(&new), None);
+    // The deprecated feed is still read
+    assert_eq!(reader.publish_slot(&old), Some(10));
+}
@@ -0,0 +1,91 @@
+use crate::{
+    accounts::PriceAccountFlags,
+    c_oracle_header::PC_STATUS_TRADING,
+    error::OracleError,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_duplicate_slot() {
+    let mut feed = FeedSimulator::new(2);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 110, 1).unwrap();
+    let aggregate = feed.price_data().agg_;
+    assert_eq!(aggregate.pub_slot_, 11);
+
+    // Another update in the slot of the aggregate writes its quote without aggregating
+    feed.publish(1, 120, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.comp_[1].latest_.price_, 120);
+    assert_eq!(price_data.comp_[1].latest_.pub_slot_, 11);
+    assert_eq!(price_data.agg_.price_, aggregate.price_);
+    assert_eq!(price_data.agg_.pub_slot_, 11);
+    // A publisher only quotes once per slot
+    assert_eq!(
+        feed.publish(0, 130, 1),
+        Err(OracleError::StaleSubmission.into())
+    );
+
+    // The next slot aggregates both quotes
+    feed.warp_to_slot(12);
+    feed.publish(0, 130, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.pub_slot_, 12);
+}
+
+#[test]
+fn test_clock_regression() {
+    let mut feed = FeedSimulator::new(3);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(1, 100, 1).unwrap();
+    let account_data = feed.price_account_data().to_vec();
+
+    // A clock behind the latest aggregate leaves the account unchanged, even for a publisher
+    // that didn't quote since
+    feed.warp_to_slot(9);
+    for publisher in 0..3 {
+        assert_eq!(
+            feed.publish(publisher, 101, 1),
+            Err(OracleError::ClockRegression.into())
+        );
+    }
+    feed.warp_to_slot(0);
+    assert_eq!(
+        feed.publish(2, 101, 1),
+        Err(OracleError::ClockRegression.into())
+    );
+    assert_eq!(feed.price_account_data(), &account_data[..]);
+
+    // Updates resume once the clock is back at the slot of the latest aggregate
+    feed.warp_to_slot(11);
+    feed.publish(2, 101, 1).unwrap();
+    assert_eq!(feed.price_data().comp_[2].latest_.pub_slot_, 11);
+}
+
+#[test]
+fn test_clock_regression_without_aggregation() {
+    // Without aggregating in the program, the latest quote of the publisher is the latest slot
+    // the account recorded
+    let mut feed = FeedSimulator::new(1);
+    feed.set_flags(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.pub_slot_, 0);
+
+    // The publishing slot is after the latest quote, but the clock is before it
+    feed.warp_to_slot(9);
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 101, 1, 11),
+        Err(OracleError::ClockRegression.into())
+    );
+    let price_data = feed.price_data();
+    assert_eq!(price_data.comp_[0].latest_.price_, 100);
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 10);
+
+    feed.warp_to_slot(11);
+    feed.upd_price(0, PC_STATUS_TRADING, 101, 1, 11).unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.pub_slot_, 11);
+}
@@ -0,0 +1,116 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        processor::ALLOW_ZERO_CI,
+        tests::pyth_simulator::{
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_clone_price() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let source_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let publishers: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for publisher in &publishers {
+        sim.add_publisher(&source_keypair, publisher.pubkey())
+            .await
+            .unwrap();
+    }
+    sim.add_publisher(&source_keypair, Pubkey::from(ALLOW_ZERO_CI))
+        .await
+        .unwrap();
+    sim.set_min_pub(&source_keypair, 2).await.unwrap();
+
+    // Give the source some price state
+    sim.airdrop(&publishers[0].pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    sim.upd_price(
+        &publishers[0],
+        source_keypair.pubkey(),
+        Quote {
+            price:      150,
+            confidence: 7,
+            status:     PC_STATUS_TRADING,
+        },
+    )
+    .await
+    .unwrap();
+
+    let clone_keypair = sim
+        .clone_price(&product_keypair, source_keypair.pubkey())
+        .await
+        .unwrap();
+    let source_data = sim
+        .get_account_data_as::<PriceAccount>(source_keypair.pubkey())
+        .await
+        .unwrap();
+    let clone_data = sim
+        .get_account_data_as::<PriceAccount>(clone_keypair.pubkey())
+        .await
+        .unwrap();
+
+    assert_eq!(clone_data.exponent, source_data.exponent);
+    assert_eq!(clone_data.price_type, source_data.price_type);
+    assert_eq!(clone_data.min_pub_, 2);
+    assert_eq!(clone_data.max_latency_, source_data.max_latency_);
+    assert!(clone_data.flags.contains(PriceAccountFlags::ALLOW_ZERO_CI));
+    assert_eq!(
+        clone_data.flags.contains(PriceAccountFlags::ACCUMULATOR_V2),
+        source_data
+            .flags
+            .contains(PriceAccountFlags::ACCUMULATOR_V2)
+    );
+    assert_ne!(clone_data.feed_index, source_data.feed_index);
+    assert_eq!(clone_data.num_, 3);
+    for (clone_component, source_component) in
+        clone_data.comp_.iter().zip(&source_data.comp_).take(3)
+    {
+        assert_eq!(clone_component.pub_, source_component.pub_);
+        assert_eq!(clone_component.latest_.price_, 0);
+        assert_eq!(clone_component.latest_.pub_slot_, 0);
+    }
+    assert_eq!(source_data.comp_[0].latest_.price_, 150);
+    assert_eq!(clone_data.last_slot_, 0);
+    assert_eq!(clone_data.agg_.price_, 0);
+    assert_eq!(clone_data.twap_.val_, 0);
+
+    // The clone is listed first in the product
+    assert_eq!(clone_data.product_account, product_keypair.pubkey());
+    assert_eq!(clone_data.next_price_account, source_keypair.pubkey());
+    let product_data = sim
+        .get_account_data_as::<ProductAccount>(product_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(product_data.first_price_account, clone_keypair.pubkey());
+
+    // The clone belongs to the product of the source
+    let other_product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    assert_eq!(
+        sim.clone_price(&other_product_keypair, source_keypair.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+}
@@ -0,0 +1,262 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            get_feed_creator,
+            FeedCreator,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        processor::{
+            ALLOW_BUNDLED_UPDATES,
+            ALLOW_ZERO_CI,
+            DISABLE_ACCUMULATOR_V2,
+            ENABLE_ACCUMULATOR_V2,
+            FORBID_ZERO_CI,
+            REQUIRE_ISOLATED_UPDATES,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+const METADATA: [&str; 6] = [
+    "symbol",
+    "Crypto.TAIL/USD",
+    "asset_type",
+    "Crypto",
+    "quote_currency",
+    "USD",
+];
+
+#[tokio::test]
+async fn test_community_feed() {
+    let mut sim = PythSimulator::new().await;
+    let creator = Keypair::new();
+    sim.airdrop(&creator.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+
+    let product = sim
+        .add_community_product(&creator, [1; 32], &METADATA)
+        .await
+        .unwrap();
+    let price = sim
+        .add_community_price(&creator, [1; 32], [2; 32], -8)
+        .await
+        .unwrap();
+
+    let product_data = sim
+        .get_account_data_as::<ProductAccount>(product)
+        .await
+        .unwrap();
+    assert_eq!(product_data.first_price_account, price);
+    let price_account = sim.get_account(price).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&price_account));
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert!(price_data.flags.contains(PriceAccountFlags::COMMUNITY));
+    assert_eq!(price_data.product_account, product);
+    assert_eq!(price_data.exponent, -8);
+    // Community feeds stay out of the cross-chain pipeline
+    assert_eq!(price_data.feed_index, 0);
+    assert!(!price_data
+        .flags
+        .intersects(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED));
+    assert_eq!(
+        get_extension::<FeedCreator>(&price_account.data)
+            .unwrap()
+            .unwrap()
+            .creator,
+        creator.pubkey()
+    );
+    assert_eq!(
+        get_feed_creator(&price_account.data),
+        Some(creator.pubkey())
+    );
+
+    // The creator adds the publishers of its feed
+    let publisher = Pubkey::new_unique();
+    sim.add_publisher_with_funding(&creator, price, publisher)
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, publisher);
+
+    // But it can't configure it like governance
+    for key in [
+        ENABLE_ACCUMULATOR_V2,
+        DISABLE_ACCUMULATOR_V2,
+        ALLOW_ZERO_CI,
+        FORBID_ZERO_CI,
+        REQUIRE_ISOLATED_UPDATES,
+        ALLOW_BUNDLED_UPDATES,
+    ] {
+        assert_eq!(
+            sim.add_publisher_with_funding(&creator, price, Pubkey::from(key))
+                .await
+                .unwrap_err()
+                .unwrap(),
+            OracleError::PermissionViolation.into()
+        );
+    }
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+    sim.add_publisher_with_funding(&genesis_keypair, price, Pubkey::from(ENABLE_ACCUMULATOR_V2))
+        .await
+        .unwrap();
+    assert!(sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .flags
+        .contains(PriceAccountFlags::ACCUMULATOR_V2));
+
+    // Nobody else does
+    let other = Keypair::new();
+    sim.airdrop(&other.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.add_publisher_with_funding(&other, price, Pubkey::new_unique())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    // Not even for the feeds listed by governance
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    assert!(!sim
+        .get_account_data_as::<PriceAccount>(price_keypair.pubkey())
+        .await
+        .unwrap()
+        .flags
+        .contains(PriceAccountFlags::COMMUNITY));
+    assert_eq!(
+        sim.add_publisher_with_funding(&creator, price_keypair.pubkey(), publisher)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+}
+
+#[tokio::test]
+async fn test_community_namespace() {
+    let mut sim = PythSimulator::new().await;
+    let creator = Keypair::new();
+    let other = Keypair::new();
+    for key in [creator.pubkey(), other.pubkey()] {
+        sim.airdrop(&key, LAMPORTS_PER_SOL).await.unwrap();
+    }
+
+    sim.add_community_product(&creator, [1; 32], &METADATA)
+        .await
+        .unwrap();
+    // Seeds can't be reused
+    assert_eq!(
+        sim.add_community_product(&creator, [1; 32], &METADATA)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidFreshAccount.into()
+    );
+    // Products need the metadata of governance products
+    assert!(sim
+        .add_community_product(&creator, [3; 32], &METADATA[..4])
+        .await
+        .is_err());
+
+    // Creators can only list prices for the products in their namespace
+    assert!(sim
+        .add_community_price(&other, [1; 32], [2; 32], -8)
+        .await
+        .is_err());
+    // The same seed is a different account in the namespace of another creator
+    sim.add_community_product(&other, [1; 32], &METADATA)
+        .await
+        .unwrap();
+    let price = sim
+        .add_community_price(&other, [1; 32], [2; 32], -8)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap()
+            .product_account,
+        sim.get_community_pubkey(&other.pubkey(), &[1; 32])
+    );
+    let creator_price = sim.get_community_pubkey(&creator.pubkey(), &[2; 32]);
+    assert!(sim.get_account(creator_price).await.is_none());
+}
+
+#[tokio::test]
+async fn test_community_feed_not_pushed() {
+    let mut sim = PythSimulator::new().await;
+    let creator = Keypair::new();
+    let publisher = Keypair::new();
+    for key in [creator.pubkey(), publisher.pubkey()] {
+        sim.airdrop(&key, LAMPORTS_PER_SOL).await.unwrap();
+    }
+    sim.add_community_product(&creator, [1; 32], &METADATA)
+        .await
+        .unwrap();
+    let price = sim
+        .add_community_price(&creator, [1; 32], [2; 32], -8)
+        .await
+        .unwrap();
+    sim.add_publisher_with_funding(&creator, price, publisher.pubkey())
+        .await
+        .unwrap();
+    let emitter = sim.accumulator_emitter_id;
+    sim.set_accumulator_emitter(emitter).await.unwrap();
+
+    // The emitter of the simulator rejects the negative aggregates, but community feeds aren't
+    // pushed to it
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(-100), emitter)
+        .await
+        .unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price_with_emitter(&publisher, price, quote(100), emitter)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.get_account_data_as::<PriceAccount>(price)
+            .await
+            .unwrap()
+            .agg_
+            .price_,
+        -100
+    );
+}
+
+fn quote(price: i64) -> Quote {
+    Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    }
+}
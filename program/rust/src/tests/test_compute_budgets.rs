@@ -0,0 +1,430 @@
+use {
+    crate::{
+        accounts::{
+            Feature,
+            PriceAccount,
+            MAX_ACCESS_CONTROL_READERS,
+        },
+        c_oracle_header::{
+            MAX_CI_DIVISOR,
+            PC_NUM_COMP,
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        error::OracleError,
+        instruction::{
+            AssertPriceConditionArgs,
+            CommandHeader,
+            ComputeBudgetEntry,
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        processor::{
+            get_compute_budget_entries,
+            process_instruction,
+            DISABLE_ACCUMULATOR_V2,
+        },
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+                Quote,
+                SANITY_MAX_PRICE,
+            },
+            test_utils::AccountSetup,
+        },
+    },
+    bytemuck::bytes_of,
+    num_traits::ToPrimitive,
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_get_compute_budgets() {
+    let program_id = Pubkey::new_unique();
+    let cmd: CommandHeader = OracleCommand::GetComputeBudgets.into();
+    let instruction_data = bytes_of::<CommandHeader>(&cmd);
+
+    assert!(process_instruction(&program_id, &[], instruction_data).is_ok());
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    assert_eq!(
+        process_instruction(&program_id, &[price_account], instruction_data),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+
+    let entries = get_compute_budget_entries();
+    assert_eq!(
+        entries.first(),
+        Some(&ComputeBudgetEntry {
+            command:       OracleCommand::InitMapping.to_i32().unwrap(),
+            compute_units: 5_000,
+        })
+    );
+    assert_eq!(
+        entries.last().map(|entry| entry.command),
+        OracleCommand::SetSharedSettings.to_i32()
+    );
+    // Removed instructions aren't listed
+    assert!(!entries
+        .iter()
+        .any(|entry| entry.command == OracleCommand::ResizePriceAccount.to_i32().unwrap()));
+    assert!(entries
+        .windows(2)
+        .all(|pair| pair[0].command < pair[1].command));
+    assert!(entries.iter().all(|entry| entry.compute_units > 0));
+
+    let mut bad_version = cmd;
+    bad_version.version = PC_VERSION + 1;
+    assert_eq!(
+        process_instruction(&program_id, &[], bytes_of(&bad_version)),
+        Err(ProgramError::from(OracleError::InvalidInstructionVersion))
+    );
+}
+
+/// Measure the worst case of the instructions that can be run in the simulator and check that it
+/// is within the budget of `OracleCommand::compute_budget`. Run with `--nocapture` to print the
+/// measurements when updating the budgets.
+#[tokio::test]
+async fn test_compute_budgets() {
+    let mut sim = PythSimulator::new().await;
+    let mut costs = vec![];
+
+    // The governance instructions then append to the audit log
+    sim.init_audit_log(&copy_keypair(&sim.genesis_keypair))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::InitAuditLog, sim.last_compute_units()));
+
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    costs.push((OracleCommand::InitMapping, sim.last_compute_units()));
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    costs.push((OracleCommand::AddProduct, sim.last_compute_units()));
+
+    // The first registered price account creates the feed registry
+    let price_keypair = sim
+        .add_price_with_registry(&product_keypair, -5)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::AddPrice, sim.last_compute_units()));
+    sim.init_price_with_registry(&price_keypair, -5)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::InitPrice, sim.last_compute_units()));
+
+    // Adding the last publisher sorts the full publisher list
+    sim.add_publisher(&price_keypair, Pubkey::from(DISABLE_ACCUMULATOR_V2))
+        .await
+        .unwrap();
+    let publishers: Vec<Keypair> = (0..PC_NUM_COMP).map(|_| Keypair::new()).collect();
+    for publisher in &publishers {
+        sim.add_publisher(&price_keypair, publisher.pubkey())
+            .await
+            .unwrap();
+    }
+    costs.push((OracleCommand::AddPublisher, sim.last_compute_units()));
+    sim.set_min_pub(&price_keypair, 1).await.unwrap();
+    costs.push((OracleCommand::SetMinPub, sim.last_compute_units()));
+    // Cloning every publisher
+    let clone_keypair = sim
+        .clone_price(&product_keypair, price_keypair.pubkey())
+        .await
+        .unwrap();
+    costs.push((OracleCommand::ClonePrice, sim.last_compute_units()));
+    sim.deprecate_price(&clone_keypair, price_keypair.pubkey())
+        .await
+        .unwrap();
+    costs.push((OracleCommand::DeprecatePrice, sim.last_compute_units()));
+    sim.del_price(&product_keypair, &clone_keypair)
+        .await
+        .unwrap();
+
+    // The update that aggregates every publisher
+    for publisher in &publishers {
+        sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+            .await
+            .unwrap();
+    }
+    sim.warp_to_slot(2).await.unwrap();
+    for (i, publisher) in publishers.iter().enumerate() {
+        sim.upd_price(publisher, price_keypair.pubkey(), quote(i))
+            .await
+            .unwrap();
+    }
+    sim.warp_to_slot(3).await.unwrap();
+    let cost = sim
+        .upd_price_compute_units(&publishers[0], price_keypair.pubkey(), quote(0))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+
+    // Growing the price account for the extension
+    sim.set_rate_limit(&price_keypair, 1).await.unwrap();
+    costs.push((OracleCommand::SetRateLimit, sim.last_compute_units()));
+    let authority = copy_keypair(&sim.genesis_keypair);
+    sim.snapshot_price_account(price_keypair.pubkey(), &authority)
+        .await
+        .unwrap();
+    costs.push((
+        OracleCommand::SnapshotPriceAccount,
+        sim.last_compute_units(),
+    ));
+    // Linking the publishers in pairs, so that every link is found in the price account and the
+    // cap masks half of the quotes
+    for pair in publishers.chunks(2) {
+        for publisher in pair {
+            sim.upd_publisher_linkage(publisher.pubkey(), pair[0].pubkey(), &authority)
+                .await
+                .unwrap();
+        }
+    }
+    costs.push((OracleCommand::UpdPublisherLinkage, sim.last_compute_units()));
+    // Creating the feature gate, then growing it with every switch
+    sim.set_feature_grace_period(10, &authority).await.unwrap();
+    costs.push((
+        OracleCommand::SetFeatureGracePeriod,
+        sim.last_compute_units(),
+    ));
+    for feature in Feature::ALL {
+        sim.set_feature(feature.name(), true, &authority)
+            .await
+            .unwrap();
+        costs.push((OracleCommand::SetFeature, sim.last_compute_units()));
+    }
+    // Filling the access control account, the feed then checks the last reader
+    let access_control_keypair = sim.create_access_control().await;
+    let readers: Vec<Keypair> = (0..MAX_ACCESS_CONTROL_READERS)
+        .map(|_| Keypair::new())
+        .collect();
+    for reader in &readers {
+        sim.upd_access_control(&access_control_keypair, reader.pubkey(), true, &authority)
+            .await
+            .unwrap();
+    }
+    costs.push((OracleCommand::UpdAccessControl, sim.last_compute_units()));
+    sim.set_access_control(&price_keypair, Some(access_control_keypair.pubkey()))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::SetAccessControl, sim.last_compute_units()));
+    sim.assert_price_condition(
+        price_keypair.pubkey(),
+        any_price(),
+        Some((readers.last().unwrap(), access_control_keypair.pubkey())),
+    )
+    .await
+    .unwrap();
+    costs.push((
+        OracleCommand::AssertPriceCondition,
+        sim.last_compute_units(),
+    ));
+    sim.set_publisher_weight_cap(&price_keypair, 1)
+        .await
+        .unwrap();
+    costs.push((
+        OracleCommand::SetPublisherWeightCap,
+        sim.last_compute_units(),
+    ));
+    sim.warp_to_slot(4).await.unwrap();
+    let cost = sim
+        .upd_price_compute_units(&publishers[0], price_keypair.pubkey(), quote(0))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+    sim.reset_ema(price_keypair.pubkey(), &authority)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::ResetEma, sim.last_compute_units()));
+
+    // Inserting the last publisher in the quote order, then merging the quotes along it
+    sim.set_quote_order(&price_keypair, true).await.unwrap();
+    costs.push((OracleCommand::SetQuoteOrder, sim.last_compute_units()));
+    sim.warp_to_slot(5).await.unwrap();
+    let (last, others) = publishers.split_last().unwrap();
+    for (i, publisher) in others.iter().enumerate() {
+        sim.upd_price(publisher, price_keypair.pubkey(), quote(i))
+            .await
+            .unwrap();
+    }
+    let cost = sim
+        .upd_price_compute_units(last, price_keypair.pubkey(), quote(others.len()))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+    sim.warp_to_slot(6).await.unwrap();
+    let cost = sim
+        .upd_price_compute_units(&publishers[0], price_keypair.pubkey(), quote(0))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+    let cost = sim
+        .upd_price_compact_compute_units(&publishers[2], price_keypair.pubkey(), quote(3))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPriceCompact, cost));
+    sim.upd_publisher_status(&publishers[1], price_keypair.pubkey(), PC_STATUS_HALTED)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPublisherStatus, sim.last_compute_units()));
+
+    // Creating the fee vault, then paying the fee of a quote
+    sim.set_update_fee(&price_keypair, 5_000).await.unwrap();
+    costs.push((OracleCommand::SetUpdateFee, sim.last_compute_units()));
+    sim.warp_to_slot(7).await.unwrap();
+    sim.upd_price_with_update_fee(&publishers[0], price_keypair.pubkey(), quote(0))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, sim.last_compute_units()));
+    sim.withdraw_fees(authority.pubkey(), 5_000, &authority)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::WithdrawFees, sim.last_compute_units()));
+    sim.set_update_fee(&price_keypair, 0).await.unwrap();
+
+    // Scoring every quote, then creating the rewards ledger
+    sim.set_publisher_rewards(&price_keypair, true)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::SetPublisherRewards, sim.last_compute_units()));
+    sim.warp_to_slot(8).await.unwrap();
+    for (i, publisher) in others.iter().enumerate() {
+        sim.upd_price(publisher, price_keypair.pubkey(), quote(i))
+            .await
+            .unwrap();
+    }
+    sim.warp_to_slot(9).await.unwrap();
+    let cost = sim
+        .upd_price_compute_units(last, price_keypair.pubkey(), quote(others.len()))
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+    sim.claim_rewards(&publishers[0], price_keypair.pubkey())
+        .await
+        .unwrap();
+    costs.push((OracleCommand::ClaimRewards, sim.last_compute_units()));
+
+    // The fully loaded update, on top of the extensions above: the quote pays its fee, and the
+    // aggregation of every publisher, with the defaults of the aggregation config, is approved by
+    // the sanity program, reported to the status observer and pushed to the emitter
+    sim.set_aggregation_config(MAX_CI_DIVISOR, 0, 1, &authority)
+        .await
+        .unwrap();
+    costs.push((
+        OracleCommand::SetAggregationConfig,
+        sim.last_compute_units(),
+    ));
+    sim.set_update_fee(&price_keypair, 5_000).await.unwrap();
+    sim.warp_to_slot(10).await.unwrap();
+    for (i, publisher) in others.iter().enumerate() {
+        sim.upd_price_with_update_fee(publisher, price_keypair.pubkey(), sane_quote(i))
+            .await
+            .unwrap();
+    }
+    // Set once the quotes of the previous slot, which the sanity program rejects, are aggregated
+    sim.set_sanity_program(&price_keypair, sim.sanity_program_id)
+        .await
+        .unwrap();
+    sim.set_status_observer(&price_keypair, sim.status_observer_id)
+        .await
+        .unwrap();
+    sim.set_accumulator_emitter(sim.accumulator_emitter_id)
+        .await
+        .unwrap();
+    sim.set_shared_settings(&price_keypair, true).await.unwrap();
+    costs.push((OracleCommand::SetSharedSettings, sim.last_compute_units()));
+    sim.warp_to_slot(11).await.unwrap();
+    let cost = sim
+        .upd_price_with_all_accounts_compute_units(
+            last,
+            price_keypair.pubkey(),
+            sane_quote(others.len()),
+        )
+        .await
+        .unwrap();
+    costs.push((OracleCommand::UpdPrice, cost));
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.pub_slot_, 11);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert!(price_data.agg_.price_ <= SANITY_MAX_PRICE);
+
+    // Removing every publisher
+    sim.warp_to_slot(12).await.unwrap();
+    sim.prune_publishers(&price_keypair, 1, false)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::PrunePublishers, sim.last_compute_units()));
+
+    sim.del_price_with_registry(&product_keypair, &price_keypair)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::DelPrice, sim.last_compute_units()));
+    sim.del_product(&mapping_keypair, &product_keypair)
+        .await
+        .unwrap();
+    costs.push((OracleCommand::DelProduct, sim.last_compute_units()));
+
+    let upgrade_authority = copy_keypair(&sim.upgrade_authority);
+    sim.upd_permissions(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::UpdPermissions.into(),
+            master_authority:        authority.pubkey(),
+            data_curation_authority: authority.pubkey(),
+            security_authority:      authority.pubkey(),
+        },
+        &upgrade_authority,
+    )
+    .await
+    .unwrap();
+    costs.push((OracleCommand::UpdPermissions, sim.last_compute_units()));
+
+    for (command, cost) in costs {
+        let budget = command.compute_budget().unwrap();
+        let command = command.to_i32().unwrap();
+        assert!(
+            cost <= budget as u64,
+            "Command {command} used {cost} CU, more than its budget of {budget} CU"
+        );
+    }
+}
+
+fn any_price() -> AssertPriceConditionArgs {
+    AssertPriceConditionArgs {
+        header:             OracleCommand::AssertPriceCondition.into(),
+        min_price:          i64::MIN,
+        max_price:          i64::MAX,
+        max_conf_ratio_bps: u64::MAX,
+        max_age:            u64::MAX,
+        required_status:    PC_STATUS_UNKNOWN,
+        unused_:            0,
+    }
+}
+
+/// Same as `quote`, with prices that the sanity program of the simulator approves.
+fn sane_quote(i: usize) -> Quote {
+    Quote {
+        price: SANITY_MAX_PRICE - 101 + ((i * 37) % 101) as i64,
+        ..quote(i)
+    }
+}
+
+fn quote(i: usize) -> Quote {
+    Quote {
+        // Spread out and unsorted so that the sort does real work
+        price:      1_000_000 + ((i * 37) % 101) as i64,
+        confidence: 10 + (i % 7) as u64,
+        status:     PC_STATUS_TRADING,
+    }
+}
@@ -0,0 +1,71 @@
+use {
+    crate::{
+        accounts::{
+            ConfFloor,
+            PermissionAccount,
+        },
+        instruction::OracleCommand,
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+/// Two slots where both publishers quote `price` and `conf`, leaving the aggregate of the first.
+fn post_quotes(feed: &mut FeedSimulator, price: i64, conf: u64) {
+    let quotes = [Some((price, conf)), Some((price, conf))];
+    feed.post_updates(&[&quotes, &quotes]);
+}
+
+#[test]
+fn test_conf_floor() {
+    let mut feed = FeedSimulator::new(2).with_extension::<ConfFloor>();
+    assert_eq!(
+        feed.set_conf_floor(0, 10_001),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    // A floor of zeros leaves the aggregates as computed
+    post_quotes(&mut feed, 1_000, 1);
+    assert_eq!(feed.price_data().agg_.conf_, 1);
+
+    feed.set_conf_floor(5, 0).unwrap();
+    post_quotes(&mut feed, 1_000, 1);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 1_000);
+    assert_eq!(price_data.agg_.conf_, 5);
+
+    // The larger of the floors applies, relative to the absolute price
+    feed.set_conf_floor(5, 100).unwrap();
+    post_quotes(&mut feed, -1_000, 1);
+    assert_eq!(feed.price_data().agg_.conf_, 10);
+    post_quotes(&mut feed, 200, 1);
+    assert_eq!(feed.price_data().agg_.conf_, 5);
+
+    // Wider aggregates are untouched
+    post_quotes(&mut feed, 1_000, 50);
+    assert_eq!(feed.price_data().agg_.conf_, 50);
+}
+
+#[test]
+fn test_conf_floor_apply() {
+    let conf_floor = ConfFloor {
+        min_conf:     0,
+        min_conf_bps: 10_000,
+    };
+    assert_eq!(conf_floor.apply(i64::MIN, 0), 1 << 63);
+    assert_eq!(conf_floor.apply(i64::MAX, u64::MAX), u64::MAX);
+    assert_eq!(ConfFloor::zeroed().apply(i64::MIN, 3), 3);
+}
+
+#[test]
+fn test_conf_floor_authority() {
+    let curator = Pubkey::new_unique();
+    let mut permissions = PermissionAccount::zeroed();
+    permissions.data_curation_authority = curator;
+    assert!(permissions.is_authorized(&curator, OracleCommand::SetConfFloor));
+    assert!(!permissions.is_authorized(&curator, OracleCommand::SetMinPub));
+}
@@ -0,0 +1,170 @@
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            PublisherRegistryAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_VERSION,
+        },
+        config_diff::{
+            diff_cluster_configs,
+            read_cluster_config,
+            ConfigDifference,
+        },
+        genesis::{
+            build_genesis_accounts,
+            core_feeds,
+            GenesisAccount,
+            GenesisConfig,
+            GenesisFeed,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+    std::collections::BTreeMap,
+};
+
+#[test]
+fn test_config_diff() {
+    let authority = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let left_config = GenesisConfig {
+        program_id:              Pubkey::new_unique(),
+        master_authority:        authority,
+        data_curation_authority: authority,
+        security_authority:      authority,
+        seed:                    "pythnet".to_string(),
+        publishers:              vec![publisher],
+        feeds:                   core_feeds(),
+    };
+
+    // The same configuration deployed under other keys doesn't drift
+    let right_config = GenesisConfig {
+        program_id: Pubkey::new_unique(),
+        seed: "mainnet".to_string(),
+        ..left_config.clone()
+    };
+    let left = read_config(&build_genesis_accounts(&left_config).unwrap());
+    let right = read_config(&build_genesis_accounts(&right_config).unwrap());
+    assert_eq!(left.len(), right.len());
+    assert_eq!(left["feeds.Crypto.BTC/USD.exponent"], "-8");
+    assert_eq!(left["feeds.Crypto.BTC/USD.aggregation_delay"], "1");
+    assert_eq!(left["feeds.Crypto.BTC/USD.product.quote_currency"], "USD");
+    assert_eq!(diff_cluster_configs(&left, &right), vec![]);
+
+    let security_authority = Pubkey::new_unique();
+    let new_publisher = Pubkey::new_unique();
+    let mut feeds = core_feeds();
+    feeds[1].min_pub = 3;
+    feeds.pop();
+    feeds.push(GenesisFeed {
+        symbol:     "Crypto.PYTH/USD".to_string(),
+        attributes: vec![
+            ("asset_type".to_string(), "Crypto".to_string()),
+            ("quote_currency".to_string(), "USD".to_string()),
+        ],
+        exponent:   -10,
+        min_pub:    1,
+    });
+    let right_config = GenesisConfig {
+        security_authority,
+        publishers: vec![publisher, new_publisher],
+        feeds,
+        ..right_config
+    };
+    let mut right_accounts = build_genesis_accounts(&right_config).unwrap();
+    right_accounts.push(publisher_registry(&[new_publisher]));
+    let right = read_config(&right_accounts);
+
+    let differences = diff_cluster_configs(&left, &right);
+    let find = |field: &str| {
+        differences
+            .iter()
+            .find(|difference| difference.field == field)
+            .cloned()
+    };
+    assert_eq!(
+        find("permissions.security_authority"),
+        Some(ConfigDifference {
+            field: "permissions.security_authority".to_string(),
+            left:  Some(authority.to_string()),
+            right: Some(security_authority.to_string()),
+        })
+    );
+    assert_eq!(
+        find(&format!("publisher_registry.{new_publisher}")),
+        Some(ConfigDifference {
+            field: format!("publisher_registry.{new_publisher}"),
+            left:  None,
+            right: Some("approved".to_string()),
+        })
+    );
+    assert_eq!(
+        find("feeds.Crypto.ETH/USD.min_pub"),
+        Some(ConfigDifference {
+            field: "feeds.Crypto.ETH/USD.min_pub".to_string(),
+            left:  Some("1".to_string()),
+            right: Some("3".to_string()),
+        })
+    );
+    assert_eq!(
+        find(&format!("feeds.Crypto.SOL/USD.publishers.{new_publisher}"))
+            .unwrap()
+            .right,
+        Some("listed".to_string())
+    );
+    assert_eq!(find("feeds.Crypto.USDC/USD.exponent").unwrap().right, None);
+    assert_eq!(
+        find("feeds.Crypto.PYTH/USD.exponent").unwrap(),
+        ConfigDifference {
+            field: "feeds.Crypto.PYTH/USD.exponent".to_string(),
+            left:  None,
+            right: Some("-10".to_string()),
+        }
+    );
+    // Fields that match aren't reported
+    assert_eq!(find("feeds.Crypto.BTC/USD.exponent"), None);
+    assert_eq!(
+        find(&format!("feeds.Crypto.BTC/USD.publishers.{publisher}")),
+        None
+    );
+
+    // Differences are sorted by field
+    assert!(differences
+        .windows(2)
+        .all(|pair| pair[0].field < pair[1].field));
+}
+
+fn read_config(accounts: &[GenesisAccount]) -> BTreeMap<String, String> {
+    read_cluster_config(
+        accounts
+            .iter()
+            .map(|account| (account.pubkey, account.data.as_slice())),
+    )
+}
+
+fn publisher_registry(publishers: &[Pubkey]) -> GenesisAccount {
+    let registry = PublisherRegistryAccount {
+        header:         AccountHeader {
+            magic_number: PC_MAGIC,
+            version:      PC_VERSION,
+            account_type: PublisherRegistryAccount::ACCOUNT_TYPE,
+            size:         PublisherRegistryAccount::entry_offset(publishers.len()) as u32,
+        },
+        num_publishers: publishers.len() as u32,
+        unused_:        0,
+    };
+    let mut data = bytes_of(&registry).to_vec();
+    for publisher in publishers {
+        data.extend_from_slice(publisher.as_ref());
+    }
+    GenesisAccount {
+        pubkey: Pubkey::new_unique(),
+        lamports: 0,
+        data,
+        owner: Pubkey::new_unique(),
+    }
+}
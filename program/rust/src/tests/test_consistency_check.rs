@@ -0,0 +1,225 @@
+use {
+    crate::{
+        accounts::{
+            ConsistencyCheckAccount,
+            ConsistencyFlag,
+            ConsistencyKind,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_checked,
+        tests::test_utils::{
+            AccountSetup,
+            FeedSimulator,
+        },
+        OracleError,
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+};
+
+fn check_data(check: &mut AccountSetup) -> ConsistencyCheckAccount {
+    *load_checked::<ConsistencyCheckAccount>(&check.as_account_info(), PC_VERSION).unwrap()
+}
+
+/// Publish `prices` to the feeds in `slot` and aggregate them in the next slot.
+fn aggregate(feeds: &mut [&mut FeedSimulator], slot: u64, prices: &[i64]) {
+    for (feed, price) in feeds.iter_mut().zip(prices) {
+        feed.warp_to_slot(slot);
+        feed.publish(0, *price, 1).unwrap();
+        feed.warp_to_slot(slot + 1);
+        feed.publish(0, *price, 1).unwrap();
+    }
+}
+
+#[test]
+fn test_deviation_bps() {
+    let mut check = ConsistencyCheckAccount::zeroed();
+    assert_eq!(check.deviation_bps(&[(1, 0), (1, 0)]), None);
+    assert!(check.linked_feeds().is_empty());
+
+    // 50000.00 and 0.0000200000 are inverses
+    check.kind = ConsistencyKind::Inverse as u32;
+    check.feeds = [Pubkey::new_unique(); 3];
+    assert_eq!(check.linked_feeds().len(), 2);
+    assert_eq!(
+        check.deviation_bps(&[(5_000_000, -2), (200_000, -10)]),
+        Some(0)
+    );
+    assert_eq!(
+        check.deviation_bps(&[(5_000_000, -2), (201_000, -10)]),
+        Some(50)
+    );
+    assert_eq!(
+        check.deviation_bps(&[(5_000_000, -2), (199_000, -10)]),
+        Some(50)
+    );
+    assert_eq!(check.deviation_bps(&[(5_000_000, -2)]), None);
+    assert_eq!(
+        check.deviation_bps(&[(i64::MAX, 0), (i64::MAX, 0)]),
+        Some(u64::MAX)
+    );
+
+    // BTC/ETH 20.000 * ETH/USD 2500.00 = BTC/USD 50000.0
+    check.kind = ConsistencyKind::Triangular as u32;
+    assert_eq!(check.linked_feeds().len(), 3);
+    assert_eq!(
+        check.deviation_bps(&[(20_000, -3), (250_000, -2), (500_000, -1)]),
+        Some(0)
+    );
+    assert_eq!(
+        check.deviation_bps(&[(20_000, -3), (250_000, -2), (490_000, -1)]),
+        Some(204)
+    );
+    // Exponents too far apart to scale compare the truncated prices
+    assert_eq!(
+        check.deviation_bps(&[(1, 30), (1, 30), (i64::MAX, 0)]),
+        Some(u64::MAX)
+    );
+    assert_eq!(check.deviation_bps(&[(0, 0), (5, 0), (0, -3)]), Some(0));
+}
+
+#[test]
+fn test_consistency_check() {
+    let mut btc_usd = FeedSimulator::new(1)
+        .with_exponent(-2)
+        .with_extension::<ConsistencyFlag>();
+    let mut usd_btc = btc_usd
+        .new_sibling(1)
+        .with_exponent(-10)
+        .with_extension::<ConsistencyFlag>();
+    let mut check = AccountSetup::new::<ConsistencyCheckAccount>(&btc_usd.program_id());
+
+    assert_eq!(
+        FeedSimulator::init_consistency_check(
+            &mut [&mut btc_usd],
+            &mut check,
+            ConsistencyKind::Inverse,
+            50
+        ),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+    FeedSimulator::init_consistency_check(
+        &mut [&mut btc_usd, &mut usd_btc],
+        &mut check,
+        ConsistencyKind::Inverse,
+        50,
+    )
+    .unwrap();
+    let data = check_data(&mut check);
+    assert_eq!(data.kind, ConsistencyKind::Inverse as u32);
+    assert_eq!(data.tolerance_bps, 50);
+    assert_eq!(
+        data.linked_feeds(),
+        [btc_usd.price_key(), usd_btc.price_key()]
+    );
+    assert!(!btc_usd.extension::<ConsistencyFlag>().is_raised());
+    // The check can't be initialized twice
+    assert_eq!(
+        FeedSimulator::init_consistency_check(
+            &mut [&mut btc_usd, &mut usd_btc],
+            &mut check,
+            ConsistencyKind::Inverse,
+            50,
+        ),
+        Err(OracleError::InvalidFreshAccount.into())
+    );
+
+    // Nothing is checked before the feeds trade
+    FeedSimulator::check_consistency(&mut [&mut btc_usd, &mut usd_btc], &mut check).unwrap();
+    assert_eq!(check_data(&mut check).last_check_slot, 0);
+
+    // The feeds are listed in the order of the check
+    assert_eq!(
+        FeedSimulator::check_consistency(&mut [&mut usd_btc, &mut btc_usd], &mut check),
+        Err(OracleError::InvalidConsistencyFeeds.into())
+    );
+    assert_eq!(
+        FeedSimulator::check_consistency(&mut [&mut btc_usd], &mut check),
+        Err(OracleError::InvalidConsistencyFeeds.into())
+    );
+
+    aggregate(&mut [&mut btc_usd, &mut usd_btc], 10, &[5_000_000, 200_000]);
+    FeedSimulator::check_consistency(&mut [&mut btc_usd, &mut usd_btc], &mut check).unwrap();
+    let data = check_data(&mut check);
+    assert_eq!(data.last_check_slot, 11);
+    assert_eq!(data.last_deviation_bps, 0);
+    assert_eq!(data.num_violations, 0);
+    assert!(!usd_btc.extension::<ConsistencyFlag>().is_raised());
+
+    // 5% off raises the flag on both feeds
+    aggregate(&mut [&mut btc_usd, &mut usd_btc], 20, &[5_000_000, 210_000]);
+    FeedSimulator::check_consistency(&mut [&mut btc_usd, &mut usd_btc], &mut check).unwrap();
+    let data = check_data(&mut check);
+    assert_eq!(data.last_deviation_bps, 500);
+    assert_eq!(data.num_violations, 1);
+    for feed in [&btc_usd, &usd_btc] {
+        let flag = feed.extension::<ConsistencyFlag>();
+        assert_eq!(flag.check, check.key);
+        assert_eq!(flag.slot, 21);
+    }
+
+    // Within the tolerance again, the flags are cleared
+    aggregate(&mut [&mut btc_usd, &mut usd_btc], 30, &[5_000_000, 200_100]);
+    FeedSimulator::check_consistency(&mut [&mut btc_usd, &mut usd_btc], &mut check).unwrap();
+    let data = check_data(&mut check);
+    assert_eq!(data.last_deviation_bps, 5);
+    assert_eq!(data.num_violations, 1);
+    assert!(!btc_usd.extension::<ConsistencyFlag>().is_raised());
+    assert!(!usd_btc.extension::<ConsistencyFlag>().is_raised());
+}
+
+#[test]
+fn test_triangular_consistency_check() {
+    let mut btc_eth = FeedSimulator::new(1)
+        .with_exponent(-3)
+        .with_extension::<ConsistencyFlag>();
+    let mut eth_usd = btc_eth
+        .new_sibling(1)
+        .with_exponent(-2)
+        .with_extension::<ConsistencyFlag>();
+    let mut btc_usd = btc_eth
+        .new_sibling(1)
+        .with_exponent(-1)
+        .with_extension::<ConsistencyFlag>();
+    let mut usd_btc = btc_eth
+        .new_sibling(1)
+        .with_exponent(-10)
+        .with_extension::<ConsistencyFlag>();
+    let program_id = btc_eth.program_id();
+    let mut triangle = AccountSetup::new::<ConsistencyCheckAccount>(&program_id);
+    let mut inverse = AccountSetup::new::<ConsistencyCheckAccount>(&program_id);
+
+    FeedSimulator::init_consistency_check(
+        &mut [&mut btc_eth, &mut eth_usd, &mut btc_usd],
+        &mut triangle,
+        ConsistencyKind::Triangular,
+        100,
+    )
+    .unwrap();
+    FeedSimulator::init_consistency_check(
+        &mut [&mut btc_usd, &mut usd_btc],
+        &mut inverse,
+        ConsistencyKind::Inverse,
+        100,
+    )
+    .unwrap();
+
+    aggregate(
+        &mut [&mut btc_eth, &mut eth_usd, &mut btc_usd, &mut usd_btc],
+        10,
+        &[20_000, 250_000, 490_000, 204_000],
+    );
+    FeedSimulator::check_consistency(
+        &mut [&mut btc_eth, &mut eth_usd, &mut btc_usd],
+        &mut triangle,
+    )
+    .unwrap();
+    assert_eq!(check_data(&mut triangle).last_deviation_bps, 204);
+    assert_eq!(btc_usd.extension::<ConsistencyFlag>().check, triangle.key);
+
+    // Another check of the feed passing leaves the flag raised by the triangle
+    FeedSimulator::check_consistency(&mut [&mut btc_usd, &mut usd_btc], &mut inverse).unwrap();
+    assert_eq!(check_data(&mut inverse).num_violations, 0);
+    assert_eq!(btc_usd.extension::<ConsistencyFlag>().check, triangle.key);
+    assert!(!usd_btc.extension::<ConsistencyFlag>().is_raised());
+}
@@ -1,9 +1,17 @@
 use {
     crate::{
-        accounts::ProductAccount,
+        accounts::{
+            FeedTombstoneMessage,
+            PriceAccount,
+            ProductAccount,
+            PythOracleSerialize,
+        },
         tests::pyth_simulator::PythSimulator,
     },
-    solana_program::pubkey::Pubkey,
+    solana_program::{
+        hash::hashv,
+        pubkey::Pubkey,
+    },
     solana_sdk::signer::Signer,
 };
 
@@ -53,3 +61,52 @@ async fn test_del_price() {
 
     assert!(product2_data.first_price_account == Pubkey::default());
 }
+
+#[tokio::test]
+async fn test_del_price_tombstone() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product = sim.add_product(&mapping_keypair).await.unwrap();
+    let price = sim.add_price(&product, -8).await.unwrap();
+    let publishers = [Pubkey::new_unique(), Pubkey::new_unique()];
+    for publisher in publishers {
+        sim.add_publisher(&price, publisher).await.unwrap();
+    }
+    let message_buffer = sim.create_message_buffer();
+
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price.pubkey())
+        .await
+        .unwrap();
+    let components: Vec<&[u8]> = price_data.comp_[..2]
+        .iter()
+        .map(|component| component.pub_.as_ref())
+        .collect();
+    assert_eq!(price_data.publishers_hash(), hashv(&components).to_bytes());
+
+    sim.del_price_with_message_buffer(&product, &price, message_buffer)
+        .await
+        .unwrap();
+    assert!(sim.get_account(price.pubkey()).await.is_none());
+
+    let buffer = sim.get_account(message_buffer).await.unwrap().data;
+    // The `put_all` instruction is borsh encoded: discriminator, key, then the messages
+    assert_eq!(&buffer[8..40], price.pubkey().as_ref());
+    assert_eq!(u32::from_le_bytes(buffer[40..44].try_into().unwrap()), 1);
+    let message_len = u32::from_le_bytes(buffer[44..48].try_into().unwrap()) as usize;
+    let message = &buffer[48..48 + message_len];
+
+    let expected = FeedTombstoneMessage {
+        feed_id:         price.pubkey().to_bytes(),
+        publish_time:    i64::from_be_bytes(message[33..41].try_into().unwrap()),
+        publish_slot:    u64::from_be_bytes(message[41..49].try_into().unwrap()),
+        exponent:        -8,
+        price:           price_data.agg_.price_,
+        conf:            price_data.agg_.conf_,
+        status:          price_data.agg_.status_,
+        aggregate_slot:  price_data.agg_.pub_slot_,
+        num_updates:     0,
+        publishers_hash: price_data.publishers_hash(),
+    };
+    assert_eq!(message, expected.to_bytes());
+}
@@ -0,0 +1,76 @@
+use {
+    crate::{
+        accounts::{
+            get_successor,
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        tests::pyth_simulator::PythSimulator,
+    },
+    solana_program::instruction::InstructionError,
+    solana_sdk::{
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_deprecate_price() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let old_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let new_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+
+    sim.deprecate_price(&old_keypair, new_keypair.pubkey())
+        .await
+        .unwrap();
+    let old_data = sim
+        .get_account_data_as::<PriceAccount>(old_keypair.pubkey())
+        .await
+        .unwrap();
+    assert!(old_data.flags.contains(PriceAccountFlags::DEPRECATED));
+    let old_account = sim.get_account(old_keypair.pubkey()).await.unwrap();
+    assert_eq!(get_successor(&old_account.data), Some(new_keypair.pubkey()));
+    let new_account = sim.get_account(new_keypair.pubkey()).await.unwrap();
+    assert_eq!(get_successor(&new_account.data), None);
+
+    // Redirects can't form a cycle, nor point a feed to itself
+    let invalid_argument = TransactionError::InstructionError(1, InstructionError::InvalidArgument);
+    assert_eq!(
+        sim.deprecate_price(&new_keypair, old_keypair.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        invalid_argument
+    );
+    assert_eq!(
+        sim.deprecate_price(&new_keypair, new_keypair.pubkey())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        invalid_argument
+    );
+
+    // A deprecated feed can be pointed to another successor
+    let other_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    sim.deprecate_price(&old_keypair, other_keypair.pubkey())
+        .await
+        .unwrap();
+    let old_account = sim.get_account(old_keypair.pubkey()).await.unwrap();
+    assert_eq!(
+        get_successor(&old_account.data),
+        Some(other_keypair.pubkey())
+    );
+
+    // Clones of a deprecated feed aren't deprecated
+    let clone_keypair = sim
+        .clone_price(&product_keypair, old_keypair.pubkey())
+        .await
+        .unwrap();
+    let clone_data = sim
+        .get_account_data_as::<PriceAccount>(clone_keypair.pubkey())
+        .await
+        .unwrap();
+    assert!(!clone_data.flags.contains(PriceAccountFlags::DEPRECATED));
+}
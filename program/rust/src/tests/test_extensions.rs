@@ -0,0 +1,260 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            extensions_end,
+            get_extension,
+            get_extension_mut,
+            init_extension,
+            iter_extensions,
+            set_extension,
+            BidAsk,
+            ExtensionHeader,
+            ExtensionInfo,
+            ExtensionType,
+            PriceAccount,
+            PriceHistoryPointer,
+            PythAccount,
+            ReadStats,
+            EXTENSIONS_OFFSET,
+            EXTENSIONS_RESERVED_SPACE,
+        },
+        c_oracle_header::PC_VERSION,
+        error::OracleError,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+#[test]
+fn test_extensions() {
+    let mut data = vec![
+        0u8;
+        EXTENSIONS_OFFSET
+            + extension_space::<PriceHistoryPointer>()
+            + extension_space::<BidAsk>()
+    ];
+
+    // A fresh account has no extensions
+    assert_eq!(iter_extensions(&data).count(), 0);
+    assert_eq!(extensions_end(&data), Ok(EXTENSIONS_OFFSET));
+    assert!(get_extension::<BidAsk>(&data).unwrap().is_none());
+    assert!(get_extension_mut::<BidAsk>(&mut data).unwrap().is_none());
+
+    let history_account = Pubkey::new_unique();
+    init_extension::<PriceHistoryPointer>(&mut data)
+        .unwrap()
+        .history_account = history_account;
+    {
+        let bid_ask = init_extension::<BidAsk>(&mut data).unwrap();
+        bid_ask.bid_price = 99;
+        bid_ask.ask_price = 101;
+    }
+
+    assert_eq!(
+        get_extension::<PriceHistoryPointer>(&data)
+            .unwrap()
+            .unwrap()
+            .history_account,
+        history_account
+    );
+    assert_eq!(
+        get_extension::<BidAsk>(&data).unwrap().unwrap().bid_price,
+        99
+    );
+    assert_eq!(
+        get_extension::<BidAsk>(&data).unwrap().unwrap().ask_price,
+        101
+    );
+    assert_eq!(extensions_end(&data), Ok(data.len()));
+
+    assert_eq!(
+        iter_extensions(&data)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        vec![
+            ExtensionInfo {
+                extension_type: ExtensionType::HistoryPointer as u32,
+                offset:         EXTENSIONS_OFFSET + size_of::<ExtensionHeader>(),
+                length:         size_of::<PriceHistoryPointer>(),
+            },
+            ExtensionInfo {
+                extension_type: ExtensionType::BidAsk as u32,
+                offset:         EXTENSIONS_OFFSET
+                    + extension_space::<PriceHistoryPointer>()
+                    + size_of::<ExtensionHeader>(),
+                length:         size_of::<BidAsk>(),
+            },
+        ]
+    );
+
+    // Initializing an existing extension returns it untouched
+    assert_eq!(init_extension::<BidAsk>(&mut data).unwrap().ask_price, 101);
+    get_extension_mut::<BidAsk>(&mut data)
+        .unwrap()
+        .unwrap()
+        .ask_price = 102;
+    assert_eq!(
+        get_extension::<BidAsk>(&data).unwrap().unwrap().ask_price,
+        102
+    );
+}
+
+#[test]
+fn test_set_extension() {
+    let mut data = vec![0u8; PriceAccount::NEW_ACCOUNT_SPACE];
+    let history_account = Pubkey::new_unique();
+    set_extension(&mut data, PriceHistoryPointer { history_account }).unwrap();
+    set_extension(
+        &mut data,
+        BidAsk {
+            bid_price: 99,
+            ask_price: 101,
+        },
+    )
+    .unwrap();
+    let end = extensions_end(&data).unwrap();
+    assert!(end <= EXTENSIONS_OFFSET + EXTENSIONS_RESERVED_SPACE);
+
+    // Setting an extension again overwrites it in place
+    set_extension(
+        &mut data,
+        BidAsk {
+            bid_price: 100,
+            ask_price: 102,
+        },
+    )
+    .unwrap();
+    assert_eq!(extensions_end(&data), Ok(end));
+    assert_eq!(iter_extensions(&data).count(), 2);
+    let bid_ask = get_extension::<BidAsk>(&data).unwrap().unwrap();
+    assert_eq!((bid_ask.bid_price, bid_ask.ask_price), (100, 102));
+    assert_eq!(
+        get_extension::<PriceHistoryPointer>(&data)
+            .unwrap()
+            .unwrap()
+            .history_account,
+        history_account
+    );
+
+    // Without reserved space, the account must grow first
+    let mut data = vec![0u8; EXTENSIONS_OFFSET];
+    assert_eq!(
+        set_extension(&mut data, PriceHistoryPointer { history_account }),
+        Err(OracleError::AccountTooSmall)
+    );
+}
+
+#[test]
+fn test_read_stats() {
+    let mut data = vec![0u8; EXTENSIONS_OFFSET + extension_space::<ReadStats>()];
+    let read_stats = init_extension::<ReadStats>(&mut data).unwrap();
+
+    read_stats.record_read(10);
+    read_stats.record_read(10);
+    assert_eq!(
+        (
+            read_stats.epoch,
+            read_stats.reads,
+            read_stats.previous_epoch_reads
+        ),
+        (10, 2, 0)
+    );
+
+    // Reads of the previous epoch are kept for one epoch
+    read_stats.record_read(11);
+    assert_eq!(
+        (
+            read_stats.epoch,
+            read_stats.reads,
+            read_stats.previous_epoch_reads
+        ),
+        (11, 1, 2)
+    );
+    read_stats.record_read(13);
+    assert_eq!(
+        (
+            read_stats.epoch,
+            read_stats.reads,
+            read_stats.previous_epoch_reads
+        ),
+        (13, 1, 0)
+    );
+}
+
+#[test]
+fn test_extensions_account_too_small() {
+    let mut data = vec![0u8; EXTENSIONS_OFFSET + extension_space::<BidAsk>() - 1];
+    assert_eq!(
+        init_extension::<BidAsk>(&mut data).err(),
+        Some(OracleError::AccountTooSmall)
+    );
+    assert_eq!(iter_extensions(&data).count(), 0);
+}
+
+#[test]
+fn test_extensions_malformed() {
+    let mut data = vec![0u8; EXTENSIONS_OFFSET + 64];
+
+    // Entries of unknown types (e.g. written by a newer program) are skipped
+    let unknown = ExtensionHeader {
+        extension_type: u32::MAX,
+        length:         4,
+    };
+    data[EXTENSIONS_OFFSET..EXTENSIONS_OFFSET + size_of::<ExtensionHeader>()]
+        .copy_from_slice(bytes_of(&unknown));
+    init_extension::<BidAsk>(&mut data).unwrap().bid_price = 7;
+    assert_eq!(iter_extensions(&data).count(), 2);
+    assert_eq!(
+        get_extension::<BidAsk>(&data).unwrap().unwrap().bid_price,
+        7
+    );
+
+    // An entry with the wrong length for its type is rejected
+    let mut data = vec![0u8; EXTENSIONS_OFFSET + 64];
+    let wrong_length = ExtensionHeader {
+        extension_type: ExtensionType::BidAsk as u32,
+        length:         8,
+    };
+    data[EXTENSIONS_OFFSET..EXTENSIONS_OFFSET + size_of::<ExtensionHeader>()]
+        .copy_from_slice(bytes_of(&wrong_length));
+    assert_eq!(
+        get_extension::<BidAsk>(&data).err(),
+        Some(OracleError::InvalidExtension)
+    );
+
+    // An entry overflowing the account is rejected
+    let mut data = vec![0u8; EXTENSIONS_OFFSET + 64];
+    let overflowing = ExtensionHeader {
+        extension_type: ExtensionType::HistoryPointer as u32,
+        length:         64,
+    };
+    data[EXTENSIONS_OFFSET..EXTENSIONS_OFFSET + size_of::<ExtensionHeader>()]
+        .copy_from_slice(bytes_of(&overflowing));
+    assert_eq!(
+        iter_extensions(&data).collect::<Vec<_>>(),
+        vec![Err(OracleError::InvalidExtension)]
+    );
+    assert_eq!(
+        init_extension::<BidAsk>(&mut data).err(),
+        Some(OracleError::InvalidExtension)
+    );
+}
+
+#[test]
+fn test_extensions_do_not_touch_price_account() {
+    let program_id = Pubkey::new_unique();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    // A price account without room for extensions simply has none
+    let data = price_account.try_borrow_data().unwrap();
+    assert_eq!(data.len(), EXTENSIONS_OFFSET);
+    assert_eq!(iter_extensions(&data).count(), 0);
+    assert!(get_extension::<PriceHistoryPointer>(&data)
+        .unwrap()
+        .is_none());
+}
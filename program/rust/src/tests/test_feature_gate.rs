@@ -0,0 +1,204 @@
+use {
+    crate::{
+        accounts::{
+            feature_name,
+            AggregationDelay,
+            Feature,
+            FeatureGateAccount,
+            FeatureSwitch,
+            PublisherLink,
+            PublisherWeightCap,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+fn switch(feature: Feature, enabled: bool) -> FeatureSwitch {
+    FeatureSwitch {
+        name: feature_name(feature.name()),
+        enabled: u8::from(enabled),
+        pending_enabled: u8::from(enabled),
+        ..FeatureSwitch::zeroed()
+    }
+}
+
+#[test]
+fn test_feature_switch() {
+    let mut switch = switch(Feature::WeightedAggregation, true);
+    assert!(switch.is_enabled(0));
+
+    // The switch keeps its state during the grace period
+    switch.flip(false, 100, 10);
+    assert!(switch.is_enabled(109));
+    assert!(!switch.is_enabled(110));
+
+    // A flip during the grace period replaces the pending one
+    switch.flip(true, 105, 10);
+    assert!(switch.is_enabled(110));
+    switch.flip(false, 106, 10);
+    switch.flip(true, 107, 0);
+    assert!(switch.is_enabled(107));
+    assert!(switch.is_enabled(116));
+
+    assert_eq!(
+        Feature::from_name(&feature_name("same_slot_aggregation")),
+        Some(Feature::SameSlotAggregation)
+    );
+    assert_eq!(Feature::from_name(&feature_name("unknown")), None);
+}
+
+#[test]
+fn test_weighted_aggregation_feature() {
+    // Publishers 0 and 1 are controlled by the same entity
+    let mut feed = FeedSimulator::new(3)
+        .with_extension::<PublisherWeightCap>()
+        .follow_shared_settings();
+    let links = [PublisherLink {
+        publisher: feed.publisher_key(1),
+        entity:    feed.publisher_key(0),
+    }];
+    feed.set_publisher_weight_cap(1, &links).unwrap();
+
+    // Features without a switch keep their default
+    feed.post_updates(&[
+        &[Some((100, 1)), Some((120, 1)), Some((200, 1))],
+        &[Some((100, 1))],
+    ]);
+    assert_eq!(feed.price_data().num_qt_, 2);
+
+    // Without weighted aggregation the cap is ignored
+    feed.pass_feature_gate(&[switch(Feature::WeightedAggregation, false)]);
+    feed.post_updates(&[
+        &[Some((100, 1)), Some((120, 1)), Some((200, 1))],
+        &[Some((100, 1))],
+    ]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_qt_, 3);
+    assert_eq!(price_data.agg_.price_, 120);
+
+    // Feeds that don't follow the shared settings take the default of every feature
+    let mut feed = FeedSimulator::new(3).with_extension::<PublisherWeightCap>();
+    let links = [PublisherLink {
+        publisher: feed.publisher_key(1),
+        entity:    feed.publisher_key(0),
+    }];
+    feed.set_publisher_weight_cap(1, &links).unwrap();
+    feed.pass_feature_gate(&[switch(Feature::WeightedAggregation, false)]);
+    feed.post_updates(&[
+        &[Some((100, 1)), Some((120, 1)), Some((200, 1))],
+        &[Some((100, 1))],
+    ]);
+    assert_eq!(feed.price_data().num_qt_, 2);
+}
+
+#[test]
+fn test_same_slot_aggregation_feature() {
+    let mut feed = FeedSimulator::new(2)
+        .with_extension::<AggregationDelay>()
+        .follow_shared_settings();
+    feed.set_aggregation_delay(0).unwrap();
+
+    // Without same slot aggregation a delay of 0 is the default delay
+    feed.pass_feature_gate(&[switch(Feature::SameSlotAggregation, false)]);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+
+    // A switch flipped in a later slot isn't effective yet
+    let mut pending = switch(Feature::SameSlotAggregation, false);
+    pending.flip(true, 10, 2);
+    feed.pass_feature_gate(&[pending]);
+    feed.warp_to_slot(11);
+    feed.publish(0, 110, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.price_, 100);
+
+    feed.warp_to_slot(12);
+    feed.publish(0, 120, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 120);
+    assert_eq!(price_data.agg_.pub_slot_, 12);
+}
+
+#[tokio::test]
+async fn test_set_feature() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let feature_gate = sim.get_feature_gate_pubkey();
+    let name = Feature::WeightedAggregation.name();
+
+    // Only the master authority can flip switches
+    let attacker = Keypair::new();
+    sim.airdrop(&attacker.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.set_feature(name, false, &attacker)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    assert_eq!(
+        sim.set_feature("", false, &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InvalidArgument)
+    );
+
+    sim.set_feature_grace_period(5, &authority).await.unwrap();
+    let feature_gate_data = sim
+        .get_account_data_as::<FeatureGateAccount>(feature_gate)
+        .await
+        .unwrap();
+    assert_eq!(feature_gate_data.grace_period, 5);
+    assert_eq!(feature_gate_data.num_features, 0);
+
+    sim.warp_to_slot(10).await.unwrap();
+    sim.set_feature(name, false, &authority).await.unwrap();
+    sim.set_feature("future_feature", true, &authority)
+        .await
+        .unwrap();
+    let data = sim.get_account(feature_gate).await.unwrap().data;
+    let features = FeatureGateAccount::features(&data).unwrap();
+    assert_eq!(features.len(), 2);
+    assert_eq!(data.len(), FeatureGateAccount::entry_offset(2));
+    // Known features start from their default, the others start disabled
+    assert!(features[0].is_enabled(14));
+    assert!(!features[0].is_enabled(15));
+    assert!(!features[1].is_enabled(14));
+    assert!(features[1].is_enabled(15));
+    assert!(FeatureGateAccount::is_enabled(&data, Feature::WeightedAggregation, 14).unwrap());
+    assert!(!FeatureGateAccount::is_enabled(&data, Feature::WeightedAggregation, 15).unwrap());
+    assert!(FeatureGateAccount::is_enabled(&data, Feature::SameSlotAggregation, 15).unwrap());
+
+    // Flipping the switch again only updates it
+    sim.warp_to_slot(20).await.unwrap();
+    sim.set_feature_grace_period(0, &authority).await.unwrap();
+    sim.set_feature(name, true, &authority).await.unwrap();
+    let data = sim.get_account(feature_gate).await.unwrap().data;
+    assert_eq!(FeatureGateAccount::features(&data).unwrap().len(), 2);
+    assert!(FeatureGateAccount::is_enabled(&data, Feature::WeightedAggregation, 20).unwrap());
+}
@@ -0,0 +1,95 @@
+use {
+    crate::{
+        accounts::FeedKind,
+        c_oracle_header::{
+            PC_MAX_SEND_LATENCY,
+            PC_MAX_SEND_LATENCY_NAV,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    num_traits::FromPrimitive,
+};
+
+#[test]
+fn test_feed_kind() {
+    assert_eq!(FeedKind::from_u32(0), None);
+    assert_eq!(FeedKind::from_u32(1), Some(FeedKind::Spot));
+    assert_eq!(FeedKind::from_u32(2), Some(FeedKind::Funding));
+    assert_eq!(FeedKind::from_u32(3), Some(FeedKind::Nav));
+    assert_eq!(FeedKind::from_u32(4), Some(FeedKind::Index));
+    assert_eq!(FeedKind::from_u32(5), None);
+
+    let mut feed = FeedSimulator::new(0);
+    assert_eq!(feed.price_data().feed_kind(), None);
+    assert_eq!(
+        feed.price_data().max_latency(),
+        u64::from(PC_MAX_SEND_LATENCY)
+    );
+
+    // NAV feeds ignore the configured latency
+    let mut feed = FeedSimulator::new(0).with_feed_kind(FeedKind::Nav);
+    assert_eq!(feed.price_data().feed_kind(), Some(FeedKind::Nav));
+    assert_eq!(
+        feed.price_data().max_latency(),
+        u64::from(PC_MAX_SEND_LATENCY_NAV)
+    );
+}
+
+#[test]
+fn test_funding_feed() {
+    // A funding rate close to 0 with a wide confidence interval would be ignored on a spot feed
+    let mut spot = FeedSimulator::new(3).with_feed_kind(FeedKind::Spot);
+    spot.warp_to_slot(10);
+    spot.publish(0, -5, 100).unwrap();
+    assert_eq!(
+        spot.price_data().comp_[0].latest_.status_,
+        PC_STATUS_IGNORED
+    );
+
+    let mut funding = FeedSimulator::new(3).with_feed_kind(FeedKind::Funding);
+    funding.warp_to_slot(10);
+    for (publisher, rate) in [-5, -3, 2].into_iter().enumerate() {
+        funding.publish(publisher, rate, 100).unwrap();
+    }
+    assert!(funding.price_data().comp_[..3]
+        .iter()
+        .all(|component| component.latest_.status_ == PC_STATUS_TRADING));
+
+    funding.warp_to_slot(11);
+    funding.publish(0, -4, 100).unwrap();
+    let price_data = funding.price_data();
+    assert_eq!(price_data.num_qt_, 3);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert!((-5..=2).contains(&price_data.agg_.price_));
+}
+
+#[test]
+fn test_nav_feed() {
+    for (feed_kind, expected_num_qt) in [(FeedKind::Spot, 0), (FeedKind::Nav, 3)] {
+        let mut feed = FeedSimulator::new(3).with_feed_kind(feed_kind);
+        feed.warp_to_slot(10);
+        for publisher in 0..3 {
+            feed.publish(publisher, 100, 1).unwrap();
+        }
+
+        // A day later the quotes of the NAV feed still count
+        feed.warp_to_slot(10 + u64::from(PC_MAX_SEND_LATENCY_NAV));
+        feed.publish(0, 101, 1).unwrap();
+        let price_data = feed.price_data();
+        assert_eq!(price_data.num_qt_, expected_num_qt, "{feed_kind:?}");
+        if feed_kind == FeedKind::Nav {
+            assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+            assert_eq!(price_data.agg_.price_, 100);
+        }
+    }
+
+    // Older quotes are stale
+    let mut feed = FeedSimulator::new(3).with_feed_kind(FeedKind::Nav);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11 + u64::from(PC_MAX_SEND_LATENCY_NAV));
+    feed.publish(1, 101, 1).unwrap();
+    assert_eq!(feed.price_data().num_qt_, 0);
+}
@@ -0,0 +1,135 @@
+use {
+    crate::{
+        accounts::{
+            FeedRegistryAccount,
+            PriceAccount,
+            PythAccount,
+        },
+        error::OracleError,
+        tests::pyth_simulator::PythSimulator,
+    },
+    solana_program::{
+        entrypoint::MAX_PERMITTED_DATA_INCREASE,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_sdk::signer::Signer,
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_feed_registry() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product1 = sim.add_product(&mapping_keypair).await.unwrap();
+    let product2 = sim.add_product(&mapping_keypair).await.unwrap();
+    let registry = sim.get_feed_registry_pubkey();
+
+    // The registry is created with the first registered feed
+    let price1 = sim.add_price_with_registry(&product1, -8).await.unwrap();
+    assert_eq!(get_feed_index(&mut sim, price1.pubkey()).await, 1);
+    assert_eq!(
+        get_entry(&mut sim, registry, 1).await,
+        Some(price1.pubkey())
+    );
+
+    // Feeds added without the registry aren't registered, until they are reinitialized with it
+    let price2 = sim.add_price(&product2, -8).await.unwrap();
+    assert_eq!(get_feed_index(&mut sim, price2.pubkey()).await, 2);
+    assert_eq!(get_entry(&mut sim, registry, 2).await, None);
+    sim.init_price_with_registry(&price2, -5).await.unwrap();
+    assert_eq!(
+        get_entry(&mut sim, registry, 2).await,
+        Some(price2.pubkey())
+    );
+
+    let registry_account = sim.get_account(registry).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&registry_account));
+    assert_eq!(
+        registry_account.data.len(),
+        FeedRegistryAccount::MINIMUM_SIZE + 2 * size_of::<Pubkey>()
+    );
+    assert!(Rent::default().is_exempt(registry_account.lamports, registry_account.data.len()));
+    assert_eq!(
+        FeedRegistryAccount::get_entry(&registry_account.data, 0),
+        Err(OracleError::InvalidFeedIndex)
+    );
+
+    // Feeds without a feed index aren't registered
+    let price3 = sim.add_price(&product2, -8).await.unwrap();
+    sim.set_feed_index(price3.pubkey(), 0).await;
+    sim.init_price_with_registry(&price3, -5).await.unwrap();
+    assert_eq!(get_entry(&mut sim, registry, 3).await, None);
+
+    // Deleting a feed clears its entry only
+    sim.del_price_with_registry(&product1, &price1)
+        .await
+        .unwrap();
+    assert_eq!(get_entry(&mut sim, registry, 1).await, None);
+    assert_eq!(
+        get_entry(&mut sim, registry, 2).await,
+        Some(price2.pubkey())
+    );
+
+    // Deleting without the registry leaves the entry behind
+    sim.del_price(&product2, &price2).await.unwrap();
+    assert_eq!(
+        get_entry(&mut sim, registry, 2).await,
+        Some(price2.pubkey())
+    );
+}
+
+#[tokio::test]
+async fn test_resize_feed_registry() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product = sim.add_product(&mapping_keypair).await.unwrap();
+    let registry = sim.get_feed_registry_pubkey();
+
+    // A cluster that handed out 1000 feed indexes before the registry existed
+    sim.add_price(&product, -8).await.unwrap();
+    sim.set_last_feed_index(1_000).await;
+
+    // The entry of the next feed is further than an instruction can grow the registry
+    assert_eq!(
+        sim.add_price_with_registry(&product, -8)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::AccountTooSmall.into()
+    );
+
+    // The registry is grown in steps up to the entry of the last feed index
+    let target_size = FeedRegistryAccount::entry_offset(1_000).unwrap() + size_of::<Pubkey>();
+    let mut expected_size = FeedRegistryAccount::MINIMUM_SIZE;
+    while expected_size < target_size {
+        sim.resize_feed_registry().await.unwrap();
+        expected_size = target_size.min(expected_size + MAX_PERMITTED_DATA_INCREASE);
+        let registry_account = sim.get_account(registry).await.unwrap();
+        assert_eq!(registry_account.data.len(), expected_size);
+        assert!(Rent::default().is_exempt(registry_account.lamports, expected_size));
+    }
+    assert_eq!(
+        sim.resize_feed_registry().await.unwrap_err().unwrap(),
+        OracleError::NoNeedToResize.into()
+    );
+
+    let price = sim.add_price_with_registry(&product, -8).await.unwrap();
+    assert_eq!(get_feed_index(&mut sim, price.pubkey()).await, 1_001);
+    assert_eq!(
+        get_entry(&mut sim, registry, 1_001).await,
+        Some(price.pubkey())
+    );
+}
+
+async fn get_feed_index(sim: &mut PythSimulator, price: Pubkey) -> u32 {
+    sim.get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .feed_index
+}
+
+async fn get_entry(sim: &mut PythSimulator, registry: Pubkey, feed_index: u32) -> Option<Pubkey> {
+    let registry_account = sim.get_account(registry).await.unwrap();
+    FeedRegistryAccount::get_entry(&registry_account.data, feed_index).unwrap()
+}
@@ -0,0 +1,109 @@
+use {
+    crate::{
+        accounts::{
+            find_product_by_symbol,
+            get_product_metadata,
+            FeedRegistryAccount,
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_account_data,
+        error::OracleError,
+        genesis::{
+            build_genesis_accounts,
+            core_feeds,
+            GenesisConfig,
+        },
+    },
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    std::collections::HashMap,
+};
+
+#[test]
+fn test_genesis() {
+    let config = GenesisConfig {
+        program_id:              Pubkey::new_unique(),
+        master_authority:        Pubkey::new_unique(),
+        data_curation_authority: Pubkey::new_unique(),
+        security_authority:      Pubkey::new_unique(),
+        seed:                    "test".to_string(),
+        publishers:              vec![Pubkey::new_unique(), Pubkey::new_unique()],
+        feeds:                   core_feeds(),
+    };
+    let accounts = build_genesis_accounts(&config).unwrap();
+    assert_eq!(accounts.len(), 3 + 2 * config.feeds.len());
+    for account in &accounts {
+        assert_eq!(account.owner, config.program_id);
+        assert!(Rent::default().is_exempt(account.lamports, account.data.len()));
+    }
+    let data: HashMap<Pubkey, &[u8]> = accounts
+        .iter()
+        .map(|account| (account.pubkey, account.data.as_slice()))
+        .collect();
+
+    let permissions = load_account_data::<PermissionAccount>(&accounts[0].data).unwrap();
+    assert_eq!(permissions.header.version, PC_VERSION);
+    assert_eq!(permissions.master_authority, config.master_authority);
+    assert_eq!(
+        permissions.data_curation_authority,
+        config.data_curation_authority
+    );
+    assert_eq!(permissions.security_authority, config.security_authority);
+
+    let mapping_key = accounts[1].pubkey;
+    let mapping = load_account_data::<MappingAccount>(&accounts[1].data).unwrap();
+    let registry_data = &accounts.last().unwrap().data;
+    assert_eq!(mapping.number_of_products as usize, config.feeds.len());
+
+    for (i, feed) in config.feeds.iter().enumerate() {
+        let product_key =
+            find_product_by_symbol(mapping_key, &feed.symbol, |key| data.get(key).copied())
+                .unwrap()
+                .unwrap();
+        assert_eq!(product_key, accounts[2 + 2 * i].pubkey);
+        assert_eq!(
+            get_product_metadata(data[&product_key], "quote_currency").unwrap(),
+            Some(&b"USD"[..])
+        );
+
+        let product = load_account_data::<ProductAccount>(data[&product_key]).unwrap();
+        let price_key = product.first_price_account;
+        assert_eq!(price_key, accounts[3 + 2 * i].pubkey);
+        let price = load_account_data::<PriceAccount>(data[&price_key]).unwrap();
+        assert_eq!(price.product_account, product_key);
+        assert_eq!(price.exponent, feed.exponent);
+        assert_eq!(price.min_pub_, feed.min_pub);
+        assert_eq!(price.feed_index as usize, i + 1);
+        assert_eq!(price.num_ as usize, config.publishers.len());
+        assert_eq!(
+            FeedRegistryAccount::get_entry(registry_data, price.feed_index),
+            Ok(Some(price_key))
+        );
+    }
+
+    // Same config, same accounts
+    assert_eq!(build_genesis_accounts(&config).unwrap(), accounts);
+    let reseeded = build_genesis_accounts(&GenesisConfig {
+        seed: "other".to_string(),
+        ..config.clone()
+    })
+    .unwrap();
+    assert_eq!(reseeded[0].pubkey, accounts[0].pubkey);
+    assert_ne!(reseeded[1].pubkey, accounts[1].pubkey);
+
+    // Invalid feeds are rejected by the program
+    let mut invalid_config = config;
+    invalid_config.feeds[0].attributes.clear();
+    assert_eq!(
+        build_genesis_accounts(&invalid_config),
+        Err(ProgramError::from(OracleError::MissingProductMetadataKey))
+    );
+}
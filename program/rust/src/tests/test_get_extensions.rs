@@ -0,0 +1,105 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            init_extension,
+            BidAsk,
+            ExtensionHeader,
+            ExtensionType,
+            PriceAccount,
+            PriceHistoryPointer,
+            PythAccount,
+            EXTENSIONS_OFFSET,
+        },
+        c_oracle_header::PC_VERSION,
+        error::OracleError,
+        instruction::{
+            CommandHeader,
+            ExtensionEntry,
+            OracleCommand,
+        },
+        processor::{
+            get_extension_entries,
+            process_instruction,
+        },
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+#[test]
+fn test_get_extensions() {
+    let program_id = Pubkey::new_unique();
+    let cmd: CommandHeader = OracleCommand::GetExtensions.into();
+    let instruction_data = bytes_of::<CommandHeader>(&cmd);
+
+    let mut price_setup = AccountSetup::new_with_size::<PriceAccount>(
+        &program_id,
+        EXTENSIONS_OFFSET + extension_space::<BidAsk>() + extension_space::<PriceHistoryPointer>(),
+    );
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    price_account.is_writable = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    assert!(process_instruction(&program_id, &[price_account.clone()], instruction_data).is_ok());
+    assert_eq!(
+        get_extension_entries(&price_account.try_borrow_data().unwrap()),
+        Ok(vec![])
+    );
+
+    {
+        let mut data = price_account.try_borrow_mut_data().unwrap();
+        init_extension::<BidAsk>(&mut data).unwrap();
+        init_extension::<PriceHistoryPointer>(&mut data).unwrap();
+    }
+
+    assert!(process_instruction(&program_id, &[price_account.clone()], instruction_data).is_ok());
+    assert_eq!(
+        get_extension_entries(&price_account.try_borrow_data().unwrap()),
+        Ok(vec![
+            ExtensionEntry {
+                extension_type: ExtensionType::BidAsk as u32,
+                offset:         (EXTENSIONS_OFFSET + size_of::<ExtensionHeader>()) as u32,
+                length:         size_of::<BidAsk>() as u32,
+            },
+            ExtensionEntry {
+                extension_type: ExtensionType::HistoryPointer as u32,
+                offset:         (EXTENSIONS_OFFSET
+                    + extension_space::<BidAsk>()
+                    + size_of::<ExtensionHeader>()) as u32,
+                length:         size_of::<PriceHistoryPointer>() as u32,
+            },
+        ])
+    );
+
+    // Wrong number of accounts
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[price_account.clone(), price_account.clone()],
+            instruction_data
+        ),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+
+    // Price account not owned by the program
+    let mut foreign_setup = AccountSetup::new::<PriceAccount>(&Pubkey::new_unique());
+    let foreign_account = foreign_setup.as_account_info();
+    PriceAccount::initialize(&foreign_account, PC_VERSION).unwrap();
+    assert_eq!(
+        process_instruction(&program_id, &[foreign_account], instruction_data),
+        Err(OracleError::InvalidReadableAccount.into())
+    );
+
+    // Not a price account
+    let mut funding_setup = AccountSetup::new_funding();
+    let mut not_price_account = funding_setup.as_account_info();
+    not_price_account.owner = &program_id;
+    assert_eq!(
+        process_instruction(&program_id, &[not_price_account], instruction_data),
+        Err(OracleError::AccountTooSmall.into())
+    );
+}
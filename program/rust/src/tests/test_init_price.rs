@@ -1,9 +1,15 @@
 use {
     crate::{
         accounts::{
+            extension_space,
+            get_extension,
+            AverageMode,
             PermissionAccount,
             PriceAccount,
+            PriceAccountFlags,
             PythAccount,
+            SlotWeightedTwap,
+            SLOT_WEIGHTED_TWAP_BUCKETS,
         },
         c_oracle_header::{
             MAX_NUM_DECIMALS,
@@ -12,7 +18,10 @@ use {
         deserialize::load_checked,
         instruction::{
             InitPriceArgs,
+            InitPriceConfig,
+            InitPriceConfigArgs,
             OracleCommand,
+            INIT_PRICE_CONFIG_VERSION,
         },
         processor::process_instruction,
         tests::test_utils::AccountSetup,
@@ -171,6 +180,130 @@ fn test_init_price() {
             ],
             instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::InvalidExponent.into())
     );
 }
+
+#[test]
+fn test_init_price_config() {
+    let program_id = Pubkey::new_unique();
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    // Room for the slot-weighted TWAP, the price account doesn't grow in unit tests
+    let mut price_setup = AccountSetup::new_with_size::<PriceAccount>(
+        &program_id,
+        PriceAccount::MINIMUM_SIZE + extension_space::<SlotWeightedTwap>(),
+    );
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.price_type = 1;
+        price_data.min_pub_ = 7;
+        price_data.flags = PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::ALLOW_ZERO_CI;
+    }
+
+    let init_price = |config: InitPriceConfig| {
+        let args = InitPriceConfigArgs {
+            header: OracleCommand::InitPrice.into(),
+            exponent: -5,
+            price_type: 1,
+            config,
+        };
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                permissions_account.clone(),
+            ],
+            bytes_of(&args),
+        )
+    };
+    let config = InitPriceConfig {
+        version:           INIT_PRICE_CONFIG_VERSION,
+        average_mode:      AverageMode::Ema as u32,
+        min_pub:           3,
+        max_latency:       10,
+        flags:             PriceAccountFlags::ISOLATED_UPDATES.bits(),
+        unused_:           [0; 5],
+        twap_window_slots: 0,
+    };
+
+    // Unknown versions, flags that governance doesn't configure and invalid averages are rejected
+    for invalid in [
+        InitPriceConfig {
+            version: INIT_PRICE_CONFIG_VERSION + 1,
+            ..config
+        },
+        InitPriceConfig {
+            flags: PriceAccountFlags::ACCUMULATOR_V2.bits(),
+            ..config
+        },
+        InitPriceConfig {
+            average_mode: AverageMode::SlotWeighted as u32,
+            ..config
+        },
+    ] {
+        assert_eq!(init_price(invalid), Err(ProgramError::InvalidArgument));
+    }
+
+    init_price(config).unwrap();
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.exponent, -5);
+        assert_eq!(price_data.min_pub_, 3);
+        assert_eq!(price_data.max_latency_, 10);
+        // The flags governance doesn't configure are kept
+        assert_eq!(
+            price_data.flags,
+            PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::ISOLATED_UPDATES
+        );
+    }
+    // The feed publishes the EMA without the extension
+    assert!(matches!(
+        get_extension::<SlotWeightedTwap>(&price_account.try_borrow_data().unwrap()),
+        Ok(None)
+    ));
+
+    let window_slots = 4 * SLOT_WEIGHTED_TWAP_BUCKETS as u64;
+    init_price(InitPriceConfig {
+        average_mode: AverageMode::SlotWeighted as u32,
+        twap_window_slots: window_slots,
+        ..config
+    })
+    .unwrap();
+    {
+        let data = price_account.try_borrow_data().unwrap();
+        let twap = get_extension::<SlotWeightedTwap>(&data).unwrap().unwrap();
+        assert_eq!(twap.mode, AverageMode::SlotWeighted as u32);
+        assert_eq!(twap.window_slots(), window_slots);
+    }
+
+    // The arguments alone keep the configuration
+    let args = InitPriceArgs {
+        header:     OracleCommand::InitPrice.into(),
+        exponent:   -6,
+        price_type: 1,
+    };
+    process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of(&args),
+    )
+    .unwrap();
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.exponent, -6);
+    assert_eq!(price_data.min_pub_, 3);
+    assert_eq!(price_data.max_latency_, 10);
+}
@@ -0,0 +1,99 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccountFlags,
+            PublisherStake,
+        },
+        error::OracleError,
+        genesis::{
+            build_genesis_accounts,
+            core_feeds,
+            GenesisConfig,
+        },
+        inspect::{
+            format_price_flags,
+            inspect_account,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::pubkey::Pubkey,
+    std::collections::HashMap,
+};
+
+#[test]
+fn test_inspect() {
+    let authority = Pubkey::new_unique();
+    let publisher = Pubkey::new_unique();
+    let config = GenesisConfig {
+        program_id:              Pubkey::new_unique(),
+        master_authority:        authority,
+        data_curation_authority: authority,
+        security_authority:      authority,
+        seed:                    "pythnet".to_string(),
+        publishers:              vec![publisher],
+        feeds:                   core_feeds(),
+    };
+    let accounts = build_genesis_accounts(&config).unwrap();
+    let inspect = |data: &[u8]| -> HashMap<String, String> {
+        inspect_account(data).unwrap().into_iter().collect()
+    };
+
+    let permissions = inspect(&accounts[0].data);
+    assert_eq!(permissions["header.account_type"], "5 (permissions)");
+    assert_eq!(permissions["master_authority"], authority.to_string());
+
+    let mapping = inspect(&accounts[1].data);
+    assert_eq!(mapping["header.account_type"], "1 (mapping)");
+    assert_eq!(
+        mapping["number_of_products"],
+        config.feeds.len().to_string()
+    );
+    assert_eq!(mapping["products_list[0]"], accounts[2].pubkey.to_string());
+
+    let product = inspect(&accounts[2].data);
+    assert_eq!(product["metadata.symbol"], "Crypto.BTC/USD");
+    assert_eq!(
+        product["first_price_account"],
+        accounts[3].pubkey.to_string()
+    );
+
+    let price = inspect(&accounts[3].data);
+    assert_eq!(price["header.account_type"], "3 (price)");
+    assert_eq!(price["exponent"], "-8");
+    assert_eq!(price["components[0].publisher"], publisher.to_string());
+    assert_eq!(price["agg.status"], "0 (unknown)");
+}
+
+#[test]
+fn test_inspect_price() {
+    let mut feed = FeedSimulator::new(1).with_extension::<PublisherStake>();
+    feed.advance_slot();
+    feed.publish(0, 100, 5).unwrap();
+    feed.set_flags(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::COMMUNITY);
+
+    let fields = inspect_account(feed.price_account_data()).unwrap();
+    let price: HashMap<String, String> = fields.iter().cloned().collect();
+    assert_eq!(price["flags"], "0b01000001 (ACCUMULATOR_V2 | COMMUNITY)");
+    assert_eq!(price["components[0].latest.price"], "100");
+    assert_eq!(price["components[0].latest.status"], "1 (trading)");
+    assert_eq!(
+        price["components[0].latest.publisher_status"],
+        "1 (trading)"
+    );
+    assert!(price["extensions[0]"].starts_with("18 (PublisherStake), 2600 bytes: "));
+    // Fields follow the layout of the account
+    assert_eq!(fields[0].0, "header.magic_number");
+    assert_eq!(fields.last().unwrap().0, "extensions[0]");
+
+    // Flags of later versions of the program are shown as bits
+    assert_eq!(
+        format_price_flags(PriceAccountFlags::from_bits_retain(0b1000_0100)),
+        "ALLOW_ZERO_CI | 0b10000000"
+    );
+    assert_eq!(format_price_flags(PriceAccountFlags::empty()), "none");
+
+    assert_eq!(
+        inspect_account(&[0; 8]).unwrap_err(),
+        OracleError::InvalidAccountHeader
+    );
+}
@@ -0,0 +1,124 @@
+extern crate test_generator;
+
+use {
+    crate::{
+        accounts::{
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        instruction::load_command_header_checked,
+        processor::process_instruction,
+        tests::test_utils::{
+            AccountSetup,
+            TestClock,
+        },
+    },
+    serde::Deserialize,
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashMap,
+        fs::File,
+    },
+    test_generator::test_resources,
+};
+
+// Replays the instruction encodings of a previous release against the current processor, so that
+// changes of the wire format don't go unnoticed. Each fixture holds the instructions of one
+// release, in the order they are executed over a single set of accounts: a funding account that
+// is the master authority, a mapping, product and price account, the permission account, the
+// clock and a publisher whose key is `[1; 32]`. Instructions without accounts are only decoded.
+// Errors are the codes returned to clients, removed instructions fail with
+// `UnrecognizedInstruction` (603). A fixture for a release should never change once added.
+#[test_resources("program/rust/test_data/instructions/*.json")]
+fn test_instruction_fixtures(input_path_raw: &str) {
+    // For some reason these tests have a different working directory than the macro.
+    let input_path = input_path_raw.replace("program/rust/", "");
+
+    let file = File::open(&input_path).expect("Test file not found");
+    let fixture: Fixture = serde_json::from_reader(&file).expect("Unable to parse JSON");
+
+    let program_id = Pubkey::new_unique();
+    let mut setups = vec![
+        ("funding", AccountSetup::new_funding()),
+        (
+            "publisher",
+            AccountSetup::new_funding_with_key(Pubkey::new_from_array([1; 32])),
+        ),
+        ("mapping", AccountSetup::new::<MappingAccount>(&program_id)),
+        ("product", AccountSetup::new::<ProductAccount>(&program_id)),
+        ("price", AccountSetup::new::<PriceAccount>(&program_id)),
+        ("permissions", AccountSetup::new_permission(&program_id)),
+        ("clock", AccountSetup::new_clock()),
+    ];
+    let mut accounts: HashMap<&str, AccountInfo> = setups
+        .iter_mut()
+        .map(|(label, setup)| (*label, setup.as_account_info()))
+        .collect();
+
+    PermissionAccount::initialize(&accounts["permissions"], PC_VERSION)
+        .unwrap()
+        .master_authority = *accounts["funding"].key;
+    let clock = accounts.get_mut("clock").unwrap();
+    clock.is_signer = false;
+    clock.is_writable = false;
+
+    for (i, instruction) in fixture.instructions.iter().enumerate() {
+        let context = format!(
+            "Instruction {} ({}) of {}",
+            i, instruction.command, fixture.version
+        );
+        let data = hex::decode(&instruction.data).expect("Invalid instruction data");
+        assert_eq!(
+            load_command_header_checked(&data).map(|command| format!("{:?}", command)),
+            Ok(instruction.command.clone()),
+            "{}",
+            context
+        );
+
+        let account_labels = match &instruction.accounts {
+            Some(account_labels) => account_labels,
+            None => continue,
+        };
+        if let Some(slot) = instruction.slot {
+            TestClock::new(accounts["clock"].clone()).warp_to_slot(slot);
+        }
+        let instruction_accounts: Vec<AccountInfo> = account_labels
+            .iter()
+            .map(|label| accounts[label.as_str()].clone())
+            .collect();
+        assert_eq!(
+            process_instruction(&program_id, &instruction_accounts, &data).map_err(u64::from),
+            instruction.error.map_or(Ok(()), Err),
+            "{}",
+            context
+        );
+    }
+}
+
+#[derive(Deserialize)]
+struct Fixture {
+    /// Release the encodings were captured from
+    version:      String,
+    instructions: Vec<FixtureInstruction>,
+}
+
+#[derive(Deserialize)]
+struct FixtureInstruction {
+    /// Name of the `OracleCommand` the data decodes to
+    command:  String,
+    /// Hex encoded instruction data
+    data:     String,
+    /// Slot of the clock when the instruction is executed
+    slot:     Option<u64>,
+    /// Labels of the accounts of the instruction, see above
+    accounts: Option<Vec<String>>,
+    /// Error code returned by the instruction, if any
+    error:    Option<u64>,
+}
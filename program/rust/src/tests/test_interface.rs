@@ -0,0 +1,395 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            get_successor,
+            init_extension,
+            AccountHeader,
+            MappingAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PriceComponent,
+            PriceCumulative,
+            PriceEma,
+            PriceInfo,
+            PriceSuccessor,
+            ProductAccount,
+            PythAccount,
+            QuoteOrder,
+            EXTENSIONS_OFFSET,
+        },
+        c_oracle_header::{
+            PC_ACCTYPE_MAPPING,
+            PC_ACCTYPE_PRICE,
+            PC_ACCTYPE_PRODUCT,
+            PC_MAGIC,
+            PC_MAP_TABLE_SIZE,
+            PC_MAX_SEND_LATENCY,
+            PC_NUM_COMP,
+            PC_NUM_COMP_PYTHNET,
+            PC_PROD_ACC_SIZE,
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+            PC_VERSION,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::{
+        bytes_of,
+        from_bytes_mut,
+        Zeroable,
+    },
+    pyth_oracle_interface as interface,
+    solana_program::pubkey::Pubkey,
+    std::mem::size_of,
+};
+
+/// The interface crate restates the layouts of the program, they must not drift apart.
+#[test]
+fn test_interface_layouts() {
+    assert_eq!(
+        size_of::<interface::AccountHeader>(),
+        size_of::<AccountHeader>()
+    );
+    assert_eq!(
+        size_of::<interface::MappingAccount>(),
+        size_of::<MappingAccount>()
+    );
+    assert_eq!(
+        size_of::<interface::ProductAccount>(),
+        size_of::<ProductAccount>()
+    );
+    assert_eq!(
+        size_of::<interface::PriceAccount>(),
+        size_of::<PriceAccount>()
+    );
+    assert_eq!(
+        size_of::<interface::PriceComponent>(),
+        size_of::<PriceComponent>()
+    );
+    assert_eq!(size_of::<interface::PriceInfo>(), size_of::<PriceInfo>());
+    assert_eq!(size_of::<interface::PriceEma>(), size_of::<PriceEma>());
+    assert_eq!(
+        size_of::<interface::PriceCumulative>(),
+        size_of::<PriceCumulative>()
+    );
+    // The summary ends where the components start
+    let price_data = PriceAccount::zeroed();
+    assert_eq!(
+        size_of::<interface::PriceSummary>(),
+        price_data.comp_.as_ptr() as usize - &price_data as *const PriceAccount as usize
+    );
+
+    assert_eq!(
+        <interface::MappingAccount as interface::PythAccount>::MINIMUM_SIZE,
+        MappingAccount::MINIMUM_SIZE
+    );
+    assert_eq!(
+        <interface::ProductAccount as interface::PythAccount>::MINIMUM_SIZE,
+        ProductAccount::MINIMUM_SIZE
+    );
+    assert_eq!(
+        <interface::PriceAccount as interface::PythAccount>::MINIMUM_SIZE,
+        PriceAccount::MINIMUM_SIZE
+    );
+
+    assert_eq!(interface::PC_MAGIC, PC_MAGIC);
+    assert_eq!(interface::PC_VERSION, PC_VERSION);
+    assert_eq!(interface::PC_ACCTYPE_MAPPING, PC_ACCTYPE_MAPPING);
+    assert_eq!(interface::PC_ACCTYPE_PRODUCT, PC_ACCTYPE_PRODUCT);
+    assert_eq!(interface::PC_ACCTYPE_PRICE, PC_ACCTYPE_PRICE);
+    assert_eq!(interface::PC_STATUS_UNKNOWN, PC_STATUS_UNKNOWN);
+    assert_eq!(interface::PC_STATUS_TRADING, PC_STATUS_TRADING);
+    assert_eq!(interface::PC_STATUS_HALTED, PC_STATUS_HALTED);
+    assert_eq!(interface::PC_STATUS_AUCTION, PC_STATUS_AUCTION);
+    assert_eq!(interface::PC_STATUS_IGNORED, PC_STATUS_IGNORED);
+    assert_eq!(interface::PC_NUM_COMP, PC_NUM_COMP);
+    assert_eq!(interface::PC_NUM_COMP_PYTHNET, PC_NUM_COMP_PYTHNET);
+    assert_eq!(interface::PC_MAP_TABLE_SIZE, PC_MAP_TABLE_SIZE);
+    assert_eq!(interface::PC_PROD_ACC_SIZE, PC_PROD_ACC_SIZE);
+    assert_eq!(interface::PC_MAX_SEND_LATENCY, PC_MAX_SEND_LATENCY);
+
+    assert_eq!(
+        interface::FLAG_ACCUMULATOR_V2,
+        PriceAccountFlags::ACCUMULATOR_V2.bits()
+    );
+    assert_eq!(
+        interface::FLAG_MESSAGE_BUFFER_CLEARED,
+        PriceAccountFlags::MESSAGE_BUFFER_CLEARED.bits()
+    );
+    assert_eq!(
+        interface::FLAG_ALLOW_ZERO_CI,
+        PriceAccountFlags::ALLOW_ZERO_CI.bits()
+    );
+    assert_eq!(
+        interface::FLAG_DEPRECATED,
+        PriceAccountFlags::DEPRECATED.bits()
+    );
+    assert_eq!(
+        interface::FLAG_ISOLATED_UPDATES,
+        PriceAccountFlags::ISOLATED_UPDATES.bits()
+    );
+    assert_eq!(
+        interface::FLAG_AGGREGATION_IN_PROGRESS,
+        PriceAccountFlags::AGGREGATION_IN_PROGRESS.bits()
+    );
+    assert_eq!(
+        interface::FLAG_COMMUNITY,
+        PriceAccountFlags::COMMUNITY.bits()
+    );
+}
+
+#[test]
+fn test_interface_price_account() {
+    let mut feed = FeedSimulator::new(2).with_exponent(-5);
+    feed.post_slot(&[Some((100, 1)), Some((102, 1))]);
+    feed.post_slot(&[Some((101, 1)), None]);
+    feed.post_slot(&[None, Some((103, 2))]);
+
+    let price_data = feed.price_data();
+    let price =
+        interface::load_checked::<interface::PriceAccount>(feed.price_account_data()).unwrap();
+    assert_eq!(price.exponent, -5);
+    assert_eq!(price.num_, 2);
+    assert_eq!(price.num_qt_, price_data.num_qt_);
+    assert_eq!(price.last_slot_, price_data.last_slot_);
+    assert_eq!(price.timestamp_, price_data.timestamp_);
+    assert_eq!(price.flags, price_data.flags.bits());
+    assert_eq!(price.prev_price_, price_data.prev_price_);
+    assert_eq!(price.agg_.price_, price_data.agg_.price_);
+    assert_eq!(price.agg_.conf_, price_data.agg_.conf_);
+    assert_eq!(price.agg_.status_, price_data.agg_.status_);
+    assert_eq!(price.twap_.val_, price_data.twap_.val_);
+    assert_eq!(
+        price.price_cumulative.price,
+        price_data.price_cumulative.price
+    );
+
+    let components: Vec<[u8; 32]> = price
+        .components()
+        .iter()
+        .map(|component| component.pub_)
+        .collect();
+    assert_eq!(
+        components,
+        vec![
+            feed.publisher_key(0).to_bytes(),
+            feed.publisher_key(1).to_bytes()
+        ]
+    );
+    assert_eq!(price.comp_[1].latest_.price_, 103);
+
+    let latest_price = price_data.get_latest_price();
+    assert_eq!(
+        price.get_latest_price(),
+        interface::LatestPrice {
+            price:        latest_price.price,
+            conf:         latest_price.conf,
+            exponent:     latest_price.exponent,
+            publish_slot: latest_price.publish_slot,
+            publish_time: latest_price.publish_time,
+        }
+    );
+    assert!(price
+        .get_price_no_older_than(latest_price.publish_slot + 1, 1)
+        .is_some());
+    assert!(price
+        .get_price_no_older_than(latest_price.publish_slot + 2, 1)
+        .is_none());
+    assert_eq!(price.get_price_unchecked(), price.get_latest_price());
+
+    let ema_price = price_data.get_ema_price();
+    assert_eq!(
+        price.get_ema_price(),
+        interface::LatestPrice {
+            price:        ema_price.price,
+            conf:         ema_price.conf,
+            exponent:     ema_price.exponent,
+            publish_slot: ema_price.publish_slot,
+            publish_time: ema_price.publish_time,
+        }
+    );
+    for exponent in [-12, -5, 0, 3] {
+        assert_eq!(
+            price
+                .get_latest_price()
+                .scale_to_exponent(exponent)
+                .map(|scaled| (scaled.price, scaled.conf)),
+            latest_price
+                .scale_to_exponent(exponent)
+                .map(|scaled| (scaled.price, scaled.conf))
+        );
+    }
+}
+
+#[test]
+fn test_interface_price_summary() {
+    let mut feed = FeedSimulator::new(2).with_exponent(-5);
+    feed.post_slot(&[Some((100, 1)), Some((102, 1))]);
+    feed.post_slot(&[Some((101, 1)), None]);
+
+    // Only the start of the account is fetched
+    let data = feed.price_account_data();
+    let summary_size = <interface::PriceSummary as interface::PythAccount>::MINIMUM_SIZE;
+    let summary =
+        interface::load_checked::<interface::PriceSummary>(&data[..summary_size]).unwrap();
+    let price = interface::load_checked::<interface::PriceAccount>(data).unwrap();
+    assert_eq!(bytes_of(summary), bytes_of(price.summary()));
+    assert_eq!(summary.exponent, -5);
+    assert_eq!(summary.num_, 2);
+    assert_eq!(summary.agg_, price.agg_);
+    assert_eq!(summary.get_latest_price(), price.get_latest_price());
+    assert_eq!(summary.get_ema_price(), price.get_ema_price());
+
+    assert_eq!(
+        interface::load_checked::<interface::PriceSummary>(&data[..summary_size - 1]).err(),
+        Some(interface::InterfaceError::AccountTooSmall)
+    );
+}
+
+#[test]
+fn test_interface_normalize_to_exponent() {
+    let price = |price: i64, conf: u64| interface::LatestPrice {
+        price,
+        conf,
+        exponent: -5,
+        publish_slot: 10,
+        publish_time: 100,
+    };
+    let normalize = |latest_price: interface::LatestPrice, exponent: i32| {
+        latest_price
+            .normalize_to_exponent(exponent)
+            .map(|normalized| (normalized.price, normalized.conf, normalized.exponent))
+    };
+
+    // The price rounds to the nearest value, half away from zero, the confidence rounds up
+    assert_eq!(normalize(price(12_349, 100), -3), Some((123, 1, -3)));
+    assert_eq!(normalize(price(12_350, 101), -3), Some((124, 2, -3)));
+    assert_eq!(normalize(price(-12_349, 0), -3), Some((-123, 0, -3)));
+    assert_eq!(normalize(price(-12_350, 1), -3), Some((-124, 1, -3)));
+    assert_eq!(
+        normalize(price(i64::MIN, 1), -4),
+        Some((i64::MIN / 10 - 1, 1, -4))
+    );
+    assert_eq!(
+        price(12_349, 100)
+            .scale_to_exponent(-3)
+            .map(|scaled| (scaled.price, scaled.conf)),
+        Some((123, 1))
+    );
+
+    // Scaling down is exact unless it overflows
+    assert_eq!(
+        normalize(price(12_345, 7), -8),
+        Some((12_345_000, 7_000, -8))
+    );
+    assert_eq!(normalize(price(12_345, 7), -5), Some((12_345, 7, -5)));
+    assert_eq!(normalize(price(i64::MAX / 10 + 1, 0), -6), None);
+    assert_eq!(normalize(price(1, u64::MAX / 10 + 1), -6), None);
+    assert_eq!(normalize(price(1, 0), i32::MIN), None);
+
+    // Beyond the range of the divisor every price rounds to zero
+    assert_eq!(normalize(price(i64::MAX, 1), 40), Some((0, 1, 40)));
+    assert_eq!(
+        normalize(price(i64::MIN, 0), i32::MAX),
+        Some((0, 0, i32::MAX))
+    );
+}
+
+#[test]
+fn test_interface_price_in_exponent() {
+    let mut feed = FeedSimulator::new(2).with_exponent(-5);
+    feed.post_slot(&[Some((100_049, 101)), Some((100_051, 101))]);
+    feed.post_slot(&[Some((100_050, 101)), None]);
+
+    let data = feed.price_account_data();
+    let price = interface::load_checked::<interface::PriceAccount>(data).unwrap();
+    let latest_price = price.get_latest_price();
+    let slot = latest_price.publish_slot;
+    let expected = latest_price.normalize_to_exponent(-3);
+    assert!(expected.is_some());
+    assert_eq!(price.get_price_in_exponent(-3, slot + 1, 1), expected);
+    assert_eq!(
+        interface::get_price_in_exponent(data, -3, slot + 1, 1),
+        Ok(expected)
+    );
+
+    // Stale prices and prices that don't fit aren't returned
+    assert_eq!(price.get_price_in_exponent(-3, slot + 2, 1), None);
+    assert_eq!(price.get_price_in_exponent(-30, slot, 0), None);
+    assert_eq!(
+        interface::get_price_in_exponent(&data[..8], -3, slot, 0),
+        Err(interface::InterfaceError::AccountTooSmall)
+    );
+}
+
+#[test]
+fn test_interface_checks() {
+    let feed = FeedSimulator::new(1);
+    let data = feed.price_account_data().to_vec();
+
+    assert_eq!(
+        interface::load_checked::<interface::ProductAccount>(&data).err(),
+        Some(interface::InterfaceError::InvalidAccountHeader)
+    );
+    assert_eq!(
+        interface::load_checked::<interface::PriceAccount>(&data[..data.len() - 1]).err(),
+        Some(interface::InterfaceError::AccountTooSmall)
+    );
+
+    // Only `load_account_data` reads other versions
+    let mut other_version = data.clone();
+    other_version[4..8].copy_from_slice(&(PC_VERSION + 1).to_le_bytes());
+    assert_eq!(
+        interface::load_checked::<interface::PriceAccount>(&other_version).err(),
+        Some(interface::InterfaceError::InvalidAccountHeader)
+    );
+    assert!(interface::load_account_data::<interface::PriceAccount>(&other_version).is_ok());
+
+    let mut other_magic = data;
+    other_magic[0] ^= 1;
+    assert_eq!(
+        interface::load_account_data::<interface::PriceAccount>(&other_magic).err(),
+        Some(interface::InterfaceError::InvalidAccountHeader)
+    );
+}
+
+#[test]
+fn test_interface_successor() {
+    let feed = FeedSimulator::new(1);
+    let successor = Pubkey::new_unique();
+    // The successor follows another extension
+    let mut data = feed.price_account_data().to_vec();
+    data.resize(
+        EXTENSIONS_OFFSET + extension_space::<QuoteOrder>() + extension_space::<PriceSuccessor>(),
+        0,
+    );
+    init_extension::<QuoteOrder>(&mut data).unwrap();
+    init_extension::<PriceSuccessor>(&mut data)
+        .unwrap()
+        .successor = successor;
+
+    // The extension only counts once the feed is flagged
+    assert_eq!(interface::get_successor(&data), Ok(None));
+    from_bytes_mut::<PriceAccount>(&mut data[..EXTENSIONS_OFFSET])
+        .flags
+        .insert(PriceAccountFlags::DEPRECATED);
+    assert_eq!(
+        interface::get_successor(&data),
+        Ok(Some(successor.to_bytes()))
+    );
+    assert_eq!(get_successor(&data), Some(successor));
+
+    // Truncated extension regions don't overflow
+    for len in EXTENSIONS_OFFSET..data.len() {
+        assert_eq!(interface::get_successor(&data[..len]), Ok(None));
+        assert_eq!(get_successor(&data[..len]), None);
+    }
+    assert_eq!(
+        interface::get_successor(&data[..EXTENSIONS_OFFSET - 1]),
+        Err(interface::InterfaceError::AccountTooSmall)
+    );
+}
@@ -0,0 +1,149 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        processor::{
+            ALLOW_BUNDLED_UPDATES,
+            REQUIRE_ISOLATED_UPDATES,
+        },
+        tests::pyth_simulator::{
+            PythSimulator,
+            Quote,
+        },
+        utils::COMPUTE_BUDGET_PROGRAM_ID,
+    },
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        system_instruction,
+    },
+    solana_sdk::{
+        compute_budget::{
+            self,
+            ComputeBudgetInstruction,
+        },
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_isolated_updates() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    let publisher = Keypair::new();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let quote = || Quote {
+        price:      100,
+        confidence: 1,
+        status:     PC_STATUS_TRADING,
+    };
+    let transfer = system_instruction::transfer(
+        &publisher.pubkey(),
+        &Pubkey::new_unique(),
+        LAMPORTS_PER_SOL / 10,
+    );
+    let compute_unit_limit = ComputeBudgetInstruction::set_compute_unit_limit(400_000);
+    assert_eq!(
+        compute_budget::id(),
+        Pubkey::from(COMPUTE_BUDGET_PROGRAM_ID)
+    );
+
+    // Feeds accept bundled updates by default
+    sim.upd_price_with_instructions_sysvar(
+        &publisher,
+        price,
+        quote(),
+        vec![],
+        vec![transfer.clone()],
+    )
+    .await
+    .unwrap();
+
+    sim.add_publisher(&price_keypair, Pubkey::from(REQUIRE_ISOLATED_UPDATES))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert!(price_data
+        .flags
+        .contains(PriceAccountFlags::ISOLATED_UPDATES));
+
+    // Instructions of other programs are rejected before and after the update, as are updates
+    // that can't be checked
+    sim.warp_to_slot(10).await.unwrap();
+    assert_eq!(
+        sim.upd_price_with_instructions_sysvar(
+            &publisher,
+            price,
+            quote(),
+            vec![],
+            vec![transfer.clone()]
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        OracleError::UpdateNotIsolated.into()
+    );
+    assert_eq!(
+        sim.upd_price_with_instructions_sysvar(
+            &publisher,
+            price,
+            quote(),
+            vec![transfer.clone()],
+            vec![]
+        )
+        .await
+        .unwrap_err()
+        .unwrap(),
+        TransactionError::InstructionError(
+            1,
+            InstructionError::Custom(OracleError::UpdateNotIsolated as u32)
+        )
+    );
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateNotIsolated.into()
+    );
+
+    // Compute budget instructions are allowed
+    sim.upd_price_with_instructions_sysvar(
+        &publisher,
+        price,
+        quote(),
+        vec![compute_unit_limit],
+        vec![],
+    )
+    .await
+    .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 10);
+
+    sim.add_publisher(&price_keypair, Pubkey::from(ALLOW_BUNDLED_UPDATES))
+        .await
+        .unwrap();
+    sim.warp_to_slot(20).await.unwrap();
+    sim.upd_price(&publisher, price, quote()).await.unwrap();
+}
@@ -0,0 +1,89 @@
+use {
+    crate::{
+        accounts::{
+            LandingLatency,
+            PublisherLandingLatency,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        tests::test_utils::FeedSimulator,
+        validator::{
+            compute_publisher_landing_stats,
+            PublisherLandingStats,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_landing_latency() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherLandingLatency>();
+    let publisher_0 = feed.publisher_key(0);
+    let publisher_1 = feed.publisher_key(1);
+
+    feed.warp_to_slot(10);
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 10).unwrap();
+    assert_eq!(get_latency(&feed, &publisher_0), (0, 0));
+
+    // The first update of a publisher sets the EMA, the next ones move it
+    feed.warp_to_slot(12);
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 11).unwrap();
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 9).unwrap();
+    assert_eq!(get_latency(&feed, &publisher_0), (1, 62));
+    assert_eq!(get_latency(&feed, &publisher_1), (3, 3_000));
+
+    feed.warp_to_slot(20);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 20).unwrap();
+    assert_eq!(get_latency(&feed, &publisher_1), (0, 2_812));
+
+    // Entries left by another publisher start over
+    let mut latency = LandingLatency {
+        publisher: publisher_0,
+        latest:    5,
+        ema:       5_000,
+    };
+    latency.update(&publisher_1, 2);
+    assert_eq!(latency.publisher, publisher_1);
+    assert_eq!((latency.latest, latency.ema), (2, 2_000));
+
+    let price_key = feed.price_key();
+    let mut expected = vec![
+        PublisherLandingStats {
+            publisher:      publisher_0,
+            price_account:  price_key,
+            latest_latency: 1,
+            latency_ema:    62,
+        },
+        PublisherLandingStats {
+            publisher:      publisher_1,
+            price_account:  price_key,
+            latest_latency: 0,
+            latency_ema:    2_812,
+        },
+    ];
+    expected.sort_by_key(|stat| stat.publisher);
+    assert_eq!(
+        compute_publisher_landing_stats([(&price_key, feed.price_account_data())]),
+        expected
+    );
+
+    // Feeds without the extension are skipped
+    let mut plain_feed = FeedSimulator::new(1);
+    plain_feed.warp_to_slot(10);
+    plain_feed
+        .upd_price(0, PC_STATUS_TRADING, 100, 1, 9)
+        .unwrap();
+    assert_eq!(
+        compute_publisher_landing_stats([(&Pubkey::new_unique(), plain_feed.price_account_data())]),
+        vec![]
+    );
+}
+
+fn get_latency(feed: &FeedSimulator, publisher: &Pubkey) -> (u64, u64) {
+    let landing_latency = feed.extension::<PublisherLandingLatency>();
+    let latency = landing_latency
+        .components
+        .iter()
+        .find(|latency| latency.publisher == *publisher)
+        .unwrap();
+    (latency.latest, latency.ema)
+}
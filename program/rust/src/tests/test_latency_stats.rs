@@ -0,0 +1,86 @@
+use {
+    crate::{
+        accounts::{
+            LatencyStats,
+            LATENCY_STATS_ENTRIES,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_latency_stats() {
+    let mut feed = FeedSimulator::new(2).with_extension::<LatencyStats>();
+    assert_eq!(
+        feed.set_latency_stats_window(LATENCY_STATS_ENTRIES as u64 + 1),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_latency_stats_window(2).unwrap();
+
+    // Updates count in the aggregation of the next slot, the first update of the slot
+    // aggregates before it's recorded
+    feed.warp_to_slot(10);
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 10).unwrap();
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 7).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!(stats.num_aggregations, 1);
+    assert_eq!(
+        (stats.pending.num_updates, stats.pending.total_latency),
+        (2, 3)
+    );
+
+    feed.warp_to_slot(11);
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 10).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!(stats.num_aggregations, 2);
+    assert_eq!((stats.mean_latency, stats.max_latency), (1_500, 3));
+
+    feed.warp_to_slot(12);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 12).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!(stats.num_aggregations, 3);
+    assert_eq!((stats.mean_latency, stats.max_latency), (1_333, 3));
+
+    // The first samples leave the window, aggregations without updates still count
+    feed.warp_to_slot(13);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 13).unwrap();
+    feed.warp_to_slot(14);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 13).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!(stats.num_aggregations, 5);
+    assert_eq!((stats.mean_latency, stats.max_latency), (0, 0));
+    feed.warp_to_slot(15);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 15).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!((stats.mean_latency, stats.max_latency), (500, 1));
+
+    // Changing the window starts over, setting it again doesn't
+    feed.set_latency_stats_window(2).unwrap();
+    assert_eq!(feed.extension::<LatencyStats>().num_aggregations, 6);
+    feed.set_latency_stats_window(0).unwrap();
+    feed.warp_to_slot(16);
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 10).unwrap();
+    let stats = feed.extension::<LatencyStats>();
+    assert_eq!(stats.num_aggregations, 0);
+    assert_eq!(stats.pending.num_updates, 0);
+}
+
+#[test]
+fn test_latency_stats_ring() {
+    let mut stats = LatencyStats::new(LATENCY_STATS_ENTRIES as u64);
+    for latency in 0..3 * LATENCY_STATS_ENTRIES as u64 {
+        stats.record_update(latency);
+        stats.record_update(latency + 1);
+        stats.record_aggregation();
+    }
+    // The window holds the latencies from 2 * ENTRIES to 3 * ENTRIES
+    let entries = LATENCY_STATS_ENTRIES as u64;
+    assert_eq!(stats.max_latency, 3 * entries);
+    assert_eq!(stats.mean_latency, (5 * entries) * 1_000 / 2);
+
+    stats.record_update(u64::MAX);
+    stats.record_update(u64::MAX);
+    assert_eq!(stats.pending.total_latency, u64::MAX);
+}
@@ -0,0 +1,102 @@
+use {
+    crate::{
+        c_oracle_header::PC_VERSION,
+        layout::{
+            layout_json,
+            struct_layouts,
+        },
+    },
+    serde::Deserialize,
+    std::fs,
+};
+
+#[derive(Deserialize)]
+struct Layout {
+    version: u32,
+    structs: Vec<Struct>,
+}
+
+#[derive(Deserialize)]
+struct Struct {
+    name:   String,
+    size:   usize,
+    fields: Vec<Field>,
+}
+
+#[derive(Deserialize)]
+struct Field {
+    name:   String,
+    offset: usize,
+    size:   usize,
+}
+
+// Clients parse the accounts by offset, so the layout published for a version of the account
+// header must hold for as long as the program writes that version. Every struct of the artifact
+// of `PC_VERSION` keeps its size and every field its offset and size, only renaming a field
+// (e.g. putting an `unused_` field to use) is allowed. Changing the layout requires bumping
+// `PC_VERSION` and adding the artifact of the new version, written by `pyth-layout`.
+#[test]
+fn test_layout() {
+    let path = format!("test_data/layout/v{PC_VERSION}.json");
+    let json = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("Missing {path}, write it with `pyth-layout {path}`"));
+    let published: Layout = serde_json::from_str(&json).expect("Unable to parse JSON");
+    assert_eq!(published.version, PC_VERSION);
+
+    let layouts = struct_layouts();
+    for published_struct in &published.structs {
+        let layout = layouts
+            .iter()
+            .find(|layout| layout.name == published_struct.name)
+            .unwrap_or_else(|| panic!("{} was removed from the layout", published_struct.name));
+        assert_eq!(
+            layout.size, published_struct.size,
+            "The size of {} changed without a version bump",
+            layout.name
+        );
+        for published_field in &published_struct.fields {
+            assert!(
+                layout
+                    .fields
+                    .iter()
+                    .any(|field| field.offset == published_field.offset
+                        && field.size == published_field.size),
+                "{}.{} moved without a version bump",
+                layout.name,
+                published_field.name
+            );
+        }
+    }
+
+    // Renames and new structs are published as well
+    assert_eq!(
+        layout_json(),
+        json,
+        "The layout changed, write it with `pyth-layout {path}`"
+    );
+}
+
+#[test]
+fn test_struct_layouts() {
+    let layouts = struct_layouts();
+    for (i, layout) in layouts.iter().enumerate() {
+        assert!(
+            layouts[..i].iter().all(|other| other.name != layout.name),
+            "{} is listed twice",
+            layout.name
+        );
+        // The fields are listed in order and cover the struct, so that a field missing from the
+        // list doesn't go unnoticed
+        let mut end = 0;
+        for field in &layout.fields {
+            assert!(field.offset >= end, "{}.{}", layout.name, field.name);
+            end = field.offset + field.size;
+        }
+        let covered: usize = layout.fields.iter().map(|field| field.size).sum();
+        assert!(
+            layout.size - covered < 8 && end <= layout.size,
+            "{} has fields missing from its layout",
+            layout.name
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use {
+    crate::{
+        accounts::{
+            data_len_with_extension,
+            extensions_end,
+            init_extension,
+            AggregationCounts,
+            AggregationDelay,
+            BidAsk,
+            ExtensionType,
+            PriceAccount,
+            PriceAccountExtension,
+            PriceHistoryPointer,
+            PublisherLandingLatency,
+            PublisherRateLimit,
+            PublisherWeightCap,
+            PublishingSlotTolerance,
+            PythAccount,
+            QuoteOrder,
+            ReadStats,
+            SanityProgram,
+            SnapshotAccount,
+        },
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_PROD_ACC_SIZE,
+            PC_VERSION,
+        },
+        error::OracleError,
+        listing_cost::{
+            get_listing_cost,
+            AccountCost,
+            FeedListing,
+            ListingAccountKind,
+        },
+        tests::test_utils::AccountSetup,
+    },
+    solana_program::{
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_listing_cost() {
+    let rent = Rent::default();
+    let listing = FeedListing {
+        publisher_capacity: PC_NUM_COMP,
+        extensions:         vec![],
+        history_depth:      0,
+    };
+    let cost = get_listing_cost(&listing, &rent).unwrap();
+    assert_eq!(
+        cost.accounts,
+        vec![
+            AccountCost {
+                kind:     ListingAccountKind::Product,
+                size:     PC_PROD_ACC_SIZE as usize,
+                lamports: rent.minimum_balance(PC_PROD_ACC_SIZE as usize),
+            },
+            AccountCost {
+                kind:     ListingAccountKind::Price,
+                size:     size_of::<PriceAccount>(),
+                lamports: rent.minimum_balance(size_of::<PriceAccount>()),
+            },
+        ]
+    );
+    assert_eq!(
+        cost.total_lamports(),
+        rent.minimum_balance(PC_PROD_ACC_SIZE as usize)
+            + rent.minimum_balance(size_of::<PriceAccount>())
+    );
+
+    // The price account is the same whatever the number of publishers
+    let small_listing = FeedListing {
+        publisher_capacity: 1,
+        ..listing.clone()
+    };
+    assert_eq!(get_listing_cost(&small_listing, &rent), Ok(cost));
+
+    let too_many_publishers = FeedListing {
+        publisher_capacity: PC_NUM_COMP + 1,
+        ..listing.clone()
+    };
+    assert_eq!(
+        get_listing_cost(&too_many_publishers, &rent),
+        Err(OracleError::MaxPublishersReached)
+    );
+
+    let uninitialized_extension = FeedListing {
+        extensions: vec![ExtensionType::Uninitialized],
+        ..listing
+    };
+    assert_eq!(
+        get_listing_cost(&uninitialized_extension, &rent),
+        Err(OracleError::InvalidExtension)
+    );
+}
+
+/// A price account of the forecasted size holds all the extensions of the listing, without
+/// growing or leaving unused space.
+#[test]
+fn test_listing_cost_extensions() {
+    let rent = Rent::default();
+    let listing = FeedListing {
+        publisher_capacity: 8,
+        extensions:         vec![
+            ExtensionType::SanityProgram,
+            ExtensionType::HistoryPointer,
+            ExtensionType::BidAsk,
+            ExtensionType::LandingLatency,
+            ExtensionType::ReadStats,
+            ExtensionType::RateLimit,
+            ExtensionType::AggregationDelay,
+            ExtensionType::PublishingSlotTolerance,
+            ExtensionType::AggregationCounts,
+            ExtensionType::PublisherWeightCap,
+            ExtensionType::QuoteOrder,
+            ExtensionType::RateLimit,
+        ],
+        history_depth:      3,
+    };
+    let cost = get_listing_cost(&listing, &rent).unwrap();
+    assert_eq!(cost.accounts.len(), 5);
+    let price_account_size = cost.accounts[1].size;
+
+    let program_id = Pubkey::new_unique();
+    let mut price_setup =
+        AccountSetup::new_with_size::<PriceAccount>(&program_id, price_account_size);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut data = price_account.try_borrow_mut_data().unwrap();
+        add_extension::<SanityProgram>(&mut data);
+        add_extension::<PriceHistoryPointer>(&mut data);
+        add_extension::<BidAsk>(&mut data);
+        add_extension::<PublisherLandingLatency>(&mut data);
+        add_extension::<ReadStats>(&mut data);
+        add_extension::<PublisherRateLimit>(&mut data);
+        add_extension::<AggregationDelay>(&mut data);
+        add_extension::<PublishingSlotTolerance>(&mut data);
+        add_extension::<AggregationCounts>(&mut data);
+        add_extension::<PublisherWeightCap>(&mut data);
+        add_extension::<QuoteOrder>(&mut data);
+        assert_eq!(extensions_end(&data), Ok(price_account_size));
+    }
+    assert_eq!(
+        cost.accounts[1].lamports,
+        rent.minimum_balance(price_account_size)
+    );
+
+    for snapshot in &cost.accounts[2..] {
+        assert_eq!(snapshot.kind, ListingAccountKind::Snapshot);
+        assert_eq!(
+            snapshot.size,
+            SnapshotAccount::MINIMUM_SIZE + price_account_size
+        );
+        assert_eq!(snapshot.lamports, rent.minimum_balance(snapshot.size));
+    }
+    assert_eq!(
+        cost.total_size(),
+        PC_PROD_ACC_SIZE as usize + 4 * price_account_size + 3 * SnapshotAccount::MINIMUM_SIZE
+    );
+}
+
+fn add_extension<T: PriceAccountExtension>(data: &mut [u8]) {
+    assert_eq!(data_len_with_extension::<T>(data), Ok(data.len()));
+    init_extension::<T>(data).unwrap();
+}
@@ -0,0 +1,236 @@
+use {
+    crate::{
+        accounts::{
+            create_pc_str_t,
+            find_product_by_symbol,
+            get_product_metadata,
+            iter_products,
+            AccountHeader,
+            MappingAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_MAP_TABLE_SIZE,
+            PC_PROD_ACC_SIZE,
+            PC_VERSION,
+        },
+        deserialize::load_mut,
+        error::OracleError,
+    },
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::{
+        collections::HashMap,
+        mem::size_of,
+    },
+};
+
+fn mapping_account_data(products: &[Pubkey], next_mapping_account: Pubkey) -> Vec<u8> {
+    let mut data = vec![0u8; size_of::<MappingAccount>()];
+    let mapping_data = load_mut::<MappingAccount>(&mut data).unwrap();
+    mapping_data.header = AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: MappingAccount::ACCOUNT_TYPE,
+        size:         MappingAccount::INITIAL_SIZE + (products.len() * size_of::<Pubkey>()) as u32,
+    };
+    mapping_data.number_of_products = products.len() as u32;
+    mapping_data.next_mapping_account = next_mapping_account;
+    mapping_data.products_list[..products.len()].copy_from_slice(products);
+    data
+}
+
+fn product_account_data(kvs: &[&str]) -> Vec<u8> {
+    let mut data = vec![0u8; PC_PROD_ACC_SIZE as usize];
+    let mut idx = size_of::<ProductAccount>();
+    for s in kvs {
+        let pc_str = create_pc_str_t(s);
+        data[idx..idx + pc_str.len()].copy_from_slice(&pc_str);
+        idx += pc_str.len();
+    }
+    load_mut::<ProductAccount>(&mut data).unwrap().header = AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: ProductAccount::ACCOUNT_TYPE,
+        size:         idx as u32,
+    };
+    data
+}
+
+#[test]
+fn test_iter_products() {
+    let mapping_1 = Pubkey::new_unique();
+    let mapping_2 = Pubkey::new_unique();
+    let mapping_3 = Pubkey::new_unique();
+    let products_1: Vec<Pubkey> = (0..PC_MAP_TABLE_SIZE)
+        .map(|_| Pubkey::new_unique())
+        .collect();
+    let products_3 = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+
+    let mut accounts = HashMap::new();
+    accounts.insert(mapping_1, mapping_account_data(&products_1, mapping_2));
+    // An empty mapping account in the middle of the list doesn't end the iteration
+    accounts.insert(mapping_2, mapping_account_data(&[], mapping_3));
+    accounts.insert(
+        mapping_3,
+        mapping_account_data(&products_3, Pubkey::default()),
+    );
+
+    let products = iter_products(mapping_1, |key| accounts.get(key).map(Vec::as_slice))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(products.len(), products_1.len() + products_3.len());
+    assert_eq!(products[..products_1.len()], products_1[..]);
+    assert_eq!(products[products_1.len()..], products_3[..]);
+
+    // Starting in the middle of the list
+    assert_eq!(
+        iter_products(mapping_3, |key| accounts.get(key).map(Vec::as_slice))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap(),
+        products_3
+    );
+
+    // Empty list
+    assert_eq!(
+        iter_products(Pubkey::default(), |key| accounts
+            .get(key)
+            .map(Vec::as_slice))
+        .count(),
+        0
+    );
+
+    // Missing mapping account
+    accounts.remove(&mapping_3);
+    let results: Vec<_> =
+        iter_products(mapping_1, |key| accounts.get(key).map(Vec::as_slice)).collect();
+    assert_eq!(results.len(), products_1.len() + 1);
+    assert_eq!(results.last(), Some(&Err(OracleError::AccountNotFound)));
+
+    // Not a mapping account
+    accounts.insert(mapping_3, product_account_data(&[]));
+    assert_eq!(
+        iter_products(mapping_3, |key| accounts.get(key).map(Vec::as_slice)).collect::<Vec<_>>(),
+        vec![Err(OracleError::AccountTooSmall)]
+    );
+
+    // Corrupted product count
+    let mut data = mapping_account_data(&[], Pubkey::default());
+    load_mut::<MappingAccount>(&mut data)
+        .unwrap()
+        .number_of_products = PC_MAP_TABLE_SIZE + 1;
+    accounts.insert(mapping_3, data);
+    assert_eq!(
+        iter_products(mapping_3, |key| accounts.get(key).map(Vec::as_slice)).collect::<Vec<_>>(),
+        vec![Err(OracleError::InvalidAccountHeader)]
+    );
+}
+
+#[test]
+fn test_find_product_by_symbol() {
+    let mapping_1 = Pubkey::new_unique();
+    let mapping_2 = Pubkey::new_unique();
+    let btc = Pubkey::new_unique();
+    let eth = Pubkey::new_unique();
+    let sol = Pubkey::new_unique();
+
+    let mut accounts = HashMap::new();
+    accounts.insert(mapping_1, mapping_account_data(&[btc, eth], mapping_2));
+    accounts.insert(mapping_2, mapping_account_data(&[sol], Pubkey::default()));
+    accounts.insert(
+        btc,
+        product_account_data(&["symbol", "Crypto.BTC/USD", "asset_type", "Crypto"]),
+    );
+    accounts.insert(
+        eth,
+        product_account_data(&["asset_type", "Crypto", "symbol", "Crypto.ETH/USD"]),
+    );
+    accounts.insert(sol, product_account_data(&["symbol", "Crypto.SOL/USD"]));
+
+    assert_eq!(
+        get_product_metadata(&accounts[&eth], "symbol"),
+        Ok(Some("Crypto.ETH/USD".as_bytes()))
+    );
+    assert_eq!(
+        get_product_metadata(&accounts[&sol], "asset_type"),
+        Ok(None)
+    );
+
+    for (symbol, product) in [
+        ("Crypto.BTC/USD", btc),
+        ("Crypto.ETH/USD", eth),
+        ("Crypto.SOL/USD", sol),
+    ] {
+        assert_eq!(
+            find_product_by_symbol(mapping_1, symbol, |key| accounts
+                .get(key)
+                .map(Vec::as_slice)),
+            Ok(Some(product))
+        );
+    }
+    // Symbols are matched exactly
+    assert_eq!(
+        find_product_by_symbol(mapping_1, "Crypto.BTC", |key| accounts
+            .get(key)
+            .map(Vec::as_slice)),
+        Ok(None)
+    );
+    assert_eq!(
+        find_product_by_symbol(mapping_2, "Crypto.BTC/USD", |key| accounts
+            .get(key)
+            .map(Vec::as_slice)),
+        Ok(None)
+    );
+
+    // Missing product account
+    accounts.remove(&btc);
+    assert_eq!(
+        find_product_by_symbol(mapping_1, "Crypto.SOL/USD", |key| accounts
+            .get(key)
+            .map(Vec::as_slice)),
+        Err(ProgramError::from(OracleError::AccountNotFound))
+    );
+}
+
+#[test]
+fn test_iter_products_cycle() {
+    let mapping_1 = Pubkey::new_unique();
+    let mapping_2 = Pubkey::new_unique();
+    let btc = Pubkey::new_unique();
+    let eth = Pubkey::new_unique();
+
+    // The second mapping account links back to the first one
+    let mut accounts = HashMap::new();
+    accounts.insert(mapping_1, mapping_account_data(&[btc], mapping_2));
+    accounts.insert(mapping_2, mapping_account_data(&[eth], mapping_1));
+    accounts.insert(btc, product_account_data(&["symbol", "Crypto.BTC/USD"]));
+    accounts.insert(eth, product_account_data(&["symbol", "Crypto.ETH/USD"]));
+
+    assert_eq!(
+        iter_products(mapping_1, |key| accounts.get(key).map(Vec::as_slice)).collect::<Vec<_>>(),
+        vec![Ok(btc), Ok(eth), Err(OracleError::MappingCycle)]
+    );
+    assert_eq!(
+        find_product_by_symbol(mapping_2, "Crypto.BTC/USD", |key| accounts
+            .get(key)
+            .map(Vec::as_slice)),
+        Ok(Some(btc))
+    );
+    assert_eq!(
+        find_product_by_symbol(mapping_1, "Crypto.SOL/USD", |key| accounts
+            .get(key)
+            .map(Vec::as_slice)),
+        Err(ProgramError::from(OracleError::MappingCycle))
+    );
+
+    // A mapping account linking to itself
+    accounts.insert(mapping_1, mapping_account_data(&[btc], mapping_1));
+    assert_eq!(
+        iter_products(mapping_1, |key| accounts.get(key).map(Vec::as_slice)).collect::<Vec<_>>(),
+        vec![Ok(btc), Err(OracleError::MappingCycle)]
+    );
+}
@@ -0,0 +1,272 @@
+use {
+    crate::{
+        accounts::{
+            create_pc_str_t,
+            symbol_hash,
+            AccountHeader,
+            MappingAccount,
+            MappingAccountV2,
+            PermissionAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_VERSION,
+        },
+        deserialize::{
+            load_checked,
+            load_mut,
+        },
+        error::OracleError,
+        instruction::{
+            CommandHeader,
+            MigrateMappingArgs,
+            OracleCommand,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+/// Data of a mapping account v2 with `capacity` free entries.
+fn mapping_v2_data(capacity: usize) -> Vec<u8> {
+    let mut data = vec![0u8; MappingAccountV2::space(capacity)];
+    let mapping_data = load_mut::<MappingAccountV2>(&mut data).unwrap();
+    mapping_data.header = AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: MappingAccountV2::ACCOUNT_TYPE,
+        size:         MappingAccountV2::space(capacity) as u32,
+    };
+    mapping_data.capacity = capacity as u32;
+    data
+}
+
+/// A symbol hash probed from entry `start` of a table of 4 entries.
+fn hash_at(start: u8, discriminator: u8) -> [u8; 32] {
+    let mut hash = [discriminator; 32];
+    hash[..8].copy_from_slice(&u64::from(start).to_le_bytes());
+    hash
+}
+
+#[test]
+fn test_mapping_v2_table() {
+    let mut data = mapping_v2_data(4);
+    let (btc, eth, sol, avax) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    assert_eq!(
+        MappingAccountV2::find_product(&data, &hash_at(3, 1)),
+        Ok(None)
+    );
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(3, 1), &btc),
+        Ok(true)
+    );
+    // Colliding hashes go to the next free entries, wrapping around the table
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(3, 2), &eth),
+        Ok(true)
+    );
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(0, 3), &sol),
+        Ok(true)
+    );
+    for (hash, product) in [
+        (hash_at(3, 1), btc),
+        (hash_at(3, 2), eth),
+        (hash_at(0, 3), sol),
+    ] {
+        assert_eq!(
+            MappingAccountV2::find_product(&data, &hash),
+            Ok(Some(product))
+        );
+    }
+    assert_eq!(
+        MappingAccountV2::find_product(&data, &hash_at(3, 4)),
+        Ok(None)
+    );
+
+    // Indexing a product again changes nothing, another product can't take its symbol
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(3, 2), &eth),
+        Ok(false)
+    );
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(3, 2), &avax),
+        Err(OracleError::DuplicateSymbol)
+    );
+
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(1, 4), &avax),
+        Ok(true)
+    );
+    assert_eq!(
+        MappingAccountV2::insert(&mut data, &hash_at(2, 5), &Pubkey::new_unique()),
+        Err(OracleError::MappingFull)
+    );
+    // Lookups of a missing symbol in a full table end
+    assert_eq!(
+        MappingAccountV2::find_product(&data, &hash_at(2, 5)),
+        Ok(None)
+    );
+    assert_eq!(
+        load_mut::<MappingAccountV2>(&mut data)
+            .unwrap()
+            .number_of_products,
+        4
+    );
+
+    // The table must fit in the account
+    assert_eq!(
+        MappingAccountV2::find_product(&data[..data.len() - 1], &hash_at(3, 1)),
+        Err(OracleError::AccountTooSmall)
+    );
+}
+
+/// Initialize `product` with the `symbol` metadata, if any.
+fn init_product(product: &AccountInfo, symbol: Option<&str>) {
+    ProductAccount::initialize(product, PC_VERSION).unwrap();
+    let mut metadata = vec![];
+    if let Some(symbol) = symbol {
+        metadata.extend(create_pc_str_t("symbol"));
+        metadata.extend(create_pc_str_t(symbol));
+    }
+    let start = size_of::<ProductAccount>();
+    product.try_borrow_mut_data().unwrap()[start..start + metadata.len()]
+        .copy_from_slice(&metadata);
+    load_mut::<ProductAccount>(&mut product.try_borrow_mut_data().unwrap())
+        .unwrap()
+        .header
+        .size = (start + metadata.len()) as u32;
+}
+
+#[test]
+fn test_migrate_mapping() {
+    let program_id = Pubkey::new_unique();
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let mut mapping_v2_setup = AccountSetup::new_with_size::<MappingAccountV2>(
+        &program_id,
+        MappingAccountV2::space(8) + 10,
+    );
+    let mapping_v2_account = mapping_v2_setup.as_account_info();
+    let init_mapping_v2: CommandHeader = OracleCommand::InitMappingV2.into();
+    process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            mapping_v2_account.clone(),
+            permissions_account.clone(),
+        ],
+        bytes_of(&init_mapping_v2),
+    )
+    .unwrap();
+    {
+        let mapping_v2_data =
+            load_checked::<MappingAccountV2>(&mapping_v2_account, PC_VERSION).unwrap();
+        assert_eq!(mapping_v2_data.capacity, 8);
+        assert_eq!(
+            mapping_v2_data.header.size as usize,
+            MappingAccountV2::space(8)
+        );
+    }
+
+    // The account must have room for an entry
+    let mut small_setup = AccountSetup::new::<MappingAccountV2>(&program_id);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                small_setup.as_account_info(),
+                permissions_account.clone(),
+            ],
+            bytes_of(&init_mapping_v2),
+        ),
+        Err(OracleError::AccountTooSmall.into())
+    );
+
+    let symbols = [Some("Crypto.BTC/USD"), None, Some("Crypto.ETH/USD")];
+    let mut product_setups: Vec<AccountSetup> = symbols
+        .iter()
+        .map(|_| AccountSetup::new::<ProductAccount>(&program_id))
+        .collect();
+    let products: Vec<AccountInfo> = product_setups
+        .iter_mut()
+        .map(AccountSetup::as_account_info)
+        .collect();
+    let mut mapping_setup = AccountSetup::new::<MappingAccount>(&program_id);
+    let mapping_account = mapping_setup.as_account_info();
+    {
+        let mut mapping_data = MappingAccount::initialize(&mapping_account, PC_VERSION).unwrap();
+        for (product, symbol) in products.iter().zip(symbols) {
+            init_product(product, symbol);
+            mapping_data.products_list[mapping_data.number_of_products as usize] = *product.key;
+            mapping_data.number_of_products += 1;
+        }
+    }
+
+    let migrate = |start: u32, products: &[AccountInfo]| {
+        let args = MigrateMappingArgs {
+            header: OracleCommand::MigrateMapping.into(),
+            start,
+            unused_: 0,
+        };
+        let mut accounts = vec![
+            funding_account.clone(),
+            mapping_v2_account.clone(),
+            permissions_account.clone(),
+            mapping_account.clone(),
+        ];
+        accounts.extend_from_slice(products);
+        process_instruction(&program_id, &accounts, bytes_of(&args))
+    };
+
+    // The products are the ones listed from `start` on, in order
+    assert_eq!(
+        migrate(1, &products[..2]),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        migrate(3, &products[2..]),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    migrate(0, &products[..2]).unwrap();
+    migrate(1, &products[1..]).unwrap();
+    let data = mapping_v2_account.try_borrow_data().unwrap();
+    for (product, symbol) in products.iter().zip(symbols) {
+        if let Some(symbol) = symbol {
+            assert_eq!(
+                MappingAccountV2::find_product(&data, &symbol_hash(symbol.as_bytes())),
+                Ok(Some(*product.key))
+            );
+        }
+    }
+    // The product without a symbol is skipped
+    assert_eq!(
+        load_checked::<MappingAccountV2>(&mapping_v2_account, PC_VERSION)
+            .unwrap()
+            .number_of_products,
+        2
+    );
+}
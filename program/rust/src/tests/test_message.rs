@@ -1,5 +1,14 @@
 use {
-    crate::accounts::PythOracleSerialize,
+    crate::{
+        accounts::{
+            FeedTombstoneMessage,
+            ProductMetadataMessage,
+            PublisherPrice,
+            PublisherPricesMessage,
+            PythOracleSerialize,
+        },
+        error::OracleError,
+    },
     byteorder::BigEndian,
     pythnet_sdk::{
         messages::{
@@ -65,3 +74,193 @@ fn test_publisher_caps_message_roundtrip() {
         .gen(Gen::new(1024))
         .quickcheck(prop_publisher_caps_message_roundtrip as fn(PublisherStakeCapsMessage) -> bool);
 }
+
+#[test]
+fn test_publisher_prices_message_layout() {
+    let message = PublisherPricesMessage {
+        feed_id:      [1; 32],
+        publish_time: 2,
+        publish_slot: 3,
+        exponent:     -4,
+        publishers:   vec![
+            PublisherPrice {
+                publisher: [5; 32],
+                price:     -6,
+                conf:      7,
+                slot:      8,
+                venue:     0,
+            },
+            PublisherPrice {
+                publisher: [9; 32],
+                price:     10,
+                conf:      11,
+                slot:      12,
+                venue:     13,
+            },
+        ],
+    };
+
+    let mut expected = vec![3u8];
+    expected.extend_from_slice(&[1; 32]);
+    expected.extend_from_slice(&2i64.to_be_bytes());
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&(-4i32).to_be_bytes());
+    expected.extend_from_slice(&2u16.to_be_bytes());
+    for (publisher, price, conf, slot, venue) in
+        [([5; 32], -6i64, 7u64, 8u64, 0u8), ([9; 32], 10, 11, 12, 13)]
+    {
+        expected.extend_from_slice(&publisher);
+        expected.extend_from_slice(&price.to_be_bytes());
+        expected.extend_from_slice(&conf.to_be_bytes());
+        expected.extend_from_slice(&slot.to_be_bytes());
+        expected.push(venue);
+    }
+
+    assert_eq!(message.to_bytes(), expected);
+}
+
+#[test]
+fn test_publisher_prices_message_compressed_layout() {
+    let message = PublisherPricesMessage {
+        feed_id:      [1; 32],
+        publish_time: 2,
+        publish_slot: 3,
+        exponent:     -4,
+        publishers:   vec![
+            PublisherPrice {
+                publisher: [5; 32],
+                price:     -6,
+                conf:      7,
+                slot:      8,
+                venue:     0,
+            },
+            PublisherPrice {
+                publisher: [9; 32],
+                price:     10,
+                conf:      300,
+                slot:      2,
+                venue:     13,
+            },
+        ],
+    };
+
+    let mut expected = vec![6u8];
+    expected.extend_from_slice(&[1; 32]);
+    expected.extend_from_slice(&2i64.to_be_bytes());
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&(-4i32).to_be_bytes());
+    expected.extend_from_slice(&2u16.to_be_bytes());
+    // Only the second publisher has a venue
+    expected.push(0b10);
+    // Price -6, 5 slots after the message
+    expected.extend_from_slice(&[5; 32]);
+    expected.extend_from_slice(&[11, 7, 9]);
+    // Price 16 above the previous one, 1 slot before the message
+    expected.extend_from_slice(&[9; 32]);
+    expected.extend_from_slice(&[32, 0xac, 0x02, 2, 13]);
+
+    let compressed = message.to_compressed_bytes();
+    assert_eq!(compressed, expected);
+    assert!(compressed.len() < message.clone().to_bytes().len());
+    assert_eq!(
+        PublisherPricesMessage::decode(&compressed).unwrap(),
+        message
+    );
+
+    // Truncated or padded messages are rejected
+    for data in [
+        &compressed[..compressed.len() - 1],
+        &[compressed.as_slice(), &[0]].concat(),
+    ] {
+        assert_eq!(
+            PublisherPricesMessage::decode(data),
+            Err(OracleError::DeserializationError)
+        );
+    }
+}
+
+#[quickcheck]
+fn test_publisher_prices_message_decode_roundtrip(
+    publish_time: i64,
+    publish_slot: u64,
+    exponent: i32,
+    quotes: Vec<(u8, i64, u64, u64, u8)>,
+) -> bool {
+    let message = PublisherPricesMessage {
+        feed_id: [1; 32],
+        publish_time,
+        publish_slot,
+        exponent,
+        publishers: quotes
+            .into_iter()
+            .map(|(key, price, conf, slot, venue)| PublisherPrice {
+                publisher: [key; 32],
+                price,
+                conf,
+                slot,
+                venue,
+            })
+            .collect(),
+    };
+
+    PublisherPricesMessage::decode(&message.to_compressed_bytes()).as_ref() == Ok(&message)
+        && PublisherPricesMessage::decode(&message.clone().to_bytes()).as_ref() == Ok(&message)
+}
+
+#[test]
+fn test_product_metadata_message_layout() {
+    let message = ProductMetadataMessage {
+        product_id:   [1; 32],
+        publish_time: 2,
+        publish_slot: 3,
+        metadata:     vec![
+            (b"symbol".to_vec(), b"Crypto.BTC/USD".to_vec()),
+            (b"tenor".to_vec(), vec![]),
+        ],
+    };
+
+    let mut expected = vec![4u8];
+    expected.extend_from_slice(&[1; 32]);
+    expected.extend_from_slice(&2i64.to_be_bytes());
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&2u16.to_be_bytes());
+    expected.push(6);
+    expected.extend_from_slice(b"symbol");
+    expected.push(14);
+    expected.extend_from_slice(b"Crypto.BTC/USD");
+    expected.push(5);
+    expected.extend_from_slice(b"tenor");
+    expected.push(0);
+
+    assert_eq!(message.to_bytes(), expected);
+}
+
+#[test]
+fn test_feed_tombstone_message_layout() {
+    let message = FeedTombstoneMessage {
+        feed_id:         [1; 32],
+        publish_time:    2,
+        publish_slot:    3,
+        exponent:        -4,
+        price:           -5,
+        conf:            6,
+        status:          7,
+        aggregate_slot:  8,
+        num_updates:     9,
+        publishers_hash: [10; 32],
+    };
+
+    let mut expected = vec![5u8];
+    expected.extend_from_slice(&[1; 32]);
+    expected.extend_from_slice(&2i64.to_be_bytes());
+    expected.extend_from_slice(&3u64.to_be_bytes());
+    expected.extend_from_slice(&(-4i32).to_be_bytes());
+    expected.extend_from_slice(&(-5i64).to_be_bytes());
+    expected.extend_from_slice(&6u64.to_be_bytes());
+    expected.extend_from_slice(&7u32.to_be_bytes());
+    expected.extend_from_slice(&8u64.to_be_bytes());
+    expected.extend_from_slice(&9u64.to_be_bytes());
+    expected.extend_from_slice(&[10; 32]);
+
+    assert_eq!(message.to_bytes(), expected);
+}
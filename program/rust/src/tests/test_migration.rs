@@ -0,0 +1,224 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+            StatusReason,
+            UpdateCount,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_checked,
+        error::OracleError,
+        migration::{
+            find_accounts_to_migrate,
+            plan_batches,
+            read_price_account,
+            Migration,
+        },
+        processor::process_instruction,
+        tests::{
+            pyth_simulator::PythSimulator,
+            test_utils::AccountSetup,
+        },
+    },
+    solana_program::{
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    solana_sdk::signer::Signer,
+    std::mem::size_of,
+};
+
+#[test]
+fn test_migration_instruction() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let mut price_account = price_setup.as_account_info();
+    price_account.is_signer = false;
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let mut permissions_account = permissions_setup.as_account_info();
+    permissions_account.is_signer = false;
+    permissions_account.is_writable = false;
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    for (migration, flag, is_set) in [
+        (
+            Migration::EnableAccumulatorV2,
+            PriceAccountFlags::ACCUMULATOR_V2,
+            true,
+        ),
+        (
+            Migration::AllowZeroCi,
+            PriceAccountFlags::ALLOW_ZERO_CI,
+            true,
+        ),
+        (
+            Migration::DisableAccumulatorV2,
+            PriceAccountFlags::ACCUMULATOR_V2,
+            false,
+        ),
+        (
+            Migration::ForbidZeroCi,
+            PriceAccountFlags::ALLOW_ZERO_CI,
+            false,
+        ),
+        (
+            Migration::RequireIsolatedUpdates,
+            PriceAccountFlags::ISOLATED_UPDATES,
+            true,
+        ),
+        (
+            Migration::AllowBundledUpdates,
+            PriceAccountFlags::ISOLATED_UPDATES,
+            false,
+        ),
+    ] {
+        assert!(migration.is_needed(&price_account.try_borrow_data().unwrap()));
+
+        let instruction =
+            migration.instruction(&program_id, funding_account.key, price_account.key);
+        let accounts = [
+            funding_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ];
+        assert_eq!(instruction.program_id, program_id);
+        for (meta, account) in instruction.accounts.iter().zip(accounts.iter()) {
+            assert_eq!(meta.pubkey, *account.key);
+            assert_eq!(meta.is_signer, account.is_signer);
+            assert_eq!(meta.is_writable, account.is_writable);
+        }
+        assert!(process_instruction(&program_id, &accounts, &instruction.data).is_ok());
+
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.flags.contains(flag), is_set);
+        drop(price_data);
+        assert!(!migration.is_needed(&price_account.try_borrow_data().unwrap()));
+    }
+}
+
+#[test]
+fn test_plan_migration() {
+    let program_id = Pubkey::new_unique();
+    let mut accounts = vec![];
+
+    for i in 0..5 {
+        let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+        let price_account = price_setup.as_account_info();
+        PriceAccount::initialize(&price_account, PC_VERSION)
+            .unwrap()
+            .flags
+            .set(PriceAccountFlags::ACCUMULATOR_V2, i % 2 == 0);
+        accounts.push((
+            *price_account.key,
+            price_account.try_borrow_data().unwrap().to_vec(),
+        ));
+    }
+
+    let mut product_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let product_account = product_setup.as_account_info();
+    ProductAccount::initialize(&product_account, PC_VERSION).unwrap();
+    accounts.push((
+        *product_account.key,
+        product_account.try_borrow_data().unwrap().to_vec(),
+    ));
+
+    assert!(read_price_account(&accounts[0].1).is_some());
+    assert!(read_price_account(&accounts[5].1).is_none());
+    assert!(read_price_account(&accounts[0].1[..PriceAccount::MINIMUM_SIZE - 1]).is_none());
+
+    let to_migrate = find_accounts_to_migrate(
+        Migration::EnableAccumulatorV2,
+        accounts.iter().map(|(key, data)| (*key, data.as_slice())),
+    );
+    let mut expected = vec![accounts[1].0, accounts[3].0];
+    expected.sort();
+    assert_eq!(to_migrate, expected);
+
+    let to_migrate = find_accounts_to_migrate(
+        Migration::DisableAccumulatorV2,
+        accounts.iter().map(|(key, data)| (*key, data.as_slice())),
+    );
+    assert_eq!(to_migrate.len(), 3);
+
+    assert_eq!(
+        plan_batches(&to_migrate, 2),
+        vec![vec![to_migrate[0], to_migrate[1]], vec![to_migrate[2]]]
+    );
+}
+
+#[tokio::test]
+async fn test_migrate_extensions() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price = sim.add_price(&product_keypair, -8).await.unwrap().pubkey();
+    let data = sim.get_account(price).await.unwrap().data;
+    assert_eq!(data.len(), size_of::<PriceAccount>());
+
+    for migration in [
+        Migration::EnableUpdateCounting,
+        Migration::EnableStatusReasonTracking,
+        Migration::ReserveExtensionSpace,
+    ] {
+        let data = sim.get_account(price).await.unwrap().data;
+        assert!(migration.is_needed(&data));
+        // The migrations growing the account pay for the new space first
+        assert_eq!(
+            migration
+                .instructions(&Pubkey::new_unique(), &Pubkey::new_unique(), &price, &data)
+                .len(),
+            2
+        );
+        assert!(migration.data_len_after(&data) > data.len());
+
+        sim.migrate(migration, price).await.unwrap();
+        let account = sim.get_account(price).await.unwrap();
+        assert!(!migration.is_needed(&account.data));
+        assert_eq!(account.data.len(), migration.data_len_after(&data));
+        assert!(account.lamports >= Rent::default().minimum_balance(account.data.len()));
+    }
+
+    let account = sim.get_account(price).await.unwrap();
+    assert_eq!(account.data.len(), PriceAccount::NEW_ACCOUNT_SPACE);
+    assert_eq!(
+        get_extension::<UpdateCount>(&account.data)
+            .unwrap()
+            .unwrap()
+            .enabled,
+        1
+    );
+    assert_eq!(
+        get_extension::<StatusReason>(&account.data)
+            .unwrap()
+            .unwrap()
+            .enabled,
+        1
+    );
+
+    // Migrated accounts are left out when the rollout is resumed
+    assert!(find_accounts_to_migrate(
+        Migration::ReserveExtensionSpace,
+        [(price, account.data.as_slice())]
+    )
+    .is_empty());
+    assert_eq!(
+        sim.migrate(Migration::ReserveExtensionSpace, price)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::NoNeedToResize.into()
+    );
+}
@@ -0,0 +1,185 @@
+use {
+    crate::{
+        accounts::{
+            MultisigAccount,
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+            MAX_MULTISIG_SIGNERS,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            InitMultisigArgs,
+            OracleCommand,
+            SetMinPubArgs,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_multisig() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut signer_setups: Vec<AccountSetup> =
+        (0..3).map(|_| AccountSetup::new_funding()).collect();
+    let signers: Vec<AccountInfo> = signer_setups
+        .iter_mut()
+        .map(|setup| setup.as_account_info())
+        .collect();
+    let signer_keys: Vec<Pubkey> = signers.iter().map(|signer| *signer.key).collect();
+
+    let mut multisig_setup = AccountSetup::new::<MultisigAccount>(&program_id);
+    let multisig_account = multisig_setup.as_account_info();
+
+    let init_multisig = |threshold: u8, keys: &[Pubkey]| {
+        let mut args = InitMultisigArgs {
+            header: OracleCommand::InitMultisig.into(),
+            threshold,
+            num_signers: keys.len() as u8,
+            unused_: [0; 6],
+            signers: [Pubkey::default(); MAX_MULTISIG_SIGNERS],
+        };
+        args.signers[..keys.len()].copy_from_slice(keys);
+        process_instruction(
+            &program_id,
+            &[funding_account.clone(), multisig_account.clone()],
+            bytes_of(&args),
+        )
+    };
+
+    assert_eq!(
+        init_multisig(0, &signer_keys),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        init_multisig(4, &signer_keys),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        init_multisig(2, &[signer_keys[0], signer_keys[1], signer_keys[0]]),
+        Err(ProgramError::InvalidArgument)
+    );
+    init_multisig(2, &signer_keys).unwrap();
+    assert_eq!(
+        init_multisig(2, &signer_keys),
+        Err(OracleError::InvalidFreshAccount.into())
+    );
+    {
+        let multisig_data = load_checked::<MultisigAccount>(&multisig_account, PC_VERSION).unwrap();
+        assert_eq!(multisig_data.threshold, 2);
+        assert_eq!(multisig_data.signers(), signer_keys.as_slice());
+    }
+
+    // The multisig is the master authority, a single key the security authority
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *multisig_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+
+    let set_min_pub = |min_pub: u8, trailing_accounts: &[AccountInfo]| {
+        let args = SetMinPubArgs {
+            header:             OracleCommand::SetMinPub.into(),
+            minimum_publishers: min_pub,
+            unused_:            [0; 3],
+        };
+        let mut accounts = vec![
+            multisig_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ];
+        accounts.extend_from_slice(trailing_accounts);
+        process_instruction(&program_id, &accounts, bytes_of(&args))
+    };
+    let min_pub = || {
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .min_pub_
+    };
+
+    set_min_pub(2, &[signers[0].clone(), signers[2].clone()]).unwrap();
+    assert_eq!(min_pub(), 2);
+    set_min_pub(3, &signers).unwrap();
+    assert_eq!(min_pub(), 3);
+
+    let mut unsigned = signers[1].clone();
+    unsigned.is_signer = false;
+    let mut outsider_setup = AccountSetup::new_funding();
+    for trailing_accounts in [
+        vec![],
+        vec![signers[0].clone()],
+        // A signer only counts once
+        vec![signers[0].clone(), signers[0].clone()],
+        vec![signers[0].clone(), unsigned],
+        vec![signers[0].clone(), outsider_setup.as_account_info()],
+    ] {
+        assert_eq!(
+            set_min_pub(4, &trailing_accounts),
+            Err(OracleError::MultisigThresholdNotMet.into())
+        );
+    }
+    assert_eq!(min_pub(), 3);
+
+    // Accounts before the signers still belong to the instruction
+    assert_eq!(
+        set_min_pub(
+            4,
+            &[
+                funding_account.clone(),
+                signers[0].clone(),
+                signers[1].clone()
+            ]
+        ),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+
+    // The signers of the multisig have no authority of their own, and the multisig loses its
+    // authority once it's replaced
+    let args = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 4,
+        unused_:            [0; 3],
+    };
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                signers[0].clone(),
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            bytes_of(&args)
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+    {
+        let mut permissions_account_data =
+            load_checked::<PermissionAccount>(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+    }
+    assert_eq!(
+        set_min_pub(4, &signers),
+        Err(OracleError::PermissionViolation.into())
+    );
+    assert_eq!(min_pub(), 3);
+}
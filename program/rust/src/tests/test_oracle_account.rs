@@ -0,0 +1,59 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::PC_VERSION,
+        deserialize::OracleAccount,
+        tests::test_utils::AccountSetup,
+        OracleError,
+    },
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_oracle_account() {
+    let program_id = Pubkey::new_unique();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+
+    // Uninitialized accounts are rejected
+    assert_eq!(
+        OracleAccount::<PriceAccount>::new(&price_account, PC_VERSION).err(),
+        Some(OracleError::InvalidAccountHeader.into())
+    );
+
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    let price = OracleAccount::<PriceAccount>::new(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price.info().key, price_account.key);
+    price.load_mut().unwrap().exponent = -8;
+    assert_eq!(price.load().unwrap().exponent, -8);
+
+    // The borrows still exclude each other
+    {
+        let _price_data = price.load_mut().unwrap();
+        assert_eq!(price.load().err(), Some(ProgramError::AccountBorrowFailed));
+    }
+    assert!(price_account.try_borrow_mut_data().is_ok());
+
+    assert_eq!(
+        OracleAccount::<PriceAccount>::new(&price_account, PC_VERSION + 1).err(),
+        Some(OracleError::InvalidAccountHeader.into())
+    );
+    assert_eq!(
+        OracleAccount::<ProductAccount>::new(&price_account, PC_VERSION).err(),
+        Some(OracleError::InvalidAccountHeader.into())
+    );
+
+    let mut small_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let small_account = small_setup.as_account_info();
+    assert_eq!(
+        OracleAccount::<PriceAccount>::new(&small_account, PC_VERSION).err(),
+        Some(OracleError::AccountTooSmall.into())
+    );
+}
@@ -0,0 +1,124 @@
+use {
+    crate::{
+        accounts::{
+            find_product_pda,
+            get_product_metadata,
+            iter_extensions,
+            FeedKind,
+            MappingAccount,
+            PriceAccount,
+            ProductAccount,
+            PythAccount,
+        },
+        error::OracleError,
+        tests::pyth_simulator::PythSimulator,
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signer::Signer,
+};
+
+const METADATA: [&str; 6] = [
+    "symbol",
+    "Crypto.BTC/USD",
+    "asset_type",
+    "Crypto",
+    "quote_currency",
+    "USD",
+];
+
+#[tokio::test]
+async fn test_pda_accounts() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+
+    let product = sim.get_product_pda("Crypto.BTC/USD");
+    // The address must be derived from the symbol in the metadata
+    let other_product = sim.get_product_pda("Crypto.ETH/USD");
+    assert_eq!(
+        sim.init_product_pda(&mapping_keypair, other_product, &METADATA)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidPda.into()
+    );
+    sim.init_product_pda(&mapping_keypair, product, &METADATA)
+        .await
+        .unwrap();
+
+    let mapping_data = sim
+        .get_account_data_as::<MappingAccount>(mapping_keypair.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(mapping_data.number_of_products, 1);
+    assert_eq!(mapping_data.products_list[0], product);
+    let product_account = sim.get_account(product).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&product_account));
+    assert_eq!(
+        get_product_metadata(&product_account.data, "asset_type").unwrap(),
+        Some(&b"Crypto"[..])
+    );
+
+    // A symbol is only listed once
+    assert_eq!(
+        sim.init_product_pda(&mapping_keypair, product, &METADATA)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidFreshAccount.into()
+    );
+    // Symbols longer than a seed have no address
+    assert_eq!(find_product_pda(&Pubkey::new_unique(), &[b'A'; 33]), None);
+
+    let spot = sim.get_price_pda(&product, FeedKind::Spot);
+    let funding = sim.get_price_pda(&product, FeedKind::Funding);
+    assert_ne!(spot, funding);
+    assert_eq!(
+        sim.init_price_pda(product, funding, FeedKind::Spot, -8)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidPda.into()
+    );
+    sim.init_price_pda(product, spot, FeedKind::Spot, -8)
+        .await
+        .unwrap();
+    // One price account of each kind
+    assert_eq!(
+        sim.init_price_pda(product, spot, FeedKind::Spot, -8)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidFreshAccount.into()
+    );
+    sim.init_price_pda(product, funding, FeedKind::Funding, -10)
+        .await
+        .unwrap();
+
+    let product_data = sim
+        .get_account_data_as::<ProductAccount>(product)
+        .await
+        .unwrap();
+    assert_eq!(product_data.first_price_account, funding);
+    let spot_data = sim.get_account_data_as::<PriceAccount>(spot).await.unwrap();
+    let funding_data = sim
+        .get_account_data_as::<PriceAccount>(funding)
+        .await
+        .unwrap();
+    assert_eq!(funding_data.next_price_account, spot);
+    assert_eq!(spot_data.next_price_account, Pubkey::default());
+    assert_eq!(funding_data.feed_kind(), Some(FeedKind::Funding));
+    assert_eq!(funding_data.exponent, -10);
+    assert_eq!(spot_data.product_account, product);
+    assert_eq!(funding_data.feed_index, spot_data.feed_index + 1);
+    // Price PDAs come with room for their first extensions
+    let spot_account = sim.get_account(spot).await.unwrap();
+    assert_eq!(spot_account.data.len(), PriceAccount::NEW_ACCOUNT_SPACE);
+    assert_eq!(iter_extensions(&spot_account.data).count(), 0);
+
+    // Products listed with a keypair have their price accounts derived the same way
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let index = sim.get_price_pda(&product_keypair.pubkey(), FeedKind::Index);
+    sim.init_price_pda(product_keypair.pubkey(), index, FeedKind::Index, -5)
+        .await
+        .unwrap();
+}
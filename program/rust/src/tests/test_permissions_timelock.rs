@@ -0,0 +1,171 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PermissionTimelock,
+        },
+        deserialize::load,
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    bytemuck::pod_read_unaligned,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[tokio::test]
+async fn test_permissions_timelock() {
+    let mut sim = PythSimulator::new().await;
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+    let permissions_pubkey = sim.get_permissions_pubkey();
+
+    // Only the upgrade authority can set the timelock
+    sim.set_permissions_timelock(100).await.unwrap();
+    let (_, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(timelock.delay, 100);
+    assert!(!timelock.has_pending_change());
+    assert_eq!(
+        sim.get_account(permissions_pubkey)
+            .await
+            .unwrap()
+            .data
+            .len(),
+        PermissionAccount::TIMELOCK_ACCOUNT_SPACE
+    );
+
+    // Nothing to apply or cancel yet
+    assert_eq!(
+        sim.apply_permissions().await.unwrap_err().unwrap(),
+        OracleError::NoPendingPermissionsChange.into()
+    );
+    assert_eq!(
+        sim.cancel_permissions(&genesis_keypair)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::NoPendingPermissionsChange.into()
+    );
+
+    // Changes are staged, and can be cancelled by the security authority
+    let new_authority = Keypair::new();
+    upd_permissions(&mut sim, new_authority.pubkey()).await;
+    let (permissions, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(permissions.master_authority, genesis_keypair.pubkey());
+    assert!(timelock.has_pending_change());
+    assert_eq!(timelock.pending_master_authority, new_authority.pubkey());
+    assert_eq!(timelock.pending_delay, 100);
+    assert_eq!(
+        sim.apply_permissions().await.unwrap_err().unwrap(),
+        OracleError::PermissionsChangeNotReady.into()
+    );
+    sim.cancel_permissions(&genesis_keypair).await.unwrap();
+    let (_, timelock) = get_permissions(&mut sim).await;
+    assert!(!timelock.has_pending_change());
+    assert_eq!(timelock.delay, 100);
+
+    // Staged changes can be applied by anyone once the delay has passed
+    upd_permissions(&mut sim, new_authority.pubkey()).await;
+    let (_, timelock) = get_permissions(&mut sim).await;
+    sim.warp_to_slot(timelock.effective_slot - 1).await.unwrap();
+    assert_eq!(
+        sim.apply_permissions().await.unwrap_err().unwrap(),
+        OracleError::PermissionsChangeNotReady.into()
+    );
+    sim.warp_to_slot(timelock.effective_slot).await.unwrap();
+    sim.apply_permissions().await.unwrap();
+    let (permissions, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(permissions.master_authority, new_authority.pubkey());
+    assert_eq!(permissions.data_curation_authority, new_authority.pubkey());
+    assert_eq!(permissions.security_authority, new_authority.pubkey());
+    assert!(!timelock.has_pending_change());
+
+    // The old authorities can't cancel anymore
+    upd_permissions(&mut sim, genesis_keypair.pubkey()).await;
+    assert_eq!(
+        sim.cancel_permissions(&genesis_keypair)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    // Removing the timelock goes through the timelock too
+    sim.set_permissions_timelock(0).await.unwrap();
+    let (permissions, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(permissions.master_authority, new_authority.pubkey());
+    assert_eq!(timelock.delay, 100);
+    assert_eq!(timelock.pending_delay, 0);
+    assert_eq!(timelock.pending_master_authority, new_authority.pubkey());
+    sim.warp_to_slot(timelock.effective_slot).await.unwrap();
+    sim.apply_permissions().await.unwrap();
+    let (_, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(timelock.delay, 0);
+
+    // Without a delay, changes are applied right away
+    upd_permissions(&mut sim, genesis_keypair.pubkey()).await;
+    let (permissions, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(permissions.master_authority, genesis_keypair.pubkey());
+    assert!(!timelock.has_pending_change());
+}
+
+#[tokio::test]
+async fn test_permissions_frozen_with_program() {
+    let mut sim = PythSimulator::new().await;
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+    sim.set_permissions_timelock(100).await.unwrap();
+    let new_authority = Keypair::new();
+    upd_permissions(&mut sim, new_authority.pubkey()).await;
+    let (_, timelock) = get_permissions(&mut sim).await;
+    sim.warp_to_slot(timelock.effective_slot).await.unwrap();
+
+    // Once the program is immutable, the change staged by its upgrade authority can't land
+    sim.set_upgrade_authority(None).await;
+    assert_eq!(
+        sim.apply_permissions().await.unwrap_err().unwrap(),
+        OracleError::InvalidUpgradeAuthority.into()
+    );
+    let (permissions, timelock) = get_permissions(&mut sim).await;
+    assert_eq!(permissions.master_authority, genesis_keypair.pubkey());
+    assert!(timelock.has_pending_change());
+
+    // The security authority can still drop it
+    sim.cancel_permissions(&genesis_keypair).await.unwrap();
+    let (_, timelock) = get_permissions(&mut sim).await;
+    assert!(!timelock.has_pending_change());
+}
+
+async fn upd_permissions(sim: &mut PythSimulator, authority: Pubkey) {
+    let upgrade_authority = copy_keypair(&sim.upgrade_authority);
+    sim.upd_permissions(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::UpdPermissions.into(),
+            master_authority:        authority,
+            data_curation_authority: authority,
+            security_authority:      authority,
+        },
+        &upgrade_authority,
+    )
+    .await
+    .unwrap();
+}
+
+async fn get_permissions(sim: &mut PythSimulator) -> (PermissionAccount, PermissionTimelock) {
+    let permissions_pubkey = sim.get_permissions_pubkey();
+    let data = sim.get_account(permissions_pubkey).await.unwrap().data;
+    (
+        *load::<PermissionAccount>(&data).unwrap(),
+        pod_read_unaligned(
+            &data[PermissionAccount::TIMELOCK_OFFSET..PermissionAccount::TIMELOCK_ACCOUNT_SPACE],
+        ),
+    )
+}
@@ -0,0 +1,178 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        instruction::{
+            AddPublisherArgs,
+            DelPublisherArgs,
+            InitPriceArgs,
+            OracleCommand,
+            SetExponentArgs,
+            SetMaxLatencyArgs,
+            SetMinPubArgs,
+            UpdPriceArgs,
+        },
+        migration::{
+            find_accounts_with_unknown_flags,
+            Migration,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            AccountSetup,
+            TestClock,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+};
+
+// Bits that no version of the program defines yet
+const UNKNOWN_BITS: u8 = 0b1000_0000;
+
+#[test]
+fn test_unknown_flags_are_kept() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    let price_type = {
+        let mut price_data = PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+        price_data.exponent = -8;
+        price_data.flags = PriceAccountFlags::from_bits_retain(UNKNOWN_BITS)
+            | PriceAccountFlags::ACCUMULATOR_V2
+            | PriceAccountFlags::MESSAGE_BUFFER_CLEARED;
+        price_data.price_type
+    };
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let mut clock_setup = AccountSetup::new_clock();
+    let mut clock = TestClock::new(clock_setup.as_account_info());
+    clock.warp_to_slot(1);
+
+    let admin_accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+    let mut admin_instructions = vec![bytes_of(&AddPublisherArgs {
+        header:    OracleCommand::AddPublisher.into(),
+        publisher: *publisher_account.key,
+    })
+    .to_vec()];
+    for migration in [
+        Migration::DisableAccumulatorV2,
+        Migration::EnableAccumulatorV2,
+        Migration::AllowZeroCi,
+        Migration::ForbidZeroCi,
+        Migration::RequireIsolatedUpdates,
+        Migration::AllowBundledUpdates,
+    ] {
+        admin_instructions.push(
+            migration
+                .instruction(&program_id, funding_account.key, price_account.key)
+                .data,
+        );
+    }
+    admin_instructions.extend([
+        bytes_of(&SetMinPubArgs {
+            header:             OracleCommand::SetMinPub.into(),
+            minimum_publishers: 1,
+            unused_:            [0; 3],
+        })
+        .to_vec(),
+        bytes_of(&SetMaxLatencyArgs {
+            header:      OracleCommand::SetMaxLatency.into(),
+            max_latency: 5,
+            unused_:     [0; 3],
+        })
+        .to_vec(),
+        bytes_of(&SetExponentArgs {
+            header:   OracleCommand::SetExponent.into(),
+            exponent: -6,
+        })
+        .to_vec(),
+        bytes_of(&InitPriceArgs {
+            header: OracleCommand::InitPrice.into(),
+            exponent: -8,
+            price_type,
+        })
+        .to_vec(),
+    ]);
+    for instruction_data in &admin_instructions {
+        process_instruction(&program_id, &admin_accounts, instruction_data).unwrap();
+        assert_eq!(get_unknown_bits(&price_account), UNKNOWN_BITS);
+    }
+
+    let upd_price = UpdPriceArgs {
+        header:          OracleCommand::UpdPrice.into(),
+        status:          PC_STATUS_TRADING,
+        venue:           0,
+        unused_:         [0; 3],
+        price:           100,
+        confidence:      1,
+        publishing_slot: 1,
+        sequence:        0,
+    };
+    process_instruction(
+        &program_id,
+        &[
+            publisher_account.clone(),
+            price_account.clone(),
+            clock.account(),
+        ],
+        bytes_of(&upd_price),
+    )
+    .unwrap();
+    assert_eq!(get_unknown_bits(&price_account), UNKNOWN_BITS);
+
+    let del_publisher = DelPublisherArgs {
+        header:    OracleCommand::DelPublisher.into(),
+        publisher: *publisher_account.key,
+    };
+    process_instruction(&program_id, &admin_accounts, bytes_of(&del_publisher)).unwrap();
+    assert_eq!(get_unknown_bits(&price_account), UNKNOWN_BITS);
+
+    // Off-chain tooling points the accounts out
+    let mut plain_price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let plain_price_account = plain_price_setup.as_account_info();
+    PriceAccount::initialize(&plain_price_account, PC_VERSION).unwrap();
+    let price_data = price_account.try_borrow_data().unwrap().to_vec();
+    let plain_price_data = plain_price_account.try_borrow_data().unwrap().to_vec();
+    assert_eq!(
+        find_accounts_with_unknown_flags([
+            (*price_account.key, price_data.as_slice()),
+            (*plain_price_account.key, plain_price_data.as_slice()),
+        ]),
+        vec![(*price_account.key, UNKNOWN_BITS)]
+    );
+}
+
+fn get_unknown_bits(price_account: &AccountInfo) -> u8 {
+    load_checked::<PriceAccount>(price_account, PC_VERSION)
+        .unwrap()
+        .flags
+        .unknown_bits()
+}
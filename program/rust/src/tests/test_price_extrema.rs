@@ -0,0 +1,109 @@
+use {
+    crate::{
+        accounts::PriceExtrema,
+        instruction::PriceExtremaSummary,
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::Zeroable,
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_price_extrema() {
+    // Feeds without the extension don't track their extrema
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        feed.get_price_extrema(),
+        PriceExtremaSummary {
+            slot: 11,
+            ..PriceExtremaSummary::zeroed()
+        }
+    );
+
+    let mut feed = FeedSimulator::new(1).with_extension::<PriceExtrema>();
+    assert_eq!(
+        feed.set_price_extrema_window(17),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_price_extrema_window(64).unwrap();
+    assert_eq!(feed.extension::<PriceExtrema>().bucket_slots, 4);
+    assert_eq!(feed.extension::<PriceExtrema>().window_slots(), 64);
+
+    // Every update aggregates the quote of the previous slot
+    for (slot, price) in [(100, 100), (101, 90), (102, 120), (103, 110)] {
+        feed.warp_to_slot(slot);
+        feed.publish(0, price, 1).unwrap();
+    }
+    assert_eq!(
+        feed.get_price_extrema(),
+        PriceExtremaSummary {
+            slot:         103,
+            window_slots: 64,
+            min_price:    90,
+            max_price:    120,
+            traded:       1,
+            unused_:      [0; 7],
+        }
+    );
+
+    // Aggregates that aren't trading don't count, the window empties as it slides
+    feed.warp_to_slot(200);
+    feed.publish(0, 105, 1).unwrap();
+    assert_eq!(
+        feed.get_price_extrema(),
+        PriceExtremaSummary {
+            slot: 200,
+            window_slots: 61,
+            ..PriceExtremaSummary::zeroed()
+        }
+    );
+    feed.warp_to_slot(201);
+    feed.publish(0, 105, 1).unwrap();
+    let summary = feed.get_price_extrema();
+    assert_eq!((summary.min_price, summary.max_price), (105, 105));
+
+    // Changing the window starts over, setting it again doesn't
+    feed.set_price_extrema_window(64).unwrap();
+    assert_eq!(feed.get_price_extrema().traded, 1);
+    feed.set_price_extrema_window(32).unwrap();
+    assert_eq!(feed.get_price_extrema().traded, 0);
+    feed.set_price_extrema_window(0).unwrap();
+    feed.warp_to_slot(202);
+    feed.publish(0, 105, 1).unwrap();
+    assert_eq!(
+        feed.get_price_extrema(),
+        PriceExtremaSummary {
+            slot: 202,
+            ..PriceExtremaSummary::zeroed()
+        }
+    );
+}
+
+#[test]
+fn test_price_extrema_window() {
+    let mut extrema = PriceExtrema::new(10);
+    extrema.record_aggregate(5, 5);
+    extrema.record_aggregate(15, 10);
+    extrema.record_aggregate(25, 70);
+    assert_eq!(extrema.window_extrema(25), (Some((5, 70)), 26));
+    assert_eq!(extrema.window_extrema(159), (Some((5, 70)), 160));
+
+    // The window slides one bucket at a time
+    assert_eq!(extrema.window_extrema(160), (Some((10, 70)), 151));
+    assert_eq!(extrema.window_extrema(175), (Some((70, 70)), 156));
+    assert_eq!(extrema.window_extrema(180), (None, 151));
+
+    // The buckets left behind are emptied before they're reused
+    extrema.record_aggregate(180, 40);
+    assert_eq!(extrema.window_extrema(180), (Some((40, 40)), 151));
+    // An aggregate replaced in its slot still counts, earlier slots don't
+    extrema.record_aggregate(180, 45);
+    extrema.record_aggregate(170, 0);
+    assert_eq!(extrema.window_extrema(180), (Some((40, 45)), 151));
+
+    assert_eq!(PriceExtrema::new(0).window_extrema(180), (None, 0));
+}
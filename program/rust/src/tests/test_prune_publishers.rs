@@ -0,0 +1,109 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signer::Signer,
+};
+
+#[test]
+fn test_prune_publishers() {
+    let mut feed = FeedSimulator::new(3);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.publish(1, 100, 1).unwrap();
+    feed.warp_to_slot(15);
+    feed.publish(0, 101, 1).unwrap();
+
+    // Without the registry and an idle limit nothing is removed
+    feed.warp_to_slot(20);
+    feed.prune_publishers(0).unwrap();
+    assert_eq!(feed.price_data().num_, 3);
+
+    // Publisher 1 last published 10 slots ago and publisher 2 never did
+    feed.prune_publishers(5).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, feed.publisher_key(0));
+    assert_eq!(price_data.comp_[0].latest_.price_, 101);
+    assert_eq!(price_data.comp_[1].pub_, Pubkey::default());
+    assert_eq!(price_data.comp_[2].pub_, Pubkey::default());
+
+    assert_eq!(
+        feed.publish(1, 100, 1),
+        Err(OracleError::PublisherNotFound.into())
+    );
+    feed.publish(0, 102, 1).unwrap();
+
+    // Removed publishers can be added back
+    let publisher = feed.publisher_key(1);
+    feed.add_publisher(publisher);
+    feed.warp_to_slot(21);
+    feed.publish(1, 103, 1).unwrap();
+    assert_eq!(feed.price_data().num_, 2);
+}
+
+#[tokio::test]
+async fn test_prune_publishers_with_registry() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+
+    let mut publishers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    publishers.sort();
+    for publisher in &publishers {
+        sim.upd_publisher_registry(*publisher, true, &authority)
+            .await
+            .unwrap();
+        sim.add_publisher_with_registry(&price_keypair, *publisher)
+            .await
+            .unwrap();
+    }
+
+    // Revoking a publisher leaves it in the price account until it's pruned
+    sim.upd_publisher_registry(publishers[1], false, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_publishers(&mut sim, &price_keypair.pubkey()).await,
+        publishers
+    );
+    sim.prune_publishers(&price_keypair, 0, true).await.unwrap();
+    assert_eq!(
+        get_publishers(&mut sim, &price_keypair.pubkey()).await,
+        vec![publishers[0], publishers[2]]
+    );
+
+    // Without the registry, revoked publishers are kept
+    sim.upd_publisher_registry(publishers[0], false, &authority)
+        .await
+        .unwrap();
+    sim.prune_publishers(&price_keypair, 0, false)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_publishers(&mut sim, &price_keypair.pubkey()).await,
+        vec![publishers[0], publishers[2]]
+    );
+}
+
+async fn get_publishers(sim: &mut PythSimulator, price: &Pubkey) -> Vec<Pubkey> {
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(*price)
+        .await
+        .unwrap();
+    price_data.comp_[..price_data.num_ as usize]
+        .iter()
+        .map(|component| component.pub_)
+        .collect()
+}
@@ -0,0 +1,78 @@
+use crate::{
+    accounts::PublishThreshold,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_publish_threshold() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublishThreshold>();
+    feed.post_slot(&[Some((1_000, 1)), Some((1_000, 1))]);
+    assert_eq!(feed.price_data().agg_.pub_slot_, 1);
+
+    feed.set_publish_threshold(100, 5).unwrap();
+    assert_eq!(feed.extension::<PublishThreshold>().heartbeat_slots, 5);
+
+    // Aggregates within 1% of the published one are dropped
+    let published = feed.price_data();
+    feed.post_slot(&[Some((1_005, 1)), Some((1_005, 1))]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 1_000);
+    assert_eq!(price_data.agg_.pub_slot_, 1);
+    assert_eq!(price_data.timestamp_, published.timestamp_);
+    assert_eq!(price_data.valid_slot_, published.valid_slot_);
+    assert_eq!(price_data.last_slot_, 1);
+    assert_eq!(price_data.twap_.val_, published.twap_.val_);
+    assert_eq!(
+        price_data.price_cumulative.price,
+        published.price_cumulative.price
+    );
+    // The quotes were aggregated all the same
+    assert_eq!(price_data.comp_[0].agg_.pub_slot_, 2);
+
+    // A larger move publishes, aggregating again in the slot replaces it whatever the deviation
+    feed.post_slot(&[Some((1_020, 1)), Some((1_020, 1))]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 1_020);
+    assert_eq!(price_data.agg_.pub_slot_, 3);
+    assert_eq!(price_data.prev_price_, 1_000);
+    assert_eq!(price_data.prev_slot_, 1);
+
+    // The heartbeat publishes whatever the deviation
+    let quotes = [Some((1_021, 1)), Some((1_021, 1))];
+    feed.post_updates(&[&quotes, &quotes, &quotes, &quotes]);
+    assert_eq!(feed.price_data().agg_.pub_slot_, 3);
+    feed.post_slot(&quotes);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, 1_021);
+    assert_eq!(price_data.agg_.pub_slot_, 8);
+
+    // A deviation of 0 publishes every aggregate
+    feed.set_publish_threshold(0, 5).unwrap();
+    feed.post_slot(&quotes);
+    assert_eq!(feed.price_data().agg_.pub_slot_, 9);
+}
+
+#[test]
+fn test_publish_threshold_publishes() {
+    let threshold = PublishThreshold {
+        deviation_bps:   100,
+        heartbeat_slots: 10,
+    };
+    assert!(!threshold.publishes((1_000, 0), (1_010, 5)));
+    assert!(threshold.publishes((1_000, 0), (1_011, 5)));
+    assert!(!threshold.publishes((-1_000, 0), (-990, 5)));
+    assert!(threshold.publishes((-1_000, 0), (-989, 5)));
+    assert!(threshold.publishes((1_000, 0), (1_000, 10)));
+    // Any move away from a zero price publishes
+    assert!(!threshold.publishes((0, 0), (0, 5)));
+    assert!(threshold.publishes((0, 0), (1, 5)));
+    assert!(threshold.publishes((i64::MIN, 0), (i64::MAX, 5)));
+    // A publish slot ahead of the aggregation doesn't underflow
+    assert!(!threshold.publishes((1_000, 20), (1_000, 5)));
+
+    let every_aggregate = PublishThreshold {
+        deviation_bps:   0,
+        heartbeat_slots: 10,
+    };
+    assert!(every_aggregate.publishes((1_000, 0), (1_000, 5)));
+}
@@ -0,0 +1,84 @@
+use crate::{
+    accounts::PublisherActivity,
+    c_oracle_header::{
+        PC_STATUS_HALTED,
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_publisher_activity() {
+    let quote = Some((100, 1));
+    let mut feed = FeedSimulator::new(3)
+        .with_min_pub(3)
+        .with_extension::<PublisherActivity>();
+    feed.set_publisher_demotion(2).unwrap();
+
+    feed.post_slot(&[quote, quote, quote]);
+    feed.post_slot(&[quote, quote, quote]);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_TRADING);
+    let activity = feed.extension::<PublisherActivity>();
+    assert!(activity.components[..3]
+        .iter()
+        .all(|entry| entry.missed == 0 && entry.inactive == 0));
+
+    // The feed misses its quorum until the stale publisher is demoted
+    feed.post_slot(&[quote, quote, None]);
+    feed.upd_publisher_status(2, PC_STATUS_HALTED, 3).unwrap();
+    feed.post_slot(&[quote, quote, None]);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(
+        feed.extension::<PublisherActivity>().components[2].missed,
+        1
+    );
+
+    feed.post_slot(&[quote, quote, None]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.num_qt_, 2);
+    assert_eq!(price_data.min_pub_, 3);
+    let entry = feed.extension::<PublisherActivity>().components[2];
+    assert_eq!(entry.publisher, feed.publisher_key(2));
+    assert_eq!(entry.missed, 2);
+    assert_eq!(entry.inactive, 1);
+
+    // The next window with a valid quote of the publisher reactivates it
+    feed.post_slot(&[quote, quote, quote]);
+    assert_eq!(
+        feed.extension::<PublisherActivity>().components[2].inactive,
+        1
+    );
+    feed.post_slot(&[quote, quote, quote]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.num_qt_, 3);
+    let entry = feed.extension::<PublisherActivity>().components[2];
+    assert_eq!(entry.missed, 0);
+    assert_eq!(entry.inactive, 0);
+}
+
+#[test]
+fn test_publisher_activity_disabled() {
+    let quote = Some((100, 1));
+    let mut feed = FeedSimulator::new(2)
+        .with_min_pub(2)
+        .with_extension::<PublisherActivity>();
+
+    // Without a limit the missed windows are counted but nobody is demoted
+    feed.post_updates(&[&[quote, None], &[quote, None], &[quote, None]]);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    let entry = feed.extension::<PublisherActivity>().components[1];
+    assert_eq!(entry.missed, 3);
+    assert_eq!(entry.inactive, 0);
+
+    // Demoting applies to the windows already missed
+    feed.set_publisher_demotion(3).unwrap();
+    feed.post_slot(&[quote, None]);
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(
+        feed.extension::<PublisherActivity>().components[1].inactive,
+        1
+    );
+}
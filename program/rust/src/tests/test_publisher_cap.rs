@@ -0,0 +1,57 @@
+use {
+    crate::{
+        accounts::{
+            PermissionAccount,
+            PublisherCap,
+        },
+        c_oracle_header::PC_NUM_COMP,
+        error::OracleError,
+        instruction::OracleCommand,
+        processor::ALLOW_ZERO_CI,
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+#[test]
+fn test_publisher_cap() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherCap>();
+    assert_eq!(
+        feed.set_publisher_cap(PC_NUM_COMP + 1),
+        Err(ProgramError::InvalidArgument)
+    );
+    // Publishers above the cap must be removed first
+    assert_eq!(
+        feed.set_publisher_cap(1),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    feed.set_publisher_cap(3).unwrap();
+    assert_eq!(feed.extension::<PublisherCap>().max_publishers, 3);
+    feed.try_add_publisher(Pubkey::new_unique()).unwrap();
+    assert_eq!(
+        feed.try_add_publisher(Pubkey::new_unique()),
+        Err(OracleError::PublisherCapReached.into())
+    );
+    assert_eq!(feed.price_data().num_, 3);
+    // The magic keys still toggle the flags of a full feed
+    feed.try_add_publisher(ALLOW_ZERO_CI.into()).unwrap();
+
+    // 0 leaves only the structural limit
+    feed.set_publisher_cap(0).unwrap();
+    feed.try_add_publisher(Pubkey::new_unique()).unwrap();
+    assert_eq!(feed.price_data().num_, 4);
+}
+
+#[test]
+fn test_publisher_cap_authority() {
+    let curator = Pubkey::new_unique();
+    let mut permissions = PermissionAccount::zeroed();
+    permissions.data_curation_authority = curator;
+    assert!(permissions.is_authorized(&curator, OracleCommand::SetPublisherCap));
+    assert!(!permissions.is_authorized(&curator, OracleCommand::AddPublisher));
+}
@@ -0,0 +1,119 @@
+use {
+    crate::{
+        accounts::PublisherRegistryAccount,
+        deserialize::load_account_data,
+        error::OracleError,
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+        },
+    },
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[tokio::test]
+async fn test_publisher_registry() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let publishers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+
+    // The registry doesn't exist yet, publishers aren't checked without it
+    sim.add_publisher(&price_keypair, Pubkey::new_unique())
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.add_publisher_with_registry(&price_keypair, publishers[0])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidReadableAccount.into()
+    );
+
+    // Only the master authority can approve publishers
+    let other_authority = Keypair::new();
+    sim.airdrop(&other_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.upd_publisher_registry(publishers[0], true, &other_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    for publisher in &publishers {
+        sim.upd_publisher_registry(*publisher, true, &authority)
+            .await
+            .unwrap();
+    }
+    assert_eq!(get_publishers(&mut sim).await, publishers);
+    assert_eq!(
+        sim.upd_publisher_registry(publishers[0], true, &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PublisherAlreadyExists.into()
+    );
+
+    // The last publisher takes the place of the revoked one
+    sim.upd_publisher_registry(publishers[0], false, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_publishers(&mut sim).await,
+        vec![publishers[2], publishers[1]]
+    );
+    assert_eq!(
+        sim.upd_publisher_registry(publishers[0], false, &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PublisherNotFound.into()
+    );
+
+    sim.add_publisher_with_registry(&price_keypair, publishers[1])
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.add_publisher_with_registry(&price_keypair, publishers[0])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PublisherNotApproved.into()
+    );
+    // Once the registry exists, leaving it out doesn't skip the approval
+    assert_eq!(
+        sim.add_publisher(&price_keypair, publishers[2])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PublisherNotApproved.into()
+    );
+    sim.add_publisher_with_registry(&price_keypair, publishers[2])
+        .await
+        .unwrap();
+}
+
+async fn get_publishers(sim: &mut PythSimulator) -> Vec<Pubkey> {
+    let registry_pubkey = sim.get_publisher_registry_pubkey();
+    let data = sim.get_account(registry_pubkey).await.unwrap().data;
+    let registry_data = load_account_data::<PublisherRegistryAccount>(&data).unwrap();
+    assert_eq!(
+        registry_data.header.size as usize,
+        PublisherRegistryAccount::entry_offset(registry_data.num_publishers as usize)
+    );
+    PublisherRegistryAccount::publishers(&data)
+        .unwrap()
+        .to_vec()
+}
@@ -0,0 +1,223 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            reward_score,
+            AggregationDelay,
+            PriceComponent,
+            PriceInfo,
+            PublisherRewards,
+            RewardsLedgerAccount,
+            REWARD_SCORE_SCALE,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        processor::DISABLE_ACCUMULATOR_V2,
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+fn price_info(price: i64, conf: u64) -> PriceInfo {
+    PriceInfo {
+        price_: price,
+        conf_: conf,
+        ..PriceInfo::zeroed()
+    }
+}
+
+fn components(publishers: &[Pubkey]) -> Vec<PriceComponent> {
+    publishers
+        .iter()
+        .map(|publisher| PriceComponent {
+            pub_: *publisher,
+            ..PriceComponent::zeroed()
+        })
+        .collect()
+}
+
+#[test]
+fn test_reward_score() {
+    let aggregate = price_info(100, 10);
+    assert_eq!(
+        reward_score(&aggregate, &price_info(100, 1)),
+        REWARD_SCORE_SCALE
+    );
+    assert_eq!(reward_score(&aggregate, &price_info(105, 1)), 750);
+    assert_eq!(reward_score(&aggregate, &price_info(90, 1)), 500);
+    assert_eq!(reward_score(&aggregate, &price_info(110, 1)), 500);
+    assert_eq!(reward_score(&aggregate, &price_info(111, 1)), 0);
+    assert_eq!(reward_score(&aggregate, &price_info(89, 1)), 0);
+
+    // Aggregates without confidence only reward their exact price
+    let aggregate = price_info(100, 0);
+    assert_eq!(
+        reward_score(&aggregate, &price_info(100, 1)),
+        REWARD_SCORE_SCALE
+    );
+    assert_eq!(reward_score(&aggregate, &price_info(101, 1)), 0);
+
+    // The distance doesn't overflow
+    let aggregate = price_info(i64::MIN, u64::MAX);
+    assert_eq!(reward_score(&aggregate, &price_info(i64::MAX, 1)), 500);
+    assert_eq!(reward_score(&price_info(i64::MAX, 0), &aggregate), 0);
+}
+
+#[test]
+fn test_record_aggregation() {
+    let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let mut rewards = PublisherRewards::zeroed();
+    rewards.record_aggregation(&components(&keys[..3]), &[10, 0, 30], 5);
+    assert_eq!(rewards.entries[0].publisher, keys[0]);
+    assert_eq!(rewards.entries[0].unclaimed, 10);
+    assert_eq!(rewards.entries[2].unclaimed, 30);
+
+    // Aggregating again in the slot replaces its scores
+    rewards.record_aggregation(&components(&keys[..3]), &[0, 20, 40], 5);
+    assert_eq!(rewards.entries[0].unclaimed, 0);
+    assert_eq!(rewards.entries[1].unclaimed, 20);
+    assert_eq!(rewards.entries[2].unclaimed, 40);
+    rewards.record_aggregation(&components(&keys[..3]), &[1, 2, 3], 6);
+    assert_eq!(rewards.entries[2].unclaimed, 43);
+
+    // The score of the last slot scored can't be claimed yet
+    assert_eq!(rewards.claim(&keys[2]), 40);
+    assert_eq!(rewards.claim(&keys[2]), 0);
+    assert_eq!(rewards.claim(&keys[3]), 0);
+    rewards.record_aggregation(&components(&keys[..3]), &[0, 0, 0], 7);
+    assert_eq!(rewards.claim(&keys[2]), 3);
+
+    // The entries follow the publishers, a new publisher takes the entry of a removed one
+    rewards.record_aggregation(&components(&[keys[3], keys[1], keys[0]]), &[5, 5, 5], 8);
+    assert_eq!(rewards.entries[0].publisher, keys[3]);
+    assert_eq!(rewards.entries[0].unclaimed, 5);
+    assert_eq!(rewards.entries[1].publisher, keys[1]);
+    assert_eq!(rewards.entries[1].unclaimed, 27);
+    assert_eq!(rewards.entries[2].publisher, keys[0]);
+    assert_eq!(rewards.entries[2].unclaimed, 6);
+    assert!(rewards
+        .entries
+        .iter()
+        .all(|entry| entry.publisher != keys[2]));
+}
+
+#[test]
+fn test_publisher_rewards() {
+    let mut feed = FeedSimulator::new(4)
+        .with_extension::<AggregationDelay>()
+        .with_extension::<PublisherRewards>();
+    feed.set_aggregation_delay(1).unwrap();
+    feed.set_publisher_rewards(true).unwrap();
+    assert_eq!(feed.extension::<PublisherRewards>().enabled, 1);
+
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 2).unwrap();
+    feed.publish(1, 102, 2).unwrap();
+    feed.publish(2, 98, 2).unwrap();
+    feed.publish(3, 200, 2).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 2).unwrap();
+
+    // The aggregation of slot 11 scored the quotes of slot 10 within its confidence interval
+    let price_data = feed.price_data();
+    let rewards = feed.extension::<PublisherRewards>();
+    for index in 0..4 {
+        assert_eq!(rewards.entries[index].publisher, feed.publisher_key(index));
+        assert_eq!(
+            rewards.entries[index].unclaimed,
+            reward_score(&price_data.agg_, &price_data.comp_[index].agg_)
+        );
+    }
+    assert!(rewards.entries[0].unclaimed > 0);
+    assert_eq!(rewards.entries[3].unclaimed, 0);
+
+    // Aggregating again in the slot doesn't score it twice
+    feed.set_aggregation_delay(0).unwrap();
+    feed.publish(1, 101, 2).unwrap();
+    let price_data = feed.price_data();
+    let rewards = feed.extension::<PublisherRewards>();
+    assert_eq!(
+        rewards.entries[1].unclaimed,
+        reward_score(&price_data.agg_, &price_data.comp_[1].agg_)
+    );
+
+    // The scores stay once the feed stops scoring
+    feed.set_publisher_rewards(false).unwrap();
+    let unclaimed = feed.extension::<PublisherRewards>().entries[0].unclaimed;
+    feed.warp_to_slot(12);
+    feed.publish(0, 100, 2).unwrap();
+    assert_eq!(
+        feed.extension::<PublisherRewards>().entries[0].unclaimed,
+        unclaimed
+    );
+}
+
+#[tokio::test]
+async fn test_claim_rewards() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, Pubkey::from(DISABLE_ACCUMULATOR_V2))
+        .await
+        .unwrap();
+    let publisher = Keypair::new();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let quote = || Quote {
+        price:      100,
+        confidence: 1,
+        status:     PC_STATUS_TRADING,
+    };
+    let ledger = sim.get_rewards_ledger_pubkey(&publisher.pubkey());
+
+    // Feeds without the extension have nothing to claim
+    sim.claim_rewards(&publisher, price).await.unwrap();
+    let ledger_data = sim
+        .get_account_data_as::<RewardsLedgerAccount>(ledger)
+        .await
+        .unwrap();
+    assert_eq!(ledger_data.publisher, publisher.pubkey());
+    assert_eq!(ledger_data.total_score, 0);
+    assert_eq!(ledger_data.num_claims, 1);
+
+    // The quote of slot 10 is scored by the aggregation of slot 11, and can be claimed once
+    // slot 12 is scored
+    sim.set_publisher_rewards(&price_keypair, true)
+        .await
+        .unwrap();
+    for slot in 10..13 {
+        sim.warp_to_slot(slot).await.unwrap();
+        sim.upd_price(&publisher, price, quote()).await.unwrap();
+    }
+    sim.claim_rewards(&publisher, price).await.unwrap();
+    let ledger_data = sim
+        .get_account_data_as::<RewardsLedgerAccount>(ledger)
+        .await
+        .unwrap();
+    assert_eq!(ledger_data.total_score, REWARD_SCORE_SCALE);
+    assert_eq!(ledger_data.num_claims, 2);
+    let price_account = sim.get_account(price).await.unwrap();
+    let rewards = get_extension::<PublisherRewards>(&price_account.data)
+        .unwrap()
+        .unwrap();
+    assert_eq!(rewards.entries[0].unclaimed, REWARD_SCORE_SCALE);
+}
@@ -0,0 +1,170 @@
+use {
+    crate::{
+        accounts::PublisherSampling,
+        c_oracle_header::{
+            PC_NUM_COMP,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::{
+        hash::{
+            hash,
+            Hash,
+        },
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    std::collections::HashMap,
+};
+
+fn with_sample_size(sample_size: u32) -> PublisherSampling {
+    PublisherSampling {
+        sample_size,
+        unused_: 0,
+    }
+}
+
+fn seeds(count: u64) -> impl Iterator<Item = Hash> {
+    (0..count).map(|i| hash(&i.to_le_bytes()))
+}
+
+#[test]
+fn test_sample() {
+    let candidates = [1, 3, 4, 6, 7, 8, 10, 12, 13, 15];
+    let seed = hash(b"seed");
+
+    let sample = with_sample_size(3).sample(&seed, &candidates);
+    assert_eq!(sample.len(), 3);
+    assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(sample.iter().all(|index| candidates.contains(index)));
+    assert_eq!(with_sample_size(3).sample(&seed, &candidates), sample);
+
+    // Small sets and a sample size of 0 keep every candidate
+    assert_eq!(with_sample_size(10).sample(&seed, &candidates), candidates);
+    assert_eq!(with_sample_size(0).sample(&seed, &candidates), candidates);
+    assert_eq!(with_sample_size(3).sample(&seed, &[]), Vec::<usize>::new());
+
+    // Every feed and slot draws its own sample
+    let slot_hash = hash(b"slot hash");
+    let price_account = Pubkey::new_unique();
+    let seed = PublisherSampling::seed(&slot_hash, &price_account, 10);
+    assert_eq!(
+        PublisherSampling::seed(&slot_hash, &price_account, 10),
+        seed
+    );
+    assert_ne!(
+        PublisherSampling::seed(&slot_hash, &price_account, 11),
+        seed
+    );
+    assert_ne!(
+        PublisherSampling::seed(&slot_hash, &Pubkey::new_unique(), 10),
+        seed
+    );
+    assert_ne!(
+        PublisherSampling::seed(&hash(b"other"), &price_account, 10),
+        seed
+    );
+}
+
+#[test]
+fn test_sample_distribution() {
+    const DRAWS: u64 = 12_000;
+    let candidates: Vec<usize> = (0..10).collect();
+    let sampling = with_sample_size(3);
+
+    let mut counts = [0u64; 10];
+    let mut subsets: HashMap<Vec<usize>, u64> = HashMap::new();
+    for seed in seeds(DRAWS) {
+        let sample = sampling.sample(&seed, &candidates);
+        for index in &sample {
+            counts[*index] += 1;
+        }
+        *subsets.entry(sample).or_default() += 1;
+    }
+
+    // Every candidate is drawn 3 times out of 10, the standard deviation is about 50 draws
+    for count in counts {
+        assert!(count.abs_diff(DRAWS * 3 / 10) < 300, "{counts:?}");
+    }
+
+    // Every one of the 120 subsets is as likely, the chi-squared statistic with 119 degrees of
+    // freedom averages 119 with a standard deviation of about 15
+    assert_eq!(subsets.len(), 120);
+    let expected = DRAWS as f64 / 120.0;
+    let chi_squared: f64 = subsets
+        .values()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum();
+    assert!(chi_squared < 200.0, "{chi_squared}");
+
+    // Sampling the whole component array is as uniform
+    let candidates: Vec<usize> = (0..PC_NUM_COMP as usize).collect();
+    let sampling = with_sample_size(8);
+    let mut counts = vec![0u64; candidates.len()];
+    for seed in seeds(DRAWS) {
+        for index in sampling.sample(&seed, &candidates) {
+            counts[index] += 1;
+        }
+    }
+    let expected = DRAWS * 8 / PC_NUM_COMP as u64;
+    for count in &counts {
+        assert!(count.abs_diff(expected) < 200, "{counts:?}");
+    }
+}
+
+#[test]
+fn test_publisher_sampling() {
+    let mut feed = FeedSimulator::new(5).with_extension::<PublisherSampling>();
+    assert_eq!(
+        feed.set_publisher_sampling(PC_NUM_COMP + 1),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_publisher_sampling(3).unwrap();
+    assert_eq!(feed.extension::<PublisherSampling>().sample_size, 3);
+
+    // Without the slot hashes every quote counts
+    feed.warp_to_slot(10);
+    for publisher in 0..5 {
+        feed.publish(publisher, 100 + publisher as i64, 1).unwrap();
+    }
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().num_qt_, 5);
+
+    // The aggregation of the next slot samples the quotes of slot 11 with its slot hash
+    for publisher in 1..5 {
+        feed.publish(publisher, 100 + publisher as i64, 1).unwrap();
+    }
+    feed.warp_to_slot(12);
+    let slot_hash = hash(b"slot 11");
+    feed.pass_slot_hashes(slot_hash);
+    feed.publish(0, 100, 1).unwrap();
+
+    let seed = PublisherSampling::seed(&slot_hash, &feed.price_key(), 12);
+    let sample = feed
+        .extension::<PublisherSampling>()
+        .sample(&seed, &[0, 1, 2, 3, 4]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_qt_, 3);
+    for (index, component) in price_data.comp_[..5].iter().enumerate() {
+        let expected_status = if sample.contains(&index) {
+            PC_STATUS_TRADING
+        } else {
+            PC_STATUS_IGNORED
+        };
+        assert_eq!(component.agg_.status_, expected_status);
+    }
+    // The quotes left out still count in later samples
+    assert!(price_data.comp_[..5]
+        .iter()
+        .all(|component| component.latest_.status_ == PC_STATUS_TRADING));
+
+    // A sample size of 0 counts every quote
+    feed.set_publisher_sampling(0).unwrap();
+    feed.warp_to_slot(13);
+    feed.pass_slot_hashes(hash(b"slot 12"));
+    feed.publish(1, 101, 1).unwrap();
+    assert_eq!(feed.price_data().num_qt_, 5);
+}
@@ -0,0 +1,171 @@
+use {
+    crate::{
+        accounts::PublisherSequences,
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPriceArgs,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_publisher_sequences() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherSequences>();
+    let publisher = feed.publisher_key(0);
+
+    // Nothing is checked until the checks are enabled
+    feed.warp_to_slot(10);
+    feed.publish_sequenced(0, 100, 1, 5).unwrap();
+    assert_eq!(
+        feed.extension::<PublisherSequences>().latest(0, &publisher),
+        0
+    );
+
+    feed.set_sequence_tracking(true).unwrap();
+    assert_eq!(feed.extension::<PublisherSequences>().enabled, 1);
+    feed.warp_to_slot(11);
+    feed.publish_sequenced(0, 101, 1, 5).unwrap();
+    assert_eq!(
+        feed.extension::<PublisherSequences>().latest(0, &publisher),
+        5
+    );
+
+    // Replayed and reordered quotes are rejected, even for a later slot
+    feed.warp_to_slot(12);
+    assert_eq!(
+        feed.publish_sequenced(0, 102, 1, 5),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    assert_eq!(
+        feed.publish_sequenced(0, 102, 1, 4),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    // Numbers can skip
+    feed.publish_sequenced(0, 102, 1, 9).unwrap();
+
+    // The numbers are per publisher
+    feed.publish_sequenced(1, 102, 1, 1).unwrap();
+    let sequences = feed.extension::<PublisherSequences>();
+    assert_eq!(sequences.latest(0, &publisher), 9);
+    assert_eq!(sequences.latest(1, &feed.publisher_key(1)), 1);
+
+    // Quotes without a number aren't checked, nor recorded
+    feed.warp_to_slot(13);
+    feed.publish(0, 103, 1).unwrap();
+    assert_eq!(
+        feed.extension::<PublisherSequences>().latest(0, &publisher),
+        9
+    );
+
+    // Stopping keeps the numbers
+    feed.set_sequence_tracking(false).unwrap();
+    feed.warp_to_slot(14);
+    feed.publish_sequenced(0, 104, 1, 2).unwrap();
+    feed.set_sequence_tracking(true).unwrap();
+    feed.warp_to_slot(15);
+    assert_eq!(
+        feed.publish_sequenced(0, 105, 1, 9),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    feed.publish_sequenced(0, 105, 1, 10).unwrap();
+}
+
+#[test]
+fn test_publisher_sequences_follow_components() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherSequences>();
+    feed.set_sequence_tracking(true).unwrap();
+    feed.warp_to_slot(10);
+    feed.publish_sequenced(0, 100, 1, 5).unwrap();
+    feed.publish_sequenced(1, 100, 1, 7).unwrap();
+
+    // A publisher sorting before the others moves their components
+    let mut key = [0u8; 32];
+    key[31] = 0xff;
+    let added = feed.add_new_publisher(Pubkey::from(key));
+    assert_eq!(feed.price_data().comp_[0].pub_, Pubkey::from(key));
+    let sequences = feed.extension::<PublisherSequences>();
+    assert_eq!(sequences.latest(1, &feed.publisher_key(0)), 5);
+    assert_eq!(sequences.latest(2, &feed.publisher_key(1)), 7);
+    assert_eq!(sequences.latest(0, &feed.publisher_key(added)), 0);
+
+    // The numbers still apply to the publishers that moved
+    feed.warp_to_slot(11);
+    assert_eq!(
+        feed.publish_sequenced(0, 101, 1, 5),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    assert_eq!(
+        feed.publish_sequenced(1, 101, 1, 7),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    feed.publish_sequenced(0, 101, 1, 6).unwrap();
+    feed.publish_sequenced(added, 101, 1, 1).unwrap();
+
+    // Pruning the idle publisher moves the others back
+    feed.warp_to_slot(100);
+    feed.publish_sequenced(0, 102, 1, 7).unwrap();
+    feed.publish_sequenced(1, 102, 1, 8).unwrap();
+    feed.prune_publishers(50).unwrap();
+    assert_eq!(feed.price_data().comp_[0].pub_, feed.publisher_key(0));
+    feed.warp_to_slot(101);
+    assert_eq!(
+        feed.publish_sequenced(0, 103, 1, 7),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    assert_eq!(
+        feed.publish_sequenced(1, 103, 1, 8),
+        Err(OracleError::SequenceReplayed.into())
+    );
+    feed.publish_sequenced(0, 103, 1, 8).unwrap();
+}
+
+#[test]
+fn test_publisher_sequences_invalid_extension() {
+    // An invalid extension region fails the update rather than skipping the check
+    let mut feed = FeedSimulator::new(1)
+        .with_extension::<PublisherSequences>()
+        .with_corrupt_extensions();
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish_sequenced(0, 100, 1, 5),
+        Err(OracleError::InvalidExtension.into())
+    );
+}
+
+#[test]
+fn test_unsequenced_args() {
+    let mut feed = FeedSimulator::new(1).with_extension::<PublisherSequences>();
+    feed.set_sequence_tracking(true).unwrap();
+    feed.warp_to_slot(10);
+    feed.publish_sequenced(0, 100, 1, 3).unwrap();
+
+    // Publishers can keep sending the arguments without a number
+    let args = UpdPriceArgs {
+        header:          OracleCommand::UpdPrice.into(),
+        status:          PC_STATUS_TRADING,
+        venue:           0,
+        unused_:         [0; 3],
+        price:           101,
+        confidence:      1,
+        publishing_slot: 11,
+        sequence:        0,
+    };
+    feed.warp_to_slot(11);
+    let data = bytemuck::bytes_of(&args);
+    assert_eq!(
+        feed.upd_price_raw(0, &data[..UpdPriceArgs::UNSEQUENCED_SIZE - 1]),
+        Err(OracleError::InstructionDataTooShort.into())
+    );
+    feed.upd_price_raw(0, &data[..UpdPriceArgs::UNSEQUENCED_SIZE])
+        .unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.price_, 101);
+    assert_eq!(
+        feed.extension::<PublisherSequences>()
+            .latest(0, &feed.publisher_key(0)),
+        3
+    );
+}
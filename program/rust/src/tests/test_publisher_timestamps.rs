@@ -0,0 +1,81 @@
+use crate::{
+    accounts::{
+        get_component_publish_time,
+        PublisherTimestamps,
+    },
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_publisher_timestamps() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherTimestamps>();
+
+    // Nothing is recorded until the recording is enabled
+    feed.warp_to_slot(10);
+    feed.warp_to_timestamp(1_000);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 0),
+        None
+    );
+
+    feed.set_publisher_timestamps(true).unwrap();
+    assert_eq!(feed.extension::<PublisherTimestamps>().enabled, 1);
+    feed.publish(1, 100, 1).unwrap();
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 1),
+        Some(1_000)
+    );
+
+    // The next slot aggregates the quotes of slot 10 at the time of the clock
+    feed.warp_to_slot(11);
+    feed.warp_to_timestamp(1_004);
+    feed.publish(0, 101, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.get_latest_price().publish_slot, 10);
+    assert_eq!(price_data.get_latest_price().publish_time, 1_004);
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 0),
+        Some(1_004)
+    );
+
+    // The aggregated quote of publisher 1 is still its latest one
+    let timestamps = feed.extension::<PublisherTimestamps>();
+    let component = price_data.comp_[1];
+    assert_eq!(
+        timestamps.time_of(1, &component.pub_, &component.agg_),
+        Some(1_000)
+    );
+    // The aggregated quote of publisher 0 is older than its latest one
+    let component = price_data.comp_[0];
+    assert_eq!(
+        timestamps.time_of(0, &component.pub_, &component.agg_),
+        None
+    );
+    // Entries of another publisher don't apply
+    assert_eq!(
+        timestamps.time_of(1, &component.pub_, &component.latest_),
+        None
+    );
+
+    // Components that aren't in use have no timestamp
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 2),
+        None
+    );
+
+    // Stopping keeps the recorded timestamps
+    feed.set_publisher_timestamps(false).unwrap();
+    feed.warp_to_slot(12);
+    feed.warp_to_timestamp(1_008);
+    feed.publish(1, 102, 1).unwrap();
+    assert_eq!(feed.extension::<PublisherTimestamps>().enabled, 0);
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 0),
+        Some(1_004)
+    );
+    assert_eq!(
+        get_component_publish_time(feed.price_account_data(), 1),
+        None
+    );
+}
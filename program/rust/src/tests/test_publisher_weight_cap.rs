@@ -0,0 +1,219 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            PublisherLink,
+            PublisherLinkageAccount,
+            PublisherWeightCap,
+        },
+        c_oracle_header::{
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+        },
+        deserialize::load_account_data,
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_publisher_weight_cap() {
+    // Publishers 0 and 1 are controlled by the same entity
+    let mut feed = FeedSimulator::new(3).with_extension::<PublisherWeightCap>();
+    let entity = feed.publisher_key(0);
+    let links = [
+        PublisherLink {
+            publisher: feed.publisher_key(1),
+            entity,
+        },
+        // Not a publisher of the feed
+        PublisherLink {
+            publisher: Pubkey::new_unique(),
+            entity,
+        },
+    ];
+    feed.set_publisher_weight_cap(1, &links).unwrap();
+    let weight_cap = feed.extension::<PublisherWeightCap>();
+    assert_eq!(weight_cap.max_quotes_per_entity, 1);
+    for (publisher, entity) in [(0, entity), (1, entity), (2, feed.publisher_key(2))] {
+        assert_eq!(
+            weight_cap.components[publisher],
+            PublisherLink {
+                publisher: feed.publisher_key(publisher),
+                entity,
+            }
+        );
+    }
+    assert_eq!(weight_cap.components[3], PublisherLink::zeroed());
+
+    // Only the quote of publisher 0 counts for the entity, the aggregate is the one of a feed
+    // without publisher 1
+    let mut reference = FeedSimulator::new(2);
+    reference.post_updates(&[&[Some((100, 1)), Some((200, 1))], &[Some((100, 1))]]);
+    feed.post_updates(&[
+        &[Some((100, 1)), Some((120, 1)), Some((200, 1))],
+        &[Some((100, 1))],
+    ]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, reference.price_data().agg_.price_);
+    assert_eq!(price_data.agg_.conf_, reference.price_data().agg_.conf_);
+    assert_eq!(price_data.num_qt_, 2);
+    assert_eq!(price_data.comp_[0].agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.comp_[1].agg_.status_, PC_STATUS_IGNORED);
+    assert_eq!(price_data.comp_[2].agg_.status_, PC_STATUS_TRADING);
+    // The capped quote is still the latest quote of publisher 1
+    assert_eq!(price_data.comp_[1].latest_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.comp_[1].latest_.price_, 120);
+
+    // The most recent quote of the entity counts
+    feed.post_slot(&[None, Some((120, 1)), Some((200, 1))]);
+    feed.post_slot(&[None, None, Some((200, 1))]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_qt_, 2);
+    assert_eq!(price_data.comp_[0].agg_.status_, PC_STATUS_IGNORED);
+    assert_eq!(price_data.comp_[1].agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.comp_[1].agg_.price_, 120);
+
+    // Without a cap every quote counts
+    feed.set_publisher_weight_cap(0, &links).unwrap();
+    feed.post_slot(&[Some((100, 1)), Some((120, 1)), Some((200, 1))]);
+    feed.post_slot(&[Some((100, 1))]);
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_qt_, 3);
+    assert_eq!(price_data.agg_.price_, 120);
+}
+
+#[tokio::test]
+async fn test_publisher_linkage() {
+    let mut sim = PythSimulator::new().await;
+    let authority = copy_keypair(&sim.genesis_keypair);
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let publishers: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+    let entity = Pubkey::new_unique();
+    for publisher in &publishers {
+        sim.add_publisher(&price_keypair, *publisher).await.unwrap();
+    }
+
+    // The linkage account doesn't exist yet
+    assert_eq!(
+        sim.set_publisher_weight_cap(&price_keypair, 1)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidReadableAccount.into()
+    );
+
+    // Only the master authority can link publishers
+    let other_authority = Keypair::new();
+    sim.airdrop(&other_authority.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.upd_publisher_linkage(publishers[0], entity, &other_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+
+    for publisher in &publishers {
+        sim.upd_publisher_linkage(*publisher, entity, &authority)
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        get_links(&mut sim).await,
+        publishers
+            .iter()
+            .map(|publisher| PublisherLink {
+                publisher: *publisher,
+                entity,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // Linking a linked publisher replaces its entity
+    let other_entity = Pubkey::new_unique();
+    sim.upd_publisher_linkage(publishers[1], other_entity, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_links(&mut sim).await[1],
+        PublisherLink {
+            publisher: publishers[1],
+            entity:    other_entity,
+        }
+    );
+
+    // The last link takes the place of the removed one
+    sim.upd_publisher_linkage(publishers[0], Pubkey::default(), &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_links(&mut sim).await,
+        vec![
+            PublisherLink {
+                publisher: publishers[2],
+                entity,
+            },
+            PublisherLink {
+                publisher: publishers[1],
+                entity:    other_entity,
+            },
+        ]
+    );
+    assert_eq!(
+        sim.upd_publisher_linkage(publishers[0], Pubkey::default(), &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PublisherNotFound.into()
+    );
+
+    // The feed copies the entities of its publishers
+    sim.set_publisher_weight_cap(&price_keypair, 1)
+        .await
+        .unwrap();
+    let data = sim.get_account(price_keypair.pubkey()).await.unwrap().data;
+    let weight_cap = get_extension::<PublisherWeightCap>(&data).unwrap().unwrap();
+    assert_eq!(weight_cap.max_quotes_per_entity, 1);
+    let entity_of = |publisher: &Pubkey| {
+        weight_cap
+            .components
+            .iter()
+            .find(|link| link.publisher == *publisher)
+            .map(|link| link.entity)
+    };
+    assert_eq!(entity_of(&publishers[0]), Some(publishers[0]));
+    assert_eq!(entity_of(&publishers[1]), Some(other_entity));
+    assert_eq!(entity_of(&publishers[2]), Some(entity));
+}
+
+async fn get_links(sim: &mut PythSimulator) -> Vec<PublisherLink> {
+    let linkage_pubkey = sim.get_publisher_linkage_pubkey();
+    let data = sim.get_account(linkage_pubkey).await.unwrap().data;
+    let linkage_data = load_account_data::<PublisherLinkageAccount>(&data).unwrap();
+    assert_eq!(
+        linkage_data.header.size as usize,
+        PublisherLinkageAccount::entry_offset(linkage_data.num_links as usize)
+    );
+    PublisherLinkageAccount::links(&data).unwrap().to_vec()
+}
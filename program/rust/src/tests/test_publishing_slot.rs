@@ -0,0 +1,83 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        error::OracleError,
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+        },
+        processor::process_instruction,
+        tests::test_utils::{
+            AccountSetup,
+            FeedSimulator,
+        },
+    },
+    bytemuck::bytes_of,
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_publishing_slot_in_future() {
+    // Publishing slots can't be ahead of the clock
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 11),
+        Err(OracleError::PublishingSlotInFuture.into())
+    );
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 100, 1, u64::MAX),
+        Err(OracleError::PublishingSlotInFuture.into())
+    );
+    assert_eq!(feed.price_data().comp_[0].latest_.pub_slot_, 0);
+
+    // Accepted publishing slots are stored as sent
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 9).unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.pub_slot_, 9);
+    feed.upd_price(0, PC_STATUS_TRADING, 101, 1, 10).unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.pub_slot_, 10);
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 102, 1, 10),
+        Err(OracleError::StaleSubmission.into())
+    );
+    assert_eq!(feed.price_data().comp_[0].latest_.price_, 101);
+
+    // The check doesn't overflow at the end of the slot range
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(u64::MAX);
+    feed.upd_price(0, PC_STATUS_TRADING, 103, 1, u64::MAX)
+        .unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.pub_slot_, u64::MAX);
+}
+
+#[test]
+fn test_set_publishing_slot_tolerance_removed() {
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+
+    let header: CommandHeader = OracleCommand::SetPublishingSlotTolerance.into();
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[funding_account, price_account, permissions_account],
+            bytes_of(&header),
+        ),
+        Err(OracleError::UnrecognizedInstruction.into())
+    );
+}
@@ -0,0 +1,157 @@
+use {
+    crate::{
+        accounts::{
+            PriceInfo,
+            QuoteConversion,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+};
+
+fn quote(price: i64, conf: u64) -> PriceInfo {
+    PriceInfo {
+        price_: price,
+        conf_: conf,
+        ..PriceInfo::zeroed()
+    }
+}
+
+fn conversion(exponent: i32, divide: bool) -> QuoteConversion {
+    QuoteConversion {
+        exponent,
+        divide: divide as u8,
+        ..QuoteConversion::zeroed()
+    }
+}
+
+#[test]
+fn test_convert() {
+    // 100.00 ± 1.00 USD at 0.90 ± 0.01 EUR per USD is 90.00 EUR, within 101 * 0.91 = 91.91
+    let multiply = conversion(-4, false);
+    assert_eq!(
+        multiply.convert(&quote(100_00, 1_00), -2, &quote(90, 1), -2),
+        Some((90_0000, 1_9100))
+    );
+    assert_eq!(
+        multiply.convert(&quote(-100_00, 1_00), -2, &quote(90, 1), -2),
+        Some((-90_0000, 1_9100))
+    );
+    // Rounded toward zero, the confidence rounded up
+    assert_eq!(
+        conversion(0, false).convert(&quote(100_00, 1_00), -2, &quote(90, 1), -2),
+        Some((90, 2))
+    );
+
+    // 100.00 ± 1.00 USD at 1.25 ± 0.05 USD per EUR is 80.00 EUR, within 101 / 1.20 = 84.1667
+    let divide = conversion(-4, true);
+    assert_eq!(
+        divide.convert(&quote(100_00, 1_00), -2, &quote(1_25, 5), -2),
+        Some((80_0000, 4_1667))
+    );
+    assert_eq!(
+        divide.convert(&quote(100_00, 1_00), -2, &quote(-1_25, 5), -2),
+        Some((-80_0000, 4_1667))
+    );
+    // The conversion can't include 0
+    assert_eq!(
+        divide.convert(&quote(100_00, 1_00), -2, &quote(1_25, 1_25), -2),
+        None
+    );
+    assert_eq!(
+        divide.convert(&quote(100_00, 0), -2, &quote(0, 0), -2),
+        None
+    );
+
+    // Results that don't fit an i64 fail
+    assert_eq!(
+        conversion(-12, false).convert(&quote(i64::MAX, 0), 12, &quote(i64::MAX, 0), 12),
+        None
+    );
+    assert_eq!(
+        conversion(12, false).convert(&quote(i64::MAX, 0), -12, &quote(i64::MAX, 0), -12),
+        Some((85, 0))
+    );
+}
+
+#[test]
+fn test_convert_price() {
+    let mut feed = FeedSimulator::new(1)
+        .with_exponent(-2)
+        .with_extension::<QuoteConversion>();
+    let mut conversion = feed.new_sibling(1).with_exponent(-2);
+    let mut other = feed.new_sibling(1).with_exponent(-2);
+
+    // The feed doesn't convert its aggregate yet
+    assert_eq!(
+        feed.convert_price(&mut conversion),
+        Err(OracleError::InvalidConversionFeed.into())
+    );
+    assert_eq!(
+        feed.set_quote_conversion(feed.price_key(), -4, true),
+        Err(OracleError::InvalidConversionFeed.into())
+    );
+    assert_eq!(
+        feed.set_quote_conversion(conversion.price_key(), -13, true),
+        Err(OracleError::InvalidExponent.into())
+    );
+    feed.set_quote_conversion(conversion.price_key(), -4, true)
+        .unwrap();
+    assert_eq!(
+        feed.convert_price(&mut other),
+        Err(OracleError::InvalidConversionFeed.into())
+    );
+
+    feed.post_updates(&[&[Some((100_00, 1_00))], &[Some((100_00, 1_00))]]);
+    conversion.post_updates(&[&[Some((1_25, 5))], &[Some((1_25, 5))]]);
+    feed.convert_price(&mut conversion).unwrap();
+
+    let agg = feed.price_data().agg_;
+    let conversion_agg = conversion.price_data().agg_;
+    let converted = feed.extension::<QuoteConversion>().converted;
+    assert_eq!(converted.status_, PC_STATUS_TRADING);
+    assert_eq!(converted.price_, 80_0000);
+    assert_eq!(
+        Some((converted.price_, converted.conf_)),
+        feed.extension::<QuoteConversion>()
+            .convert(&agg, -2, &conversion_agg, -2)
+    );
+    assert_eq!(
+        converted.pub_slot_,
+        agg.pub_slot_.min(conversion_agg.pub_slot_)
+    );
+
+    // Once the conversion feed stops trading, the last converted price is kept
+    conversion.advance_slot();
+    let slot = conversion.slot();
+    conversion
+        .upd_price(0, PC_STATUS_UNKNOWN, 0, 0, slot)
+        .unwrap();
+    conversion.advance_slot();
+    let slot = conversion.slot();
+    conversion
+        .upd_price(0, PC_STATUS_UNKNOWN, 0, 0, slot)
+        .unwrap();
+    assert_eq!(conversion.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    feed.convert_price(&mut conversion).unwrap();
+    let stale = feed.extension::<QuoteConversion>().converted;
+    assert_eq!(stale.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(stale.price_, 80_0000);
+
+    // Changing the conversion drops the converted price
+    feed.set_quote_conversion(conversion.price_key(), -4, false)
+        .unwrap();
+    assert_eq!(feed.extension::<QuoteConversion>().converted.price_, 0);
+    feed.set_quote_conversion(Pubkey::default(), 0, false)
+        .unwrap();
+    assert_eq!(
+        feed.convert_price(&mut conversion),
+        Err(OracleError::InvalidConversionFeed.into())
+    );
+}
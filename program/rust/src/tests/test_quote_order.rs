@@ -0,0 +1,185 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceComponent,
+            QuoteOrder,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::{
+        bytes_of,
+        Zeroable,
+    },
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+/// Price account of `feed` without the publisher keys, which differ between feeds.
+fn price_state(feed: &mut FeedSimulator) -> PriceAccount {
+    let mut price_data = feed.price_data();
+    for component in price_data.comp_.iter_mut() {
+        component.pub_ = Pubkey::default();
+    }
+    price_data
+}
+
+/// Whether every list of `order` holds the components in it sorted by their quotes.
+fn is_sorted(order: &QuoteOrder, components: &[PriceComponent]) -> bool {
+    order.lists.iter().enumerate().all(|(list, entries)| {
+        entries[..order.len as usize].windows(2).all(|pair| {
+            let key = |index: u8| {
+                let quote = components[usize::from(index)].latest_;
+                let conf = i128::from(quote.conf_);
+                i128::from(quote.price_) + (list as i128 - 1) * conf
+            };
+            key(pair[0]) <= key(pair[1])
+        })
+    })
+}
+
+/// A feed maintaining its quote order and a feed sorting its quotes go through the same random
+/// updates, with ties, invalid quotes, stale quotes and publishers being removed and added back.
+/// Their price accounts must stay identical.
+#[test]
+fn test_quote_order_matches_sort() {
+    let mut rng = StdRng::seed_from_u64(42);
+
+    for num_publishers in [1, 2, 5, 32, 64] {
+        let mut ordered = FeedSimulator::new(num_publishers).with_extension::<QuoteOrder>();
+        ordered.set_quote_order(true).unwrap();
+        let mut reference = FeedSimulator::new(num_publishers);
+        let mut last_pub_slots = vec![0; num_publishers];
+
+        for slot in 1..=200 {
+            ordered.warp_to_slot(slot);
+            reference.warp_to_slot(slot);
+
+            // A quarter of the publishers goes quiet, gets pruned and is added back
+            if slot == 100 {
+                assert_eq!(ordered.prune_publishers(20), reference.prune_publishers(20));
+            }
+            if slot == 120 {
+                for publisher in (0..num_publishers).filter(|publisher| publisher % 4 == 3) {
+                    ordered.add_publisher(ordered.publisher_key(publisher));
+                    reference.add_publisher(reference.publisher_key(publisher));
+                }
+            }
+
+            for publisher in 0..num_publishers {
+                let quiet = publisher % 4 == 3 && (60..120).contains(&slot);
+                if quiet || !rng.gen_bool(0.7) {
+                    continue;
+                }
+                let status = if rng.gen_bool(0.9) {
+                    PC_STATUS_TRADING
+                } else {
+                    PC_STATUS_UNKNOWN
+                };
+                let price = 1_000 + rng.gen_range(-20..=20);
+                // A confidence of zero makes the quote invalid
+                let conf = rng.gen_range(0..=5);
+                // Some quotes land late enough to be left out of the aggregate
+                let publishing_slot = if rng.gen_bool(0.1) {
+                    (last_pub_slots[publisher] + 1).max(slot.saturating_sub(30))
+                } else {
+                    slot
+                };
+                last_pub_slots[publisher] = publishing_slot;
+
+                assert_eq!(
+                    ordered.upd_price(publisher, status, price, conf, publishing_slot),
+                    reference.upd_price(publisher, status, price, conf, publishing_slot)
+                );
+            }
+
+            let ordered_state = price_state(&mut ordered);
+            assert_eq!(
+                bytes_of(&ordered_state),
+                bytes_of(&price_state(&mut reference)),
+                "{num_publishers} publishers, slot {slot}"
+            );
+            let order = ordered.extension::<QuoteOrder>();
+            assert!(is_sorted(
+                &order,
+                &ordered_state.comp_[..ordered_state.num_ as usize]
+            ));
+        }
+
+        // Every publisher published since being added back
+        let price_data = ordered.price_data();
+        assert_eq!(ordered.extension::<QuoteOrder>().len, price_data.num_);
+    }
+}
+
+#[test]
+fn test_quote_order_update() {
+    let mut components = [PriceComponent::zeroed(); 4];
+    let mut order = QuoteOrder::zeroed();
+    let mut publish = |order: &mut QuoteOrder, index: usize, price: i64, conf: u64| {
+        components[index].latest_.price_ = price;
+        components[index].latest_.conf_ = conf;
+        order.update(&components, index);
+    };
+
+    // Components are inserted as they publish
+    publish(&mut order, 2, 100, 1);
+    publish(&mut order, 0, 90, 20);
+    publish(&mut order, 3, 95, 1);
+    assert_eq!(order.len, 3);
+    assert_eq!(order.lists[0][..3], [0, 3, 2]);
+    assert_eq!(order.lists[1][..3], [0, 3, 2]);
+    assert_eq!(order.lists[2][..3], [3, 2, 0]);
+
+    // An update moves the component after the ones with the same prices
+    publish(&mut order, 0, 100, 1);
+    assert_eq!(order.len, 3);
+    assert_eq!(order.lists[0][..3], [3, 2, 0]);
+    assert_eq!(order.lists[1][..3], [3, 2, 0]);
+    assert_eq!(order.lists[2][..3], [3, 2, 0]);
+
+    // Extreme quotes don't overflow
+    publish(&mut order, 1, i64::MIN, u64::MAX);
+    assert_eq!(order.len, 4);
+    assert_eq!(order.lists[0][..4], [1, 3, 2, 0]);
+    assert_eq!(order.lists[2][..4], [3, 2, 0, 1]);
+
+    // An order with a list missing the component starts over
+    order.lists[1][1] = 0;
+    publish(&mut order, 3, 95, 1);
+    assert_eq!(order.len, 1);
+    assert_eq!(order.lists[1][0], 3);
+}
+
+#[test]
+fn test_set_quote_order() {
+    let mut feed = FeedSimulator::new(3).with_extension::<QuoteOrder>();
+    feed.set_quote_order(true).unwrap();
+    assert_eq!(feed.extension::<QuoteOrder>().enabled, 1);
+
+    feed.post_slot(&[Some((100, 1)), Some((101, 1)), Some((99, 1))]);
+    let order = feed.extension::<QuoteOrder>();
+    assert_eq!(order.len, 3);
+    assert_eq!(order.lists[1][..3], [2, 0, 1]);
+
+    // Moving the components empties the order
+    feed.add_publisher(Pubkey::new_unique());
+    assert_eq!(feed.extension::<QuoteOrder>().len, 0);
+    feed.post_slot(&[Some((100, 1))]);
+    assert_eq!(feed.extension::<QuoteOrder>().len, 1);
+
+    // A disabled order isn't maintained
+    feed.set_quote_order(false).unwrap();
+    feed.post_slot(&[Some((100, 1)), Some((101, 1))]);
+    let order = feed.extension::<QuoteOrder>();
+    assert_eq!(order.enabled, 0);
+    assert_eq!(order.len, 0);
+}
@@ -0,0 +1,39 @@
+use crate::{
+    c_oracle_header::PC_STATUS_TRADING,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_quote_venue() {
+    let mut feed = FeedSimulator::new(2);
+
+    feed.warp_to_slot(10);
+    feed.upd_price_from_venue(0, PC_STATUS_TRADING, 100, 1, 10, 3)
+        .unwrap();
+    feed.publish(1, 101, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.comp_[0].latest_.venue(), 3);
+    assert_eq!(price_data.comp_[1].latest_.venue(), 0);
+
+    // The aggregation snapshots the venue with the quote it used
+    feed.warp_to_slot(11);
+    feed.upd_price_from_venue(0, PC_STATUS_TRADING, 102, 1, 11, 4)
+        .unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.comp_[0].latest_.venue(), 4);
+    let message = price_data.as_publisher_prices_message(&feed.price_key());
+    assert_eq!(message.publish_slot, 11);
+    assert_eq!(
+        message
+            .publishers
+            .iter()
+            .map(|publisher| (publisher.price, publisher.venue))
+            .collect::<Vec<_>>(),
+        [(100, 3), (101, 0)]
+    );
+
+    // An untagged quote clears the venue of the previous one
+    feed.warp_to_slot(12);
+    feed.publish(0, 103, 1).unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.venue(), 0);
+}
@@ -0,0 +1,60 @@
+use {
+    crate::{
+        accounts::ReadStats,
+        error::OracleError,
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_get_price() {
+    let mut feed = FeedSimulator::new(1);
+    feed.warp_to_slot(10);
+    feed.warp_to_timestamp(1_000);
+    feed.publish(0, 100, 2).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 101, 2).unwrap();
+
+    let summary = feed.get_price(false).unwrap();
+    assert_eq!((summary.price, summary.conf), (100, 2));
+    assert_eq!(summary.publish_slot, 11);
+    assert_eq!(summary.publish_time, 1_000);
+    assert_eq!(summary.ema_price, 100);
+    assert_eq!(summary.ema_conf, 2);
+    assert_eq!(summary.exponent, feed.price_data().exponent);
+}
+
+#[test]
+fn test_read_stats() {
+    let mut feed = FeedSimulator::new(1).with_extension::<ReadStats>();
+    feed.enable_read_stats().unwrap();
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 2).unwrap();
+
+    // Feeds counting their reads can't be read without recording it
+    assert_eq!(
+        feed.get_price(false),
+        Err::<_, ProgramError>(OracleError::InvalidWritableAccount.into())
+    );
+    assert_eq!(feed.extension::<ReadStats>().reads, 0);
+
+    feed.warp_to_epoch(3);
+    feed.get_price(true).unwrap();
+    feed.get_price(true).unwrap();
+    feed.warp_to_epoch(4);
+    feed.get_price(true).unwrap();
+    let read_stats = feed.extension::<ReadStats>();
+    assert_eq!(
+        (
+            read_stats.epoch,
+            read_stats.reads,
+            read_stats.previous_epoch_reads
+        ),
+        (4, 1, 2)
+    );
+
+    // Enabling again keeps the counts
+    feed.enable_read_stats().unwrap();
+    assert_eq!(feed.extension::<ReadStats>().reads, 1);
+}
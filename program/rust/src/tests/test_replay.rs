@@ -0,0 +1,119 @@
+extern crate test_generator;
+
+use {
+    crate::tests::test_utils::FeedSimulator,
+    csv::ReaderBuilder,
+    serde::Deserialize,
+    solana_program::pubkey::Pubkey,
+    std::{
+        collections::HashMap,
+        fs::File,
+        path::Path,
+        str::FromStr,
+    },
+    test_generator::test_resources,
+};
+
+// Replays dumps of the `upd_price` instructions that landed for a feed and checks that the
+// aggregates match the values recorded on-chain. Each dump is a JSON file holding the feed
+// parameters and the updates in landing order, either inline or as the name of a CSV file next
+// to it with the same columns. The bundled dumps are synthetic, real ones can be dropped in
+// `test_data/replay` as they are.
+#[test_resources("program/rust/test_data/replay/*.json")]
+fn test_replay(input_path_raw: &str) {
+    // For some reason these tests have a different working directory than the macro.
+    let input_path = input_path_raw.replace("program/rust/", "");
+
+    let file = File::open(&input_path).expect("Test file not found");
+    let replay: Replay = serde_json::from_reader(&file).expect("Unable to parse JSON");
+
+    let updates = match replay.updates {
+        Updates::Inline(updates) => updates,
+        Updates::Csv(csv_file) => {
+            let csv_path = Path::new(&input_path).with_file_name(csv_file);
+            let file = File::open(csv_path).expect("Test file not found");
+            ReaderBuilder::new()
+                .has_headers(true)
+                .from_reader(file)
+                .deserialize::<RecordedUpdate>()
+                .map(|record| record.expect("Could not parse CSV record"))
+                .collect()
+        }
+    };
+    assert!(!updates.is_empty(), "Empty replay");
+
+    run_replay(replay.exponent, replay.min_pub, &updates);
+}
+
+fn run_replay(exponent: i32, min_pub: u8, updates: &[RecordedUpdate]) {
+    // Every publisher of the dump is a component of the feed from the start
+    let mut publishers: HashMap<Pubkey, usize> = HashMap::new();
+    for update in updates {
+        let next_index = publishers.len();
+        publishers
+            .entry(update.publisher_key())
+            .or_insert(next_index);
+    }
+    let mut feed = FeedSimulator::new(publishers.len())
+        .with_exponent(exponent)
+        .with_min_pub(min_pub);
+
+    for (i, update) in updates.iter().enumerate() {
+        assert!(update.slot >= feed.slot(), "Updates out of order");
+        feed.warp_to_slot(update.slot);
+        feed.upd_price(
+            publishers[&update.publisher_key()],
+            update.status,
+            update.price,
+            update.conf,
+            update.publishing_slot,
+        )
+        .unwrap_or_else(|err| panic!("Update {} failed: {:?}", i, err));
+
+        let aggregate = feed.price_data().agg_;
+        if let Some(status) = update.agg_status {
+            assert_eq!(aggregate.status_, status, "Status after update {}", i);
+        }
+        if let Some(price) = update.agg_price {
+            assert_eq!(aggregate.price_, price, "Price after update {}", i);
+        }
+        if let Some(conf) = update.agg_conf {
+            assert_eq!(aggregate.conf_, conf, "Confidence after update {}", i);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Replay {
+    exponent: i32,
+    min_pub:  u8,
+    updates:  Updates,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Updates {
+    Inline(Vec<RecordedUpdate>),
+    Csv(String),
+}
+
+/// An `upd_price` instruction that landed in `slot`, with the aggregate of the price account
+/// after it. Aggregate fields left out aren't checked.
+#[derive(Deserialize)]
+struct RecordedUpdate {
+    slot:            u64,
+    publisher:       String,
+    status:          u32,
+    price:           i64,
+    conf:            u64,
+    publishing_slot: u64,
+    agg_status:      Option<u32>,
+    agg_price:       Option<i64>,
+    agg_conf:        Option<u64>,
+}
+
+impl RecordedUpdate {
+    fn publisher_key(&self) -> Pubkey {
+        Pubkey::from_str(&self.publisher).expect("Invalid publisher key")
+    }
+}
@@ -0,0 +1,107 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[tokio::test]
+async fn test_reset_ema() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price = price_accounts["LTC"];
+    let quote = |price| Quote {
+        price,
+        confidence: 10,
+        status: PC_STATUS_TRADING,
+    };
+
+    // There is no aggregate to restart from yet
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+    assert_eq!(
+        sim.reset_ema(price, &genesis_keypair)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::NeedsSuccesfulAggregation.into()
+    );
+
+    // A bad print poisons the EMAs
+    sim.warp_to_slot(2).await.unwrap();
+    sim.upd_price(&publisher, price, quote(1_000_000))
+        .await
+        .unwrap();
+    for slot in 3..5 {
+        sim.warp_to_slot(slot).await.unwrap();
+        sim.upd_price(&publisher, price, quote(100)).await.unwrap();
+    }
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 100);
+    assert!(price_data.twap_.val_ > 10_000);
+
+    // Only the master and the security authorities can reset the EMAs
+    assert_eq!(
+        sim.reset_ema(price, &security_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    sim.upd_permissions(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::UpdPermissions.into(),
+            master_authority:        genesis_keypair.pubkey(),
+            data_curation_authority: genesis_keypair.pubkey(),
+            security_authority:      security_authority.pubkey(),
+        },
+        &copy_keypair(&sim.upgrade_authority),
+    )
+    .await
+    .unwrap();
+    sim.reset_ema(price, &security_authority).await.unwrap();
+
+    let reset_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(reset_data.twap_.val_, 100);
+    assert_eq!(reset_data.twac_.val_, 10);
+    assert_eq!(reset_data.agg_.price_, price_data.agg_.price_);
+    assert_eq!(
+        reset_data.price_cumulative.price,
+        price_data.price_cumulative.price
+    );
+
+    // The EMAs follow the new prints from there, up to rounding
+    sim.warp_to_slot(5).await.unwrap();
+    sim.upd_price(&publisher, price, quote(100)).await.unwrap();
+    let twap = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap()
+        .twap_
+        .val_;
+    assert!((99..=101).contains(&twap));
+}
@@ -0,0 +1,246 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceAccountFlags,
+            PriceInfo,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::load_checked,
+        error::OracleError,
+        instruction::{
+            CommandHeader,
+            OracleCommand,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+    },
+    bytemuck::bytes_of,
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+fn quote(price: i64) -> PriceInfo {
+    PriceInfo {
+        price_:           price,
+        conf_:            1,
+        status_:          PC_STATUS_TRADING,
+        corp_act_status_: 0,
+        pub_slot_:        42,
+    }
+}
+
+#[test]
+fn test_rotate_publisher() {
+    let program_id = Pubkey::new_unique();
+    let instruction_data = CommandHeader::from(OracleCommand::RotatePublisher);
+    let instruction_data = bytes_of(&instruction_data);
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+    let others = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let mut new_publisher_setup = AccountSetup::new_funding();
+    let mut new_publisher_account = new_publisher_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+
+    let mut price_setups = [
+        AccountSetup::new::<PriceAccount>(&program_id),
+        AccountSetup::new::<PriceAccount>(&program_id),
+    ];
+    let mut price_accounts = price_setups
+        .iter_mut()
+        .map(|setup| setup.as_account_info())
+        .collect::<Vec<_>>();
+    for (i, price_account) in price_accounts.iter().enumerate() {
+        PriceAccount::initialize(price_account, PC_VERSION).unwrap();
+        let mut price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+        price_data.num_ = 3;
+        for (j, key) in [*publisher_account.key, others[0], others[1]]
+            .iter()
+            .enumerate()
+        {
+            price_data.comp_[j].pub_ = *key;
+            price_data.comp_[j].latest_ = quote((100 * i + j) as i64);
+            price_data.comp_[j].agg_ = quote((100 * i + j) as i64 + 10);
+        }
+    }
+    let mut accounts = vec![
+        publisher_account.clone(),
+        new_publisher_account.clone(),
+        permissions_account.clone(),
+    ];
+    accounts.extend(price_accounts.iter().cloned());
+
+    // The new key must sign
+    new_publisher_account.is_signer = false;
+    let mut unsigned_accounts = accounts.clone();
+    unsigned_accounts[1] = new_publisher_account.clone();
+    assert_eq!(
+        process_instruction(&program_id, &unsigned_accounts, instruction_data),
+        Err(OracleError::InvalidSignableAccount.into())
+    );
+    new_publisher_account.is_signer = true;
+    assert_eq!(
+        process_instruction(&program_id, &accounts[..3], instruction_data),
+        Err(OracleError::InvalidNumberOfAccounts.into())
+    );
+
+    process_instruction(&program_id, &accounts, instruction_data).unwrap();
+
+    // The component keeps its quotes and moves after the other publishers
+    for (i, price_account) in price_accounts.iter().enumerate() {
+        let price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.num_, 3);
+        assert_eq!(price_data.comp_[0].pub_, others[0]);
+        assert_eq!(price_data.comp_[1].pub_, others[1]);
+        assert_eq!(price_data.comp_[2].pub_, *new_publisher_account.key);
+        assert_eq!(price_data.comp_[2].latest_, quote((100 * i) as i64));
+        assert_eq!(price_data.comp_[2].agg_, quote((100 * i) as i64 + 10));
+        assert_eq!(price_data.comp_[0].latest_, quote((100 * i + 1) as i64));
+    }
+
+    // The old key is gone
+    assert_eq!(
+        process_instruction(&program_id, &accounts, instruction_data),
+        Err(OracleError::PublisherNotFound.into())
+    );
+}
+
+#[test]
+fn test_rotate_publisher_rejects_listed_key() {
+    let program_id = Pubkey::new_unique();
+    let instruction_data = CommandHeader::from(OracleCommand::RotatePublisher);
+    let instruction_data = bytes_of(&instruction_data);
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+    let mut new_publisher_setup = AccountSetup::new_funding();
+    let new_publisher_account = new_publisher_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 2;
+        price_data.comp_[0].pub_ = *publisher_account.key;
+        price_data.comp_[1].pub_ = *new_publisher_account.key;
+    }
+    let accounts = [
+        publisher_account.clone(),
+        new_publisher_account.clone(),
+        permissions_account.clone(),
+        price_account.clone(),
+    ];
+
+    assert_eq!(
+        process_instruction(&program_id, &accounts, instruction_data),
+        Err(OracleError::PublisherAlreadyExists.into())
+    );
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                publisher_account.clone(),
+                publisher_account.clone(),
+                permissions_account.clone(),
+                price_account.clone()
+            ],
+            instruction_data
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    // Rotating in the middle of an aggregation would leave the aggregate half computed
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.comp_[1].pub_ = Pubkey::new_unique();
+        price_data
+            .flags
+            .insert(PriceAccountFlags::AGGREGATION_IN_PROGRESS);
+    }
+    assert_eq!(
+        process_instruction(&program_id, &accounts, instruction_data),
+        Err(OracleError::AggregationInProgress.into())
+    );
+}
+
+#[test]
+fn test_rotate_publisher_with_registry() {
+    let program_id = Pubkey::new_unique();
+    let instruction_data = CommandHeader::from(OracleCommand::RotatePublisher);
+    let instruction_data = bytes_of(&instruction_data);
+
+    let mut publisher_setup = AccountSetup::new_funding();
+    let publisher_account = publisher_setup.as_account_info();
+    let mut new_publisher_setup = AccountSetup::new_funding();
+    let new_publisher_account = new_publisher_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission_with_publisher_registry(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.num_ = 1;
+        price_data.comp_[0].pub_ = *publisher_account.key;
+    }
+    let mut registry_setup =
+        AccountSetup::new_publisher_registry(&program_id, &[Pubkey::new_unique()]);
+    let registry_account = registry_setup.as_account_info();
+
+    // Once the registry exists it can't be left out
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                publisher_account.clone(),
+                new_publisher_account.clone(),
+                permissions_account.clone(),
+                price_account.clone(),
+            ],
+            instruction_data
+        ),
+        Err(OracleError::InvalidPda.into())
+    );
+
+    let accounts = [
+        publisher_account.clone(),
+        new_publisher_account.clone(),
+        permissions_account.clone(),
+        registry_account.clone(),
+        price_account.clone(),
+    ];
+    assert_eq!(
+        process_instruction(&program_id, &accounts, instruction_data),
+        Err(OracleError::PublisherNotApproved.into())
+    );
+
+    let mut registry_setup = AccountSetup::new_publisher_registry(
+        &program_id,
+        &[Pubkey::new_unique(), *new_publisher_account.key],
+    );
+    let registry_account = registry_setup.as_account_info();
+    process_instruction(
+        &program_id,
+        &[
+            publisher_account.clone(),
+            new_publisher_account.clone(),
+            permissions_account.clone(),
+            registry_account.clone(),
+            price_account.clone(),
+        ],
+        instruction_data,
+    )
+    .unwrap();
+    let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+    assert_eq!(price_data.comp_[0].pub_, *new_publisher_account.key);
+}
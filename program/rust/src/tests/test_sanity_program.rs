@@ -0,0 +1,139 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            SanityProgram,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+                SANITY_MAX_PRICE,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_sanity_program_missing() {
+    let sanity_program_id = Pubkey::new_unique();
+    let mut feed = FeedSimulator::new(1).with_extension::<SanityProgram>();
+    feed.set_sanity_program(sanity_program_id).unwrap();
+    assert_eq!(
+        feed.extension::<SanityProgram>().program_id,
+        sanity_program_id
+    );
+
+    // Quotes are still accepted, but the aggregate can't be checked without the sanity program
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.comp_[0].latest_.price_, 100);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.agg_.price_, 0);
+    assert_eq!(price_data.last_slot_, 0);
+
+    // Nor with another program in its place
+    feed.pass_sanity_program(Pubkey::new_unique());
+    feed.warp_to_slot(12);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+
+    // The default key disables the check
+    feed.set_sanity_program(Pubkey::default()).unwrap();
+    feed.warp_to_slot(13);
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 100);
+    assert_eq!(price_data.last_slot_, 13);
+}
+
+#[tokio::test]
+async fn test_sanity_program() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.set_sanity_program(&price_keypair, sim.sanity_program_id)
+        .await
+        .unwrap();
+
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price_with_sanity_program(&publisher, price, quote(SANITY_MAX_PRICE))
+        .await
+        .unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price_with_sanity_program(&publisher, price, quote(SANITY_MAX_PRICE + 1))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, SANITY_MAX_PRICE);
+    assert_eq!(price_data.last_slot_, 11);
+
+    // The vetoed aggregate keeps the last approved price
+    sim.warp_to_slot(12).await.unwrap();
+    sim.upd_price_with_sanity_program(&publisher, price, quote(SANITY_MAX_PRICE))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[0].latest_.price_, SANITY_MAX_PRICE);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.agg_.price_, SANITY_MAX_PRICE);
+    assert_eq!(price_data.last_slot_, 11);
+
+    sim.warp_to_slot(13).await.unwrap();
+    sim.upd_price_with_sanity_program(&publisher, price, quote(SANITY_MAX_PRICE))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.last_slot_, 13);
+
+    // Publishers that leave out the sanity program can't publish an aggregate
+    sim.warp_to_slot(14).await.unwrap();
+    sim.upd_price(&publisher, price, quote(SANITY_MAX_PRICE))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(price_data.last_slot_, 13);
+}
+
+fn quote(price: i64) -> Quote {
+    Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    }
+}
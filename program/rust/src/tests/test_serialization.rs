@@ -0,0 +1,104 @@
+use {
+    crate::{
+        accounts::{
+            AccountHeader,
+            MappingAccount,
+            PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
+            ProductAccount,
+            PythAccount,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+    },
+    borsh::{
+        BorshDeserialize,
+        BorshSerialize,
+    },
+    bytemuck::{
+        bytes_of,
+        Pod,
+        Zeroable,
+    },
+    serde::{
+        de::DeserializeOwned,
+        Serialize,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+#[test]
+fn test_serialization() {
+    let mut price_account = PriceAccount::zeroed();
+    price_account.header = header::<PriceAccount>();
+    price_account.exponent = -8;
+    price_account.num_ = 2;
+    price_account.flags = PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::ALLOW_ZERO_CI;
+    price_account.feed_index = 7;
+    price_account.product_account = Pubkey::new_unique();
+    price_account.agg_.price_ = 100;
+    price_account.agg_.status_ = PC_STATUS_TRADING;
+    price_account.comp_[1].pub_ = Pubkey::new_unique();
+    price_account.comp_[1].latest_.price_ = 101;
+    price_account.comp_[127].pub_ = Pubkey::new_unique();
+    price_account.price_cumulative.price = -1 << 100;
+    price_account.price_cumulative.conf = 1 << 100;
+    check_round_trips(&price_account);
+
+    let mut product_account = ProductAccount::zeroed();
+    product_account.header = header::<ProductAccount>();
+    product_account.first_price_account = Pubkey::new_unique();
+    check_round_trips(&product_account);
+
+    let mut mapping_account = MappingAccount::zeroed();
+    mapping_account.header = header::<MappingAccount>();
+    mapping_account.number_of_products = 2;
+    mapping_account.next_mapping_account = Pubkey::new_unique();
+    mapping_account.products_list[0] = Pubkey::new_unique();
+    mapping_account.products_list[4999] = Pubkey::new_unique();
+    check_round_trips(&mapping_account);
+
+    let mut permission_account = PermissionAccount::zeroed();
+    permission_account.header = header::<PermissionAccount>();
+    permission_account.master_authority = Pubkey::new_unique();
+    permission_account.data_curation_authority = Pubkey::new_unique();
+    permission_account.security_authority = Pubkey::new_unique();
+    check_round_trips(&permission_account);
+}
+
+// The accounts have no padding, so their borsh encoding is their layout
+fn check_round_trips<T: Pod + Serialize + DeserializeOwned + BorshSerialize + BorshDeserialize>(
+    account: &T,
+) {
+    let borsh_data = account.try_to_vec().unwrap();
+    assert_eq!(borsh_data, bytes_of(account));
+    assert_eq!(
+        bytes_of(&T::try_from_slice(&borsh_data).unwrap()),
+        bytes_of(account)
+    );
+
+    let json = serde_json::to_string(account).unwrap();
+    assert_eq!(
+        bytes_of(&serde_json::from_str::<T>(&json).unwrap()),
+        bytes_of(account)
+    );
+
+    let bincode_data = bincode::serialize(account).unwrap();
+    assert_eq!(
+        bytes_of(&bincode::deserialize::<T>(&bincode_data).unwrap()),
+        bytes_of(account)
+    );
+}
+
+fn header<T: PythAccount>() -> AccountHeader {
+    AccountHeader {
+        magic_number: PC_MAGIC,
+        version:      PC_VERSION,
+        account_type: T::ACCOUNT_TYPE,
+        size:         T::INITIAL_SIZE,
+    }
+}
@@ -0,0 +1,265 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            get_extension,
+            init_extension,
+            BidAsk,
+            PermissionAccount,
+            PriceAccount,
+            PythAccount,
+            EXTENSIONS_OFFSET,
+        },
+        c_oracle_header::{
+            MAX_NUM_DECIMALS,
+            PC_VERSION,
+        },
+        deserialize::{
+            load_checked,
+            load_mut,
+        },
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            SetExponentArgs,
+        },
+        processor::process_instruction,
+        tests::{
+            test_ema::upd_twap,
+            test_utils::AccountSetup,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::{
+        account_info::AccountInfo,
+        pubkey::Pubkey,
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_set_exponent() {
+    let mut instruction_data = [0u8; size_of::<SetExponentArgs>()];
+
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new_with_size::<PriceAccount>(
+        &program_id,
+        EXTENSIONS_OFFSET + extension_space::<BidAsk>(),
+    );
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    {
+        let mut price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data.exponent = -5;
+        price_data.num_ = 2;
+        price_data.agg_.price_ = 12_344;
+        price_data.agg_.conf_ = 101;
+        price_data.comp_[0].agg_.price_ = 12_345;
+        price_data.comp_[0].latest_.price_ = -12_345;
+        price_data.comp_[1].latest_.conf_ = 1;
+        // Not a component of the feed, left untouched
+        price_data.comp_[2].latest_.price_ = 7;
+        price_data.prev_price_ = 12_000;
+        price_data.prev_conf_ = 100;
+        price_data.twap_.val_ = 12_300;
+        price_data.twap_.numer_ = 4_000;
+        price_data.twap_.denom_ = 3_000_001;
+        price_data.twac_.val_ = 99;
+        price_data.price_cumulative.price = 1_234_500;
+        price_data.price_cumulative.conf = 10_100;
+    }
+    {
+        let bid_ask =
+            init_extension::<BidAsk>(&mut price_account.try_borrow_mut_data().unwrap()).unwrap();
+        bid_ask.bid_price = 12_300;
+        bid_ask.ask_price = 12_400;
+    }
+
+    let accounts = [
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    // Smaller exponent, every value gets more digits
+    populate_instruction(&mut instruction_data, -8);
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.exponent, -8);
+        assert_eq!(price_data.agg_.price_, 12_344_000);
+        assert_eq!(price_data.agg_.conf_, 101_000);
+        assert_eq!(price_data.comp_[0].agg_.price_, 12_345_000);
+        assert_eq!(price_data.comp_[0].latest_.price_, -12_345_000);
+        assert_eq!(price_data.comp_[1].latest_.conf_, 1_000);
+        assert_eq!(price_data.comp_[2].latest_.price_, 7);
+        assert_eq!(price_data.prev_price_, 12_000_000);
+        assert_eq!(price_data.prev_conf_, 100_000);
+        assert_eq!(price_data.twap_.val_, 12_300_000);
+        assert_eq!(price_data.twap_.numer_, 4_000);
+        assert_eq!(price_data.twap_.denom_, 3_000_001);
+        assert_eq!(price_data.twac_.val_, 99_000);
+        assert_eq!(price_data.price_cumulative.price, 1_234_500_000);
+        assert_eq!(price_data.price_cumulative.conf, 10_100_000);
+    }
+    assert_eq!(get_bid_ask(&price_account), (12_300_000, 12_400_000));
+
+    // Larger exponent, prices are rounded to the nearest value and confidences are rounded up
+    populate_instruction(&mut instruction_data, -4);
+    assert!(process_instruction(&program_id, &accounts, &instruction_data).is_ok());
+    {
+        let price_data = load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        assert_eq!(price_data.exponent, -4);
+        assert_eq!(price_data.agg_.price_, 1_234);
+        assert_eq!(price_data.agg_.conf_, 11);
+        assert_eq!(price_data.comp_[0].agg_.price_, 1_235);
+        assert_eq!(price_data.comp_[0].latest_.price_, -1_235);
+        assert_eq!(price_data.comp_[1].latest_.conf_, 1);
+        assert_eq!(price_data.prev_price_, 1_200);
+        assert_eq!(price_data.prev_conf_, 10);
+        assert_eq!(price_data.twap_.val_, 1_230);
+        assert_eq!(price_data.twap_.numer_, 4_000);
+        assert_eq!(price_data.twap_.denom_, 3_000_001);
+        assert_eq!(price_data.twac_.val_, 10);
+        assert_eq!(price_data.price_cumulative.price, 123_450);
+        assert_eq!(price_data.price_cumulative.conf, 1_010);
+    }
+    assert_eq!(get_bid_ask(&price_account), (1_230, 1_240));
+
+    // Exponent out of range
+    populate_instruction(&mut instruction_data, -MAX_NUM_DECIMALS - 1);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data),
+        Err(OracleError::InvalidExponent.into())
+    );
+
+    // Prices that don't fit with the new exponent
+    load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .agg_
+        .price_ = i64::MAX / 10;
+    populate_instruction(&mut instruction_data, -6);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data),
+        Err(OracleError::PriceRescaleOverflow.into())
+    );
+    assert_eq!(
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .exponent,
+        -4
+    );
+
+    // Only the master authority can change the exponent
+    let mut attacker_setup = AccountSetup::new_funding();
+    let attacker_account = attacker_setup.as_account_info();
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                attacker_account,
+                price_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction_data
+        ),
+        Err(OracleError::PermissionViolation.into())
+    );
+}
+
+/// The EMAs carry on after a change of exponent as if the feed always had the new exponent.
+#[test]
+fn test_set_exponent_ema() {
+    let mut instruction_data = [0u8; size_of::<SetExponentArgs>()];
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION)
+        .unwrap()
+        .exponent = -5;
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+
+    let mut reference = PriceAccount::zeroed();
+    reference.exponent = -8;
+    upd_emas(&price_account, &mut reference, 12_300, 10, 5_000, 1_000);
+    upd_emas(&price_account, &mut reference, 12_400, 20, 1, 1_000);
+    upd_emas(&price_account, &mut reference, 12_350, 15, 1, 1_000);
+
+    populate_instruction(&mut instruction_data, -8);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ],
+        &instruction_data
+    )
+    .is_ok());
+
+    for (price, conf) in [
+        (12_500_000, 12_000),
+        (12_450_000, 30_000),
+        (12_600_000, 9_000),
+    ] {
+        let (twap, twac) = upd_emas(&price_account, &mut reference, price, conf, 1, 1);
+        // The packed decimals of the EMAs only keep so many digits
+        assert!((twap - reference.twap_.val_).abs() <= 10);
+        assert!((twac - reference.twac_.val_).abs() <= 10);
+    }
+}
+
+/// Update the EMAs of `price_account` with an aggregate, and those of `reference` with the same
+/// aggregate multiplied by `scale`. Returns the EMAs of `price_account`.
+fn upd_emas(
+    price_account: &AccountInfo,
+    reference: &mut PriceAccount,
+    price: i64,
+    conf: u64,
+    nslots: i64,
+    scale: i64,
+) -> (i64, i64) {
+    let mut price_data = load_checked::<PriceAccount>(price_account, PC_VERSION).unwrap();
+    price_data.agg_.price_ = price;
+    price_data.agg_.conf_ = conf;
+    upd_twap(&mut price_data, nslots);
+
+    reference.agg_.price_ = price * scale;
+    reference.agg_.conf_ = conf * scale as u64;
+    upd_twap(reference, nslots);
+
+    (price_data.twap_.val_, price_data.twac_.val_)
+}
+
+// Populate the instruction data with SetExponentArgs
+fn populate_instruction(instruction_data: &mut [u8], exponent: i32) {
+    let hdr = load_mut::<SetExponentArgs>(instruction_data).unwrap();
+    hdr.header = OracleCommand::SetExponent.into();
+    hdr.exponent = exponent;
+}
+
+fn get_bid_ask(account: &AccountInfo) -> (i64, i64) {
+    let data = account.try_borrow_data().unwrap();
+    let bid_ask = get_extension::<BidAsk>(&data).unwrap().unwrap();
+    (bid_ask.bid_price, bid_ask.ask_price)
+}
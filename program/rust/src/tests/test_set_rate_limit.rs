@@ -0,0 +1,157 @@
+use {
+    crate::{
+        accounts::{
+            extension_space,
+            get_extension,
+            PublisherRateLimit,
+            EXTENSIONS_OFFSET,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_set_rate_limit() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherRateLimit>();
+    feed.set_rate_limit(3).unwrap();
+    assert_eq!(feed.extension::<PublisherRateLimit>().window, 3);
+
+    // A second update in the same slot is rejected, even with a newer publishing slot
+    feed.warp_to_slot(10);
+    feed.upd_price(0, PC_STATUS_TRADING, 100, 1, 9).unwrap();
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 101, 1, 10),
+        Err(OracleError::UpdateRateLimited.into())
+    );
+    assert_eq!(get_latest_price(&mut feed, 0), 100);
+
+    // Other publishers have their own window
+    feed.upd_price(1, PC_STATUS_TRADING, 100, 1, 10).unwrap();
+
+    feed.warp_to_slot(12);
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 102, 1, 12),
+        Err(OracleError::UpdateRateLimited.into())
+    );
+    feed.warp_to_slot(13);
+    feed.upd_price(0, PC_STATUS_TRADING, 103, 1, 13).unwrap();
+
+    // A window of 0 allows one update per slot
+    feed.set_rate_limit(0).unwrap();
+    feed.warp_to_slot(14);
+    feed.upd_price(0, PC_STATUS_TRADING, 104, 1, 14).unwrap();
+    feed.warp_to_slot(16);
+    feed.upd_price(0, PC_STATUS_TRADING, 105, 1, 15).unwrap();
+    assert_eq!(
+        feed.upd_price(0, PC_STATUS_TRADING, 106, 1, 16),
+        Err(OracleError::UpdateRateLimited.into())
+    );
+    assert_eq!(get_latest_price(&mut feed, 0), 105);
+}
+
+#[test]
+fn test_rate_limit_follows_components() {
+    let mut feed = FeedSimulator::new(2).with_extension::<PublisherRateLimit>();
+    feed.set_rate_limit(3).unwrap();
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+
+    // A publisher sorting before the others moves their components, the limit of the publisher
+    // that published moves with it
+    let mut key = [0u8; 32];
+    key[31] = 0xff;
+    let added = feed.add_new_publisher(Pubkey::from(key));
+    assert_eq!(feed.price_data().comp_[0].pub_, Pubkey::from(key));
+    feed.warp_to_slot(11);
+    assert_eq!(
+        feed.publish(0, 101, 1),
+        Err(OracleError::UpdateRateLimited.into())
+    );
+    feed.publish(1, 101, 1).unwrap();
+    feed.publish(added, 101, 1).unwrap();
+}
+
+#[test]
+fn test_rate_limit_invalid_extension() {
+    // An invalid extension region fails the update rather than lifting the limit
+    let mut feed = FeedSimulator::new(1)
+        .with_extension::<PublisherRateLimit>()
+        .with_corrupt_extensions();
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::InvalidExtension.into())
+    );
+}
+
+#[tokio::test]
+async fn test_set_rate_limit_grows_price_account() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+
+    sim.set_rate_limit(&price_keypair, 3).await.unwrap();
+    let price_account = sim.get_account(price).await.unwrap();
+    assert_eq!(
+        price_account.data.len(),
+        EXTENSIONS_OFFSET + extension_space::<PublisherRateLimit>()
+    );
+    assert_eq!(
+        get_extension::<PublisherRateLimit>(&price_account.data)
+            .unwrap()
+            .unwrap()
+            .window,
+        3
+    );
+
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price(&publisher, price, quote(100)).await.unwrap();
+    sim.warp_to_slot(12).await.unwrap();
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote(101))
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateRateLimited.into()
+    );
+    sim.warp_to_slot(13).await.unwrap();
+    sim.upd_price(&publisher, price, quote(102)).await.unwrap();
+}
+
+fn get_latest_price(feed: &mut FeedSimulator, publisher: usize) -> i64 {
+    let publisher_key = feed.publisher_key(publisher);
+    let price_data = feed.price_data();
+    price_data.comp_[..price_data.num_ as usize]
+        .iter()
+        .find(|comp| comp.pub_ == publisher_key)
+        .unwrap()
+        .latest_
+        .price_
+}
+
+fn quote(price: i64) -> Quote {
+    Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    accounts::{
+        ConfFloor,
+        StatusReasonCode,
+    },
+    c_oracle_header::{
+        PC_STATUS_TRADING,
+        PC_STATUS_UNKNOWN,
+    },
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_simulate_aggregate() {
+    let mut feed = FeedSimulator::new(3).with_extension::<ConfFloor>();
+    feed.set_conf_floor(1_000, 0).unwrap();
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.publish(1, 110, 1).unwrap();
+    feed.publish(2, 120, 1).unwrap();
+
+    // The simulation leaves the price account unchanged
+    feed.warp_to_slot(11);
+    let data = feed.price_account_data().to_vec();
+    let simulated = feed.simulate_aggregate();
+    assert_eq!(feed.price_account_data(), &data[..]);
+    assert_eq!(simulated.slot, 11);
+    assert_eq!(simulated.updated, 1);
+    assert_eq!(simulated.status, PC_STATUS_TRADING);
+    assert_eq!(simulated.num_qt, 3);
+    assert_eq!(simulated.reason, StatusReasonCode::None as u32);
+    assert_eq!(simulated.conf, 1_000);
+
+    // The next update publishes the simulated aggregate
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.price_, simulated.price);
+    assert_eq!(price_data.agg_.conf_, simulated.conf);
+    assert_eq!(price_data.agg_.status_, simulated.status);
+    assert_eq!(price_data.num_qt_, simulated.num_qt);
+}
+
+#[test]
+fn test_simulate_aggregate_stale() {
+    let mut feed = FeedSimulator::new(2);
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.publish(1, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+
+    // Without recent quotes the aggregate would stay at its price, without a status
+    feed.warp_to_slot(100);
+    let simulated = feed.simulate_aggregate();
+    assert_eq!(simulated.updated, 0);
+    assert_eq!(simulated.status, PC_STATUS_UNKNOWN);
+    assert_eq!(simulated.num_qt, 0);
+    assert_eq!(simulated.price, price_data.agg_.price_);
+    assert_eq!(
+        simulated.reason,
+        StatusReasonCode::AllComponentsStale as u32
+    );
+}
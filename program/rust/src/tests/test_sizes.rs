@@ -1,15 +1,70 @@
 use {
     crate::{
         accounts::{
+            AccessControl,
+            AccessControlAccount,
             AccountHeader,
+            AccumulatorEmitter,
+            AggregateCache,
+            AggregationConfigAccount,
+            AuditLogAccount,
+            AuditRecord,
+            AuxData,
+            Checksum,
+            ComponentActivity,
+            ComponentAuxData,
+            ComponentSequence,
+            ComponentTime,
+            ConfFloor,
+            ConsistencyCheckAccount,
+            ConsistencyFlag,
+            FeatureGateAccount,
+            FeatureSwitch,
+            FeeVaultAccount,
+            FeedCreator,
+            FeedRegistryAccount,
+            LatencyStats,
             MappingAccount,
+            MappingAccountV2,
+            MappingEntry,
+            MultisigAccount,
             PermissionAccount,
+            PermissionTimelock,
             PriceAccount,
             PriceComponent,
             PriceEma,
+            PriceExtrema,
             PriceInfo,
+            PriceSuccessor,
             ProductAccount,
+            PublishThreshold,
+            PublisherActivity,
+            PublisherCap,
+            PublisherLink,
+            PublisherLinkageAccount,
+            PublisherRegistryAccount,
+            PublisherRewards,
+            PublisherSampling,
+            PublisherSequences,
+            PublisherStake,
+            PublisherTimestamps,
+            PublisherWeightCap,
             PythAccount,
+            QuoteConversion,
+            QuoteOrder,
+            RewardEntry,
+            RewardsLedgerAccount,
+            SharedSettings,
+            SlotWeightedTwap,
+            SnapshotAccount,
+            StakeEntry,
+            StatusObserver,
+            StatusPolicy,
+            StatusReason,
+            TwapBucket,
+            UpdateCount,
+            UpdateFee,
+            UpdaterProgram,
         },
         c_oracle_header::{
             PC_MAP_TABLE_SIZE,
@@ -22,14 +77,72 @@ use {
             load_checked,
         },
         instruction::{
+            AddCommunityPriceArgs,
+            AddCommunityProductArgs,
             AddPriceArgs,
             AddPublisherArgs,
+            AggregationCountsSummary,
+            AssertPriceConditionArgs,
             CommandHeader,
             DelPublisherArgs,
+            InitConsistencyCheckArgs,
+            InitMultisigArgs,
             InitPriceArgs,
+            InitPriceConfig,
+            InitPriceConfigArgs,
+            InitPricePdaArgs,
+            MigrateMappingArgs,
+            PriceExtremaSummary,
+            PriceSummary,
+            PrunePublishersArgs,
+            PublisherActivityChange,
+            SanityCheckArgs,
+            SetAccumulatorEmitterArgs,
+            SetAggregateCacheArgs,
+            SetAggregationConfigArgs,
+            SetAggregationCountWindowArgs,
+            SetAggregationDelayArgs,
+            SetAuxDataArgs,
+            SetAverageModeArgs,
+            SetChecksumArgs,
+            SetConfFloorArgs,
+            SetExponentArgs,
+            SetFeatureArgs,
+            SetFeatureGracePeriodArgs,
+            SetLatencyStatsWindowArgs,
             SetMaxLatencyArgs,
             SetMinPubArgs,
+            SetPriceExtremaWindowArgs,
+            SetPublishThresholdArgs,
+            SetPublisherCapArgs,
+            SetPublisherDemotionArgs,
+            SetPublisherRewardsArgs,
+            SetPublisherSamplingArgs,
+            SetPublisherTimestampsArgs,
+            SetPublisherWeightCapArgs,
+            SetQuoteConversionArgs,
+            SetQuoteOrderArgs,
+            SetRateLimitArgs,
+            SetSanityProgramArgs,
+            SetSequenceTrackingArgs,
+            SetSharedSettingsArgs,
+            SetStakeProgramArgs,
+            SetStatusObserverArgs,
+            SetStatusPolicyArgs,
+            SetStatusReasonTrackingArgs,
+            SetUpdateCountingArgs,
+            SetUpdateFeeArgs,
+            SetUpdaterProgramArgs,
+            SimulatedAggregate,
+            StakeCheckArgs,
+            StatusTransition,
+            UpdAccessControlArgs,
+            UpdAuxDataArgs,
             UpdPriceArgs,
+            UpdPriceCompactArgs,
+            UpdPublisherLinkageArgs,
+            UpdPublisherStatusArgs,
+            WithdrawFeesArgs,
         },
         tests::test_utils::AccountSetup,
         utils::try_convert,
@@ -79,18 +192,131 @@ fn test_sizes() {
     assert_eq!(size_of::<CommandHeader>(), 8);
     assert_eq!(size_of::<AddPriceArgs>(), 16);
     assert_eq!(size_of::<InitPriceArgs>(), 16);
+    assert_eq!(size_of::<InitPricePdaArgs>(), 16);
     assert_eq!(size_of::<SetMinPubArgs>(), 12);
     assert_eq!(size_of::<SetMaxLatencyArgs>(), 12);
+    assert_eq!(size_of::<SetExponentArgs>(), 12);
+    assert_eq!(size_of::<SetRateLimitArgs>(), 16);
+    assert_eq!(size_of::<SetAggregationDelayArgs>(), 16);
+    assert_eq!(size_of::<AssertPriceConditionArgs>(), 48);
+    assert_eq!(size_of::<InitMultisigArgs>(), 368);
+    assert_eq!(size_of::<SetAggregationCountWindowArgs>(), 16);
+    assert_eq!(size_of::<AggregationCountsSummary>(), 24);
+    assert_eq!(size_of::<SetSanityProgramArgs>(), 40);
+    assert_eq!(size_of::<SanityCheckArgs>(), 88);
+    assert_eq!(size_of::<PrunePublishersArgs>(), 16);
+    assert_eq!(size_of::<UpdPublisherLinkageArgs>(), 72);
+    assert_eq!(size_of::<SetPublisherWeightCapArgs>(), 16);
+    assert_eq!(size_of::<SetQuoteOrderArgs>(), 16);
     assert_eq!(size_of::<AddPublisherArgs>(), 40);
     assert_eq!(size_of::<DelPublisherArgs>(), 40);
-    assert_eq!(size_of::<UpdPriceArgs>(), 40);
+    assert_eq!(size_of::<UpdPriceArgs>(), 48);
+    assert_eq!(size_of::<UpdPriceCompactArgs>(), 24);
+    assert_eq!(size_of::<UpdPublisherStatusArgs>(), 24);
+    assert_eq!(size_of::<SetFeatureArgs>(), 48);
+    assert_eq!(size_of::<SetFeatureGracePeriodArgs>(), 16);
+    assert_eq!(size_of::<UpdAccessControlArgs>(), 48);
+    assert_eq!(size_of::<SetLatencyStatsWindowArgs>(), 16);
+    assert_eq!(size_of::<SetPriceExtremaWindowArgs>(), 16);
+    assert_eq!(size_of::<PriceExtremaSummary>(), 40);
+    assert_eq!(size_of::<SimulatedAggregate>(), 40);
+    assert_eq!(size_of::<PriceSummary>(), 56);
+    assert_eq!(size_of::<SetPublisherDemotionArgs>(), 16);
+    assert_eq!(size_of::<PublisherActivityChange>(), 80);
+    assert_eq!(size_of::<SetUpdaterProgramArgs>(), 40);
+    assert_eq!(size_of::<SetAggregationConfigArgs>(), 24);
+    assert_eq!(size_of::<SetConfFloorArgs>(), 24);
+    assert_eq!(size_of::<SetStatusReasonTrackingArgs>(), 16);
+    assert_eq!(size_of::<SetStakeProgramArgs>(), 48);
+    assert_eq!(size_of::<StakeCheckArgs>(), 64);
+    assert_eq!(size_of::<AddCommunityProductArgs>(), 40);
+    assert_eq!(size_of::<AddCommunityPriceArgs>(), 80);
+    assert_eq!(size_of::<SetPublisherCapArgs>(), 16);
+    assert_eq!(size_of::<SetQuoteConversionArgs>(), 48);
+    assert_eq!(size_of::<SetAggregateCacheArgs>(), 16);
+    assert_eq!(size_of::<SetSharedSettingsArgs>(), 16);
+    assert_eq!(size_of::<SetPublisherSamplingArgs>(), 16);
+    assert_eq!(size_of::<SetUpdateFeeArgs>(), 16);
+    assert_eq!(size_of::<WithdrawFeesArgs>(), 16);
+    assert_eq!(size_of::<SetPublisherRewardsArgs>(), 16);
+    assert_eq!(size_of::<InitConsistencyCheckArgs>(), 16);
+    assert_eq!(size_of::<SetUpdateCountingArgs>(), 16);
+    assert_eq!(size_of::<SetAverageModeArgs>(), 24);
+    assert_eq!(size_of::<MigrateMappingArgs>(), 16);
+    assert_eq!(size_of::<SetPublisherTimestampsArgs>(), 16);
+    assert_eq!(size_of::<SetPublishThresholdArgs>(), 24);
+    assert_eq!(size_of::<SetStatusObserverArgs>(), 40);
+    assert_eq!(size_of::<StatusTransition>(), 72);
+    assert_eq!(size_of::<SetChecksumArgs>(), 16);
+    assert_eq!(size_of::<SetSequenceTrackingArgs>(), 16);
+    assert_eq!(size_of::<SetStatusPolicyArgs>(), 16);
+    assert_eq!(size_of::<SetAccumulatorEmitterArgs>(), 40);
+    assert_eq!(size_of::<SetAuxDataArgs>(), 16);
+    assert_eq!(size_of::<UpdAuxDataArgs>(), 24);
+    assert_eq!(size_of::<InitPriceConfig>(), 24);
+    assert_eq!(size_of::<InitPriceConfigArgs>(), 40);
     assert_eq!(size_of::<Pubkey>(), 32);
     assert_eq!(size_of::<AccountHeader>(), 16);
     assert_eq!(size_of::<MappingAccount>(), 160056);
+    assert_eq!(size_of::<MappingAccountV2>(), 24);
+    assert_eq!(size_of::<MappingEntry>(), 64);
     assert_eq!(size_of::<ProductAccount>(), 48);
     assert_eq!(size_of::<PriceComponent>(), 96);
     assert_eq!(size_of::<PriceEma>(), 24);
     assert_eq!(size_of::<PermissionAccount>(), 112);
+    assert_eq!(size_of::<PermissionTimelock>(), 120);
+    assert_eq!(size_of::<AccumulatorEmitter>(), 32);
+    assert_eq!(size_of::<SnapshotAccount>(), 56);
+    assert_eq!(size_of::<FeedRegistryAccount>(), 16);
+    assert_eq!(size_of::<PublisherRegistryAccount>(), 24);
+    assert_eq!(size_of::<PublisherLinkageAccount>(), 24);
+    assert_eq!(size_of::<PublisherLink>(), 64);
+    assert_eq!(size_of::<PublisherWeightCap>(), 4104);
+    assert_eq!(size_of::<QuoteOrder>(), 200);
+    assert_eq!(size_of::<PriceSuccessor>(), 32);
+    assert_eq!(size_of::<MultisigAccount>(), 376);
+    assert_eq!(size_of::<FeatureGateAccount>(), 32);
+    assert_eq!(size_of::<FeatureSwitch>(), 48);
+    assert_eq!(size_of::<AccessControlAccount>(), 2072);
+    assert_eq!(size_of::<AccessControl>(), 32);
+    assert_eq!(size_of::<AuditLogAccount>(), 10264);
+    assert_eq!(size_of::<AuditRecord>(), 80);
+    assert_eq!(size_of::<FeeVaultAccount>(), 32);
+    assert_eq!(size_of::<AggregationConfigAccount>(), 32);
+    assert_eq!(size_of::<RewardsLedgerAccount>(), 64);
+    assert_eq!(size_of::<ConsistencyCheckAccount>(), 144);
+    assert_eq!(size_of::<LatencyStats>(), 440);
+    assert_eq!(size_of::<PriceExtrema>(), 272);
+    assert_eq!(size_of::<ConfFloor>(), 16);
+    assert_eq!(size_of::<StatusReason>(), 16);
+    assert_eq!(size_of::<StakeEntry>(), 40);
+    assert_eq!(size_of::<PublisherStake>(), 2600);
+    assert_eq!(size_of::<FeedCreator>(), 32);
+    assert_eq!(size_of::<PublisherCap>(), 8);
+    assert_eq!(size_of::<QuoteConversion>(), 72);
+    assert_eq!(size_of::<AggregateCache>(), 8);
+    assert_eq!(size_of::<PublisherSampling>(), 8);
+    assert_eq!(size_of::<UpdateFee>(), 8);
+    assert_eq!(size_of::<RewardEntry>(), 56);
+    assert_eq!(size_of::<PublisherRewards>(), 3592);
+    assert_eq!(size_of::<ConsistencyFlag>(), 40);
+    assert_eq!(size_of::<UpdateCount>(), 16);
+    assert_eq!(size_of::<TwapBucket>(), 40);
+    assert_eq!(size_of::<SlotWeightedTwap>(), 696);
+    assert_eq!(size_of::<ComponentTime>(), 48);
+    assert_eq!(size_of::<PublisherTimestamps>(), 3080);
+    assert_eq!(size_of::<ComponentSequence>(), 40);
+    assert_eq!(size_of::<PublisherSequences>(), 2568);
+    assert_eq!(size_of::<PublishThreshold>(), 16);
+    assert_eq!(size_of::<StatusObserver>(), 32);
+    assert_eq!(size_of::<Checksum>(), 40);
+    assert_eq!(size_of::<StatusPolicy>(), 8);
+    assert_eq!(size_of::<ComponentAuxData>(), 56);
+    assert_eq!(size_of::<AuxData>(), 3616);
+    assert_eq!(size_of::<ComponentActivity>(), 40);
+    assert_eq!(size_of::<PublisherActivity>(), 2576);
+    assert_eq!(size_of::<UpdaterProgram>(), 32);
+    assert_eq!(size_of::<SharedSettings>(), 8);
 }
 
 #[test]
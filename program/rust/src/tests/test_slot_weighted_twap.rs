@@ -0,0 +1,93 @@
+use {
+    crate::{
+        accounts::{
+            AverageMode,
+            SlotWeightedTwap,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_record_aggregate() {
+    let mut twap = SlotWeightedTwap::new(AverageMode::SlotWeighted, 10);
+    assert_eq!(twap.window_slots(), 160);
+    assert_eq!(twap.published(), None);
+
+    // The first aggregate is its own average
+    twap.record_aggregate(100, 1000, 10);
+    assert_eq!(twap.published(), Some((1000, 10)));
+
+    // Every aggregate counts for the slots until the next one
+    twap.record_aggregate(110, 2000, 20);
+    assert_eq!((twap.price, twap.conf), (1000, 10));
+    twap.record_aggregate(130, 3000, 30);
+    assert_eq!((twap.price, twap.conf), (1666, 16));
+
+    // An aggregate replaced in its slot never counts
+    twap.record_aggregate(130, 4000, 40);
+    assert_eq!((twap.price, twap.conf), (1666, 16));
+    twap.record_aggregate(140, 4000, 40);
+    assert_eq!((twap.price, twap.conf), (2250, 22));
+
+    // Older aggregates are ignored
+    twap.record_aggregate(120, -1000, 0);
+    assert_eq!(twap.last_slot, 140);
+
+    // The window of slot 400 starts at slot 250, 150 slots of the last aggregate
+    twap.record_aggregate(400, -5000, 50);
+    assert_eq!((twap.price, twap.conf), (4000, 40));
+    twap.record_aggregate(410, 0, 0);
+    assert_eq!((twap.price, twap.conf), (3400, 40));
+
+    twap.mode = AverageMode::Migrating as u32;
+    assert_eq!(twap.published(), None);
+}
+
+#[test]
+fn test_set_average_mode() {
+    let mut feed = FeedSimulator::new(1).with_extension::<SlotWeightedTwap>();
+
+    assert_eq!(
+        feed.set_average_mode(AverageMode::SlotWeighted, 0),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        feed.set_average_mode(AverageMode::SlotWeighted, 100),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_average_mode(AverageMode::SlotWeighted, 160)
+        .unwrap();
+    let twap = feed.extension::<SlotWeightedTwap>();
+    assert_eq!(twap.mode(), Some(AverageMode::SlotWeighted));
+    assert_eq!(twap.bucket_slots, 10);
+
+    // Every update aggregates the quotes of the previous slots
+    for (slot, price) in [(100, 1000), (101, 2000), (111, 3000), (131, 3000)] {
+        feed.warp_to_slot(slot);
+        feed.publish(0, price, 1).unwrap();
+    }
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.pub_slot_, 131);
+    assert_eq!(price_data.twap_.val_, 1666);
+    assert_eq!(price_data.twac_.val_, 1);
+    assert_eq!(feed.price_data().get_ema_price().price, 1666);
+
+    // While migrating the EMA is published again and the TWAP is still maintained
+    feed.set_average_mode(AverageMode::Migrating, 160).unwrap();
+    feed.warp_to_slot(132);
+    feed.publish(0, 3000, 1).unwrap();
+    let twap = feed.extension::<SlotWeightedTwap>();
+    assert_eq!((twap.last_slot, twap.price), (132, 1709));
+    assert_ne!(feed.price_data().twap_.val_, twap.price);
+
+    // A new window starts the TWAP over, keeping only the mode
+    feed.set_average_mode(AverageMode::SlotWeighted, 320)
+        .unwrap();
+    let twap = feed.extension::<SlotWeightedTwap>();
+    assert_eq!((twap.bucket_slots, twap.last_slot), (20, 0));
+    feed.warp_to_slot(133);
+    feed.publish(0, 3000, 1).unwrap();
+    assert_eq!(feed.price_data().twap_.val_, 3000);
+}
@@ -0,0 +1,144 @@
+use {
+    crate::{
+        accounts::{
+            PythAccount,
+            SnapshotAccount,
+        },
+        c_oracle_header::{
+            PC_ACCTYPE_SNAPSHOT,
+            PC_MAGIC,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPermissionsArgs,
+        },
+        tests::pyth_simulator::{
+            copy_keypair,
+            PythSimulator,
+            Quote,
+        },
+    },
+    solana_program::native_token::LAMPORTS_PER_SOL,
+    solana_sdk::{
+        instruction::InstructionError,
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+};
+
+#[tokio::test]
+async fn test_snapshot_price_account() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let security_authority = Keypair::new();
+    let price_accounts = sim
+        .setup_product_fixture(&[publisher.pubkey()], security_authority.pubkey())
+        .await;
+    let price = price_accounts["LTC"];
+
+    sim.upd_price(
+        &publisher,
+        price,
+        Quote {
+            price:      150,
+            confidence: 7,
+            status:     PC_STATUS_TRADING,
+        },
+    )
+    .await
+    .unwrap();
+
+    // Only the master and the security authorities can take snapshots
+    assert_eq!(
+        sim.snapshot_price_account(price, &security_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::PermissionViolation as u32)
+        )
+    );
+
+    let genesis_keypair = copy_keypair(&sim.genesis_keypair);
+    sim.upd_permissions(
+        UpdPermissionsArgs {
+            header:                  OracleCommand::UpdPermissions.into(),
+            master_authority:        genesis_keypair.pubkey(),
+            data_curation_authority: genesis_keypair.pubkey(),
+            security_authority:      security_authority.pubkey(),
+        },
+        &copy_keypair(&sim.upgrade_authority),
+    )
+    .await
+    .unwrap();
+
+    let snapshot = sim
+        .snapshot_price_account(price, &security_authority)
+        .await
+        .unwrap();
+
+    let price_account = sim.get_account(price).await.unwrap();
+    let snapshot_account = sim.get_account(snapshot).await.unwrap();
+    assert!(sim.is_owned_by_oracle(&snapshot_account));
+    assert_eq!(
+        snapshot_account.data.len(),
+        SnapshotAccount::MINIMUM_SIZE + price_account.data.len()
+    );
+    assert_eq!(
+        snapshot_account.data[SnapshotAccount::MINIMUM_SIZE..],
+        price_account.data[..]
+    );
+
+    let snapshot_data = sim
+        .get_account_data_as::<SnapshotAccount>(snapshot)
+        .await
+        .unwrap();
+    assert_eq!(snapshot_data.header.magic_number, PC_MAGIC);
+    assert_eq!(snapshot_data.header.version, PC_VERSION);
+    assert_eq!(snapshot_data.header.account_type, PC_ACCTYPE_SNAPSHOT);
+    assert_eq!(
+        snapshot_data.header.size as usize,
+        snapshot_account.data.len()
+    );
+    assert_eq!(snapshot_data.price_account, price);
+
+    // A price account can only be snapshotted once per slot
+    assert_eq!(
+        sim.snapshot_price_account(price, &security_authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(OracleError::InvalidFreshAccount as u32)
+        )
+    );
+
+    sim.warp_to_slot(snapshot_data.slot + 1).await.unwrap();
+    let next_snapshot = sim
+        .snapshot_price_account(price, &security_authority)
+        .await
+        .unwrap();
+    assert_ne!(next_snapshot, snapshot);
+    assert_eq!(
+        sim.get_account_data_as::<SnapshotAccount>(next_snapshot)
+            .await
+            .unwrap()
+            .slot,
+        snapshot_data.slot + 1
+    );
+
+    // Snapshots are funded by the caller
+    assert!(
+        sim.get_account(security_authority.pubkey())
+            .await
+            .unwrap()
+            .lamports
+            < 100 * LAMPORTS_PER_SOL
+    );
+}
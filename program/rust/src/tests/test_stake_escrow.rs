@@ -0,0 +1,139 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            PriceAccount,
+            PublisherStake,
+        },
+        error::OracleError,
+        tests::{
+            pyth_simulator::PythSimulator,
+            test_utils::FeedSimulator,
+        },
+    },
+    bytemuck::Zeroable,
+    solana_program::pubkey::Pubkey,
+    solana_sdk::signer::Signer,
+};
+
+#[test]
+fn test_stake_entries() {
+    let publishers = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    let mut stake = PublisherStake::zeroed();
+
+    stake.insert(0, 1, &publishers[1], 10);
+    stake.insert(0, 2, &publishers[0], 20);
+    stake.insert(2, 3, &publishers[2], 30);
+    assert_eq!(stake.stake_of(0, &publishers[0]), 20);
+    assert_eq!(stake.stake_of(1, &publishers[1]), 10);
+    assert_eq!(stake.stake_of(2, &publishers[2]), 30);
+    // Entries only apply to the component at their index
+    assert_eq!(stake.stake_of(1, &publishers[0]), 0);
+
+    stake.remove(0, 3);
+    assert_eq!(stake.stake_of(0, &publishers[1]), 10);
+    assert_eq!(stake.stake_of(1, &publishers[2]), 30);
+    assert_eq!(stake.stake_of(2, &publishers[2]), 0);
+
+    // Out of range indexes are ignored
+    stake.insert(5, 3, &publishers[0], 40);
+    stake.remove(5, 2);
+    assert_eq!(stake.components[2].amount, 0);
+}
+
+#[test]
+fn test_set_stake_program() {
+    let staking_program = Pubkey::new_unique();
+    let mut feed = FeedSimulator::new(0).with_extension::<PublisherStake>();
+    feed.set_stake_program(staking_program, 100).unwrap();
+    let stake = feed.extension::<PublisherStake>();
+    assert_eq!(stake.staking_program, staking_program);
+    assert_eq!(stake.min_stake, 100);
+
+    // The default key stops checking the stake of new publishers
+    feed.set_stake_program(Pubkey::default(), 100).unwrap();
+    let publisher = Pubkey::new_unique();
+    feed.add_publisher(publisher);
+    let stake = feed.extension::<PublisherStake>();
+    assert_eq!(feed.price_data().num_, 1);
+    assert_eq!(stake.staking_program, Pubkey::default());
+    // The entries still follow the components
+    assert_eq!(stake.components[0].publisher, publisher);
+    assert_eq!(stake.stake_of(0, &publisher), 0);
+}
+
+#[tokio::test]
+async fn test_stake_escrow() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    let staking_program_id = sim.staking_program_id;
+    sim.set_stake_program(&price_keypair, staking_program_id, 100)
+        .await
+        .unwrap();
+
+    // Publishers must pass their escrow
+    let publisher = Pubkey::new_unique();
+    assert_eq!(
+        sim.add_publisher(&price_keypair, publisher)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidStakeEscrow.into()
+    );
+    // Owned by the staking program
+    let escrow = sim.create_stake_escrow(Pubkey::new_unique(), 150);
+    assert_eq!(
+        sim.add_publisher_with_stake(&price_keypair, publisher, escrow)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InvalidStakeEscrow.into()
+    );
+    // Holding the minimum stake
+    let escrow = sim.create_stake_escrow(staking_program_id, 99);
+    assert_eq!(
+        sim.add_publisher_with_stake(&price_keypair, publisher, escrow)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::InsufficientStake.into()
+    );
+
+    let escrow = sim.create_stake_escrow(staking_program_id, 150);
+    sim.add_publisher_with_stake(&price_keypair, publisher, escrow)
+        .await
+        .unwrap();
+    let other_publisher = Pubkey::new_unique();
+    let other_escrow = sim.create_stake_escrow(staking_program_id, 100);
+    sim.add_publisher_with_stake(&price_keypair, other_publisher, other_escrow)
+        .await
+        .unwrap();
+
+    // The stakes are recorded at the index of the components
+    let data = sim.get_account(price).await.unwrap().data;
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    let stake = get_extension::<PublisherStake>(&data).unwrap().unwrap();
+    assert_eq!(price_data.num_, 2);
+    for (i, comp) in price_data.comp_[..2].iter().enumerate() {
+        let expected = if comp.pub_ == publisher { 150 } else { 100 };
+        assert_eq!(stake.stake_of(i, &comp.pub_), expected);
+    }
+
+    // Changing the staking program drops the recorded stakes
+    sim.set_stake_program(&price_keypair, Pubkey::new_unique(), 100)
+        .await
+        .unwrap();
+    let data = sim.get_account(price).await.unwrap().data;
+    let stake = get_extension::<PublisherStake>(&data).unwrap().unwrap();
+    assert!(stake.components.iter().all(|entry| entry.amount == 0));
+}
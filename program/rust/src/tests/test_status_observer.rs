@@ -0,0 +1,122 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            StatusObserver,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_status_observer_missing() {
+    let observer_id = Pubkey::new_unique();
+    let mut feed = FeedSimulator::new(1).with_extension::<StatusObserver>();
+    let program_id = feed.program_id();
+    assert_eq!(
+        feed.set_status_observer(program_id),
+        Err(ProgramError::InvalidArgument)
+    );
+    feed.set_status_observer(observer_id).unwrap();
+    assert_eq!(feed.extension::<StatusObserver>().program_id, observer_id);
+
+    // Without the observer, the transitions are only logged
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_TRADING);
+
+    // Nor with other programs in its place
+    feed.pass_sanity_program(Pubkey::new_unique());
+    feed.pass_status_observer(Pubkey::new_unique());
+    feed.warp_to_slot(100);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+}
+
+#[tokio::test]
+async fn test_status_observer() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.set_status_observer(&price_keypair, sim.status_observer_id)
+        .await
+        .unwrap();
+
+    // The observer of the simulator accepts the transition to trading
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price_with_status_observer(&publisher, price, quote(100))
+        .await
+        .unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price_with_status_observer(&publisher, price, quote(100))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+
+    // It rejects the transition to unknown once the quote is stale, failing the update
+    sim.warp_to_slot(100).await.unwrap();
+    assert!(sim
+        .upd_price_with_status_observer(&publisher, price, quote(100))
+        .await
+        .is_err());
+    sim.upd_price(&publisher, price, quote(100)).await.unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
+
+    // Aggregations keeping the status don't notify it
+    sim.warp_to_slot(101).await.unwrap();
+    sim.upd_price_with_status_observer(&publisher, price, quote(100))
+        .await
+        .unwrap();
+    sim.warp_to_slot(102).await.unwrap();
+    sim.upd_price_with_status_observer(&publisher, price, quote(100))
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.last_slot_, 102);
+}
+
+fn quote(price: i64) -> Quote {
+    Quote {
+        price,
+        confidence: 1,
+        status: PC_STATUS_TRADING,
+    }
+}
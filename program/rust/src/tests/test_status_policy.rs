@@ -0,0 +1,112 @@
+use {
+    crate::{
+        accounts::{
+            StatusPolicy,
+            StatusReason,
+            StatusReasonCode,
+        },
+        c_oracle_header::{
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+            PC_STATUS_IGNORED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_status_policy() {
+    let mut feed = FeedSimulator::new(3)
+        .with_min_pub(2)
+        .with_extension::<StatusPolicy>()
+        .with_extension::<StatusReason>();
+    feed.set_status_reason_tracking(true).unwrap();
+
+    // Without a quorum, halted publishers leave the feed unknown
+    feed.set_status_policy(0, 0, false).unwrap();
+    feed.warp_to_slot(10);
+    feed.upd_price(0, PC_STATUS_HALTED, 100, 1, 10).unwrap();
+    feed.upd_price(1, PC_STATUS_HALTED, 100, 1, 10).unwrap();
+    feed.publish(2, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(2, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(
+        feed.extension::<StatusReason>().code(),
+        Some(StatusReasonCode::InsufficientQuorum)
+    );
+
+    // A quorum of halted publishers halts the feed
+    feed.set_status_policy(2, 2, false).unwrap();
+    feed.warp_to_slot(12);
+    feed.publish(2, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_HALTED);
+    assert_eq!(
+        feed.extension::<StatusReason>().code(),
+        Some(StatusReasonCode::StatusPolicy)
+    );
+
+    feed.upd_price(0, PC_STATUS_AUCTION, 100, 1, 12).unwrap();
+    feed.upd_price(1, PC_STATUS_AUCTION, 100, 1, 12).unwrap();
+    feed.warp_to_slot(13);
+    feed.publish(2, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_AUCTION);
+
+    // A quorum doesn't replace an aggregate of the trading quotes unless configured to
+    feed.set_status_policy(1, 0, false).unwrap();
+    feed.upd_price(0, PC_STATUS_HALTED, 100, 1, 13).unwrap();
+    feed.publish(1, 104, 1).unwrap();
+    feed.warp_to_slot(14);
+    feed.publish(2, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    let price = price_data.agg_.price_;
+
+    feed.set_status_policy(1, 0, true).unwrap();
+    feed.publish(1, 108, 1).unwrap();
+    feed.warp_to_slot(15);
+    feed.publish(2, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_HALTED);
+    assert_eq!(price_data.agg_.price_, price);
+
+    // A quorum above the number of components could never be reached
+    assert_eq!(
+        feed.set_status_policy(65, 0, false),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn test_publisher_status() {
+    let mut feed = FeedSimulator::new(2);
+    feed.warp_to_slot(10);
+
+    // A quote too wide to count keeps the status its publisher supplied
+    feed.upd_price_from_venue(0, PC_STATUS_TRADING, 100, 1_000, 10, 3)
+        .unwrap();
+    let quote = feed.price_data().comp_[0].latest_;
+    assert_eq!(quote.status_, PC_STATUS_IGNORED);
+    assert_eq!(quote.publisher_status(), PC_STATUS_TRADING);
+    assert_eq!(quote.venue(), 3);
+
+    feed.upd_price(1, PC_STATUS_HALTED, 100, 1, 10).unwrap();
+    let price_data = feed.price_data();
+    let quote = price_data.comp_[1].latest_;
+    assert_eq!(quote.status_, PC_STATUS_HALTED);
+    assert_eq!(quote.publisher_status(), PC_STATUS_HALTED);
+    assert_eq!(quote.venue(), 0);
+
+    // The aggregation snapshots the status with the quote
+    assert_eq!(
+        price_data.comp_[0].agg_.publisher_status(),
+        PC_STATUS_TRADING
+    );
+    assert_eq!(
+        price_data.comp_[1].agg_.publisher_status(),
+        PC_STATUS_HALTED
+    );
+}
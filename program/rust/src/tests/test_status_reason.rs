@@ -0,0 +1,88 @@
+use {
+    crate::{
+        accounts::{
+            SanityProgram,
+            StatusReason,
+            StatusReasonCode,
+        },
+        c_oracle_header::{
+            PC_STATUS_HALTED,
+            PC_STATUS_UNKNOWN,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+fn last_reason(feed: &FeedSimulator) -> (Option<StatusReasonCode>, u64) {
+    let status_reason = feed.extension::<StatusReason>();
+    (status_reason.code(), status_reason.slot)
+}
+
+#[test]
+fn test_status_reason() {
+    let mut feed = FeedSimulator::new(2)
+        .with_min_pub(2)
+        .with_extension::<StatusReason>()
+        .with_extension::<SanityProgram>();
+
+    // Nothing is recorded until the tracking is enabled
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    feed.publish(1, 100, 1).unwrap();
+    assert_eq!(last_reason(&feed), (Some(StatusReasonCode::None), 0));
+
+    feed.set_status_reason_tracking(true).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(last_reason(&feed), (Some(StatusReasonCode::None), 11));
+
+    // Every update aggregates the quotes of the previous slot
+    feed.warp_to_slot(12);
+    feed.upd_price(1, PC_STATUS_HALTED, 100, 1, 12).unwrap();
+    feed.warp_to_slot(13);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().agg_.status_, PC_STATUS_UNKNOWN);
+    assert_eq!(
+        last_reason(&feed),
+        (Some(StatusReasonCode::InsufficientQuorum), 13)
+    );
+
+    feed.warp_to_slot(14);
+    feed.publish(1, 100, 0).unwrap();
+    feed.warp_to_slot(15);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        last_reason(&feed),
+        (Some(StatusReasonCode::ZeroConfForbidden), 15)
+    );
+
+    feed.warp_to_slot(100);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        last_reason(&feed),
+        (Some(StatusReasonCode::AllComponentsStale), 100)
+    );
+
+    // The sanity program isn't passed, so it can't approve the aggregate
+    feed.set_sanity_program(Pubkey::new_unique()).unwrap();
+    feed.warp_to_slot(101);
+    feed.publish(1, 100, 1).unwrap();
+    feed.warp_to_slot(102);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(
+        last_reason(&feed),
+        (Some(StatusReasonCode::CircuitBreaker), 102)
+    );
+
+    // Stopping the tracking keeps the last reason
+    feed.set_status_reason_tracking(false).unwrap();
+    feed.set_sanity_program(Pubkey::default()).unwrap();
+    feed.warp_to_slot(103);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().last_slot_, 103);
+    assert_eq!(
+        last_reason(&feed),
+        (Some(StatusReasonCode::CircuitBreaker), 102)
+    );
+}
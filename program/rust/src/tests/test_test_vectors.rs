@@ -0,0 +1,56 @@
+use {
+    crate::{
+        accounts::PriceAccount,
+        processor::c_upd_aggregate,
+        test_vectors::{
+            test_vectors_json,
+            AGGREGATION_VECTORS,
+        },
+    },
+    bytemuck::Zeroable,
+    std::fs,
+};
+
+// The vectors are published for other implementations to check themselves against, so each of
+// them must hold for the current implementation.
+#[test]
+fn test_test_vectors() {
+    for vector in AGGREGATION_VECTORS {
+        let mut price_account = PriceAccount::zeroed();
+        price_account.last_slot_ = vector.slot - 1;
+        price_account.agg_.pub_slot_ = vector.slot - 1;
+        price_account.exponent = vector.exponent;
+        price_account.num_ = vector.components.len() as u32;
+        for (component, quote) in price_account.comp_.iter_mut().zip(vector.components) {
+            component.latest_.price_ = quote.price;
+            component.latest_.conf_ = quote.conf;
+            component.latest_.status_ = quote.status;
+            component.latest_.pub_slot_ = quote.pub_slot;
+        }
+
+        unsafe {
+            c_upd_aggregate(
+                (&mut price_account as *mut PriceAccount) as *mut u8,
+                vector.slot,
+                1234,
+            );
+        }
+
+        assert_eq!(price_account.agg_.price_, vector.price, "{}", vector.name);
+        assert_eq!(price_account.agg_.conf_, vector.conf, "{}", vector.name);
+        assert_eq!(price_account.agg_.status_, vector.status, "{}", vector.name);
+        assert_eq!(price_account.num_qt_, vector.num_qt, "{}", vector.name);
+    }
+}
+
+#[test]
+fn test_test_vectors_json() {
+    let path = "test_data/vectors/aggregation.json";
+    let json = fs::read_to_string(path).expect("Test file not found");
+    assert_eq!(
+        test_vectors_json(),
+        json,
+        "The vectors changed, write them with `pyth-test-vectors {path}`"
+    );
+    let _: serde_json::Value = serde_json::from_str(&json).expect("Unable to parse JSON");
+}
@@ -19,7 +19,10 @@ use {
             UpdPriceArgs,
         },
         processor::c_upd_aggregate,
-        tests::test_utils::AccountSetup,
+        tests::test_utils::{
+            AccountSetup,
+            FeedSimulator,
+        },
     },
     solana_program::pubkey::Pubkey,
     std::mem::size_of,
@@ -363,6 +366,29 @@ fn test_upd_aggregate() {
     }
 }
 
+#[test]
+fn test_upd_aggregate_min_pub() {
+    let mut feed = FeedSimulator::new(8).with_min_pub(8);
+    let quotes = [
+        Some((100, 30)),
+        Some((100, 30)),
+        Some((100, 30)),
+        Some((100, 30)),
+        Some((120, 30)),
+        Some((120, 30)),
+        Some((120, 30)),
+        Some((120, 30)),
+    ];
+
+    // The last publisher only starts publishing in the second slot, so the feed only reaches
+    // min_pub when the third slot aggregates the quotes of the second one.
+    feed.post_updates(&[&quotes[..7], &quotes, &quotes]);
+    feed.assert_aggregate_statuses(&[PC_STATUS_UNKNOWN, PC_STATUS_UNKNOWN, PC_STATUS_TRADING]);
+    assert_eq!(feed.aggregates()[2].price_, 110);
+    assert_eq!(feed.aggregates()[2].conf_, 20);
+    assert_eq!(feed.price_data().num_qt_, 8);
+}
+
 // Create an upd_price instruction with the provided parameters
 fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_slot: u64) {
     let mut cmd = load_mut::<UpdPriceArgs>(instruction_data).unwrap();
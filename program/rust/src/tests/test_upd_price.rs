@@ -14,20 +14,18 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
         },
         processor::process_instruction,
         tests::test_utils::{
-            update_clock_slot,
             AccountSetup,
+            TestClock,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -53,18 +51,16 @@ fn test_upd_price() {
     }
 
     let mut clock_setup = AccountSetup::new_clock();
-    let mut clock_account = clock_setup.as_account_info();
-    clock_account.is_signer = false;
-    clock_account.is_writable = false;
+    let mut clock = TestClock::new(clock_setup.as_account_info());
 
-    update_clock_slot(&mut clock_account, 1);
+    clock.warp_to_slot(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -91,11 +87,11 @@ fn test_upd_price() {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -112,14 +108,14 @@ fn test_upd_price() {
 
     // add next price in new slot triggering snapshot and aggregate calc
     populate_instruction(&mut instruction_data, 81, 2, 2);
-    update_clock_slot(&mut clock_account, 3);
+    clock.warp_to_slot(3);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -139,13 +135,13 @@ fn test_upd_price() {
 
     // next price doesn't change but slot does
     populate_instruction(&mut instruction_data, 81, 2, 3);
-    update_clock_slot(&mut clock_account, 4);
+    clock.advance_slots(1);
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -165,13 +161,13 @@ fn test_upd_price() {
 
     // next price doesn't change and neither does aggregate but slot does
     populate_instruction(&mut instruction_data, 81, 2, 4);
-    update_clock_slot(&mut clock_account, 5);
+    clock.advance_slots(1);
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -191,18 +187,18 @@ fn test_upd_price() {
 
     // try to publish back-in-time
     populate_instruction(&mut instruction_data, 81, 2, 1);
-    update_clock_slot(&mut clock_account, 5);
+    clock.warp_to_slot(5);
     assert_eq!(
         process_instruction(
             &program_id,
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -218,7 +214,7 @@ fn test_upd_price() {
     }
 
     populate_instruction(&mut instruction_data, 50, 20, 5);
-    update_clock_slot(&mut clock_account, 6);
+    clock.advance_slots(1);
 
     // Publishing a wide CI results in a status of unknown.
 
@@ -233,7 +229,7 @@ fn test_upd_price() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -253,14 +249,14 @@ fn test_upd_price() {
 
     // Crank one more time and aggregate should be unknown
     populate_instruction(&mut instruction_data, 50, 20, 6);
-    update_clock_slot(&mut clock_account, 7);
+    clock.advance_slots(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -280,14 +276,14 @@ fn test_upd_price() {
 
     // Negative prices are accepted
     populate_instruction(&mut instruction_data, -100, 1, 7);
-    update_clock_slot(&mut clock_account, 8);
+    clock.advance_slots(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -307,14 +303,14 @@ fn test_upd_price() {
 
     // Crank again for aggregate
     populate_instruction(&mut instruction_data, -100, 1, 8);
-    update_clock_slot(&mut clock_account, 9);
+    clock.advance_slots(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -0,0 +1,234 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            PriceInfo,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        instruction::{
+            OracleCommand,
+            UpdPriceArgs,
+            UpdPriceCompactArgs,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    bytemuck::bytes_of,
+    quickcheck::TestResult,
+    quickcheck_macros::quickcheck,
+    rand::{
+        rngs::StdRng,
+        Rng,
+        SeedableRng,
+    },
+    solana_program::pubkey::Pubkey,
+};
+
+fn upd_price_args(price: i64, confidence: u64, publishing_slot: u64, status: u32) -> UpdPriceArgs {
+    UpdPriceArgs {
+        header: OracleCommand::UpdPrice.into(),
+        status,
+        venue: 0,
+        unused_: [0; 3],
+        price,
+        confidence,
+        publishing_slot,
+        sequence: 0,
+    }
+}
+
+/// Arguments that can be encoded decode back to themselves.
+#[quickcheck]
+fn test_encode_compact(
+    latest: PriceInfo,
+    price: i64,
+    confidence: u64,
+    publishing_slot: u64,
+    status: u32,
+    venue: u8,
+) -> TestResult {
+    let mut args = upd_price_args(price, confidence, publishing_slot, status);
+    args.venue = venue;
+    let compact = match UpdPriceCompactArgs::encode(&args, &latest) {
+        Some(compact) => compact,
+        None => return TestResult::discard(),
+    };
+    assert_eq!(
+        compact.header.command,
+        OracleCommand::UpdPriceCompact as i32
+    );
+    let decoded = compact.decode(&latest).unwrap();
+    TestResult::from_bool(bytes_of(&decoded) == bytes_of(&args))
+}
+
+/// Arguments close to the latest quote always fit.
+#[quickcheck]
+fn test_encode_compact_nearby(
+    latest: PriceInfo,
+    price_delta: i32,
+    confidence: u32,
+    slot_delta: u16,
+    status: u8,
+) -> TestResult {
+    let (price, publishing_slot) = match (
+        latest.price_.checked_add(i64::from(price_delta)),
+        latest.pub_slot_.checked_add(u64::from(slot_delta)),
+    ) {
+        (Some(price), Some(publishing_slot)) => (price, publishing_slot),
+        _ => return TestResult::discard(),
+    };
+    let args = upd_price_args(
+        price,
+        u64::from(confidence),
+        publishing_slot,
+        u32::from(status),
+    );
+    let compact = UpdPriceCompactArgs::encode(&args, &latest).unwrap();
+    TestResult::from_bool(compact.price_delta == price_delta && compact.slot_delta == slot_delta)
+}
+
+/// Any compact arguments either decode to arguments encoding back to them, or are rejected
+/// for not being relative to the latest quote.
+#[quickcheck]
+fn test_decode_compact(
+    latest: PriceInfo,
+    price_delta: i32,
+    confidence: u32,
+    base_slot: u16,
+    slot_delta: u16,
+    status: u8,
+    venue: u8,
+) {
+    let compact = UpdPriceCompactArgs {
+        header: OracleCommand::UpdPriceCompact.into(),
+        price_delta,
+        confidence,
+        base_slot,
+        slot_delta,
+        status,
+        venue,
+        unused_: [0; 2],
+    };
+    match compact.decode(&latest) {
+        Ok(args) => {
+            assert_eq!(base_slot, latest.pub_slot_ as u16);
+            assert_eq!(args.header.command, OracleCommand::UpdPrice as i32);
+            assert_eq!(
+                bytes_of(&UpdPriceCompactArgs::encode(&args, &latest).unwrap()),
+                bytes_of(&compact)
+            );
+        }
+        Err(error) => assert_eq!(error, OracleError::CompactBaseMismatch),
+    }
+}
+
+/// Price account of `feed` without the publisher keys, which differ between feeds.
+fn price_state(feed: &mut FeedSimulator) -> PriceAccount {
+    let mut price_data = feed.price_data();
+    for component in price_data.comp_.iter_mut() {
+        component.pub_ = Pubkey::default();
+    }
+    price_data
+}
+
+/// A feed updated with compact arguments whenever they fit and a feed updated with `UpdPrice`
+/// go through the same random updates. Their price accounts must stay identical.
+#[test]
+fn test_upd_price_compact_matches_upd_price() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let num_publishers = 5;
+    let mut compact = FeedSimulator::new(num_publishers);
+    let mut reference = FeedSimulator::new(num_publishers);
+    let mut compact_updates = 0;
+
+    for slot in 1..=200 {
+        compact.warp_to_slot(slot);
+        reference.warp_to_slot(slot);
+
+        for publisher in 0..num_publishers {
+            if !rng.gen_bool(0.7) {
+                continue;
+            }
+            let status = if rng.gen_bool(0.9) {
+                PC_STATUS_TRADING
+            } else {
+                PC_STATUS_UNKNOWN
+            };
+            // Mostly small moves, with jumps that don't fit the compact arguments
+            let price = if rng.gen_bool(0.05) {
+                rng.gen_range(0..=i64::from(u32::MAX) * 4)
+            } else {
+                1_000_000 + rng.gen_range(-1_000..=1_000)
+            };
+            let conf = rng.gen_range(0..=5);
+            let args = upd_price_args(price, conf, slot, status);
+
+            let latest = compact.price_data().comp_[publisher].latest_;
+            let result = match UpdPriceCompactArgs::encode(&args, &latest) {
+                Some(compact_args) => {
+                    compact_updates += 1;
+                    compact.upd_price_compact(publisher, &compact_args)
+                }
+                None => compact.upd_price(publisher, status, price, conf, slot),
+            };
+            assert_eq!(
+                result,
+                reference.upd_price(publisher, status, price, conf, slot)
+            );
+        }
+
+        assert_eq!(
+            bytes_of(&price_state(&mut compact)),
+            bytes_of(&price_state(&mut reference)),
+            "slot {slot}"
+        );
+    }
+
+    assert!(compact_updates > 500);
+}
+
+#[test]
+fn test_upd_price_compact() {
+    let mut feed = FeedSimulator::new(2);
+    feed.post_slot(&[Some((100, 1)), Some((110, 1))]);
+    feed.advance_slot();
+
+    let latest = feed.price_data().comp_[0].latest_;
+    let compact_args = |price: i64, publishing_slot: u64| {
+        UpdPriceCompactArgs::encode(
+            &upd_price_args(price, 2, publishing_slot, PC_STATUS_TRADING),
+            &latest,
+        )
+        .unwrap()
+    };
+    feed.upd_price_compact(0, &compact_args(95, 2)).unwrap();
+    let quote = feed.price_data().comp_[0].latest_;
+    assert_eq!(quote.price_, 95);
+    assert_eq!(quote.conf_, 2);
+    assert_eq!(quote.pub_slot_, 2);
+
+    // An update relative to an older quote is rejected
+    feed.advance_slot();
+    assert_eq!(
+        feed.upd_price_compact(0, &compact_args(97, 3)),
+        Err(OracleError::CompactBaseMismatch.into())
+    );
+
+    // Publishing slots follow the rules of `UpdPrice`
+    let latest = feed.price_data().comp_[0].latest_;
+    let mut stale =
+        UpdPriceCompactArgs::encode(&upd_price_args(97, 2, 3, PC_STATUS_TRADING), &latest).unwrap();
+    stale.slot_delta = 0;
+    assert_eq!(
+        feed.upd_price_compact(0, &stale),
+        Err(OracleError::StaleSubmission.into())
+    );
+    stale.slot_delta = 2;
+    assert_eq!(
+        feed.upd_price_compact(0, &stale),
+        Err(OracleError::PublishingSlotInFuture.into())
+    );
+}
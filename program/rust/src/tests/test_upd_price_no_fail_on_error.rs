@@ -2,6 +2,7 @@ use {
     crate::{
         accounts::{
             PriceAccount,
+            PriceAccountFlags,
             PythAccount,
         },
         c_oracle_header::{
@@ -20,14 +21,11 @@ use {
         },
         processor::process_instruction,
         tests::test_utils::{
-            update_clock_slot,
             AccountSetup,
+            TestClock,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -46,11 +44,9 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
     PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
 
     let mut clock_setup = AccountSetup::new_clock();
-    let mut clock_account = clock_setup.as_account_info();
-    clock_account.is_signer = false;
-    clock_account.is_writable = false;
+    let mut clock = TestClock::new(clock_setup.as_account_info());
 
-    update_clock_slot(&mut clock_account, 1);
+    clock.warp_to_slot(1);
 
 
     // Check that the normal upd_price fails
@@ -62,11 +58,11 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(OracleError::PermissionViolation.into())
+        Err(OracleError::PublisherNotFound.into())
     );
 
 
@@ -79,7 +75,7 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -108,7 +104,7 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -137,11 +133,11 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     populate_instruction(&mut instruction_data, 55, 22, 1, false);
@@ -150,7 +146,7 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -167,6 +163,27 @@ fn test_upd_price_no_fail_on_error_no_fail_on_error() {
         assert_eq!(price_data.agg_.price_, 0);
         assert_eq!(price_data.agg_.status_, PC_STATUS_UNKNOWN);
     }
+
+    // An update failing in the middle of an aggregation isn't swallowed, so that the runtime
+    // rolls the partial aggregation back with the transaction
+    load_checked::<PriceAccount>(&price_account, PC_VERSION)
+        .unwrap()
+        .flags
+        .insert(PriceAccountFlags::AGGREGATION_IN_PROGRESS);
+    clock.advance_slots(1);
+    populate_instruction(&mut instruction_data, 55, 22, 2, false);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                price_account.clone(),
+                clock.account()
+            ],
+            &instruction_data
+        ),
+        Err(OracleError::AggregationInProgress.into())
+    );
 }
 
 
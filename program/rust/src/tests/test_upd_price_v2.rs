@@ -14,20 +14,18 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
         },
         processor::process_instruction,
         tests::test_utils::{
-            update_clock_slot,
             AccountSetup,
+            TestClock,
         },
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -53,18 +51,16 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     let mut clock_setup = AccountSetup::new_clock();
-    let mut clock_account = clock_setup.as_account_info();
-    clock_account.is_signer = false;
-    clock_account.is_writable = false;
+    let mut clock = TestClock::new(clock_setup.as_account_info());
 
-    update_clock_slot(&mut clock_account, 1);
+    clock.warp_to_slot(1);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -95,11 +91,11 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -121,14 +117,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // add next price in new slot triggering snapshot and aggregate calc
     populate_instruction(&mut instruction_data, 81, 2, 2);
-    update_clock_slot(&mut clock_account, 3);
+    clock.warp_to_slot(3);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -152,13 +148,13 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // next price doesn't change but slot does
     populate_instruction(&mut instruction_data, 81, 2, 3);
-    update_clock_slot(&mut clock_account, 4);
+    clock.advance_slots(1);
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -182,13 +178,13 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // next price doesn't change and neither does aggregate but slot does
     populate_instruction(&mut instruction_data, 81, 2, 4);
-    update_clock_slot(&mut clock_account, 5);
+    clock.advance_slots(1);
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -212,18 +208,18 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // try to publish back-in-time
     populate_instruction(&mut instruction_data, 81, 2, 1);
-    update_clock_slot(&mut clock_account, 5);
+    clock.warp_to_slot(5);
     assert_eq!(
         process_instruction(
             &program_id,
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -244,7 +240,7 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     populate_instruction(&mut instruction_data, 50, 20, 5);
-    update_clock_slot(&mut clock_account, 6);
+    clock.advance_slots(1);
 
     // Publishing a wide CI results in a status of unknown.
 
@@ -259,7 +255,7 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -283,14 +279,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // Crank one more time and aggregate should be unknown
     populate_instruction(&mut instruction_data, 50, 20, 6);
-    update_clock_slot(&mut clock_account, 7);
+    clock.advance_slots(1);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -314,14 +310,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // Negative prices are accepted
     populate_instruction(&mut instruction_data, -100, 1, 7);
-    update_clock_slot(&mut clock_account, 8);
+    clock.advance_slots(1);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -345,14 +341,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
 
     // Crank again for aggregate
     populate_instruction(&mut instruction_data, -100, 1, 8);
-    update_clock_slot(&mut clock_account, 9);
+    clock.advance_slots(1);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -377,14 +373,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
     // Big gap
 
     populate_instruction(&mut instruction_data, 60, 4, 50);
-    update_clock_slot(&mut clock_account, 50);
+    clock.warp_to_slot(50);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -409,14 +405,14 @@ fn test_upd_price_v2() -> Result<(), Box<dyn std::error::Error>> {
     // Crank again for aggregate
 
     populate_instruction(&mut instruction_data, 55, 5, 51);
-    update_clock_slot(&mut clock_account, 51);
+    clock.advance_slots(1);
 
     process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
@@ -477,26 +473,20 @@ fn test_upd_works_with_unordered_publisher_set() -> Result<(), Box<dyn std::erro
     }
 
     let mut clock_setup = AccountSetup::new_clock();
-    let mut clock_account = clock_setup.as_account_info();
-    clock_account.is_signer = false;
-    clock_account.is_writable = false;
+    let mut clock = TestClock::new(clock_setup.as_account_info());
 
-    update_clock_slot(&mut clock_account, 1);
+    clock.warp_to_slot(1);
 
     for (i, publisher) in publishers.iter().enumerate() {
         populate_instruction(&mut instruction_data, (i + 100) as i64, 10, 1);
         process_instruction(
             &program_id,
-            &[
-                publisher.clone(),
-                price_account.clone(),
-                clock_account.clone(),
-            ],
+            &[publisher.clone(), price_account.clone(), clock.account()],
             &instruction_data,
         )?;
     }
 
-    update_clock_slot(&mut clock_account, 2);
+    clock.advance_slots(1);
 
     // Trigger the aggregate calculation by sending another price
     // update
@@ -506,7 +496,7 @@ fn test_upd_works_with_unordered_publisher_set() -> Result<(), Box<dyn std::erro
         &[
             publishers[0].clone(),
             price_account.clone(),
-            clock_account.clone(),
+            clock.account(),
         ],
         &instruction_data,
     )?;
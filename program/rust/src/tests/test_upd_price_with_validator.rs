@@ -1,43 +1,51 @@
 use {
     crate::{
         accounts::{
+            data_len_with_extension,
+            init_extension,
+            AggregationConfigAccount,
             PriceAccount,
             PriceAccountFlags,
+            PriceInfo,
+            PublisherPrice,
+            PublisherPricesMessage,
             PythAccount,
             PythOracleSerialize,
+            SharedSettings,
         },
         c_oracle_header::{
             PC_STATUS_IGNORED,
             PC_STATUS_TRADING,
             PC_STATUS_UNKNOWN,
             PC_VERSION,
+            PRICE_ACCOUNT_DEFAULT_MIN_PUB,
         },
         deserialize::{
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             OracleCommand,
             UpdPriceArgs,
         },
         processor::process_instruction,
         tests::test_utils::{
-            update_clock_slot,
             AccountSetup,
+            TestClock,
         },
         validator::{
             self,
             checked_load_price_account_mut,
+            AggregationError,
         },
     },
+    bytemuck::Zeroable,
     pythnet_sdk::messages::{
         PriceFeedMessage,
         TwapMessage,
     },
-    solana_program::{
-        program_error::ProgramError,
-        pubkey::Pubkey,
-    },
+    solana_program::pubkey::Pubkey,
     std::mem::size_of,
 };
 
@@ -69,18 +77,16 @@ fn test_upd_price_with_validator() {
     }
 
     let mut clock_setup = AccountSetup::new_clock();
-    let mut clock_account = clock_setup.as_account_info();
-    clock_account.is_signer = false;
-    clock_account.is_writable = false;
+    let mut clock = TestClock::new(clock_setup.as_account_info());
 
-    update_clock_slot(&mut clock_account, 1);
+    clock.warp_to_slot(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -108,11 +114,11 @@ fn test_upd_price_with_validator() {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -128,7 +134,7 @@ fn test_upd_price_with_validator() {
     }
 
     // We aggregate the price at the end of each slot now.
-    let messages1 = validator::aggregate_price(
+    let messages1 = validator::aggregate_price_with_publisher_prices(
         1,
         101,
         price_account.key,
@@ -158,10 +164,24 @@ fn test_upd_price_with_validator() {
             publish_slot:      1,
         }
         .to_bytes(),
+        PublisherPricesMessage {
+            feed_id:      price_account.key.to_bytes(),
+            publish_time: 101,
+            publish_slot: 1,
+            exponent:     0,
+            publishers:   vec![PublisherPrice {
+                publisher: funding_account.key.to_bytes(),
+                price:     42,
+                conf:      2,
+                slot:      1,
+                venue:     0,
+            }],
+        }
+        .to_compressed_bytes(),
     ];
     assert_eq!(messages1, expected_messages1);
 
-    update_clock_slot(&mut clock_account, 2);
+    clock.advance_slots(1);
     let messages2 = validator::aggregate_price(
         2,
         102,
@@ -196,14 +216,14 @@ fn test_upd_price_with_validator() {
     ];
     assert_eq!(messages2, expected_messages2);
 
-    update_clock_slot(&mut clock_account, 3);
+    clock.advance_slots(1);
     populate_instruction(&mut instruction_data, 81, 2, 2);
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -232,13 +252,13 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 4);
+    clock.advance_slots(1);
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -265,14 +285,14 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 5);
+    clock.advance_slots(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -292,7 +312,7 @@ fn test_upd_price_with_validator() {
 
     // try to publish back-in-time
     populate_instruction(&mut instruction_data, 81, 2, 1);
-    update_clock_slot(&mut clock_account, 5);
+    clock.warp_to_slot(5);
 
     assert_eq!(
         process_instruction(
@@ -300,11 +320,11 @@ fn test_upd_price_with_validator() {
             &[
                 funding_account.clone(),
                 price_account.clone(),
-                clock_account.clone()
+                clock.account()
             ],
             &instruction_data
         ),
-        Err(ProgramError::InvalidArgument)
+        Err(OracleError::StaleSubmission.into())
     );
 
     {
@@ -327,7 +347,7 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 6);
+    clock.advance_slots(1);
 
     // Publishing a wide CI results in a status of unknown.
 
@@ -343,7 +363,7 @@ fn test_upd_price_with_validator() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -371,14 +391,14 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 7);
+    clock.advance_slots(1);
 
     assert!(process_instruction(
         &program_id,
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -405,7 +425,7 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 8);
+    clock.advance_slots(1);
 
 
     assert!(process_instruction(
@@ -413,7 +433,7 @@ fn test_upd_price_with_validator() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -440,7 +460,7 @@ fn test_upd_price_with_validator() {
         checked_load_price_account_mut(*price_account.data.borrow_mut()).unwrap(),
     )
     .unwrap();
-    update_clock_slot(&mut clock_account, 9);
+    clock.advance_slots(1);
 
 
     assert!(process_instruction(
@@ -448,7 +468,7 @@ fn test_upd_price_with_validator() {
         &[
             funding_account.clone(),
             price_account.clone(),
-            clock_account.clone()
+            clock.account()
         ],
         &instruction_data
     )
@@ -467,6 +487,69 @@ fn test_upd_price_with_validator() {
     }
 }
 
+#[test]
+fn test_aggregate_price_with_shared_settings() {
+    let program_id = Pubkey::new_unique();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    let key = *price_account.key;
+    let mut data = price_account.data.borrow().to_vec();
+    data.resize(data_len_with_extension::<SharedSettings>(&data).unwrap(), 0);
+    init_extension::<SharedSettings>(&mut data).unwrap().enabled = 1;
+    {
+        let price_data = checked_load_price_account_mut(&mut data).unwrap();
+        price_data
+            .flags
+            .insert(PriceAccountFlags::ACCUMULATOR_V2 | PriceAccountFlags::MESSAGE_BUFFER_CLEARED);
+        price_data.min_pub_ = PRICE_ACCOUNT_DEFAULT_MIN_PUB;
+        price_data.num_ = 1;
+        price_data.comp_[0].latest_ = PriceInfo {
+            price_:           100,
+            conf_:            1,
+            status_:          PC_STATUS_TRADING,
+            corp_act_status_: 0,
+            pub_slot_:        9,
+        };
+    }
+
+    // The feed follows the shared settings, the validator can't aggregate it without them
+    let mut config = AggregationConfigAccount::zeroed();
+    config.min_pub = 1;
+    let mut feature_gate_setup = AccountSetup::new_feature_gate(&program_id, &[]);
+    let feature_gate = feature_gate_setup.as_account_info().data.borrow().to_vec();
+    assert_eq!(
+        validator::aggregate_price_with_shared_settings(10, 110, &key, &mut data, None, None),
+        Err(AggregationError::MissingSharedSettings)
+    );
+    assert_eq!(
+        validator::aggregate_price_with_shared_settings(
+            10,
+            110,
+            &key,
+            &mut data,
+            Some(&config),
+            None
+        ),
+        Err(AggregationError::MissingSharedSettings)
+    );
+
+    // The single quote reaches the shared quorum, and the feed keeps its own
+    validator::aggregate_price_with_shared_settings(
+        10,
+        110,
+        &key,
+        &mut data,
+        Some(&config),
+        Some(&feature_gate),
+    )
+    .unwrap();
+    let price_data = checked_load_price_account_mut(&mut data).unwrap();
+    assert_eq!(price_data.agg_.pub_slot_, 10);
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.min_pub_, PRICE_ACCOUNT_DEFAULT_MIN_PUB);
+}
+
 // Create an upd_price instruction with the provided parameters
 fn populate_instruction(instruction_data: &mut [u8], price: i64, conf: u64, pub_slot: u64) {
     let mut cmd = load_mut::<UpdPriceArgs>(instruction_data).unwrap();
@@ -3,9 +3,15 @@ use {
         accounts::{
             account_has_key_values,
             create_pc_str_t,
+            get_product_metadata,
+            validate_product_metadata,
             PermissionAccount,
             ProductAccount,
+            ProductMetadataMessage,
             PythAccount,
+            PythOracleSerialize,
+            PRODUCT_METADATA_MAX_KEY_LEN,
+            PRODUCT_METADATA_MAX_VALUE_LEN,
         },
         c_oracle_header::{
             PC_PROD_ACC_SIZE,
@@ -15,17 +21,22 @@ use {
             load_checked,
             load_mut,
         },
+        error::OracleError,
         instruction::{
             CommandHeader,
             OracleCommand,
         },
         processor::process_instruction,
-        tests::test_utils::AccountSetup,
+        tests::{
+            pyth_simulator::PythSimulator,
+            test_utils::AccountSetup,
+        },
     },
     solana_program::{
         program_error::ProgramError,
         pubkey::Pubkey,
     },
+    solana_sdk::signer::Signer,
     std::mem::size_of,
 };
 
@@ -54,7 +65,14 @@ fn test_upd_product() {
         permissions_account_data.security_authority = *funding_account.key;
     }
 
-    let kvs = ["foo", "barz"];
+    let kvs = [
+        "symbol",
+        "BTC/USD",
+        "asset_type",
+        "Crypto",
+        "quote_currency",
+        "USD",
+    ];
     let size = populate_instruction(&mut instruction_data, &kvs);
     assert!(process_instruction(
         &program_id,
@@ -70,7 +88,7 @@ fn test_upd_product() {
 
     {
         let product_data = load_checked::<ProductAccount>(&product_account, PC_VERSION).unwrap();
-        assert_eq!(product_data.header.size, ProductAccount::INITIAL_SIZE + 9);
+        assert_eq!(product_data.header.size, ProductAccount::INITIAL_SIZE + 52);
     }
     // bad size on the 1st string in the key-value pair list
     instruction_data[size_of::<CommandHeader>()] = 2;
@@ -88,23 +106,21 @@ fn test_upd_product() {
     );
     assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
 
-    let kvs = [];
-    let size = populate_instruction(&mut instruction_data, &kvs);
-    assert!(process_instruction(
-        &program_id,
-        &[
-            funding_account.clone(),
-            product_account.clone(),
-            permissions_account.clone()
-        ],
-        &instruction_data[..size]
-    )
-    .is_ok());
+    // required keys are missing
+    let size = populate_instruction(&mut instruction_data, &[]);
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[
+                funding_account.clone(),
+                product_account.clone(),
+                permissions_account.clone()
+            ],
+            &instruction_data[..size]
+        ),
+        Err(OracleError::MissingProductMetadataKey.into())
+    );
     assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
-    {
-        let product_data = load_checked::<ProductAccount>(&product_account, PC_VERSION).unwrap();
-        assert_eq!(product_data.header.size, ProductAccount::INITIAL_SIZE);
-    }
 
     // uneven number of keys and values
     let bad_kvs = ["foo", "bar", "baz"];
@@ -122,9 +138,339 @@ fn test_upd_product() {
         Err(ProgramError::InvalidArgument)
     );
     assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
+
+    // extra keys are allowed and the metadata is fully overwritten
+    let kvs = [
+        "symbol",
+        "ETH/USD",
+        "asset_type",
+        "Crypto",
+        "quote_currency",
+        "USD",
+        "description",
+        "ETHEREUM / US DOLLAR",
+    ];
+    let size = populate_instruction(&mut instruction_data, &kvs);
+    assert!(process_instruction(
+        &program_id,
+        &[
+            funding_account.clone(),
+            product_account.clone(),
+            permissions_account.clone()
+        ],
+        &instruction_data[..size]
+    )
+    .is_ok());
+    assert!(account_has_key_values(&product_account, &kvs).unwrap_or(false));
+}
+
+#[test]
+fn test_upd_product_schema() {
+    let mut instruction_data = [0u8; PC_PROD_ACC_SIZE as usize];
+    let mut size;
+
+    // missing required key
+    size = populate_instruction(
+        &mut instruction_data,
+        &["symbol", "BTC/USD", "asset_type", "Crypto"],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::MissingProductMetadataKey.into())
+    );
+
+    // empty required value
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "symbol",
+            "",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::MissingProductMetadataKey.into())
+    );
+
+    // duplicate key
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+            "symbol",
+            "ETH/USD",
+        ],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::DuplicateProductMetadataKey.into())
+    );
+
+    // empty key
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "",
+            "x",
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+
+    // key too long
+    let long_key = "k".repeat(PRODUCT_METADATA_MAX_KEY_LEN + 1);
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            long_key.as_str(),
+            "x",
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+
+    // value too long
+    let long_value = "v".repeat(PRODUCT_METADATA_MAX_VALUE_LEN + 1);
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "symbol",
+            long_value.as_str(),
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ],
+    );
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+
+    // invalid UTF-8
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+        ],
+    );
+    instruction_data[size_of::<CommandHeader>() + 1] = 0xff;
+    assert_eq!(
+        validate_product_metadata(&instruction_data[..size]),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+
+    // keys can be in any order
+    size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "quote_currency",
+            "USD",
+            "asset_type",
+            "Crypto",
+            "symbol",
+            "BTC/USD",
+        ],
+    );
+    assert_eq!(validate_product_metadata(&instruction_data[..size]), Ok(()));
+}
+
+#[test]
+fn test_patch_product() {
+    let mut instruction_data = [0u8; PC_PROD_ACC_SIZE as usize];
+
+    let program_id = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+
+    let mut product_setup = AccountSetup::new::<ProductAccount>(&program_id);
+    let product_account = product_setup.as_account_info();
+
+    ProductAccount::initialize(&product_account, PC_VERSION).unwrap();
+
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+
+    {
+        let mut permissions_account_data =
+            PermissionAccount::initialize(&permissions_account, PC_VERSION).unwrap();
+        permissions_account_data.master_authority = *funding_account.key;
+        permissions_account_data.data_curation_authority = *funding_account.key;
+        permissions_account_data.security_authority = *funding_account.key;
+    }
+    let accounts = [
+        funding_account.clone(),
+        product_account.clone(),
+        permissions_account.clone(),
+    ];
+
+    let size = populate_instruction(
+        &mut instruction_data,
+        &[
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto",
+            "quote_currency",
+            "USD",
+            "description",
+            "BITCOIN / US DOLLAR",
+        ],
+    );
+    process_instruction(&program_id, &accounts, &instruction_data[..size]).unwrap();
+
+    // Values are replaced in place, new keys are added at the end and the other keys are kept
+    let size = populate_patch_instruction(
+        &mut instruction_data,
+        &["generic_symbol", "BTCUSD", "asset_type", "Crypto Spot"],
+    );
+    process_instruction(&program_id, &accounts, &instruction_data[..size]).unwrap();
+    assert!(account_has_key_values(
+        &product_account,
+        &[
+            "symbol",
+            "BTC/USD",
+            "asset_type",
+            "Crypto Spot",
+            "quote_currency",
+            "USD",
+            "description",
+            "BITCOIN / US DOLLAR",
+            "generic_symbol",
+            "BTCUSD",
+        ]
+    )
+    .unwrap());
+    assert_eq!(
+        get_product_metadata(&product_account.try_borrow_data().unwrap(), "asset_type"),
+        Ok(Some(&b"Crypto Spot"[..]))
+    );
+
+    // Empty values delete their keys, deleting a missing key does nothing
+    let size =
+        populate_patch_instruction(&mut instruction_data, &["description", "", "missing", ""]);
+    process_instruction(&program_id, &accounts, &instruction_data[..size]).unwrap();
+    let kvs = [
+        "symbol",
+        "BTC/USD",
+        "asset_type",
+        "Crypto Spot",
+        "quote_currency",
+        "USD",
+        "generic_symbol",
+        "BTCUSD",
+    ];
+    assert!(account_has_key_values(&product_account, &kvs).unwrap());
+
+    // The patched metadata must match the schema
+    let size = populate_patch_instruction(&mut instruction_data, &["symbol", ""]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data[..size]),
+        Err(OracleError::MissingProductMetadataKey.into())
+    );
+    let long_value = "v".repeat(PRODUCT_METADATA_MAX_VALUE_LEN + 1);
+    let size = populate_patch_instruction(&mut instruction_data, &["symbol", long_value.as_str()]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data[..size]),
+        Err(OracleError::InvalidProductMetadataString.into())
+    );
+    let size = populate_patch_instruction(
+        &mut instruction_data,
+        &["symbol", "ETH/USD", "symbol", "SOL/USD"],
+    );
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data[..size]),
+        Err(OracleError::DuplicateProductMetadataKey.into())
+    );
+    let size = populate_patch_instruction(&mut instruction_data, &["symbol"]);
+    assert_eq!(
+        process_instruction(&program_id, &accounts, &instruction_data[..size]),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert!(account_has_key_values(&product_account, &kvs).unwrap());
+
+    // An empty patch keeps the metadata
+    let size = populate_patch_instruction(&mut instruction_data, &[]);
+    process_instruction(&program_id, &accounts, &instruction_data[..size]).unwrap();
+    assert!(account_has_key_values(&product_account, &kvs).unwrap());
 }
 
 // Create an upd_product instruction that sets the product metadata to strings
+#[tokio::test]
+async fn test_upd_product_message() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let message_buffer = sim.create_message_buffer();
+    let kvs = [
+        "symbol",
+        "Crypto.BTC/USD",
+        "asset_type",
+        "Crypto",
+        "quote_currency",
+        "USD",
+    ];
+
+    // Without the message buffer accounts, only the product account changes
+    sim.upd_product(&product_keypair, &kvs, None).await.unwrap();
+    let buffer = sim.get_account(message_buffer).await.unwrap().data;
+    assert!(buffer.iter().all(|byte| *byte == 0));
+
+    sim.upd_product(&product_keypair, &kvs, Some(message_buffer))
+        .await
+        .unwrap();
+    let buffer = sim.get_account(message_buffer).await.unwrap().data;
+    // The `put_all` instruction is borsh encoded: discriminator, key, then the messages
+    assert_eq!(&buffer[8..40], product_keypair.pubkey().as_ref());
+    assert_eq!(u32::from_le_bytes(buffer[40..44].try_into().unwrap()), 1);
+    let message_len = u32::from_le_bytes(buffer[44..48].try_into().unwrap()) as usize;
+    let message = &buffer[48..48 + message_len];
+
+    let publish_time = i64::from_be_bytes(message[33..41].try_into().unwrap());
+    let publish_slot = u64::from_be_bytes(message[41..49].try_into().unwrap());
+    let expected = ProductMetadataMessage {
+        product_id: product_keypair.pubkey().to_bytes(),
+        publish_time,
+        publish_slot,
+        metadata: kvs
+            .chunks(2)
+            .map(|kv| (kv[0].as_bytes().to_vec(), kv[1].as_bytes().to_vec()))
+            .collect(),
+    };
+    assert_eq!(message, expected.to_bytes());
+}
+
 fn populate_instruction(instruction_data: &mut [u8], strings: &[&str]) -> usize {
     {
         let hdr = load_mut::<CommandHeader>(instruction_data).unwrap();
@@ -140,3 +486,10 @@ fn populate_instruction(instruction_data: &mut [u8], strings: &[&str]) -> usize
 
     idx
 }
+
+// Create a patch_product instruction that sets the product metadata keys to strings
+fn populate_patch_instruction(instruction_data: &mut [u8], strings: &[&str]) -> usize {
+    let size = populate_instruction(instruction_data, strings);
+    *load_mut::<CommandHeader>(instruction_data).unwrap() = OracleCommand::PatchProduct.into();
+    size
+}
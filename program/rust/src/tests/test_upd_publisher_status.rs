@@ -0,0 +1,83 @@
+use {
+    crate::{
+        c_oracle_header::{
+            PC_STATUS_AUCTION,
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_STATUS_UNKNOWN,
+        },
+        error::OracleError,
+        tests::test_utils::FeedSimulator,
+    },
+    solana_program::program_error::ProgramError,
+};
+
+#[test]
+fn test_upd_publisher_status() {
+    let mut feed = FeedSimulator::new(3);
+    feed.post_slot(&[Some((100, 1)), Some((110, 1)), Some((90, 1))]);
+
+    // Only the status and the publishing slot of the quote change
+    feed.advance_slot();
+    feed.upd_publisher_status(2, PC_STATUS_HALTED, 2).unwrap();
+    let quote = feed.price_data().comp_[2].latest_;
+    assert_eq!(quote.status_, PC_STATUS_HALTED);
+    assert_eq!(quote.publisher_status(), PC_STATUS_HALTED);
+    assert_eq!(quote.price_, 90);
+    assert_eq!(quote.conf_, 1);
+    assert_eq!(quote.pub_slot_, 2);
+
+    // The halted quote is left out of the aggregate
+    feed.publish(0, 100, 1).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.agg_.status_, PC_STATUS_TRADING);
+    assert_eq!(price_data.agg_.price_, 105);
+    assert_eq!(price_data.num_qt_, 2);
+
+    // Publishing a price resumes trading
+    feed.advance_slot();
+    feed.publish(2, 95, 1).unwrap();
+    assert_eq!(feed.price_data().num_qt_, 2);
+    feed.advance_slot();
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().num_qt_, 3);
+
+    // Only halted and auction statuses are accepted
+    for status in [PC_STATUS_UNKNOWN, PC_STATUS_TRADING, 5] {
+        assert_eq!(
+            feed.upd_publisher_status(1, status, 4),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+
+    // Publishing slots follow the rules of `UpdPrice`
+    feed.upd_publisher_status(1, PC_STATUS_AUCTION, 4).unwrap();
+    assert_eq!(
+        feed.upd_publisher_status(1, PC_STATUS_HALTED, 4),
+        Err(OracleError::StaleSubmission.into())
+    );
+    assert_eq!(
+        feed.upd_publisher_status(1, PC_STATUS_HALTED, 5),
+        Err(OracleError::PublishingSlotInFuture.into())
+    );
+}
+
+#[test]
+fn test_upd_publisher_status_keeps_publisher() {
+    let mut feed = FeedSimulator::new(3);
+    feed.post_slot(&[Some((100, 1)), Some((110, 1)), Some((90, 1))]);
+
+    // A publisher whose market is in auction isn't idle
+    feed.warp_to_slot(50);
+    feed.upd_publisher_status(1, PC_STATUS_AUCTION, 50).unwrap();
+    feed.prune_publishers(20).unwrap();
+    let price_data = feed.price_data();
+    assert_eq!(price_data.num_, 1);
+    assert_eq!(price_data.comp_[0].pub_, feed.publisher_key(1));
+    assert_eq!(price_data.comp_[0].latest_.status_, PC_STATUS_AUCTION);
+
+    assert_eq!(
+        feed.upd_publisher_status(0, PC_STATUS_HALTED, 50),
+        Err(OracleError::PublisherNotFound.into())
+    );
+}
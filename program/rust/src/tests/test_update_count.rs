@@ -0,0 +1,37 @@
+use crate::{
+    accounts::UpdateCount,
+    error::OracleError,
+    tests::test_utils::FeedSimulator,
+};
+
+#[test]
+fn test_update_count() {
+    let mut feed = FeedSimulator::new(2).with_extension::<UpdateCount>();
+
+    // Nothing is counted until the counting is enabled
+    feed.warp_to_slot(10);
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.extension::<UpdateCount>().updates, 0);
+
+    feed.set_update_counting(true).unwrap();
+    assert_eq!(feed.extension::<UpdateCount>().enabled, 1);
+    feed.publish(1, 100, 1).unwrap();
+    feed.warp_to_slot(11);
+    feed.publish(0, 101, 1).unwrap();
+    assert_eq!(feed.extension::<UpdateCount>().updates, 2);
+
+    // Rejected updates aren't counted
+    assert_eq!(
+        feed.publish(0, 102, 1),
+        Err(OracleError::StaleSubmission.into())
+    );
+    assert_eq!(feed.extension::<UpdateCount>().updates, 2);
+
+    // Stopping keeps the count
+    feed.set_update_counting(false).unwrap();
+    feed.warp_to_slot(12);
+    feed.publish(1, 102, 1).unwrap();
+    let update_count = feed.extension::<UpdateCount>();
+    assert_eq!(update_count.enabled, 0);
+    assert_eq!(update_count.updates, 2);
+}
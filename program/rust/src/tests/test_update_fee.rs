@@ -0,0 +1,164 @@
+use {
+    crate::{
+        accounts::{
+            get_extension,
+            FeeVaultAccount,
+            PriceAccount,
+            UpdateFee,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                copy_keypair,
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::{
+        instruction::InstructionError,
+        native_token::LAMPORTS_PER_SOL,
+        rent::Rent,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+        transaction::TransactionError,
+    },
+    std::mem::size_of,
+};
+
+#[tokio::test]
+async fn test_update_fee() {
+    let mut sim = PythSimulator::new().await;
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    let publisher = Keypair::new();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    sim.airdrop(&publisher.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let quote = || Quote {
+        price:      100,
+        confidence: 1,
+        status:     PC_STATUS_TRADING,
+    };
+    let fee_vault = sim.get_fee_vault_pubkey();
+    let vault_rent = Rent::default().minimum_balance(size_of::<FeeVaultAccount>());
+
+    // Feeds are free by default
+    sim.upd_price(&publisher, price, quote()).await.unwrap();
+    assert!(sim.get_account(fee_vault).await.is_none());
+
+    // The first fee creates the fee vault
+    sim.set_update_fee(&price_keypair, 5_000).await.unwrap();
+    let price_account = sim.get_account(price).await.unwrap();
+    assert_eq!(
+        get_extension::<UpdateFee>(&price_account.data)
+            .unwrap()
+            .unwrap()
+            .lamports,
+        5_000
+    );
+    assert_eq!(
+        sim.get_account(fee_vault).await.unwrap().lamports,
+        vault_rent
+    );
+
+    // The quotes that don't pay are rejected
+    sim.warp_to_slot(10).await.unwrap();
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateFeeNotPaid.into()
+    );
+
+    let publisher_lamports = sim.get_account(publisher.pubkey()).await.unwrap().lamports;
+    sim.upd_price_with_update_fee(&publisher, price, quote())
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 10);
+    // The publisher pays the signature on top of the fee
+    assert!(
+        sim.get_account(publisher.pubkey()).await.unwrap().lamports < publisher_lamports - 5_000
+    );
+    assert_eq!(
+        sim.get_account(fee_vault).await.unwrap().lamports,
+        vault_rent + 5_000
+    );
+    let vault_data = sim
+        .get_account_data_as::<FeeVaultAccount>(fee_vault)
+        .await
+        .unwrap();
+    assert_eq!(vault_data.total_collected, 5_000);
+    assert_eq!(vault_data.total_withdrawn, 0);
+
+    // Only the master authority withdraws, and never the rent exemption of the vault
+    let other = Keypair::new();
+    sim.airdrop(&other.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.withdraw_fees(other.pubkey(), 1_000, &other)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::PermissionViolation.into()
+    );
+    let authority = copy_keypair(&sim.genesis_keypair);
+    assert_eq!(
+        sim.withdraw_fees(authority.pubkey(), 5_001, &authority)
+            .await
+            .unwrap_err()
+            .unwrap(),
+        TransactionError::InstructionError(0, InstructionError::InsufficientFunds)
+    );
+    sim.withdraw_fees(other.pubkey(), 3_000, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        sim.get_account(other.pubkey()).await.unwrap().lamports,
+        LAMPORTS_PER_SOL + 3_000
+    );
+    assert_eq!(
+        sim.get_account(fee_vault).await.unwrap().lamports,
+        vault_rent + 2_000
+    );
+    let vault_data = sim
+        .get_account_data_as::<FeeVaultAccount>(fee_vault)
+        .await
+        .unwrap();
+    assert_eq!(vault_data.total_collected, 5_000);
+    assert_eq!(vault_data.total_withdrawn, 3_000);
+
+    // A fee of 0 lets the quotes through for free again
+    sim.set_update_fee(&price_keypair, 0).await.unwrap();
+    sim.warp_to_slot(11).await.unwrap();
+    sim.upd_price(&publisher, price, quote()).await.unwrap();
+}
+
+#[test]
+fn test_update_fee_invalid_extension() {
+    // An invalid extension region fails the update rather than waiving the fee
+    let mut feed = FeedSimulator::new(1)
+        .with_extension::<UpdateFee>()
+        .with_corrupt_extensions();
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::InvalidExtension.into())
+    );
+    assert_eq!(feed.price_data().comp_[0].latest_.price_, 0);
+}
@@ -0,0 +1,142 @@
+use {
+    crate::{
+        accounts::{
+            PriceAccount,
+            UpdaterProgram,
+        },
+        c_oracle_header::PC_STATUS_TRADING,
+        error::OracleError,
+        tests::{
+            pyth_simulator::{
+                PythSimulator,
+                Quote,
+            },
+            test_utils::FeedSimulator,
+        },
+    },
+    solana_program::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+    solana_sdk::{
+        signature::Keypair,
+        signer::Signer,
+    },
+};
+
+#[test]
+fn test_updater_program_unit() {
+    let updater_program_id = Pubkey::new_unique();
+    let mut feed = FeedSimulator::new(1).with_extension::<UpdaterProgram>();
+
+    // The oracle can't be its own updater program
+    assert_eq!(
+        feed.set_updater_program(feed.program_id()),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    feed.set_updater_program(updater_program_id).unwrap();
+    assert_eq!(
+        feed.extension::<UpdaterProgram>().program_id,
+        updater_program_id
+    );
+
+    // Quotes sent without the instructions sysvar can't be checked
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::UpdateNotForwarded.into())
+    );
+    assert_eq!(feed.price_data().comp_[0].latest_.price_, 0);
+
+    // The default key accepts the quotes of any instruction again
+    feed.set_updater_program(Pubkey::default()).unwrap();
+    feed.publish(0, 100, 1).unwrap();
+    assert_eq!(feed.price_data().comp_[0].latest_.price_, 100);
+}
+
+#[test]
+fn test_updater_program_invalid_extension() {
+    // An invalid extension region fails the update rather than accepting any instruction
+    let mut feed = FeedSimulator::new(1)
+        .with_extension::<UpdaterProgram>()
+        .with_corrupt_extensions();
+    feed.warp_to_slot(10);
+    assert_eq!(
+        feed.publish(0, 100, 1),
+        Err(OracleError::InvalidExtension.into())
+    );
+}
+
+#[tokio::test]
+async fn test_updater_program() {
+    let mut sim = PythSimulator::new().await;
+    let publisher = Keypair::new();
+    let mapping_keypair = sim.init_mapping().await.unwrap();
+    let product_keypair = sim.add_product(&mapping_keypair).await.unwrap();
+    let price_keypair = sim.add_price(&product_keypair, -8).await.unwrap();
+    let price = price_keypair.pubkey();
+    sim.add_publisher(&price_keypair, publisher.pubkey())
+        .await
+        .unwrap();
+    let quote = || Quote {
+        price:      100,
+        confidence: 1,
+        status:     PC_STATUS_TRADING,
+    };
+
+    // Feeds accept forwarded quotes without an updater program
+    sim.warp_to_slot(10).await.unwrap();
+    sim.upd_price_through_updater_program(&publisher, price, quote())
+        .await
+        .unwrap();
+
+    sim.set_updater_program(&price_keypair, sim.updater_program_id)
+        .await
+        .unwrap();
+
+    // Quotes sent to the oracle directly are rejected, with or without the instructions sysvar
+    sim.warp_to_slot(20).await.unwrap();
+    assert_eq!(
+        sim.upd_price(&publisher, price, quote())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateNotForwarded.into()
+    );
+    assert_eq!(
+        sim.upd_price_with_instructions_sysvar(&publisher, price, quote(), vec![], vec![])
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateNotForwarded.into()
+    );
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 10);
+
+    // Quotes forwarded by the updater program are accepted, signed by the publisher as usual
+    sim.upd_price_through_updater_program(&publisher, price, quote())
+        .await
+        .unwrap();
+    let price_data = sim
+        .get_account_data_as::<PriceAccount>(price)
+        .await
+        .unwrap();
+    assert_eq!(price_data.comp_[0].latest_.pub_slot_, 20);
+
+    // Another updater program takes its place
+    sim.set_updater_program(&price_keypair, Pubkey::new_unique())
+        .await
+        .unwrap();
+    sim.warp_to_slot(30).await.unwrap();
+    assert_eq!(
+        sim.upd_price_through_updater_program(&publisher, price, quote())
+            .await
+            .unwrap_err()
+            .unwrap(),
+        OracleError::UpdateNotForwarded.into()
+    );
+}
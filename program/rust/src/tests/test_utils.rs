@@ -1,24 +1,113 @@
 use {
     crate::{
         accounts::{
+            data_len_with_extension,
+            get_extension,
+            init_extension,
+            AggregationConfigAccount,
+            AverageMode,
+            ConsistencyKind,
+            ExtensionHeader,
+            FeatureGateAccount,
+            FeatureSwitch,
+            FeedKind,
             PermissionAccount,
+            PriceAccount,
+            PriceAccountExtension,
+            PriceAccountFlags,
+            PriceInfo,
+            ProgramdataAccount,
+            PublisherLink,
+            PublisherLinkageAccount,
+            PublisherRegistryAccount,
             PythAccount,
+            SharedSettings,
+            AGGREGATION_CONFIG_SEED,
+            EXTENSIONS_OFFSET,
+            FEATURE_GATE_SEED,
             PERMISSIONS_SEED,
+            PUBLISHER_LINKAGE_SEED,
+            PUBLISHER_REGISTRY_SEED,
+        },
+        c_oracle_header::{
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::{
+            load_checked,
+            load_mut,
         },
-        c_oracle_header::PC_VERSION,
         error::OracleError,
         instruction::{
+            AddPublisherArgs,
+            AggregationCountsSummary,
+            AssertPriceConditionArgs,
             CommandHeader,
+            InitConsistencyCheckArgs,
             OracleCommand,
+            PriceExtremaSummary,
+            PriceSummary,
+            PrunePublishersArgs,
+            SetAggregateCacheArgs,
+            SetAggregationCountWindowArgs,
+            SetAggregationDelayArgs,
+            SetAuxDataArgs,
+            SetAverageModeArgs,
+            SetChecksumArgs,
+            SetConfFloorArgs,
+            SetLatencyStatsWindowArgs,
+            SetPriceExtremaWindowArgs,
+            SetPublishThresholdArgs,
+            SetPublisherCapArgs,
+            SetPublisherDemotionArgs,
+            SetPublisherRewardsArgs,
+            SetPublisherSamplingArgs,
+            SetPublisherTimestampsArgs,
+            SetPublisherWeightCapArgs,
+            SetQuoteConversionArgs,
+            SetQuoteOrderArgs,
+            SetRateLimitArgs,
+            SetSanityProgramArgs,
+            SetSequenceTrackingArgs,
+            SetSharedSettingsArgs,
+            SetStakeProgramArgs,
+            SetStatusObserverArgs,
+            SetStatusPolicyArgs,
+            SetStatusReasonTrackingArgs,
+            SetUpdateCountingArgs,
+            SetUpdaterProgramArgs,
+            SimulatedAggregate,
+            UpdAuxDataArgs,
+            UpdPriceArgs,
+            UpdPriceCompactArgs,
+            UpdPublisherStatusArgs,
+        },
+        processor::{
+            get_aggregation_counts_summary,
+            get_price_extrema_summary,
+            get_price_summary,
+            process_instruction,
+            simulate_aggregation,
         },
     },
+    bytemuck::{
+        bytes_of,
+        cast_slice,
+    },
     num_traits::ToPrimitive,
+    serde::{
+        Deserialize,
+        Serialize,
+    },
     solana_program::{
         account_info::AccountInfo,
+        bpf_loader_upgradeable,
         clock::{
             self,
             Epoch,
         },
+        entrypoint::ProgramResult,
+        hash::Hash,
         instruction::InstructionError,
         native_token::LAMPORTS_PER_SOL,
         program_error::ProgramError,
@@ -32,6 +121,12 @@ use {
         },
     },
     solana_sdk::transaction::TransactionError,
+    std::{
+        fs::File,
+        mem::size_of,
+        path::Path,
+        str::FromStr,
+    },
 };
 
 const UPPER_BOUND_OF_ALL_ACCOUNT_SIZES: usize = 160056;
@@ -69,6 +164,15 @@ impl AccountSetup {
         }
     }
 
+    /// Same as `new` but the account holds `size` bytes of data, e.g. to leave room for
+    /// price account extensions.
+    pub fn new_with_size<T: PythAccount>(owner: &Pubkey, size: usize) -> Self {
+        let mut setup = Self::new::<T>(owner);
+        setup.balance = Rent::minimum_balance(&Rent::default(), size);
+        setup.size = size;
+        setup
+    }
+
     pub fn new_funding() -> Self {
         let key = Pubkey::new_unique();
         let owner = system_program::id();
@@ -84,6 +188,14 @@ impl AccountSetup {
         }
     }
 
+    /// Same as `new_funding` but with a given key, e.g. one that instruction data refers to.
+    pub fn new_funding_with_key(key: Pubkey) -> Self {
+        AccountSetup {
+            key,
+            ..Self::new_funding()
+        }
+    }
+
     pub fn new_permission(owner: &Pubkey) -> Self {
         let (key, _bump) = Pubkey::find_program_address(&[PERMISSIONS_SEED.as_bytes()], owner);
         let owner = *owner;
@@ -99,6 +211,136 @@ impl AccountSetup {
         }
     }
 
+    /// Same as `new_permission` but recording that the publisher registry exists.
+    pub fn new_permission_with_publisher_registry(owner: &Pubkey) -> Self {
+        let size = PermissionAccount::PUBLISHER_REGISTRY_ACCOUNT_SPACE;
+        let mut setup = AccountSetup {
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            ..Self::new_permission(owner)
+        };
+        setup.data[PermissionAccount::PUBLISHER_REGISTRY_OFFSET] = 1;
+        setup
+    }
+
+    /// The publisher registry of the program `owner`, approving `publishers`.
+    pub fn new_publisher_registry(owner: &Pubkey, publishers: &[Pubkey]) -> Self {
+        let (key, _bump) =
+            Pubkey::find_program_address(&[PUBLISHER_REGISTRY_SEED.as_bytes()], owner);
+        let size = PublisherRegistryAccount::entry_offset(publishers.len());
+        let mut setup = AccountSetup {
+            key,
+            owner: *owner,
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data: vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES],
+        };
+        {
+            let account = setup.as_account_info();
+            let mut registry_data =
+                PublisherRegistryAccount::initialize(&account, PC_VERSION).unwrap();
+            registry_data.num_publishers = publishers.len() as u32;
+            registry_data.header.size = size as u32;
+        }
+        setup.data[PublisherRegistryAccount::MINIMUM_SIZE..size]
+            .copy_from_slice(cast_slice(publishers));
+        setup
+    }
+
+    /// The publisher linkage account of the program `owner`, listing `links`.
+    pub fn new_publisher_linkage(owner: &Pubkey, links: &[PublisherLink]) -> Self {
+        let (key, _bump) =
+            Pubkey::find_program_address(&[PUBLISHER_LINKAGE_SEED.as_bytes()], owner);
+        let size = PublisherLinkageAccount::entry_offset(links.len());
+        let mut setup = AccountSetup {
+            key,
+            owner: *owner,
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data: vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES],
+        };
+        {
+            let account = setup.as_account_info();
+            let mut linkage_data =
+                PublisherLinkageAccount::initialize(&account, PC_VERSION).unwrap();
+            linkage_data.num_links = links.len() as u32;
+            linkage_data.header.size = size as u32;
+        }
+        setup.data[PublisherLinkageAccount::MINIMUM_SIZE..size].copy_from_slice(cast_slice(links));
+        setup
+    }
+
+    /// The feature gate of the program `owner`, listing `switches`.
+    pub fn new_feature_gate(owner: &Pubkey, switches: &[FeatureSwitch]) -> Self {
+        let (key, _bump) = Pubkey::find_program_address(&[FEATURE_GATE_SEED.as_bytes()], owner);
+        let size = FeatureGateAccount::entry_offset(switches.len());
+        let mut setup = AccountSetup {
+            key,
+            owner: *owner,
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data: vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES],
+        };
+        {
+            let account = setup.as_account_info();
+            let mut feature_gate_data =
+                FeatureGateAccount::initialize(&account, PC_VERSION).unwrap();
+            feature_gate_data.num_features = switches.len() as u32;
+            feature_gate_data.header.size = size as u32;
+        }
+        setup.data[FeatureGateAccount::MINIMUM_SIZE..size].copy_from_slice(cast_slice(switches));
+        setup
+    }
+
+    /// The aggregation config of the program `owner`, with the given defaults.
+    pub fn new_aggregation_config(
+        owner: &Pubkey,
+        max_ci_divisor: i64,
+        max_latency: u8,
+        min_pub: u8,
+    ) -> Self {
+        let (key, _bump) =
+            Pubkey::find_program_address(&[AGGREGATION_CONFIG_SEED.as_bytes()], owner);
+        let size = size_of::<AggregationConfigAccount>();
+        let mut setup = AccountSetup {
+            key,
+            owner: *owner,
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data: vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES],
+        };
+        {
+            let account = setup.as_account_info();
+            let mut config_data =
+                AggregationConfigAccount::initialize(&account, PC_VERSION).unwrap();
+            config_data.max_ci_divisor = max_ci_divisor;
+            config_data.max_latency = max_latency;
+            config_data.min_pub = min_pub;
+        }
+        setup
+    }
+
+    /// The programdata account of the upgradeable program `program_id`, with `upgrade_authority`.
+    pub fn new_programdata(program_id: &Pubkey, upgrade_authority: &Pubkey) -> Self {
+        let size = size_of::<ProgramdataAccount>();
+        let mut setup = AccountSetup {
+            key: ProgramdataAccount::address(program_id),
+            owner: bpf_loader_upgradeable::id(),
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data: vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES],
+        };
+        let programdata = ProgramdataAccount {
+            account_type:          ProgramdataAccount::ACCOUNT_TYPE,
+            slot:                  [0; 2],
+            has_upgrade_authority: 1,
+            upgrade_authority:     *upgrade_authority,
+            unused:                [0; 3],
+        };
+        setup.data[..size].copy_from_slice(bytes_of(&programdata));
+        setup
+    }
+
     pub fn new_clock() -> Self {
         let key = clock::Clock::id();
         let owner = sysvar::id();
@@ -114,6 +356,71 @@ impl AccountSetup {
         }
     }
 
+    /// The slot hashes sysvar, holding `slot_hashes` from the most recent.
+    pub fn new_slot_hashes(slot_hashes: &[(u64, Hash)]) -> Self {
+        let mut data = vec![0u8; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES];
+        data[..8].copy_from_slice(&(slot_hashes.len() as u64).to_le_bytes());
+        for (i, (slot, hash)) in slot_hashes.iter().enumerate() {
+            let offset = 8 + i * 40;
+            data[offset..offset + 8].copy_from_slice(&slot.to_le_bytes());
+            data[offset + 8..offset + 40].copy_from_slice(hash.as_ref());
+        }
+        let size = 8 + slot_hashes.len() * 40;
+        AccountSetup {
+            key: sysvar::slot_hashes::id(),
+            owner: sysvar::id(),
+            balance: Rent::minimum_balance(&Rent::default(), size),
+            size,
+            data,
+        }
+    }
+
+    /// Capture the current state of the account, see `AccountFixture`.
+    pub fn to_fixture(&self, description: &str) -> AccountFixture {
+        let data = &self.data[..self.size];
+        let end = data
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |i| i + 1);
+        AccountFixture {
+            description: description.to_string(),
+            key:         self.key.to_string(),
+            owner:       self.owner.to_string(),
+            lamports:    self.balance,
+            size:        self.size,
+            data:        hex::encode(&data[..end]),
+        }
+    }
+
+    pub fn from_fixture(fixture: &AccountFixture) -> Self {
+        let bytes = hex::decode(&fixture.data).expect("Invalid fixture data");
+        assert!(bytes.len() <= fixture.size && fixture.size <= UPPER_BOUND_OF_ALL_ACCOUNT_SIZES);
+        let mut data = vec![0; UPPER_BOUND_OF_ALL_ACCOUNT_SIZES];
+        data[..bytes.len()].copy_from_slice(&bytes);
+        AccountSetup {
+            key: Pubkey::from_str(&fixture.key).expect("Invalid fixture key"),
+            owner: Pubkey::from_str(&fixture.owner).expect("Invalid fixture owner"),
+            balance: fixture.lamports,
+            size: fixture.size,
+            data,
+        }
+    }
+
+    /// Dump the account to the fixture file at `path`, to be checked in once the state it pins
+    /// has been reviewed.
+    pub fn save_fixture(&self, path: &Path, description: &str) {
+        let file = File::create(path).expect("Unable to create fixture file");
+        serde_json::to_writer_pretty(file, &self.to_fixture(description))
+            .expect("Unable to write fixture");
+    }
+
+    /// Rebuild an account from the fixture file at `path`.
+    pub fn load_fixture(path: &Path) -> Self {
+        let file = File::open(path).expect("Fixture file not found");
+        let fixture: AccountFixture = serde_json::from_reader(file).expect("Unable to parse JSON");
+        Self::from_fixture(&fixture)
+    }
+
     pub fn as_account_info(&mut self) -> AccountInfo {
         AccountInfo::new(
             &self.key,
@@ -128,10 +435,1250 @@ impl AccountSetup {
     }
 }
 
-pub fn update_clock_slot(clock_account: &mut AccountInfo, slot: u64) {
-    let mut clock_data = clock::Clock::from_account_info(clock_account).unwrap();
-    clock_data.slot = slot;
-    clock_data.to_account_info(clock_account);
+/// Raw state of an account, stored as JSON under `test_data/accounts` to pin the exact bytes of
+/// accounts written by previous releases. A fixture should never change once added.
+#[derive(Serialize, Deserialize)]
+pub struct AccountFixture {
+    /// Where the account comes from, e.g. the release that wrote it
+    pub description: String,
+    pub key:         String,
+    pub owner:       String,
+    pub lamports:    u64,
+    pub size:        usize,
+    /// Hex encoded data, without its trailing zero bytes
+    pub data:        String,
+}
+
+/// Clock sysvar of the unit tests. Every change is written to the clock account right away, so
+/// that the instructions passed `account()` see the slot and timestamp of the scenario, which
+/// only move when the test moves them.
+pub struct TestClock<'a> {
+    account: AccountInfo<'a>,
+}
+
+impl<'a> TestClock<'a> {
+    /// The clock held by `account`, usually `AccountSetup::new_clock`. The account is passed to
+    /// the instructions as a read-only sysvar.
+    pub fn new(mut account: AccountInfo<'a>) -> Self {
+        account.is_signer = false;
+        account.is_writable = false;
+        TestClock { account }
+    }
+
+    /// The clock account, to pass to the instructions.
+    pub fn account(&self) -> AccountInfo<'a> {
+        self.account.clone()
+    }
+
+    pub fn slot(&self) -> u64 {
+        clock::Clock::from_account_info(&self.account).unwrap().slot
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.update(|clock| clock.slot = slot);
+    }
+
+    /// Move the clock `slots` slots forward, returning the new slot. The timestamp is left as it
+    /// is.
+    pub fn advance_slots(&mut self, slots: u64) -> u64 {
+        let slot = self.slot() + slots;
+        self.warp_to_slot(slot);
+        slot
+    }
+
+    pub fn set_timestamp(&mut self, timestamp: i64) {
+        self.update(|clock| clock.unix_timestamp = timestamp);
+    }
+
+    pub fn set_epoch(&mut self, epoch: u64) {
+        self.update(|clock| clock.epoch = epoch);
+    }
+
+    fn update(&mut self, update: impl FnOnce(&mut clock::Clock)) {
+        let mut clock_data = clock::Clock::from_account_info(&self.account).unwrap();
+        update(&mut clock_data);
+        clock_data.to_account_info(&mut self.account);
+    }
+}
+
+/// A price feed with several publishers, to write multi-publisher scenarios without setting up
+/// every account by hand. Every call to `post_slot` moves the clock to the next slot, publishes
+/// the given quotes and records the resulting aggregate, which can then be checked with the
+/// `assert_*` methods.
+///
+/// Aggregation is triggered by the first update of a slot, so the aggregate recorded after a slot
+/// is computed from the quotes of the previous slots.
+pub struct FeedSimulator {
+    program_id:         Pubkey,
+    funding:            AccountSetup,
+    price:              AccountSetup,
+    permissions:        AccountSetup,
+    clock:              AccountSetup,
+    publishers:         Vec<AccountSetup>,
+    slot:               u64,
+    aggregates:         Vec<PriceInfo>,
+    /// Passed to `UpdPrice` after its accounts if set
+    sanity:             Option<AccountSetup>,
+    /// Passed to `UpdPrice` after its accounts and the sanity program if set
+    observer:           Option<AccountSetup>,
+    /// Passed to `UpdPrice` after its accounts and the programs if set
+    aggregation_config: Option<AccountSetup>,
+    /// Passed to `UpdPrice` after the aggregation config if set
+    feature_gate:       Option<AccountSetup>,
+    /// Passed to `UpdPrice` after the feature gate if set
+    slot_hashes:        Option<AccountSetup>,
+}
+
+impl FeedSimulator {
+    pub fn new(num_publishers: usize) -> Self {
+        Self::new_with_program_id(Pubkey::new_unique(), num_publishers)
+    }
+
+    /// Another feed of the same program, e.g. the conversion feed of a `QuoteConversion`.
+    pub fn new_sibling(&self, num_publishers: usize) -> Self {
+        Self::new_with_program_id(self.program_id, num_publishers)
+    }
+
+    fn new_with_program_id(program_id: Pubkey, num_publishers: usize) -> Self {
+        let mut funding = AccountSetup::new_funding();
+        let mut price = AccountSetup::new::<PriceAccount>(&program_id);
+        let mut permissions = AccountSetup::new_permission(&program_id);
+
+        PriceAccount::initialize(&price.as_account_info(), PC_VERSION).unwrap();
+        PermissionAccount::initialize(&permissions.as_account_info(), PC_VERSION)
+            .unwrap()
+            .master_authority = *funding.as_account_info().key;
+
+        let mut feed = FeedSimulator {
+            program_id,
+            funding,
+            price,
+            permissions,
+            clock: AccountSetup::new_clock(),
+            publishers: Vec::new(),
+            slot: 0,
+            aggregates: Vec::new(),
+            sanity: None,
+            observer: None,
+            aggregation_config: None,
+            feature_gate: None,
+            slot_hashes: None,
+        };
+        for _ in 0..num_publishers {
+            let publisher = AccountSetup::new_funding();
+            feed.add_publisher(publisher.key);
+            feed.publishers.push(publisher);
+        }
+        feed
+    }
+
+    pub fn with_exponent(mut self, exponent: i32) -> Self {
+        self.price_data_mut(|price_data| price_data.exponent = exponent);
+        self
+    }
+
+    pub fn with_min_pub(mut self, min_pub: u8) -> Self {
+        self.price_data_mut(|price_data| price_data.min_pub_ = min_pub);
+        self
+    }
+
+    pub fn with_feed_kind(mut self, feed_kind: FeedKind) -> Self {
+        self.price_data_mut(|price_data| price_data.price_type = feed_kind as u32);
+        self
+    }
+
+    /// Grow the price account and add an extension of type `T` to it.
+    pub fn with_extension<T: PriceAccountExtension>(mut self) -> Self {
+        let size = data_len_with_extension::<T>(self.price_account_data()).unwrap();
+        self.price.balance = Rent::minimum_balance(&Rent::default(), size);
+        self.price.size = size;
+        init_extension::<T>(&mut self.price.data[..size]).unwrap();
+        self
+    }
+
+    /// Break the length of the first extension of the price account, so that reading it fails
+    /// with `InvalidExtension`.
+    pub fn with_corrupt_extensions(mut self) -> Self {
+        let header_end = EXTENSIONS_OFFSET + size_of::<ExtensionHeader>();
+        bytemuck::from_bytes_mut::<ExtensionHeader>(
+            &mut self.price.data[EXTENSIONS_OFFSET..header_end],
+        )
+        .length += 1;
+        self
+    }
+
+    /// Make the feed follow the `SharedSettings`, passing an aggregation config keeping the
+    /// defaults of the program and a feature gate without switches to every following `UpdPrice`.
+    pub fn follow_shared_settings(self) -> Self {
+        let mut feed = self.with_extension::<SharedSettings>();
+        feed.set_shared_settings(true).unwrap();
+        feed.pass_aggregation_config(0, 0, 0);
+        feed.pass_feature_gate(&[]);
+        feed
+    }
+
+    /// Send `AddPublisher` for `publisher`, e.g. one of the magic keys toggling price account
+    /// flags.
+    pub fn add_publisher(&mut self, publisher: Pubkey) {
+        self.try_add_publisher(publisher).unwrap();
+    }
+
+    /// Send `AddPublisher` for `publisher`, returning its result.
+    pub fn try_add_publisher(&mut self, publisher: Pubkey) -> ProgramResult {
+        let args = AddPublisherArgs {
+            header: OracleCommand::AddPublisher.into(),
+            publisher,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Add a publisher with key `key` to the feed, returning the index that `publish` and the
+    /// other methods take for it. The components are sorted by key, so the components of the
+    /// publishers already listed can move.
+    pub fn add_new_publisher(&mut self, key: Pubkey) -> usize {
+        self.add_publisher(key);
+        self.publishers
+            .push(AccountSetup::new_funding_with_key(key));
+        self.publishers.len() - 1
+    }
+
+    /// Send `SetRateLimit`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn set_rate_limit(&mut self, window: u64) -> ProgramResult {
+        let args = SetRateLimitArgs {
+            header: OracleCommand::SetRateLimit.into(),
+            window,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetAggregationDelay`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_aggregation_delay(&mut self, slots: u64) -> ProgramResult {
+        let args = SetAggregationDelayArgs {
+            header: OracleCommand::SetAggregationDelay.into(),
+            slots,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetAggregationCountWindow`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_aggregation_count_window(&mut self, window_slots: u64) -> ProgramResult {
+        let args = SetAggregationCountWindowArgs {
+            header: OracleCommand::SetAggregationCountWindow.into(),
+            window_slots,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetLatencyStatsWindow`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_latency_stats_window(&mut self, window: u64) -> ProgramResult {
+        let args = SetLatencyStatsWindowArgs {
+            header: OracleCommand::SetLatencyStatsWindow.into(),
+            window,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPriceExtremaWindow`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_price_extrema_window(&mut self, window_slots: u64) -> ProgramResult {
+        let args = SetPriceExtremaWindowArgs {
+            header: OracleCommand::SetPriceExtremaWindow.into(),
+            window_slots,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetConfFloor`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn set_conf_floor(&mut self, min_conf: u64, min_conf_bps: u64) -> ProgramResult {
+        let args = SetConfFloorArgs {
+            header: OracleCommand::SetConfFloor.into(),
+            min_conf,
+            min_conf_bps,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPublisherCap`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publisher_cap(&mut self, max_publishers: u32) -> ProgramResult {
+        let args = SetPublisherCapArgs {
+            header: OracleCommand::SetPublisherCap.into(),
+            max_publishers,
+            unused_: 0,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetQuoteConversion`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_quote_conversion(
+        &mut self,
+        conversion_feed: Pubkey,
+        exponent: i32,
+        divide: bool,
+    ) -> ProgramResult {
+        let args = SetQuoteConversionArgs {
+            header: OracleCommand::SetQuoteConversion.into(),
+            conversion_feed,
+            exponent,
+            divide: divide as u8,
+            unused_: [0; 3],
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `ConvertPrice` with the latest aggregate of `conversion`.
+    pub fn convert_price(&mut self, conversion: &mut FeedSimulator) -> ProgramResult {
+        let header: CommandHeader = OracleCommand::ConvertPrice.into();
+        let mut conversion_account = conversion.price.as_account_info();
+        conversion_account.is_signer = false;
+        conversion_account.is_writable = false;
+        let mut price_account = self.price.as_account_info();
+        price_account.is_signer = false;
+        process_instruction(
+            &self.program_id,
+            &[price_account, conversion_account],
+            bytes_of(&header),
+        )
+    }
+
+    /// Send `InitConsistencyCheck` from the funding account of the first feed, linking `feeds` in
+    /// order. The price accounts don't grow in unit tests, use `with_extension` to make room for
+    /// the `ConsistencyFlag` first.
+    pub fn init_consistency_check(
+        feeds: &mut [&mut FeedSimulator],
+        check: &mut AccountSetup,
+        kind: ConsistencyKind,
+        tolerance_bps: u32,
+    ) -> ProgramResult {
+        let args = InitConsistencyCheckArgs {
+            header: OracleCommand::InitConsistencyCheck.into(),
+            kind: kind as u32,
+            tolerance_bps,
+        };
+        let (first, others) = feeds.split_first_mut().unwrap();
+        let mut accounts = vec![
+            first.funding.as_account_info(),
+            check.as_account_info(),
+            first.permissions.as_account_info(),
+            first.price.as_account_info(),
+        ];
+        accounts.extend(others.iter_mut().map(|feed| feed.price.as_account_info()));
+        process_instruction(&first.program_id, &accounts, bytes_of(&args))
+    }
+
+    /// Send `CheckConsistency` with the clock of the first feed.
+    pub fn check_consistency(
+        feeds: &mut [&mut FeedSimulator],
+        check: &mut AccountSetup,
+    ) -> ProgramResult {
+        let header: CommandHeader = OracleCommand::CheckConsistency.into();
+        let (first, others) = feeds.split_first_mut().unwrap();
+        let mut accounts = vec![
+            check.as_account_info(),
+            first.clock.as_account_info(),
+            first.price.as_account_info(),
+        ];
+        accounts.extend(others.iter_mut().map(|feed| feed.price.as_account_info()));
+        for account in &mut accounts {
+            account.is_signer = false;
+        }
+        process_instruction(&first.program_id, &accounts, bytes_of(&header))
+    }
+
+    /// Send `SetPublisherSampling`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publisher_sampling(&mut self, sample_size: u32) -> ProgramResult {
+        let args = SetPublisherSamplingArgs {
+            header: OracleCommand::SetPublisherSampling.into(),
+            sample_size,
+            unused_: 0,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPublisherRewards`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publisher_rewards(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetPublisherRewardsArgs {
+            header:  OracleCommand::SetPublisherRewards.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetAggregateCache`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_aggregate_cache(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetAggregateCacheArgs {
+            header:  OracleCommand::SetAggregateCache.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetSharedSettings`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_shared_settings(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetSharedSettingsArgs {
+            header:  OracleCommand::SetSharedSettings.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetSanityProgram`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_sanity_program(&mut self, program_id: Pubkey) -> ProgramResult {
+        let args = SetSanityProgramArgs {
+            header: OracleCommand::SetSanityProgram.into(),
+            program_id,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetStatusObserver`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_status_observer(&mut self, program_id: Pubkey) -> ProgramResult {
+        let args = SetStatusObserverArgs {
+            header: OracleCommand::SetStatusObserver.into(),
+            program_id,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetChecksum`. The price account doesn't grow in unit tests, use `with_extension` to
+    /// make room for the extension first.
+    pub fn set_checksum(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetChecksumArgs {
+            header:  OracleCommand::SetChecksum.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetSequenceTracking`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_sequence_tracking(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetSequenceTrackingArgs {
+            header:  OracleCommand::SetSequenceTracking.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetStatusPolicy`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn set_status_policy(
+        &mut self,
+        halted_quorum: u8,
+        auction_quorum: u8,
+        override_trading: bool,
+    ) -> ProgramResult {
+        let args = SetStatusPolicyArgs {
+            header: OracleCommand::SetStatusPolicy.into(),
+            halted_quorum,
+            auction_quorum,
+            override_trading: u8::from(override_trading),
+            unused_: [0; 5],
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPublisherDemotion`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publisher_demotion(&mut self, max_missed: u32) -> ProgramResult {
+        let args = SetPublisherDemotionArgs {
+            header: OracleCommand::SetPublisherDemotion.into(),
+            max_missed,
+            unused_: 0,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetUpdaterProgram`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_updater_program(&mut self, program_id: Pubkey) -> ProgramResult {
+        let args = SetUpdaterProgramArgs {
+            header: OracleCommand::SetUpdaterProgram.into(),
+            program_id,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetAuxData`. The price account doesn't grow in unit tests, use `with_extension` to
+    /// make room for the extension first.
+    pub fn set_aux_data(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetAuxDataArgs {
+            header:  OracleCommand::SetAuxData.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPublisherWeightCap` with a publisher linkage account listing `links`. The price
+    /// account doesn't grow in unit tests, use `with_extension` to make room for the extension
+    /// first.
+    pub fn set_publisher_weight_cap(
+        &mut self,
+        max_quotes_per_entity: u64,
+        links: &[PublisherLink],
+    ) -> ProgramResult {
+        let args = SetPublisherWeightCapArgs {
+            header: OracleCommand::SetPublisherWeightCap.into(),
+            max_quotes_per_entity,
+        };
+        let mut linkage_setup = AccountSetup::new_publisher_linkage(&self.program_id, links);
+        let mut linkage = linkage_setup.as_account_info();
+        linkage.is_signer = false;
+        linkage.is_writable = false;
+
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+                linkage,
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetQuoteOrder`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn set_quote_order(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetQuoteOrderArgs {
+            header:  OracleCommand::SetQuoteOrder.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetStatusReasonTracking`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_status_reason_tracking(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetStatusReasonTrackingArgs {
+            header:  OracleCommand::SetStatusReasonTracking.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetUpdateCounting`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_update_counting(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetUpdateCountingArgs {
+            header:  OracleCommand::SetUpdateCounting.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `EnableReadStats`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn enable_read_stats(&mut self) -> ProgramResult {
+        let header: CommandHeader = OracleCommand::EnableReadStats.into();
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&header),
+        )
+    }
+
+    /// Send `SetPublisherTimestamps`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publisher_timestamps(&mut self, enabled: bool) -> ProgramResult {
+        let args = SetPublisherTimestampsArgs {
+            header:  OracleCommand::SetPublisherTimestamps.into(),
+            enabled: u64::from(enabled),
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetPublishThreshold`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_publish_threshold(
+        &mut self,
+        deviation_bps: u64,
+        heartbeat_slots: u64,
+    ) -> ProgramResult {
+        let args = SetPublishThresholdArgs {
+            header: OracleCommand::SetPublishThreshold.into(),
+            deviation_bps,
+            heartbeat_slots,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetAverageMode`. The price account doesn't grow in unit tests, use `with_extension`
+    /// to make room for the extension first.
+    pub fn set_average_mode(&mut self, mode: AverageMode, window_slots: u64) -> ProgramResult {
+        let args = SetAverageModeArgs {
+            header: OracleCommand::SetAverageMode.into(),
+            mode: mode as u32,
+            unused_: 0,
+            window_slots,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `SetStakeProgram`. The price account doesn't grow in unit tests, use
+    /// `with_extension` to make room for the extension first.
+    pub fn set_stake_program(&mut self, staking_program: Pubkey, min_stake: u64) -> ProgramResult {
+        let args = SetStakeProgramArgs {
+            header: OracleCommand::SetStakeProgram.into(),
+            staking_program,
+            min_stake,
+        };
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `PrunePublishers` in the current slot, without the publisher registry.
+    pub fn prune_publishers(&mut self, max_idle_slots: u64) -> ProgramResult {
+        let args = PrunePublishersArgs {
+            header: OracleCommand::PrunePublishers.into(),
+            max_idle_slots,
+        };
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(
+            &self.program_id,
+            &[
+                self.funding.as_account_info(),
+                self.price.as_account_info(),
+                self.permissions.as_account_info(),
+                clock,
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Pass the account `key` to every following `UpdPrice` as its sanity program.
+    pub fn pass_sanity_program(&mut self, key: Pubkey) {
+        self.sanity = Some(AccountSetup::new_funding_with_key(key));
+    }
+
+    /// Pass the account `key` to every following `UpdPrice` as its status observer.
+    pub fn pass_status_observer(&mut self, key: Pubkey) {
+        self.observer = Some(AccountSetup::new_funding_with_key(key));
+    }
+
+    /// Pass an aggregation config with the given defaults to every following `UpdPrice`.
+    pub fn pass_aggregation_config(&mut self, max_ci_divisor: i64, max_latency: u8, min_pub: u8) {
+        self.aggregation_config = Some(AccountSetup::new_aggregation_config(
+            &self.program_id,
+            max_ci_divisor,
+            max_latency,
+            min_pub,
+        ));
+    }
+
+    /// Pass a feature gate listing `switches` to every following `UpdPrice`.
+    pub fn pass_feature_gate(&mut self, switches: &[FeatureSwitch]) {
+        self.feature_gate = Some(AccountSetup::new_feature_gate(&self.program_id, switches));
+    }
+
+    /// Pass the slot hashes sysvar to `UpdPrice`, with `hash` as the hash of the previous slot.
+    pub fn pass_slot_hashes(&mut self, hash: Hash) {
+        self.slot_hashes = Some(AccountSetup::new_slot_hashes(&[(
+            self.slot.saturating_sub(1),
+            hash,
+        )]));
+    }
+
+    /// Send `GetAggregationCounts` in the current slot and return its summary.
+    pub fn get_aggregation_counts(&mut self) -> AggregationCountsSummary {
+        let header: CommandHeader = OracleCommand::GetAggregationCounts.into();
+        let mut price = self.price.as_account_info();
+        price.is_signer = false;
+        price.is_writable = false;
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(&self.program_id, &[price, clock], bytes_of(&header)).unwrap();
+        get_aggregation_counts_summary(self.price_account_data(), self.slot).unwrap()
+    }
+
+    /// Send `GetPriceExtrema` in the current slot and return its summary.
+    pub fn get_price_extrema(&mut self) -> PriceExtremaSummary {
+        let header: CommandHeader = OracleCommand::GetPriceExtrema.into();
+        let mut price = self.price.as_account_info();
+        price.is_signer = false;
+        price.is_writable = false;
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(&self.program_id, &[price, clock], bytes_of(&header)).unwrap();
+        get_price_extrema_summary(self.price_account_data(), self.slot).unwrap()
+    }
+
+    /// Send `GetPrice` with the price account writable or not, and return its summary.
+    pub fn get_price(&mut self, writable: bool) -> Result<PriceSummary, ProgramError> {
+        let header: CommandHeader = OracleCommand::GetPrice.into();
+        let mut price = self.price.as_account_info();
+        price.is_signer = false;
+        price.is_writable = writable;
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(&self.program_id, &[price, clock], bytes_of(&header))?;
+        Ok(get_price_summary(&self.price_data()))
+    }
+
+    /// Send `SimulateAggregate` in the current slot and return the simulated aggregate, with the
+    /// default features.
+    pub fn simulate_aggregate(&mut self) -> SimulatedAggregate {
+        let header: CommandHeader = OracleCommand::SimulateAggregate.into();
+        let mut price = self.price.as_account_info();
+        price.is_signer = false;
+        price.is_writable = false;
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(
+            &self.program_id,
+            &[price.clone(), clock.clone()],
+            bytes_of(&header),
+        )
+        .unwrap();
+        let clock = clock::Clock::from_account_info(&clock).unwrap();
+        simulate_aggregation(&price, &clock, true, None).unwrap()
+    }
+
+    /// Send `AssertPriceCondition` in the current slot.
+    pub fn assert_price_condition(&mut self, condition: AssertPriceConditionArgs) -> ProgramResult {
+        let mut price = self.price.as_account_info();
+        price.is_signer = false;
+        price.is_writable = false;
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(&self.program_id, &[price, clock], bytes_of(&condition))
+    }
+
+    pub fn publisher_key(&self, publisher: usize) -> Pubkey {
+        self.publishers[publisher].key
+    }
+
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+
+    /// Move the clock to the next slot without publishing.
+    pub fn advance_slot(&mut self) -> u64 {
+        self.warp_to_slot(self.slot + 1);
+        self.slot
+    }
+
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.slot = slot;
+        TestClock::new(self.clock.as_account_info()).warp_to_slot(slot);
+    }
+
+    /// Move the unix timestamp of the clock, which `warp_to_slot` leaves as it is.
+    pub fn warp_to_timestamp(&mut self, timestamp: i64) {
+        TestClock::new(self.clock.as_account_info()).set_timestamp(timestamp);
+    }
+
+    /// Move the epoch of the clock, which `warp_to_slot` leaves as it is.
+    pub fn warp_to_epoch(&mut self, epoch: u64) {
+        TestClock::new(self.clock.as_account_info()).set_epoch(epoch);
+    }
+
+    /// Publish a trading quote for the current slot.
+    pub fn publish(&mut self, publisher: usize, price: i64, conf: u64) -> ProgramResult {
+        self.upd_price(publisher, PC_STATUS_TRADING, price, conf, self.slot)
+    }
+
+    /// Send `UpdPrice` from `publisher` in the current slot.
+    pub fn upd_price(
+        &mut self,
+        publisher: usize,
+        status: u32,
+        price: i64,
+        conf: u64,
+        publishing_slot: u64,
+    ) -> ProgramResult {
+        self.upd_price_from_venue(publisher, status, price, conf, publishing_slot, 0)
+    }
+
+    /// Send `UpdPrice` from `publisher` in the current slot with the quote tagged with `venue`.
+    pub fn upd_price_from_venue(
+        &mut self,
+        publisher: usize,
+        status: u32,
+        price: i64,
+        conf: u64,
+        publishing_slot: u64,
+        venue: u8,
+    ) -> ProgramResult {
+        let instruction_data = &mut [0u8; size_of::<UpdPriceArgs>()];
+        let cmd = load_mut::<UpdPriceArgs>(instruction_data).unwrap();
+        cmd.header = OracleCommand::UpdPrice.into();
+        cmd.status = status;
+        cmd.venue = venue;
+        cmd.price = price;
+        cmd.confidence = conf;
+        cmd.publishing_slot = publishing_slot;
+        cmd.unused_ = [0; 3];
+
+        self.upd_price_raw(publisher, instruction_data)
+    }
+
+    /// Publish a trading quote for the current slot numbered `sequence`.
+    pub fn publish_sequenced(
+        &mut self,
+        publisher: usize,
+        price: i64,
+        conf: u64,
+        sequence: u64,
+    ) -> ProgramResult {
+        let args = UpdPriceArgs {
+            header: OracleCommand::UpdPrice.into(),
+            status: PC_STATUS_TRADING,
+            venue: 0,
+            unused_: [0; 3],
+            price,
+            confidence: conf,
+            publishing_slot: self.slot,
+            sequence,
+        };
+        self.upd_price_raw(publisher, bytes_of(&args))
+    }
+
+    /// Send `UpdPrice` from `publisher` with the encoded arguments `instruction_data`.
+    pub fn upd_price_raw(&mut self, publisher: usize, instruction_data: &[u8]) -> ProgramResult {
+        let accounts = self.upd_price_accounts(publisher);
+        process_instruction(&self.program_id, &accounts, instruction_data)
+    }
+
+    /// Send `UpdPriceCompact` with `args` from `publisher`.
+    pub fn upd_price_compact(
+        &mut self,
+        publisher: usize,
+        args: &UpdPriceCompactArgs,
+    ) -> ProgramResult {
+        let accounts = self.upd_price_accounts(publisher);
+        process_instruction(&self.program_id, &accounts, bytes_of(args))
+    }
+
+    fn upd_price_accounts(&mut self, publisher: usize) -> Vec<AccountInfo> {
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        let mut accounts = vec![
+            self.publishers[publisher].as_account_info(),
+            self.price.as_account_info(),
+            clock,
+        ];
+        if let Some(sanity) = self.sanity.as_mut() {
+            let mut sanity = sanity.as_account_info();
+            sanity.is_signer = false;
+            sanity.is_writable = false;
+            accounts.push(sanity);
+        }
+        if let Some(observer) = self.observer.as_mut() {
+            let mut observer = observer.as_account_info();
+            observer.is_signer = false;
+            observer.is_writable = false;
+            accounts.push(observer);
+        }
+        if let Some(aggregation_config) = self.aggregation_config.as_mut() {
+            let mut aggregation_config = aggregation_config.as_account_info();
+            aggregation_config.is_signer = false;
+            aggregation_config.is_writable = false;
+            accounts.push(aggregation_config);
+        }
+        if let Some(feature_gate) = self.feature_gate.as_mut() {
+            let mut feature_gate = feature_gate.as_account_info();
+            feature_gate.is_signer = false;
+            feature_gate.is_writable = false;
+            accounts.push(feature_gate);
+        }
+        if let Some(slot_hashes) = self.slot_hashes.as_mut() {
+            let mut slot_hashes = slot_hashes.as_account_info();
+            slot_hashes.is_signer = false;
+            slot_hashes.is_writable = false;
+            accounts.push(slot_hashes);
+        }
+        accounts
+    }
+
+    /// Send `UpdPublisherStatus` from `publisher` in the current slot.
+    pub fn upd_publisher_status(
+        &mut self,
+        publisher: usize,
+        status: u32,
+        publishing_slot: u64,
+    ) -> ProgramResult {
+        let args = UpdPublisherStatusArgs {
+            header: OracleCommand::UpdPublisherStatus.into(),
+            status,
+            unused_: 0,
+            publishing_slot,
+        };
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(
+            &self.program_id,
+            &[
+                self.publishers[publisher].as_account_info(),
+                self.price.as_account_info(),
+                clock,
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Send `UpdAuxData` from `publisher` in the current slot.
+    pub fn upd_aux_data(
+        &mut self,
+        publisher: usize,
+        open_interest: u64,
+        volume_24h: u64,
+    ) -> ProgramResult {
+        let args = UpdAuxDataArgs {
+            header: OracleCommand::UpdAuxData.into(),
+            open_interest,
+            volume_24h,
+        };
+        let mut clock = self.clock.as_account_info();
+        clock.is_signer = false;
+        clock.is_writable = false;
+
+        process_instruction(
+            &self.program_id,
+            &[
+                self.publishers[publisher].as_account_info(),
+                self.price.as_account_info(),
+                clock,
+            ],
+            bytes_of(&args),
+        )
+    }
+
+    /// Advance to the next slot and publish `quotes`, where `quotes[i]` is the `(price, conf)`
+    /// of publisher `i`, or `None` if it doesn't publish in this slot.
+    pub fn post_slot(&mut self, quotes: &[Option<(i64, u64)>]) {
+        assert!(quotes.len() <= self.publishers.len());
+        self.advance_slot();
+        for (publisher, quote) in quotes.iter().enumerate() {
+            if let Some((price, conf)) = *quote {
+                self.publish(publisher, price, conf).unwrap();
+            }
+        }
+        let aggregate = self.price_data().agg_;
+        self.aggregates.push(aggregate);
+    }
+
+    /// `post_slot` every slot of `schedule`.
+    pub fn post_updates(&mut self, schedule: &[&[Option<(i64, u64)>]]) {
+        for quotes in schedule {
+            self.post_slot(quotes);
+        }
+    }
+
+    pub fn price_data(&mut self) -> PriceAccount {
+        let price_account = self.price.as_account_info();
+        let price_data = *load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap();
+        price_data
+    }
+
+    /// Overwrite the flags of the price account, e.g. to leave it the way an interrupted
+    /// instruction would if the runtime didn't roll it back.
+    pub fn set_flags(&mut self, flags: PriceAccountFlags) {
+        let price_account = self.price.as_account_info();
+        load_checked::<PriceAccount>(&price_account, PC_VERSION)
+            .unwrap()
+            .flags = flags;
+    }
+
+    pub fn price_key(&self) -> Pubkey {
+        self.price.key
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn price_account_data(&self) -> &[u8] {
+        &self.price.data[..self.price.size]
+    }
+
+    pub fn extension<T: PriceAccountExtension>(&self) -> T {
+        *get_extension::<T>(self.price_account_data())
+            .unwrap()
+            .unwrap()
+    }
+
+    /// Aggregates recorded after every slot posted so far.
+    pub fn aggregates(&self) -> &[PriceInfo] {
+        &self.aggregates
+    }
+
+    pub fn assert_aggregate_prices(&self, expected: &[i64]) {
+        let prices: Vec<i64> = self.aggregates.iter().map(|agg| agg.price_).collect();
+        assert_eq!(prices, expected);
+    }
+
+    pub fn assert_aggregate_confs(&self, expected: &[u64]) {
+        let confs: Vec<u64> = self.aggregates.iter().map(|agg| agg.conf_).collect();
+        assert_eq!(confs, expected);
+    }
+
+    pub fn assert_aggregate_statuses(&self, expected: &[u32]) {
+        let statuses: Vec<u32> = self.aggregates.iter().map(|agg| agg.status_).collect();
+        assert_eq!(statuses, expected);
+    }
+
+    fn price_data_mut(&mut self, f: impl FnOnce(&mut PriceAccount)) {
+        let price_account = self.price.as_account_info();
+        f(&mut *load_checked::<PriceAccount>(&price_account, PC_VERSION).unwrap());
+    }
 }
 
 impl From<OracleCommand> for CommandHeader {
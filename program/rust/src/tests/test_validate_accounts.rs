@@ -0,0 +1,1132 @@
+use {
+    crate::{
+        accounts::{
+            create_pc_str_t,
+            find_price_pda,
+            find_product_pda,
+            AccessControlAccount,
+            AccountHeader,
+            AggregationConfigAccount,
+            AuditLogAccount,
+            ConsistencyCheckAccount,
+            ConsistencyKind,
+            FeatureGateAccount,
+            FeeVaultAccount,
+            FeedKind,
+            FeedRegistryAccount,
+            MappingAccount,
+            MappingAccountV2,
+            MultisigAccount,
+            PermissionAccount,
+            PriceAccount,
+            ProductAccount,
+            ProgramdataAccount,
+            PublisherLinkageAccount,
+            PublisherRegistryAccount,
+            PythAccount,
+            RewardsLedgerAccount,
+            SnapshotAccount,
+            AGGREGATION_CONFIG_SEED,
+            AUDIT_LOG_SEED,
+            COMMUNITY_SEED,
+            FEATURE_GATE_SEED,
+            FEED_REGISTRY_SEED,
+            FEE_VAULT_SEED,
+            PERMISSIONS_SEED,
+            PUBLISHER_LINKAGE_SEED,
+            PUBLISHER_REGISTRY_SEED,
+            REWARDS_LEDGER_SEED,
+            SNAPSHOT_SEED,
+        },
+        c_oracle_header::{
+            PC_ACCTYPE_TEST,
+            PC_PTYPE_PRICE,
+            PC_STATUS_HALTED,
+            PC_STATUS_TRADING,
+            PC_VERSION,
+        },
+        deserialize::{
+            load_account_as_mut,
+            load_checked,
+        },
+        error::OracleError,
+        instruction::{
+            AddCommunityPriceArgs,
+            AddCommunityProductArgs,
+            AddPriceArgs,
+            AddPublisherArgs,
+            AssertPriceConditionArgs,
+            CommandHeader,
+            DelPublisherArgs,
+            InitConsistencyCheckArgs,
+            InitMultisigArgs,
+            InitPriceArgs,
+            InitPricePdaArgs,
+            MigrateMappingArgs,
+            OracleCommand,
+            PrunePublishersArgs,
+            SetAccumulatorEmitterArgs,
+            SetAggregateCacheArgs,
+            SetAggregationConfigArgs,
+            SetAggregationCountWindowArgs,
+            SetAggregationDelayArgs,
+            SetAuxDataArgs,
+            SetAverageModeArgs,
+            SetChecksumArgs,
+            SetConfFloorArgs,
+            SetExponentArgs,
+            SetFeatureArgs,
+            SetFeatureGracePeriodArgs,
+            SetLatencyStatsWindowArgs,
+            SetMaxLatencyArgs,
+            SetMinPubArgs,
+            SetPermissionsTimelockArgs,
+            SetPriceExtremaWindowArgs,
+            SetPublishThresholdArgs,
+            SetPublisherCapArgs,
+            SetPublisherDemotionArgs,
+            SetPublisherRewardsArgs,
+            SetPublisherSamplingArgs,
+            SetPublisherTimestampsArgs,
+            SetPublisherWeightCapArgs,
+            SetQuoteConversionArgs,
+            SetQuoteOrderArgs,
+            SetRateLimitArgs,
+            SetSanityProgramArgs,
+            SetSequenceTrackingArgs,
+            SetSharedSettingsArgs,
+            SetStakeProgramArgs,
+            SetStatusObserverArgs,
+            SetStatusPolicyArgs,
+            SetStatusReasonTrackingArgs,
+            SetUpdateCountingArgs,
+            SetUpdateFeeArgs,
+            SetUpdaterProgramArgs,
+            UpdAccessControlArgs,
+            UpdAuxDataArgs,
+            UpdPermissionsArgs,
+            UpdPriceArgs,
+            UpdPriceCompactArgs,
+            UpdPublisherLinkageArgs,
+            UpdPublisherRegistryArgs,
+            UpdPublisherStatusArgs,
+            WithdrawFeesArgs,
+        },
+        processor::process_instruction,
+        tests::test_utils::AccountSetup,
+        utils::{
+            validate_accounts,
+            AccountCheck,
+        },
+    },
+    bytemuck::{
+        bytes_of,
+        Pod,
+    },
+    num_traits::FromPrimitive,
+    solana_program::{
+        account_info::AccountInfo,
+        entrypoint::ProgramResult,
+        pubkey::Pubkey,
+        system_program,
+        sysvar::{
+            clock,
+            rent,
+        },
+    },
+    std::mem::size_of,
+};
+
+#[test]
+fn test_validate_accounts() {
+    let program_id = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+    let system_program_id = system_program::id();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    let mut clock_setup = AccountSetup::new_clock();
+    let clock_account = clock_setup.as_account_info();
+    let mut system_setup = AccountSetup::new_funding();
+    let mut system_account = system_setup.as_account_info();
+    system_account.key = &system_program_id;
+
+    assert_eq!(
+        validate_accounts(
+            &program_id,
+            &[
+                (&funding_account, AccountCheck::Funding),
+                (&funding_account, AccountCheck::Signer),
+                (&price_account, AccountCheck::Writable),
+                (&price_account, AccountCheck::Readable),
+                (&permissions_account, AccountCheck::Permissions),
+                (&system_account, AccountCheck::SystemProgram),
+                (&clock_account, AccountCheck::Sysvar(clock::id())),
+            ],
+        ),
+        Ok(())
+    );
+
+    let check = |account: &AccountInfo, check: AccountCheck| {
+        validate_accounts(&program_id, &[(account, check)])
+    };
+
+    // Non-signer or read-only funding account
+    let mut account = funding_account.clone();
+    account.is_signer = false;
+    assert_eq!(
+        check(&account, AccountCheck::Funding),
+        Err(OracleError::InvalidFundingAccount.into())
+    );
+    assert_eq!(
+        check(&account, AccountCheck::Signer),
+        Err(OracleError::InvalidSignableAccount.into())
+    );
+    let mut account = funding_account.clone();
+    account.is_writable = false;
+    assert_eq!(
+        check(&account, AccountCheck::Funding),
+        Err(OracleError::InvalidFundingAccount.into())
+    );
+    assert_eq!(check(&account, AccountCheck::Signer), Ok(()));
+
+    // Accounts of another program
+    let mut account = price_account.clone();
+    account.owner = &other_program;
+    assert_eq!(
+        check(&account, AccountCheck::Writable),
+        Err(OracleError::InvalidWritableAccount.into())
+    );
+    assert_eq!(
+        check(&account, AccountCheck::Readable),
+        Err(OracleError::InvalidReadableAccount.into())
+    );
+    let mut account = price_account.clone();
+    account.is_writable = false;
+    assert_eq!(
+        check(&account, AccountCheck::Writable),
+        Err(OracleError::InvalidWritableAccount.into())
+    );
+    assert_eq!(check(&account, AccountCheck::Readable), Ok(()));
+
+    // Permissions account at another address
+    assert_eq!(
+        check(&price_account, AccountCheck::Permissions),
+        Err(OracleError::InvalidPda.into())
+    );
+    assert_eq!(
+        check(&funding_account, AccountCheck::SystemProgram),
+        Err(OracleError::InvalidSystemAccount.into())
+    );
+
+    // Sysvars must be at their address and owned by the sysvar program
+    assert_eq!(
+        check(&funding_account, AccountCheck::Sysvar(clock::id())),
+        Err(OracleError::InvalidSysvarAccount.into())
+    );
+    let mut account = clock_account.clone();
+    account.owner = &program_id;
+    assert_eq!(
+        check(&account, AccountCheck::Sysvar(clock::id())),
+        Err(OracleError::InvalidSysvarAccount.into())
+    );
+
+    // The first failing account fails the instruction
+    assert_eq!(
+        validate_accounts(
+            &program_id,
+            &[
+                (&price_account, AccountCheck::Sysvar(clock::id())),
+                (&funding_account, AccountCheck::Writable),
+            ],
+        ),
+        Err(OracleError::InvalidSysvarAccount.into())
+    );
+}
+
+/// Instructions reject a funding account that didn't sign, a price account of another program and
+/// a clock that isn't the clock sysvar before reading them.
+#[test]
+fn test_handlers_validate_accounts() {
+    let program_id = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+
+    let mut funding_setup = AccountSetup::new_funding();
+    let funding_account = funding_setup.as_account_info();
+    let mut price_setup = AccountSetup::new::<PriceAccount>(&program_id);
+    let price_account = price_setup.as_account_info();
+    PriceAccount::initialize(&price_account, PC_VERSION).unwrap();
+    let mut permissions_setup = AccountSetup::new_permission(&program_id);
+    let permissions_account = permissions_setup.as_account_info();
+    PermissionAccount::initialize(&permissions_account, PC_VERSION)
+        .unwrap()
+        .master_authority = *funding_account.key;
+    let mut clock_setup = AccountSetup::new_clock();
+    let clock_account = clock_setup.as_account_info();
+
+    let mut unsigned_funding_account = funding_account.clone();
+    unsigned_funding_account.is_signer = false;
+    let mut foreign_price_account = price_account.clone();
+    foreign_price_account.owner = &other_program;
+    let mut fake_clock_setup = AccountSetup::new_clock();
+    let mut fake_clock_account = fake_clock_setup.as_account_info();
+    fake_clock_account.owner = &other_program;
+
+    let upd_price_args = UpdPriceArgs {
+        header:          OracleCommand::UpdPrice.into(),
+        status:          PC_STATUS_TRADING,
+        venue:           0,
+        unused_:         [0; 3],
+        price:           100,
+        confidence:      1,
+        publishing_slot: 1,
+        sequence:        0,
+    };
+    let upd_price = |accounts: &[AccountInfo]| {
+        process_instruction(&program_id, accounts, bytes_of(&upd_price_args))
+    };
+    assert_eq!(
+        upd_price(&[
+            unsigned_funding_account.clone(),
+            price_account.clone(),
+            clock_account.clone(),
+        ]),
+        Err(OracleError::InvalidFundingAccount.into())
+    );
+    assert_eq!(
+        upd_price(&[
+            funding_account.clone(),
+            foreign_price_account.clone(),
+            clock_account.clone(),
+        ]),
+        Err(OracleError::InvalidWritableAccount.into())
+    );
+    assert_eq!(
+        upd_price(&[
+            funding_account.clone(),
+            price_account.clone(),
+            fake_clock_account.clone(),
+        ]),
+        Err(OracleError::InvalidSysvarAccount.into())
+    );
+
+    let set_min_pub_args = SetMinPubArgs {
+        header:             OracleCommand::SetMinPub.into(),
+        minimum_publishers: 1,
+        unused_:            [0; 3],
+    };
+    let set_min_pub = |accounts: &[AccountInfo]| {
+        process_instruction(&program_id, accounts, bytes_of(&set_min_pub_args))
+    };
+    assert_eq!(
+        set_min_pub(&[
+            unsigned_funding_account.clone(),
+            price_account.clone(),
+            permissions_account.clone(),
+        ]),
+        Err(OracleError::InvalidFundingAccount.into())
+    );
+    assert_eq!(
+        set_min_pub(&[
+            funding_account.clone(),
+            foreign_price_account.clone(),
+            permissions_account.clone(),
+        ]),
+        Err(OracleError::InvalidWritableAccount.into())
+    );
+    assert_eq!(
+        set_min_pub(&[
+            funding_account.clone(),
+            price_account.clone(),
+            price_account.clone(),
+        ]),
+        Err(OracleError::InvalidPda.into())
+    );
+    assert!(set_min_pub(&[
+        funding_account.clone(),
+        price_account.clone(),
+        permissions_account.clone(),
+    ])
+    .is_ok());
+
+    // Read-only instructions
+    for command in [
+        OracleCommand::GetAggregationCounts,
+        OracleCommand::GetPriceExtrema,
+    ] {
+        let header: CommandHeader = command.into();
+        let read = |accounts: &[AccountInfo]| {
+            process_instruction(&program_id, accounts, bytes_of(&header))
+        };
+        assert_eq!(
+            read(&[foreign_price_account.clone(), clock_account.clone()]),
+            Err(OracleError::InvalidReadableAccount.into())
+        );
+        assert_eq!(
+            read(&[price_account.clone(), fake_clock_account.clone()]),
+            Err(OracleError::InvalidSysvarAccount.into())
+        );
+        assert_eq!(
+            read(&[price_account.clone(), price_account.clone()]),
+            Err(OracleError::InvalidSysvarAccount.into())
+        );
+        assert!(read(&[price_account.clone(), clock_account.clone()]).is_ok());
+    }
+
+    let get_extensions: CommandHeader = OracleCommand::GetExtensions.into();
+    assert_eq!(
+        process_instruction(
+            &program_id,
+            &[foreign_price_account.clone()],
+            bytes_of(&get_extensions)
+        ),
+        Err(OracleError::InvalidReadableAccount.into())
+    );
+}
+
+/// How an account is broken for a negative case.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Mutation {
+    Unsigned,
+    WrongOwner,
+    /// Data holding only an account header
+    WrongSize,
+    /// An account of another type: another discriminator, an initialized fresh account or
+    /// another sysvar
+    WrongType,
+}
+
+impl Mutation {
+    const ALL: [Mutation; 4] = [
+        Mutation::Unsigned,
+        Mutation::WrongOwner,
+        Mutation::WrongSize,
+        Mutation::WrongType,
+    ];
+}
+
+/// Accounts of the program an instruction reads or writes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    Mapping,
+    MappingV2,
+    Product,
+    CommunityProduct,
+    ProductPda,
+    Price,
+    CommunityPrice,
+    PricePda,
+    Permissions,
+    PublisherRegistry,
+    PublisherLinkage,
+    FeatureGate,
+    AccessControl,
+    AuditLog,
+    FeeVault,
+    RewardsLedger,
+    Multisig,
+    ConsistencyCheck,
+    AggregationConfig,
+    FeedRegistry,
+    Snapshot,
+}
+
+/// Symbol of the product created by `InitProductPda`.
+const SYMBOL: &str = "Crypto.BTC/USD";
+
+impl Kind {
+    /// Address of the account, given the keys of the accounts before it in the instruction: the
+    /// PDAs are derived from the funding account or the price account before them.
+    fn address(&self, program_id: &Pubkey, keys: &[Pubkey]) -> Pubkey {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, program_id).0;
+        match self {
+            Kind::CommunityProduct => {
+                pda(&[COMMUNITY_SEED.as_bytes(), &keys[0].to_bytes(), &[0; 32]])
+            }
+            Kind::CommunityPrice => {
+                pda(&[COMMUNITY_SEED.as_bytes(), &keys[0].to_bytes(), &[1; 32]])
+            }
+            Kind::ProductPda => find_product_pda(program_id, SYMBOL.as_bytes()).unwrap().0,
+            Kind::PricePda => find_price_pda(program_id, &keys[1], FeedKind::Spot).0,
+            Kind::Permissions => pda(&[PERMISSIONS_SEED.as_bytes()]),
+            Kind::PublisherRegistry => pda(&[PUBLISHER_REGISTRY_SEED.as_bytes()]),
+            Kind::PublisherLinkage => pda(&[PUBLISHER_LINKAGE_SEED.as_bytes()]),
+            Kind::FeatureGate => pda(&[FEATURE_GATE_SEED.as_bytes()]),
+            Kind::AuditLog => pda(&[AUDIT_LOG_SEED.as_bytes()]),
+            Kind::FeeVault => pda(&[FEE_VAULT_SEED.as_bytes()]),
+            Kind::RewardsLedger => pda(&[REWARDS_LEDGER_SEED.as_bytes(), &keys[0].to_bytes()]),
+            Kind::AggregationConfig => pda(&[AGGREGATION_CONFIG_SEED.as_bytes()]),
+            Kind::FeedRegistry => pda(&[FEED_REGISTRY_SEED.as_bytes()]),
+            Kind::Snapshot => pda(&[
+                SNAPSHOT_SEED.as_bytes(),
+                &keys[1].to_bytes(),
+                &0u64.to_le_bytes(),
+            ]),
+            _ => Pubkey::new_unique(),
+        }
+    }
+
+    fn setup(&self, program_id: &Pubkey) -> AccountSetup {
+        match self {
+            Kind::Mapping => AccountSetup::new::<MappingAccount>(program_id),
+            Kind::MappingV2 => AccountSetup::new_with_size::<MappingAccountV2>(
+                program_id,
+                MappingAccountV2::space(1),
+            ),
+            Kind::Product | Kind::CommunityProduct | Kind::ProductPda => {
+                AccountSetup::new::<ProductAccount>(program_id)
+            }
+            Kind::Price | Kind::CommunityPrice | Kind::PricePda => {
+                AccountSetup::new::<PriceAccount>(program_id)
+            }
+            Kind::Permissions => AccountSetup::new_permission(program_id),
+            Kind::PublisherRegistry => AccountSetup::new::<PublisherRegistryAccount>(program_id),
+            Kind::PublisherLinkage => AccountSetup::new::<PublisherLinkageAccount>(program_id),
+            Kind::FeatureGate => AccountSetup::new::<FeatureGateAccount>(program_id),
+            Kind::AccessControl => AccountSetup::new::<AccessControlAccount>(program_id),
+            Kind::AuditLog => AccountSetup::new::<AuditLogAccount>(program_id),
+            Kind::FeeVault => AccountSetup::new::<FeeVaultAccount>(program_id),
+            Kind::RewardsLedger => AccountSetup::new::<RewardsLedgerAccount>(program_id),
+            Kind::Multisig => AccountSetup::new::<MultisigAccount>(program_id),
+            Kind::ConsistencyCheck => AccountSetup::new::<ConsistencyCheckAccount>(program_id),
+            Kind::AggregationConfig => AccountSetup::new::<AggregationConfigAccount>(program_id),
+            Kind::FeedRegistry => AccountSetup::new::<FeedRegistryAccount>(program_id),
+            // A snapshot holds a copy of the price account after its own header
+            Kind::Snapshot => AccountSetup::new_with_size::<SnapshotAccount>(
+                program_id,
+                SnapshotAccount::MINIMUM_SIZE + PriceAccount::MINIMUM_SIZE,
+            ),
+        }
+    }
+
+    /// Initialize the account, the price accounts list `funding` as their publisher and the
+    /// permissions make it every authority.
+    fn initialize(&self, account: &AccountInfo, funding: &Pubkey) {
+        match self {
+            Kind::Mapping => initialize::<MappingAccount>(account),
+            Kind::MappingV2 => initialize::<MappingAccountV2>(account),
+            Kind::Product | Kind::CommunityProduct | Kind::ProductPda => {
+                initialize::<ProductAccount>(account)
+            }
+            Kind::Price | Kind::CommunityPrice | Kind::PricePda => {
+                let mut price_data = PriceAccount::initialize(account, PC_VERSION).unwrap();
+                price_data.num_ = 1;
+                price_data.comp_[0].pub_ = *funding;
+            }
+            Kind::Permissions => {
+                let mut permissions_data =
+                    PermissionAccount::initialize(account, PC_VERSION).unwrap();
+                permissions_data.master_authority = *funding;
+                permissions_data.data_curation_authority = *funding;
+                permissions_data.security_authority = *funding;
+            }
+            Kind::PublisherRegistry => initialize::<PublisherRegistryAccount>(account),
+            Kind::PublisherLinkage => initialize::<PublisherLinkageAccount>(account),
+            Kind::FeatureGate => initialize::<FeatureGateAccount>(account),
+            Kind::AccessControl => initialize::<AccessControlAccount>(account),
+            Kind::AuditLog => initialize::<AuditLogAccount>(account),
+            Kind::FeeVault => initialize::<FeeVaultAccount>(account),
+            Kind::RewardsLedger => initialize::<RewardsLedgerAccount>(account),
+            Kind::Multisig => initialize::<MultisigAccount>(account),
+            Kind::ConsistencyCheck => initialize::<ConsistencyCheckAccount>(account),
+            Kind::AggregationConfig => initialize::<AggregationConfigAccount>(account),
+            Kind::FeedRegistry => initialize::<FeedRegistryAccount>(account),
+            Kind::Snapshot => initialize::<SnapshotAccount>(account),
+        }
+    }
+}
+
+fn initialize<T: PythAccount>(account: &AccountInfo) {
+    T::initialize(account, PC_VERSION).unwrap();
+}
+
+/// What an instruction expects of one of its accounts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Role {
+    Funding,
+    Signer,
+    Writable(Kind),
+    Readable(Kind),
+    /// Account initialized by the instruction
+    Fresh(Kind),
+    /// PDA created by the instruction
+    NewPda(Kind),
+    Programdata,
+    Clock,
+    System,
+    /// Account the instruction doesn't check, e.g. the recipient of a transfer
+    Other,
+}
+
+impl Role {
+    fn address(&self, program_id: &Pubkey, keys: &[Pubkey]) -> Pubkey {
+        match self {
+            Role::Writable(kind)
+            | Role::Readable(kind)
+            | Role::Fresh(kind)
+            | Role::NewPda(kind) => kind.address(program_id, keys),
+            Role::Programdata => ProgramdataAccount::address(program_id),
+            Role::Clock => clock::id(),
+            Role::System => system_program::id(),
+            Role::Funding | Role::Signer | Role::Other => Pubkey::new_unique(),
+        }
+    }
+
+    fn setup(&self, program_id: &Pubkey, keys: &[Pubkey]) -> AccountSetup {
+        match self {
+            Role::Writable(kind)
+            | Role::Readable(kind)
+            | Role::Fresh(kind)
+            | Role::NewPda(kind) => kind.setup(program_id),
+            Role::Programdata => AccountSetup::new_programdata(program_id, &keys[0]),
+            Role::Clock => AccountSetup::new_clock(),
+            Role::Funding | Role::Signer | Role::System | Role::Other => {
+                AccountSetup::new_funding()
+            }
+        }
+    }
+
+    /// Error of an instruction given this account broken by `mutation`, `None` if the mutation
+    /// doesn't apply to the role.
+    fn expected(&self, mutation: Mutation) -> Option<OracleError> {
+        match (self, mutation) {
+            (Role::Funding, Mutation::Unsigned) => Some(OracleError::InvalidFundingAccount),
+            (Role::Signer, Mutation::Unsigned) => Some(OracleError::InvalidSignableAccount),
+            (Role::Writable(_) | Role::Fresh(_), Mutation::WrongOwner) => {
+                Some(OracleError::InvalidWritableAccount)
+            }
+            (Role::Readable(_), Mutation::WrongOwner) => Some(OracleError::InvalidReadableAccount),
+            (Role::Writable(_) | Role::Readable(_) | Role::Fresh(_), Mutation::WrongSize) => {
+                Some(OracleError::AccountTooSmall)
+            }
+            (Role::Writable(_) | Role::Readable(_), Mutation::WrongType) => {
+                Some(OracleError::InvalidAccountHeader)
+            }
+            (Role::Fresh(_) | Role::NewPda(_), Mutation::WrongType) => {
+                Some(OracleError::InvalidFreshAccount)
+            }
+            (Role::Programdata, Mutation::WrongType) => Some(OracleError::InvalidUpgradeAuthority),
+            (Role::Clock, Mutation::WrongOwner | Mutation::WrongType) => {
+                Some(OracleError::InvalidSysvarAccount)
+            }
+            (Role::System, Mutation::WrongType) => Some(OracleError::InvalidSystemAccount),
+            _ => None,
+        }
+    }
+}
+
+/// Keep the first `len` bytes of the data of `account`.
+fn truncate(account: &AccountInfo, len: usize) {
+    let mut data = account.data.borrow_mut();
+    let full = std::mem::take(&mut *data);
+    *data = &mut full[..len];
+}
+
+struct Case {
+    data:     Vec<u8>,
+    roles:    Vec<Role>,
+    /// Sets up the accounts beyond their initialization, before the mutation
+    prepare:  fn(&[AccountInfo]),
+    /// Mutations the instruction accepts
+    accepted: Vec<(usize, Mutation)>,
+}
+
+/// Instruction data of `command`, with the zeroed arguments `T` changed by `f`.
+fn args<T: Pod>(command: OracleCommand, f: impl FnOnce(&mut T)) -> Vec<u8> {
+    let mut args = T::zeroed();
+    f(&mut args);
+    let mut data = bytes_of(&args).to_vec();
+    data[..size_of::<CommandHeader>()].copy_from_slice(bytes_of(&CommandHeader::from(command)));
+    data
+}
+
+fn zeroed<T: Pod>(command: OracleCommand) -> Vec<u8> {
+    args::<T>(command, |_| ())
+}
+
+/// Metadata of a product, with every required key.
+fn product_metadata() -> Vec<u8> {
+    [
+        "symbol",
+        SYMBOL,
+        "asset_type",
+        "Crypto",
+        "quote_currency",
+        "USD",
+    ]
+    .iter()
+    .flat_map(|s| create_pc_str_t(s))
+    .collect()
+}
+
+/// Valid instruction data and accounts of `command`. The instructions that are no longer
+/// supported or take no accounts have none.
+fn case_for(command: OracleCommand) -> Case {
+    use {
+        Kind::*,
+        OracleCommand::*,
+        Role::*,
+    };
+
+    let plain = |data: Vec<u8>, roles: Vec<Role>| Case {
+        data,
+        roles,
+        prepare: |_| (),
+        accepted: vec![],
+    };
+    let governed_price = vec![Funding, Writable(Price), Readable(Permissions)];
+    let published_price = vec![Funding, Writable(Price), Clock];
+    let read_price = vec![Readable(Price), Clock];
+    let upgrade_authority = vec![Funding, Programdata, Writable(Permissions), System];
+
+    match command {
+        InitMapping => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Fresh(Mapping), Readable(Permissions)],
+        ),
+        AddMapping
+        | InitTest
+        | UpdTest
+        | ResizePriceAccount
+        | InitPriceFeedIndex
+        | SetPublishingSlotTolerance
+        | GetComputeBudgets => plain(zeroed::<CommandHeader>(command), vec![]),
+        AddProduct => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Writable(Mapping),
+                Fresh(Product),
+                Readable(Permissions),
+            ],
+        ),
+        UpdProduct | PatchProduct => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Writable(Product), Readable(Permissions)],
+        ),
+        AddPrice => plain(
+            args::<AddPriceArgs>(command, |args| args.price_type = PC_PTYPE_PRICE),
+            vec![
+                Funding,
+                Writable(Product),
+                Fresh(Price),
+                Readable(Permissions),
+            ],
+        ),
+        AddPublisher => plain(zeroed::<AddPublisherArgs>(command), governed_price),
+        DelPublisher => plain(
+            args::<DelPublisherArgs>(command, |args| {
+                args.publisher = Pubkey::new_from_array([1; 32])
+            }),
+            governed_price,
+        ),
+        UpdPrice | AggPrice | UpdPriceNoFailOnError => {
+            plain(zeroed::<UpdPriceArgs>(command), published_price)
+        }
+        InitPrice => plain(zeroed::<InitPriceArgs>(command), governed_price),
+        SetMinPub => plain(zeroed::<SetMinPubArgs>(command), governed_price),
+        DelPrice => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Writable(Product),
+                Writable(Price),
+                Readable(Permissions),
+            ],
+        ),
+        DelProduct => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Writable(Mapping),
+                Writable(Product),
+                Readable(Permissions),
+            ],
+        ),
+        UpdPermissions => plain(zeroed::<UpdPermissionsArgs>(command), upgrade_authority),
+        SetMaxLatency => plain(zeroed::<SetMaxLatencyArgs>(command), governed_price),
+        // A mapping account smaller than the current layout is what the instruction grows
+        ResizeMapping => Case {
+            accepted: vec![(0, Mutation::WrongSize)],
+            ..plain(zeroed::<CommandHeader>(command), vec![Writable(Mapping)])
+        },
+        GetExtensions => plain(zeroed::<CommandHeader>(command), vec![Readable(Price)]),
+        SetExponent => plain(zeroed::<SetExponentArgs>(command), governed_price),
+        SnapshotPriceAccount => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Readable(Price),
+                Fresh(Snapshot),
+                Clock,
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        SetRateLimit => plain(zeroed::<SetRateLimitArgs>(command), governed_price),
+        SetPermissionsTimelock => plain(
+            zeroed::<SetPermissionsTimelockArgs>(command),
+            vec![Funding, Programdata, Writable(Permissions), System, Clock],
+        ),
+        ApplyPermissions => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Readable(Permissions), Clock, Programdata],
+        ),
+        CancelPermissions => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Readable(Permissions)],
+        ),
+        UpdPublisherRegistry => plain(
+            zeroed::<UpdPublisherRegistryArgs>(command),
+            vec![
+                Funding,
+                Writable(PublisherRegistry),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        SetAggregationDelay => plain(zeroed::<SetAggregationDelayArgs>(command), governed_price),
+        AssertPriceCondition => plain(zeroed::<AssertPriceConditionArgs>(command), read_price),
+        InitMultisig => plain(
+            args::<InitMultisigArgs>(command, |args| {
+                args.threshold = 1;
+                args.num_signers = 1;
+                args.signers[0] = Pubkey::new_from_array([1; 32]);
+            }),
+            vec![Funding, Fresh(Multisig)],
+        ),
+        SetAggregationCountWindow => plain(
+            zeroed::<SetAggregationCountWindowArgs>(command),
+            governed_price,
+        ),
+        GetAggregationCounts | GetPriceExtrema | SimulateAggregate | GetPrice => {
+            plain(zeroed::<CommandHeader>(command), read_price)
+        }
+        SetSanityProgram => plain(zeroed::<SetSanityProgramArgs>(command), governed_price),
+        PrunePublishers => plain(
+            zeroed::<PrunePublishersArgs>(command),
+            vec![Funding, Writable(Price), Readable(Permissions), Clock],
+        ),
+        UpdPublisherLinkage => plain(
+            zeroed::<UpdPublisherLinkageArgs>(command),
+            vec![
+                Funding,
+                Writable(PublisherLinkage),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        SetPublisherWeightCap => plain(
+            zeroed::<SetPublisherWeightCapArgs>(command),
+            vec![
+                Funding,
+                Writable(Price),
+                Readable(Permissions),
+                Readable(PublisherLinkage),
+            ],
+        ),
+        ResetEma | SetAccessControl | ReserveExtensionSpace | EnableReadStats => {
+            plain(zeroed::<CommandHeader>(command), governed_price)
+        }
+        // The source must be a price account of the product
+        ClonePrice => Case {
+            prepare: |accounts| {
+                load_checked::<PriceAccount>(&accounts[2], PC_VERSION)
+                    .unwrap()
+                    .product_account = *accounts[1].key;
+            },
+            ..plain(
+                zeroed::<CommandHeader>(command),
+                vec![
+                    Funding,
+                    Writable(Product),
+                    Readable(Price),
+                    Fresh(Price),
+                    Readable(Permissions),
+                ],
+            )
+        },
+        SetQuoteOrder => plain(zeroed::<SetQuoteOrderArgs>(command), governed_price),
+        UpdPublisherStatus => plain(
+            args::<UpdPublisherStatusArgs>(command, |args| args.status = PC_STATUS_HALTED),
+            published_price,
+        ),
+        DeprecatePrice => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Writable(Price),
+                Readable(Permissions),
+                Readable(Price),
+            ],
+        ),
+        UpdPriceCompact => plain(zeroed::<UpdPriceCompactArgs>(command), published_price),
+        SetFeature => plain(
+            args::<SetFeatureArgs>(command, |args| args.name[0] = b'a'),
+            vec![
+                Funding,
+                Writable(FeatureGate),
+                Readable(Permissions),
+                System,
+                Clock,
+            ],
+        ),
+        SetFeatureGracePeriod => plain(
+            zeroed::<SetFeatureGracePeriodArgs>(command),
+            vec![
+                Funding,
+                Writable(FeatureGate),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        UpdAccessControl => plain(
+            args::<UpdAccessControlArgs>(command, |args| {
+                args.reader = Pubkey::new_from_array([1; 32])
+            }),
+            vec![Funding, Writable(AccessControl), Readable(Permissions)],
+        ),
+        SetLatencyStatsWindow => {
+            plain(zeroed::<SetLatencyStatsWindowArgs>(command), governed_price)
+        }
+        SetPriceExtremaWindow => {
+            plain(zeroed::<SetPriceExtremaWindowArgs>(command), governed_price)
+        }
+        RotatePublisher => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Signer, Readable(Permissions), Writable(Price)],
+        ),
+        SetConfFloor => plain(zeroed::<SetConfFloorArgs>(command), governed_price),
+        SetStatusReasonTracking => plain(
+            zeroed::<SetStatusReasonTrackingArgs>(command),
+            governed_price,
+        ),
+        SetStakeProgram => plain(zeroed::<SetStakeProgramArgs>(command), governed_price),
+        AddCommunityProduct => plain(
+            [
+                zeroed::<AddCommunityProductArgs>(command),
+                product_metadata(),
+            ]
+            .concat(),
+            vec![Funding, NewPda(CommunityProduct), System],
+        ),
+        AddCommunityPrice => plain(
+            args::<AddCommunityPriceArgs>(command, |args| {
+                args.price_type = PC_PTYPE_PRICE;
+                args.price_seed = [1; 32];
+            }),
+            vec![
+                Funding,
+                Writable(CommunityProduct),
+                NewPda(CommunityPrice),
+                System,
+            ],
+        ),
+        SetPublisherCap => plain(zeroed::<SetPublisherCapArgs>(command), governed_price),
+        SetQuoteConversion => plain(zeroed::<SetQuoteConversionArgs>(command), governed_price),
+        ConvertPrice => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Writable(Price), Readable(Price)],
+        ),
+        SetAggregateCache => plain(zeroed::<SetAggregateCacheArgs>(command), governed_price),
+        SetPublisherSampling => plain(zeroed::<SetPublisherSamplingArgs>(command), governed_price),
+        InitAuditLog => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Writable(AuditLog), Readable(Permissions), System],
+        ),
+        SetUpdateFee => plain(
+            zeroed::<SetUpdateFeeArgs>(command),
+            vec![
+                Funding,
+                Writable(Price),
+                Readable(Permissions),
+                Writable(FeeVault),
+                System,
+            ],
+        ),
+        WithdrawFees => plain(
+            zeroed::<WithdrawFeesArgs>(command),
+            vec![Funding, Writable(FeeVault), Readable(Permissions), Other],
+        ),
+        SetPublisherRewards => plain(zeroed::<SetPublisherRewardsArgs>(command), governed_price),
+        ClaimRewards => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Writable(Price), Writable(RewardsLedger), System],
+        ),
+        InitConsistencyCheck => plain(
+            args::<InitConsistencyCheckArgs>(command, |args| {
+                args.kind = ConsistencyKind::Inverse as u32
+            }),
+            vec![
+                Funding,
+                Fresh(ConsistencyCheck),
+                Readable(Permissions),
+                Writable(Price),
+                Writable(Price),
+            ],
+        ),
+        CheckConsistency => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Writable(ConsistencyCheck), Clock],
+        ),
+        InitProductPda => plain(
+            [zeroed::<CommandHeader>(command), product_metadata()].concat(),
+            vec![
+                Funding,
+                Writable(Mapping),
+                NewPda(ProductPda),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        InitPricePda => plain(
+            args::<InitPricePdaArgs>(command, |args| args.price_type = PC_PTYPE_PRICE),
+            vec![
+                Funding,
+                Writable(Product),
+                NewPda(PricePda),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        SetUpdateCounting => plain(zeroed::<SetUpdateCountingArgs>(command), governed_price),
+        SetAverageMode => plain(zeroed::<SetAverageModeArgs>(command), governed_price),
+        InitMappingV2 => plain(
+            zeroed::<CommandHeader>(command),
+            vec![Funding, Fresh(MappingV2), Readable(Permissions)],
+        ),
+        MigrateMapping => plain(
+            zeroed::<MigrateMappingArgs>(command),
+            vec![
+                Funding,
+                Writable(MappingV2),
+                Readable(Permissions),
+                Readable(Mapping),
+            ],
+        ),
+        SetPublisherTimestamps => plain(
+            zeroed::<SetPublisherTimestampsArgs>(command),
+            governed_price,
+        ),
+        SetPublishThreshold => plain(zeroed::<SetPublishThresholdArgs>(command), governed_price),
+        SetStatusObserver => plain(zeroed::<SetStatusObserverArgs>(command), governed_price),
+        SetChecksum => plain(zeroed::<SetChecksumArgs>(command), governed_price),
+        SetSequenceTracking => plain(zeroed::<SetSequenceTrackingArgs>(command), governed_price),
+        SetStatusPolicy => plain(zeroed::<SetStatusPolicyArgs>(command), governed_price),
+        SetAccumulatorEmitter => plain(
+            zeroed::<SetAccumulatorEmitterArgs>(command),
+            upgrade_authority,
+        ),
+        SetAuxData => plain(zeroed::<SetAuxDataArgs>(command), governed_price),
+        UpdAuxData => plain(zeroed::<UpdAuxDataArgs>(command), published_price),
+        SetPublisherDemotion => plain(zeroed::<SetPublisherDemotionArgs>(command), governed_price),
+        SetUpdaterProgram => plain(zeroed::<SetUpdaterProgramArgs>(command), governed_price),
+        SetAggregationConfig => plain(
+            zeroed::<SetAggregationConfigArgs>(command),
+            vec![
+                Funding,
+                Writable(AggregationConfig),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        ResizeFeedRegistry => plain(
+            zeroed::<CommandHeader>(command),
+            vec![
+                Funding,
+                Writable(FeedRegistry),
+                Readable(Permissions),
+                System,
+            ],
+        ),
+        SetSharedSettings => plain(zeroed::<SetSharedSettingsArgs>(command), governed_price),
+    }
+}
+
+/// Every instruction rejects each of its accounts when it didn't sign, belongs to another
+/// program, is too small or is another type of account, before using it.
+#[test]
+fn test_every_command_validates_accounts() {
+    let program_id = Pubkey::new_unique();
+    let other_program = Pubkey::new_unique();
+
+    for command in (0..).map_while(OracleCommand::from_i32) {
+        let name = format!("{:?}", command);
+        // The error is dropped unless an aggregation was interrupted
+        let lenient = command == OracleCommand::UpdPriceNoFailOnError;
+        let case = case_for(command);
+
+        for (index, role) in case.roles.iter().enumerate() {
+            for mutation in Mutation::ALL {
+                let err = match role.expected(mutation) {
+                    Some(err) if !case.accepted.contains(&(index, mutation)) => err,
+                    _ => continue,
+                };
+
+                let mut keys: Vec<Pubkey> = vec![];
+                for role in &case.roles {
+                    let key = role.address(&program_id, &keys);
+                    keys.push(key);
+                }
+                let mut setups: Vec<AccountSetup> = case
+                    .roles
+                    .iter()
+                    .map(|role| role.setup(&program_id, &keys))
+                    .collect();
+                let mut accounts: Vec<AccountInfo> = setups
+                    .iter_mut()
+                    .map(AccountSetup::as_account_info)
+                    .collect();
+                for (i, (account, role)) in accounts.iter_mut().zip(&case.roles).enumerate() {
+                    account.key = &keys[i];
+                    match role {
+                        Role::Writable(kind) | Role::Readable(kind) => {
+                            kind.initialize(account, &keys[0])
+                        }
+                        // The PDA doesn't exist yet
+                        Role::NewPda(_) if (i, mutation) != (index, Mutation::WrongType) => {
+                            truncate(account, 0)
+                        }
+                        _ => {}
+                    }
+                }
+                (case.prepare)(&accounts);
+
+                let account = &mut accounts[index];
+                match (mutation, role) {
+                    (Mutation::Unsigned, _) => account.is_signer = false,
+                    (Mutation::WrongOwner, _) => account.owner = &other_program,
+                    (Mutation::WrongSize, _) => truncate(account, size_of::<AccountHeader>()),
+                    (Mutation::WrongType, Role::Writable(_) | Role::Readable(_)) => {
+                        load_account_as_mut::<AccountHeader>(account)
+                            .unwrap()
+                            .account_type = PC_ACCTYPE_TEST
+                    }
+                    (Mutation::WrongType, Role::Fresh(kind) | Role::NewPda(kind)) => {
+                        kind.initialize(account, &keys[0])
+                    }
+                    (Mutation::WrongType, Role::Programdata) => {
+                        // A program account rather than its programdata
+                        load_account_as_mut::<ProgramdataAccount>(account)
+                            .unwrap()
+                            .account_type = 2
+                    }
+                    (Mutation::WrongType, Role::Clock) => account.key = &rent::ID,
+                    (Mutation::WrongType, _) => account.key = &other_program,
+                }
+
+                let result = process_instruction(&program_id, &accounts, &case.data);
+                let expected: ProgramResult = if lenient { Ok(()) } else { Err(err.into()) };
+                assert_eq!(
+                    result, expected,
+                    "{} with account {} {:?}",
+                    name, index, mutation
+                );
+            }
+        }
+    }
+}
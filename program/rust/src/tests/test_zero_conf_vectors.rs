@@ -0,0 +1,85 @@
+extern crate test_generator;
+
+use {
+    crate::{
+        processor::{
+            ALLOW_ZERO_CI,
+            FORBID_ZERO_CI,
+        },
+        tests::test_utils::FeedSimulator,
+    },
+    serde::Deserialize,
+    std::fs::File,
+    test_generator::test_resources,
+};
+
+// Canonical scenarios of feeds with zero confidence quotes, shared with the off-chain simulators,
+// which should reach the same aggregate after every step. Each vector lists the steps of a feed
+// in landing order, all expecting the default aggregation delay: the first update of a slot
+// aggregates the quotes of the earlier slots before writing its own. Steps either publish a
+// trading quote for the publishing slot `slot`, or toggle `ALLOW_ZERO_CI` with the magic
+// publisher keys of `AddPublisher`. Statuses are the `PC_STATUS_*` values.
+#[test_resources("program/rust/test_data/zero_conf/*.json")]
+fn test_zero_conf_vectors(input_path_raw: &str) {
+    // For some reason these tests have a different working directory than the macro.
+    let input_path = input_path_raw.replace("program/rust/", "");
+
+    let file = File::open(&input_path).expect("Test file not found");
+    let vector: Vector = serde_json::from_reader(&file).expect("Unable to parse JSON");
+
+    let mut feed = FeedSimulator::new(vector.num_publishers).with_min_pub(vector.min_pub);
+    for (i, step) in vector.steps.iter().enumerate() {
+        assert!(step.slot >= feed.slot(), "Steps out of order");
+        feed.warp_to_slot(step.slot);
+        match step.action {
+            Action::Update {
+                publisher,
+                price,
+                conf,
+            } => feed
+                .publish(publisher, price, conf)
+                .unwrap_or_else(|err| panic!("Step {} failed: {:?}", i, err)),
+            Action::AllowZeroCi => feed.add_publisher(ALLOW_ZERO_CI.into()),
+            Action::ForbidZeroCi => feed.add_publisher(FORBID_ZERO_CI.into()),
+        }
+
+        let price_data = feed.price_data();
+        let context = format!("Step {} of {}", i, vector.description);
+        assert_eq!(price_data.agg_.status_, step.agg_status, "{}", context);
+        assert_eq!(price_data.agg_.price_, step.agg_price, "{}", context);
+        assert_eq!(price_data.agg_.conf_, step.agg_conf, "{}", context);
+        assert_eq!(price_data.num_qt_, step.num_qt, "{}", context);
+    }
+}
+
+#[derive(Deserialize)]
+struct Vector {
+    description:    String,
+    min_pub:        u8,
+    num_publishers: usize,
+    steps:          Vec<Step>,
+}
+
+/// A step of the feed in `slot`, with the aggregate of the price account after it.
+#[derive(Deserialize)]
+struct Step {
+    slot:       u64,
+    #[serde(flatten)]
+    action:     Action,
+    agg_status: u32,
+    agg_price:  i64,
+    agg_conf:   u64,
+    num_qt:     u32,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum Action {
+    Update {
+        publisher: usize,
+        price:     i64,
+        conf:      u64,
+    },
+    AllowZeroCi,
+    ForbidZeroCi,
+}
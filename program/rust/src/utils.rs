@@ -1,8 +1,25 @@
+use {
+    crate::{
+        accounts::AccountHeader,
+        deserialize::load_account_as,
+        OracleError,
+    },
+    solana_program::{
+        account_info::AccountInfo,
+        program_error::ProgramError,
+        sysvar::rent::Rent,
+    },
+};
+#[cfg(not(feature = "wasm"))]
 use {
     crate::{
         accounts::{
-            AccountHeader,
+            get_access_control,
+            get_feed_creator,
+            AccessControlAccount,
             PermissionAccount,
+            PriceAccount,
+            PriceAccountFlags,
             PERMISSIONS_SEED,
         },
         c_oracle_header::{
@@ -10,32 +27,21 @@ use {
             MAX_NUM_DECIMALS,
             PC_STATUS_IGNORED,
         },
-        deserialize::{
-            load_account_as,
-            load_checked,
-        },
+        deserialize::load_checked,
         instruction::{
             CommandHeader,
             OracleCommand,
             UpdPriceArgs,
         },
-        OracleError,
-    },
-    bytemuck::{
-        Pod,
-        Zeroable,
     },
     num_traits::FromPrimitive,
     solana_program::{
-        account_info::AccountInfo,
-        bpf_loader_upgradeable,
         program::invoke,
-        program_error::ProgramError,
         pubkey::Pubkey,
         system_instruction::transfer,
-        sysvar::rent::Rent,
+        system_program,
+        sysvar,
     },
-    std::cell::Ref,
 };
 
 pub fn pyth_assert(condition: bool, error_code: ProgramError) -> Result<(), ProgramError> {
@@ -46,10 +52,12 @@ pub fn pyth_assert(condition: bool, error_code: ProgramError) -> Result<(), Prog
     }
 }
 
+#[cfg(not(feature = "wasm"))]
 pub fn valid_funding_account(account: &AccountInfo) -> bool {
     account.is_signer && account.is_writable
 }
 
+#[cfg(not(feature = "wasm"))]
 pub fn check_valid_funding_account(account: &AccountInfo) -> Result<(), ProgramError> {
     pyth_assert(
         valid_funding_account(account),
@@ -59,6 +67,7 @@ pub fn check_valid_funding_account(account: &AccountInfo) -> Result<(), ProgramE
 
 /// Check that `account` is a valid signable pyth account or
 /// that `funding_account` is a signer and is permissioned by the `permission_account`
+#[cfg(not(feature = "wasm"))]
 pub fn check_permissioned_funding_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -66,18 +75,45 @@ pub fn check_permissioned_funding_account(
     permissions_account: &AccountInfo,
     cmd_hdr: &CommandHeader,
 ) -> Result<(), ProgramError> {
-    check_valid_permissions_account(program_id, permissions_account)?;
+    validate_accounts(
+        program_id,
+        &[
+            (permissions_account, AccountCheck::Permissions),
+            (funding_account, AccountCheck::Funding),
+            (account, AccountCheck::Writable),
+        ],
+    )?;
     let permissions_account_data =
         load_checked::<PermissionAccount>(permissions_account, cmd_hdr.version)?;
-    check_valid_funding_account(funding_account)?;
+    let command =
+        OracleCommand::from_i32(cmd_hdr.command).ok_or(OracleError::UnrecognizedInstruction)?;
     pyth_assert(
-        permissions_account_data.is_authorized(
-            funding_account.key,
-            OracleCommand::from_i32(cmd_hdr.command).ok_or(OracleError::UnrecognizedInstruction)?,
-        ),
+        permissions_account_data.is_authorized(funding_account.key, command)
+            || is_feed_creator(account, funding_account.key, command),
         OracleError::PermissionViolation.into(),
-    )?;
-    check_valid_writable_account(program_id, account)
+    )
+}
+
+/// Commands the creator of a community feed can send to it besides the master authority, see
+/// `FeedCreator`.
+#[cfg(not(feature = "wasm"))]
+const FEED_CREATOR_COMMANDS: [OracleCommand; 4] = [
+    OracleCommand::AddPublisher,
+    OracleCommand::DelPublisher,
+    OracleCommand::SetMinPub,
+    OracleCommand::SetMaxLatency,
+];
+
+/// Whether `key` created the community feed of `account` and `command` is one it can send. The
+/// caller still checks that the program owns `account`.
+#[cfg(not(feature = "wasm"))]
+fn is_feed_creator(account: &AccountInfo, key: &Pubkey, command: OracleCommand) -> bool {
+    FEED_CREATOR_COMMANDS.contains(&command)
+        && account
+            .try_borrow_data()
+            .ok()
+            .and_then(|data| get_feed_creator(&data))
+            == Some(*key)
 }
 
 /// Returns `true` if the `account` is fresh, i.e., its data can be overwritten.
@@ -98,10 +134,11 @@ pub fn check_valid_fresh_account(account: &AccountInfo) -> Result<(), ProgramErr
 }
 
 // Check that an exponent is within the range of permitted exponents for price accounts.
+#[cfg(not(feature = "wasm"))]
 pub fn check_exponent_range(expo: i32) -> Result<(), ProgramError> {
     pyth_assert(
         (-MAX_NUM_DECIMALS..=MAX_NUM_DECIMALS).contains(&expo),
-        ProgramError::InvalidArgument,
+        OracleError::InvalidExponent.into(),
     )
 }
 
@@ -111,6 +148,7 @@ pub fn try_convert<T, U: TryFrom<T>>(x: T) -> Result<U, OracleError> {
     U::try_from(x).map_err(|_| OracleError::IntegerCastingError)
 }
 
+#[cfg(not(feature = "wasm"))]
 fn valid_writable_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -118,6 +156,7 @@ fn valid_writable_account(
     Ok(account.is_writable && account.owner == program_id)
 }
 
+#[cfg(not(feature = "wasm"))]
 pub fn check_valid_writable_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -128,6 +167,7 @@ pub fn check_valid_writable_account(
     )
 }
 
+#[cfg(not(feature = "wasm"))]
 fn valid_readable_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -135,6 +175,7 @@ fn valid_readable_account(
     Ok(account.owner == program_id)
 }
 
+#[cfg(not(feature = "wasm"))]
 pub fn check_valid_readable_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -145,6 +186,7 @@ pub fn check_valid_readable_account(
     )
 }
 
+#[cfg(not(feature = "wasm"))]
 pub fn check_valid_permissions_account(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -158,8 +200,61 @@ pub fn check_valid_permissions_account(
     )
 }
 
+/// What a handler expects of an account of its instruction, see `validate_accounts`.
+#[derive(Clone, Copy, Debug)]
+#[cfg(not(feature = "wasm"))]
+pub enum AccountCheck {
+    /// Signer and writable, see `check_valid_funding_account`
+    Funding,
+    /// Signer, e.g. a key proving it's held by the sender
+    Signer,
+    /// Writable and owned by the program, see `check_valid_writable_account`
+    Writable,
+    /// Owned by the program, see `check_valid_readable_account`
+    Readable,
+    /// The permissions account of the program, see `check_valid_permissions_account`
+    Permissions,
+    /// The system program
+    SystemProgram,
+    /// The sysvar at this address, e.g. `clock::id()`
+    Sysvar(Pubkey),
+}
+
+/// Check the accounts of an instruction in order, failing with the error of the first account
+/// that isn't what the handler expects. Handlers check every account they read or write before
+/// using it, rather than relying on the runtime or on deserialization to reject the unexpected
+/// ones.
+#[cfg(not(feature = "wasm"))]
+pub fn validate_accounts(
+    program_id: &Pubkey,
+    checks: &[(&AccountInfo, AccountCheck)],
+) -> Result<(), ProgramError> {
+    for (account, check) in checks {
+        match check {
+            AccountCheck::Funding => check_valid_funding_account(account)?,
+            AccountCheck::Signer => pyth_assert(
+                account.is_signer,
+                OracleError::InvalidSignableAccount.into(),
+            )?,
+            AccountCheck::Writable => check_valid_writable_account(program_id, account)?,
+            AccountCheck::Readable => check_valid_readable_account(program_id, account)?,
+            AccountCheck::Permissions => check_valid_permissions_account(program_id, account)?,
+            AccountCheck::SystemProgram => pyth_assert(
+                system_program::check_id(account.key),
+                OracleError::InvalidSystemAccount.into(),
+            )?,
+            AccountCheck::Sysvar(id) => pyth_assert(
+                account.key == id && sysvar::check_id(account.owner),
+                OracleError::InvalidSysvarAccount.into(),
+            )?,
+        }
+    }
+    Ok(())
+}
+
 /// Checks whether this instruction is trying to update an individual publisher's price (`true`) or
 /// is only trying to refresh the aggregate (`false`)
+#[cfg(not(feature = "wasm"))]
 pub fn is_component_update(cmd_args: &UpdPriceArgs) -> Result<bool, OracleError> {
     match OracleCommand::from_i32(cmd_args.header.command)
         .ok_or(OracleError::UnrecognizedInstruction)?
@@ -169,13 +264,126 @@ pub fn is_component_update(cmd_args: &UpdPriceArgs) -> Result<bool, OracleError>
     }
 }
 
+/// Fails if an aggregation of the price account started and didn't complete, see
+/// `PriceAccountFlags::AGGREGATION_IN_PROGRESS`.
+#[cfg(not(feature = "wasm"))]
+pub fn check_aggregation_complete(price_data: &PriceAccount) -> Result<(), ProgramError> {
+    pyth_assert(
+        !price_data
+            .flags
+            .contains(PriceAccountFlags::AGGREGATION_IN_PROGRESS),
+        OracleError::AggregationInProgress.into(),
+    )
+}
+
+/// Fails with `ReaderNotAllowed` if the price account is permissioned, unless a reader listed in
+/// its access control account signs. `reader_accounts` are the reader and the access control
+/// account, which open feeds don't need.
+#[cfg(not(feature = "wasm"))]
+pub fn check_reader_allowed(
+    program_id: &Pubkey,
+    price_account: &AccountInfo,
+    reader_accounts: Option<(&AccountInfo, &AccountInfo)>,
+    version: u32,
+) -> Result<(), ProgramError> {
+    if let Some(access_control) = get_access_control(&price_account.try_borrow_data()?)? {
+        let (reader_account, access_control_account) =
+            reader_accounts.ok_or(OracleError::ReaderNotAllowed)?;
+        validate_accounts(
+            program_id,
+            &[(access_control_account, AccountCheck::Readable)],
+        )?;
+        let access_control_data =
+            load_checked::<AccessControlAccount>(access_control_account, version)?;
+        pyth_assert(
+            *access_control_account.key == access_control
+                && reader_account.is_signer
+                && access_control_data.is_reader(reader_account.key),
+            OracleError::ReaderNotAllowed.into(),
+        )?;
+    }
+    Ok(())
+}
+
+/// Id of the compute budget program, `ComputeBudget111111111111111111111111111111`.
+#[cfg(not(feature = "wasm"))]
+pub const COMPUTE_BUDGET_PROGRAM_ID: [u8; 32] = [
+    3, 6, 70, 111, 229, 33, 23, 50, 255, 236, 173, 186, 114, 195, 155, 231, 188, 140, 229, 187,
+    197, 247, 18, 107, 44, 67, 155, 58, 64, 0, 0, 0,
+];
+
+/// Whether the transaction of the current instruction only holds instructions of `program_id`
+/// and of the compute budget program, so that a price update in it can't be used by another
+/// program in the same transaction, including by invoking the update. The instructions are read
+/// from the serialized instructions sysvar without deserializing them: a `u16` count and the
+/// `u16` offsets of the instructions, each starting with a `u16` count of 33 bytes account
+/// metas followed by its program id.
+#[cfg(not(feature = "wasm"))]
+pub fn is_isolated_transaction(
+    program_id: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    pyth_assert(
+        sysvar::instructions::check_id(instructions_sysvar.key),
+        ProgramError::InvalidArgument,
+    )?;
+
+    let data = instructions_sysvar.try_borrow_data()?;
+    let read_u16 = |offset: usize| {
+        data.get(offset..offset + 2)
+            .map(|bytes| usize::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+            .ok_or(ProgramError::InvalidAccountData)
+    };
+    for index in 0..read_u16(0)? {
+        let instruction_offset = read_u16(2 + 2 * index)?;
+        let program_id_offset = instruction_offset + 2 + 33 * read_u16(instruction_offset)?;
+        let instruction_program_id = data
+            .get(program_id_offset..program_id_offset + 32)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        if instruction_program_id != program_id.as_ref()
+            && instruction_program_id != COMPUTE_BUDGET_PROGRAM_ID.as_slice()
+        {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Whether the instruction of the transaction being executed is an instruction of
+/// `updater_program`, i.e. whether the current invocation was made by it, directly or through
+/// the programs it invoked.
+#[cfg(not(feature = "wasm"))]
+pub fn is_forwarded_by(
+    updater_program: &Pubkey,
+    instructions_sysvar: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    let current_index = sysvar::instructions::load_current_index_checked(instructions_sysvar)?;
+    let instruction = sysvar::instructions::load_instruction_at_checked(
+        usize::from(current_index),
+        instructions_sysvar,
+    )?;
+    Ok(instruction.program_id == *updater_program)
+}
+
 // Return PC_STATUS_IGNORED if confidence is bigger than price divided by MAX_CI_DIVISOR else returns status
+#[cfg(not(feature = "wasm"))]
 pub fn get_status_for_conf_price_ratio(
     price: i64,
     confidence: u64,
     status: u32,
 ) -> Result<u32, OracleError> {
-    let threshold_conf = price.abs() / MAX_CI_DIVISOR;
+    get_status_for_max_ci_divisor(price, confidence, status, MAX_CI_DIVISOR)
+}
+
+/// `get_status_for_conf_price_ratio` with the divisor of an `AggregationConfigAccount`.
+#[cfg(not(feature = "wasm"))]
+pub fn get_status_for_max_ci_divisor(
+    price: i64,
+    confidence: u64,
+    status: u32,
+    max_ci_divisor: i64,
+) -> Result<u32, OracleError> {
+    let threshold_conf = price.abs() / max_ci_divisor;
 
     if confidence > try_convert::<_, u64>(threshold_conf)? {
         Ok(PC_STATUS_IGNORED)
@@ -184,56 +392,6 @@ pub fn get_status_for_conf_price_ratio(
     }
 }
 
-/// This struct represents UpgradeableLoaderState from bpf-upgradable-loader.
-/// Solana uses bincode for the struct. However the bincode crate is too big the space we have onchain,
-/// therefore we will use bytemuck for deserialization
-#[repr(C)]
-#[derive(Pod, Zeroable, Copy, Clone)]
-struct ProgramdataAccount {
-    /// 3 is the variant for programdata
-    pub account_type:          u32,
-    /// Space for slot of last upgrade (we don't use this field)
-    pub slot:                  [u32; 2],
-    /// 0 if immutable, 1 if has upgrade authority
-    pub has_upgrade_authority: u8,
-    /// Upgrade authority of the program
-    pub upgrade_authority:     Pubkey,
-    /// Unused field needed for this struct to be Pod
-    pub unused:                [u8; 3],
-}
-
-/// Check that `programdata_account` is actually the buffer for `program_id`.
-/// Check that the authority in `programdata_account` matches `upgrade_authority_account`.
-pub fn check_is_upgrade_authority_for_program(
-    upgrade_authority_account: &AccountInfo,
-    programdata_account: &AccountInfo,
-    program_id: &Pubkey,
-) -> Result<(), ProgramError> {
-    let programdata_deserialized: Ref<ProgramdataAccount> =
-        load_account_as::<ProgramdataAccount>(programdata_account)?;
-
-    // 1. programdata_account is actually this program's buffer
-    let (programdata_address, _) =
-        Pubkey::find_program_address(&[&program_id.to_bytes()], &bpf_loader_upgradeable::id());
-
-    pyth_assert(
-        programdata_address.eq(programdata_account.key),
-        OracleError::InvalidUpgradeAuthority.into(),
-    )?;
-
-    // 2. upgrade_authority_account is actually the authority inside programdata_account
-    pyth_assert(
-        programdata_deserialized
-            .upgrade_authority
-            .eq(upgrade_authority_account.key)
-            && programdata_deserialized.account_type == 3
-            && programdata_deserialized.has_upgrade_authority == 1,
-        OracleError::InvalidUpgradeAuthority.into(),
-    )?;
-
-    Ok(())
-}
-
 #[cfg(not(test))]
 pub fn get_rent() -> Result<Rent, ProgramError> {
     use solana_program::sysvar::Sysvar;
@@ -245,7 +403,7 @@ pub fn get_rent() -> Result<Rent, ProgramError> {
     Ok(Rent::default())
 }
 
-#[allow(dead_code)]
+#[cfg(not(feature = "wasm"))]
 pub fn send_lamports<'a>(
     from: &AccountInfo<'a>,
     to: &AccountInfo<'a>,
@@ -1,17 +1,27 @@
 use {
     crate::{
         accounts::{
+            get_extension,
             AccountHeader,
+            AggregationConfigAccount,
+            Feature,
+            FeatureGateAccount,
             PriceAccount,
             PriceAccountFlags,
+            PublisherLandingLatency,
             PythAccount,
             PythOracleSerialize,
+            SharedSettings,
+        },
+        c_oracle_header::{
+            PC_MAGIC,
+            PC_STATUS_IGNORED,
         },
-        c_oracle_header::PC_MAGIC,
         error::OracleError,
         processor::{
             c_upd_aggregate,
             c_upd_twap,
+            find_capped_components,
         },
         utils::pyth_assert,
     },
@@ -87,6 +97,10 @@ pub enum AggregationError {
     V1AggregationMode,
     #[error("AlreadyAggregated")]
     AlreadyAggregated,
+    #[error("MissingSharedSettings")]
+    MissingSharedSettings,
+    #[error("InvalidFeatureGate")]
+    InvalidFeatureGate,
 }
 
 /// Attempts to read a price account and create a new price aggregate if v2
@@ -94,12 +108,114 @@ pub enum AggregationError {
 /// Returns messages that should be included in the merkle tree, unless v1 aggregation
 /// is still in use.
 /// Note that the `messages` may be returned even if aggregation fails for some reason.
+/// The extensions of the feed aren't read, the feeds following the `SharedSettings` are
+/// aggregated with `aggregate_price_with_shared_settings`.
 pub fn aggregate_price(
     slot: u64,
     timestamp: i64,
     price_account_pubkey: &Pubkey,
     price_account: &mut PriceAccount,
 ) -> Result<[Vec<u8>; 2], AggregationError> {
+    try_aggregate(slot, timestamp, price_account)?;
+    Ok([
+        price_account
+            .as_price_feed_message(price_account_pubkey)
+            .to_bytes(),
+        price_account
+            .as_twap_message(price_account_pubkey)
+            .to_bytes(),
+    ])
+}
+
+/// Same as `aggregate_price` with the whole account data, so that the feeds aggregate like in
+/// `UpdPrice`. The feeds following the `SharedSettings` aggregate with the defaults of
+/// `aggregation_config` where they don't set their own quorum or latency, and cap the quotes of
+/// every entity if `feature_gate`, the data of the feature gate, enables `WeightedAggregation`.
+/// Their aggregation fails without them. The other feeds cap the quotes by default. The quorum
+/// and the latency of the feed are restored right after, the capped quotes keep
+/// `PC_STATUS_IGNORED`. `SameSlotAggregation` doesn't apply, the validator aggregates once per
+/// slot.
+pub fn aggregate_price_with_shared_settings(
+    slot: u64,
+    timestamp: i64,
+    price_account_pubkey: &Pubkey,
+    price_account_data: &mut [u8],
+    aggregation_config: Option<&AggregationConfigAccount>,
+    feature_gate: Option<&[u8]>,
+) -> Result<[Vec<u8>; 2], AggregationError> {
+    let (aggregation_config, weighted_aggregation) =
+        match SharedSettings::is_followed(price_account_data) {
+            Ok(true) => (
+                Some(aggregation_config.ok_or(AggregationError::MissingSharedSettings)?),
+                FeatureGateAccount::is_enabled(
+                    feature_gate.ok_or(AggregationError::MissingSharedSettings)?,
+                    Feature::WeightedAggregation,
+                    slot,
+                )
+                .map_err(|_| AggregationError::InvalidFeatureGate)?,
+            ),
+            Ok(false) => (None, Feature::WeightedAggregation.enabled_by_default()),
+            Err(_) => return Err(AggregationError::NotPriceFeedAccount),
+        };
+    let (min_pub, max_latency) = {
+        let price_account = checked_load_price_account_mut(price_account_data)
+            .map_err(|_| AggregationError::NotPriceFeedAccount)?;
+        check_aggregation(slot, price_account)?;
+        let saved = (price_account.min_pub_, price_account.max_latency_);
+        if let Some(config) = aggregation_config {
+            config.apply_defaults(price_account);
+        }
+        saved
+    };
+    let capped_components = if weighted_aggregation {
+        find_capped_components(price_account_data, slot)
+            .map_err(|_| AggregationError::NotPriceFeedAccount)?
+    } else {
+        vec![]
+    };
+    let price_account = checked_load_price_account_mut(price_account_data)
+        .map_err(|_| AggregationError::NotPriceFeedAccount)?;
+    for index in capped_components {
+        price_account.comp_[index].latest_.status_ = PC_STATUS_IGNORED;
+    }
+    let messages = aggregate_price(slot, timestamp, price_account_pubkey, price_account);
+    price_account.min_pub_ = min_pub;
+    price_account.max_latency_ = max_latency;
+    messages
+}
+
+/// Same as `aggregate_price`, but also returns the publisher prices message after the price
+/// feed and TWAP messages, compressed to fit the quotes of large feeds in the buffer, see
+/// `PublisherPricesMessage::to_compressed_bytes`.
+pub fn aggregate_price_with_publisher_prices(
+    slot: u64,
+    timestamp: i64,
+    price_account_pubkey: &Pubkey,
+    price_account: &mut PriceAccount,
+) -> Result<[Vec<u8>; 3], AggregationError> {
+    let [price_feed_message, twap_message] =
+        aggregate_price(slot, timestamp, price_account_pubkey, price_account)?;
+    Ok([
+        price_feed_message,
+        twap_message,
+        price_account
+            .as_publisher_prices_message(price_account_pubkey)
+            .to_compressed_bytes(),
+    ])
+}
+
+fn try_aggregate(
+    slot: u64,
+    timestamp: i64,
+    price_account: &mut PriceAccount,
+) -> Result<(), AggregationError> {
+    check_aggregation(slot, price_account)?;
+    update_aggregate(slot, timestamp, price_account);
+    Ok(())
+}
+
+/// Whether the validator aggregates the feed in `slot`.
+fn check_aggregation(slot: u64, price_account: &PriceAccount) -> Result<(), AggregationError> {
     if !price_account
         .flags
         .contains(PriceAccountFlags::ACCUMULATOR_V2)
@@ -120,15 +236,7 @@ pub fn aggregate_price(
         // (this should normally happen only in the slot that contains the v1->v2 transition).
         return Err(AggregationError::AlreadyAggregated);
     }
-    update_aggregate(slot, timestamp, price_account);
-    Ok([
-        price_account
-            .as_price_feed_message(price_account_pubkey)
-            .to_bytes(),
-        price_account
-            .as_twap_message(price_account_pubkey)
-            .to_bytes(),
-    ])
+    Ok(())
 }
 
 /// Load a price account as read-only, returning `None` if it isn't a valid price account.
@@ -191,3 +299,50 @@ pub fn compute_publisher_stake_caps<'a>(
     }
     .to_bytes()
 }
+
+/// Transaction landing latency of a publisher on a feed, see `LandingLatency`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublisherLandingStats {
+    pub publisher:      Pubkey,
+    pub price_account:  Pubkey,
+    /// Latency of the last update, in slots
+    pub latest_latency: u64,
+    /// In thousandths of a slot
+    pub latency_ema:    u64,
+}
+
+/// Collects the landing latency of every publisher of the price accounts provided, sorted by
+/// publisher. Feeds without the `PublisherLandingLatency` extension are skipped, as well as
+/// publishers that haven't published since their component moved.
+pub fn compute_publisher_landing_stats<'a>(
+    accounts: impl IntoIterator<Item = (&'a Pubkey, &'a [u8])>,
+) -> Vec<PublisherLandingStats> {
+    let mut stats = Vec::new();
+    for (key, data) in accounts {
+        let price_account = match checked_load_price_account(data) {
+            Some(price_account) => price_account,
+            None => continue,
+        };
+        let landing_latency = match get_extension::<PublisherLandingLatency>(data) {
+            Ok(Some(landing_latency)) => landing_latency,
+            _ => continue,
+        };
+        for (component, latency) in price_account
+            .comp_
+            .iter()
+            .take(price_account.num_ as usize)
+            .zip(landing_latency.components.iter())
+        {
+            if latency.publisher == component.pub_ {
+                stats.push(PublisherLandingStats {
+                    publisher:      component.pub_,
+                    price_account:  *key,
+                    latest_latency: latency.latest,
+                    latency_ema:    latency.ema,
+                });
+            }
+        }
+    }
+    stats.sort_by_key(|stat| (stat.publisher, stat.price_account));
+    stats
+}
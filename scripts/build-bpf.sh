@@ -26,6 +26,13 @@ echo "Checking size of pyth_oracle.so for pythnet"
 ./scripts/check-size.sh 88429
 mkdir -p target/pyth/pythnet/
 mv target/deploy/pyth_oracle.so target/pyth/pythnet/pyth_oracle_pythnet.so
+echo "Writing the layout of the accounts for the SDKs"
+cargo run --locked -p pyth-oracle --features tools --bin pyth-layout target/pyth/pythnet/layout.json
+echo "Writing the conformance vectors of the aggregation for the SDKs"
+cargo run --locked -p pyth-oracle --features test-vectors --bin pyth-test-vectors target/pyth/pythnet/test_vectors.json
+echo "Checking that the account parsing compiles to WebAssembly"
+rustup target add wasm32-unknown-unknown
+cargo check --locked -p pyth-oracle --features wasm --target wasm32-unknown-unknown
 
 # Re-run tests affected by features
 cargo-build-bpf -- --locked -Z build-std=std,panic_abort -Z build-std-features=panic_immediate_abort --features no-default-accumulator-v2
@@ -1,13 +1,15 @@
 #!/usr/bin/env bash
 #
+# Usage: ./scripts/check-size.sh <max size in bytes> [path of the binary]
 
 # While Solana doesn't support resizing programs, the oracle binary needs to be smaller than 81760 bytes
 # (The available space for the oracle program on pythnet is 88429 and mainnet is 81760)
-ORACLE_SIZE=$(wc -c ./target/deploy/pyth_oracle.so | awk '{print $1}')
+ORACLE_SO=${2:-./target/deploy/pyth_oracle.so}
+ORACLE_SIZE=$(wc -c "${ORACLE_SO}" | awk '{print $1}')
 if [ $ORACLE_SIZE -lt ${1} ]
 then
-    echo "Size of pyth_oracle.so is small enough to be deployed, since ${ORACLE_SIZE} is less than ${1}"
+    echo "Size of ${ORACLE_SO} is small enough to be deployed, since ${ORACLE_SIZE} is less than ${1}"
 else
-    echo "Size of pyth_oracle.so is too big to be deployed, since ${ORACLE_SIZE} is greater than ${1}"
+    echo "Size of ${ORACLE_SO} is too big to be deployed, since ${ORACLE_SIZE} is greater than ${1}"
     exit 1
-fi
\ No newline at end of file
+fi